@@ -1,7 +1,19 @@
-use crate::core::bin::{bin_to_json, bin_to_text, json_to_bin, read_bin, text_to_bin, write_bin};
-use crate::state::HashtableState;
+use crate::core::bin::colors::{extract_bin_colors as extract_colors, BinColorReport};
+use crate::core::bin::merge::{merge_bins as merge_bin_versions, BinMergeReport, MergeSide};
+use crate::core::bin::property::{
+    get_bin_property as read_property, ritobin_path, set_bin_property as write_property, BinPropertyValue,
+};
+use crate::core::bin::search::{search_bins as search_project_bins, BinQuery, BinScanStats, BinSearchHit, BinTreeCache};
+use crate::core::bin::verify::verify_round_trip;
+use crate::core::bin::{bin_to_json, bin_to_text, get_cached_bin_hashes, json_to_bin, read_bin, text_to_bin, write_bin};
+use crate::core::project::access::{fallback_text, store_fallback_text};
+use crate::core::project::{ensure_path_writable, find_project_root};
+use crate::core::undo::{FileSnapshot, UndoJournal};
+use crate::error::{CommandError, Error, ErrorCode};
+use crate::state::{HashtableState, HASHTABLE_WAIT};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
@@ -20,36 +32,36 @@ pub struct BinInfo {
 /// * `state` - The managed HashtableState for hash resolution
 ///
 /// # Returns
-/// * `Result<(), String>` - Ok if conversion succeeded, error message otherwise
+/// * `Result<(), CommandError>` - Ok if conversion succeeded, error message otherwise
 #[tauri::command]
 pub async fn convert_bin_to_text(
     input_path: String,
     output_path: String,
     state: State<'_, HashtableState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     tracing::info!("Converting bin to text: {} -> {}", input_path, output_path);
     
     // Validate input path
     if input_path.is_empty() {
         tracing::error!("Input path cannot be empty");
-        return Err("Input path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Input path cannot be empty"));
     }
     if output_path.is_empty() {
         tracing::error!("Output path cannot be empty");
-        return Err("Output path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Output path cannot be empty"));
     }
 
     let input = Path::new(&input_path);
     if !input.exists() {
         tracing::error!("Input file does not exist: {}", input_path);
-        return Err(format!("Input file does not exist: {}", input_path));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("Input file does not exist: {}", input_path)));
     }
 
     // Read the binary file
     let data = fs::read(input)
         .map_err(|e| {
             tracing::error!("Failed to read input file '{}': {}", input_path, e);
-            format!("Failed to read input file '{}': {}", input_path, e)
+            CommandError::new(ErrorCode::Io, format!("Failed to read input file '{}': {}", input_path, e))
         })?;
 
     tracing::debug!("Read {} bytes from {}", data.len(), input_path);
@@ -58,27 +70,27 @@ pub async fn convert_bin_to_text(
     let bin = read_bin(&data)
         .map_err(|e| {
             tracing::error!("Failed to parse bin file '{}': {}", input_path, e);
-            format!("Failed to parse bin file '{}': {}", input_path, e)
+            CommandError::new(ErrorCode::Parse, format!("Failed to parse bin file '{}': {}", input_path, e))
         })?;
 
     tracing::debug!("Parsed bin file with {} objects", bin.objects.len());
 
     // Get hashtable for resolution (lazy loaded on first use)
-    let hashtable = state.get_hashtable();
-    let hashtable_ref = hashtable.as_ref().map(|h| h.as_ref());
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
+    let hashtable_ref = Some(hashtable.as_ref());
 
     // Convert to text format
     let text = bin_to_text(&bin, hashtable_ref)
         .map_err(|e| {
             tracing::error!("Failed to convert to text: {}", e);
-            format!("Failed to convert to text: {}", e)
+            CommandError::new(ErrorCode::BinConversion, format!("Failed to convert to text: {}", e))
         })?;
 
     // Write to output file
     fs::write(&output_path, text)
         .map_err(|e| {
             tracing::error!("Failed to write output file '{}': {}", output_path, e);
-            format!("Failed to write output file '{}': {}", output_path, e)
+            CommandError::new(ErrorCode::Io, format!("Failed to write output file '{}': {}", output_path, e))
         })?;
 
     tracing::info!("Successfully converted bin to text: {}", output_path);
@@ -94,45 +106,45 @@ pub async fn convert_bin_to_text(
 /// * `state` - The managed HashtableState for hash resolution
 ///
 /// # Returns
-/// * `Result<(), String>` - Ok if conversion succeeded, error message otherwise
+/// * `Result<(), CommandError>` - Ok if conversion succeeded, error message otherwise
 #[tauri::command]
 pub async fn convert_bin_to_json(
     input_path: String,
     output_path: String,
     state: State<'_, HashtableState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Validate input path
     if input_path.is_empty() {
-        return Err("Input path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Input path cannot be empty"));
     }
     if output_path.is_empty() {
-        return Err("Output path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Output path cannot be empty"));
     }
 
     let input = Path::new(&input_path);
     if !input.exists() {
-        return Err(format!("Input file does not exist: {}", input_path));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("Input file does not exist: {}", input_path)));
     }
 
     // Read the binary file
     let data = fs::read(input)
-        .map_err(|e| format!("Failed to read input file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read input file: {}", e)))?;
 
     // Parse the bin file
     let bin = read_bin(&data)
-        .map_err(|e| format!("Failed to parse bin file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse bin file: {}", e)))?;
 
     // Get hashtable for resolution (lazy loaded on first use)
-    let hashtable = state.get_hashtable();
-    let hashtable_ref = hashtable.as_ref().map(|h| h.as_ref());
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
+    let hashtable_ref = Some(hashtable.as_ref());
 
     // Convert to JSON format
     let json = bin_to_json(&bin, hashtable_ref)
-        .map_err(|e| format!("Failed to convert to JSON: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::BinConversion, format!("Failed to convert to JSON: {}", e)))?;
 
     // Write to output file
     fs::write(&output_path, json)
-        .map_err(|e| format!("Failed to write output file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to write output file: {}", e)))?;
 
     Ok(())
 }
@@ -142,52 +154,55 @@ pub async fn convert_bin_to_json(
 /// # Arguments
 /// * `input_path` - Path to the input .py file
 /// * `output_path` - Path to the output .bin file
+/// * `verify` - Read the BIN back and fail, writing nothing, if it differs
+///   from the parsed text (default off)
 /// * `state` - The managed HashtableState for string-to-hash conversion
 ///
 /// # Returns
-/// * `Result<(), String>` - Ok if conversion succeeded, error message otherwise
+/// * `Result<(), CommandError>` - Ok if conversion succeeded, error message otherwise
 #[tauri::command]
 pub async fn convert_text_to_bin(
     input_path: String,
     output_path: String,
+    verify: Option<bool>,
     state: State<'_, HashtableState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     tracing::info!("Converting text to bin: {} -> {}", input_path, output_path);
     
     // Validate input path
     if input_path.is_empty() {
         tracing::error!("Input path cannot be empty");
-        return Err("Input path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Input path cannot be empty"));
     }
     if output_path.is_empty() {
         tracing::error!("Output path cannot be empty");
-        return Err("Output path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Output path cannot be empty"));
     }
 
     let input = Path::new(&input_path);
     if !input.exists() {
         tracing::error!("Input file does not exist: {}", input_path);
-        return Err(format!("Input file does not exist: {}", input_path));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("Input file does not exist: {}", input_path)));
     }
 
     // Read the text file
     let text = fs::read_to_string(input)
         .map_err(|e| {
             tracing::error!("Failed to read input file '{}': {}", input_path, e);
-            format!("Failed to read input file '{}': {}", input_path, e)
+            CommandError::new(ErrorCode::Io, format!("Failed to read input file '{}': {}", input_path, e))
         })?;
 
     tracing::debug!("Read {} characters from {}", text.len(), input_path);
 
     // Get hashtable for conversion (lazy loaded on first use)
-    let hashtable = state.get_hashtable();
+    let hashtable = state.ensure_loaded().await;
     let hashtable_ref = hashtable.as_ref().map(|h| h.as_ref());
 
     // Parse text to bin
     let bin = text_to_bin(&text, hashtable_ref)
         .map_err(|e| {
             tracing::error!("Failed to parse text from '{}': {}", input_path, e);
-            format!("Failed to parse text from '{}': {}", input_path, e)
+            CommandError::new(ErrorCode::Parse, format!("Failed to parse text from '{}': {}", input_path, e))
         })?;
 
     tracing::debug!("Parsed text to bin with {} objects", bin.objects.len());
@@ -196,14 +211,20 @@ pub async fn convert_text_to_bin(
     let data = write_bin(&bin)
         .map_err(|e| {
             tracing::error!("Failed to write bin: {}", e);
-            format!("Failed to write bin: {}", e)
+            CommandError::new(ErrorCode::BinConversion, format!("Failed to write bin: {}", e))
         })?;
 
+    // Check the BIN reads back as parsed before writing it
+    if verify.unwrap_or(false) {
+        let hashes = get_cached_bin_hashes().read();
+        verify_round_trip(&bin, &data, Path::new(&output_path), &*hashes)?;
+    }
+
     // Write to output file
     fs::write(&output_path, data)
         .map_err(|e| {
             tracing::error!("Failed to write output file '{}': {}", output_path, e);
-            format!("Failed to write output file '{}': {}", output_path, e)
+            CommandError::new(ErrorCode::Io, format!("Failed to write output file '{}': {}", output_path, e))
         })?;
 
     tracing::info!("Successfully converted text to bin: {}", output_path);
@@ -216,48 +237,57 @@ pub async fn convert_text_to_bin(
 /// # Arguments
 /// * `input_path` - Path to the input .json file
 /// * `output_path` - Path to the output .bin file
+/// * `verify` - Read the BIN back and fail, writing nothing, if it differs
+///   from the parsed JSON (default off)
 /// * `state` - The managed HashtableState for string-to-hash conversion
 ///
 /// # Returns
-/// * `Result<(), String>` - Ok if conversion succeeded, error message otherwise
+/// * `Result<(), CommandError>` - Ok if conversion succeeded, error message otherwise
 #[tauri::command]
 pub async fn convert_json_to_bin(
     input_path: String,
     output_path: String,
+    verify: Option<bool>,
     state: State<'_, HashtableState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Validate input path
     if input_path.is_empty() {
-        return Err("Input path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Input path cannot be empty"));
     }
     if output_path.is_empty() {
-        return Err("Output path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Output path cannot be empty"));
     }
 
     let input = Path::new(&input_path);
     if !input.exists() {
-        return Err(format!("Input file does not exist: {}", input_path));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("Input file does not exist: {}", input_path)));
     }
 
     // Read the JSON file
     let json = fs::read_to_string(input)
-        .map_err(|e| format!("Failed to read input file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read input file: {}", e)))?;
 
     // Get hashtable for conversion (lazy loaded on first use)
-    let hashtable = state.get_hashtable();
+    let hashtable = state.ensure_loaded().await;
     let hashtable_ref = hashtable.as_ref().map(|h| h.as_ref());
 
     // Parse JSON to bin
     let bin = json_to_bin(&json, hashtable_ref)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse JSON: {}", e)))?;
 
     // Convert to binary
     let data = write_bin(&bin)
-        .map_err(|e| format!("Failed to write bin: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::BinConversion, format!("Failed to write bin: {}", e)))?;
+
+    // Check the BIN reads back as parsed before writing it
+    if verify.unwrap_or(false) {
+        let hashes = get_cached_bin_hashes().read();
+        verify_round_trip(&bin, &data, Path::new(&output_path), &*hashes)?;
+    }
 
     // Write to output file
     fs::write(&output_path, data)
-        .map_err(|e| format!("Failed to write output file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to write output file: {}", e)))?;
 
     Ok(())
 }
@@ -268,26 +298,26 @@ pub async fn convert_json_to_bin(
 /// * `input_path` - Path to the .bin file
 ///
 /// # Returns
-/// * `Result<BinInfo, String>` - Metadata about the bin file
+/// * `Result<BinInfo, CommandError>` - Metadata about the bin file
 #[tauri::command]
-pub async fn read_bin_info(input_path: String) -> Result<BinInfo, String> {
+pub async fn read_bin_info(input_path: String) -> Result<BinInfo, CommandError> {
     // Validate input path
     if input_path.is_empty() {
-        return Err("Input path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Input path cannot be empty"));
     }
 
     let input = Path::new(&input_path);
     if !input.exists() {
-        return Err(format!("Input file does not exist: {}", input_path));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("Input file does not exist: {}", input_path)));
     }
 
     // Read the binary file
     let data = fs::read(input)
-        .map_err(|e| format!("Failed to read input file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read input file: {}", e)))?;
 
     // Parse the bin file
     let bin = read_bin(&data)
-        .map_err(|e| format!("Failed to parse bin file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse bin file: {}", e)))?;
 
     // Return metadata
     Ok(BinInfo {
@@ -303,39 +333,39 @@ pub async fn read_bin_info(input_path: String) -> Result<BinInfo, String> {
 /// * `state` - The managed HashtableState for hash resolution
 ///
 /// # Returns
-/// * `Result<String, String>` - Python-like text format for the editor
+/// * `Result<String, CommandError>` - Python-like text format for the editor
 #[tauri::command]
 pub async fn parse_bin_file_to_text(
     path: String,
     _state: State<'_, HashtableState>,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     tracing::info!("Parsing BIN file for editor: {}", path);
     
     // Validate path
     if path.is_empty() {
-        return Err("Path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Path cannot be empty"));
     }
 
     let input = Path::new(&path);
     if !input.exists() {
-        return Err(format!("File does not exist: {}", path));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("File does not exist: {}", path)));
     }
 
     // Read the binary file
     let data = fs::read(input)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read file: {}", e)))?;
 
     tracing::debug!("Read {} bytes from {}", data.len(), path);
 
     // Parse with ritobin_rust
     let bin = crate::core::bin::read_bin_ltk(&data)
-        .map_err(|e| format!("Failed to parse bin file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse bin file: {}", e)))?;
 
     tracing::debug!("Parsed bin file with {} objects", bin.objects.len());
 
     // Convert to text format using cached hash resolution (faster)
     let text = crate::core::bin::tree_to_text_cached(&bin)
-        .map_err(|e| format!("Failed to convert to text: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::BinConversion, format!("Failed to convert to text: {}", e)))?;
 
     tracing::info!("Successfully parsed BIN file to text ({} chars)", text.len());
 
@@ -352,22 +382,22 @@ pub async fn parse_bin_file_to_text(
 /// * `bin_path` - Path to the .bin file
 ///
 /// # Returns
-/// * `Result<String, String>` - The text content (either from cache or freshly converted)
+/// * `Result<String, CommandError>` - The text content (either from cache or freshly converted)
 #[tauri::command]
 pub async fn read_or_convert_bin(
     bin_path: String,
     _state: State<'_, HashtableState>,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     tracing::info!("[BIN_READ] === Starting read_or_convert_bin ===");
     tracing::info!("[BIN_READ] Path: {}", bin_path);
     
     if bin_path.is_empty() {
-        return Err("Path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Path cannot be empty"));
     }
 
     let bin_file = Path::new(&bin_path);
     if !bin_file.exists() {
-        return Err(format!("File does not exist: {}", bin_path));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("File does not exist: {}", bin_path)));
     }
 
     // Log .bin file size
@@ -394,7 +424,7 @@ pub async fn read_or_convert_bin(
                     // Cache is valid, read it directly - NO CONVERSION!
                     tracing::info!("[BIN_READ] *** CACHE HIT *** Reading cached file directly");
                     let content = fs::read_to_string(ritobin_file)
-                        .map_err(|e| format!("Failed to read cached file: {}", e))?;
+                        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read cached file: {}", e)))?;
                     tracing::info!("[BIN_READ] *** CACHE HIT *** Loaded {} chars from cache", content.len());
                     return Ok(content);
                 } else {
@@ -406,27 +436,37 @@ pub async fn read_or_convert_bin(
         tracing::info!("[BIN_READ] No cache file found");
     }
 
+    // Projects on read-only media keep converted text in memory instead
+    let bin_time = fs::metadata(bin_file).and_then(|meta| meta.modified()).ok();
+    if let Some(text) = bin_time.and_then(|time| fallback_text(bin_file, time)) {
+        tracing::info!("[BIN_READ] *** MEMORY HIT *** Loaded {} chars", text.len());
+        return Ok(text);
+    }
+
     // Cache miss or stale - need to convert
     tracing::warn!("[BIN_READ] *** CACHE MISS *** Converting BIN file...");
     
     // Read and parse the binary file
     let data = fs::read(bin_file)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read file: {}", e)))?;
     tracing::info!("[BIN_READ] Read {} bytes from .bin file", data.len());
 
     tracing::info!("[BIN_READ] Parsing BIN structure...");
     let bin = crate::core::bin::read_bin_ltk(&data)
-        .map_err(|e| format!("Failed to parse bin file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse bin file: {}", e)))?;
     tracing::info!("[BIN_READ] Parsed: {} objects, {} dependencies", bin.objects.len(), bin.dependencies.len());
 
     tracing::info!("[BIN_READ] Converting to text (using cached hashes)...");
     let text = crate::core::bin::tree_to_text_cached(&bin)
-        .map_err(|e| format!("Failed to convert to text: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::BinConversion, format!("Failed to convert to text: {}", e)))?;
     tracing::info!("[BIN_READ] Converted to {} chars of text", text.len());
 
     // Cache the result
     if let Err(e) = fs::write(&ritobin_path, &text) {
         tracing::warn!("[BIN_READ] Failed to cache .ritobin file: {}", e);
+        if let Some(time) = bin_time {
+            store_fallback_text(bin_file, time, &text);
+        }
     } else {
         tracing::info!("[BIN_READ] Wrote cache file: {}", ritobin_path);
     }
@@ -440,46 +480,263 @@ pub async fn read_or_convert_bin(
 /// # Arguments
 /// * `bin_path` - Path to the .bin file
 /// * `content` - The edited text content
+/// * `verify` - Read the BIN back and fail, writing nothing, if it differs
+///   from the parsed text (default on)
 ///
 /// # Returns
-/// * `Result<(), String>` - Ok if save succeeded
+/// * `Result<(), CommandError>` - Ok if save succeeded
 #[tauri::command]
 pub async fn save_ritobin_to_bin(
     bin_path: String,
     content: String,
+    verify: Option<bool>,
     _state: State<'_, HashtableState>,
-) -> Result<(), String> {
+    undo: State<'_, UndoJournal>,
+) -> Result<(), CommandError> {
     tracing::info!("Saving ritobin content to: {}", bin_path);
     
     if bin_path.is_empty() {
-        return Err("Path cannot be empty".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Path cannot be empty"));
     }
+    ensure_path_writable(Path::new(&bin_path))?;
 
     // Parse the text content back to BIN structure
     let bin = crate::core::bin::text_to_tree(&content)
-        .map_err(|e| format!("Failed to parse text content: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse text content: {}", e)))?;
 
     // Convert to binary format
     let binary_data = crate::core::bin::write_bin_ltk(&bin)
-        .map_err(|e| format!("Failed to convert to binary: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::BinConversion, format!("Failed to convert to binary: {}", e)))?;
+
+    // Check the BIN reads back as parsed before writing it
+    if verify.unwrap_or(true) {
+        let hashes = get_cached_bin_hashes().read();
+        verify_round_trip(&bin, &binary_data, Path::new(&bin_path), &*hashes)?;
+    }
+
+    // Keep the old bytes so the edit can be undone
+    let ritobin_path = format!("{}.ritobin", bin_path);
+    let project_root = find_project_root(Path::new(&bin_path));
+    let snapshot = match &project_root {
+        Some(_) => Some(FileSnapshot::capture(&[&bin_path, &ritobin_path])?),
+        None => None,
+    };
 
     // Write the .bin file
     fs::write(&bin_path, &binary_data)
-        .map_err(|e| format!("Failed to write .bin file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to write .bin file: {}", e)))?;
 
     tracing::info!("Saved .bin file: {} ({} bytes)", bin_path, binary_data.len());
 
     // Update the .ritobin cache
-    let ritobin_path = format!("{}.ritobin", bin_path);
     if let Err(e) = fs::write(&ritobin_path, &content) {
         tracing::warn!("Failed to update .ritobin cache: {}", e);
     } else {
         tracing::info!("Updated .ritobin cache: {}", ritobin_path);
     }
 
+    if let (Some(root), Some(snapshot)) = (project_root, snapshot) {
+        let name = Path::new(&bin_path).file_name().unwrap_or_default().to_string_lossy().to_string();
+        undo.push(&root, format!("Edit {}", name), snapshot);
+    }
+
     Ok(())
 }
 
+/// Read one property of a BIN without converting the whole file
+///
+/// # Arguments
+/// * `bin_path` - Path to the .bin file
+/// * `object` - Object path, or its hash as "0x…"
+/// * `property_path` - Fields joined by dots, `[n]` for list items and
+///   `[key]` for map entries, e.g. "complexEmitterDefinitionData[0].rate"
+#[tauri::command]
+pub async fn get_bin_property(
+    bin_path: String,
+    object: String,
+    property_path: String,
+) -> Result<BinPropertyValue, CommandError> {
+    tokio::task::spawn_blocking(move || read_property(Path::new(&bin_path), &object, &property_path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(CommandError::from)
+}
+
+/// Set one property of a BIN and write it back, returning the old value
+///
+/// The value is ritobin text of type `value_type`, e.g. "f32" and "1.5" or
+/// "rgba" and "{ 255, 128, 0, 255 }", and must be of the same kind as the
+/// value it replaces. Inside a project the change can be undone.
+///
+/// # Arguments
+/// * `bin_path` - Path to the .bin file
+/// * `object` - Object path, or its hash as "0x…"
+/// * `property_path` - As for `get_bin_property`
+/// * `value_type` - Ritobin type of the new value
+/// * `value` - The new value in ritobin syntax
+#[tauri::command]
+pub async fn set_bin_property(
+    bin_path: String,
+    object: String,
+    property_path: String,
+    value_type: String,
+    value: String,
+    undo: State<'_, UndoJournal>,
+) -> Result<BinPropertyValue, CommandError> {
+    tracing::info!("Setting {} of {} in {}", property_path, object, bin_path);
+    let path = std::path::PathBuf::from(&bin_path);
+    ensure_path_writable(&path)?;
+
+    let journal = undo.inner().clone();
+    tokio::task::spawn_blocking(move || -> crate::error::Result<BinPropertyValue> {
+        let project_root = find_project_root(&path);
+        let snapshot = match &project_root {
+            Some(_) => Some(FileSnapshot::capture(&[path.clone(), ritobin_path(&path)])?),
+            None => None,
+        };
+        let old = write_property(&path, &object, &property_path, &value_type, &value)?;
+        if let (Some(root), Some(snapshot)) = (project_root, snapshot) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            journal.push(&root, format!("Set {} in {}", property_path, name), snapshot);
+        }
+        Ok(old)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Three-way merge two edited versions of a BIN into `output_path`
+///
+/// Changes made on one side only are applied, and identical changes are
+/// taken once. Fields or objects both sides changed differently are
+/// reported as conflicts and keep our version unless `resolutions` picks
+/// theirs; call again with the conflicts' ids to settle them. Inside a
+/// project the write can be undone.
+///
+/// # Arguments
+/// * `base_path` - The BIN both versions were edited from
+/// * `ours_path` - Our version, whose values unresolved conflicts keep
+/// * `theirs_path` - Their version
+/// * `output_path` - Where the merged BIN is written; may be `ours_path`
+/// * `resolutions` - Side to take per conflict id
+#[tauri::command]
+pub async fn merge_bins(
+    base_path: String,
+    ours_path: String,
+    theirs_path: String,
+    output_path: String,
+    resolutions: Option<HashMap<String, MergeSide>>,
+    undo: State<'_, UndoJournal>,
+) -> Result<BinMergeReport, CommandError> {
+    tracing::info!("Merging {} and {} into {}", ours_path, theirs_path, output_path);
+    let output = std::path::PathBuf::from(&output_path);
+    ensure_path_writable(&output)?;
+
+    let journal = undo.inner().clone();
+    tokio::task::spawn_blocking(move || -> crate::error::Result<BinMergeReport> {
+        let project_root = find_project_root(&output);
+        let snapshot = match &project_root {
+            Some(_) => Some(FileSnapshot::capture(&[output.clone(), ritobin_path(&output)])?),
+            None => None,
+        };
+        let hashes = get_cached_bin_hashes().read();
+        let report = merge_bin_versions(
+            Path::new(&base_path),
+            Path::new(&ours_path),
+            Path::new(&theirs_path),
+            &output,
+            &resolutions.unwrap_or_default(),
+            &*hashes,
+        )?;
+        if let (Some(root), Some(snapshot)) = (project_root, snapshot) {
+            let name = output.file_name().unwrap_or_default().to_string_lossy().to_string();
+            journal.push(&root, format!("Merge into {}", name), snapshot);
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// List the colors of a BIN, or of every BIN of a project, for recoloring
+///
+/// Each `rgba` and `vec4` value comes with its object and property path;
+/// `known_field` marks those below a known color field such as
+/// `birthColor`. Animated colors are returned as gradients of their keys.
+///
+/// # Arguments
+/// * `path` - A .bin file, or a project folder
+#[tauri::command]
+pub async fn extract_bin_colors(path: String) -> Result<BinColorReport, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        let hashes = get_cached_bin_hashes().read();
+        extract_colors(Path::new(&path), &*hashes)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Results shown per page when `search_bins` isn't given a limit
+const SEARCH_PAGE_SIZE: usize = 200;
+
+/// One page of `search_bins` hits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinSearchPage {
+    pub hits: Vec<BinSearchHit>,
+    /// Hits across all pages
+    pub total: usize,
+    pub offset: usize,
+    pub stats: BinScanStats,
+}
+
+/// Search every BIN of a project for a name, string, number or hash
+///
+/// Text matches field, object and hash names and string values without
+/// regard to case; "0x…" matches hashes and links exactly; a number matches
+/// numeric values. Parsed BINs are cached until they change, so paging is
+/// fast. Past 5000 hits the search stops and `stats.truncated` is set.
+/// `bin-search-progress` events report the scan.
+///
+/// # Arguments
+/// * `project_path` - Project folder
+/// * `query` - Text, a number, or a hash as "0x…"
+/// * `offset` - First hit to return (default: 0)
+/// * `limit` - Hits to return (default: 200)
+/// * `cache` - Parsed BINs of earlier searches
+#[tauri::command]
+pub async fn search_bins(
+    app: AppHandle,
+    project_path: String,
+    query: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    cache: State<'_, BinTreeCache>,
+) -> Result<BinSearchPage, CommandError> {
+    let query = BinQuery::parse(&query)?;
+    let cache = cache.inner().clone();
+    let (offset, limit) = (offset.unwrap_or(0), limit.unwrap_or(SEARCH_PAGE_SIZE));
+
+    tokio::task::spawn_blocking(move || {
+        let progress = |searched: usize, total: usize| {
+            let _ = app.emit("bin-search-progress", serde_json::json!({ "searched": searched, "total": total }));
+        };
+        let hashes = get_cached_bin_hashes().read();
+        let (hits, stats) = search_project_bins(Path::new(&project_path), &query, &*hashes, &cache, progress)?;
+        Ok::<_, Error>(BinSearchPage {
+            total: hits.len(),
+            hits: hits.into_iter().skip(offset).take(limit).collect(),
+            offset,
+            stats,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -503,7 +760,9 @@ mod tests {
         let result = read_bin_info("".to_string()).await;
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Input path cannot be empty"));
+        let err = result.unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidInput);
+        assert!(err.message.contains("Input path cannot be empty"));
     }
 
     #[tokio::test]
@@ -511,6 +770,8 @@ mod tests {
         let result = read_bin_info("nonexistent.bin".to_string()).await;
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("does not exist"));
+        let err = result.unwrap_err();
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert!(err.message.contains("does not exist"));
     }
 }
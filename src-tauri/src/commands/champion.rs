@@ -7,6 +7,7 @@ use crate::core::champion::{
     get_champion_skins as core_get_champion_skins,
     ChampionInfo, SkinInfo,
 };
+use crate::error::CommandError;
 use std::path::PathBuf;
 
 /// Discover all champions in a League installation
@@ -16,9 +17,9 @@ use std::path::PathBuf;
 ///
 /// # Returns
 /// * `Ok(Vec<ChampionInfo>)` - List of discovered champions
-/// * `Err(String)` - Error message if discovery failed
+/// * `Err(CommandError)` - Error message if discovery failed
 #[tauri::command]
-pub async fn discover_champions(league_path: String) -> Result<Vec<ChampionInfo>, String> {
+pub async fn discover_champions(league_path: String) -> Result<Vec<ChampionInfo>, CommandError> {
     tracing::info!("Frontend requested champion discovery for: {}", league_path);
 
     let path = PathBuf::from(league_path);
@@ -26,7 +27,7 @@ pub async fn discover_champions(league_path: String) -> Result<Vec<ChampionInfo>
     tokio::task::spawn_blocking(move || core_discover_champions(&path))
         .await
         .map_err(|e| format!("Task failed: {}", e))?
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// Get skins for a specific champion
@@ -37,12 +38,12 @@ pub async fn discover_champions(league_path: String) -> Result<Vec<ChampionInfo>
 ///
 /// # Returns
 /// * `Ok(Vec<SkinInfo>)` - List of skins
-/// * `Err(String)` - Error message if discovery failed
+/// * `Err(CommandError)` - Error message if discovery failed
 #[tauri::command]
 pub async fn get_champion_skins(
     league_path: String,
     champion: String,
-) -> Result<Vec<SkinInfo>, String> {
+) -> Result<Vec<SkinInfo>, CommandError> {
     tracing::info!("Frontend requested skins for: {}", champion);
 
     let path = PathBuf::from(league_path);
@@ -50,7 +51,7 @@ pub async fn get_champion_skins(
     tokio::task::spawn_blocking(move || core_get_champion_skins(&path, &champion))
         .await
         .map_err(|e| format!("Task failed: {}", e))?
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// Search champions by name
@@ -1,6 +1,13 @@
-use crate::core::checkpoint::{Checkpoint, CheckpointDiff, CheckpointFileContent, CheckpointManager, CheckpointProgress};
-use std::path::PathBuf;
-use tauri::{AppHandle, Emitter};
+use crate::core::checkpoint::{
+    Checkpoint, CheckpointDiff, CheckpointFileContent, CheckpointFilter, CheckpointGraph, CheckpointLockInfo,
+    CheckpointManager, CheckpointOrder, CheckpointProgress, CheckpointStorageStats, CheckpointVerifyReport,
+};
+use crate::core::operation::{OperationKind, OperationRegistry};
+use crate::core::project::ensure_writable;
+use crate::core::undo::UndoJournal;
+use crate::error::CommandError;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
 
 #[tauri::command]
 pub async fn create_checkpoint(
@@ -8,38 +15,138 @@ pub async fn create_checkpoint(
     project_path: String,
     message: String,
     tags: Vec<String>,
-) -> Result<Checkpoint, String> {
+    store_bins_as_text: Option<bool>,
+    operations: State<'_, OperationRegistry>,
+) -> Result<Checkpoint, CommandError> {
+    let path = PathBuf::from(&project_path);
+    ensure_writable(&path)?;
+    let operation = operations.start(OperationKind::Checkpoint, Some(&project_path));
+    let operation_id = operation.id().to_string();
+    let cancel = operation.token();
+
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || {
+        let _span = span.enter();
+        let mut manager = CheckpointManager::new(path);
+        manager.store_bins_as_text = store_bins_as_text.unwrap_or(true);
+        manager.init()?;
+        manager.create_checkpoint_with_progress(
+            message,
+            tags,
+            Some(|progress: &CheckpointProgress| {
+                emit_progress(&app, progress, &operation_id);
+            }),
+            Some(cancel.as_flag()),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+
+    operation.finish(&result);
+    result.map_err(CommandError::from)
+}
+
+fn emit_progress(app: &AppHandle, progress: &CheckpointProgress, operation_id: &str) {
+    let progress = CheckpointProgress {
+        operation_id: Some(operation_id.to_string()),
+        ..progress.clone()
+    };
+    let _ = app.emit("checkpoint-progress", &progress);
+}
+
+/// Request cancellation of an in-flight `create_checkpoint` for this project.
+/// Returns false if no checkpoint is being created.
+#[tauri::command]
+pub async fn cancel_checkpoint_creation(
+    project_path: String,
+    operations: State<'_, OperationRegistry>,
+) -> Result<bool, CommandError> {
+    match operations.find_running(OperationKind::Checkpoint, &project_path) {
+        Some(id) => operations.cancel(&id).map_err(CommandError::from),
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub async fn list_checkpoints(
+    project_path: String,
+    filter: Option<CheckpointFilter>,
+    order: Option<CheckpointOrder>,
+) -> Result<Vec<Checkpoint>, CommandError> {
     let path = PathBuf::from(project_path);
     let manager = CheckpointManager::new(path);
-    manager.init().map_err(|e| e.to_string())?;
-
-    let app_handle = app.clone();
-    manager.create_checkpoint_with_progress(
-        message,
-        tags,
-        Some(move |phase: &str, current: u64, total: u64| {
-            let _ = app_handle.emit("checkpoint-progress", CheckpointProgress {
-                phase: phase.to_string(),
-                current,
-                total,
-            });
-        }),
-    ).map_err(|e| e.to_string())
+    manager
+        .list_checkpoints_filtered(&filter.unwrap_or_default(), order.unwrap_or_default())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn list_checkpoints(project_path: String) -> Result<Vec<Checkpoint>, String> {
+pub async fn update_checkpoint(
+    project_path: String,
+    checkpoint_id: String,
+    message: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<Checkpoint, CommandError> {
     let path = PathBuf::from(project_path);
+    ensure_writable(&path)?;
     let manager = CheckpointManager::new(path);
-    manager.list_checkpoints().map_err(|e| e.to_string())
+    manager.update_checkpoint(&checkpoint_id, message, tags).map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn restore_checkpoint(project_path: String, checkpoint_id: String) -> Result<(), String> {
+pub async fn restore_checkpoint(
+    app: AppHandle,
+    project_path: String,
+    checkpoint_id: String,
+    queue: Option<bool>,
+    operations: State<'_, OperationRegistry>,
+    undo: State<'_, UndoJournal>,
+) -> Result<(), CommandError> {
+    ensure_writable(Path::new(&project_path))?;
+    // A restore swaps the working tree in place: it holds the project's exclusive
+    // slot and can't be cancelled midway
+    let operation = operations
+        .start_exclusive(OperationKind::Checkpoint, &project_path, false, queue.unwrap_or(false))
+        .await?;
+    let operation_id = operation.id().to_string();
+
+    let path = PathBuf::from(project_path);
+    undo.invalidate(&path);
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || {
+        let _span = span.enter();
+        let manager = CheckpointManager::new(path);
+        manager.init()?;
+        manager.restore_checkpoint_with_progress(
+            &checkpoint_id,
+            Some(|progress: &CheckpointProgress| {
+                emit_progress(&app, progress, &operation_id);
+            }),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+
+    operation.finish(&result);
+    result.map_err(CommandError::from)
+}
+
+/// Checkpoint lineage (parent/child links and the current head) for tree rendering
+#[tauri::command]
+pub async fn get_checkpoint_graph(project_path: String) -> Result<CheckpointGraph, CommandError> {
     let path = PathBuf::from(project_path);
     let manager = CheckpointManager::new(path);
-    manager.init().map_err(|e| e.to_string())?;
-    manager.restore_checkpoint(&checkpoint_id).map_err(|e| e.to_string())
+    manager.checkpoint_graph().map_err(CommandError::from)
+}
+
+/// Re-hash all stored checkpoint data and report corrupt or missing objects
+#[tauri::command]
+pub async fn verify_checkpoints(project_path: String) -> Result<CheckpointVerifyReport, CommandError> {
+    let path = PathBuf::from(project_path);
+    tokio::task::spawn_blocking(move || CheckpointManager::new(path).verify())
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -47,17 +154,84 @@ pub async fn compare_checkpoints(
     project_path: String,
     from_id: String,
     to_id: String,
-) -> Result<CheckpointDiff, String> {
+) -> Result<CheckpointDiff, CommandError> {
+    let path = PathBuf::from(project_path);
+    let manager = CheckpointManager::new(path);
+    manager.compare_checkpoints(&from_id, &to_id).map_err(CommandError::from)
+}
+
+/// Delete a checkpoint and return the number of bytes freed by garbage collection
+#[tauri::command]
+pub async fn delete_checkpoint(project_path: String, checkpoint_id: String) -> Result<u64, CommandError> {
     let path = PathBuf::from(project_path);
+    ensure_writable(&path)?;
     let manager = CheckpointManager::new(path);
-    manager.compare_checkpoints(&from_id, &to_id).map_err(|e| e.to_string())
+    manager.delete_checkpoint(&checkpoint_id).map_err(CommandError::from)
+}
+
+/// Delete the oldest `count` checkpoints and return the number of bytes freed
+#[tauri::command]
+pub async fn prune_checkpoints(project_path: String, count: usize) -> Result<u64, CommandError> {
+    let path = PathBuf::from(project_path);
+    ensure_writable(&path)?;
+    tokio::task::spawn_blocking(move || CheckpointManager::new(path).prune_oldest(count))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(CommandError::from)
 }
 
+/// Who currently holds the project's checkpoint lock (None if free)
 #[tauri::command]
-pub async fn delete_checkpoint(project_path: String, checkpoint_id: String) -> Result<(), String> {
+pub async fn get_checkpoint_lock_status(project_path: String) -> Result<Option<CheckpointLockInfo>, CommandError> {
+    let path = PathBuf::from(project_path);
+    let manager = CheckpointManager::new(path);
+    manager.lock_status().map_err(CommandError::from)
+}
+
+/// Clear a checkpoint lock left behind by a crashed process
+#[tauri::command]
+pub async fn break_checkpoint_lock(project_path: String) -> Result<bool, CommandError> {
+    let path = PathBuf::from(project_path);
+    ensure_writable(&path)?;
+    let manager = CheckpointManager::new(path);
+    manager.break_stale_lock().map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_checkpoint_storage_stats(project_path: String) -> Result<CheckpointStorageStats, CommandError> {
+    let path = PathBuf::from(project_path);
+    let manager = CheckpointManager::new(path);
+    manager.storage_stats().map_err(CommandError::from)
+}
+
+/// Export a checkpoint's files as a zip that can be imported on another machine.
+/// Returns the number of files written.
+#[tauri::command]
+pub async fn export_checkpoint(
+    project_path: String,
+    checkpoint_id: String,
+    output_zip: String,
+) -> Result<usize, CommandError> {
+    let path = PathBuf::from(project_path);
+    let manager = CheckpointManager::new(path);
+    manager
+        .export_checkpoint(&checkpoint_id, &PathBuf::from(output_zip))
+        .map_err(CommandError::from)
+}
+
+/// Import a checkpoint zip as a new checkpoint without touching the working tree.
+#[tauri::command]
+pub async fn import_checkpoint(
+    project_path: String,
+    zip_path: String,
+    message: String,
+) -> Result<Checkpoint, CommandError> {
     let path = PathBuf::from(project_path);
+    ensure_writable(&path)?;
     let manager = CheckpointManager::new(path);
-    manager.delete_checkpoint(&checkpoint_id).map_err(|e| e.to_string())
+    manager
+        .import_checkpoint(&PathBuf::from(zip_path), message)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -65,8 +239,8 @@ pub async fn read_checkpoint_file(
     project_path: String,
     hash: String,
     file_path: String,
-) -> Result<CheckpointFileContent, String> {
+) -> Result<CheckpointFileContent, CommandError> {
     let path = PathBuf::from(project_path);
     let manager = CheckpointManager::new(path);
-    manager.read_checkpoint_file(&hash, &file_path).map_err(|e| e.to_string())
+    manager.read_checkpoint_file(&hash, &file_path).map_err(CommandError::from)
 }
@@ -0,0 +1,51 @@
+//! Tauri commands for building diagnostic bundles
+
+use crate::core::diagnostics::{collect_environment, write_bundle, BundleContents, DiagnosticBundle};
+use crate::core::log_capture::log_files;
+use crate::error::CommandError;
+use crate::state::{HashtableState, SettingsState};
+use std::path::PathBuf;
+use tauri::State;
+
+/// Zip logs, settings and environment details for a bug report
+///
+/// # Arguments
+/// * `output_path` - Where to write the zip
+/// * `include_project` - Project whose metadata and reports to add (never its assets)
+/// * `include_league_path` - Set to false to replace League paths with "set"/"unset"
+///
+/// # Returns
+/// * `Ok(DiagnosticBundle)` - The bundle path and a list of what went into it
+#[tauri::command]
+pub async fn create_diagnostic_bundle(
+    output_path: String,
+    include_project: Option<String>,
+    include_league_path: Option<bool>,
+    settings: State<'_, SettingsState>,
+    hashtable: State<'_, HashtableState>,
+) -> Result<DiagnosticBundle, CommandError> {
+    tracing::info!("Creating diagnostic bundle at {}", output_path);
+
+    let settings = settings.get();
+    let hash_dir = hashtable.hash_dir();
+    let loaded_count = hashtable.len();
+    let include_league_path = include_league_path.unwrap_or(true);
+
+    tokio::task::spawn_blocking(move || {
+        let environment = collect_environment(&settings, hash_dir, loaded_count, include_league_path);
+        let project = include_project.map(PathBuf::from);
+        write_bundle(
+            &PathBuf::from(&output_path),
+            &BundleContents {
+                environment: &environment,
+                settings: &settings,
+                log_files: &log_files(),
+                project: project.as_deref(),
+                include_league_path,
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
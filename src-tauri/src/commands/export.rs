@@ -3,15 +3,35 @@
 //! These commands expose export and repathing functionality to the frontend.
 //! Uses ltk_fantome for league-mod compatible .fantome export.
 
-use crate::core::export::generate_fantome_filename;
-use crate::core::repath::{organize_project, OrganizerConfig};
+use crate::core::diagnostics::{record_export, save_project_report, ExportRecord, REPATH_REPORT_FILE};
+use crate::core::disk::{check_disk_space, directory_size};
+use crate::core::export::{
+    bundle_description, check_mod_conflicts as core_check_mod_conflicts, check_wad_targets, export_manifest, find_bundle_conflicts, generate_fantome_filename,
+    populate_overlay, rename_champion_wad, resolve_champion_wad, stage_bundle, sync_changes, watch_project, BundleConflict,
+    BundlePart, ExportManifestEntry, LiveSyncBatch, LiveSyncRegistry, ModConflictReport, pack_fantome_with_wads,
+};
+use crate::core::hash::Hashtable;
+use crate::core::mapping::PathMappings;
+use crate::core::operation::{CancellationToken, OperationKind, OperationRegistry, PhaseTimer, PhaseTiming};
+use crate::core::project::{ensure_writable, is_writable, open_project, save_project};
+use crate::core::repath::{
+    detect_repath_prefix as core_detect_repath_prefix, detect_skin_ids, list_quarantine as core_list_quarantine,
+    organize_project, purge_quarantine as core_purge_quarantine, reconcile_skin_id,
+    restore_quarantine as core_restore_quarantine, strip_repath_prefix as core_strip_repath_prefix, MapOverrideMode,
+    OrganizerConfig, PrefixStripResult, QuarantineBatch, QuarantineRestore, RepathConfig, RepathFileError, RepathWarning,
+};
+use crate::core::undo::UndoJournal;
+use crate::core::wad::list_client_wads;
+use crate::error::{CommandError, Error, ErrorCode};
+use crate::state::{HashtableState, SettingsState};
 use ltk_fantome::pack_to_fantome;
 use ltk_mod_project::{ModProject, ModProjectAuthor};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
 use std::path::{Path, PathBuf};
-use tauri::Emitter;
+use std::sync::Arc;
+use tauri::{Emitter, Manager, State};
 
 /// Metadata for export operations (received from frontend)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +49,15 @@ pub struct ExportResult {
     pub output_path: String,
     pub file_count: usize,
     pub total_size: u64,
+    /// Problems worked around during the export (e.g. a skin ID mismatch)
+    pub warnings: Vec<String>,
+    /// Time spent in each phase, in order
+    pub timings: Vec<PhaseTiming>,
+    /// Packed files and the WAD each goes into
+    pub manifest: Vec<ExportManifestEntry>,
+    /// Files shared with the mods in the configured mod manager directory;
+    /// `None` when none is configured or the check couldn't run
+    pub conflicts: Option<ModConflictReport>,
     pub message: String,
 }
 
@@ -40,6 +69,20 @@ pub struct RepathResultDto {
     pub paths_modified: usize,
     pub files_relocated: usize,
     pub missing_paths: Vec<String>,
+    /// BINs that couldn't be repathed; the others were still processed
+    pub failed_bins: Vec<RepathFileError>,
+    /// Unused files moved to the project's quarantine (project-relative)
+    pub quarantined: Vec<String>,
+    /// Quarantine batch to restore them from
+    pub quarantine_id: Option<String>,
+    /// Values that didn't come from the project config (e.g. the settings creator)
+    pub warnings: Vec<String>,
+    /// Problems following the main BIN's dependencies
+    pub repath_warnings: Vec<RepathWarning>,
+    /// Map-scoped BINs referencing the champion, relative to `content/base`
+    pub map_bins: Vec<String>,
+    /// Time spent in each phase, in order
+    pub timings: Vec<PhaseTiming>,
     pub message: String,
 }
 
@@ -47,56 +90,101 @@ pub struct RepathResultDto {
 ///
 /// This modifies BIN file paths and relocates asset files to prevent conflicts.
 ///
+/// The champion and skin come from the project. Names left out default to
+/// the project's first author and slug; values the project itself lacks are
+/// filled in from settings and reported in `warnings`. If the content is for
+/// a different skin than the project says, the detected skin is used and the
+/// mismatch is reported too.
+///
 /// # Arguments
 /// * `project_path` - Path to the project directory
-/// * `creator_name` - Creator name for prefix (e.g., "SirDexal")
-/// * `project_name` - Project name for prefix (e.g., "MyMod")
+/// * `creator_name` - Creator name for prefix (e.g., "SirDexal"), defaults to the project author
+/// * `project_name` - Project name for prefix (e.g., "MyMod"), defaults to the project slug
+/// * `fix_skin_id` - Also write a detected skin ID back to the project
+/// * `map_overrides` - What to do with map-scoped BINs referencing the champion (default: keep in place)
+/// * `queue` - Wait for another mutating operation on the project instead of failing with Busy
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn repath_project_cmd(
     project_path: String,
     creator_name: Option<String>,
     project_name: Option<String>,
+    fix_skin_id: Option<bool>,
+    map_overrides: Option<MapOverrideMode>,
+    queue: Option<bool>,
     app: tauri::AppHandle,
-) -> Result<RepathResultDto, String> {
+    operations: State<'_, OperationRegistry>,
+    settings: State<'_, SettingsState>,
+    undo: State<'_, UndoJournal>,
+) -> Result<RepathResultDto, CommandError> {
     tracing::info!("Frontend requested repathing for: {}", project_path);
 
+    let mut timer = PhaseTimer::new();
+    timer.phase("prepare");
     let path = PathBuf::from(&project_path);
     let content_base = path.join("content").join("base");
-    
-    let creator = creator_name.unwrap_or_else(|| "bum".to_string());
-    let project = project_name.unwrap_or_else(|| "mod".to_string());
+    let (open_path, detect_path) = (path.clone(), content_base.clone());
+    let (mut project, detection) = tokio::task::spawn_blocking(move || {
+        ensure_writable(&open_path)?;
+        open_project(&open_path).map(|project| (project, detect_skin_ids(&detect_path)))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+    let settings = settings.get();
+    let (mut config, mut warnings) =
+        OrganizerConfig::for_project(&project, creator_name, project_name, settings.creator_name)?;
+    config.delete_unused = settings.delete_unused_files;
+    config.map_overrides = map_overrides.unwrap_or_default();
+    let detected_skin = reconcile_skin_id(&mut config, &detection, &mut warnings);
+
+    let operation = operations
+        .start_exclusive(OperationKind::Repath, &project_path, true, queue.unwrap_or(false))
+        .await?;
+    undo.invalidate(&path);
+    let cancel = operation.token();
+
+    if let (Some(skin_id), true) = (detected_skin, fix_skin_id.unwrap_or(false)) {
+        project.skin_id = skin_id;
+        tokio::task::spawn_blocking(move || save_project(&project))
+            .await
+            .map_err(|e| format!("Task failed: {}", e))??;
+        warnings.push(format!("Updated the project's skin ID to {}", skin_id));
+    }
+    for warning in &warnings {
+        tracing::warn!("Repath: {}", warning);
+    }
+    let mut timings = timer.finish();
 
     // Emit start event
     let _ = app.emit("repath-progress", serde_json::json!({
         "status": "starting",
-        "message": "Starting repathing..."
+        "message": "Starting repathing...",
+        "operation_id": operation.id()
     }));
 
-    let config = OrganizerConfig {
-        enable_concat: true,
-        enable_repath: true,
-        creator_name: creator.clone(),
-        project_name: project.clone(),
-        champion: String::new(), // Champion not provided in direct repath call
-        target_skin_id: 0,
-        cleanup_unused: true,
-    };
-
+    let span = operation.span();
+    let mappings_path = path.clone();
     let result = tokio::task::spawn_blocking(move || {
-        // Empty mappings since this is a manual repath, not from extraction
-        let path_mappings: HashMap<String, String> = HashMap::new();
-        organize_project(&content_base, &config, &path_mappings)
+        let _span = span.enter();
+        let path_mappings = project_path_mappings(&mappings_path);
+        organize_project(&content_base, &config, &path_mappings, Some(&cancel))
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
 
-    match result {
+    let response = match result {
         Ok(result) => {
+            timings.extend(result.timings.iter().cloned());
             let repath_res = result.repath_result.as_ref();
             let bins_processed = repath_res.map(|r| r.bins_processed).unwrap_or(0);
             let paths_modified = repath_res.map(|r| r.paths_modified).unwrap_or(0);
             let files_relocated = repath_res.map(|r| r.files_relocated).unwrap_or(0);
             let missing_paths = repath_res.map(|r| r.missing_paths.clone()).unwrap_or_default();
+            let failed_bins = repath_res.map(|r| r.failed_bins.clone()).unwrap_or_default();
+            let quarantined = repath_res.map(|r| r.quarantined.clone()).unwrap_or_default();
+            let quarantine_id = repath_res.and_then(|r| r.quarantine_id.clone());
+            let repath_warnings = repath_res.map(|r| r.warnings.clone()).unwrap_or_default();
+            let map_bins = result.map_bins.iter().map(|bin| bin.file.clone()).collect();
 
             let _ = app.emit("repath-progress", serde_json::json!({
                 "status": "complete",
@@ -109,6 +197,13 @@ pub async fn repath_project_cmd(
                 paths_modified,
                 files_relocated,
                 missing_paths,
+                failed_bins,
+                quarantined,
+                quarantine_id,
+                warnings,
+                repath_warnings,
+                map_bins,
+                timings: timings.clone(),
                 message: format!(
                     "Successfully repathed {} paths in {} BIN files",
                     paths_modified, bins_processed
@@ -121,28 +216,206 @@ pub async fn repath_project_cmd(
                 "message": format!("Repathing failed: {}", e)
             }));
 
-            Err(e.to_string())
+            Err(e.into())
+        }
+    };
+    if let Ok(report) = &response {
+        if let Err(e) = save_project_report(&path, REPATH_REPORT_FILE, report) {
+            tracing::warn!("Failed to save the repath report: {}", e);
         }
     }
+
+    operation.set_timings(&timings);
+    operation.finish(&response);
+    response
+}
+
+/// List the batches of unused files repath cleanup moved to quarantine
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+#[tauri::command]
+pub async fn list_quarantine(project_path: String) -> Result<Vec<QuarantineBatch>, CommandError> {
+    tokio::task::spawn_blocking(move || core_list_quarantine(&PathBuf::from(&project_path)))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(CommandError::from)
+}
+
+/// Move a quarantine batch back into the project
+///
+/// Files whose original path has been taken again stay in quarantine and are
+/// listed in `conflicts`.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `timestamp` - Batch ID from `RepathResultDto::quarantine_id` or `list_quarantine`
+#[tauri::command]
+pub async fn restore_quarantine(
+    project_path: String,
+    timestamp: String,
+    operations: State<'_, OperationRegistry>,
+    undo: State<'_, UndoJournal>,
+) -> Result<QuarantineRestore, CommandError> {
+    tracing::info!("Restoring quarantine {} in {}", timestamp, project_path);
+
+    ensure_writable(Path::new(&project_path))?;
+    let operation = operations
+        .start_exclusive(OperationKind::Repath, &project_path, false, false)
+        .await?;
+    let path = PathBuf::from(&project_path);
+    undo.invalidate(&path);
+    let result = tokio::task::spawn_blocking(move || core_restore_quarantine(&path, &timestamp))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(CommandError::from);
+    operation.finish(&result);
+    result
+}
+
+/// Permanently delete one quarantine batch, or all of them
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `timestamp` - Batch to delete; all batches when omitted
+///
+/// # Returns
+/// * `Ok(usize)` - Number of files deleted
+#[tauri::command]
+pub async fn purge_quarantine(
+    project_path: String,
+    timestamp: Option<String>,
+    operations: State<'_, OperationRegistry>,
+) -> Result<usize, CommandError> {
+    tracing::info!("Purging quarantine {:?} in {}", timestamp, project_path);
+
+    ensure_writable(Path::new(&project_path))?;
+    let operation = operations
+        .start_exclusive(OperationKind::Repath, &project_path, false, false)
+        .await?;
+    let path = PathBuf::from(&project_path);
+    let result = tokio::task::spawn_blocking(move || core_purge_quarantine(&path, timestamp.as_deref()))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(CommandError::from);
+    operation.finish(&result);
+    result
+}
+
+/// Find the repath prefix another mod left on the project's content and
+/// record it in the project, so repathing leaves those references alone
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+///
+/// # Returns
+/// * `Ok(Some(prefix))` - The prefix as "creator/project"
+/// * `Ok(None)` - The content isn't repathed by another mod
+#[tauri::command]
+pub async fn detect_repath_prefix(project_path: String) -> Result<Option<String>, CommandError> {
+    tokio::task::spawn_blocking(move || -> crate::error::Result<Option<String>> {
+        let path = PathBuf::from(&project_path);
+        let mut project = open_project(&path)?;
+        let prefix = core_detect_repath_prefix(&path.join("content").join("base"));
+        if project.foreign_prefix != prefix && is_writable(&path) {
+            project.foreign_prefix = prefix.clone();
+            save_project(&project)?;
+        }
+        Ok(prefix)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Rewrite references under another mod's repath prefix back to canonical
+/// `assets/` and `data/` paths and move the files to match
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `prefix` - Prefix to strip, as "creator/project"; defaults to the one
+///   recorded for the project, or detected when none is
+#[tauri::command]
+pub async fn strip_repath_prefix(
+    project_path: String,
+    prefix: Option<String>,
+    operations: State<'_, OperationRegistry>,
+    undo: State<'_, UndoJournal>,
+) -> Result<PrefixStripResult, CommandError> {
+    tracing::info!("Stripping repath prefix {:?} in {}", prefix, project_path);
+
+    ensure_writable(Path::new(&project_path))?;
+    let operation = operations
+        .start_exclusive(OperationKind::Repath, &project_path, false, false)
+        .await?;
+    let path = PathBuf::from(&project_path);
+    undo.invalidate(&path);
+    let result = tokio::task::spawn_blocking(move || -> crate::error::Result<PrefixStripResult> {
+        let mut project = open_project(&path)?;
+        let content_base = path.join("content").join("base");
+        let prefix = prefix
+            .filter(|p| !p.trim().is_empty())
+            .or_else(|| project.foreign_prefix.clone())
+            .or_else(|| core_detect_repath_prefix(&content_base))
+            .ok_or_else(|| Error::InvalidInput("No repath prefix found in the project".to_string()))?;
+
+        let result = core_strip_repath_prefix(&content_base, &prefix, &project.champion)?;
+        if project.foreign_prefix.as_deref().is_some_and(|p| p.eq_ignore_ascii_case(&result.prefix)) {
+            project.foreign_prefix = None;
+            save_project(&project)?;
+        }
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from);
+    operation.finish(&result);
+    result
 }
 
 /// Export a project as a .fantome mod package using ltk_fantome
 ///
+/// Repathing targets the project's skin, or the skin detected in the content
+/// when they disagree. WAD folders that don't name a client WAD of the
+/// installation are packed anyway. Both are reported in `warnings`. The
+/// champion's WAD folder is renamed to the installed WAD it targets (e.g.
+/// `wukong.wad.client` -> `MonkeyKing.wad.client`); when no installed WAD
+/// matches, the export fails with the closest names. BINs the
+/// repath couldn't read stop the export unless `force` is set, since the
+/// package would be missing what they define. With `pack_as_wad` the WAD
+/// folders are built into `.wad.client` archives instead of being packed as
+/// loose files.
+///
 /// # Arguments
 /// * `project_path` - Path to the project directory
 /// * `output_path` - Path where the .fantome file will be created
-/// * `champion` - Champion name for WAD structure (unused by ltk_fantome, kept for API compat)
+/// * `champion` - Champion name, used for repathing and to find the champion's WAD
 /// * `metadata` - Mod metadata
-/// * `auto_repath` - Whether to run repathing before export (default: true)
+/// * `wad_name` - WAD to pack the champion's content as, e.g. "Gnar.wad.client", instead of the one matching `champion`
+/// * `auto_repath` - Whether to run repathing before export (default: from settings)
+/// * `map_overrides` - With repathing, what to do with map-scoped BINs referencing the champion
+/// * `queue` - With repathing, wait for another mutating operation instead of failing with Busy
+/// * `force` - Export even if repathing reported unreadable BINs
+/// * `pack_as_wad` - Build each WAD folder into a `.wad.client` archive (default: false)
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn export_fantome(
     project_path: String,
     output_path: String,
     champion: String,
     metadata: ExportMetadata,
+    wad_name: Option<String>,
     auto_repath: Option<bool>,
+    map_overrides: Option<MapOverrideMode>,
+    queue: Option<bool>,
+    force: Option<bool>,
+    pack_as_wad: Option<bool>,
     app: tauri::AppHandle,
-) -> Result<ExportResult, String> {
+    operations: State<'_, OperationRegistry>,
+    settings: State<'_, SettingsState>,
+    undo: State<'_, UndoJournal>,
+    hashes: State<'_, HashtableState>,
+) -> Result<ExportResult, CommandError> {
     tracing::info!(
         "Frontend requested fantome export: {} -> {}",
         project_path,
@@ -151,14 +424,30 @@ pub async fn export_fantome(
 
     let path = PathBuf::from(&project_path);
     let output = PathBuf::from(&output_path);
-    let do_repath = auto_repath.unwrap_or(true);
+    let do_repath = auto_repath.unwrap_or_else(|| settings.get().export.auto_repath);
+
+    // Repathing rewrites the content directory, so only then does export need the project to itself
+    let operation = if do_repath {
+        ensure_writable(&path)?;
+        operations
+            .start_exclusive(OperationKind::Export, &project_path, true, queue.unwrap_or(false))
+            .await?
+    } else {
+        operations.start(OperationKind::Export, Some(&project_path))
+    };
+    undo.invalidate(&path);
+    let cancel = operation.token();
+    let mut warnings = Vec::new();
+    let mut timer = PhaseTimer::new();
 
     // Step 1: Repath if requested
     if do_repath {
+        timer.phase("repath");
         let _ = app.emit("export-progress", serde_json::json!({
             "status": "repathing",
             "progress": 0.2,
-            "message": "Repathing assets..."
+            "message": "Repathing assets...",
+            "operation_id": operation.id()
         }));
 
         let config = OrganizerConfig {
@@ -169,35 +458,53 @@ pub async fn export_fantome(
             champion: champion.clone(),
             target_skin_id: 0,
             cleanup_unused: false,
+            delete_unused: false,
+            map_overrides: map_overrides.unwrap_or_default(),
+            foreign_prefix: None,
         };
 
-        let repath_path = path.join("content").join("base");
-        let repath_result = tokio::task::spawn_blocking(move || {
-            let path_mappings: HashMap<String, String> = HashMap::new();
-            organize_project(&repath_path, &config, &path_mappings)
+        let repath_cancel = cancel.clone();
+        let project_path = path.clone();
+        let span = operation.span();
+        let force = force.unwrap_or(false);
+        let repath_warnings = tokio::task::spawn_blocking(move || {
+            let _span = span.enter();
+            repath_for_export(&project_path, config, force, &repath_cancel)
         })
         .await
-        .map_err(|e| format!("Repath task failed: {}", e))?;
-
-        if let Err(e) = repath_result {
-            tracing::warn!("Repathing failed (continuing anyway): {}", e);
-        }
+        .map_err(|e| format!("Repath task failed: {}", e))??;
+        warnings.extend(repath_warnings);
     }
+    cancel.check()?;
+
+    timer.phase("check_wads");
+    let check_path = path.clone();
+    let settings_league = settings.get().league_path;
+    let check_champion = champion.clone();
+    let wad_warnings = tokio::task::spawn_blocking(move || {
+        prepare_export_wads(&check_path, &check_champion, wad_name.as_deref(), settings_league)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)?;
+    warnings.extend(wad_warnings);
 
     // Step 2: Export using ltk_fantome
+    timer.phase("pack");
     let _ = app.emit("export-progress", serde_json::json!({
         "status": "exporting",
         "progress": 0.5,
-        "message": "Creating fantome package..."
+        "message": "Creating fantome package...",
+        "operation_id": operation.id()
     }));
 
     // Read ModProject from mod.config.json (contains author from project creation)
     let mod_config_path = path.join("mod.config.json");
     let mod_project = if mod_config_path.exists() {
         let config_data = std::fs::read_to_string(&mod_config_path)
-            .map_err(|e| format!("Failed to read mod.config.json: {}", e))?;
+            .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read mod.config.json: {}", e)))?;
         serde_json::from_str::<ModProject>(&config_data)
-            .map_err(|e| format!("Failed to parse mod.config.json: {}", e))?
+            .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse mod.config.json: {}", e)))?
     } else {
         // Fallback: create from metadata if mod.config.json doesn't exist
         ModProject {
@@ -215,15 +522,36 @@ pub async fn export_fantome(
 
     let export_path = path.clone();
     let export_output = output.clone();
+    let pack_as_wad = pack_as_wad.unwrap_or(false);
 
+    let span = operation.span();
     let result = tokio::task::spawn_blocking(move || {
-        export_with_ltk_fantome(&export_path, &export_output, &mod_project)
+        let _span = span.enter();
+        let content_base = export_path.join("content").join("base");
+        let packed = if pack_as_wad {
+            export_with_packed_wads(&export_path, &export_output, &mod_project)
+        } else {
+            export_with_ltk_fantome(&export_path, &export_output, &mod_project)
+                .map(|(file_count, total_size)| (file_count, total_size, Vec::new()))
+        };
+        packed.map(|(file_count, total_size, pack_warnings)| {
+            (file_count, total_size, pack_warnings, export_manifest(&content_base))
+        })
     })
     .await
     .map_err(|e| format!("Export task failed: {}", e))?;
+    let conflicts = match (&result, settings.get().export.mod_manager_dir) {
+        (Ok(_), Some(dir)) => {
+            timer.phase("conflicts");
+            installed_mod_conflicts(&output, dir, hashes.peek(), &mut warnings).await
+        }
+        _ => None,
+    };
+    let timings = timer.finish();
 
-    match result {
-        Ok((file_count, total_size)) => {
+    let response = match result {
+        Ok((file_count, total_size, pack_warnings, manifest)) => {
+            warnings.extend(pack_warnings);
             let _ = app.emit("export-progress", serde_json::json!({
                 "status": "complete",
                 "progress": 1.0,
@@ -235,6 +563,10 @@ pub async fn export_fantome(
                 output_path: output.to_string_lossy().to_string(),
                 file_count,
                 total_size,
+                warnings,
+                timings: timings.clone(),
+                manifest,
+                conflicts,
                 message: format!(
                     "Successfully exported {} files ({} bytes)",
                     file_count, total_size
@@ -250,21 +582,428 @@ pub async fn export_fantome(
 
             Err(e)
         }
+    };
+    record_export_history(&path, "fantome", &output, &response);
+
+    operation.set_timings(&timings);
+    operation.finish(&response);
+    response
+}
+
+/// Add an export to the project's export history. A history that can't be
+/// written (e.g. the project is read-only) is logged and never fails the export.
+fn record_export_history(
+    project_path: &Path,
+    format: &str,
+    output: &Path,
+    response: &Result<ExportResult, CommandError>,
+) {
+    let (output_path, file_count, total_size, warnings, error) = match response {
+        Ok(result) => (result.output_path.clone(), result.file_count, result.total_size, result.warnings.clone(), None),
+        Err(e) => (output.to_string_lossy().to_string(), 0, 0, Vec::new(), Some(e.message.clone())),
+    };
+    let record = ExportRecord {
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        format: format.to_string(),
+        output_path,
+        file_count,
+        total_size,
+        warnings,
+        error,
+    };
+    if let Err(e) = record_export(project_path, record) {
+        tracing::warn!("Failed to record the export in {}: {}", project_path.display(), e);
     }
 }
 
+/// Check a package against the mods in the configured mod manager
+/// directory, noting shared files in `warnings`. A failed check is logged
+/// and never fails the export.
+async fn installed_mod_conflicts(
+    output: &Path,
+    mod_manager_dir: PathBuf,
+    hashtable: Option<Arc<Hashtable>>,
+    warnings: &mut Vec<String>,
+) -> Option<ModConflictReport> {
+    let package = output.to_path_buf();
+    let report = tokio::task::spawn_blocking(move || {
+        core_check_mod_conflicts(&package, &[mod_manager_dir], hashtable.as_deref())
+    })
+    .await;
+    let report = match report {
+        Ok(Ok(report)) => report,
+        Ok(Err(e)) => {
+            tracing::warn!("Conflict check after export failed: {}", e);
+            return None;
+        }
+        Err(e) => {
+            tracing::warn!("Conflict check task failed: {}", e);
+            return None;
+        }
+    };
+    for group in &report.conflicts {
+        warnings.push(format!(
+            "Shares {} file(s) with the installed mod '{}'; whichever loads last wins",
+            group.count, group.mod_name
+        ));
+    }
+    Some(report)
+}
+
+/// Export several projects as one .fantome
+///
+/// Each project is repathed under its own prefix (its first author, or
+/// `metadata.author` when it has none, and its slug) and its champion WAD
+/// folder is resolved as in `export_fantome`. The projects' content is then
+/// merged by WAD folder into one package, whose description credits every
+/// project. Files two projects ship with different contents fail the
+/// export; so does any project failing, with its name in the error.
+/// `export-progress` events carry `project`, `project_index` and
+/// `project_count` while projects are processed.
+///
+/// # Arguments
+/// * `project_paths` - Project directories, in the order they are credited
+/// * `metadata` - Bundle name, version, description and author
+/// * `output_path` - Path where the .fantome file will be created
+/// * `force` - Export even if repathing a project reported unreadable BINs
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_bundle(
+    project_paths: Vec<String>,
+    metadata: ExportMetadata,
+    output_path: String,
+    force: Option<bool>,
+    app: tauri::AppHandle,
+    operations: State<'_, OperationRegistry>,
+    settings: State<'_, SettingsState>,
+    undo: State<'_, UndoJournal>,
+    hashes: State<'_, HashtableState>,
+) -> Result<ExportResult, CommandError> {
+    tracing::info!("Frontend requested bundle export of {} projects -> {}", project_paths.len(), output_path);
+
+    let mut seen = std::collections::HashSet::new();
+    if project_paths.len() < 2 || !project_paths.iter().all(|p| seen.insert(p.as_str())) {
+        return Err(CommandError::new(ErrorCode::InvalidInput, "A bundle needs at least two different projects"));
+    }
+    let paths: Vec<PathBuf> = project_paths.iter().map(PathBuf::from).collect();
+    let output = PathBuf::from(&output_path);
+
+    // Every project is repathed, so the bundle needs each of them to itself
+    let mut project_slots = Vec::with_capacity(paths.len());
+    for (project_path, path) in project_paths.iter().zip(&paths) {
+        let slot = ensure_writable(path)
+            .and_then(|_| operations.try_start_exclusive(OperationKind::Export, project_path, false))
+            .map_err(|e| bundle_project_error(project_path, e.into()))?;
+        project_slots.push(slot);
+        undo.invalidate(path);
+    }
+    let operation = operations.start(OperationKind::Export, None);
+    let cancel = operation.token();
+    let settings_league = settings.get().league_path;
+    let force = force.unwrap_or(false);
+    let project_count = paths.len();
+    let mut warnings = Vec::new();
+    let mut credits = Vec::with_capacity(project_count);
+    let mut authors: Vec<String> = Vec::new();
+    let mut timer = PhaseTimer::new();
+
+    for (index, path) in paths.iter().enumerate() {
+        cancel.check()?;
+        let project_path = path.to_string_lossy().to_string();
+        let project = open_project(path).map_err(|e| bundle_project_error(&project_path, e.into()))?;
+        let name = if project.display_name.is_empty() { project.name.clone() } else { project.display_name.clone() };
+        timer.phase(format!("project:{}", project.name));
+        let _ = app.emit("export-progress", serde_json::json!({
+            "status": "repathing",
+            "progress": 0.6 * index as f32 / project_count as f32,
+            "message": format!("Repathing {} ({}/{})...", name, index + 1, project_count),
+            "operation_id": operation.id(),
+            "project": name,
+            "project_index": index,
+            "project_count": project_count
+        }));
+
+        let (mut config, config_warnings) =
+            OrganizerConfig::for_project(&project, None, None, Some(metadata.author.clone()))
+                .map_err(|e| bundle_project_error(&name, e.into()))?;
+        config.cleanup_unused = false;
+        let repath_path = path.clone();
+        let repath_cancel = cancel.clone();
+        let champion = project.champion.clone();
+        let league = project.league_path.clone().or_else(|| settings_league.clone());
+        let span = operation.span();
+        let project_warnings = tokio::task::spawn_blocking(move || -> Result<Vec<String>, CommandError> {
+            let _span = span.enter();
+            let mut warnings = repath_for_export(&repath_path, config, force, &repath_cancel)?;
+            warnings.extend(prepare_export_wads(&repath_path, &champion, None, league)?);
+            Ok(warnings)
+        })
+        .await
+        .map_err(|e| format!("Repath task failed: {}", e))?;
+        let project_warnings = project_warnings.map_err(|e| {
+            let _ = app.emit("export-progress", serde_json::json!({
+                "status": "error",
+                "progress": 0.0,
+                "message": format!("{} failed: {}", name, e.message),
+                "operation_id": operation.id(),
+                "project": name,
+                "project_index": index,
+                "project_count": project_count
+            }));
+            bundle_project_error(&name, e)
+        })?;
+        warnings.extend(config_warnings.into_iter().chain(project_warnings).map(|w| format!("{}: {}", name, w)));
+
+        for author in &project.authors {
+            if !authors.iter().any(|a| a.eq_ignore_ascii_case(author)) {
+                authors.push(author.clone());
+            }
+        }
+        credits.push((name, project.authors));
+    }
+    cancel.check()?;
+    if !metadata.author.trim().is_empty() && !authors.iter().any(|a| a.eq_ignore_ascii_case(&metadata.author)) {
+        authors.insert(0, metadata.author.clone());
+    }
+
+    timer.phase("pack");
+    let _ = app.emit("export-progress", serde_json::json!({
+        "status": "exporting",
+        "progress": 0.6,
+        "message": "Merging projects into one package...",
+        "operation_id": operation.id()
+    }));
+    let mod_project = ModProject {
+        name: slugify(&metadata.name),
+        display_name: metadata.name.clone(),
+        version: metadata.version.clone(),
+        description: bundle_description(&metadata.description, &credits),
+        authors: authors.into_iter().map(ModProjectAuthor::Name).collect(),
+        license: None,
+        transformers: vec![],
+        layers: ltk_mod_project::default_layers(),
+        thumbnail: None,
+    };
+    let names: Vec<String> = credits.into_iter().map(|(name, _)| name).collect();
+    let export_output = output.clone();
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || {
+        let _span = span.enter();
+        let content_bases: Vec<PathBuf> = paths.iter().map(|p| p.join("content").join("base")).collect();
+        let parts: Vec<BundlePart> = names
+            .iter()
+            .zip(&content_bases)
+            .map(|(name, content_base)| BundlePart { name, content_base })
+            .collect();
+        let conflicts = find_bundle_conflicts(&parts)?;
+        if !conflicts.is_empty() {
+            return Err(bundle_conflict_error(&conflicts));
+        }
+
+        let mut staging = export_output.as_os_str().to_owned();
+        staging.push(".staging");
+        let staging = PathBuf::from(staging);
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging).map_err(|e| Error::io_with_path(e, &staging))?;
+        }
+        let staged_base = staging.join("content").join("base");
+        let packed = stage_bundle(&parts, &staged_base)
+            .map_err(CommandError::from)
+            .and_then(|_| export_with_ltk_fantome(&staging, &export_output, &mod_project))
+            .map(|(file_count, total_size)| (file_count, total_size, export_manifest(&staged_base)));
+        if let Err(e) = std::fs::remove_dir_all(&staging) {
+            tracing::warn!("Failed to remove bundle staging {}: {}", staging.display(), e);
+        }
+        packed
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?;
+    let conflicts = match (&result, settings.get().export.mod_manager_dir) {
+        (Ok(_), Some(dir)) => {
+            timer.phase("conflicts");
+            installed_mod_conflicts(&output, dir, hashes.peek(), &mut warnings).await
+        }
+        _ => None,
+    };
+    let timings = timer.finish();
+
+    let response = match result {
+        Ok((file_count, total_size, manifest)) => {
+            let _ = app.emit("export-progress", serde_json::json!({
+                "status": "complete",
+                "progress": 1.0,
+                "message": format!("Export complete: {}", output.display())
+            }));
+
+            Ok(ExportResult {
+                success: true,
+                output_path: output.to_string_lossy().to_string(),
+                file_count,
+                total_size,
+                warnings,
+                timings: timings.clone(),
+                manifest,
+                conflicts,
+                message: format!(
+                    "Bundled {} projects: {} files ({} bytes)",
+                    project_count, file_count, total_size
+                ),
+            })
+        }
+        Err(e) => {
+            let _ = app.emit("export-progress", serde_json::json!({
+                "status": "error",
+                "progress": 0.0,
+                "message": format!("Export failed: {}", e)
+            }));
+
+            Err(e)
+        }
+    };
+
+    for project_path in &project_paths {
+        record_export_history(Path::new(project_path), "bundle", &output, &response);
+    }
+    for slot in project_slots {
+        slot.finish(&response);
+    }
+    operation.set_timings(&timings);
+    operation.finish(&response);
+    response
+}
+
+/// `error`, saying which project of a bundle it came from
+fn bundle_project_error(project: &str, mut error: CommandError) -> CommandError {
+    error.message = format!("Bundle project '{}' failed: {}", project, error.message);
+    error
+}
+
+/// Error for files two bundled projects ship with different contents
+fn bundle_conflict_error(conflicts: &[BundleConflict]) -> CommandError {
+    let mut error = CommandError::new(
+        ErrorCode::InvalidInput,
+        format!(
+            "{} file(s) are shipped with different contents by more than one project; \
+             remove them from all but one project before bundling",
+            conflicts.len()
+        ),
+    );
+    error.details = Some(
+        conflicts
+            .iter()
+            .map(|c| format!("{}: {}", c.file, c.projects.join(", ")))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    error.path = conflicts.first().map(|c| c.file.clone());
+    error
+}
+
+/// Repath a project's content before it is packed. Projects with a
+/// mod.config.json supply the skin ID and any recorded foreign prefix;
+/// legacy ones keep skin 0. The skin is reconciled with the one detected in
+/// the content. BINs the repath couldn't read fail the export unless `force`
+/// is set; other problems are returned as warnings.
+fn repath_for_export(
+    project_path: &Path,
+    mut config: OrganizerConfig,
+    force: bool,
+    cancel: &CancellationToken,
+) -> Result<Vec<String>, CommandError> {
+    let content_base = project_path.join("content").join("base");
+    if let Ok(project) = open_project(project_path) {
+        config.target_skin_id = project.skin_id;
+        config.foreign_prefix = project.foreign_prefix;
+    }
+    let mut skin_warnings = Vec::new();
+    reconcile_skin_id(&mut config, &detect_skin_ids(&content_base), &mut skin_warnings);
+
+    let path_mappings = project_path_mappings(project_path);
+    let mut warnings = Vec::new();
+    match organize_project(&content_base, &config, &path_mappings, Some(cancel)) {
+        Ok(result) => {
+            let repath_warnings = result.repath_result.map(|r| r.warnings).unwrap_or_default();
+            let blocking: Vec<&RepathWarning> = repath_warnings.iter().filter(|w| w.kind.is_blocking()).collect();
+            if !blocking.is_empty() && !force {
+                return Err(blocking_repath_error(&blocking));
+            }
+            warnings.extend(repath_warnings.iter().map(|w| format!("{}: {}", w.path, w.message)));
+        }
+        Err(e) => tracing::warn!("Repathing failed (continuing anyway): {}", e),
+    }
+    for warning in &skin_warnings {
+        tracing::warn!("Export: {}", warning);
+    }
+    warnings.extend(skin_warnings);
+    Ok(warnings)
+}
+
+/// Rename the project's champion WAD folder to the installed WAD it targets
+/// and check the other WAD folders against the installation. Without an
+/// installation only the name checks are skipped.
+fn prepare_export_wads(
+    project_path: &Path,
+    champion: &str,
+    wad_name: Option<&str>,
+    settings_league: Option<PathBuf>,
+) -> crate::error::Result<Vec<String>> {
+    let installed = open_project(project_path)
+        .ok()
+        .and_then(|p| p.league_path)
+        .or(settings_league)
+        .map(|league_path| list_client_wads(&league_path))
+        .unwrap_or_default();
+    let content_base = project_path.join("content").join("base");
+    let mut warnings = Vec::new();
+    if !champion.is_empty() || wad_name.is_some() {
+        let wad = resolve_champion_wad(champion, wad_name, &installed)?;
+        match rename_champion_wad(&content_base, champion, &wad) {
+            Ok(note) => warnings.extend(note),
+            Err(_) if !is_writable(project_path) => warnings.push(format!(
+                "The project is read-only, so its champion WAD folder couldn't be renamed to {}",
+                wad
+            )),
+            Err(e) => return Err(e),
+        }
+    }
+    warnings.extend(check_wad_targets(&content_base, &installed));
+    for warning in &warnings {
+        tracing::warn!("Export: {}", warning);
+    }
+    Ok(warnings)
+}
+
+/// Error for repath warnings that would leave the package incomplete
+fn blocking_repath_error(warnings: &[&RepathWarning]) -> CommandError {
+    let mut error = CommandError::new(
+        ErrorCode::BinConversion,
+        format!(
+            "Repathing couldn't read {} BIN file(s), so the exported mod would be incomplete. \
+             Fix them, or export with force to ignore this.",
+            warnings.len()
+        ),
+    );
+    error.details = Some(warnings.iter().map(|w| format!("{}: {}", w.path, w.message)).collect::<Vec<_>>().join("\n"));
+    error.path = warnings.first().map(|w| w.path.clone());
+    error
+}
+
 /// Helper function to export using ltk_fantome::pack_to_fantome
 fn export_with_ltk_fantome(
     project_path: &Path,
     output_path: &Path,
     mod_project: &ModProject,
-) -> Result<(usize, u64), String> {
+) -> Result<(usize, u64), CommandError> {
+    let content_base = project_path.join("content").join("base");
+
+    // The package is never bigger than the files that go into it
+    check_disk_space(output_path, directory_size(&content_base))?;
+
     // Create output file
     let file = File::create(output_path)
         .map_err(|e| format!("Failed to create output file: {}", e))?;
 
     // Count files before export
-    let content_base = project_path.join("content").join("base");
     let file_count = walkdir::WalkDir::new(&content_base)
         .into_iter()
         .filter_map(|e| e.ok())
@@ -283,35 +1022,229 @@ fn export_with_ltk_fantome(
     Ok((file_count, total_size))
 }
 
+/// Helper function to export a fantome whose WAD folders are built into
+/// archives, with a warning per file left out for a duplicate path hash
+fn export_with_packed_wads(
+    project_path: &Path,
+    output_path: &Path,
+    mod_project: &ModProject,
+) -> Result<(usize, u64, Vec<String>), CommandError> {
+    let content_base = project_path.join("content").join("base");
+
+    // The package is never bigger than the files that go into it
+    check_disk_space(output_path, directory_size(&content_base))?;
+
+    let packed = pack_fantome_with_wads(project_path, output_path, mod_project)?;
+    let warnings = packed
+        .duplicates
+        .iter()
+        .map(|path| format!("{} was left out of its WAD: another file has the same path hash", path))
+        .collect();
+    Ok((packed.file_count, packed.total_size, warnings))
+}
+
 /// Generate a suggested filename for the fantome export
 #[tauri::command]
 pub fn get_fantome_filename(name: String, version: String) -> String {
     generate_fantome_filename(&name, &version)
 }
 
-/// Get export preview (list of files that would be exported)
+/// Get export preview: the files that would be exported and the WAD each goes into
 #[tauri::command]
-pub async fn get_export_preview(project_path: String) -> Result<Vec<String>, String> {
+pub async fn get_export_preview(project_path: String) -> Result<Vec<ExportManifestEntry>, CommandError> {
     let path = PathBuf::from(&project_path);
     let content_base = path.join("content").join("base");
 
     if !content_base.exists() {
-        return Err(format!("Content directory not found: {}", content_base.display()));
+        return Err(CommandError::new(
+            ErrorCode::NotFound,
+            format!("Content directory not found: {}", content_base.display()),
+        ));
     }
 
-    let files: Vec<String> = walkdir::WalkDir::new(&content_base)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-        .filter_map(|e| {
-            e.path()
-                .strip_prefix(&content_base)
-                .ok()
-                .map(|p| p.to_string_lossy().to_string())
-        })
-        .collect();
+    tokio::task::spawn_blocking(move || export_manifest(&content_base))
+        .await
+        .map_err(|e| CommandError::from(format!("Task failed: {}", e)))
+}
+
+/// Report the files a package shares with other mods, grouped by mod
+///
+/// # Arguments
+/// * `package_path` - A .fantome, a mod folder or a project
+/// * `others` - Packages to compare with, or directories of them; empty for
+///   the mod manager directory in settings
+#[tauri::command]
+pub async fn check_mod_conflicts(
+    package_path: String,
+    others: Vec<String>,
+    settings: State<'_, SettingsState>,
+    hashes: State<'_, HashtableState>,
+) -> Result<ModConflictReport, CommandError> {
+    let others: Vec<PathBuf> = if others.is_empty() {
+        let dir = settings.get().export.mod_manager_dir.ok_or_else(|| {
+            CommandError::new(ErrorCode::InvalidInput, "No mods to compare with and no mod manager directory set")
+        })?;
+        vec![dir]
+    } else {
+        others.iter().map(PathBuf::from).collect()
+    };
+    let hashtable = hashes.peek();
+
+    tokio::task::spawn_blocking(move || {
+        core_check_mod_conflicts(&PathBuf::from(&package_path), &others, hashtable.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Result of `live_sync`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveSyncStarted {
+    pub overlay_dir: String,
+    /// Files copied by the initial sync
+    pub file_count: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Payload of `live-sync`, emitted after each batch of changes is synced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveSyncEvent {
+    pub project_path: String,
+    pub overlay_dir: String,
+    #[serde(flatten)]
+    pub batch: LiveSyncBatch,
+    /// Some synced file only shows after the game restarts
+    pub restart_required: bool,
+}
+
+/// Start syncing a project into a mod folder a running game reads
+/// (experimental)
+///
+/// The project is repathed (with auto-repath on) and copied unpacked into
+/// `overlay_dir`, which must be empty or an earlier live sync's. From then
+/// on every change under `content/base` is copied over once the project has
+/// been quiet for a moment, changed BINs repathed first, and reported in a
+/// `live-sync` event. Textures and BINs can show while the game runs;
+/// other files are flagged as needing a restart.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `overlay_dir` - Mod folder the runtime reads, e.g. in the mod manager's installed mods
+/// * `queue` - Wait for another mutating operation on the project instead of failing with Busy
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn live_sync(
+    project_path: String,
+    overlay_dir: String,
+    queue: Option<bool>,
+    app: tauri::AppHandle,
+    operations: State<'_, OperationRegistry>,
+    settings: State<'_, SettingsState>,
+    undo: State<'_, UndoJournal>,
+    live: State<'_, LiveSyncRegistry>,
+) -> Result<LiveSyncStarted, CommandError> {
+    tracing::info!("Frontend requested live sync: {} -> {}", project_path, overlay_dir);
+
+    let path = PathBuf::from(&project_path);
+    let overlay = PathBuf::from(&overlay_dir);
+    let content_base = path.join("content").join("base");
+    // A restart begins from a fresh copy
+    live.stop(&path);
+
+    let open_path = path.clone();
+    let project = tokio::task::spawn_blocking(move || {
+        ensure_writable(&open_path)?;
+        open_project(&open_path)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+    let settings = settings.get();
+    let do_repath = settings.export.auto_repath;
+    let (mut config, mut warnings) = OrganizerConfig::for_project(&project, None, None, settings.creator_name)?;
+    config.cleanup_unused = false;
+
+    let operation = operations
+        .start_exclusive(OperationKind::Export, &project_path, true, queue.unwrap_or(false))
+        .await?;
+    undo.invalidate(&path);
+    let cancel = operation.token();
+    let span = operation.span();
+    let (sync_path, sync_base, sync_overlay) = (path.clone(), content_base.clone(), overlay.clone());
+    let result = tokio::task::spawn_blocking(move || -> Result<(usize, Option<RepathConfig>, Vec<String>), CommandError> {
+        let _span = span.enter();
+        let mut warnings = Vec::new();
+        reconcile_skin_id(&mut config, &detect_skin_ids(&sync_base), &mut warnings);
+        let repath = if do_repath {
+            warnings.extend(repath_for_export(&sync_path, config.clone(), false, &cancel)?);
+            Some(config.repath_config())
+        } else {
+            None
+        };
+        cancel.check()?;
+        warnings.extend(prepare_export_wads(&sync_path, &project.champion, None, settings.league_path)?);
+        let name = if project.display_name.is_empty() { &project.name } else { &project.display_name };
+        let copied = populate_overlay(&sync_base, &sync_overlay, name, &config.creator_name)?;
+        Ok((copied, repath, warnings))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    operation.finish(&result);
+    let (file_count, repath, sync_warnings) = result?;
+    warnings.extend(sync_warnings);
+
+    let (watch_base, watch_overlay, watch_project_path) = (content_base.clone(), overlay.clone(), project_path.clone());
+    let watcher = watch_project(&content_base, move |changed| {
+        let operations = app.state::<OperationRegistry>();
+        // Repathing rewrites project BINs, so only then does a sync need the project to itself
+        let repaths_bins = repath.is_some()
+            && changed.iter().any(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")));
+        let operation = if repaths_bins {
+            match tauri::async_runtime::block_on(operations.start_exclusive(
+                OperationKind::Export,
+                &watch_project_path,
+                false,
+                true,
+            )) {
+                Ok(operation) => Some(operation),
+                Err(e) => {
+                    tracing::warn!("Live sync skipped a batch: {}", e);
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        let batch = sync_changes(&watch_base, &watch_overlay, &changed, repath.as_ref());
+        if let Some(operation) = operation {
+            operation.finish(&Ok::<_, CommandError>(()));
+        }
+        if batch.is_empty() {
+            return;
+        }
+        let _ = app.emit("live-sync", LiveSyncEvent {
+            project_path: watch_project_path.clone(),
+            overlay_dir: watch_overlay.to_string_lossy().to_string(),
+            restart_required: batch.restart_required(),
+            batch,
+        });
+    })?;
+    live.insert(path, overlay.clone(), watcher);
 
-    Ok(files)
+    Ok(LiveSyncStarted { overlay_dir: overlay.to_string_lossy().to_string(), file_count, warnings })
+}
+
+/// Stop a project's live sync. The overlay folder is left as it is.
+/// Returns whether a sync was running.
+#[tauri::command]
+pub fn stop_live_sync(project_path: String, live: State<'_, LiveSyncRegistry>) -> bool {
+    match live.stop(&PathBuf::from(&project_path)) {
+        Some(overlay_dir) => {
+            tracing::info!("Stopped live sync of {} to {}", project_path, overlay_dir.display());
+            true
+        }
+        None => false,
+    }
 }
 
 /// Export a project as a .modpkg mod package using ltk_modpkg
@@ -324,44 +1257,62 @@ pub async fn export_modpkg(
     project_path: String,
     output_path: String,
     app: tauri::AppHandle,
-) -> Result<ExportResult, String> {
+    operations: State<'_, OperationRegistry>,
+    undo: State<'_, UndoJournal>,
+) -> Result<ExportResult, CommandError> {
     tracing::info!(
         "Frontend requested modpkg export: {} -> {}",
         project_path,
         output_path
     );
 
+    let operation = operations.start(OperationKind::Export, Some(&project_path));
+    let cancel = operation.token();
+
     let path = PathBuf::from(&project_path);
     let output = PathBuf::from(&output_path);
+    undo.invalidate(&path);
+    let mut timer = PhaseTimer::new();
+    timer.phase("read_config");
 
     let _ = app.emit("export-progress", serde_json::json!({
         "status": "exporting",
         "progress": 0.3,
-        "message": "Creating modpkg package..."
+        "message": "Creating modpkg package...",
+        "operation_id": operation.id()
     }));
 
     // Read ModProject from mod.config.json
     let mod_config_path = path.join("mod.config.json");
     let mod_project = if mod_config_path.exists() {
         let config_data = std::fs::read_to_string(&mod_config_path)
-            .map_err(|e| format!("Failed to read mod.config.json: {}", e))?;
+            .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read mod.config.json: {}", e)))?;
         serde_json::from_str::<ModProject>(&config_data)
-            .map_err(|e| format!("Failed to parse mod.config.json: {}", e))?
+            .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse mod.config.json: {}", e)))?
     } else {
-        return Err("mod.config.json not found - cannot export modpkg without project metadata".to_string());
+        return Err(CommandError::new(
+            ErrorCode::NotFound,
+            "mod.config.json not found - cannot export modpkg without project metadata",
+        ));
     };
 
     let export_path = path.clone();
     let export_output = output.clone();
 
+    timer.phase("pack");
+    let span = operation.span();
     let result = tokio::task::spawn_blocking(move || {
-        export_with_ltk_modpkg(&export_path, &export_output, &mod_project)
+        let _span = span.enter();
+        let content_base = export_path.join("content").join("base");
+        export_with_ltk_modpkg(&export_path, &export_output, &mod_project, &cancel)
+            .map(|(file_count, total_size)| (file_count, total_size, export_manifest(&content_base)))
     })
     .await
     .map_err(|e| format!("Export task failed: {}", e))?;
+    let timings = timer.finish();
 
-    match result {
-        Ok((file_count, total_size)) => {
+    let response = match result {
+        Ok((file_count, total_size, manifest)) => {
             let _ = app.emit("export-progress", serde_json::json!({
                 "status": "complete",
                 "progress": 1.0,
@@ -373,6 +1324,10 @@ pub async fn export_modpkg(
                 output_path: output.to_string_lossy().to_string(),
                 file_count,
                 total_size,
+                warnings: Vec::new(),
+                timings: timings.clone(),
+                manifest,
+                conflicts: None,
                 message: format!(
                     "Successfully exported {} files ({} bytes)",
                     file_count, total_size
@@ -388,7 +1343,12 @@ pub async fn export_modpkg(
 
             Err(e)
         }
-    }
+    };
+    record_export_history(&path, "modpkg", &output, &response);
+
+    operation.set_timings(&timings);
+    operation.finish(&response);
+    response
 }
 
 /// Helper function to export using ltk_modpkg
@@ -396,13 +1356,16 @@ fn export_with_ltk_modpkg(
     project_path: &Path,
     output_path: &Path,
     mod_project: &ModProject,
-) -> Result<(usize, u64), String> {
+    cancel: &CancellationToken,
+) -> Result<(usize, u64), CommandError> {
     use ltk_modpkg::builder::{ModpkgBuilder, ModpkgChunkBuilder, ModpkgLayerBuilder};
     use ltk_modpkg::{ModpkgMetadata, ModpkgAuthor};
     use std::io::Write;
 
-    // Collect all files and their data
     let content_base = project_path.join("content").join("base");
+    check_disk_space(output_path, directory_size(&content_base))?;
+
+    // Collect all files and their data
     let mut file_map: HashMap<String, Vec<u8>> = HashMap::new();
     
     for entry in walkdir::WalkDir::new(&content_base)
@@ -410,6 +1373,7 @@ fn export_with_ltk_modpkg(
         .filter_map(|e| e.ok())
         .filter(|e| e.path().is_file())
     {
+        cancel.check()?;
         let file_path = entry.path();
         let relative_path = file_path
             .strip_prefix(&content_base)
@@ -500,3 +1464,15 @@ fn slugify(name: &str) -> String {
         .collect::<Vec<_>>()
         .join("-")
 }
+
+/// The project's path mappings for repathing. A mapping file that can't be
+/// read is logged and ignored; only hash-named BINs are missed then.
+fn project_path_mappings(project_path: &Path) -> HashMap<String, String> {
+    match PathMappings::load(project_path) {
+        Ok(mappings) => mappings.lookup(),
+        Err(e) => {
+            tracing::warn!("Ignoring path mappings: {}", e);
+            HashMap::new()
+        }
+    }
+}
@@ -7,6 +7,15 @@ use walkdir::WalkDir;
 use image::{RgbaImage, Rgba};
 use ltk_texture::Texture;
 use std::io::Cursor;
+use crate::core::blob::{BinaryPayload, BlobStore, BLOB_THRESHOLD};
+use crate::core::file::texture::analyze_texture_bytes;
+use crate::core::file::{load_rst_keys, StringTable, StringTableEntry, TextureAnalysis};
+use crate::core::project::{ensure_path_writable, find_project_root};
+use crate::core::recolor::{hsl_to_rgb, rgb_to_hsl};
+use crate::core::undo::{FileSnapshot, UndoJournal};
+use crate::error::{self, CommandError, ErrorCode};
+use crate::state::HashtableState;
+use tauri::State;
 
 /// Information about a file
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,13 +26,17 @@ pub struct FileInfo {
     pub extension: String,
     /// For images: width x height
     pub dimensions: Option<(u32, u32)>,
+    /// For DDS and TEX textures: format, colorspace and channel usage
+    pub texture: Option<TextureAnalysis>,
 }
 
 /// Result of decoding a DDS file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecodedImage {
-    /// Base64-encoded PNG data
+    /// Base64-encoded PNG data; empty when the PNG is served as a blob
     pub data: String,
+    /// Blob token for PNGs over the inline threshold
+    pub blob: Option<String>,
     pub width: u32,
     pub height: u32,
     pub format: String,
@@ -39,55 +52,6 @@ pub struct RecolorFolderResult {
 // HSL Color Transformation Helpers
 // =============================================================================
 
-fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
-    let max = r.max(g).max(b);
-    let min = r.min(g).min(b);
-    let l = (max + min) / 2.0;
-
-    if max == min {
-        return (0.0, 0.0, l);
-    }
-
-    let d = max - min;
-    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
-
-    let mut h = if max == r {
-        (g - b) / d + (if g < b { 6.0 } else { 0.0 })
-    } else if max == g {
-        (b - r) / d + 2.0
-    } else {
-        (r - g) / d + 4.0
-    };
-    h /= 6.0;
-
-    (h * 360.0, s, l)
-}
-
-fn hue_to_rgb(p: f32, q: f32, mut t: f32) -> f32 {
-    if t < 0.0 { t += 1.0; }
-    if t > 1.0 { t -= 1.0; }
-    if t < 1.0/6.0 { return p + (q - p) * 6.0 * t; }
-    if t < 1.0/2.0 { return q; }
-    if t < 2.0/3.0 { return p + (q - p) * (2.0/3.0 - t) * 6.0; }
-    p
-}
-
-fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
-    if s == 0.0 {
-        return (l, l, l);
-    }
-
-    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
-    let p = 2.0 * l - q;
-
-    let h = h / 360.0;
-    let r = hue_to_rgb(p, q, h + 1.0/3.0);
-    let g = hue_to_rgb(p, q, h);
-    let b = hue_to_rgb(p, q, h - 1.0/3.0);
-
-    (r, g, b)
-}
-
 fn apply_hsl_to_image(img: &mut RgbaImage, hue_shift: f32, sat_mult: f32, bri_mult: f32) {
     for pixel in img.pixels_mut() {
         let Rgba([r, g, b, a]) = *pixel;
@@ -226,17 +190,18 @@ fn detect_file_type(path: &Path, data: &[u8]) -> (String, String) {
 /// * `path` - Path to the file
 ///
 /// # Returns
-/// * `Ok(Vec<u8>)` - File contents as bytes
-/// * `Err(String)` - Error message
+/// * `Ok(BinaryPayload)` - File contents, inline or as a blob token when large
+/// * `Err(CommandError)` - Error message
 #[tauri::command]
-pub async fn read_file_bytes(path: String) -> Result<Vec<u8>, String> {
+pub async fn read_file_bytes(path: String, blobs: State<'_, BlobStore>) -> Result<BinaryPayload, CommandError> {
     let path = Path::new(&path);
 
     if !path.exists() {
-        return Err(format!("File not found: {}", path.display()));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("File not found: {}", path.display())));
     }
 
-    fs::read(path).map_err(|e| format!("Failed to read file: {}", e))
+    let data = fs::read(path).map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read file: {}", e)))?;
+    Ok(blobs.payload(data, "application/octet-stream"))
 }
 
 /// Get file metadata and type information
@@ -246,28 +211,38 @@ pub async fn read_file_bytes(path: String) -> Result<Vec<u8>, String> {
 ///
 /// # Returns
 /// * `Ok(FileInfo)` - File metadata
-/// * `Err(String)` - Error message
+/// * `Err(CommandError)` - Error message
 #[tauri::command]
-pub async fn read_file_info(path: String) -> Result<FileInfo, String> {
+pub async fn read_file_info(path: String) -> Result<FileInfo, CommandError> {
     let path_buf = std::path::PathBuf::from(&path);
 
     if !path_buf.exists() {
-        return Err(format!("File not found: {}", path));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("File not found: {}", path)));
     }
 
-    let metadata = fs::metadata(&path_buf).map_err(|e| format!("Failed to read metadata: {}", e))?;
+    let metadata = fs::metadata(&path_buf)
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read metadata: {}", e)))?;
 
     // Read first few bytes for magic detection
-    let data = fs::read(&path_buf).map_err(|e| format!("Failed to read file: {}", e))?;
+    let data = fs::read(&path_buf)
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read file: {}", e)))?;
 
     let (file_type, extension) = detect_file_type(&path_buf, &data);
 
     // Try to get dimensions for texture files (DDS and TEX)
-    let dimensions = if file_type == "image/dds" || file_type == "image/tex" {
+    let is_texture = file_type == "image/dds" || file_type == "image/tex";
+    let dimensions = if is_texture {
         parse_texture_dimensions(&data).ok()
     } else {
         None
     };
+    let texture = if is_texture {
+        analyze_texture_bytes(&data)
+            .inspect_err(|e| tracing::debug!("Texture analysis failed for {}: {}", path, e))
+            .ok()
+    } else {
+        None
+    };
 
     Ok(FileInfo {
         path,
@@ -275,6 +250,7 @@ pub async fn read_file_info(path: String) -> Result<FileInfo, String> {
         file_type,
         extension,
         dimensions,
+        texture,
     })
 }
 
@@ -290,8 +266,9 @@ fn parse_texture_dimensions(data: &[u8]) -> Result<(u32, u32), String> {
     Ok((texture.width(), texture.height()))
 }
 
-/// Shared decode logic: take raw DDS/TEX bytes and produce a base64-encoded PNG.
-fn decode_texture_bytes_impl(data: &[u8]) -> Result<DecodedImage, String> {
+/// Shared decode logic: take raw DDS/TEX bytes and produce a PNG. Without
+/// `blobs`, or when the PNG is small, it is returned base64-encoded inline.
+fn decode_texture_bytes_impl(data: &[u8], blobs: Option<&BlobStore>) -> Result<DecodedImage, String> {
     if data.len() < 4 {
         return Err("Data too small to be a valid texture".to_string());
     }
@@ -326,26 +303,41 @@ fn decode_texture_bytes_impl(data: &[u8]) -> Result<DecodedImage, String> {
             .map_err(|e| format!("Failed to encode PNG: {}", e))?;
     }
 
+    let (data, blob) = match blobs {
+        Some(blobs) if png_data.len() > BLOB_THRESHOLD => (String::new(), Some(blobs.insert(png_data, "image/png"))),
+        _ => (STANDARD.encode(&png_data), None),
+    };
+
     Ok(DecodedImage {
-        data: STANDARD.encode(&png_data),
+        data,
+        blob,
         width,
         height,
         format: format.to_string(),
     })
 }
 
-/// Decode a DDS or TEX texture file to base64-encoded PNG
+/// Decode a DDS or TEX texture file to base64-encoded PNG, always inline.
+/// Used where the PNG is consumed on the backend, e.g. mesh material previews.
+pub async fn decode_texture_file(path: String) -> Result<DecodedImage, CommandError> {
+    let data = fs::read(&path)
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read texture file: {}", e)))?;
+    decode_texture_bytes_impl(&data, None).map_err(CommandError::from)
+}
+
+/// Decode a DDS or TEX texture file to PNG
 ///
 /// # Arguments
 /// * `path` - Path to the texture file (DDS or TEX)
 ///
 /// # Returns
-/// * `Ok(DecodedImage)` - Base64 PNG data with dimensions
-/// * `Err(String)` - Error message
+/// * `Ok(DecodedImage)` - Base64 PNG data (or a blob token when large) with dimensions
+/// * `Err(CommandError)` - Error message
 #[tauri::command]
-pub async fn decode_dds_to_png(path: String) -> Result<DecodedImage, String> {
-    let data = fs::read(&path).map_err(|e| format!("Failed to read texture file: {}", e))?;
-    decode_texture_bytes_impl(&data)
+pub async fn decode_dds_to_png(path: String, blobs: State<'_, BlobStore>) -> Result<DecodedImage, CommandError> {
+    let data = fs::read(&path)
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read texture file: {}", e)))?;
+    decode_texture_bytes_impl(&data, Some(&blobs)).map_err(CommandError::from)
 }
 
 /// Decode raw DDS/TEX bytes (already in memory) to base64-encoded PNG.
@@ -356,11 +348,11 @@ pub async fn decode_dds_to_png(path: String) -> Result<DecodedImage, String> {
 /// * `data` - Raw decompressed DDS or TEX bytes
 ///
 /// # Returns
-/// * `Ok(DecodedImage)` - Base64 PNG data with width/height
-/// * `Err(String)` - Error message
+/// * `Ok(DecodedImage)` - Base64 PNG data (or a blob token when large) with width/height
+/// * `Err(CommandError)` - Error message
 #[tauri::command]
-pub async fn decode_bytes_to_png(data: Vec<u8>) -> Result<DecodedImage, String> {
-    decode_texture_bytes_impl(&data)
+pub async fn decode_bytes_to_png(data: Vec<u8>, blobs: State<'_, BlobStore>) -> Result<DecodedImage, CommandError> {
+    decode_texture_bytes_impl(&data, Some(&blobs)).map_err(CommandError::from)
 }
 
 
@@ -372,16 +364,16 @@ pub async fn decode_bytes_to_png(data: Vec<u8>) -> Result<DecodedImage, String>
 ///
 /// # Returns
 /// * `Ok(String)` - File content as string
-/// * `Err(String)` - Error message
+/// * `Err(CommandError)` - Error message
 #[tauri::command]
-pub async fn read_text_file(path: String) -> Result<String, String> {
+pub async fn read_text_file(path: String) -> Result<String, CommandError> {
     let path = Path::new(&path);
 
     if !path.exists() {
-        return Err(format!("File not found: {}", path.display()));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("File not found: {}", path.display())));
     }
 
-    fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))
+    fs::read_to_string(path).map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to read file: {}", e)))
 }
 
 /// Recolor a single texture file (DDS or TEX)
@@ -391,8 +383,9 @@ pub async fn recolor_image(
     hue: f32,
     saturation: f32,
     brightness: f32,
-) -> Result<(), String> {
-    recolor_single_file(&path, hue, saturation, brightness).await
+) -> Result<(), CommandError> {
+    ensure_path_writable(Path::new(&path))?;
+    recolor_single_file(&path, hue, saturation, brightness).await.map_err(CommandError::from)
 }
 
 /// Helper to recolor a single file
@@ -485,11 +478,12 @@ pub async fn recolor_folder(
     saturation: f32,
     brightness: f32,
     skip_distortion: Option<bool>,
-) -> Result<RecolorFolderResult, String> {
+) -> Result<RecolorFolderResult, CommandError> {
     let root = PathBuf::from(&path);
     if !root.exists() || !root.is_dir() {
-        return Err("Invalid folder path".into());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Invalid folder path"));
     }
+    ensure_path_writable(&root)?;
 
     let should_skip_distortion = skip_distortion.unwrap_or(true);
     let mut processed = 0;
@@ -528,8 +522,9 @@ pub async fn colorize_image(
     path: String,
     target_hue: f32,
     preserve_saturation: bool,
-) -> Result<(), String> {
-    colorize_single_file(&path, target_hue, preserve_saturation).await
+) -> Result<(), CommandError> {
+    ensure_path_writable(Path::new(&path))?;
+    colorize_single_file(&path, target_hue, preserve_saturation).await.map_err(CommandError::from)
 }
 
 /// Helper to colorize a single file
@@ -620,11 +615,12 @@ pub async fn colorize_folder(
     target_hue: f32,
     preserve_saturation: bool,
     skip_distortion: Option<bool>,
-) -> Result<RecolorFolderResult, String> {
+) -> Result<RecolorFolderResult, CommandError> {
     let root = PathBuf::from(&path);
     if !root.exists() || !root.is_dir() {
-        return Err("Invalid folder path".into());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "Invalid folder path"));
     }
+    ensure_path_writable(&root)?;
 
     let should_skip_distortion = skip_distortion.unwrap_or(true);
     let mut processed = 0;
@@ -656,3 +652,71 @@ pub async fn colorize_folder(
 
     Ok(RecolorFolderResult { processed, failed })
 }
+
+// =============================================================================
+// String Tables
+// =============================================================================
+
+/// Read a `.stringtable` (RST) file, naming the keys found in the
+/// downloaded `hashes.rst.txt`
+///
+/// # Arguments
+/// * `path` - Path to the string table
+///
+/// # Returns
+/// * `Ok(Vec<StringTableEntry>)` - Entries in file order
+/// * `Err(CommandError)` - If the file can't be read or isn't a string table
+#[tauri::command]
+pub async fn read_stringtable(
+    path: String,
+    hashtable: State<'_, HashtableState>,
+) -> Result<Vec<StringTableEntry>, CommandError> {
+    let hash_dir = hashtable.hash_dir();
+    tokio::task::spawn_blocking(move || -> error::Result<Vec<StringTableEntry>> {
+        let table = StringTable::read(Path::new(&path))?;
+        let keys = hash_dir.map(|dir| load_rst_keys(&dir, table.version)).unwrap_or_default();
+        Ok(table.resolved_entries(&keys))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Set one entry of a `.stringtable` file, adding it if the key is new
+///
+/// # Arguments
+/// * `path` - Path to the string table
+/// * `key` - Key name (e.g. "game_character_skin_displayname_Ahri_1") or `{hash}`
+/// * `value` - New text
+///
+/// # Returns
+/// * `Ok(StringTableEntry)` - The entry as written
+/// * `Err(CommandError)` - If the file can't be read, parsed or written
+#[tauri::command]
+pub async fn set_stringtable_entry(
+    path: String,
+    key: String,
+    value: String,
+    undo: State<'_, UndoJournal>,
+) -> Result<StringTableEntry, CommandError> {
+    let journal = undo.inner().clone();
+    tokio::task::spawn_blocking(move || -> error::Result<StringTableEntry> {
+        let path = PathBuf::from(&path);
+        ensure_path_writable(&path)?;
+        let mut table = StringTable::read(&path)?;
+        let hash = table.key_hash(&key)?;
+
+        let snapshot = FileSnapshot::capture(&[&path])?;
+        table.set(hash, value.clone());
+        table.write(&path)?;
+        if let Some(project) = find_project_root(&path) {
+            journal.push(&project, format!("Edit string {}", key), snapshot);
+        }
+
+        let key = (!key.starts_with('{')).then_some(key);
+        Ok(StringTableEntry { hash: format!("{:010x}", hash), key, value })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
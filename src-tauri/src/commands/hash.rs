@@ -1,103 +1,637 @@
-use crate::core::hash::{download_hashes as core_download_hashes, DownloadStats};
+use crate::core::hash::{
+    add_hash_mapping as core_add_hash_mapping, download_hashes as core_download_hashes, fallback_hash_dir,
+    has_hash_lists, migrate_hash_dir, HashDirMigration, import_hash_file as core_import_hash_file, user_hash_count, watch_hash_dir, DownloadStats, HashCategory, HashDirWatcher,
+    is_hash_file, is_newer_version, record_after_download, recorded_game_version, HashDownloadProgress, HashImportResult, HashMapping, HashSource, USER_HASH_FILE,
+};
 use crate::core::hash::downloader::get_ritoshark_hash_dir;
-use crate::state::HashtableState;
+use crate::core::bin::{bin_hash_counts, is_bin_hash_file, reload_bin_hashes, set_bin_hash_dir, BinHashCounts};
+use crate::core::hash::Hashtable;
+use crate::core::league::detect_game_version;
+use crate::core::settings::AppSettings;
+use crate::core::validation::engine::compute_path_hash as xxh64_path_hash;
+use crate::core::operation::{OperationKind, OperationRegistry};
+use crate::error::{CommandError, Error, ErrorCode};
+use crate::state::{HashReload, HashtableLoadState, HashtableState, SettingsState};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tracing::Instrument;
+
+/// The directory chosen at startup (settings override or RitoShark), or the
+/// RitoShark directory if setup hasn't run
+fn resolve_hash_dir(state: &HashtableState) -> Result<PathBuf, CommandError> {
+    match state.hash_dir() {
+        Some(dir) => Ok(dir),
+        None => get_ritoshark_hash_dir()
+            .map_err(|e| format!("Failed to get hash directory: {}", e).into()),
+    }
+}
+
+/// Emit `hash-download-progress` for a file being downloaded; the startup
+/// update has no operation
+pub fn emit_download_progress(app: &AppHandle, operation_id: Option<&str>, progress: &HashDownloadProgress) {
+    let _ = app.emit("hash-download-progress", serde_json::json!({
+        "status": "downloading",
+        "operation_id": operation_id,
+        "file": progress.file,
+        "file_index": progress.file_index,
+        "file_count": progress.file_count,
+        "downloaded": progress.downloaded,
+        "total": progress.total
+    }));
+}
+
+/// Payload of `hashes-updated`, emitted after the watcher merged lists that
+/// changed on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashesUpdated {
+    pub files: Vec<String>,
+    pub previous_count: usize,
+    pub loaded_count: usize,
+    pub delta: i64,
+    /// Hashes added, removed or resolving to another path
+    pub changed: usize,
+}
+
+/// Payload of `hashtable-ready`, emitted whenever a full load of the
+/// hashtable finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashtableReady {
+    pub loaded_count: usize,
+}
+
+/// Emit `hashtable-ready` after every full load, so the frontend can drop
+/// its loading indicator and retry commands that failed with
+/// `HashtableNotReady`
+pub fn emit_hashtable_ready(app: AppHandle, state: &HashtableState) {
+    state.on_ready(move |loaded_count| {
+        let _ = app.emit("hashtable-ready", HashtableReady { loaded_count });
+    });
+}
+
+/// Emit `hashtable-load-progress` every `PROGRESS_INTERVAL` entries while
+/// the hashtable loads, so the frontend can show how far it is
+pub fn emit_hashtable_load_progress(app: AppHandle, state: &HashtableState) {
+    state.on_progress(move |progress| {
+        let _ = app.emit("hashtable-load-progress", progress);
+    });
+}
+
+/// Watch the hash directory and hot-reload lists other tools update while
+/// Flint is open. Returns `None` (after logging) if the directory can't be
+/// watched; hashes then refresh on restart or `reload_hashes` as before.
+pub fn start_hash_watcher(app: AppHandle, state: HashtableState) -> Option<HashDirWatcher> {
+    let hash_dir = state.hash_dir()?;
+    let reloads = state.clone();
+    let watcher = watch_hash_dir(&hash_dir, move |files, changed_at| {
+        if files.iter().any(|f| is_bin_hash_file(f)) {
+            reload_bin_hashes();
+        }
+        let Some(HashReload { previous_count, loaded_count, changed }) = reloads.reload_files(&files, changed_at) else {
+            return;
+        };
+        let _ = app.emit("hashes-updated", HashesUpdated {
+            files: files
+                .iter()
+                .filter_map(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .collect(),
+            previous_count,
+            loaded_count,
+            delta: loaded_count as i64 - previous_count as i64,
+            changed,
+        });
+    });
+
+    match watcher {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            tracing::warn!("Hash directory won't hot-reload: {}", e);
+            None
+        }
+    }
+}
 
 /// Status information about the loaded hashtable
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HashStatus {
+    /// Hash loading is deferred until a command needs it
+    pub state: HashtableLoadState,
     pub loaded_count: usize,
     pub last_updated: Option<String>,
+    /// Entries in the user list, which downloads never overwrite
+    pub user_count: usize,
+    /// The directory hashes load from
+    pub hash_dir: String,
+    /// Where `hash_dir` came from: "settings", "ritoshark" or "fallback"
+    /// (the app data directory when there is no RitoShark directory)
+    pub hash_dir_source: String,
+    /// The RitoShark directory, when it can be used but hashes still load
+    /// from the fallback; `migrate_hash_directory` moves them over
+    pub preferred_hash_dir: Option<String>,
+    /// Hash lists in `hash_dir`, by name
+    pub files: Vec<HashFileStatus>,
+    /// The CommunityDragon lists among `files` by category
+    pub categories: Vec<HashCategoryStatus>,
+    /// Version of the configured League install
+    pub game_version: Option<String>,
+    /// Game version the lists were last refreshed against
+    pub hashes_game_version: Option<String>,
+    /// The install is newer than the lists; a forced download may help
+    pub possibly_stale: bool,
+    /// Entries per list in the BIN name cache; `None` until a BIN is converted
+    pub bin_counts: Option<BinHashCounts>,
+}
+
+/// One hash list in the hash directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashFileStatus {
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<String>,
+    /// Entries parsed from it; `None` until the table loaded it
+    pub entries: Option<usize>,
+    /// Malformed lines skipped; `None` until the table loaded it
+    pub rejected: Option<usize>,
+    /// 1-based numbers of the first malformed lines
+    pub rejected_lines: Vec<usize>,
+    /// Time the table took to parse it; `None` until the table loaded it
+    pub duration_ms: Option<u64>,
+    /// Why the loaded table couldn't read it
+    pub error: Option<String>,
+}
+
+/// The lists of one category in the hash directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashCategoryStatus {
+    pub category: HashCategory,
+    /// Fetched by `download_hashes`
+    pub enabled: bool,
+    /// Its lists in the hash directory
+    pub files: Vec<String>,
+    /// Entries the loaded table parsed from them; `None` until it is loaded
+    pub entries: Option<usize>,
+}
+
+/// ISO 8601 timestamp of a file time
+fn format_time(time: std::time::SystemTime) -> Option<String> {
+    let secs = time.duration_since(std::time::SystemTime::UNIX_EPOCH).ok()?.as_secs();
+    let datetime = chrono::DateTime::from_timestamp(secs as i64, 0).unwrap_or_default();
+    Some(datetime.format("%Y-%m-%dT%H:%M:%SZ").to_string())
 }
 
-/// Downloads hash files from CommunityDragon repository
+/// Hash lists in `hash_dir` with their on-disk size and time, and what the
+/// loaded table (if any) parsed from them
+fn hash_file_status(hash_dir: &std::path::Path, hashtable: Option<&Hashtable>) -> Vec<HashFileStatus> {
+    let Ok(entries) = std::fs::read_dir(hash_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<HashFileStatus> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| is_hash_file(&e.path()))
+        .filter_map(|e| {
+            let metadata = e.metadata().ok().filter(|m| m.is_file())?;
+            let name = e.file_name().to_string_lossy().to_string();
+            let stats = hashtable.and_then(|ht| ht.file_stats().iter().find(|f| f.name == name));
+            Some(HashFileStatus {
+                size: metadata.len(),
+                modified: metadata.modified().ok().and_then(format_time),
+                entries: stats.map(|s| s.entries),
+                rejected: stats.map(|s| s.rejected),
+                rejected_lines: stats.map(|s| s.rejected_lines.clone()).unwrap_or_default(),
+                duration_ms: stats.map(|s| s.duration_ms),
+                error: stats.and_then(|s| s.error.clone()),
+                name,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    files
+}
+
+/// `files` grouped by category, with entry totals once the table is loaded
+fn hash_category_status(files: &[HashFileStatus], enabled: &[HashCategory], loaded: bool) -> Vec<HashCategoryStatus> {
+    HashCategory::ALL
+        .into_iter()
+        .map(|category| {
+            let lists: Vec<&HashFileStatus> =
+                files.iter().filter(|f| HashCategory::of_file(&f.name) == Some(category)).collect();
+            HashCategoryStatus {
+                category,
+                enabled: enabled.contains(&category),
+                files: lists.iter().map(|f| f.name.clone()).collect(),
+                entries: loaded.then(|| lists.iter().filter_map(|f| f.entries).sum()),
+            }
+        })
+        .collect()
+}
+
+/// Downloads hash files from CommunityDragon repository, or from the
+/// configured mirrors
 ///
 /// # Arguments
-/// * `force` - If true, downloads all files regardless of age
+/// * `force` - If true, downloads all files unconditionally
+/// * `sources` - Mirrors to use from now on, saved to settings; an empty
+///   list goes back to CommunityDragon, `None` keeps the saved ones
+/// * `categories` - CommunityDragon lists to fetch from now on, saved to
+///   settings; `None` keeps the saved ones
 ///
 /// # Returns
-/// * `Result<DownloadStats, String>` - Statistics about the download operation
+/// * `Result<DownloadStats, CommandError>` - Statistics about the download operation
 #[tauri::command]
-pub async fn download_hashes(force: bool) -> Result<DownloadStats, String> {
-    // Get the RitoShark hash directory
-    let hash_dir = get_ritoshark_hash_dir()
-        .map_err(|e| format!("Failed to get hash directory: {}", e))?;
+pub async fn download_hashes(
+    app: AppHandle,
+    force: bool,
+    sources: Option<Vec<HashSource>>,
+    categories: Option<Vec<HashCategory>>,
+    state: State<'_, HashtableState>,
+    operations: State<'_, OperationRegistry>,
+    settings: State<'_, SettingsState>,
+) -> Result<DownloadStats, CommandError> {
+    settings.ensure_online()?;
+    let hash_dir = resolve_hash_dir(&state)?;
+    if let Some(sources) = sources {
+        if let Some(updated) = settings.update(&serde_json::json!({ "hash_sources": sources }))? {
+            let _ = app.emit("settings-changed", &updated);
+        }
+    }
+    if let Some(categories) = categories {
+        if let Some(updated) = settings.update(&serde_json::json!({ "hash_categories": categories }))? {
+            let _ = app.emit("settings-changed", &updated);
+        }
+    }
+    let AppSettings { hash_sources: sources, hash_categories: categories, league_path, .. } = settings.get();
+
+    let operation = operations.start(OperationKind::Download, None);
+    let cancel = operation.token();
+    let _ = app.emit("hash-download-progress", serde_json::json!({
+        "status": "starting",
+        "operation_id": operation.id()
+    }));
     
     // Download hashes to the directory
-    let stats = core_download_hashes(&hash_dir, force)
-        .await
-        .map_err(|e| format!("Failed to download hashes: {}", e))?;
-    
-    Ok(stats)
+    let operation_id = operation.id().to_string();
+    let progress = |progress: &HashDownloadProgress| emit_download_progress(&app, Some(&operation_id), progress);
+    let result = core_download_hashes(&hash_dir, force, &sources, &categories, Some(&cancel), progress)
+        .instrument(operation.span())
+        .await;
+    match &result {
+        Ok(stats) if stats.cancelled => operation.finish::<(), _>(&Err(Error::Cancelled)),
+        _ => operation.finish(&result),
+    }
+    if let Ok(stats) = &result {
+        record_after_download(&hash_dir, league_path.as_deref(), stats);
+        if stats.downloaded > 0 {
+            let _ = tokio::task::spawn_blocking(reload_bin_hashes).await;
+        }
+    }
+
+    result.map_err(|e| match e {
+        Error::Cancelled => e.into(),
+        e => CommandError::new(ErrorCode::Network, format!("Failed to download hashes: {}", e)),
+    })
+}
+
+/// Cancels running hash downloads, the startup update included
+///
+/// Files finished before stay; the one in flight is discarded. The
+/// download returns its stats with `cancelled` set.
+///
+/// # Returns
+/// * `Result<bool, CommandError>` - Whether a download was running
+#[tauri::command]
+pub async fn cancel_hash_download(operations: State<'_, OperationRegistry>) -> Result<bool, CommandError> {
+    Ok(operations.cancel_kind(OperationKind::Download) > 0)
 }
 
 /// Returns information about the currently loaded hashtable
 ///
+/// Lists each hash file with its size and time, and once the table is
+/// loaded, how many entries it parsed and how many lines it rejected.
+///
 /// # Arguments
 /// * `state` - The managed HashtableState
 ///
 /// # Returns
-/// * `Result<HashStatus, String>` - Status information about the hashtable
+/// * `Result<HashStatus, CommandError>` - Status information about the hashtable
 #[tauri::command]
-pub async fn get_hash_status(state: State<'_, HashtableState>) -> Result<HashStatus, String> {
+pub async fn get_hash_status(
+    state: State<'_, HashtableState>,
+    settings: State<'_, SettingsState>,
+) -> Result<HashStatus, CommandError> {
     let loaded_count = state.len();
     
     // Try to get last modified time of the hash directory
-    let hash_dir = get_ritoshark_hash_dir()
-        .map_err(|e| format!("Failed to get hash directory: {}", e))?;
+    let hash_dir = resolve_hash_dir(&state)?;
     
     let last_updated = if hash_dir.exists() {
         std::fs::metadata(&hash_dir)
             .ok()
             .and_then(|metadata| metadata.modified().ok())
-            .and_then(|time| {
-                use std::time::SystemTime;
-                time.duration_since(SystemTime::UNIX_EPOCH)
-                    .ok()
-                    .map(|duration| {
-                        // Format as ISO 8601 timestamp
-                        let secs = duration.as_secs();
-                        let datetime = chrono::DateTime::from_timestamp(secs as i64, 0)
-                            .unwrap_or_default();
-                        datetime.format("%Y-%m-%dT%H:%M:%SZ").to_string()
-                    })
-            })
+            .and_then(format_time)
     } else {
         None
     };
+
+    let settings = settings.get();
+    let hash_dir_source = if settings.hash_dir.as_ref().is_some_and(|dir| *dir == hash_dir) {
+        "settings"
+    } else if get_ritoshark_hash_dir().is_ok_and(|dir| dir == hash_dir) {
+        "ritoshark"
+    } else {
+        "fallback"
+    };
+    let preferred_hash_dir = match &settings.hash_dir {
+        Some(_) => None,
+        None => get_ritoshark_hash_dir().ok().filter(|dir| *dir != hash_dir),
+    };
+    let hashtable = state.peek();
+    let game_version = settings.league_path.as_deref().and_then(detect_game_version);
+    let hashes_game_version = recorded_game_version(&hash_dir);
+    let possibly_stale = game_version
+        .as_deref()
+        .zip(hashes_game_version.as_deref())
+        .is_some_and(|(installed, recorded)| is_newer_version(installed, recorded));
+    let files = hash_file_status(&hash_dir, hashtable.as_deref());
+    let categories = hash_category_status(&files, &settings.hash_categories, hashtable.is_some());
     
     Ok(HashStatus {
+        state: state.load_state(),
         loaded_count,
         last_updated,
+        user_count: user_hash_count(&hash_dir),
+        hash_dir_source: hash_dir_source.to_string(),
+        preferred_hash_dir: preferred_hash_dir.map(|dir| dir.to_string_lossy().to_string()),
+        files,
+        categories,
+        hash_dir: hash_dir.to_string_lossy().to_string(),
+        game_version,
+        hashes_game_version,
+        possibly_stale,
+        bin_counts: bin_hash_counts(),
     })
 }
 
 /// Reloads the hashtable from disk
 ///
+/// Without `files`, builds a fresh table from every list and swaps it in;
+/// operations already holding the old table finish against that snapshot.
+/// The BIN name cache reloads as well.
+///
+/// With `files`, only those lists are re-read and spliced into the loaded
+/// table, which is much faster when a small list changed. The BIN name
+/// cache reloads only if one of them is a BIN list. A table that isn't
+/// loaded yet gets a full load.
+///
 /// # Arguments
+/// * `files` - Hash lists to re-read: names in the hash directory, or paths
 /// * `state` - The managed HashtableState
 ///
 /// # Returns
-/// * `Result<(), String>` - Ok if reload succeeded, error message otherwise
+/// * `Result<HashReload, CommandError>` - Entry counts and how many hashes changed
 #[tauri::command]
-pub async fn reload_hashes(state: State<'_, HashtableState>) -> Result<(), String> {
-    // Get the hash directory
-    let hash_dir = get_ritoshark_hash_dir()
-        .map_err(|e| format!("Failed to get hash directory: {}", e))?;
-    
-    // Ensure the directory is set (this doesn't load, just sets the path)
-    state.set_hash_dir(hash_dir);
-    
-    // Trigger a lazy load by calling get_hashtable
-    // Note: With OnceLock, the hashtable is only loaded once - subsequent reloads
-    // will return the cached version. For a true reload, the app would need to restart.
-    if state.get_hashtable().is_some() {
-        tracing::info!("Hashtable is loaded with {} entries", state.len());
-        Ok(())
+pub async fn reload_hashes(
+    files: Option<Vec<String>>,
+    state: State<'_, HashtableState>,
+) -> Result<HashReload, CommandError> {
+    let hash_dir = resolve_hash_dir(&state)?;
+    set_bin_hash_dir(hash_dir.clone());
+    state.set_hash_dir(hash_dir.clone());
+
+    let files: Option<Vec<PathBuf>> = files.map(|files| files.iter().map(|f| hash_dir.join(f)).collect());
+    if let Some(file) = files.iter().flatten().find(|f| !is_hash_file(f)) {
+        return Err(Error::InvalidInput(format!("{} is not a hash list", file.display())).into());
+    }
+
+    let state = state.inner().clone();
+    let reload = tokio::task::spawn_blocking(move || match files {
+        Some(files) => {
+            if files.iter().any(|f| is_bin_hash_file(f)) {
+                reload_bin_hashes();
+            }
+            state.reload_files(&files, std::time::Instant::now()).or_else(|| full_reload(&state))
+        }
+        None => {
+            reload_bin_hashes();
+            full_reload(&state)
+        }
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+
+    match reload {
+        Some(reload) => {
+            tracing::info!("Hashtable reloaded with {} entries, {} changed", reload.loaded_count, reload.changed);
+            Ok(reload)
+        }
+        None => Err(Error::HashtableNotReady.into()),
+    }
+}
+
+/// Reload every list, comparing with the table it replaces
+fn full_reload(state: &HashtableState) -> Option<HashReload> {
+    let previous = state.peek();
+    let loaded_count = state.reload()?;
+    let current = state.peek()?;
+    Some(HashReload {
+        previous_count: previous.as_ref().map_or(0, |p| p.len()),
+        loaded_count,
+        changed: previous.map_or(loaded_count, |p| p.count_changes(&current)),
+    })
+}
+
+/// Move hashes from the fallback directory (app data) to the RitoShark
+/// directory, at startup or when asked
+///
+/// Lists newer than the RitoShark copy replace it and the user list is
+/// merged; the fallback directory is renamed so it isn't used again.
+/// Returns `None` when the fallback has no lists.
+pub fn migrate_fallback_hashes(fallback: &Path, preferred: &Path) -> Option<HashDirMigration> {
+    if !has_hash_lists(fallback) {
+        return None;
+    }
+    match migrate_hash_dir(fallback, preferred) {
+        Ok(migration) => Some(migration),
+        Err(e) => {
+            tracing::warn!("Failed to migrate hashes from {}: {}", fallback.display(), e);
+            None
+        }
+    }
+}
+
+/// Switch from the fallback hash directory to the RitoShark directory
+///
+/// Moves the fallback lists over (see `migrate_fallback_hashes`), points
+/// hash loading, the BIN name cache and the directory watcher at the
+/// RitoShark directory, and reloads a loaded hashtable from there.
+///
+/// # Returns
+/// * `Ok(Some(HashDirMigration))` - What was moved
+/// * `Ok(None)` - Already using the RitoShark directory with nothing left in the fallback
+/// * `Err(CommandError)` - A hash directory is configured in settings, or there is no RitoShark directory
+#[tauri::command]
+pub async fn migrate_hash_directory(
+    app: AppHandle,
+    state: State<'_, HashtableState>,
+    settings: State<'_, SettingsState>,
+) -> Result<Option<HashDirMigration>, CommandError> {
+    if let Some(dir) = settings.get().hash_dir {
+        return Err(Error::InvalidInput(format!("Hashes load from {} as configured in settings", dir.display())).into());
+    }
+    let preferred = get_ritoshark_hash_dir()?;
+    let current = resolve_hash_dir(&state)?;
+    let from = if current != preferred {
+        current
     } else {
-        Err("Failed to load hashtable".to_string())
+        // Already switched; a fallback left over from an earlier run can still be moved
+        let Ok(app_data) = app.path().app_data_dir() else {
+            return Ok(None);
+        };
+        let fallback = fallback_hash_dir(&app_data);
+        if !has_hash_lists(&fallback) {
+            return Ok(None);
+        }
+        fallback
+    };
+
+    let state = state.inner().clone();
+    let target = preferred.clone();
+    let (migration, reload) = tokio::task::spawn_blocking(move || -> crate::error::Result<_> {
+        let migration = if has_hash_lists(&from) { Some(migrate_hash_dir(&from, &target)?) } else { None };
+        state.set_hash_dir(target.clone());
+        set_bin_hash_dir(target);
+        reload_bin_hashes();
+        let reload = state.peek().and_then(|_| full_reload(&state));
+        Ok((migration, reload))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)?;
+
+    if let Some(watcher) = app.try_state::<HashDirWatcher>() {
+        if let Err(e) = watcher.switch_to(&preferred) {
+            tracing::warn!("{}", e);
+        }
     }
+    if let Some(HashReload { previous_count, loaded_count, changed }) = reload {
+        let _ = app.emit("hashes-updated", HashesUpdated {
+            files: migration.as_ref().map(|m| m.copied.clone()).unwrap_or_default(),
+            previous_count,
+            loaded_count,
+            delta: loaded_count as i64 - previous_count as i64,
+            changed,
+        });
+    }
+    tracing::info!("Hash directory is now {}", preferred.display());
+    Ok(migration)
+}
+
+/// Imports a local hash list into the user list in the hash directory
+///
+/// Lines must be "hex<TAB>path" or "hex path"; malformed ones are counted
+/// and skipped. User entries win over downloaded lists for the same hash.
+/// A loaded hashtable re-reads just the user list so the entries resolve
+/// right away.
+///
+/// # Arguments
+/// * `path` - The text file to import
+///
+/// # Returns
+/// * `Result<HashImportResult, CommandError>` - How many entries were added, merged and rejected
+#[tauri::command]
+pub async fn import_hash_file(path: String, state: State<'_, HashtableState>) -> Result<HashImportResult, CommandError> {
+    tracing::info!("Frontend requested hash import from {}", path);
+    let hash_dir = resolve_hash_dir(&state)?;
+    let state = state.inner().clone();
+
+    tokio::task::spawn_blocking(move || -> crate::error::Result<HashImportResult> {
+        let result = core_import_hash_file(&PathBuf::from(&path), &hash_dir)?;
+        state.reload_files(&[hash_dir.join(USER_HASH_FILE)], std::time::Instant::now());
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Records one hash -> path mapping in the user list
+///
+/// The user list outlives hash downloads and wins over downloaded lists for
+/// the same hash. A loaded hashtable merges just that file, without a full
+/// reload, so the path resolves right away.
+///
+/// # Arguments
+/// * `hash` - The hash in hex: 16 digits for WAD paths, 8 for BIN names
+/// * `path` - What it hashes from
+///
+/// # Returns
+/// * `Result<HashMapping, CommandError>` - The mapping, and whether the path verifiably hashes to it
+#[tauri::command]
+pub async fn add_hash_mapping(
+    hash: String,
+    path: String,
+    state: State<'_, HashtableState>,
+) -> Result<HashMapping, CommandError> {
+    let hash_dir = resolve_hash_dir(&state)?;
+    let state = state.inner().clone();
+
+    tokio::task::spawn_blocking(move || -> crate::error::Result<HashMapping> {
+        let mapping = core_add_hash_mapping(&hash_dir, &hash, &path)?;
+        state.reload_files(&[hash_dir.join(USER_HASH_FILE)], std::time::Instant::now());
+        Ok(mapping)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// A path and its WAD path hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathHash {
+    /// The path as hashed: lowercase with forward slashes
+    pub path: String,
+    /// Decimal, as a string since it doesn't fit a JavaScript number
+    pub hash: String,
+    /// 16 hex digits, as in WAD chunk listings
+    pub hex: String,
+    /// Whether the hashtable has this hash; `None` when it wasn't checked
+    pub known: Option<bool>,
+    /// The path the hashtable has for this hash
+    pub known_path: Option<String>,
+}
+
+fn path_hash(path: &str, hashtable: Option<&Hashtable>) -> PathHash {
+    let hash = xxh64_path_hash(path);
+    let known_path = hashtable.and_then(|h| h.get(hash)).map(str::to_string);
+    PathHash {
+        path: path.to_lowercase().replace('\\', "/"),
+        hash: hash.to_string(),
+        hex: format!("{:016x}", hash),
+        known: hashtable.map(|_| known_path.is_some()),
+        known_path,
+    }
+}
+
+/// Computes the xxhash64 WAD path hash of each path
+///
+/// # Arguments
+/// * `paths` - Game paths; case and slash direction don't matter
+/// * `check_known` - Also look each hash up in the hashtable, loading it if needed
+///
+/// # Returns
+/// * `Result<Vec<PathHash>, CommandError>` - One entry per path, in order
+#[tauri::command]
+pub async fn compute_path_hash(
+    paths: Vec<String>,
+    check_known: Option<bool>,
+    state: State<'_, HashtableState>,
+) -> Result<Vec<PathHash>, CommandError> {
+    let hashtable = if check_known.unwrap_or(false) {
+        Some(state.ensure_loaded().await.ok_or(Error::HashtableNotReady)?)
+    } else {
+        None
+    };
+    Ok(paths.iter().map(|path| path_hash(path, hashtable.as_deref())).collect())
 }
 
 #[cfg(test)]
@@ -107,22 +641,82 @@ mod tests {
     #[test]
     fn test_hash_status_serialization() {
         let status = HashStatus {
+            state: HashtableLoadState::Ready,
             loaded_count: 100,
             last_updated: Some("2024-01-01T00:00:00Z".to_string()),
+            user_count: 0,
+            hash_dir: "C:/hashes".to_string(),
+            hash_dir_source: "ritoshark".to_string(),
+            preferred_hash_dir: None,
+            files: Vec::new(),
+            categories: Vec::new(),
+            game_version: Some("14.10.590.1".to_string()),
+            hashes_game_version: Some("14.9.586.9467".to_string()),
+            possibly_stale: true,
+            bin_counts: None,
         };
 
         let json = serde_json::to_string(&status).unwrap();
+        assert!(json.contains(r#""state":"ready""#));
         assert!(json.contains("loaded_count"));
         assert!(json.contains("100"));
         assert!(json.contains("last_updated"));
     }
 
+    #[test]
+    fn test_hash_category_status_groups_lists() {
+        let file = |name: &str, entries| HashFileStatus {
+            name: name.to_string(),
+            size: 0,
+            modified: None,
+            entries,
+            rejected: entries.map(|_| 0),
+            rejected_lines: Vec::new(),
+            duration_ms: entries.map(|_| 0),
+            error: None,
+        };
+        let files = [
+            file("hashes.game.txt", Some(10)),
+            file("hashes.binfields.txt", Some(3)),
+            file("hashes.bintypes.txt", Some(5)),
+            file("hashes.user.txt", Some(1)),
+        ];
+
+        let categories = hash_category_status(&files, &[HashCategory::Game, HashCategory::Bin], true);
+        let summary: Vec<_> = categories.iter().map(|c| (c.category, c.enabled, c.files.len(), c.entries)).collect();
+        assert_eq!(
+            summary,
+            [
+                (HashCategory::Game, true, 1, Some(10)),
+                (HashCategory::Lcu, false, 0, Some(0)),
+                (HashCategory::Bin, true, 2, Some(8)),
+                (HashCategory::Rst, false, 0, Some(0)),
+            ]
+        );
+        assert!(hash_category_status(&files, &[], false).iter().all(|c| c.entries.is_none()));
+    }
+
+    #[test]
+    fn test_path_hash_normalizes() {
+        let hash = path_hash("DATA\\Characters\\Ahri\\Ahri.bin", None);
+        assert_eq!(hash.path, "data/characters/ahri/ahri.bin");
+        assert_eq!(hash.hex.len(), 16);
+        assert_eq!(u64::from_str_radix(&hash.hex, 16).unwrap(), hash.hash.parse::<u64>().unwrap());
+        assert_eq!(hash.hex, path_hash("data/characters/ahri/ahri.bin", None).hex);
+        assert!(hash.known.is_none());
+        assert_eq!(path_hash("a.bin", Some(&Hashtable::empty())).known, Some(false));
+    }
+
     #[test]
     fn test_download_stats_serialization() {
         let stats = DownloadStats {
             downloaded: 5,
             skipped: 2,
+            not_modified: 1,
+            age_skipped: 1,
             errors: 1,
+            rejected: Vec::new(),
+            cancelled: false,
         };
 
         let json = serde_json::to_string(&stats).unwrap();
@@ -139,6 +733,47 @@ mod tests {
         let state = HashtableState::new();
         assert_eq!(state.len(), 0);
         assert!(state.is_empty());
+        assert_eq!(state.load_state(), HashtableLoadState::NotLoaded);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_loaded_transitions_to_ready() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "0x1a2b3c4d a.bin\n").unwrap();
+
+        let state = HashtableState::new();
+        // Without a hash directory there is nothing to load
+        assert!(state.ensure_loaded().await.is_none());
+
+        state.set_hash_dir(tmp.path().to_path_buf());
+        assert!(state.peek().is_none());
+        assert_eq!(state.load_state(), HashtableLoadState::NotLoaded);
+        assert_eq!(hash_file_status(tmp.path(), None)[0].entries, None);
+
+        let (a, b) = tokio::join!(state.ensure_loaded(), state.ensure_loaded());
+        assert!(std::sync::Arc::ptr_eq(&a.unwrap(), &b.unwrap()));
+        assert_eq!(state.load_state(), HashtableLoadState::Ready);
+        assert_eq!(state.len(), 1);
+        let files = hash_file_status(tmp.path(), state.peek().as_deref());
+        assert_eq!((files[0].name.as_str(), files[0].size, files[0].entries), ("a.txt", 17, Some(1)));
+    }
+
+    #[tokio::test]
+    async fn test_wait_ready_fails_typed_and_notifies() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "0x1a2b3c4d a.bin\n").unwrap();
+
+        let state = HashtableState::new();
+        let loads = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = std::sync::Arc::clone(&loads);
+        state.on_ready(move |count| seen.lock().unwrap().push(count));
+
+        let wait = std::time::Duration::from_secs(5);
+        assert!(matches!(state.wait_ready(wait).await, Err(Error::HashtableNotReady)));
+        state.set_hash_dir(tmp.path().to_path_buf());
+        assert_eq!(state.wait_ready(wait).await.unwrap().len(), 1);
+        state.reload().unwrap();
+        assert_eq!(*loads.lock().unwrap(), [1, 1]);
     }
 
     #[test]
@@ -150,5 +785,45 @@ mod tests {
         // attempts to load (and gracefully fails on a non-existent dir).
         assert!(state.get_hashtable().is_some()); // returns empty table on failure
     }
+
+    #[test]
+    fn test_hashtable_state_reload_swaps_snapshot() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "0x1a2b3c4d old.bin\n").unwrap();
+
+        let state = HashtableState::new();
+        state.set_hash_dir(tmp.path().to_path_buf());
+        let before = state.get_hashtable().unwrap();
+        assert_eq!(before.len(), 1);
+
+        std::fs::write(tmp.path().join("b.txt"), "0x5e6f7a8b new.bin\n").unwrap();
+        assert_eq!(state.reload(), Some(2));
+
+        // Holders of the old Arc keep their snapshot; new callers see the reload
+        assert_eq!(before.len(), 1);
+        assert_eq!(state.get_hashtable().unwrap().len(), 2);
+        assert_eq!(state.len(), 2);
+    }
+
+    #[test]
+    fn test_reload_files_skips_changes_a_full_load_saw() {
+        let tmp = tempfile::tempdir().unwrap();
+        let changed = vec![tmp.path().join("b.txt")];
+        std::fs::write(tmp.path().join("a.txt"), "0x1a2b3c4d a.bin\n").unwrap();
+
+        let state = HashtableState::new();
+        state.set_hash_dir(tmp.path().to_path_buf());
+        // Not loaded yet: the first load will read the file anyway
+        assert_eq!(state.reload_files(&changed, std::time::Instant::now()), None);
+
+        state.get_hashtable().unwrap();
+        std::fs::write(&changed[0], "0x5e6f7a8b b.bin\n").unwrap();
+        let reload = HashReload { previous_count: 1, loaded_count: 2, changed: 1 };
+        assert_eq!(state.reload_files(&changed, std::time::Instant::now()), Some(reload));
+
+        let before_reload = std::time::Instant::now();
+        state.reload().unwrap();
+        assert_eq!(state.reload_files(&changed, before_reload), None);
+    }
 }
 
@@ -2,25 +2,46 @@
 //!
 //! These commands expose league detection functionality to the frontend.
 
-use crate::core::league::{detect_league_installation, validate_league_path, LeagueInstallation};
+use crate::core::league::{
+    check_game_running as running_game_processes, resolve_league_installation, validate_league_path, LeagueInstallation,
+};
+use crate::error::CommandError;
+use crate::state::SettingsState;
+use tauri::State;
 
 /// Automatically detect League of Legends installation
 ///
-/// Searches Windows registry and common installation paths.
+/// Returns the League path from settings when it is still valid, otherwise
+/// searches Windows registry and common installation paths.
 ///
 /// # Returns
 /// * `Ok(LeagueInstallation)` - Detected installation info
-/// * `Err(String)` - Error message if detection failed
+/// * `Err(CommandError)` - Error message if detection failed
 #[tauri::command]
-pub async fn detect_league() -> Result<LeagueInstallation, String> {
+pub async fn detect_league(
+    settings: State<'_, SettingsState>,
+) -> Result<LeagueInstallation, CommandError> {
     tracing::info!("Frontend requested League detection");
-    
+
+    let configured = settings.get().league_path;
     tokio::task::spawn_blocking(move || {
-        detect_league_installation()
+        resolve_league_installation(configured.as_deref())
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?
-    .map_err(|e| e.to_string())
+    .map_err(CommandError::from)
+}
+
+/// Names of the League and Riot Client processes running, for a check
+/// before long operations on game files
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - Process names, sorted; empty when none runs
+#[tauri::command]
+pub async fn check_game_running() -> Result<Vec<String>, CommandError> {
+    tokio::task::spawn_blocking(running_game_processes)
+        .await
+        .map_err(|e| CommandError::from(format!("Task failed: {}", e)))
 }
 
 /// Validate a manually specified League path
@@ -30,9 +51,9 @@ pub async fn detect_league() -> Result<LeagueInstallation, String> {
 ///
 /// # Returns
 /// * `Ok(LeagueInstallation)` - Validated installation info
-/// * `Err(String)` - Error message if validation failed
+/// * `Err(CommandError)` - Error message if validation failed
 #[tauri::command]
-pub async fn validate_league(path: String) -> Result<LeagueInstallation, String> {
+pub async fn validate_league(path: String) -> Result<LeagueInstallation, CommandError> {
     tracing::info!("Frontend requested validation for path: {}", path);
     
     tokio::task::spawn_blocking(move || {
@@ -40,5 +61,5 @@ pub async fn validate_league(path: String) -> Result<LeagueInstallation, String>
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?
-    .map_err(|e| e.to_string())
+    .map_err(CommandError::from)
 }
@@ -0,0 +1,39 @@
+//! Tauri commands for reading captured log output
+
+use crate::core::log_capture::{log_file_path, recent_logs, LogLine};
+use crate::error::{CommandError, ErrorCode};
+
+/// Default number of lines returned by `get_recent_logs`
+const DEFAULT_LOG_LIMIT: usize = 500;
+
+/// Returns the most recent captured log lines, oldest first
+///
+/// # Arguments
+/// * `limit` - Maximum number of lines (default: 500)
+/// * `level` - Least severe level to include, e.g. "warn" (default: all)
+/// * `operation_id` - Only lines logged while this operation ran
+#[tauri::command]
+pub async fn get_recent_logs(
+    limit: Option<usize>,
+    level: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Vec<LogLine>, CommandError> {
+    let min_level = match level {
+        Some(level) => level.parse().map_err(|_| {
+            CommandError::new(ErrorCode::InvalidInput, format!("Unknown log level '{}'", level))
+        })?,
+        None => tracing::Level::TRACE,
+    };
+
+    Ok(recent_logs(
+        limit.unwrap_or(DEFAULT_LOG_LIMIT),
+        min_level,
+        operation_id.as_deref(),
+    ))
+}
+
+/// Path of the active log file, or None if file logging couldn't be set up
+#[tauri::command]
+pub async fn get_log_file_path() -> Result<Option<String>, CommandError> {
+    Ok(log_file_path().map(|p| p.to_string_lossy().to_string()))
+}
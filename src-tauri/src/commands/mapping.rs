@@ -0,0 +1,82 @@
+//! Tauri commands for moving path mappings between projects
+//!
+//! The mapping file format is described in `core::mapping`.
+
+use crate::core::mapping::PathMappings;
+use crate::core::project::ensure_writable;
+use crate::error::{self, CommandError};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Result of importing a mapping file (sent to frontend)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathMappingImport {
+    /// Mappings that were new or changed
+    pub imported: usize,
+    /// Mappings in the project afterwards
+    pub total: usize,
+    /// Imported game paths whose file isn't in the project's content
+    pub missing_files: Vec<String>,
+}
+
+/// Write a project's path mappings to a file another project or tool can import
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `output_path` - Where to write the mapping file
+///
+/// # Returns
+/// * `Ok(usize)` - Number of mappings written
+#[tauri::command]
+pub async fn export_path_mappings(
+    project_path: String,
+    output_path: String,
+) -> Result<usize, CommandError> {
+    tracing::info!("Exporting path mappings of {} to {}", project_path, output_path);
+
+    tokio::task::spawn_blocking(move || -> error::Result<usize> {
+        let mappings = PathMappings::load(&PathBuf::from(&project_path))?;
+        mappings.write(&PathBuf::from(&output_path))?;
+        Ok(mappings.len())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Merge a mapping file into a project's path mappings
+///
+/// Entries from the file replace the project's entries for the same game
+/// path. Files are not copied, so imported mappings whose file the project
+/// doesn't have are listed in `missing_files`.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `input_path` - Mapping file to import
+#[tauri::command]
+pub async fn import_path_mappings(
+    project_path: String,
+    input_path: String,
+) -> Result<PathMappingImport, CommandError> {
+    tracing::info!("Importing path mappings from {} into {}", input_path, project_path);
+
+    tokio::task::spawn_blocking(move || -> error::Result<PathMappingImport> {
+        let project_path = PathBuf::from(&project_path);
+        ensure_writable(&project_path)?;
+        let incoming = PathMappings::read(&PathBuf::from(&input_path))?;
+        let mut mappings = PathMappings::load(&project_path)?;
+        let imported = mappings.merge(&incoming);
+        if imported > 0 {
+            mappings.save(&project_path)?;
+        }
+
+        let missing_files = incoming.missing_files(&project_path.join("content").join("base"));
+        if !missing_files.is_empty() {
+            tracing::warn!("{} imported mappings point to files the project doesn't have", missing_files.len());
+        }
+        Ok(PathMappingImport { imported, total: mappings.len(), missing_files })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
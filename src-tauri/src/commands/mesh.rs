@@ -8,20 +8,21 @@ use std::collections::HashMap;
 use crate::core::mesh::skn::{parse_skn_file, SknMeshData};
 use crate::core::mesh::scb::{parse_scb_file, ScbMeshData};
 use crate::core::mesh::texture::{find_skin_bin, extract_texture_mapping, lookup_material_texture_by_name, MaterialProperties};
-use crate::commands::file::decode_dds_to_png;
+use crate::commands::file::decode_texture_file;
+use crate::error::{CommandError, ErrorCode};
 
 /// Read and parse an SCB (Static Mesh Binary) file
 /// 
 /// Returns mesh data including vertices, normals, UVs, indices, and materials
 /// for 3D rendering in the frontend.
 #[tauri::command]
-pub async fn read_scb_mesh(path: String) -> Result<ScbMeshData, String> {
+pub async fn read_scb_mesh(path: String) -> Result<ScbMeshData, CommandError> {
     tracing::debug!("Reading SCB mesh: {}", path);
     
     parse_scb_file(&path)
         .map_err(|e| {
             tracing::error!("Failed to parse SCB file {}: {}", path, e);
-            format!("Failed to parse SCB file: {}", e)
+            CommandError::new(ErrorCode::Parse, format!("Failed to parse SCB file: {}", e))
         })
 }
 
@@ -30,7 +31,7 @@ pub async fn read_scb_mesh(path: String) -> Result<ScbMeshData, String> {
 /// Returns mesh data including vertices, normals, UVs, indices, materials,
 /// and decoded textures for 3D rendering in the frontend.
 #[tauri::command]
-pub async fn read_skn_mesh(path: String) -> Result<SknMeshData, String> {
+pub async fn read_skn_mesh(path: String) -> Result<SknMeshData, CommandError> {
     tracing::info!("Reading SKN mesh: {}", path);
     
     let skn_path = Path::new(&path);
@@ -39,7 +40,7 @@ pub async fn read_skn_mesh(path: String) -> Result<SknMeshData, String> {
     let mut mesh_data = parse_skn_file(&path)
         .map_err(|e| {
             tracing::error!("Failed to parse SKN file {}: {}", path, e);
-            format!("Failed to parse SKN file: {}", e)
+            CommandError::new(ErrorCode::Parse, format!("Failed to parse SKN file: {}", e))
         })?;
     
     tracing::info!("SKN parsed successfully. Materials: {:?}", 
@@ -154,7 +155,7 @@ pub async fn read_skn_mesh(path: String) -> Result<SknMeshData, String> {
                 let load_futures: Vec<_> = texture_tasks.into_iter()
                     .map(|(path_key, resolved_path, _)| {
                         async move {
-                            match decode_dds_to_png(resolved_path.to_string_lossy().to_string()).await {
+                            match decode_texture_file(resolved_path.to_string_lossy().to_string()).await {
                                 Ok(decoded) => Some((path_key, decoded.data)),
                                 Err(e) => {
                                     tracing::warn!("Failed to decode texture {}: {}", resolved_path.display(), e);
@@ -275,7 +276,7 @@ fn resolve_texture_path(base_dir: &Path, texture_path: &str) -> Option<std::path
 pub async fn resolve_asset_path(
     asset_path: String,
     bin_path: String
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     tracing::debug!("Resolving asset path: {} relative to {}", asset_path, bin_path);
     
     let bin_path = std::path::Path::new(&bin_path);
@@ -385,7 +386,10 @@ pub async fn resolve_asset_path(
         }
     }
     
-    Err(format!("Asset not found: {} (searched from {})", asset_path, content_root.display()))
+    Err(CommandError::new(
+        ErrorCode::NotFound,
+        format!("Asset not found: {} (searched from {})", asset_path, content_root.display()),
+    ))
 }
 
 use crate::core::mesh::skl::{parse_skl_file, SklData};
@@ -395,13 +399,13 @@ use crate::core::mesh::skl::{parse_skl_file, SklData};
 /// Returns skeleton data including bone hierarchy with names, parent IDs,
 /// and local transforms for visualization and animation.
 #[tauri::command]
-pub async fn read_skl_skeleton(path: String) -> Result<SklData, String> {
+pub async fn read_skl_skeleton(path: String) -> Result<SklData, CommandError> {
     tracing::debug!("Reading SKL skeleton: {}", path);
     
     parse_skl_file(&path)
         .map_err(|e| {
             tracing::error!("Failed to parse SKL file {}: {}", path, e);
-            format!("Failed to parse SKL file: {}", e)
+            CommandError::new(ErrorCode::Parse, format!("Failed to parse SKL file: {}", e))
         })
 }
 
@@ -415,27 +419,27 @@ use crate::core::mesh::animation::{
 /// 
 /// Parses the animation BIN file to extract AtomicClipData animation paths
 #[tauri::command]
-pub async fn read_animation_list(skn_path: String) -> Result<AnimationList, String> {
+pub async fn read_animation_list(skn_path: String) -> Result<AnimationList, CommandError> {
     tracing::debug!("Reading animation list for: {}", skn_path);
     
     let skn_path = std::path::Path::new(&skn_path);
     
     // Find animation BIN file
     let bin_path = find_animation_bin(skn_path)
-        .ok_or_else(|| "Animation BIN file not found".to_string())?;
+        .ok_or_else(|| CommandError::new(ErrorCode::NotFound, "Animation BIN file not found"))?;
     
     tracing::debug!("Found animation BIN: {}", bin_path.display());
     
     extract_animation_list(&bin_path)
         .map_err(|e| {
             tracing::error!("Failed to extract animation list: {}", e);
-            format!("Failed to extract animation list: {}", e)
+            CommandError::new(ErrorCode::Parse, format!("Failed to extract animation list: {}", e))
         })
 }
 
 /// Read and parse an ANM animation file
 #[tauri::command]
-pub async fn read_animation(path: String, base_path: Option<String>) -> Result<AnimationData, String> {
+pub async fn read_animation(path: String, base_path: Option<String>) -> Result<AnimationData, CommandError> {
     tracing::debug!("Reading animation: {}", path);
     
     // Try to resolve the animation path
@@ -447,16 +451,16 @@ pub async fn read_animation(path: String, base_path: Option<String>) -> Result<A
     };
     
     let anim_path = resolved_path
-        .ok_or_else(|| format!("Could not resolve animation path: {}", path))?;
+        .ok_or_else(|| CommandError::new(ErrorCode::NotFound, format!("Could not resolve animation path: {}", path)))?;
     
     if !anim_path.exists() {
-        return Err(format!("Animation file not found: {}", anim_path.display()));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("Animation file not found: {}", anim_path.display())));
     }
     
     parse_animation_file(&anim_path)
         .map_err(|e| {
             tracing::error!("Failed to parse animation {}: {}", anim_path.display(), e);
-            format!("Failed to parse animation: {}", e)
+            CommandError::new(ErrorCode::Parse, format!("Failed to parse animation: {}", e))
         })
 }
 
@@ -468,7 +472,7 @@ pub async fn evaluate_animation(
     path: String, 
     base_path: Option<String>, 
     time: f32
-) -> Result<AnimationPose, String> {
+) -> Result<AnimationPose, CommandError> {
     tracing::debug!("Evaluating animation at time {}: {}", time, path);
     
     // Resolve the animation path
@@ -482,15 +486,15 @@ pub async fn evaluate_animation(
     };
     
     let anim_path = resolved_path
-        .ok_or_else(|| format!("Could not resolve animation path: {}", path))?;
+        .ok_or_else(|| CommandError::new(ErrorCode::NotFound, format!("Could not resolve animation path: {}", path)))?;
     
     if !anim_path.exists() {
-        return Err(format!("Animation file not found: {}", anim_path.display()));
+        return Err(CommandError::new(ErrorCode::NotFound, format!("Animation file not found: {}", anim_path.display())));
     }
     
     evaluate_animation_at(&anim_path, time)
         .map_err(|e| {
             tracing::error!("Failed to evaluate animation {}: {}", anim_path.display(), e);
-            format!("Failed to evaluate animation: {}", e)
+            CommandError::new(ErrorCode::Parse, format!("Failed to evaluate animation: {}", e))
         })
 }
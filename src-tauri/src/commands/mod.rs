@@ -11,3 +11,10 @@ pub mod export;
 pub mod mesh;
 pub mod checkpoint;
 pub mod updater;
+pub mod operation;
+pub mod settings;
+pub mod log;
+pub mod diagnostics;
+pub mod startup;
+pub mod mapping;
+pub mod undo;
@@ -0,0 +1,42 @@
+//! Tauri commands for tracking and cancelling long-running operations
+
+use crate::core::operation::{OperationInfo, OperationRegistry, ProjectBusyStatus};
+use crate::error::CommandError;
+use tauri::State;
+
+/// Running operations plus recently finished ones (kept for a short grace period)
+#[tauri::command]
+pub async fn list_operations(
+    registry: State<'_, OperationRegistry>,
+) -> Result<Vec<OperationInfo>, CommandError> {
+    Ok(registry.list())
+}
+
+/// Request cancellation of an operation.
+/// Returns false if it had already finished.
+#[tauri::command]
+pub async fn cancel_operation(
+    id: String,
+    registry: State<'_, OperationRegistry>,
+) -> Result<bool, CommandError> {
+    registry.cancel(&id).map_err(CommandError::from)
+}
+
+/// Whether a mutating operation (repath, export with repath, restore) is
+/// running on the project, so the UI can disable conflicting actions
+#[tauri::command]
+pub async fn get_project_busy_status(
+    project_path: String,
+    registry: State<'_, OperationRegistry>,
+) -> Result<ProjectBusyStatus, CommandError> {
+    Ok(registry.busy_status(&project_path))
+}
+
+/// The most recent finished operation of each kind, with its phase timings,
+/// most recent first
+#[tauri::command]
+pub async fn get_last_operation_metrics(
+    registry: State<'_, OperationRegistry>,
+) -> Result<Vec<OperationInfo>, CommandError> {
+    Ok(registry.last_finished())
+}
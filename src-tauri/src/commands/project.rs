@@ -6,13 +6,31 @@ use crate::core::project::{
     create_project as core_create_project,
     open_project as core_open_project,
     save_project as core_save_project,
-    Project,
+    setup_project_content as core_setup_project_content,
+    export_project_hashes as core_export_project_hashes,
+    ensure_path_writable, ensure_writable, find_project_root, is_writable, load_project_hashes, Project, ProjectHashExport, SetupOptions, SetupReport,
+    SetupTarget,
 };
-use crate::core::repath::{organize_project, OrganizerConfig};
-use crate::core::bin::{classify_bin, BinCategory};
+use crate::core::champion::resolve_skin_name;
+use crate::core::disk::{disk_usage, DiskUsage};
+use crate::core::undo::{FileSnapshot, UndoJournal};
+use crate::core::league::detect_game_version;
+use crate::core::operation::{OperationKind, OperationRegistry};
+use crate::core::recolor::{
+    recolor, recolor_bins, BinColorTransform, BinRecolorReport, ColorTransform, RecolorReport, RecolorSelector,
+};
+use crate::core::checkpoint::{CheckpointManager, CheckpointProgress};
+use crate::core::bin::get_cached_bin_hashes;
+use crate::core::bin::rename::{rename_vfx_system as core_rename_vfx_system, RenameReport};
+use crate::core::repath::{
+    apply_rebase, organize_project, plan_rebase, MapOverrideMode, OrganizerConfig, RebaseReport, RebaseTarget,
+};
+use crate::core::bin::batch::{convert_project_bins as convert_bins, BinConversionReport, BinOutputFormat};
+use crate::core::bin::preconvert::{preconvert_bins, BATCH_SIZE};
 use crate::core::wad::extractor::{find_champion_wad, extract_skin_assets};
-use crate::state::HashtableState;
-use league_toolkit::wad::Wad;
+use crate::core::wad::reader::WadReader;
+use crate::error::{CommandError, Error, ErrorCode};
+use crate::state::{HashtableState, SettingsState, HASHTABLE_WAIT};
 use std::path::PathBuf;
 use tauri::Emitter;
 
@@ -26,9 +44,15 @@ use tauri::Emitter;
 /// * `output_path` - Directory where project will be created
 /// * `creator_name` - Creator name for repathing (e.g., "SirDexal")
 ///
+/// The project starts with a numeric display name ("Ahri Skin 85"). The
+/// skin's name ("Star Guardian Ahri") is looked up once the project exists,
+/// so creation never waits on the network; when found it is saved and the
+/// updated project sent as `project-display-name`. In offline mode the
+/// numeric name is kept and nothing is looked up.
+///
 /// # Returns
 /// * `Ok(Project)` - The created project
-/// * `Err(String)` - Error message if creation failed
+/// * `Err(CommandError)` - Error message if creation failed
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub async fn create_project(
@@ -39,8 +63,9 @@ pub async fn create_project(
     output_path: String,
     creator_name: Option<String>,
     hashtable_state: tauri::State<'_, HashtableState>,
+    settings: tauri::State<'_, SettingsState>,
     app: tauri::AppHandle,
-) -> Result<Project, String> {
+) -> Result<Project, CommandError> {
     tracing::info!(
         "Frontend requested project creation: {} ({} skin {})",
         name, champion, skin_id
@@ -55,17 +80,18 @@ pub async fn create_project(
         "message": "Initializing..."
     }));
 
-    let hashtable = hashtable_state.get_hashtable().ok_or_else(|| 
-        "Failed to load hashtable. Please check that hash files are available.".to_string()
-    )?;
+    let hashtable = hashtable_state.ensure_loaded().await.ok_or_else(|| CommandError::new(
+        ErrorCode::HashtableNotReady,
+        "Failed to load hashtable. Please check that hash files are available.",
+    ))?;
     
     tracing::info!("Hashtable ready with {} entries", hashtable.len());
 
     // 2. Validate WAD existence before creating project
     let wad_path = find_champion_wad(&league_path_buf, &champion)
-        .ok_or_else(|| format!(
-            "Champion WAD not found for '{}'. Please check League installation.",
-            champion
+        .ok_or_else(|| CommandError::new(
+            ErrorCode::NotFound,
+            format!("Champion WAD not found for '{}'. Please check League installation.", champion),
         ))?;
 
     // 3. Create the project directory structure
@@ -81,11 +107,18 @@ pub async fn create_project(
     let creator_clone = creator_name.clone();
 
     let project = tokio::task::spawn_blocking(move || {
-        core_create_project(&name_clone, &champion_clone, skin_id, &league_clone, &output_clone, creator_clone)
+        core_create_project(
+            &name_clone,
+            &champion_clone,
+            skin_id,
+            &league_clone,
+            &output_clone,
+            creator_clone,
+            None,
+        )
     })
     .await
-    .map_err(|e| format!("Task failed: {}", e))?
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("Task failed: {}", e))??;
     
     // 4. Extract skin assets into the project
     let _ = app.emit("project-create-progress", serde_json::json!({
@@ -99,21 +132,20 @@ pub async fn create_project(
     let champion_for_extract = champion.clone();
     
     let extraction_result = tokio::task::spawn_blocking(move || {
-        let mut wad = Wad::mount(std::fs::File::open(&wad_path)
-            .map_err(|e| format!("Failed to open WAD: {}", e))?)
-            .map_err(|e| format!("Failed to mount WAD: {}", e))?;
+        let mut reader = WadReader::open(&wad_path)?;
         
         extract_skin_assets(
-            &mut wad,
+            &mut reader,
             &assets_path,
             &champion_for_extract,
             skin_id,
             &hashtable,
-        ).map_err(|e| e.to_string())
+            None,
+        )
     })
     .await;
     
-    let extraction_result = match extraction_result {
+    let mut extraction_result = match extraction_result {
         Ok(Ok(result)) => {
             tracing::info!("Extracted {} assets to project", result.extracted_count);
             result
@@ -124,17 +156,31 @@ pub async fn create_project(
             if let Err(cleanup_err) = std::fs::remove_dir_all(&project.project_path) {
                 tracing::error!("Failed to clean up project directory: {}", cleanup_err);
             }
-            return Err(format!("Asset extraction failed: {}. Project creation cancelled.", e));
+            // Keep the numbers so the UI can say how much space to free
+            if matches!(e, Error::InsufficientDiskSpace { .. }) {
+                return Err(e.into());
+            }
+            return Err(CommandError::new(
+                ErrorCode::Wad,
+                format!("Asset extraction failed: {}. Project creation cancelled.", e),
+            ));
         }
         Err(e) => {
             tracing::error!("Extraction task panicked: {}", e);
             if let Err(cleanup_err) = std::fs::remove_dir_all(&project.project_path) {
                 tracing::error!("Failed to clean up project directory: {}", cleanup_err);
             }
-            return Err(format!("Internal error during extraction: {}", e));
+            return Err(format!("Internal error during extraction: {}", e).into());
         }
     };
 
+    // Keep the path mappings with the project so later repaths and exports
+    // (by Flint or another tool) can find hash-named files
+    extraction_result.path_mappings.game_version = detect_game_version(&league_path_buf);
+    if let Err(e) = extraction_result.path_mappings.save(&project.project_path) {
+        tracing::warn!("Failed to save path mappings: {}", e);
+    }
+
     // 5. Repath assets if creator name is provided
     if let Some(creator) = creator_name {
         if !creator.is_empty() {
@@ -153,12 +199,16 @@ pub async fn create_project(
                 champion: champion.clone(),
                 target_skin_id: skin_id,
                 cleanup_unused: true,
+                // Freshly extracted game files; nothing user-made to quarantine
+                delete_unused: true,
+                map_overrides: MapOverrideMode::Keep,
+                foreign_prefix: None,
             };
 
             let assets_path_for_repath = project.assets_path();
-            let path_mappings = extraction_result.path_mappings.clone();
+            let path_mappings = extraction_result.path_mappings.lookup();
             let repath_result = tokio::task::spawn_blocking(move || {
-                organize_project(&assets_path_for_repath, &repath_config, &path_mappings)
+                organize_project(&assets_path_for_repath, &repath_config, &path_mappings, None)
             })
             .await;
 
@@ -190,10 +240,403 @@ pub async fn create_project(
         "message": "Project created successfully!"
     }));
 
+    if settings.ensure_online().is_ok() {
+        let (settings, path, champion) = (settings.inner().clone(), project.project_path.clone(), champion.clone());
+        tauri::async_runtime::spawn(async move {
+            match apply_skin_name(&settings, path, &champion, skin_id).await {
+                Ok(project) => {
+                    let _ = app.emit("project-display-name", &project);
+                }
+                Err(e) => tracing::info!("Keeping numeric display name for {} skin {}: {}", champion, skin_id, e),
+            }
+        });
+    } else {
+        tracing::info!("Offline; keeping numeric display name for {} skin {}", champion, skin_id);
+    }
+
     Ok(project)
 }
 
 
+/// Fill a freshly created project with content in one operation: extract the
+/// champion's assets, concat/repath BINs, pre-convert them and take an
+/// initial checkpoint.
+///
+/// Progress is emitted as `setup-progress` with the current phase. A failing
+/// phase stops the pipeline; the returned report names it and describes the
+/// partial state, and is also saved to the project's reports directory.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `options` - Optional steps to run (all on by default); the repath creator
+///   defaults to the one in settings
+/// * `queue` - Wait for another mutating operation on the project instead of failing with Busy
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn setup_project_content(
+    project_path: String,
+    options: Option<SetupOptions>,
+    queue: Option<bool>,
+    app: tauri::AppHandle,
+    hashtable_state: tauri::State<'_, HashtableState>,
+    operations: tauri::State<'_, OperationRegistry>,
+    settings: tauri::State<'_, SettingsState>,
+    undo: tauri::State<'_, UndoJournal>,
+) -> Result<SetupReport, CommandError> {
+    tracing::info!("Frontend requested content setup for: {}", project_path);
+
+    let path = PathBuf::from(&project_path);
+    let project = tokio::task::spawn_blocking(move || {
+        ensure_writable(&path)?;
+        core_open_project(&path)
+    })
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+
+    let settings = settings.get();
+    let mut options = options.unwrap_or_default();
+    if options.creator_name.is_none() {
+        options.creator_name = settings.creator_name.clone();
+    }
+
+    let league_path = project.league_path.clone().or(settings.league_path).ok_or_else(|| CommandError::new(
+        ErrorCode::InvalidInput,
+        "No League installation is configured for this project.",
+    ))?;
+    let wad_path = find_champion_wad(&league_path, &project.champion)
+        .ok_or_else(|| CommandError::new(
+            ErrorCode::NotFound,
+            format!("Champion WAD not found for '{}'. Please check League installation.", project.champion),
+        ))?;
+    let game_version = detect_game_version(&league_path);
+
+    let operation = operations
+        .start_exclusive(OperationKind::Setup, &project_path, true, queue.unwrap_or(false))
+        .await?;
+    undo.invalidate(&PathBuf::from(&project_path));
+
+    let hashtable = hashtable_state.ensure_loaded().await.ok_or_else(|| CommandError::new(
+        ErrorCode::HashtableNotReady,
+        "Failed to load hashtable. Please check that hash files are available.",
+    ))?;
+
+    let cancel = operation.token();
+    let operation_id = operation.id().to_string();
+    let span = operation.span();
+    let (report, operation) = tokio::task::spawn_blocking(move || {
+        let _span = span.enter();
+        let target = SetupTarget {
+            project: &project,
+            wad_path: &wad_path,
+            game_version: game_version.as_deref(),
+            hashtable: &hashtable,
+        };
+        let report = core_setup_project_content(&target, &options, &cancel, |phase, progress, message| {
+            operation.set_progress(progress, message);
+            let _ = app.emit("setup-progress", serde_json::json!({
+                "phase": phase,
+                "progress": progress,
+                "message": message,
+                "operation_id": operation_id,
+            }));
+        });
+        if let Err(e) = report.save(&project.project_path) {
+            tracing::warn!("Failed to save setup report: {}", e);
+        }
+        (report, operation)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+
+    let outcome = match report.steps.iter().find(|s| Some(s.phase) == report.failed_phase) {
+        Some(step) => Err(format!("{:?} phase stopped: {}", step.phase, step.message)),
+        None => Ok(()),
+    };
+    operation.finish(&outcome);
+
+    Ok(report)
+}
+
+/// Check a project against the current game patch and remap references the
+/// patch made stale.
+///
+/// The game's BIN for the project's skin is extracted to `.flint/rebase` and
+/// the project's references are compared with the champion WAD. Without
+/// `approved` this only reports the proposed remappings and the references
+/// that need manual attention; with it, the listed old paths are rewritten.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `league_path` - Installation to compare against; defaults to the project's, then the settings'
+/// * `approved` - Old paths from a previous proposal to apply
+/// * `queue` - Wait for another mutating operation on the project instead of failing with Busy
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn rebase_project(
+    project_path: String,
+    league_path: Option<String>,
+    approved: Option<Vec<String>>,
+    queue: Option<bool>,
+    hashtable_state: tauri::State<'_, HashtableState>,
+    operations: tauri::State<'_, OperationRegistry>,
+    settings: tauri::State<'_, SettingsState>,
+    undo: tauri::State<'_, UndoJournal>,
+) -> Result<RebaseReport, CommandError> {
+    tracing::info!("Frontend requested rebase for: {}", project_path);
+
+    let path = PathBuf::from(&project_path);
+    let project = tokio::task::spawn_blocking(move || core_open_project(&path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+
+    let league_path = league_path
+        .map(PathBuf::from)
+        .or_else(|| project.league_path.clone())
+        .or(settings.get().league_path)
+        .ok_or_else(|| CommandError::new(
+            ErrorCode::InvalidInput,
+            "No League installation is configured for this project.",
+        ))?;
+    let wad_path = find_champion_wad(&league_path, &project.champion)
+        .ok_or_else(|| CommandError::new(
+            ErrorCode::NotFound,
+            format!("Champion WAD not found for '{}'. Please check League installation.", project.champion),
+        ))?;
+
+    // Only applying rewrites the project's BINs
+    let approved = approved.unwrap_or_default();
+    let operation = if approved.is_empty() {
+        operations.start(OperationKind::Repath, Some(&project_path))
+    } else {
+        ensure_writable(&project.project_path)?;
+        let operation = operations
+            .start_exclusive(OperationKind::Repath, &project_path, true, queue.unwrap_or(false))
+            .await?;
+        undo.invalidate(&project.project_path);
+        operation
+    };
+
+    let hashtable = hashtable_state.ensure_loaded().await.ok_or_else(|| CommandError::new(
+        ErrorCode::HashtableNotReady,
+        "Failed to load hashtable. Please check that hash files are available.",
+    ))?;
+
+    let cancel = operation.token();
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || -> crate::error::Result<RebaseReport> {
+        let _span = span.enter();
+        let content_base = project.assets_path();
+        let temp_dir = project.project_path.join(".flint").join("rebase");
+        let target = RebaseTarget {
+            content_base: &content_base,
+            wad_path: &wad_path,
+            champion: &project.champion,
+            skin_id: project.skin_id,
+            hashtable: &hashtable,
+            temp_dir: &temp_dir,
+        };
+        let report = plan_rebase(&target, &cancel)?;
+        if approved.is_empty() {
+            return Ok(report);
+        }
+        cancel.check()?;
+        Ok(apply_rebase(&content_base, report, &approved))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from);
+
+    operation.finish(&result);
+    result
+}
+
+/// Recolor a VFX system or a set of textures.
+///
+/// A dry run reports the files that would change with small before/after
+/// PNGs and writes nothing. Otherwise the files are rewritten together and
+/// the change is recorded for `undo_last`, unless the old files are too
+/// large for the undo journal.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `selector` - A VFX system by object path or hash, or a texture path glob
+/// * `transform` - Hue shift, tint or gradient map
+/// * `dry_run` - Only preview the change
+/// * `queue` - Wait for another mutating operation on the project instead of failing with Busy
+#[tauri::command]
+pub async fn recolor_project(
+    project_path: String,
+    selector: RecolorSelector,
+    transform: ColorTransform,
+    dry_run: bool,
+    queue: Option<bool>,
+    operations: tauri::State<'_, OperationRegistry>,
+    undo: tauri::State<'_, UndoJournal>,
+) -> Result<RecolorReport, CommandError> {
+    tracing::info!("Frontend requested recolor for: {} (dry run: {})", project_path, dry_run);
+
+    let path = PathBuf::from(&project_path);
+    let project = tokio::task::spawn_blocking(move || core_open_project(&path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+
+    let operation = if dry_run {
+        operations.start(OperationKind::Recolor, Some(&project_path))
+    } else {
+        ensure_writable(&project.project_path)?;
+        operations
+            .start_exclusive(OperationKind::Recolor, &project_path, true, queue.unwrap_or(false))
+            .await?
+    };
+
+    let journal = undo.inner().clone();
+    let cancel = operation.token();
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || -> crate::error::Result<RecolorReport> {
+        let _span = span.enter();
+        let (mut report, writes) = recolor(&project.assets_path(), &selector, &transform, dry_run, &cancel)?;
+        if dry_run || writes.is_empty() {
+            return Ok(report);
+        }
+        // Journal first so undo can also revert a partly written recolor
+        report.undoable = journal.push(&project.project_path, "Recolor VFX", writes.snapshot()?);
+        writes.write()?;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from);
+
+    operation.finish(&result);
+    result
+}
+
+/// Apply a hue/saturation/value shift or a palette swap to the colors of a
+/// BIN, or of a project's particle and skin BINs
+///
+/// Colors are transformed as stored (sRGB-encoded), so opposite shifts
+/// round-trip. Alpha is kept unless a palette swap sets it. Inside a project
+/// a checkpoint is taken before anything is written, to undo the recolor by
+/// restoring it.
+///
+/// # Arguments
+/// * `path` - A .bin file, or a project folder
+/// * `transform` - `{ type: "hsv", hue, saturation, value }` or
+///   `{ type: "palette", swaps: [{ from, to, alpha? }], tolerance }`
+/// * `queue` - Wait for another mutating operation on the project instead of failing with Busy
+#[tauri::command]
+pub async fn apply_bin_recolor(
+    path: String,
+    transform: BinColorTransform,
+    queue: Option<bool>,
+    operations: tauri::State<'_, OperationRegistry>,
+) -> Result<BinRecolorReport, CommandError> {
+    tracing::info!("Frontend requested BIN recolor for: {}", path);
+    let target = PathBuf::from(&path);
+    ensure_path_writable(&target)?;
+    let project_root = find_project_root(&target);
+    let operation = match &project_root {
+        Some(root) => {
+            operations
+                .start_exclusive(OperationKind::Recolor, &root.to_string_lossy(), true, queue.unwrap_or(false))
+                .await?
+        }
+        None => operations.start(OperationKind::Recolor, Some(&path)),
+    };
+
+    let cancel = operation.token();
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || -> crate::error::Result<BinRecolorReport> {
+        let _span = span.enter();
+        let (mut report, writes) = recolor_bins(&target, &transform, &cancel)?;
+        if writes.is_empty() {
+            return Ok(report);
+        }
+        if let Some(root) = project_root {
+            let manager = CheckpointManager::new(root);
+            manager.init()?;
+            let checkpoint = manager.create_checkpoint_with_progress(
+                "Before BIN recolor".to_string(),
+                vec!["auto".to_string()],
+                None::<fn(&CheckpointProgress)>,
+                Some(cancel.as_flag()),
+            )?;
+            report.checkpoint_id = Some(checkpoint.id);
+        }
+        writes.write()?;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from);
+
+    operation.finish(&result);
+    result
+}
+
+/// Rename a VFX system and every reference to it in the project's BINs.
+///
+/// The definition's key, object links, hash fields, strings and resource
+/// resolver keys naming the system are all rewritten. A dry run lists the
+/// locations that would change and writes nothing; otherwise the BINs are
+/// rewritten together and recorded for `undo_last`.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `old_name` - The system's object path, or its hash as "0x…"
+/// * `new_name` - The new object path
+/// * `dry_run` - Only list the locations
+/// * `queue` - Wait for another mutating operation on the project instead of failing with Busy
+#[tauri::command]
+pub async fn rename_vfx_system(
+    project_path: String,
+    old_name: String,
+    new_name: String,
+    dry_run: bool,
+    queue: Option<bool>,
+    operations: tauri::State<'_, OperationRegistry>,
+    undo: tauri::State<'_, UndoJournal>,
+) -> Result<RenameReport, CommandError> {
+    tracing::info!("Frontend requested renaming VFX system {} to {} (dry run: {})", old_name, new_name, dry_run);
+
+    let path = PathBuf::from(&project_path);
+    let project = tokio::task::spawn_blocking(move || core_open_project(&path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+
+    let operation = if dry_run {
+        operations.start(OperationKind::Repath, Some(&project_path))
+    } else {
+        ensure_writable(&project.project_path)?;
+        operations
+            .start_exclusive(OperationKind::Repath, &project_path, true, queue.unwrap_or(false))
+            .await?
+    };
+
+    let journal = undo.inner().clone();
+    let cancel = operation.token();
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || -> crate::error::Result<RenameReport> {
+        let _span = span.enter();
+        let (mut report, writes) = {
+            let hashes = get_cached_bin_hashes().read();
+            core_rename_vfx_system(&project.assets_path(), &old_name, &new_name, &*hashes, dry_run, &cancel)?
+        };
+        if dry_run || writes.is_empty() {
+            return Ok(report);
+        }
+        report.undoable = journal.push(&project.project_path, "Rename VFX system", writes.snapshot()?);
+        writes.write()?;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from);
+
+    operation.finish(&result);
+    result
+}
+
 /// Open an existing project
 ///
 /// # Arguments
@@ -201,17 +644,74 @@ pub async fn create_project(
 ///
 /// # Returns
 /// * `Ok(Project)` - The loaded project
-/// * `Err(String)` - Error message if loading failed
+/// * `Err(CommandError)` - Error message if loading failed
 #[tauri::command]
-pub async fn open_project(path: String) -> Result<Project, String> {
+pub async fn open_project(
+    path: String,
+    hashtable: tauri::State<'_, HashtableState>,
+) -> Result<Project, CommandError> {
     tracing::info!("Frontend requested opening project: {}", path);
 
     let path = PathBuf::from(path);
+    let hashtable = hashtable.inner().clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut project = core_open_project(&path)?;
+        project.read_only = !is_writable(&project.project_path);
+        if project.read_only {
+            tracing::info!("Project '{}' is read-only; editing is disabled", project.name);
+        }
+        // Saved hashes only add names, so a broken file doesn't stop the open
+        match load_project_hashes(&project.project_path) {
+            Ok(Some(file)) => hashtable.add_overlay(file),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to load the project's saved hashes: {}", e),
+        }
+        Ok::<_, Error>(project)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Save the hash names the project uses to `project.hashes.txt` in its folder.
+///
+/// Covers the paths of the files under content/base and the object, class,
+/// field and hash names in its BINs, as far as the local hash lists resolve
+/// them. Opening the project merges the file back in, so collaborators with
+/// other lists see the same names.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+#[tauri::command]
+pub async fn export_project_hashes(
+    project_path: String,
+    operations: tauri::State<'_, OperationRegistry>,
+    hashtable: tauri::State<'_, HashtableState>,
+) -> Result<ProjectHashExport, CommandError> {
+    tracing::info!("Frontend requested exporting hashes for: {}", project_path);
 
-    tokio::task::spawn_blocking(move || core_open_project(&path))
+    let path = PathBuf::from(&project_path);
+    let project = tokio::task::spawn_blocking(move || core_open_project(&path))
         .await
-        .map_err(|e| format!("Task failed: {}", e))?
-        .map_err(|e| e.to_string())
+        .map_err(|e| format!("Task failed: {}", e))??;
+    ensure_writable(&project.project_path)?;
+
+    let ht = hashtable.wait_ready(HASHTABLE_WAIT).await?;
+    let operation = operations.start(OperationKind::Export, Some(&project_path));
+    let cancel = operation.token();
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || -> crate::error::Result<ProjectHashExport> {
+        let _span = span.enter();
+        let hashes = get_cached_bin_hashes().read();
+        core_export_project_hashes(&project.project_path, &project.assets_path(), Some(ht.as_ref()), &*hashes, &cancel)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from);
+
+    operation.finish(&result);
+    result
 }
 
 /// Save project state
@@ -221,15 +721,93 @@ pub async fn open_project(path: String) -> Result<Project, String> {
 ///
 /// # Returns
 /// * `Ok(())` - If save succeeded
-/// * `Err(String)` - Error message if save failed
+/// * `Err(CommandError)` - Error message if save failed
 #[tauri::command]
-pub async fn save_project(project: Project) -> Result<(), String> {
+pub async fn save_project(project: Project, undo: tauri::State<'_, UndoJournal>) -> Result<(), CommandError> {
     tracing::info!("Frontend requested saving project: {}", project.name);
 
-    tokio::task::spawn_blocking(move || core_save_project(&project))
+    let project_path = project.project_path.clone();
+    let snapshot = tokio::task::spawn_blocking(move || {
+        ensure_writable(&project.project_path)?;
+        let snapshot = FileSnapshot::capture(&[project.config_path(), project.flint_path()])?;
+        core_save_project(&project).map(|_| snapshot)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+
+    undo.push(&project_path, "Edit project settings", snapshot);
+    Ok(())
+}
+
+/// Replace a project's display name with its skin's resolved name, e.g. for
+/// projects created offline or before names were resolved
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+///
+/// # Returns
+/// * `Ok(Project)` - The updated project
+/// * `Err(CommandError)` - If the name couldn't be resolved or the project
+///   saved, or at once with `OfflineMode` in offline mode
+#[tauri::command]
+pub async fn refresh_project_display_name(
+    project_path: String,
+    settings: tauri::State<'_, SettingsState>,
+) -> Result<Project, CommandError> {
+    tracing::info!("Frontend requested display name refresh for: {}", project_path);
+    settings.ensure_online()?;
+
+    let path = PathBuf::from(&project_path);
+    let project = tokio::task::spawn_blocking({
+        let path = path.clone();
+        move || {
+            ensure_writable(&path)?;
+            core_open_project(&path)
+        }
+    })
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+
+    apply_skin_name(&settings, path, &project.champion, project.skin_id).await
+}
+
+/// Look up the skin's name and save it as the display name of the project
+/// at `path`. Fails with `OfflineMode`, without a request, in offline mode.
+async fn apply_skin_name(
+    settings: &SettingsState,
+    path: PathBuf,
+    champion: &str,
+    skin_id: u32,
+) -> Result<Project, CommandError> {
+    settings.ensure_online()?;
+    let skin_name = resolve_skin_name(champion, skin_id).await?;
+    tokio::task::spawn_blocking(move || {
+        // Re-read so edits made while the lookup ran aren't overwritten
+        let mut project = core_open_project(&path)?;
+        project.set_skin_name(&skin_name);
+        core_save_project(&project).map(|_| project)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Report how much disk space a project uses and how much is free
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+///
+/// # Returns
+/// * `Ok(DiskUsage)` - Bytes used by content, output, checkpoints and the rest
+/// * `Err(CommandError)` - If the project doesn't exist
+#[tauri::command]
+pub async fn get_disk_usage(project_path: String) -> Result<DiskUsage, CommandError> {
+    let path = PathBuf::from(project_path);
+
+    tokio::task::spawn_blocking(move || disk_usage(&path))
         .await
         .map_err(|e| format!("Task failed: {}", e))?
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 /// List files in a project directory
@@ -239,23 +817,31 @@ pub async fn save_project(project: Project) -> Result<(), String> {
 ///
 /// # Returns
 /// * `Ok(FileTree)` - The file tree structure
-/// * `Err(String)` - Error message if listing failed
+/// * `Err(CommandError)` - Error message if listing failed
 #[tauri::command]
-pub async fn list_project_files(project_path: String) -> Result<serde_json::Value, String> {
+pub async fn list_project_files(project_path: String) -> Result<serde_json::Value, CommandError> {
     use std::fs;
     use serde_json::json;
     
     let path = PathBuf::from(&project_path);
     
     if !path.exists() {
-        return Err(format!("Project path does not exist: {}", project_path));
+        return Err(CommandError::new(
+            ErrorCode::NotFound,
+            format!("Project path does not exist: {}", project_path),
+        ));
     }
+    ensure_writable(&path)?;
     
     fn build_tree(dir: &std::path::Path, base: &std::path::Path) -> serde_json::Value {
         let mut tree = serde_json::Map::new();
         
         if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries.flatten() {
+            // Sorted by name so the tree doesn't depend on directory order;
+            // entries are keyed by their relative `path`
+            let mut entries: Vec<_> = entries.flatten().collect();
+            entries.sort_by_key(|e| e.file_name());
+            for entry in entries {
                 let entry_path = entry.path();
                 let name = entry.file_name().to_string_lossy().to_string();
                 
@@ -306,201 +892,132 @@ pub async fn list_project_files(project_path: String) -> Result<serde_json::Valu
 ///
 /// # Returns
 /// * `Ok(usize)` - Number of BIN files converted
-/// * `Err(String)` - Error message if conversion failed
+/// * `Err(CommandError)` - Error message if conversion failed
 #[tauri::command]
 pub async fn preconvert_project_bins(
     project_path: String,
     app: tauri::AppHandle,
-) -> Result<usize, String> {
-    use std::fs;
-    use std::sync::atomic::{AtomicUsize, Ordering};
-    use std::sync::Arc;
-    use rayon::prelude::*;
-    use walkdir::WalkDir;
-    
+) -> Result<usize, CommandError> {
     tracing::info!("Pre-converting BIN files in project: {}", project_path);
     
     let path = std::path::PathBuf::from(&project_path);
     if !path.exists() {
-        return Err(format!("Project path does not exist: {}", project_path));
+        return Err(CommandError::new(
+            ErrorCode::NotFound,
+            format!("Project path does not exist: {}", project_path),
+        ));
     }
     
-    // Pre-warm the hash cache before parallel processing
-    // This ensures the cache is initialized on the main thread before workers access it
-    tracing::info!("Pre-warming BIN hash cache...");
-    let _ = crate::core::bin::get_cached_bin_hashes();
-    tracing::info!("Hash cache ready");
-    
-    // Find all .bin files
-    let bin_files: Vec<_> = WalkDir::new(&path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| {
-            e.path().extension()
-                .map(|ext| ext == "bin")
-                .unwrap_or(false)
-        })
-        .filter(|e| {
-            if let Ok(rel_path) = e.path().strip_prefix(&path) {
-                let rel_str = rel_path.to_string_lossy();
-                let category = classify_bin(&rel_str);
-                
-                // Skip Ignore category (corrupt/recursive names)
-                if category == BinCategory::Ignore {
-                    tracing::warn!("Skipping suspicious BIN file: {}", rel_str);
-                    return false;
-                }
-                
-                // Skip Animation BINs - they shouldn't be pre-converted and can have corrupt metadata
-                if category == BinCategory::Animation {
-                    tracing::debug!("Skipping animation BIN: {}", rel_str);
-                    return false;
-                }
-                
-                // Skip ChampionRoot BINs - these reference game data and shouldn't be converted
-                if category == BinCategory::ChampionRoot {
-                    tracing::debug!("Skipping champion root BIN: {}", rel_str);
-                    return false;
-                }
-            }
-            true
-        })
-        .map(|e| e.path().to_path_buf())
-        .collect();
-    
-    let total = bin_files.len();
-    tracing::info!("Found {} BIN files to convert", total);
-    
-    // Emit initial progress
     let _ = app.emit("bin-convert-progress", serde_json::json!({
         "current": 0,
-        "total": total,
+        "total": 0,
         "file": "",
         "status": "starting"
     }));
-    
-    // Filter to only files that need conversion (not already up-to-date)
-    let files_to_convert: Vec<_> = bin_files.iter()
-        .filter(|bin_path| {
-            let ritobin_path = format!("{}.ritobin", bin_path.display());
-            let ritobin_file = std::path::Path::new(&ritobin_path);
-            
-            if ritobin_file.exists() {
-                if let (Ok(bin_meta), Ok(ritobin_meta)) = (fs::metadata(bin_path), fs::metadata(ritobin_file)) {
-                    if let (Ok(bin_time), Ok(ritobin_time)) = (bin_meta.modified(), ritobin_meta.modified()) {
-                        if ritobin_time >= bin_time {
-                            tracing::debug!("[PRECONVERT] CACHE HIT - skipping: {}", bin_path.file_name().unwrap_or_default().to_string_lossy());
-                            return false;
-                        } else {
-                            tracing::debug!("[PRECONVERT] CACHE STALE - will convert: {}", bin_path.file_name().unwrap_or_default().to_string_lossy());
-                        }
-                    }
-                }
-            } else {
-                tracing::debug!("[PRECONVERT] NO CACHE - will convert: {}", bin_path.file_name().unwrap_or_default().to_string_lossy());
-            }
-            true
+
+    let progress_app = app.clone();
+    let stats = tokio::task::spawn_blocking(move || {
+        preconvert_bins(&path, None, |done, total| {
+            let _ = progress_app.emit("bin-convert-progress", serde_json::json!({
+                "current": done,
+                "total": total,
+                "file": format!("Batch {}/{}", done / BATCH_SIZE + 1, total.div_ceil(BATCH_SIZE)),
+                "status": "converting"
+            }));
         })
-        .cloned()
-        .collect();
-    
-    let cache_hits = total - files_to_convert.len();
-    let to_convert_count = files_to_convert.len();
-    tracing::info!("[PRECONVERT] {} files need conversion, {} CACHE HITS (already up-to-date)", 
-        to_convert_count, cache_hits);
-    
-    // Atomic counter for thread-safe progress tracking
-    let converted = Arc::new(AtomicUsize::new(0));
-    let failed = Arc::new(AtomicUsize::new(0));
-    
-    // Process in batches to control peak memory usage
-    const BATCH_SIZE: usize = 50;
-    
-    for (batch_idx, batch) in files_to_convert.chunks(BATCH_SIZE).enumerate() {
-        let batch_start = batch_idx * BATCH_SIZE;
-        
-        // Emit progress for batch start
-        let _ = app.emit("bin-convert-progress", serde_json::json!({
-            "current": batch_start,
-            "total": to_convert_count,
-            "file": format!("Batch {}/{}", batch_idx + 1, to_convert_count.div_ceil(BATCH_SIZE)),
-            "status": "converting"
-        }));
-        
-        // Process batch in parallel using rayon
-        let converted_clone = Arc::clone(&converted);
-        let failed_clone = Arc::clone(&failed);
-        
-        batch.par_iter().for_each(|bin_path| {
-            let bin_path_str = bin_path.to_string_lossy().to_string();
-            
-            match convert_bin_file_sync(&bin_path_str) {
-                Ok(_) => {
-                    converted_clone.fetch_add(1, Ordering::Relaxed);
-                    tracing::debug!("Converted: {}", bin_path.display());
-                }
-                Err(e) => {
-                    failed_clone.fetch_add(1, Ordering::Relaxed);
-                    tracing::warn!("Failed to convert {}: {}", bin_path.display(), e);
-                }
-            }
-        });
-        
-        // Log batch completion
-        let current_converted = converted.load(Ordering::Relaxed);
-        tracing::info!("Batch {} complete: {} converted so far", batch_idx + 1, current_converted);
-    }
-    
-    let final_converted = converted.load(Ordering::Relaxed);
-    let final_failed = failed.load(Ordering::Relaxed);
-    
-    // Emit completion
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+
+    let total = stats.converted + stats.failed + stats.skipped;
     let _ = app.emit("bin-convert-progress", serde_json::json!({
         "current": total,
         "total": total,
         "file": "",
         "status": "complete"
     }));
-    
-    tracing::info!("Pre-converted {} BIN files ({} failed, {} skipped)", 
-        final_converted, final_failed, total - to_convert_count);
-    Ok(final_converted)
+
+    Ok(stats.converted)
 }
 
-/// Synchronous helper function to convert a single BIN file to ritobin
-/// Used by parallel processing (rayon doesn't work well with async)
-fn convert_bin_file_sync(bin_path: &str) -> Result<(), String> {
-    use std::fs;
-    use crate::core::bin::{read_bin_ltk, tree_to_text_cached, MAX_BIN_SIZE};
-    
-    // Check file size before reading to avoid loading huge corrupt files
-    let metadata = fs::metadata(bin_path)
-        .map_err(|e| format!("Failed to get file metadata for '{}': {}", bin_path, e))?;
-    
-    let file_size = metadata.len() as usize;
-    
-    // Reject suspiciously large files (using the same limit as ltk_bridge)
-    if file_size > MAX_BIN_SIZE {
-        return Err(format!(
-            "BIN file too large ({} bytes, max {} bytes) - likely corrupt, skipping: {}",
-            file_size, MAX_BIN_SIZE, bin_path
+/// Options of `convert_project_bins`
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct BinConvertOptions {
+    /// Convert BINs whose converted file is already newer
+    #[serde(default)]
+    pub force: bool,
+    #[serde(default)]
+    pub format: BinOutputFormat,
+}
+
+/// Convert every BIN under the project's content layers to ritobin text
+/// (`.py`) or JSON next to the original, reporting each file
+///
+/// Emits `bin-convert-progress` events as `preconvert_project_bins` does.
+///
+/// # Arguments
+/// * `project_path` - Project folder
+/// * `options` - `force` re-converts up-to-date files, e.g. after a
+///   hashtable update; `format` is "text" (default) or "json"
+#[tauri::command]
+pub async fn convert_project_bins(
+    project_path: String,
+    options: Option<BinConvertOptions>,
+    app: tauri::AppHandle,
+) -> Result<BinConversionReport, CommandError> {
+    let options = options.unwrap_or_default();
+    let path = PathBuf::from(&project_path);
+    if !path.join("content").is_dir() {
+        return Err(CommandError::new(
+            ErrorCode::NotFound,
+            format!("Project has no content folder: {}", project_path),
         ));
     }
-    
-    let data = fs::read(bin_path)
-        .map_err(|e| format!("Failed to read file '{}': {}", bin_path, e))?;
 
-    let bin = read_bin_ltk(&data)
-        .map_err(|e| format!("Failed to parse bin file '{}': {}", bin_path, e))?;
+    let _ = app.emit("bin-convert-progress", serde_json::json!({
+        "current": 0,
+        "total": 0,
+        "file": "",
+        "status": "starting"
+    }));
+    let progress_app = app.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        convert_bins(&path, options.format, options.force, |done, total, file| {
+            let _ = progress_app.emit("bin-convert-progress", serde_json::json!({
+                "current": done,
+                "total": total,
+                "file": file,
+                "status": "converting"
+            }));
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
 
-    // Use cached hash resolution for performance
-    let text = tree_to_text_cached(&bin)
-        .map_err(|e| format!("Failed to convert to text for '{}': {}", bin_path, e))?;
+    let total = report.files.len();
+    let _ = app.emit("bin-convert-progress", serde_json::json!({
+        "current": total,
+        "total": total,
+        "file": "",
+        "status": "complete"
+    }));
+    Ok(report)
+}
 
-    let ritobin_path = format!("{}.ritobin", bin_path);
-    fs::write(&ritobin_path, &text)
-        .map_err(|e| format!("Failed to write ritobin '{}': {}", ritobin_path, e))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
-}
+    #[tokio::test]
+    async fn test_skin_name_lookup_is_skipped_offline() {
+        let temp = tempfile::tempdir().unwrap();
+        let settings = SettingsState::new();
+        settings.update(&serde_json::json!({ "offline_mode": true })).unwrap();
 
+        // Fails at once with the typed error rather than after a network timeout
+        let started = std::time::Instant::now();
+        let error = apply_skin_name(&settings, temp.path().to_path_buf(), "Ahri", 85).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::OfflineMode);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,56 @@
+//! Tauri commands for reading and updating application settings
+
+use crate::core::parallel;
+use crate::core::settings::AppSettings;
+use crate::error::CommandError;
+use crate::state::SettingsState;
+use tauri::{AppHandle, Emitter, State};
+
+/// Returns the current application settings
+#[tauri::command]
+pub async fn get_settings(settings: State<'_, SettingsState>) -> Result<AppSettings, CommandError> {
+    Ok(settings.get())
+}
+
+/// Apply a partial settings update and persist it
+///
+/// # Arguments
+/// * `patch` - Fields to change; `null` resets a field to its default
+///
+/// Emits `settings-changed` with the new settings when anything changed.
+#[tauri::command]
+pub async fn update_settings(
+    patch: serde_json::Value,
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+) -> Result<AppSettings, CommandError> {
+    match settings.update(&patch)? {
+        Some(updated) => {
+            tracing::info!("Settings updated");
+            parallel::configure(&updated.performance);
+            let _ = app.emit("settings-changed", &updated);
+            Ok(updated)
+        }
+        None => Ok(settings.get()),
+    }
+}
+
+/// Turn offline mode on or off and persist it
+///
+/// While on, the startup hash update is skipped and network commands fail
+/// with `OFFLINE_MODE`. Emits `settings-changed` when it changed.
+#[tauri::command]
+pub async fn set_offline_mode(
+    enabled: bool,
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+) -> Result<AppSettings, CommandError> {
+    match settings.update(&serde_json::json!({ "offline_mode": enabled }))? {
+        Some(updated) => {
+            tracing::info!("Offline mode {}", if enabled { "on" } else { "off" });
+            let _ = app.emit("settings-changed", &updated);
+            Ok(updated)
+        }
+        None => Ok(settings.get()),
+    }
+}
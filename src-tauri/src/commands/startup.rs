@@ -0,0 +1,69 @@
+//! Startup summary sent to the frontend once the app is initialized
+
+use crate::core::hash::DownloadStats;
+use crate::core::settings::AppSettings;
+use crate::core::startup::{recent_project_statuses, LeagueSummary, Pending, RecentProjectStatus, StartupState};
+use crate::error::CommandError;
+use crate::state::{HashtableLoadState, HashtableState, SettingsState};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HashSummary {
+    pub dir: Option<PathBuf>,
+    pub state: HashtableLoadState,
+    pub loaded_count: usize,
+    /// Result of the startup check for newer hash files
+    pub update: Pending<DownloadStats>,
+}
+
+/// Everything the frontend needs on load, in one payload
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupSummary {
+    pub version: String,
+    pub settings: AppSettings,
+    pub hashes: HashSummary,
+    pub league: Pending<LeagueSummary>,
+    pub recent_projects: Vec<RecentProjectStatus>,
+}
+
+fn build_summary(
+    startup: &StartupState,
+    settings: &SettingsState,
+    hashtable: &HashtableState,
+) -> StartupSummary {
+    let settings = settings.get();
+    StartupSummary {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        hashes: HashSummary {
+            dir: hashtable.hash_dir(),
+            state: hashtable.load_state(),
+            loaded_count: hashtable.len(),
+            update: startup.hash_update(),
+        },
+        league: startup.league(),
+        recent_projects: recent_project_statuses(&settings.recent_projects),
+        settings,
+    }
+}
+
+/// Emit the current summary as `event` (`app-ready` once, then `startup-updated`)
+pub fn emit_startup_summary(app: &AppHandle, event: &str) {
+    let summary = build_summary(
+        &app.state::<StartupState>(),
+        &app.state::<SettingsState>(),
+        &app.state::<HashtableState>(),
+    );
+    let _ = app.emit(event, summary);
+}
+
+/// The startup summary for views that subscribed after `app-ready` fired
+#[tauri::command]
+pub async fn get_startup_summary(
+    startup: State<'_, StartupState>,
+    settings: State<'_, SettingsState>,
+    hashtable: State<'_, HashtableState>,
+) -> Result<StartupSummary, CommandError> {
+    Ok(build_summary(&startup, &settings, &hashtable))
+}
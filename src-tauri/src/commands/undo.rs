@@ -0,0 +1,41 @@
+//! Tauri commands for the session undo journal
+
+use crate::core::operation::OperationRegistry;
+use crate::core::project::ensure_writable;
+use crate::core::undo::{UndoEntryInfo, UndoJournal};
+use crate::error::{CommandError, Error};
+use std::path::PathBuf;
+use tauri::State;
+
+/// Revert the project's most recent small edit (BIN save, project settings).
+/// Returns the undone entry, or null if there was nothing to undo.
+#[tauri::command]
+pub async fn undo_last(
+    project_path: String,
+    undo: State<'_, UndoJournal>,
+    registry: State<'_, OperationRegistry>,
+) -> Result<Option<UndoEntryInfo>, CommandError> {
+    // Writing old bytes back under a running repath or restore would mix states
+    if let Some(holder) = registry.busy_status(&project_path).operation {
+        return Err(Error::Busy(format!("{} {} is running", holder.kind, holder.id)).into());
+    }
+
+    let journal = undo.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let project_path = PathBuf::from(project_path);
+        ensure_writable(&project_path)?;
+        journal.undo_last(&project_path)
+    })
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(CommandError::from)
+}
+
+/// The project's undoable edits this session, most recent first
+#[tauri::command]
+pub async fn get_undo_history(
+    project_path: String,
+    undo: State<'_, UndoJournal>,
+) -> Result<Vec<UndoEntryInfo>, CommandError> {
+    Ok(undo.history(&PathBuf::from(project_path)))
+}
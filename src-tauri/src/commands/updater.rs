@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::path::PathBuf;
+#[cfg(target_os = "windows")]
 use std::process::Command;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, State};
+use crate::core::operation::{OperationKind, OperationRegistry};
+use crate::error::{CommandError, ErrorCode};
+use crate::state::SettingsState;
 
 const GITHUB_OWNER: &str = "RitoShark";
 const GITHUB_REPO: &str = "Flint";
@@ -21,6 +25,7 @@ pub struct UpdateInfo {
 pub struct DownloadProgress {
     pub downloaded: u64,
     pub total: u64,
+    pub operation_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,7 +48,8 @@ pub fn get_current_version() -> String {
 }
 
 #[tauri::command]
-pub async fn check_for_updates() -> Result<UpdateInfo, String> {
+pub async fn check_for_updates(settings: State<'_, SettingsState>) -> Result<UpdateInfo, CommandError> {
+    settings.ensure_online()?;
     let current_version = get_current_version();
 
     let client = reqwest::Client::new();
@@ -58,7 +64,7 @@ pub async fn check_for_updates() -> Result<UpdateInfo, String> {
         .header("Accept", "application/vnd.github.v3+json")
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch releases: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Network, format!("Failed to fetch releases: {}", e)))?;
 
     if response.status() == 404 {
         return Ok(UpdateInfo {
@@ -72,7 +78,7 @@ pub async fn check_for_updates() -> Result<UpdateInfo, String> {
     }
 
     if !response.status().is_success() {
-        return Err(format!("GitHub API error: {}", response.status()));
+        return Err(CommandError::new(ErrorCode::Network, format!("GitHub API error: {}", response.status())));
     }
 
     let release: GitHubRelease = response
@@ -124,11 +130,17 @@ pub async fn check_for_updates() -> Result<UpdateInfo, String> {
 pub async fn download_and_install_update(
     app: AppHandle,
     download_url: String,
-) -> Result<(), String> {
+    operations: State<'_, OperationRegistry>,
+    settings: State<'_, SettingsState>,
+) -> Result<(), CommandError> {
+    settings.ensure_online()?;
     if download_url.is_empty() {
-        return Err("No download URL provided".to_string());
+        return Err(CommandError::new(ErrorCode::InvalidInput, "No download URL provided"));
     }
 
+    let operation = operations.start(OperationKind::Download, None);
+    let cancel = operation.token();
+
     tracing::info!("Downloading update from: {}", download_url);
 
     let client = reqwest::Client::new();
@@ -137,10 +149,10 @@ pub async fn download_and_install_update(
         .header("User-Agent", format!("Flint/{}", get_current_version()))
         .send()
         .await
-        .map_err(|e| format!("Failed to download update: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Network, format!("Failed to download update: {}", e)))?;
 
     if !response.status().is_success() {
-        return Err(format!("Download failed: {}", response.status()));
+        return Err(CommandError::new(ErrorCode::Network, format!("Download failed: {}", response.status())));
     }
 
     // Get total size from Content-Length header
@@ -158,15 +170,22 @@ pub async fn download_and_install_update(
     // Stream download with real progress events
     let mut downloaded: u64 = 0;
     let mut file = std::fs::File::create(&installer_path)
-        .map_err(|e| format!("Failed to create installer file: {}", e))?;
+        .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to create installer file: {}", e)))?;
 
     let mut stream = response.bytes_stream();
     use futures::StreamExt;
 
     while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| format!("Failed to read download chunk: {}", e))?;
+        if cancel.is_cancelled() {
+            drop(file);
+            let _ = std::fs::remove_file(&installer_path);
+            tracing::info!("Update download cancelled");
+            return Err(crate::error::Error::Cancelled.into());
+        }
+        let chunk = chunk_result
+            .map_err(|e| CommandError::new(ErrorCode::Network, format!("Failed to read download chunk: {}", e)))?;
         file.write_all(&chunk)
-            .map_err(|e| format!("Failed to write installer: {}", e))?;
+            .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to write installer: {}", e)))?;
 
         downloaded += chunk.len() as u64;
 
@@ -174,7 +193,11 @@ pub async fn download_and_install_update(
         let _ = app.emit("update-download-progress", DownloadProgress {
             downloaded,
             total: total_size,
+            operation_id: operation.id().to_string(),
         });
+        if total_size > 0 {
+            operation.set_progress(downloaded as f32 / total_size as f32, "Downloading update");
+        }
     }
 
     tracing::info!("Update downloaded to: {}", installer_path.display());
@@ -183,16 +206,17 @@ pub async fn download_and_install_update(
     {
         Command::new(&installer_path)
             .spawn()
-            .map_err(|e| format!("Failed to launch installer: {}", e))?;
+            .map_err(|e| CommandError::new(ErrorCode::Io, format!("Failed to launch installer: {}", e)))?;
+
+        operation.finish(&Ok::<(), String>(()));
+        tracing::info!("Exiting for update...");
+        app.exit(0);
+
+        Ok(())
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        return Err("Auto-update is only supported on Windows".to_string());
+        Err("Auto-update is only supported on Windows".into())
     }
-
-    tracing::info!("Exiting for update...");
-    app.exit(0);
-
-    Ok(())
 }
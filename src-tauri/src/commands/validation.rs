@@ -2,24 +2,123 @@
 //!
 //! These commands expose asset validation functionality to the frontend.
 
+use crate::core::diagnostics::{save_project_report, VALIDATION_REPORT_FILE};
+use crate::core::operation::{OperationKind, OperationRegistry, PhaseTimer};
+use crate::core::project::open_project;
+use crate::core::bin::{get_cached_bin_hashes, text_to_tree};
 use crate::core::validation::{
-    extract_asset_references as core_extract_references,
-    validate_assets as core_validate_assets,
-    AssetReference, ValidationReport,
+    available_path_hashes, bin_references, check_map_vfx, check_texture_overrides,
+    extract_asset_references as core_extract_references, project_references, validate_assets as core_validate_assets,
+    validate_reference_groups, AssetReference, BinReferences, MapVfxWarning, ObjectReferences, TextureOverrideWarning,
+    ValidationReport,
 };
+use crate::core::wad::list_client_wads;
+use crate::error::{self, CommandError, ErrorCode};
+use crate::state::{HashtableState, SettingsState};
+use serde::Serialize;
 use std::collections::HashSet;
+use std::convert::Infallible;
+use std::path::PathBuf;
+use tauri::State;
 
 /// Extract asset references from BIN content
 ///
 /// # Arguments
 /// * `content` - BIN file content in text format
+/// * `paths_only` - Return the flat, deduplicated list of paths instead of grouping by object
 ///
 /// # Returns
-/// * `Vec<AssetReference>` - List of found asset references
+/// * `AssetReferences` - References grouped by owning object, or the flat list
 #[tauri::command]
-pub fn extract_asset_references(content: String) -> Vec<AssetReference> {
+pub fn extract_asset_references(content: String, paths_only: Option<bool>) -> Result<AssetReferences, CommandError> {
     tracing::debug!("Frontend requested asset reference extraction");
-    core_extract_references(&content)
+    if paths_only.unwrap_or(false) {
+        return Ok(AssetReferences::Paths(core_extract_references(&content)));
+    }
+
+    let tree = text_to_tree(&content)
+        .map_err(|e| CommandError::new(ErrorCode::Parse, format!("Failed to parse text content: {}", e)))?;
+    let hashes = get_cached_bin_hashes().read();
+    Ok(AssetReferences::Grouped(bin_references(&tree, &*hashes)))
+}
+
+/// Result of `extract_asset_references`
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AssetReferences {
+    Grouped(Vec<ObjectReferences>),
+    Paths(Vec<AssetReference>),
+}
+
+/// List the asset references of every BIN in a project, by file and object
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+#[tauri::command]
+pub async fn find_project_references(project_path: String) -> Result<Vec<BinReferences>, CommandError> {
+    tracing::info!("Frontend requested asset references for: {}", project_path);
+
+    tokio::task::spawn_blocking(move || -> error::Result<Vec<BinReferences>> {
+        let project = open_project(&PathBuf::from(&project_path))?;
+        let hashes = get_cached_bin_hashes().read();
+        Ok(project_references(&project.assets_path(), &*hashes))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Check that every file the project's BINs reference is in the project or
+/// the game, naming the file, object and field of each missing reference
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `league_path` - Installation to check against; defaults to the project's, then the settings'
+#[tauri::command]
+pub async fn validate_project_assets(
+    project_path: String,
+    league_path: Option<String>,
+    settings: State<'_, SettingsState>,
+    operations: State<'_, OperationRegistry>,
+) -> Result<ValidationReport, CommandError> {
+    tracing::info!("Frontend requested asset validation for: {}", project_path);
+
+    let operation = operations.start_uncancellable(OperationKind::Validation, Some(&project_path));
+    let settings_league = settings.get().league_path;
+    let span = operation.span();
+    let result = tokio::task::spawn_blocking(move || -> error::Result<ValidationReport> {
+        let _span = span.enter();
+        let mut timer = PhaseTimer::new();
+        let project = open_project(&PathBuf::from(&project_path))?;
+        let league_path = league_path
+            .map(PathBuf::from)
+            .or(project.league_path.clone())
+            .or(settings_league)
+            .ok_or_else(|| error::Error::InvalidInput("No League installation is configured for this project.".to_string()))?;
+        let content_base = project.assets_path();
+
+        timer.phase("scan");
+        let groups = project_references(&content_base, &*get_cached_bin_hashes().read());
+        timer.phase("index");
+        let available = available_path_hashes(&content_base, &list_client_wads(&league_path))?;
+        timer.phase("check");
+        let mut report = validate_reference_groups(&groups, &available);
+
+        report.timings = timer.finish();
+        if let Err(e) = save_project_report(&project.project_path, VALIDATION_REPORT_FILE, &report) {
+            tracing::warn!("Failed to save the validation report: {}", e);
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from);
+
+    if let Ok(report) = &result {
+        operation.set_timings(&report.timings);
+    }
+    operation.finish(&result);
+    result
 }
 
 /// Validate asset references against available hashes
@@ -36,9 +135,81 @@ pub fn validate_assets(
     references: Vec<AssetReference>,
     available_hashes: Vec<u64>,
     source_file: String,
+    operations: State<'_, OperationRegistry>,
 ) -> ValidationReport {
     tracing::info!("Frontend requested validation of {} references", references.len());
-    
+    let operation = operations.start_uncancellable(OperationKind::Validation, None);
+    let mut timer = PhaseTimer::new();
+
+    timer.phase("index");
     let hash_set: HashSet<u64> = available_hashes.into_iter().collect();
-    core_validate_assets(&references, &hash_set, &source_file)
+    timer.phase("check");
+    let mut report = operation.span().in_scope(|| core_validate_assets(&references, &hash_set, &source_file));
+
+    report.timings = timer.finish();
+    operation.set_timings(&report.timings);
+    operation.finish(&Ok::<_, Infallible>(()));
+    report
+}
+
+/// Compare the project's textures with the game textures they replace and
+/// report format, colorspace or alpha usage differences
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `league_path` - Installation to compare against; defaults to the project's, then the settings'
+#[tauri::command]
+pub async fn validate_texture_overrides(
+    project_path: String,
+    league_path: Option<String>,
+    settings: State<'_, SettingsState>,
+) -> Result<Vec<TextureOverrideWarning>, CommandError> {
+    tracing::info!("Frontend requested texture override validation for: {}", project_path);
+
+    let settings_league = settings.get().league_path;
+    tokio::task::spawn_blocking(move || -> error::Result<Vec<TextureOverrideWarning>> {
+        let project = open_project(&PathBuf::from(&project_path))?;
+        let league_path = league_path
+            .map(PathBuf::from)
+            .or(project.league_path.clone())
+            .or(settings_league)
+            .ok_or_else(|| error::Error::InvalidInput("No League installation is configured for this project.".to_string()))?;
+        check_texture_overrides(&project.assets_path(), &list_client_wads(&league_path))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Report the project's VFX systems that Summoner's Rift data defines or
+/// links to but the export won't carry there
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory
+/// * `league_path` - Installation whose map WAD is read; defaults to the project's, then the settings'.
+///   Without one, only the project's own map BINs are checked.
+#[tauri::command]
+pub async fn validate_map_vfx(
+    project_path: String,
+    league_path: Option<String>,
+    settings: State<'_, SettingsState>,
+    hashtable: State<'_, HashtableState>,
+) -> Result<Vec<MapVfxWarning>, CommandError> {
+    tracing::info!("Frontend requested map VFX validation for: {}", project_path);
+
+    let settings_league = settings.get().league_path;
+    let wad_hashes = hashtable.get_hashtable();
+    tokio::task::spawn_blocking(move || -> error::Result<Vec<MapVfxWarning>> {
+        let project = open_project(&PathBuf::from(&project_path))?;
+        let installed_wads = league_path
+            .map(PathBuf::from)
+            .or(project.league_path.clone())
+            .or(settings_league)
+            .map(|league_path| list_client_wads(&league_path))
+            .unwrap_or_default();
+        check_map_vfx(&project.assets_path(), &installed_wads, wad_hashes.as_deref(), &*get_cached_bin_hashes().read())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
 }
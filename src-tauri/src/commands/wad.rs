@@ -1,15 +1,40 @@
-use crate::core::wad::extractor::{extract_all, extract_chunk};
-use crate::core::wad::reader::WadReader;
-use crate::state::HashtableState;
+use crate::core::blob::{BinaryPayload, BlobStore};
+use crate::core::hash::{GuessReport, Hashtable};
+use crate::core::parallel;
+use crate::core::operation::{OperationHandle, OperationKind, OperationRegistry, PhaseTimer, PhaseTiming};
+use crate::core::project::{find_project_root, open_project};
+use crate::core::wad::extractor::{
+    chunk_fingerprint, extract_chunks, plan_extraction, write_unknown_hashes, ExtractProgress, PlannedFile, UnknownHash,
+};
+use crate::core::wad::manifest::{ChunkChange, ExtractManifest};
+use crate::core::wad::diff::WadDiff;
+use crate::core::wad::chunk_kind::sniff_chunk_kind;
+use crate::core::wad::reader::{compression_name, WadReader, WadSource};
+use crate::core::wad::skin::{skin_chunks, SkinChunks};
+use crate::core::wad::search::{find_in_wads as search_wads, WadQuery, WadScanStats, WadSearchHit};
+use crate::core::wad::verify::{verify_wad as verify_wad_chunks, WadVerification};
+use crate::core::wad::writer::{repack_wad as repack_wad_folder, WadRepackStats};
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::mount::champion_wad_paths;
+use crate::core::wad::{
+    locate_game_path as locate_path, ChunkFilter, ChunkKind, GamePathLocation, WadKind, WadMountRegistry, WadMountSet,
+    WadName, WadTocCache,
+};
+use crate::error::{CommandError, Error, ErrorCode};
+use crate::state::{HashtableState, SettingsState, HASHTABLE_WAIT};
+use league_toolkit::wad::WadChunk;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::io::{BufWriter, Write};
+use tauri::{AppHandle, Emitter, State};
 use walkdir::WalkDir;
 
 /// Information about a WAD archive
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WadInfo {
     pub path: String,
+    /// Version from the header, e.g. "3.3"
+    pub version: String,
     pub chunk_count: usize,
 }
 
@@ -19,6 +44,18 @@ pub struct ChunkInfo {
     pub hash: String,
     pub path: Option<String>,
     pub size: u32,
+    pub compressed_size: u32,
+    /// "none", "gzip", "satellite", "zstd" or "zstd_multi"
+    pub compression: String,
+    /// xxh3 checksum from the table of contents as 16 hex digits, all zeros
+    /// before WAD v3
+    pub checksum: String,
+    /// Stored as zstd subchunks
+    pub subchunked: bool,
+    /// Shares its data offset with another chunk
+    pub is_duplicate: bool,
+    /// From the resolved path's extension, or the data's magic
+    pub kind: ChunkKind,
 }
 
 /// Result of a WAD extraction operation
@@ -26,6 +63,55 @@ pub struct ChunkInfo {
 pub struct ExtractionResult {
     pub extracted_count: usize,
     pub failed_count: usize,
+    /// Chunks selected: the given hashes, the skin's or the whole WAD,
+    /// narrowed by `filters`
+    pub matched_count: usize,
+    /// Chunks `filters` left out
+    pub skipped_count: usize,
+    /// Extracted chunks the hashtable doesn't know, saved under their hash
+    pub unknown_hashes: Vec<UnknownHash>,
+    /// Decompressed bytes written
+    pub bytes_written: u64,
+    /// Stopped by `cancel_extraction`; the counts cover what was finished
+    pub cancelled: bool,
+    /// Chunks not extracted into the folder before, or whose file is gone.
+    /// This and the next three counts compare with the project's extraction
+    /// manifest and stay 0 outside a project.
+    pub added_count: usize,
+    /// Chunks whose content changed since the last extraction
+    pub updated_count: usize,
+    /// Chunks with the same content as last time; not written again when
+    /// extracting incrementally
+    pub unchanged_count: usize,
+    /// Unchanged chunks whose file was edited since it was extracted
+    pub edited_count: usize,
+    /// Time spent in each phase, in order
+    pub timings: Vec<PhaseTiming>,
+    /// BINs read and referenced paths missing from the WAD, when extracting a skin
+    pub skin: Option<SkinChunks>,
+}
+
+/// What `extract_wad` would write, from `preview_extraction`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionPreview {
+    /// Destination of each chunk, sorted by path
+    pub files: Vec<PlannedFile>,
+    /// Decompressed bytes of `files`
+    pub total_size: u64,
+    /// Files saved under their hash because the hashtable doesn't know them
+    pub unknown_count: usize,
+    pub matched_count: usize,
+    pub skipped_count: usize,
+    /// Selected chunks missing from the WAD or that would fail to extract
+    pub failed_count: usize,
+    pub skin: Option<SkinChunks>,
+}
+
+/// Skin of a champion WAD to extract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkinSelection {
+    pub champion: String,
+    pub skin_id: u32,
 }
 
 /// Opens a WAD file and returns metadata about it
@@ -34,65 +120,122 @@ pub struct ExtractionResult {
 /// * `path` - Path to the WAD file
 /// 
 /// # Returns
-/// * `Result<WadInfo, String>` - WAD metadata or error message
+/// * `Result<WadInfo, CommandError>` - WAD metadata or error message
 /// 
 /// # Requirements
 /// Validates: Requirements 3.1
 #[tauri::command]
-pub async fn read_wad(path: String) -> Result<WadInfo, String> {
+pub async fn read_wad(path: String) -> Result<WadInfo, CommandError> {
     let reader = WadReader::open(&path)?;
     
+    let (major, minor) = reader.version();
     Ok(WadInfo {
         path,
+        version: format!("{}.{}", major, minor),
         chunk_count: reader.chunk_count(),
     })
 }
 
+/// Order of chunk listings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkSort {
+    /// Resolved paths A-Z (case-insensitive), then unresolved chunks by hash
+    #[default]
+    Path,
+    Hash,
+    /// Largest first
+    Size,
+}
+
+/// Chunk metadata for the WAD at `wad_path` in `sort` order. The hash is the
+/// chunk's stable ID; ties are broken by it so the order never depends on the
+/// WAD's hash map. Chunks the path doesn't give a kind for are sniffed.
+fn chunk_infos(
+    wad_path: &str,
+    reader: &mut WadReader,
+    hashtable: Option<&Hashtable>,
+    sort: ChunkSort,
+) -> Vec<ChunkInfo> {
+    let mut source: Option<Option<WadSource>> = None;
+    let mut chunk_infos: Vec<ChunkInfo> = reader
+        .toc()
+        .into_iter()
+        .map(|entry| {
+            let resolved = hashtable.and_then(|ht| {
+                let r = ht.resolve(entry.path_hash);
+                // Hex-only 16-char strings are unknown hashes — treat as None
+                if r.len() == 16 && r.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    None
+                } else {
+                    Some(r.to_string())
+                }
+            });
+            let kind = resolved.as_deref().and_then(ChunkKind::from_path).unwrap_or_else(|| {
+                let chunk = reader.get_chunk(entry.path_hash).copied();
+                let source = source.get_or_insert_with(|| WadSource::open(wad_path).ok());
+                match (source.as_mut(), chunk) {
+                    (Some(source), Some(chunk)) => sniff_chunk_kind(source, reader, &chunk),
+                    _ => ChunkKind::Other,
+                }
+            });
+            ChunkInfo {
+                hash: format!("{:016x}", entry.path_hash),
+                path: resolved,
+                size: entry.uncompressed_size as u32,
+                compressed_size: entry.compressed_size as u32,
+                compression: compression_name(entry.compression).to_string(),
+                checksum: format!("{:016x}", entry.checksum),
+                subchunked: entry.is_subchunked(),
+                is_duplicate: entry.is_duplicate,
+                kind,
+            }
+        })
+        .collect();
+    sort_chunks(&mut chunk_infos, sort);
+    chunk_infos
+}
+
+fn sort_chunks(chunks: &mut [ChunkInfo], sort: ChunkSort) {
+    match sort {
+        ChunkSort::Path => chunks.sort_by_cached_key(|c| {
+            (c.path.is_none(), c.path.as_ref().map(|p| p.to_lowercase()), c.hash.clone())
+        }),
+        ChunkSort::Hash => chunks.sort_by(|a, b| a.hash.cmp(&b.hash)),
+        ChunkSort::Size => chunks.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.hash.cmp(&b.hash))),
+    }
+}
+
 /// Returns a list of all chunks in a WAD archive with resolved paths
 /// 
 /// # Arguments
 /// * `path` - Path to the WAD file
+/// * `sort` - Result order (default: by path)
+/// * `kind` - Only list chunks of this kind (default: all)
 /// * `state` - Hashtable state for path resolution
 /// 
 /// # Returns
-/// * `Result<Vec<ChunkInfo>, String>` - List of chunk information or error message
+/// * `Result<Vec<ChunkInfo>, CommandError>` - List of chunk information or error message
 /// 
 /// # Requirements
 /// Validates: Requirements 3.2, 3.3, 3.4
 #[tauri::command]
 pub async fn get_wad_chunks(
     path: String,
+    sort: Option<ChunkSort>,
+    kind: Option<ChunkKind>,
     state: State<'_, HashtableState>,
-) -> Result<Vec<ChunkInfo>, String> {
-    let reader = WadReader::open(&path)?;
-    let chunks = reader.chunks();
+) -> Result<Vec<ChunkInfo>, CommandError> {
+    let mut reader = WadReader::open(&path)?;
     
     // Get hashtable for path resolution (lazy loaded on first use)
-    let hashtable = state.get_hashtable();
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
     
-    let mut chunk_infos = Vec::new();
-    
-    for (path_hash, chunk) in chunks.iter() {
-        let resolved_path = if let Some(ref ht) = hashtable {
-            let resolved = ht.resolve(*path_hash);
-            // Only include as resolved if it's not a hex fallback
-            if !resolved.starts_with(|c: char| c.is_ascii_hexdigit()) || resolved.len() != 16 {
-                Some(resolved.to_string())
-            } else {
-                None
-            }
-        } else {
-            None
-        };
-        
-        chunk_infos.push(ChunkInfo {
-            hash: format!("{:016x}", path_hash),
-            path: resolved_path,
-            size: chunk.uncompressed_size() as u32,
-        });
+    let mut chunks = chunk_infos(&path, &mut reader, Some(hashtable.as_ref()), sort.unwrap_or_default());
+    if let Some(kind) = kind {
+        chunks.retain(|chunk| chunk.kind == kind);
     }
-    
-    Ok(chunk_infos)
+    Ok(chunks)
 }
 
 /// Result of loading one WAD in a batch operation
@@ -112,46 +255,35 @@ pub struct WadChunkBatch {
 /// - A single IPC round-trip instead of N
 /// - WADs are read in parallel via rayon
 /// - One combined JSON serialization
+///
+/// Batches come back in the order of `paths`; chunks within each are in
+/// `sort` order (default: by path).
 #[tauri::command]
 pub async fn load_all_wad_chunks(
     paths: Vec<String>,
+    sort: Option<ChunkSort>,
     state: State<'_, HashtableState>,
-) -> Result<Vec<WadChunkBatch>, String> {
+) -> Result<Vec<WadChunkBatch>, CommandError> {
     // Clone the Arc so we can move it into the rayon closure
-    let hashtable = state.get_hashtable();
-
-    let batches: Vec<WadChunkBatch> = paths
-        .par_iter()
-        .map(|wad_path| {
-            let result: Result<Vec<ChunkInfo>, String> = (|| {
-                let reader = WadReader::open(wad_path).map_err(|e| e.to_string())?;
-                let chunks = reader.chunks();
-                let mut chunk_infos = Vec::with_capacity(chunks.len());
-                for (path_hash, chunk) in chunks.iter() {
-                    let resolved = hashtable.as_ref().and_then(|ht| {
-                        let r = ht.resolve(*path_hash);
-                        // Hex-only 16-char strings are unknown hashes — treat as None
-                        if r.len() == 16 && r.bytes().all(|b| b.is_ascii_hexdigit()) {
-                            None
-                        } else {
-                            Some(r.to_string())
-                        }
-                    });
-                    chunk_infos.push(ChunkInfo {
-                        hash: format!("{:016x}", path_hash),
-                        path: resolved,
-                        size: chunk.uncompressed_size() as u32,
-                    });
-                }
-                Ok(chunk_infos)
-            })();
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
+    let sort = sort.unwrap_or_default();
 
-            match result {
-                Ok(chunks) => WadChunkBatch { path: wad_path.clone(), chunks, error: None },
-                Err(e) => WadChunkBatch { path: wad_path.clone(), chunks: vec![], error: Some(e) },
-            }
-        })
-        .collect();
+    let batches: Vec<WadChunkBatch> = parallel::install(|| {
+        paths
+            .par_iter()
+            .map(|wad_path| {
+                let _io = parallel::io_permit();
+                match WadReader::open(wad_path) {
+                    Ok(mut reader) => WadChunkBatch {
+                        path: wad_path.clone(),
+                        chunks: chunk_infos(wad_path, &mut reader, Some(hashtable.as_ref()), sort),
+                        error: None,
+                    },
+                    Err(e) => WadChunkBatch { path: wad_path.clone(), chunks: vec![], error: Some(e.to_string()) },
+                }
+            })
+            .collect()
+    });
 
     Ok(batches)
 }
@@ -162,77 +294,741 @@ pub async fn load_all_wad_chunks(
 /// * `wad_path` - Path to the WAD file
 /// * `output_dir` - Directory where chunks should be extracted
 /// * `chunk_hashes` - Optional list of chunk hashes to extract (None = extract all)
+/// * `skin` - Extract only the chunks this skin references: its BIN, the BINs
+///   it links or names, and the assets they reference, base skin assets
+///   included. Can't be combined with `chunk_hashes`.
+/// * `filters` - Glob patterns the resolved paths must match (e.g. `*.dds`);
+///   chunks with unknown hashes are only extracted with the `unknown` filter
+/// * `incremental` - Only write chunks that are new or changed since the last
+///   extraction into the folder; `output_dir` must be inside a project
 /// * `state` - Hashtable state for path resolution
+/// * `operations` - Registry the extraction is tracked (and cancelled) through
+///
+/// `wad-extract-progress` events report the chunks done, bytes written and
+/// the path last finished. `cancel_extraction` stops the extraction early: a
+/// file being written is removed and the result comes back with `cancelled`.
 /// 
 /// # Returns
-/// * `Result<ExtractionResult, String>` - Extraction statistics or error message
+/// * `Result<ExtractionResult, CommandError>` - Extraction statistics or error message
 /// 
 /// # Requirements
 /// Validates: Requirements 4.1, 4.2, 4.3, 4.4
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn extract_wad(
+    app: AppHandle,
     wad_path: String,
     output_dir: String,
     chunk_hashes: Option<Vec<String>>,
+    skin: Option<SkinSelection>,
+    filters: Option<Vec<String>>,
+    incremental: Option<bool>,
     state: State<'_, HashtableState>,
-) -> Result<ExtractionResult, String> {
-    let mut reader = WadReader::open(&wad_path)?;
-    
+    operations: State<'_, OperationRegistry>,
+) -> Result<ExtractionResult, CommandError> {
+    let filter = filters.as_deref().map(ChunkFilter::new).transpose()?;
+    let operation = operations.start(OperationKind::Extraction, None);
+    let _ = app.emit("wad-extract-progress", serde_json::json!({
+        "operation_id": operation.id(),
+        "status": "starting",
+        "wad_path": wad_path,
+    }));
+
     // Get hashtable for path resolution (lazy loaded on first use)
-    let hashtable = state.get_hashtable();
-    let hashtable_ref = hashtable.as_ref().map(|h| h.as_ref());
-    
-    let mut extracted_count = 0;
-    let mut failed_count = 0;
-    
-    if let Some(hashes) = chunk_hashes {
-        // Extract specific chunks
-        for hash_str in hashes {
-            // Parse the hash string
-            let path_hash = u64::from_str_radix(&hash_str, 16)
-                .map_err(|e| format!("Invalid hash format '{}': {}", hash_str, e))?;
-            
-            // Check if the chunk exists and get its data
-            let chunk_exists = reader.get_chunk(path_hash).is_some();
-            
-            if chunk_exists {
-                // Get the chunk again (we need to release the previous borrow)
-                let chunk = reader.get_chunk(path_hash).unwrap();
-                
-                // Resolve the path
-                let resolved_path = if let Some(ht) = hashtable_ref {
-                    ht.resolve(path_hash).to_string()
-                } else {
-                    format!("{:016x}", path_hash)
-                };
-                
-                // Determine output path
-                let output_path = std::path::Path::new(&output_dir).join(&resolved_path);
-
-                // Copy the chunk data we need before borrowing mutably
-                let chunk_copy = *chunk;
-
-                // Extract the chunk
-                match extract_chunk(reader.wad_mut(), &chunk_copy, &output_path, hashtable_ref) {
-                    Ok(_) => extracted_count += 1,
-                    Err(_) => failed_count += 1,
+    let mut timer = PhaseTimer::new();
+    timer.phase("load_hashes");
+    let result = match state.wait_ready(HASHTABLE_WAIT).await {
+        Ok(hashtable) => operation.span().in_scope(|| {
+            let hashtable = Some(hashtable.as_ref());
+            let progress = |progress: &ExtractProgress| {
+                let _ = app.emit("wad-extract-progress", serde_json::json!({
+                    "operation_id": operation.id(),
+                    "status": "extracting",
+                    "wad_path": wad_path,
+                    "done": progress.done,
+                    "total": progress.total,
+                    "bytes_written": progress.bytes_written,
+                    "current_path": progress.current_path,
+                }));
+            };
+            extract_wad_chunks(
+                &wad_path,
+                &output_dir,
+                chunk_hashes,
+                skin.as_ref(),
+                filter.as_ref(),
+                incremental.unwrap_or(false),
+                hashtable,
+                &operation,
+                progress,
+                timer,
+            )
+        }),
+        Err(e) => Err(e.into()),
+    };
+    if let Ok(result) = &result {
+        operation.set_timings(&result.timings);
+    }
+    match &result {
+        Ok(extraction) if extraction.cancelled => operation.finish_cancelled(),
+        _ => operation.finish(&result),
+    }
+    result
+}
+
+/// Request cancellation of a running `extract_wad`, or of every running
+/// extraction without an `operation_id`. Returns how many were cancelled.
+#[tauri::command]
+pub async fn cancel_extraction(
+    operation_id: Option<String>,
+    operations: State<'_, OperationRegistry>,
+) -> Result<usize, CommandError> {
+    match operation_id {
+        Some(id) => Ok(usize::from(operations.cancel(&id)?)),
+        None => Ok(operations.cancel_kind(OperationKind::Extraction)),
+    }
+}
+
+/// Decompress and checksum every chunk of a WAD without writing anything
+///
+/// `wad-verify-progress` events report the chunks checked; the verification
+/// is tracked as a validation operation and stops early through
+/// `cancel_extraction` with its `operation_id`.
+///
+/// # Arguments
+/// * `wad_path` - Path to the WAD file
+/// * `state` - Hashtable state for path resolution
+/// * `operations` - Registry the verification is tracked (and cancelled) through
+#[tauri::command]
+pub async fn verify_wad(
+    app: AppHandle,
+    wad_path: String,
+    state: State<'_, HashtableState>,
+    operations: State<'_, OperationRegistry>,
+) -> Result<WadVerification, CommandError> {
+    let operation = operations.start(OperationKind::Validation, None);
+    let _ = app.emit("wad-verify-progress", serde_json::json!({
+        "operation_id": operation.id(),
+        "status": "starting",
+        "wad_path": wad_path,
+    }));
+
+    let result = match state.wait_ready(HASHTABLE_WAIT).await {
+        Ok(hashtable) => operation.span().in_scope(|| {
+            let progress = |done: usize, total: usize| {
+                let _ = app.emit("wad-verify-progress", serde_json::json!({
+                    "operation_id": operation.id(),
+                    "status": "verifying",
+                    "wad_path": wad_path,
+                    "done": done,
+                    "total": total,
+                }));
+            };
+            verify_wad_chunks(
+                std::path::Path::new(&wad_path),
+                Some(hashtable.as_ref()),
+                Some(&operation.token()),
+                progress,
+            )
+            .map_err(CommandError::from)
+        }),
+        Err(e) => Err(e.into()),
+    };
+    match &result {
+        Ok(verification) if verification.cancelled => operation.finish_cancelled(),
+        _ => operation.finish(&result),
+    }
+    result
+}
+
+/// Chunks an extraction picks out of a WAD
+struct ChunkSelection {
+    /// In the WAD, sorted by path hash
+    chunks: Vec<(u64, WadChunk)>,
+    matched_count: usize,
+    skipped_count: usize,
+    /// Selected hashes the WAD doesn't have
+    missing: usize,
+    skin: Option<SkinChunks>,
+}
+
+/// Select the chunks `extract_wad` and `preview_extraction` work on: the
+/// given hashes, the skin's or the whole WAD, narrowed by `filter`
+fn select_chunks(
+    reader: &mut WadReader,
+    wad_path: &str,
+    chunk_hashes: Option<Vec<String>>,
+    skin: Option<&SkinSelection>,
+    filter: Option<&ChunkFilter>,
+    hashtable_ref: Option<&Hashtable>,
+) -> Result<ChunkSelection, CommandError> {
+    let skin = match skin {
+        Some(_) if chunk_hashes.is_some() => {
+            return Err(CommandError::new(ErrorCode::InvalidInput, "Pass either chunk hashes or a skin, not both"));
+        }
+        Some(skin) => Some(skin_chunks(reader, &skin.champion, skin.skin_id)?),
+        None => None,
+    };
+    let mut hashes: Vec<u64> = match (&skin, chunk_hashes) {
+        (Some(skin), _) => skin.hashes.iter().copied().collect(),
+        (None, Some(hashes)) => hashes
+            .iter()
+            .map(|hash| {
+                u64::from_str_radix(hash, 16).map_err(|e| {
+                    CommandError::new(ErrorCode::InvalidInput, format!("Invalid hash format '{}': {}", hash, e))
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        (None, None) => reader.chunks().keys().copied().collect(),
+    };
+    hashes.sort_unstable();
+
+    // A filter narrows the given hashes, or the whole WAD, to the matching chunks
+    let mut skipped_count = 0;
+    if let Some(filter) = filter {
+        let total = hashes.len();
+        hashes.retain(|hash| filter.matches(hashtable_ref.and_then(|ht| ht.get(*hash))));
+        skipped_count = total - hashes.len();
+        if hashes.is_empty() {
+            tracing::warn!("No chunk of {} matched the filters ({} skipped)", wad_path, skipped_count);
+        }
+    }
+    let matched_count = hashes.len();
+
+    // Hashes the WAD doesn't have are left out and counted
+    let chunks: Vec<_> = hashes
+        .iter()
+        .filter_map(|hash| reader.get_chunk(*hash).map(|chunk| (*hash, *chunk)))
+        .collect();
+    let missing = matched_count - chunks.len();
+    Ok(ChunkSelection { chunks, matched_count, skipped_count, missing, skin })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn extract_wad_chunks(
+    wad_path: &str,
+    output_dir: &str,
+    chunk_hashes: Option<Vec<String>>,
+    skin: Option<&SkinSelection>,
+    filter: Option<&ChunkFilter>,
+    incremental: bool,
+    hashtable_ref: Option<&Hashtable>,
+    operation: &OperationHandle,
+    progress: impl Fn(&ExtractProgress) + Sync,
+    mut timer: PhaseTimer,
+) -> Result<ExtractionResult, CommandError> {
+    let cancel = operation.token();
+    timer.phase("open");
+    let mut reader = WadReader::open(wad_path)?;
+    timer.phase("extract");
+
+    let ChunkSelection { chunks, matched_count, skipped_count, missing, skin } =
+        select_chunks(&mut reader, wad_path, chunk_hashes, skin, filter, hashtable_ref)?;
+
+    // Inside a project, chunks are compared with what was extracted there last time
+    let output_path = std::path::Path::new(output_dir);
+    let project_root = find_project_root(output_path);
+    if incremental && project_root.is_none() {
+        return Err(CommandError::new(
+            ErrorCode::InvalidInput,
+            format!("Incremental extraction needs an output folder inside a project: {}", output_dir),
+        ));
+    }
+    // Skipping unchanged chunks needs their fingerprints before extracting.
+    // Otherwise chunks without a table of contents checksum are fingerprinted
+    // by the extraction workers and compared once they're written.
+    let fingerprints: Vec<(u64, Option<u64>)> = chunks
+        .iter()
+        .map(|(hash, chunk)| {
+            let fingerprint = match chunk.checksum() {
+                0 if incremental => chunk_fingerprint(&mut reader, chunk)
+                    .inspect_err(|e| tracing::warn!("Chunk {:016x} will be extracted again: {}", hash, e))
+                    .ok(),
+                0 => None,
+                checksum => Some(checksum),
+            };
+            (*hash, fingerprint)
+        })
+        .collect();
+    drop(reader);
+
+    let mut manifest = match &project_root {
+        Some(root) => ExtractManifest::load(root)?,
+        None => ExtractManifest::default(),
+    };
+    let (mut added_count, mut updated_count, mut unchanged_count, mut edited_count) = (0, 0, 0, 0);
+    let mut count = |change: ChunkChange| match change {
+        ChunkChange::Added => added_count += 1,
+        ChunkChange::Updated => updated_count += 1,
+        ChunkChange::Unchanged { edited } => {
+            unchanged_count += 1;
+            edited_count += usize::from(edited);
+        }
+        ChunkChange::Pending { .. } => {}
+    };
+    let mut pending = Vec::new();
+    let chunks = match &project_root {
+        Some(root) => {
+            let changes = manifest.compare(root, output_path, &fingerprints);
+            let mut changed = Vec::with_capacity(chunks.len());
+            for (chunk, change) in chunks.into_iter().zip(changes) {
+                match change {
+                    ChunkChange::Unchanged { .. } if incremental => {
+                        count(change);
+                        continue;
+                    }
+                    ChunkChange::Pending { .. } => pending.push((chunk.0, change)),
+                    _ => count(change),
                 }
-            } else {
-                failed_count += 1;
+                changed.push(chunk);
             }
+            changed
         }
-    } else {
-        // Extract all chunks
-        match extract_all(reader.wad_mut(), &output_dir, hashtable_ref) {
-            Ok(count) => extracted_count = count,
-            Err(e) => return Err(e.into()),
+        None => chunks,
+    };
+
+    let extraction = extract_chunks(wad_path, &chunks, output_dir, hashtable_ref, Some(&cancel), |update| {
+        let (done, total) = (update.done, update.total);
+        operation.set_progress(done as f32 / total as f32, format!("{}/{} chunks", done, total));
+        progress(update);
+    })?;
+    for (hash, change) in pending {
+        count(change.resolve(extraction.fingerprints.get(&hash).copied()));
+    }
+    if let Some(root) = &project_root {
+        for (hash, path) in &extraction.files {
+            manifest.record(root, output_path, path, *hash, extraction.fingerprints[hash]);
+        }
+        if let Err(e) = manifest.save(root) {
+            tracing::warn!("Failed to save the extraction manifest: {}", e);
+        }
+        if incremental {
+            tracing::info!(
+                "Incremental extraction of {}: {} added, {} updated, {} unchanged ({} edited locally)",
+                wad_path,
+                added_count,
+                updated_count,
+                unchanged_count,
+                edited_count
+            );
         }
     }
-    
+    let extracted_count = extraction.extracted;
+    let failed_count = extraction.failed + missing;
+    let unknown_hashes = extraction.unknown;
+
     Ok(ExtractionResult {
         extracted_count,
         failed_count,
+        matched_count,
+        skipped_count,
+        unknown_hashes,
+        bytes_written: extraction.bytes_written,
+        cancelled: extraction.cancelled,
+        added_count,
+        updated_count,
+        unchanged_count,
+        edited_count,
+        timings: timer.finish(),
+        skin,
+    })
+}
+
+/// Report what `extract_wad` would write, without writing anything
+///
+/// Chunks are selected exactly as `extract_wad` selects them; the result
+/// lists each destination path with its decompressed size. Incremental
+/// extraction isn't taken into account.
+///
+/// # Arguments
+/// * `wad_path` - WAD to preview
+/// * `output_dir` - Folder the extraction would write to
+/// * `chunk_hashes`, `skin`, `filters` - As for `extract_wad`
+#[tauri::command]
+pub async fn preview_extraction(
+    wad_path: String,
+    output_dir: String,
+    chunk_hashes: Option<Vec<String>>,
+    skin: Option<SkinSelection>,
+    filters: Option<Vec<String>>,
+    state: State<'_, HashtableState>,
+) -> Result<ExtractionPreview, CommandError> {
+    let filter = filters.as_deref().map(ChunkFilter::new).transpose()?;
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
+
+    tokio::task::spawn_blocking(move || -> Result<ExtractionPreview, CommandError> {
+        let hashtable = Some(hashtable.as_ref());
+        let mut reader = WadReader::open(&wad_path)?;
+        let selection = select_chunks(&mut reader, &wad_path, chunk_hashes, skin.as_ref(), filter.as_ref(), hashtable)?;
+        drop(reader);
+
+        let plan = plan_extraction(&wad_path, &selection.chunks, &output_dir, hashtable)?;
+        let unknown_count = plan.files.iter().filter(|file| file.unknown).count();
+        Ok(ExtractionPreview {
+            files: plan.files,
+            total_size: plan.total_size,
+            unknown_count,
+            matched_count: selection.matched_count,
+            skipped_count: selection.skipped_count,
+            failed_count: plan.failed + selection.missing,
+            skin: selection.skin,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Write the unknown hashes of an extraction to a text file for submitting
+/// to CommunityDragon
+///
+/// # Arguments
+/// * `hashes` - `unknown_hashes` from one or more extractions
+/// * `output_path` - File to write
+/// * `details` - Add the detected type, size and WAD after each hash
+///
+/// # Returns
+/// * `Ok(usize)` - Number of distinct hashes written
+#[tauri::command]
+pub async fn export_unknown_hashes(
+    hashes: Vec<UnknownHash>,
+    output_path: String,
+    details: Option<bool>,
+) -> Result<usize, CommandError> {
+    tokio::task::spawn_blocking(move || {
+        write_unknown_hashes(std::path::Path::new(&output_path), &hashes, details.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// File format of `export_wad_manifest`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestFormat {
+    /// A header row, then one row per chunk
+    Csv,
+    /// An array of chunk objects shaped like `get_wad_chunks` returns them
+    Json,
+}
+
+/// Result of `export_wad_manifest`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WadManifestExport {
+    pub output_path: String,
+    /// Chunks written, unresolved ones included
+    pub row_count: usize,
+    /// Size of the written file in bytes
+    pub size: u64,
+}
+
+const MANIFEST_CSV_HEADER: &str = "hash,path,size,compressed_size,compression,kind,checksum,subchunked,is_duplicate";
+
+/// `field` quoted for CSV if it needs to be
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Write `chunks` to `writer` one row at a time
+fn write_chunk_manifest(chunks: &[ChunkInfo], format: ManifestFormat, mut writer: impl Write) -> std::io::Result<()> {
+    match format {
+        ManifestFormat::Csv => {
+            writeln!(writer, "{}", MANIFEST_CSV_HEADER)?;
+            for chunk in chunks {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{},{}",
+                    chunk.hash,
+                    csv_field(chunk.path.as_deref().unwrap_or_default()),
+                    chunk.size,
+                    chunk.compressed_size,
+                    chunk.compression,
+                    chunk.kind.name(),
+                    chunk.checksum,
+                    chunk.subchunked,
+                    chunk.is_duplicate
+                )?;
+            }
+        }
+        ManifestFormat::Json => {
+            writer.write_all(b"[")?;
+            for (index, chunk) in chunks.iter().enumerate() {
+                writer.write_all(if index == 0 { b"\n  " } else { b",\n  " })?;
+                serde_json::to_writer(&mut writer, chunk)?;
+            }
+            writer.write_all(b"\n]\n")?;
+        }
+    }
+    writer.flush()
+}
+
+/// Write the complete chunk listing of a WAD to a file for auditing in other
+/// tools
+///
+/// Rows come in path order like `get_wad_chunks`, unresolved hashes last,
+/// and are streamed to the file as they are formatted.
+///
+/// # Arguments
+/// * `wad_path` - Path to the WAD file
+/// * `output_path` - File to write
+/// * `format` - `csv` or `json`
+/// * `state` - Hashtable state for path resolution
+#[tauri::command]
+pub async fn export_wad_manifest(
+    wad_path: String,
+    output_path: String,
+    format: ManifestFormat,
+    state: State<'_, HashtableState>,
+) -> Result<WadManifestExport, CommandError> {
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut reader = WadReader::open(&wad_path)?;
+        let chunks = chunk_infos(&wad_path, &mut reader, Some(hashtable.as_ref()), ChunkSort::Path);
+
+        let path = std::path::Path::new(&output_path);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        }
+        let file = std::fs::File::create(path).map_err(|e| Error::io_with_path(e, path))?;
+        write_chunk_manifest(&chunks, format, BufWriter::new(file)).map_err(|e| Error::io_with_path(e, path))?;
+        let size = std::fs::metadata(path).map_err(|e| Error::io_with_path(e, path))?.len();
+
+        tracing::info!("Wrote the {} chunks of {} to {} ({} bytes)", chunks.len(), wad_path, output_path, size);
+        Ok::<_, Error>(WadManifestExport { output_path, row_count: chunks.len(), size })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Write a copy of a game WAD with a project's modified files packed in, for
+/// quick testing in game
+///
+/// The project's WAD folder of the same name (`content/base/Ahri.wad.client`)
+/// replaces the chunks its files have paths for and adds the rest; every
+/// other chunk is copied untouched. Refuses to overwrite the original WAD or
+/// any other existing file of the League installation.
+///
+/// # Arguments
+/// * `project_path` - Project holding the modified files
+/// * `wad_path` - The game's WAD to start from
+/// * `output_path` - File to write the patched WAD to
+#[tauri::command]
+pub async fn repack_wad(
+    project_path: String,
+    wad_path: String,
+    output_path: String,
+    settings: State<'_, SettingsState>,
+) -> Result<WadRepackStats, CommandError> {
+    let settings_league = settings.get().league_path;
+
+    tokio::task::spawn_blocking(move || {
+        let project = open_project(std::path::Path::new(&project_path))?;
+        let wad_path = std::path::PathBuf::from(&wad_path);
+        let output = std::path::PathBuf::from(&output_path);
+        let wad_name = wad_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let assets = project.assets_path();
+        let folder = std::fs::read_dir(&assets)
+            .map_err(|e| Error::io_with_path(e, &assets))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| {
+                path.is_dir() && path.file_name().is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(&wad_name))
+            })
+            .ok_or_else(|| Error::NotFound(format!("The project has no {} folder in content/base", wad_name)))?;
+
+        let league = project.league_path.clone().or(settings_league);
+        let in_league = league
+            .and_then(|league| league.canonicalize().ok())
+            .zip(output.canonicalize().ok())
+            .is_some_and(|(league, output)| output.starts_with(league));
+        if in_league {
+            return Err(Error::InvalidInput(format!(
+                "Refusing to overwrite {}, a file of the League installation",
+                output.display()
+            )));
+        }
+        repack_wad_folder(&wad_path, &folder, &output)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Guess paths for unresolved hashes of a WAD from its resolved paths
+///
+/// Matches are reported, not added: the frontend offers them to the user and
+/// saves the accepted ones with `add_hash_mapping`.
+///
+/// # Arguments
+/// * `wad_path` - Path to the WAD file
+/// * `hashes` - Hashes to guess as 16-char hex strings (default: every unresolved chunk)
+/// * `state` - Hashtable state for path resolution
+#[tauri::command]
+pub async fn guess_unknown_hashes(
+    wad_path: String,
+    hashes: Option<Vec<String>>,
+    state: State<'_, HashtableState>,
+) -> Result<GuessReport, CommandError> {
+    let unknown = match hashes {
+        Some(hashes) => Some(
+            hashes
+                .iter()
+                .map(|hash| {
+                    u64::from_str_radix(hash, 16).map_err(|e| {
+                        CommandError::new(ErrorCode::InvalidInput, format!("Invalid hash '{}': {}", hash, e))
+                    })
+                })
+                .collect::<Result<Vec<u64>, _>>()?,
+        ),
+        None => None,
+    };
+    let chunk_hashes: Vec<u64> = WadReader::open(&wad_path)?.chunks().keys().copied().collect();
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let known: Vec<&str> = chunk_hashes.iter().filter_map(|hash| hashtable.get(*hash)).collect();
+        let unknown = unknown.unwrap_or_else(|| {
+            chunk_hashes.iter().copied().filter(|hash| hashtable.get(*hash).is_none()).collect()
+        });
+        let report = crate::core::hash::guess_unknown_hashes(&unknown, &known);
+        tracing::info!(
+            "Guessed {} of {} unknown hashes in {} ({} candidates)",
+            report.guesses.len(),
+            unknown.len(),
+            wad_path,
+            report.candidates
+        );
+        report
+    })
+    .await
+    .map_err(|e| CommandError::from(format!("Task failed: {}", e)))
+}
+
+/// Compare two versions of a WAD, e.g. a champion's WAD before and after a patch
+///
+/// # Arguments
+/// * `old_path` - The earlier WAD
+/// * `new_path` - The later WAD
+/// * `state` - Hashtable state for path resolution
+///
+/// # Returns
+/// * `Ok(WadDiff)` - Added, removed and modified chunks with their paths and sizes
+#[tauri::command]
+pub async fn diff_wads(
+    old_path: String,
+    new_path: String,
+    state: State<'_, HashtableState>,
+) -> Result<WadDiff, CommandError> {
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
+    tokio::task::spawn_blocking(move || {
+        crate::core::wad::diff::diff_wads(
+            std::path::Path::new(&old_path),
+            std::path::Path::new(&new_path),
+            Some(hashtable.as_ref()),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Results shown per page when `find_in_wads` isn't given a limit
+const SEARCH_PAGE_SIZE: usize = 200;
+
+/// One page of `find_in_wads` hits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WadSearchPage {
+    pub hits: Vec<WadSearchHit>,
+    /// Hits across all pages
+    pub total: usize,
+    pub offset: usize,
+    pub stats: WadScanStats,
+}
+
+/// Find the WADs of an installation that hold a path, hash or path substring
+///
+/// Only the tables of contents are read, and they are cached per WAD until
+/// its size or modification time changes, so paging through the results or
+/// searching again is fast. `wad-search-progress` events report the scan.
+///
+/// # Arguments
+/// * `league_path` - League installation directory
+/// * `query` - A full path, a 16-digit hex hash, or part of a path
+/// * `offset` - First hit to return (default: 0)
+/// * `limit` - Hits to return (default: 200)
+/// * `state` - Hashtable state for path resolution
+/// * `cache` - Cached tables of contents
+#[tauri::command]
+pub async fn find_in_wads(
+    app: AppHandle,
+    league_path: String,
+    query: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: State<'_, HashtableState>,
+    cache: State<'_, WadTocCache>,
+) -> Result<WadSearchPage, CommandError> {
+    let query = WadQuery::parse(&query)?;
+    let hashtable = state.wait_ready(HASHTABLE_WAIT).await?;
+    let cache = cache.inner().clone();
+    let (offset, limit) = (offset.unwrap_or(0), limit.unwrap_or(SEARCH_PAGE_SIZE));
+
+    tokio::task::spawn_blocking(move || {
+        let progress = |scanned: usize, total: usize| {
+            let _ = app.emit("wad-search-progress", serde_json::json!({ "scanned": scanned, "total": total }));
+        };
+        let (hits, stats) = search_wads(
+            std::path::Path::new(&league_path),
+            &query,
+            Some(hashtable.as_ref()),
+            &cache,
+            progress,
+        )?;
+        Ok::<_, Error>(WadSearchPage {
+            total: hits.len(),
+            hits: hits.into_iter().skip(offset).take(limit).collect(),
+            offset,
+            stats,
+        })
     })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Find the WAD of an installation a game path comes from
+///
+/// The champion's WADs are checked first, then every other WAD through the
+/// tables `find_in_wads` caches.
+///
+/// # Arguments
+/// * `league_path` - League installation directory
+/// * `path` - Game path, e.g. `assets/characters/ahri/skins/skin11/ahri_skin11_tx_cm.dds`
+/// * `champion` - Champion whose WADs to check first (default: the one the path is under)
+/// * `cache` - Cached tables of contents
+///
+/// # Returns
+/// * `Ok(None)` - No WAD of the installation has the path
+#[tauri::command]
+pub async fn locate_game_path(
+    league_path: String,
+    path: String,
+    champion: Option<String>,
+    cache: State<'_, WadTocCache>,
+) -> Result<Option<GamePathLocation>, CommandError> {
+    let cache = cache.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        locate_path(std::path::Path::new(&league_path), &path, champion.as_deref(), &cache)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
 }
 
 /// Info about a WAD file found on disk (for game WAD scanning)
@@ -242,6 +1038,7 @@ pub struct GameWadInfo {
     pub name: String,
     /// Parent directory name used as a display category (e.g. "Champions", "Maps")
     pub category: String,
+    pub kind: WadKind,
 }
 
 /// Read decompressed chunk data from a WAD archive into memory — no disk write.
@@ -251,49 +1048,153 @@ pub struct GameWadInfo {
 /// * `hash`     - Chunk path-hash as a 16-char lowercase hex string
 ///
 /// # Returns
-/// * `Ok(Vec<u8>)` - Decompressed chunk bytes
-/// * `Err(String)` - Error message
+/// * `Ok(BinaryPayload)` - Decompressed chunk bytes, inline or as a blob token when large
+/// * `Err(CommandError)` - Error message
 #[tauri::command]
 pub async fn read_wad_chunk_data(
     wad_path: String,
     hash: String,
-) -> Result<Vec<u8>, String> {
+    blobs: State<'_, BlobStore>,
+) -> Result<BinaryPayload, CommandError> {
     let path_hash = u64::from_str_radix(&hash, 16)
-        .map_err(|e| format!("Invalid hash '{}': {}", hash, e))?;
+        .map_err(|e| CommandError::new(ErrorCode::InvalidInput, format!("Invalid hash '{}': {}", hash, e)))?;
 
     let mut reader = WadReader::open(&wad_path)?;
 
     // Clone the chunk to release the immutable borrow before decoding
     let chunk = *reader
         .get_chunk(path_hash)
-        .ok_or_else(|| format!("Chunk {:016x} not found in WAD", path_hash))?;
+        .ok_or_else(|| CommandError::new(ErrorCode::NotFound, format!("Chunk {:016x} not found in WAD", path_hash)))?;
+
+    let data = reader.load_chunk(&chunk)?;
+    Ok(blobs.payload(data, "application/octet-stream"))
+}
+
+/// A champion's WADs mounted as one filesystem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WadMountInfo {
+    /// Handle for `lookup_mounted_chunk`, `read_mounted_chunk` and `unmount_wads`
+    pub id: String,
+    /// Mounted WADs, highest priority first
+    pub wads: Vec<String>,
+    /// Distinct chunks across the WADs
+    pub chunk_count: usize,
+}
+
+/// Where a mount set finds a chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MountedChunk {
+    /// WAD providing the chunk
+    pub wad_path: String,
+    /// Path hash as a 16-char lowercase hex string
+    pub hash: String,
+    /// Decompressed size in bytes
+    pub size: usize,
+}
+
+/// Path hash of a 16-digit hex hash, or of a game path
+fn mounted_entry_hash(entry: &str) -> u64 {
+    let hex = entry.strip_prefix("0x").unwrap_or(entry);
+    match u64::from_str_radix(hex, 16) {
+        Ok(hash) if hex.len() == 16 => hash,
+        _ => compute_path_hash(entry),
+    }
+}
 
-    let (mut decoder, _) = reader.wad_mut().decode();
-    decoder
-        .load_chunk_decompressed(&chunk)
-        .map(|b| b.into())
-        .map_err(|e| format!("Failed to decompress chunk {:016x}: {}", path_hash, e))
+fn mount_set(mounts: &WadMountRegistry, id: &str) -> Result<std::sync::Arc<WadMountSet>, CommandError> {
+    mounts
+        .get(id)
+        .ok_or_else(|| CommandError::new(ErrorCode::NotFound, format!("No mounted WADs with handle '{}'", id)))
+}
+
+/// Mount a champion's client WAD and its locale WADs, in that priority order
+///
+/// Only the tables of contents are loaded. The returned handle answers
+/// lookups and preview reads until `unmount_wads` drops it.
+///
+/// # Arguments
+/// * `league_path` - League installation directory
+/// * `champion` - Champion internal name (e.g., "Ahri")
+/// * `locale` - Only mount this locale's WAD (e.g., "en_US"); all locales if omitted
+#[tauri::command]
+pub async fn mount_champion_wads(
+    league_path: String,
+    champion: String,
+    locale: Option<String>,
+    mounts: State<'_, WadMountRegistry>,
+) -> Result<WadMountInfo, CommandError> {
+    let mounts = mounts.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let paths = champion_wad_paths(std::path::Path::new(&league_path), &champion, locale.as_deref())?;
+        let mount_set = WadMountSet::mount(&paths)?;
+        let wads = mount_set.wads().map(|path| path.display().to_string()).collect();
+        let chunk_count = mount_set.chunk_count();
+        tracing::info!("Mounted {} WADs of {} ({} chunks)", paths.len(), champion, chunk_count);
+        Ok::<_, Error>(WadMountInfo { id: mounts.insert(mount_set), wads, chunk_count })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(CommandError::from)
+}
+
+/// Find which mounted WAD provides a game path or hash; `None` if none does
+#[tauri::command]
+pub async fn lookup_mounted_chunk(
+    id: String,
+    entry: String,
+    mounts: State<'_, WadMountRegistry>,
+) -> Result<Option<MountedChunk>, CommandError> {
+    let mount_set = mount_set(&mounts, &id)?;
+    let hash = mounted_entry_hash(&entry);
+    Ok(mount_set.find_hash(hash).map(|(wad_path, chunk)| MountedChunk {
+        wad_path: wad_path.display().to_string(),
+        hash: format!("{:016x}", hash),
+        size: chunk.uncompressed_size(),
+    }))
+}
+
+/// Read a game path or hash from the highest-priority mounted WAD holding it
+#[tauri::command]
+pub async fn read_mounted_chunk(
+    id: String,
+    entry: String,
+    mounts: State<'_, WadMountRegistry>,
+    blobs: State<'_, BlobStore>,
+) -> Result<BinaryPayload, CommandError> {
+    let mount_set = mount_set(&mounts, &id)?;
+    let hash = mounted_entry_hash(&entry);
+    let data = tokio::task::spawn_blocking(move || mount_set.read_chunk(hash))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    Ok(blobs.payload(data, "application/octet-stream"))
+}
+
+/// Drop a mount set; returns whether the handle existed
+#[tauri::command]
+pub async fn unmount_wads(id: String, mounts: State<'_, WadMountRegistry>) -> Result<bool, CommandError> {
+    Ok(mounts.remove(&id))
 }
 
 /// Scan a game installation directory for all WAD archive files.
 ///
-/// Searches `{game_path}/DATA/FINAL/` recursively for `*.wad.client` and `*.wad`
-/// files, grouping them by their parent directory name.
+/// Searches `{game_path}/DATA/FINAL/` recursively for `*.wad.client`, `*.wad.mobile`
+/// and `*.wad` files, grouping them by their parent directory name. Each WAD is
+/// tagged with its kind so locale and mobile WADs can be told apart from client ones.
 ///
 /// # Arguments
 /// * `game_path` - Path to the League `Game/` directory
 ///
 /// # Returns
 /// * `Ok(Vec<GameWadInfo>)` - Discovered WAD files sorted by category then name
-/// * `Err(String)`          - Error if the WAD root does not exist
+/// * `Err(CommandError)`    - Error if the WAD root does not exist
 #[tauri::command]
-pub async fn scan_game_wads(game_path: String) -> Result<Vec<GameWadInfo>, String> {
+pub async fn scan_game_wads(game_path: String) -> Result<Vec<GameWadInfo>, CommandError> {
     let root = std::path::Path::new(&game_path).join("DATA").join("FINAL");
 
     if !root.exists() {
-        return Err(format!(
-            "WAD directory not found: {} — make sure this is the League Game/ folder",
-            root.display()
+        return Err(CommandError::new(
+            ErrorCode::NotFound,
+            format!("WAD directory not found: {} — make sure this is the League Game/ folder", root.display()),
         ));
     }
 
@@ -305,9 +1206,7 @@ pub async fn scan_game_wads(game_path: String) -> Result<Vec<GameWadInfo>, Strin
         .filter_map(|e| {
             let path = e.path();
             let name = path.file_name()?.to_str()?;
-            if !name.ends_with(".wad.client") && !name.ends_with(".wad") {
-                return None;
-            }
+            let wad = WadName::parse(name)?;
             let category = path
                 .parent()
                 .and_then(|p| p.file_name())
@@ -318,11 +1217,93 @@ pub async fn scan_game_wads(game_path: String) -> Result<Vec<GameWadInfo>, Strin
                 path: path.to_string_lossy().to_string(),
                 name: name.to_string(),
                 category,
+                kind: wad.kind,
             })
         })
         .collect();
 
-    wads.sort_unstable_by(|a, b| a.category.cmp(&b.category).then(a.name.cmp(&b.name)));
+    wads.sort_unstable_by(|a, b| {
+        a.category.cmp(&b.category).then(a.name.cmp(&b.name)).then_with(|| a.path.cmp(&b.path))
+    });
 
     Ok(wads)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_chunks_is_stable() {
+        let chunk = |hash: &str, path: Option<&str>, size| ChunkInfo {
+            hash: hash.to_string(),
+            path: path.map(str::to_string),
+            size,
+            compressed_size: size,
+            compression: "none".to_string(),
+            checksum: "0000000000000000".to_string(),
+            subchunked: false,
+            is_duplicate: false,
+            kind: ChunkKind::Other,
+        };
+        let original = vec![
+            chunk("00000000000000ff", None, 10),
+            chunk("0000000000000002", Some("data/B.bin"), 30),
+            chunk("0000000000000001", Some("assets/a.dds"), 30),
+            chunk("000000000000000a", None, 5),
+        ];
+        let order = |sort, chunks: &[ChunkInfo]| {
+            let mut chunks = chunks.to_vec();
+            sort_chunks(&mut chunks, sort);
+            chunks.into_iter().map(|c| c.hash).collect::<Vec<_>>()
+        };
+
+        let by_path = order(ChunkSort::Path, &original);
+        assert_eq!(by_path, ["0000000000000001", "0000000000000002", "000000000000000a", "00000000000000ff"]);
+        let reversed: Vec<ChunkInfo> = original.iter().rev().cloned().collect();
+        assert_eq!(order(ChunkSort::Path, &reversed), by_path);
+
+        assert_eq!(order(ChunkSort::Size, &original)[..2], ["0000000000000001", "0000000000000002"]);
+        assert_eq!(order(ChunkSort::Hash, &reversed)[0], "0000000000000001");
+    }
+
+    #[test]
+    fn test_chunk_manifest_formats() {
+        let chunk = |hash: &str, path: Option<&str>, kind| ChunkInfo {
+            hash: hash.to_string(),
+            path: path.map(str::to_string),
+            size: 20,
+            compressed_size: 10,
+            compression: "zstd".to_string(),
+            checksum: "00000000000000ab".to_string(),
+            subchunked: false,
+            is_duplicate: false,
+            kind,
+        };
+        let chunks = vec![
+            chunk("0000000000000001", Some("assets/a, \"quoted\".dds"), ChunkKind::Texture),
+            chunk("00000000000000ff", None, ChunkKind::Bin),
+        ];
+
+        let mut csv = Vec::new();
+        write_chunk_manifest(&chunks, ManifestFormat::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows, [
+            MANIFEST_CSV_HEADER,
+            "0000000000000001,\"assets/a, \"\"quoted\"\".dds\",20,10,zstd,texture,00000000000000ab,false,false",
+            "00000000000000ff,,20,10,zstd,bin,00000000000000ab,false,false",
+        ]);
+
+        let mut json = Vec::new();
+        write_chunk_manifest(&chunks, ManifestFormat::Json, &mut json).unwrap();
+        let parsed: Vec<ChunkInfo> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].path, None);
+        assert_eq!(parsed[0].kind, ChunkKind::Texture);
+
+        let mut empty = Vec::new();
+        write_chunk_manifest(&[], ManifestFormat::Json, &mut empty).unwrap();
+        assert!(serde_json::from_slice::<Vec<ChunkInfo>>(&empty).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+//! Converting every BIN of a project to text or JSON
+//!
+//! Unlike pre-conversion, which caches `.ritobin` text for the editor, this
+//! writes the converted file next to each BIN (`skin0.bin` → `skin0.py` or
+//! `skin0.json`) and reports what happened to every file. Run again with
+//! `force` after a hashtable update to pick up newly resolved names.
+
+use crate::core::bin::{bin_to_json, get_cached_bin_hashes, read_bin_ltk, tree_to_text_cached, MAX_BIN_SIZE};
+use crate::core::parallel;
+use crate::error::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use walkdir::WalkDir;
+
+/// Format `convert_project_bins` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinOutputFormat {
+    /// Ritobin text, saved as `.py`
+    #[default]
+    Text,
+    Json,
+}
+
+impl BinOutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            BinOutputFormat::Text => "py",
+            BinOutputFormat::Json => "json",
+        }
+    }
+}
+
+/// What happened to one BIN
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinConversionStatus {
+    Converted,
+    /// Its converted file was newer
+    Skipped,
+    Failed,
+}
+
+/// One BIN of a `convert_project_bins` run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinConversion {
+    /// BIN path relative to the project, with forward slashes
+    pub path: String,
+    /// Converted file, relative to the project
+    pub output: String,
+    pub status: BinConversionStatus,
+    pub error: Option<String>,
+}
+
+/// Result of `convert_project_bins`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BinConversionReport {
+    /// Every BIN found, sorted by path
+    pub files: Vec<BinConversion>,
+    pub converted: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Convert every BIN under the project's `content` layers to `format`,
+/// alongside the original. A BIN whose converted file is at least as new is
+/// skipped unless `force`; a BIN that fails doesn't stop the others.
+///
+/// `progress(done, total, path)` is called after each file, from the worker
+/// threads.
+pub fn convert_project_bins(
+    project_path: &Path,
+    format: BinOutputFormat,
+    force: bool,
+    progress: impl Fn(usize, usize, &str) + Sync,
+) -> Result<BinConversionReport> {
+    let content = project_path.join("content");
+    let mut bins: Vec<PathBuf> = WalkDir::new(&content)
+        .min_depth(2)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+        .collect();
+    bins.sort();
+    let total = bins.len();
+    tracing::info!("Converting {} BIN files of {} to {:?} (force: {})", total, project_path.display(), format, force);
+
+    // Load the BIN hashes once before the workers need them
+    if format == BinOutputFormat::Text {
+        let _ = get_cached_bin_hashes();
+    }
+    let relative = |path: &Path| path.strip_prefix(project_path).unwrap_or(path).to_string_lossy().replace('\\', "/");
+    let done = AtomicUsize::new(0);
+    let files: Vec<BinConversion> = parallel::install(|| {
+        bins.par_iter()
+            .map(|bin_path| {
+                let output = bin_path.with_extension(format.extension());
+                let outcome = if !force && is_up_to_date(bin_path, &output) {
+                    Ok(false)
+                } else {
+                    convert_bin(bin_path, &output, format).map(|_| true)
+                };
+                let path = relative(bin_path);
+                let (status, error) = match outcome {
+                    Ok(true) => (BinConversionStatus::Converted, None),
+                    Ok(false) => (BinConversionStatus::Skipped, None),
+                    Err(e) => {
+                        tracing::warn!("Failed to convert {}: {}", path, e);
+                        (BinConversionStatus::Failed, Some(e))
+                    }
+                };
+                progress(done.fetch_add(1, Ordering::Relaxed) + 1, total, &path);
+                BinConversion { path, output: relative(&output), status, error }
+            })
+            .collect()
+    });
+
+    let count = |status| files.iter().filter(|file| file.status == status).count();
+    let report = BinConversionReport {
+        converted: count(BinConversionStatus::Converted),
+        skipped: count(BinConversionStatus::Skipped),
+        failed: count(BinConversionStatus::Failed),
+        files,
+    };
+    tracing::info!(
+        "Converted {} BIN files ({} skipped, {} failed)",
+        report.converted,
+        report.skipped,
+        report.failed
+    );
+    Ok(report)
+}
+
+/// Whether `output` exists and was written no earlier than `source` changed
+fn is_up_to_date(source: &Path, output: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified());
+    matches!((modified(source), modified(output)), (Ok(source), Ok(output)) if output >= source)
+}
+
+fn convert_bin(bin_path: &Path, output: &Path, format: BinOutputFormat) -> std::result::Result<(), String> {
+    let size = fs::metadata(bin_path).map_err(|e| format!("Failed to read file metadata: {}", e))?.len() as usize;
+    if size > MAX_BIN_SIZE {
+        return Err(format!("BIN file too large ({} bytes, max {} bytes), likely corrupt", size, MAX_BIN_SIZE));
+    }
+    let data = fs::read(bin_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let tree = read_bin_ltk(&data).map_err(|e| format!("Failed to parse BIN: {}", e))?;
+    let converted = match format {
+        BinOutputFormat::Text => tree_to_text_cached(&tree).map_err(|e| format!("Failed to convert to text: {}", e))?,
+        BinOutputFormat::Json => bin_to_json(&tree, None).map_err(|e| e.to_string())?,
+    };
+    fs::write(output, converted).map_err(|e| format!("Failed to write '{}': {}", output.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bin::{text_to_tree, write_bin_ltk};
+
+    #[test]
+    fn test_convert_project_bins_reports_each_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let skins = temp.path().join("content/base/data/characters/ahri/skins");
+        fs::create_dir_all(&skins).unwrap();
+        let tree = text_to_tree("#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {}\nentries: map[hash,embed] = {}\n").unwrap();
+        fs::write(skins.join("skin0.bin"), write_bin_ltk(&tree).unwrap()).unwrap();
+        fs::write(skins.join("skin1.bin"), b"not a bin").unwrap();
+        // Outside a content layer
+        fs::write(temp.path().join("content/stray.bin"), b"not a bin").unwrap();
+
+        let report = convert_project_bins(temp.path(), BinOutputFormat::Text, false, |_, _, _| {}).unwrap();
+        assert_eq!((report.converted, report.skipped, report.failed), (1, 0, 1));
+        assert_eq!(report.files[0].path, "content/base/data/characters/ahri/skins/skin0.bin");
+        assert_eq!(report.files[0].output, "content/base/data/characters/ahri/skins/skin0.py");
+        assert!(report.files[1].error.is_some());
+        assert!(fs::read_to_string(skins.join("skin0.py")).unwrap().starts_with("#PROP_text"));
+
+        let report = convert_project_bins(temp.path(), BinOutputFormat::Text, false, |_, _, _| {}).unwrap();
+        assert_eq!(report.files[0].status, BinConversionStatus::Skipped);
+        let report = convert_project_bins(temp.path(), BinOutputFormat::Json, true, |_, _, _| {}).unwrap();
+        assert_eq!(report.files[0].status, BinConversionStatus::Converted);
+        assert!(skins.join("skin0.json").exists());
+    }
+}
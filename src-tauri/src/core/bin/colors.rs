@@ -0,0 +1,296 @@
+//! Listing the colors of BINs for recoloring
+//!
+//! Every `rgba` value and every `vec4` is reported with its object and
+//! property path; `vec4`s count as colors for certain only below a known
+//! color field (`birthColor`, `lingerColor`, …), which `known_field` tells.
+//! Animated colors, structs holding `times` and a same-length list of colors
+//! in `values`, are reported whole as gradients rather than color by color.
+
+use crate::core::bin::search::key_text;
+use crate::core::bin::{read_bin_ltk, PropertyValueEnum, MAX_BIN_SIZE};
+use crate::core::parallel;
+use crate::core::recolor::{fnv1a, COLOR_FIELDS};
+use crate::error::{Error, Result};
+use ltk_ritobin::HashProvider;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// One color value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinColor {
+    /// BIN path relative to the project, or its file name for a single BIN
+    pub file: String,
+    /// Object path, or its hash as "0x…"
+    pub object: String,
+    /// Property path as `get_bin_property` takes it
+    pub path: String,
+    /// RGBA in 0..1; `vec4` colors may exceed 1
+    pub rgba: [f32; 4],
+    /// Stored as `rgba` bytes rather than a `vec4`
+    pub bytes: bool,
+    /// Below a known color field
+    pub known_field: bool,
+}
+
+/// One keyframe of a gradient
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GradientKey {
+    pub time: f32,
+    pub rgba: [f32; 4],
+}
+
+/// An animated color
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinGradient {
+    pub file: String,
+    pub object: String,
+    /// Path of the struct holding `times` and `values`
+    pub path: String,
+    /// In stored order
+    pub keys: Vec<GradientKey>,
+    pub known_field: bool,
+}
+
+/// Result of `extract_bin_colors`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BinColorReport {
+    pub colors: Vec<BinColor>,
+    pub gradients: Vec<BinGradient>,
+    pub files_scanned: usize,
+    /// BINs that couldn't be read, with the reason
+    pub failed: Vec<String>,
+}
+
+/// Colors of the BIN at `path`, or of every BIN under the content layers
+/// of the project at `path`
+pub fn extract_bin_colors<H: HashProvider + Sync>(path: &Path, hashes: &H) -> Result<BinColorReport> {
+    let (root, bins): (&Path, Vec<PathBuf>) = if path.is_file() {
+        (path.parent().unwrap_or(path), vec![path.to_path_buf()])
+    } else {
+        let content = path.join("content");
+        if !content.is_dir() {
+            return Err(Error::NotFound(format!("No BIN or project content at {}", path.display())));
+        }
+        let mut bins: Vec<PathBuf> = WalkDir::new(&content)
+            .min_depth(2)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+            .collect();
+        bins.sort();
+        (path, bins)
+    };
+
+    let color_fields: HashSet<u32> = COLOR_FIELDS.iter().map(|field| fnv1a(field)).collect();
+    let results: Vec<_> = parallel::install(|| {
+        bins.par_iter()
+            .map(|bin_path| {
+                let file = bin_path.strip_prefix(root).unwrap_or(bin_path).to_string_lossy().replace('\\', "/");
+                (bin_path, bin_colors(bin_path, &file, &color_fields, hashes))
+            })
+            .collect()
+    });
+
+    let mut report = BinColorReport { files_scanned: bins.len(), ..Default::default() };
+    for (bin_path, result) in results {
+        match result {
+            Ok((colors, gradients)) => {
+                report.colors.extend(colors);
+                report.gradients.extend(gradients);
+            }
+            Err(e) => {
+                tracing::warn!("Skipping {} for colors: {}", bin_path.display(), e);
+                report.failed.push(format!("{}: {}", bin_path.display(), e));
+            }
+        }
+    }
+    tracing::info!(
+        "Found {} colors and {} gradients in {} BINs",
+        report.colors.len(),
+        report.gradients.len(),
+        report.files_scanned
+    );
+    Ok(report)
+}
+
+fn bin_colors<H: HashProvider>(
+    bin_path: &Path,
+    file: &str,
+    color_fields: &HashSet<u32>,
+    hashes: &H,
+) -> Result<(Vec<BinColor>, Vec<BinGradient>)> {
+    let size = fs::metadata(bin_path).map_err(|e| Error::io_with_path(e, bin_path))?.len() as usize;
+    if size > MAX_BIN_SIZE {
+        return Err(Error::bin_conversion_with_path(
+            format!("BIN file too large ({} bytes, max {} bytes), likely corrupt", size, MAX_BIN_SIZE),
+            bin_path,
+        ));
+    }
+    let data = fs::read(bin_path).map_err(|e| Error::io_with_path(e, bin_path))?;
+    let tree = read_bin_ltk(&data).map_err(|e| Error::bin_conversion_with_path(e.to_string(), bin_path))?;
+
+    let mut walk =
+        ColorWalk { file, color_fields, hashes, object: String::new(), colors: Vec::new(), gradients: Vec::new() };
+    for (path_hash, object) in &tree.objects {
+        walk.object = hashes.lookup_entry(*path_hash).map_or_else(|| format!("{:#010x}", path_hash), str::to_string);
+        for (name_hash, prop) in &object.properties {
+            walk.field(*name_hash, &prop.value, "", false);
+        }
+    }
+    Ok((walk.colors, walk.gradients))
+}
+
+/// Walks a BIN value, collecting its colors
+struct ColorWalk<'a, H> {
+    file: &'a str,
+    color_fields: &'a HashSet<u32>,
+    hashes: &'a H,
+    /// Object being walked
+    object: String,
+    colors: Vec<BinColor>,
+    gradients: Vec<BinGradient>,
+}
+
+impl<H: HashProvider> ColorWalk<'_, H> {
+    fn field(&mut self, name_hash: u32, value: &PropertyValueEnum, parent: &str, in_color: bool) {
+        let field = self.hashes.lookup_field(name_hash).map_or_else(|| format!("{:#010x}", name_hash), str::to_string);
+        let path = if parent.is_empty() { field } else { format!("{}.{}", parent, field) };
+        self.value(value, path, in_color || self.color_fields.contains(&name_hash));
+    }
+
+    /// `in_color` is set below a color field
+    fn value(&mut self, value: &PropertyValueEnum, path: String, in_color: bool) {
+        match value {
+            PropertyValueEnum::Color(c) => {
+                let rgba = [c.0.r, c.0.g, c.0.b, c.0.a].map(|v| v as f32 / 255.0);
+                self.push_color(path, rgba, true, in_color);
+            }
+            PropertyValueEnum::Vector4(v) => self.push_color(path, [v.0.x, v.0.y, v.0.z, v.0.w], false, in_color),
+            PropertyValueEnum::Container(c) => {
+                for (i, item) in c.items.iter().enumerate() {
+                    self.value(item, format!("{}[{}]", path, i), in_color);
+                }
+            }
+            PropertyValueEnum::UnorderedContainer(c) => {
+                for (i, item) in c.0.items.iter().enumerate() {
+                    self.value(item, format!("{}[{}]", path, i), in_color);
+                }
+            }
+            PropertyValueEnum::Struct(s) => {
+                if let Some(keys) = gradient(|hash| s.properties.get(&hash).map(|prop| &prop.value)) {
+                    self.push_gradient(path, keys, in_color);
+                    return;
+                }
+                for (name_hash, prop) in &s.properties {
+                    self.field(*name_hash, &prop.value, &path, in_color);
+                }
+            }
+            PropertyValueEnum::Embedded(e) => {
+                if let Some(keys) = gradient(|hash| e.0.properties.get(&hash).map(|prop| &prop.value)) {
+                    self.push_gradient(path, keys, in_color);
+                    return;
+                }
+                for (name_hash, prop) in &e.0.properties {
+                    self.field(*name_hash, &prop.value, &path, in_color);
+                }
+            }
+            PropertyValueEnum::Optional(o) => {
+                if let Some(inner) = &o.value {
+                    self.value(inner, path, in_color);
+                }
+            }
+            PropertyValueEnum::Map(m) => {
+                for (i, (key, val)) in m.entries.iter().enumerate() {
+                    let key = key_text(&key.0, self.hashes).unwrap_or_else(|| i.to_string());
+                    self.value(val, format!("{}[{}]", path, key), in_color);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_color(&mut self, path: String, rgba: [f32; 4], bytes: bool, known_field: bool) {
+        self.colors.push(BinColor {
+            file: self.file.to_string(),
+            object: self.object.clone(),
+            path,
+            rgba,
+            bytes,
+            known_field,
+        });
+    }
+
+    fn push_gradient(&mut self, path: String, keys: Vec<GradientKey>, known_field: bool) {
+        self.gradients.push(BinGradient {
+            file: self.file.to_string(),
+            object: self.object.clone(),
+            path,
+            keys,
+            known_field,
+        });
+    }
+}
+
+/// The keys of a struct holding `times` and as many colors in `values`
+fn gradient<'v>(field: impl Fn(u32) -> Option<&'v PropertyValueEnum>) -> Option<Vec<GradientKey>> {
+    let (Some(PropertyValueEnum::Container(times)), Some(PropertyValueEnum::Container(values))) =
+        (field(fnv1a("times")), field(fnv1a("values")))
+    else {
+        return None;
+    };
+    if times.items.is_empty() || times.items.len() != values.items.len() {
+        return None;
+    }
+    times
+        .items
+        .iter()
+        .zip(&values.items)
+        .map(|(time, value)| {
+            let rgba = match value {
+                PropertyValueEnum::Vector4(v) => [v.0.x, v.0.y, v.0.z, v.0.w],
+                PropertyValueEnum::Color(c) => [c.0.r, c.0.g, c.0.b, c.0.a].map(|v| v as f32 / 255.0),
+                _ => return None,
+            };
+            match time {
+                PropertyValueEnum::F32(t) => Some(GradientKey { time: t.0, rgba }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bin::{text_to_tree, write_bin_ltk, HashMapProvider};
+
+    #[test]
+    fn test_colors_and_gradients() {
+        let text = "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {}\nentries: map[hash,embed] = {\n    \"Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis\" = VfxSystemDefinitionData {\n        complexEmitterDefinitionData: list[pointer] = {\n            VfxEmitterDefinitionData {\n                birthColor: embed = ValueColor {\n                    constantValue: vec4 = { 1, 0.5, 0, 1 }\n                    dynamics: pointer = VfxAnimatedColorVariableData {\n                        times: list[f32] = {\n                            0\n                            1\n                        }\n                        values: list[vec4] = {\n                            { 1, 0, 0, 1 }\n                            { 0, 0, 1, 0 }\n                        }\n                    }\n                }\n                tint: rgba = { 255, 0, 0, 255 }\n            }\n        }\n    }\n}\n";
+        let temp = tempfile::tempdir().unwrap();
+        let bin_path = temp.path().join("skin0.bin");
+        fs::write(&bin_path, write_bin_ltk(&text_to_tree(text).unwrap()).unwrap()).unwrap();
+
+        let report = extract_bin_colors(&bin_path, &HashMapProvider::new()).unwrap();
+        assert_eq!((report.files_scanned, report.colors.len(), report.gradients.len()), (1, 2, 1));
+        let constant = report.colors.iter().find(|color| !color.bytes).unwrap();
+        assert_eq!((constant.rgba, constant.known_field), ([1.0, 0.5, 0.0, 1.0], true));
+        assert_eq!(constant.file, "skin0.bin");
+        let tint = report.colors.iter().find(|color| color.bytes).unwrap();
+        assert_eq!((tint.rgba, tint.known_field), ([1.0, 0.0, 0.0, 1.0], false));
+
+        let gradient = &report.gradients[0];
+        assert!(gradient.known_field);
+        assert!(gradient.path.ends_with(&format!("{:#010x}", fnv1a("dynamics"))));
+        assert_eq!(gradient.keys[1].time, 1.0);
+        assert_eq!(gradient.keys[1].rgba, [0.0, 0.0, 1.0, 0.0]);
+
+        assert!(extract_bin_colors(temp.path(), &HashMapProvider::new()).is_err());
+    }
+}
@@ -4,9 +4,11 @@
 //! wrapping their APIs for use throughout the application.
 
 use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 use parking_lot::RwLock;
 use ltk_meta::{BinTree, BinTreeObject};
+use serde::{Deserialize, Serialize};
 
 /// Maximum allowed BIN file size (50MB - no legitimate BIN should be larger)
 pub const MAX_BIN_SIZE: usize = 50 * 1024 * 1024;
@@ -167,9 +169,40 @@ pub fn tree_to_text_with_hashes<H: ltk_ritobin::HashProvider>(
         .map_err(|e| BinError(format!("Failed to convert to text: {}", e)))
 }
 
+/// Entries in each BIN hash list, as last loaded into the cache
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BinHashCounts {
+    /// hashes.binentries.txt: object paths
+    pub entries: usize,
+    /// hashes.binfields.txt: property names
+    pub fields: usize,
+    /// hashes.binhashes.txt: hash values
+    pub hashes: usize,
+    /// hashes.bintypes.txt: class names
+    pub types: usize,
+}
+
+impl BinHashCounts {
+    /// Count the entries of the lists in `hash_dir`: lines that aren't blank
+    /// or comments. Missing lists count 0.
+    pub fn from_directory(hash_dir: &Path) -> Self {
+        let count = |file: &str| {
+            std::fs::read_to_string(hash_dir.join(file)).map_or(0, |content| {
+                content.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#')).count()
+            })
+        };
+        Self {
+            entries: count("hashes.binentries.txt"),
+            fields: count("hashes.binfields.txt"),
+            hashes: count("hashes.binhashes.txt"),
+            types: count("hashes.bintypes.txt"),
+        }
+    }
+}
+
 /// Load BIN-specific hash files into a HashMapProvider
 ///
-/// Loads hashes from the RitoShark hash directory:
+/// Loads hashes from `hash_dir`:
 /// - hashes.bintypes.txt (type names)
 /// - hashes.binfields.txt (field/property names)
 /// - hashes.binentries.txt (entry/object names)
@@ -180,20 +213,9 @@ pub fn tree_to_text_with_hashes<H: ltk_ritobin::HashProvider>(
 ///
 /// # Returns
 /// A HashMapProvider populated with all loaded hashes
-pub fn load_bin_hashes() -> HashMapProvider {
+pub fn load_bin_hashes(hash_dir: &Path) -> HashMapProvider {
     let mut hashes = HashMapProvider::new();
     
-    // Get the RitoShark hash directory
-    let hash_dir = if let Ok(appdata) = std::env::var("APPDATA") {
-        std::path::PathBuf::from(appdata)
-            .join("RitoShark")
-            .join("Requirements")
-            .join("Hashes")
-    } else {
-        tracing::warn!("APPDATA not set, cannot load hash files");
-        return hashes;
-    };
-    
     if !hash_dir.exists() {
         tracing::warn!("Hash directory does not exist: {}", hash_dir.display());
         return hashes;
@@ -205,7 +227,7 @@ pub fn load_bin_hashes() -> HashMapProvider {
     // - hashes.binfields.txt  -> fields (property/field name hashes)
     // - hashes.binhashes.txt  -> hashes (hash value hashes)
     // - hashes.bintypes.txt   -> types (type/class name hashes)
-    hashes.load_from_directory(&hash_dir);
+    hashes.load_from_directory(hash_dir);
     
     let total = hashes.total_count();
     tracing::info!("Loaded {} total BIN hashes for name resolution", total);
@@ -217,6 +239,38 @@ pub fn load_bin_hashes() -> HashMapProvider {
 /// This eliminates the massive overhead of loading hash files for every BIN conversion
 static BIN_HASHES_CACHE: OnceLock<RwLock<HashMapProvider>> = OnceLock::new();
 
+/// Directory the cache loads from; the RitoShark directory until set
+static BIN_HASH_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Per-list counts of what the cache holds; `None` until it is loaded
+static BIN_HASH_COUNTS: RwLock<Option<BinHashCounts>> = RwLock::new(None);
+
+/// Directories of BIN lists loaded over the hash directory's, in order
+static BIN_HASH_OVERLAYS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// Use `hash_dir` (the configured hash directory) for BIN hashes
+pub fn set_bin_hash_dir(hash_dir: PathBuf) {
+    *BIN_HASH_DIR.write() = Some(hash_dir);
+}
+
+fn bin_hash_dir() -> Option<PathBuf> {
+    BIN_HASH_DIR.read().clone().or_else(|| crate::core::hash::get_ritoshark_hash_dir().ok())
+}
+
+/// Load the cache contents and record their counts
+fn load_bin_cache() -> HashMapProvider {
+    let Some(hash_dir) = bin_hash_dir() else {
+        tracing::warn!("No hash directory, cannot load BIN hash files");
+        return HashMapProvider::new();
+    };
+    let mut hashes = load_bin_hashes(&hash_dir);
+    *BIN_HASH_COUNTS.write() = Some(BinHashCounts::from_directory(&hash_dir));
+    for overlay in BIN_HASH_OVERLAYS.read().iter() {
+        hashes.load_from_directory(overlay);
+    }
+    hashes
+}
+
 /// Get or initialize the cached BIN hash provider
 /// 
 /// This is thread-safe and will only load hashes from disk once.
@@ -224,12 +278,50 @@ static BIN_HASHES_CACHE: OnceLock<RwLock<HashMapProvider>> = OnceLock::new();
 pub fn get_cached_bin_hashes() -> &'static RwLock<HashMapProvider> {
     BIN_HASHES_CACHE.get_or_init(|| {
         tracing::info!("Initializing global BIN hash cache...");
-        let hashes = load_bin_hashes();
+        let hashes = load_bin_cache();
         tracing::info!("Global BIN hash cache initialized with {} hashes", hashes.total_count());
         RwLock::new(hashes)
     })
 }
 
+/// Reload the cache after its lists changed on disk. Does nothing before
+/// the first use, which loads the current lists anyway.
+pub fn reload_bin_hashes() {
+    let Some(cache) = BIN_HASHES_CACHE.get() else {
+        return;
+    };
+    let hashes = load_bin_cache();
+    tracing::info!("Reloaded BIN hash cache with {} hashes", hashes.total_count());
+    *cache.write() = hashes;
+}
+
+/// Load the BIN lists in `dir` over the cached ones, now if the cache is
+/// loaded and after every reload
+pub fn add_bin_hash_overlay(dir: PathBuf) {
+    {
+        let mut overlays = BIN_HASH_OVERLAYS.write();
+        if !overlays.contains(&dir) {
+            overlays.push(dir.clone());
+        }
+    }
+    if let Some(cache) = BIN_HASHES_CACHE.get() {
+        cache.write().load_from_directory(&dir);
+        tracing::info!("Merged BIN hash overlay from {}", dir.display());
+    }
+}
+
+/// Per-list counts of the cached BIN hashes; `None` until first used
+pub fn bin_hash_counts() -> Option<BinHashCounts> {
+    *BIN_HASH_COUNTS.read()
+}
+
+/// Whether `file` is one of the lists the BIN hash cache loads
+pub fn is_bin_hash_file(file: &Path) -> bool {
+    file.file_name().and_then(|n| n.to_str()).is_some_and(|name| {
+        matches!(name, "hashes.binentries.txt" | "hashes.binfields.txt" | "hashes.binhashes.txt" | "hashes.bintypes.txt")
+    })
+}
+
 /// Convert a BinTree to ritobin text format using the cached hash provider
 /// 
 /// This is the preferred method for BIN conversion as it reuses the globally
@@ -0,0 +1,310 @@
+//! Three-way merging of BINs
+//!
+//! Two edited versions of a BIN, ours and theirs, are merged against the
+//! version both started from. Objects are compared whole, then field by
+//! field when both sides changed one: a change made on one side only is
+//! applied, the same change made on both is taken once, and different
+//! changes to one field, or an object edited on one side and removed or
+//! retyped on the other, are conflicts. A conflict keeps our version unless
+//! the resolution map picks theirs by the conflict's id. Values are compared
+//! in their JSON form.
+
+use crate::core::bin::ltk_bridge::write_bin;
+use crate::core::bin::property::{read_tree, remove_ritobin_cache};
+use crate::error::{Error, Result};
+use ltk_meta::{BinProperty, BinTree, BinTreeObject};
+use ltk_ritobin::HashProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Version a conflict is resolved to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeSide {
+    Ours,
+    Theirs,
+}
+
+/// An object or field both sides changed differently
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// Key of the conflict in the resolution map: the object, or
+    /// `object.field` for a field
+    pub id: String,
+    /// Object path, or its hash as "0x…"
+    pub object: String,
+    /// Field name, or its hash as "0x…"; `None` when the whole object conflicts
+    pub field: Option<String>,
+    /// Each version in the BIN's JSON form, `None` where it doesn't exist
+    pub base: Option<serde_json::Value>,
+    pub ours: Option<serde_json::Value>,
+    pub theirs: Option<serde_json::Value>,
+    /// Version the merge took; `None` when unresolved, which keeps ours
+    pub resolution: Option<MergeSide>,
+}
+
+/// Result of `merge_bins`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BinMergeReport {
+    /// Objects and fields taken from theirs without conflict
+    pub applied: usize,
+    pub conflicts: Vec<MergeConflict>,
+    /// Conflicts the resolution map didn't cover
+    pub unresolved: usize,
+}
+
+/// `value` in its JSON form, for comparing versions
+fn json<T: Serialize>(value: Option<&T>) -> Result<Option<serde_json::Value>> {
+    value
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| Error::InvalidInput(format!("Failed to serialize value: {}", e)))
+}
+
+/// Merge `ours` and `theirs`, both edited from `base`. Linked BINs are
+/// merged as a set; everything else of the tree is ours.
+pub fn merge_trees<H: HashProvider>(
+    base: &BinTree,
+    ours: BinTree,
+    mut theirs: BinTree,
+    resolutions: &HashMap<String, MergeSide>,
+    hashes: &H,
+) -> Result<(BinTree, BinMergeReport)> {
+    let mut merged = ours;
+    let mut report = BinMergeReport::default();
+    let links = merge_links(&base.dependencies, &merged.dependencies, &theirs.dependencies);
+    merged.dependencies = links;
+
+    // Objects removed on both sides are in neither
+    let mut path_hashes: Vec<u32> = merged.objects.keys().copied().collect();
+    path_hashes.extend(theirs.objects.keys().copied().filter(|hash| !merged.objects.contains_key(hash)));
+    for path_hash in path_hashes {
+        let b = json(base.objects.get(&path_hash))?;
+        let o = json(merged.objects.get(&path_hash))?;
+        let t = json(theirs.objects.get(&path_hash))?;
+        if o == t || b == t {
+            continue;
+        }
+        if b == o {
+            take_object(&mut merged, path_hash, theirs.objects.shift_remove(&path_hash));
+            report.applied += 1;
+            continue;
+        }
+
+        let object = hashes.lookup_entry(path_hash).map_or_else(|| format!("{:#010x}", path_hash), str::to_string);
+        let same_class = matches!(
+            (merged.objects.get(&path_hash), theirs.objects.get(&path_hash)),
+            (Some(o), Some(t)) if o.class_hash == t.class_hash
+        );
+        if same_class {
+            if let (Some(ours_object), Some(theirs_object)) =
+                (merged.objects.get_mut(&path_hash), theirs.objects.shift_remove(&path_hash))
+            {
+                let base_object = base.objects.get(&path_hash);
+                merge_fields(base_object, ours_object, theirs_object, &object, resolutions, hashes, &mut report)?;
+            }
+            continue;
+        }
+
+        let resolution = resolutions.get(&object).copied();
+        if resolution == Some(MergeSide::Theirs) {
+            take_object(&mut merged, path_hash, theirs.objects.shift_remove(&path_hash));
+        }
+        report.conflicts.push(MergeConflict {
+            id: object.clone(),
+            object,
+            field: None,
+            base: b,
+            ours: o,
+            theirs: t,
+            resolution,
+        });
+    }
+    report.unresolved = report.conflicts.iter().filter(|conflict| conflict.resolution.is_none()).count();
+    Ok((merged, report))
+}
+
+/// Merge the fields of an object both sides changed
+fn merge_fields<H: HashProvider>(
+    base: Option<&BinTreeObject>,
+    ours: &mut BinTreeObject,
+    mut theirs: BinTreeObject,
+    object: &str,
+    resolutions: &HashMap<String, MergeSide>,
+    hashes: &H,
+    report: &mut BinMergeReport,
+) -> Result<()> {
+    let mut name_hashes: Vec<u32> = ours.properties.keys().copied().collect();
+    name_hashes.extend(theirs.properties.keys().copied().filter(|hash| !ours.properties.contains_key(hash)));
+    for name_hash in name_hashes {
+        let value = |object: Option<&BinTreeObject>| {
+            json(object.and_then(|object| object.properties.get(&name_hash)).map(|prop| &prop.value))
+        };
+        let (b, o, t) = (value(base)?, value(Some(&*ours))?, value(Some(&theirs))?);
+        if o == t || b == t {
+            continue;
+        }
+        if b == o {
+            take_field(ours, name_hash, theirs.properties.shift_remove(&name_hash));
+            report.applied += 1;
+            continue;
+        }
+
+        let field = hashes.lookup_field(name_hash).map_or_else(|| format!("{:#010x}", name_hash), str::to_string);
+        let id = format!("{}.{}", object, field);
+        let resolution = resolutions.get(&id).copied();
+        if resolution == Some(MergeSide::Theirs) {
+            take_field(ours, name_hash, theirs.properties.shift_remove(&name_hash));
+        }
+        report.conflicts.push(MergeConflict {
+            id,
+            object: object.to_string(),
+            field: Some(field),
+            base: b,
+            ours: o,
+            theirs: t,
+            resolution,
+        });
+    }
+    Ok(())
+}
+
+/// Put their version of an object in the merged tree, where ours was
+fn take_object(merged: &mut BinTree, path_hash: u32, object: Option<BinTreeObject>) {
+    match (merged.objects.get_mut(&path_hash), object) {
+        (Some(slot), Some(object)) => *slot = object,
+        (None, Some(object)) => {
+            merged.objects.insert(path_hash, object);
+        }
+        (_, None) => {
+            merged.objects.shift_remove(&path_hash);
+        }
+    }
+}
+
+/// Put their version of a field in our object, where ours was
+fn take_field(object: &mut BinTreeObject, name_hash: u32, prop: Option<BinProperty>) {
+    match (object.properties.get_mut(&name_hash), prop) {
+        (Some(slot), Some(prop)) => *slot = prop,
+        (None, Some(prop)) => {
+            object.properties.insert(name_hash, prop);
+        }
+        (_, None) => {
+            object.properties.shift_remove(&name_hash);
+        }
+    }
+}
+
+/// Our links, less those theirs removed, then those theirs added
+fn merge_links(base: &[String], ours: &[String], theirs: &[String]) -> Vec<String> {
+    let mut links: Vec<String> =
+        ours.iter().filter(|link| theirs.contains(link) || !base.contains(link)).cloned().collect();
+    for link in theirs {
+        if !base.contains(link) && !links.contains(link) {
+            links.push(link.clone());
+        }
+    }
+    links
+}
+
+/// Merge the BINs at `ours_path` and `theirs_path`, both edited from the
+/// one at `base_path`, and write the result to `output_path`, removing its
+/// stale `.ritobin` cache. Unresolved conflicts keep our version; run again
+/// with `resolutions` to settle them.
+pub fn merge_bins<H: HashProvider>(
+    base_path: &Path,
+    ours_path: &Path,
+    theirs_path: &Path,
+    output_path: &Path,
+    resolutions: &HashMap<String, MergeSide>,
+    hashes: &H,
+) -> Result<BinMergeReport> {
+    let base = read_tree(base_path)?;
+    let (merged, report) = merge_trees(&base, read_tree(ours_path)?, read_tree(theirs_path)?, resolutions, hashes)?;
+    let data = write_bin(&merged).map_err(|e| Error::bin_conversion_with_path(e.to_string(), output_path))?;
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+    }
+    fs::write(output_path, data).map_err(|e| Error::io_with_path(e, output_path))?;
+    remove_ritobin_cache(output_path);
+    tracing::info!(
+        "Merged {} into {}: {} changes applied, {} conflicts ({} unresolved)",
+        theirs_path.display(),
+        output_path.display(),
+        report.applied,
+        report.conflicts.len(),
+        report.unresolved
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bin::property::get_bin_property;
+    use crate::core::bin::{text_to_tree, HashMapProvider};
+    use crate::core::recolor::fnv1a;
+
+    fn write_version(path: &Path, fields: &str, extra: &str) {
+        let text = format!(
+            "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {{}}\nentries: map[hash,embed] = {{\n    \"Skin0\" = SkinCharacterDataProperties {{\n{}    }}\n{}}}\n",
+            fields, extra
+        );
+        fs::write(path, write_bin(&text_to_tree(&text).unwrap()).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_merge_applies_one_sided_changes_and_reports_conflicts() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = |name: &str| temp.path().join(name);
+        let removable = "    \"Removable\" = StaticMaterialDef {\n        name: string = \"old\"\n    }\n";
+        write_version(
+            &path("base.bin"),
+            "        skinScale: f32 = 1\n        iconName: string = \"base\"\n        rank: u32 = 1\n",
+            removable,
+        );
+        // Both set skinScale to 2: the same edit twice is no conflict
+        write_version(
+            &path("ours.bin"),
+            "        skinScale: f32 = 2\n        iconName: string = \"ours\"\n        rank: u32 = 1\n",
+            &format!("{}    \"Added\" = StaticMaterialDef {{\n        name: string = \"new\"\n    }}\n", removable),
+        );
+        write_version(
+            &path("theirs.bin"),
+            "        skinScale: f32 = 2\n        iconName: string = \"theirs\"\n        rank: u32 = 5\n",
+            "",
+        );
+
+        let hashes = HashMapProvider::new();
+        let merge = |resolutions: &HashMap<String, MergeSide>| {
+            merge_bins(
+                &path("base.bin"),
+                &path("ours.bin"),
+                &path("theirs.bin"),
+                &path("out.bin"),
+                resolutions,
+                &hashes,
+            )
+            .unwrap()
+        };
+        let report = merge(&HashMap::new());
+        let id = format!("{:#010x}.{:#010x}", fnv1a("Skin0"), fnv1a("iconName"));
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!((report.conflicts[0].id.as_str(), report.unresolved), (id.as_str(), 1));
+        assert!(report.conflicts[0].theirs.is_some());
+        // rank, and the removal of Removable
+        assert_eq!(report.applied, 2);
+
+        let value = |file: &str, object: &str, field: &str| get_bin_property(&path(file), object, field).unwrap().value;
+        assert_eq!(value("out.bin", "Skin0", "rank"), value("theirs.bin", "Skin0", "rank"));
+        assert_eq!(value("out.bin", "Skin0", "iconName"), value("ours.bin", "Skin0", "iconName"));
+        assert_eq!(value("out.bin", "Added", "name"), value("ours.bin", "Added", "name"));
+        assert!(get_bin_property(&path("out.bin"), "Removable", "name").is_err());
+
+        let report = merge(&HashMap::from([(id, MergeSide::Theirs)]));
+        assert_eq!((report.conflicts[0].resolution, report.unresolved), (Some(MergeSide::Theirs), 0));
+        assert_eq!(value("out.bin", "Skin0", "iconName"), value("theirs.bin", "Skin0", "iconName"));
+    }
+}
@@ -1,7 +1,15 @@
 // Bin module exports
 pub mod ltk_bridge;
+pub mod batch;
+pub mod colors;
+pub mod property;
 pub mod converter;
 pub mod concat;
+pub mod merge;
+pub mod preconvert;
+pub mod rename;
+pub mod search;
+pub mod verify;
 
 // Re-export ltk-based functions from bridge
 #[allow(unused_imports)]
@@ -12,6 +20,12 @@ pub use ltk_bridge::{
     tree_to_text_with_resolved_names,
     tree_to_text_cached,
     get_cached_bin_hashes,
+    reload_bin_hashes,
+    add_bin_hash_overlay,
+    set_bin_hash_dir,
+    bin_hash_counts,
+    is_bin_hash_file,
+    BinHashCounts,
     text_to_tree,
     HashMapProvider,
     MAX_BIN_SIZE,
@@ -32,3 +46,4 @@ pub use converter::{bin_to_text, text_to_bin, bin_to_json, json_to_bin};
 #[allow(unused_imports)]
 pub use concat::{classify_bin, concatenate_linked_bins, BinCategory, ConcatResult};
 
+pub use preconvert::preconvert_bins;
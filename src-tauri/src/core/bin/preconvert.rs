@@ -0,0 +1,187 @@
+//! Pre-conversion of project BINs to cached `.ritobin` text
+//!
+//! Opening a BIN in the editor needs its text form; converting every BIN up
+//! front makes that instant. A `.ritobin` newer than its BIN is reused.
+
+use crate::core::bin::{classify_bin, get_cached_bin_hashes, read_bin_ltk, tree_to_text_cached, BinCategory, MAX_BIN_SIZE};
+use crate::core::operation::CancellationToken;
+use crate::core::parallel;
+use crate::error::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use walkdir::WalkDir;
+
+/// Files converted per batch, to bound peak memory
+pub const BATCH_SIZE: usize = 50;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreconvertStats {
+    pub converted: usize,
+    pub failed: usize,
+    /// Already had an up-to-date `.ritobin`
+    pub skipped: usize,
+}
+
+/// Convert every eligible BIN under `project_path`.
+///
+/// `on_batch(done, total)` is called before each batch. `cancel` is checked
+/// between batches; files converted so far keep their `.ritobin`.
+pub fn preconvert_bins(
+    project_path: &Path,
+    cancel: Option<&CancellationToken>,
+    mut on_batch: impl FnMut(usize, usize),
+) -> Result<PreconvertStats> {
+    // Pre-warm the hash cache before parallel processing
+    // This ensures the cache is initialized on the main thread before workers access it
+    tracing::info!("Pre-warming BIN hash cache...");
+    let _ = get_cached_bin_hashes();
+    tracing::info!("Hash cache ready");
+
+    let bin_files = find_bin_files(project_path);
+    let total = bin_files.len();
+    tracing::info!("Found {} BIN files to convert", total);
+
+    // Filter to only files that need conversion (not already up-to-date)
+    let files_to_convert: Vec<PathBuf> = bin_files.into_iter().filter(|p| needs_conversion(p)).collect();
+    let to_convert_count = files_to_convert.len();
+    tracing::info!("[PRECONVERT] {} files need conversion, {} CACHE HITS (already up-to-date)",
+        to_convert_count, total - to_convert_count);
+
+    let converted = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+
+    for (batch_idx, batch) in files_to_convert.chunks(BATCH_SIZE).enumerate() {
+        if let Some(token) = cancel {
+            token.check()?;
+        }
+        on_batch(batch_idx * BATCH_SIZE, to_convert_count);
+
+        parallel::install(|| {
+            batch.par_iter().for_each(|bin_path| {
+                match convert_bin_file(bin_path) {
+                    Ok(_) => {
+                        converted.fetch_add(1, Ordering::Relaxed);
+                        tracing::debug!("Converted: {}", bin_path.display());
+                    }
+                    Err(e) => {
+                        failed.fetch_add(1, Ordering::Relaxed);
+                        tracing::warn!("Failed to convert {}: {}", bin_path.display(), e);
+                    }
+                }
+            })
+        });
+
+        tracing::info!("Batch {} complete: {} converted so far", batch_idx + 1, converted.load(Ordering::Relaxed));
+    }
+
+    let stats = PreconvertStats {
+        converted: converted.into_inner(),
+        failed: failed.into_inner(),
+        skipped: total - to_convert_count,
+    };
+    tracing::info!("Pre-converted {} BIN files ({} failed, {} skipped)",
+        stats.converted, stats.failed, stats.skipped);
+    Ok(stats)
+}
+
+/// BINs worth converting: animation, champion root and suspicious BINs are left out
+fn find_bin_files(project_path: &Path) -> Vec<PathBuf> {
+    WalkDir::new(project_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.path().extension()
+                .map(|ext| ext == "bin")
+                .unwrap_or(false)
+        })
+        .filter(|e| {
+            if let Ok(rel_path) = e.path().strip_prefix(project_path) {
+                let rel_str = rel_path.to_string_lossy();
+                let category = classify_bin(&rel_str);
+                
+                // Skip Ignore category (corrupt/recursive names)
+                if category == BinCategory::Ignore {
+                    tracing::warn!("Skipping suspicious BIN file: {}", rel_str);
+                    return false;
+                }
+                
+                // Skip Animation BINs - they shouldn't be pre-converted and can have corrupt metadata
+                if category == BinCategory::Animation {
+                    tracing::debug!("Skipping animation BIN: {}", rel_str);
+                    return false;
+                }
+                
+                // Skip ChampionRoot BINs - these reference game data and shouldn't be converted
+                if category == BinCategory::ChampionRoot {
+                    tracing::debug!("Skipping champion root BIN: {}", rel_str);
+                    return false;
+                }
+            }
+            true
+        })
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+fn ritobin_path(bin_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.ritobin", bin_path.display()))
+}
+
+fn needs_conversion(bin_path: &Path) -> bool {
+    let ritobin_file = ritobin_path(bin_path);
+    let name = bin_path.file_name().unwrap_or_default().to_string_lossy();
+
+    if ritobin_file.exists() {
+        if let (Ok(bin_meta), Ok(ritobin_meta)) = (fs::metadata(bin_path), fs::metadata(&ritobin_file)) {
+            if let (Ok(bin_time), Ok(ritobin_time)) = (bin_meta.modified(), ritobin_meta.modified()) {
+                if ritobin_time >= bin_time {
+                    tracing::debug!("[PRECONVERT] CACHE HIT - skipping: {}", name);
+                    return false;
+                } else {
+                    tracing::debug!("[PRECONVERT] CACHE STALE - will convert: {}", name);
+                }
+            }
+        }
+    } else {
+        tracing::debug!("[PRECONVERT] NO CACHE - will convert: {}", name);
+    }
+    true
+}
+
+/// Convert a single BIN file to ritobin
+fn convert_bin_file(bin_path: &Path) -> std::result::Result<(), String> {
+    let bin_path_str = bin_path.display();
+
+    // Check file size before reading to avoid loading huge corrupt files
+    let metadata = fs::metadata(bin_path)
+        .map_err(|e| format!("Failed to get file metadata for '{}': {}", bin_path_str, e))?;
+    
+    let file_size = metadata.len() as usize;
+    
+    // Reject suspiciously large files (using the same limit as ltk_bridge)
+    if file_size > MAX_BIN_SIZE {
+        return Err(format!(
+            "BIN file too large ({} bytes, max {} bytes) - likely corrupt, skipping: {}",
+            file_size, MAX_BIN_SIZE, bin_path_str
+        ));
+    }
+    
+    let data = fs::read(bin_path)
+        .map_err(|e| format!("Failed to read file '{}': {}", bin_path_str, e))?;
+
+    let bin = read_bin_ltk(&data)
+        .map_err(|e| format!("Failed to parse bin file '{}': {}", bin_path_str, e))?;
+
+    // Use cached hash resolution for performance
+    let text = tree_to_text_cached(&bin)
+        .map_err(|e| format!("Failed to convert to text for '{}': {}", bin_path_str, e))?;
+
+    let ritobin_path = ritobin_path(bin_path);
+    fs::write(&ritobin_path, &text)
+        .map_err(|e| format!("Failed to write ritobin '{}': {}", ritobin_path.display(), e))?;
+
+    Ok(())
+}
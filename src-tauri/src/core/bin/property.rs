@@ -0,0 +1,287 @@
+//! Reading and setting one property of a BIN
+//!
+//! A property is addressed by its object, as a path or "0x…" hash, and a
+//! path into the object: field names joined by dots, with `[n]` for list
+//! items and `[key]` for map entries, e.g. `emitters[2].birthColor` or
+//! `resourceMap[Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis]`.
+//! Field names may also be given as "0x…" hashes. Options are stepped
+//! through as if they were their value. New values are written in ritobin
+//! syntax with their type (`f32` and `1.5`, `rgba` and `{ 255, 0, 0, 255 }`)
+//! and must be of the same kind as the value they replace.
+
+use crate::core::bin::ltk_bridge::{read_bin, text_to_tree, write_bin};
+use crate::core::recolor::parse_object_name;
+use crate::error::{Error, Result};
+use ltk_meta::{BinTree, PropertyValueEnum};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A property value as the frontend gets it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinPropertyValue {
+    /// Kind of value, e.g. "F32", "Container" or "Embedded"
+    pub kind: String,
+    /// The value serialized as in the BIN's JSON form
+    pub value: serde_json::Value,
+}
+
+impl BinPropertyValue {
+    fn new(value: &PropertyValueEnum) -> Result<Self> {
+        let value_json =
+            serde_json::to_value(value).map_err(|e| Error::InvalidInput(format!("Failed to serialize value: {}", e)))?;
+        Ok(Self { kind: kind_name(value), value: value_json })
+    }
+}
+
+/// One step of a property path
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    /// Field of a struct or embed
+    Field(String),
+    /// Item of a list, or entry of a map
+    Index(String),
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Field(name) => write!(f, ".{}", name),
+            Step::Index(key) => write!(f, "[{}]", key),
+        }
+    }
+}
+
+fn parse_path(path: &str) -> Result<Vec<Step>> {
+    let invalid = |reason: &str| Error::InvalidInput(format!("Invalid property path '{}': {}", path, reason));
+    let mut steps = Vec::new();
+    let mut rest = path.trim();
+    let mut expect_field = true;
+    while expect_field || !rest.is_empty() {
+        if expect_field {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(invalid("missing a field name"));
+            }
+            steps.push(Step::Field(rest[..end].to_string()));
+            rest = &rest[end..];
+            expect_field = false;
+        } else if let Some(after) = rest.strip_prefix('.') {
+            rest = after;
+            expect_field = true;
+        } else if let Some(inner) = rest.strip_prefix('[') {
+            let end = inner.find(']').ok_or_else(|| invalid("'[' is never closed"))?;
+            let key = inner[..end].trim();
+            let key = key.strip_prefix('"').and_then(|key| key.strip_suffix('"')).unwrap_or(key);
+            steps.push(Step::Index(key.to_string()));
+            rest = &inner[end + 1..];
+        } else {
+            return Err(invalid("expected '.' or '[' after ']'"));
+        }
+    }
+    Ok(steps)
+}
+
+/// Kind of a value, its variant name
+fn kind_name(value: &PropertyValueEnum) -> String {
+    let debug = format!("{:?}", value);
+    debug.split(['(', ' ', '{']).next().unwrap_or_default().to_string()
+}
+
+/// Whether map key `key` is the one written `wanted`: a string, a hash by
+/// name or "0x…", or a number
+fn key_matches(key: &PropertyValueEnum, wanted: &str) -> bool {
+    match key {
+        PropertyValueEnum::String(s) => s.0 == wanted,
+        PropertyValueEnum::Hash(h) => h.0 == parse_object_name(wanted),
+        PropertyValueEnum::ObjectLink(link) => link.0 == parse_object_name(wanted),
+        PropertyValueEnum::U8(v) => v.0.to_string() == wanted,
+        PropertyValueEnum::U16(v) => v.0.to_string() == wanted,
+        PropertyValueEnum::U32(v) => v.0.to_string() == wanted,
+        PropertyValueEnum::U64(v) => v.0.to_string() == wanted,
+        PropertyValueEnum::I8(v) => v.0.to_string() == wanted,
+        PropertyValueEnum::I16(v) => v.0.to_string() == wanted,
+        PropertyValueEnum::I32(v) => v.0.to_string() == wanted,
+        PropertyValueEnum::I64(v) => v.0.to_string() == wanted,
+        _ => false,
+    }
+}
+
+fn list_item<'a>(items: &'a mut [PropertyValueEnum], index: &str) -> Option<&'a mut PropertyValueEnum> {
+    index.parse::<usize>().ok().and_then(|index| items.get_mut(index))
+}
+
+/// The value `step` leads to from `value`, which is at `at`
+fn step_into<'a>(value: &'a mut PropertyValueEnum, step: &Step, at: &str) -> Result<&'a mut PropertyValueEnum> {
+    let missing = || Error::NotFound(format!("{}{} does not exist", at, step));
+    match (value, step) {
+        (PropertyValueEnum::Optional(o), _) => match o.value.as_deref_mut() {
+            Some(inner) => step_into(inner, step, at),
+            None => Err(Error::NotFound(format!("{} is an empty option", at))),
+        },
+        (PropertyValueEnum::Struct(s), Step::Field(name)) => {
+            s.properties.get_mut(&parse_object_name(name)).map(|prop| &mut prop.value).ok_or_else(missing)
+        }
+        (PropertyValueEnum::Embedded(e), Step::Field(name)) => {
+            e.0.properties.get_mut(&parse_object_name(name)).map(|prop| &mut prop.value).ok_or_else(missing)
+        }
+        (PropertyValueEnum::Container(c), Step::Index(index)) => list_item(&mut c.items, index).ok_or_else(missing),
+        (PropertyValueEnum::UnorderedContainer(c), Step::Index(index)) => {
+            list_item(&mut c.0.items, index).ok_or_else(missing)
+        }
+        (PropertyValueEnum::Map(m), Step::Index(key)) => {
+            m.entries.iter_mut().find(|(k, _)| key_matches(&k.0, key)).map(|(_, v)| v).ok_or_else(missing)
+        }
+        (value, _) => Err(Error::InvalidInput(format!("{} is a {} and has no {}", at, kind_name(value), step))),
+    }
+}
+
+/// The value at `path` in `object`
+pub fn property_mut<'a>(tree: &'a mut BinTree, object: &str, path: &str) -> Result<&'a mut PropertyValueEnum> {
+    let steps = parse_path(path)?;
+    let Some((Step::Field(first), rest)) = steps.split_first() else {
+        return Err(Error::InvalidInput(format!("Invalid property path '{}'", path)));
+    };
+    let entry = tree
+        .objects
+        .get_mut(&parse_object_name(object))
+        .ok_or_else(|| Error::NotFound(format!("Object '{}' is not in the BIN", object)))?;
+    let mut value = entry
+        .properties
+        .get_mut(&parse_object_name(first))
+        .map(|prop| &mut prop.value)
+        .ok_or_else(|| Error::NotFound(format!("'{}' has no field {}", object, first)))?;
+    let mut at = first.clone();
+    for step in rest {
+        value = step_into(value, step, &at)?;
+        at.push_str(&step.to_string());
+    }
+    Ok(value)
+}
+
+/// Parse `value` written in ritobin syntax as a value of type `value_type`,
+/// e.g. "vec3" and "{ 1, 2, 3 }" or "list[string]" and `{ "a" "b" }`
+pub fn parse_value(value_type: &str, value: &str) -> Result<PropertyValueEnum> {
+    let text = format!(
+        "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {{}}\nentries: map[hash,embed] = {{\n    \"Flint\" = Flint {{\n        value: {} = {}\n    }}\n}}\n",
+        value_type, value
+    );
+    let invalid = |reason: String| Error::InvalidInput(format!("Invalid {} value '{}': {}", value_type, value, reason));
+    let tree = text_to_tree(&text).map_err(|e| invalid(e.to_string()))?;
+    tree.objects
+        .into_values()
+        .next()
+        .and_then(|object| object.properties.into_values().next())
+        .map(|prop| prop.value)
+        .ok_or_else(|| invalid("nothing was parsed".to_string()))
+}
+
+/// Replace the value at `path` in `object` with `value`, returning the old
+/// one. The new value must be of the same kind.
+pub fn set_property(tree: &mut BinTree, object: &str, path: &str, value: PropertyValueEnum) -> Result<PropertyValueEnum> {
+    let target = property_mut(tree, object, path)?;
+    if std::mem::discriminant(target) != std::mem::discriminant(&value) {
+        return Err(Error::InvalidInput(format!(
+            "{} is a {}, not a {}",
+            path,
+            kind_name(target),
+            kind_name(&value)
+        )));
+    }
+    Ok(std::mem::replace(target, value))
+}
+
+pub(crate) fn read_tree(bin_path: &Path) -> Result<BinTree> {
+    let data = fs::read(bin_path).map_err(|e| Error::io_with_path(e, bin_path))?;
+    read_bin(&data).map_err(|e| Error::bin_conversion_with_path(e.to_string(), bin_path))
+}
+
+/// Cached ritobin text of a BIN, stale once the BIN changes
+pub fn ritobin_path(bin_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.ritobin", bin_path.display()))
+}
+
+/// Remove the cached ritobin text of a BIN that was just written
+pub(crate) fn remove_ritobin_cache(bin_path: &Path) {
+    let cache = ritobin_path(bin_path);
+    if let Err(e) = fs::remove_file(&cache) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove stale {}: {}", cache.display(), e);
+        }
+    }
+}
+
+/// Read the value at `path` in `object` of the BIN at `bin_path`
+pub fn get_bin_property(bin_path: &Path, object: &str, path: &str) -> Result<BinPropertyValue> {
+    let mut tree = read_tree(bin_path)?;
+    BinPropertyValue::new(property_mut(&mut tree, object, path)?)
+}
+
+/// Set the value at `path` in `object` of the BIN at `bin_path` and write
+/// it back, removing its stale `.ritobin` cache. Returns the old value;
+/// nothing is written if the path doesn't resolve or the kinds differ.
+pub fn set_bin_property(
+    bin_path: &Path,
+    object: &str,
+    path: &str,
+    value_type: &str,
+    value: &str,
+) -> Result<BinPropertyValue> {
+    let value = parse_value(value_type, value)?;
+    let mut tree = read_tree(bin_path)?;
+    let old = set_property(&mut tree, object, path, value)?;
+    let data = write_bin(&tree).map_err(|e| Error::bin_conversion_with_path(e.to_string(), bin_path))?;
+    fs::write(bin_path, data).map_err(|e| Error::io_with_path(e, bin_path))?;
+    remove_ritobin_cache(bin_path);
+    tracing::info!("Set {} of {} in {}", path, object, bin_path.display());
+    BinPropertyValue::new(&old)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_and_set_nested_property() {
+        let text = "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {}\nentries: map[hash,embed] = {\n    \"Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis\" = VfxSystemDefinitionData {\n        complexEmitterDefinitionData: list[pointer] = {\n            VfxEmitterDefinitionData {\n                rate: f32 = 2\n                birthColor: option[rgba] = { { 255, 0, 0, 255 } }\n            }\n        }\n        particleName: string = \"Ahri_Base_Q_Mis\"\n        assetRemappingTable: map[hash,string] = {\n            \"missile\" = \"ASSETS/Ahri/Q.dds\"\n        }\n    }\n}\n";
+        let temp = tempfile::tempdir().unwrap();
+        let bin_path = temp.path().join("skin0.bin");
+        fs::write(&bin_path, write_bin(&text_to_tree(text).unwrap()).unwrap()).unwrap();
+        fs::write(ritobin_path(&bin_path), text).unwrap();
+        let object = "Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis";
+
+        let rate = get_bin_property(&bin_path, object, "complexEmitterDefinitionData[0].rate").unwrap();
+        assert_eq!(rate.kind, "F32");
+        let old = set_bin_property(&bin_path, object, "complexEmitterDefinitionData[0].rate", "f32", "4.5").unwrap();
+        assert_eq!(old.value, rate.value);
+        assert!(!ritobin_path(&bin_path).exists());
+        let rate = get_bin_property(&bin_path, object, "complexEmitterDefinitionData[0].rate").unwrap();
+        assert_ne!(rate.value, old.value);
+
+        set_bin_property(&bin_path, object, "complexEmitterDefinitionData[0].birthColor", "option[rgba]", "{ { 0, 0, 255, 255 } }")
+            .unwrap();
+        let remapped = get_bin_property(&bin_path, object, "assetRemappingTable[missile]").unwrap();
+        assert_eq!(remapped.kind, "String");
+
+        // A kind mismatch or a path that doesn't resolve leaves the file alone
+        let before = fs::read(&bin_path).unwrap();
+        let mismatch = set_bin_property(&bin_path, object, "particleName", "f32", "1");
+        assert!(matches!(mismatch, Err(Error::InvalidInput(_))));
+        let missing = set_bin_property(&bin_path, object, "complexEmitterDefinitionData[3].rate", "f32", "1");
+        assert!(matches!(missing, Err(Error::NotFound(_))));
+        assert!(matches!(get_bin_property(&bin_path, "Missing", "rate"), Err(Error::NotFound(_))));
+        assert_eq!(fs::read(&bin_path).unwrap(), before);
+
+        assert_eq!(parse_path("a.b[2][\"x.y\"].c").unwrap(), vec![
+            Step::Field("a".into()),
+            Step::Field("b".into()),
+            Step::Index("2".into()),
+            Step::Index("x.y".into()),
+            Step::Field("c".into()),
+        ]);
+        assert!(parse_path("a..b").is_err());
+        assert!(parse_path("a[1").is_err());
+        assert!(parse_path("a[1]b").is_err());
+    }
+}
@@ -0,0 +1,379 @@
+//! Renaming a VFX system across a project's BINs
+//!
+//! A skin that copies a base system has to rename it, or the base skin's
+//! definition and the copy collide in game. The system's object path is
+//! stored hashed as its key, in object links and in hash fields, and as
+//! plain text in strings; resource resolver maps key it by its short name
+//! (the last path segment) as well. `rename_vfx_system` rewrites all of
+//! them and lists every place it touched. Like `recolor`, nothing is written
+//! until the returned writes are applied, so the files can be snapshotted
+//! for undo first.
+
+use crate::core::bin::ltk_bridge::{read_bin, write_bin};
+use crate::core::mapping::normalize_path;
+use crate::core::operation::CancellationToken;
+use crate::core::recolor::{fnv1a, parse_object_name};
+use crate::core::undo::FileSnapshot;
+use crate::error::{Error, Result};
+use ltk_meta::PropertyValueEnum;
+use ltk_ritobin::HashProvider;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// What a renamed location held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenameKind {
+    /// The system's own object key
+    Definition,
+    Link,
+    Hash,
+    String,
+    /// A map key, such as a resource resolver entry
+    MapKey,
+}
+
+/// One place a rename changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameLocation {
+    /// BIN relative to `content/base`
+    pub file: String,
+    /// Object holding the reference, or its hash as "0x1a2b3c4d" when unknown
+    pub object: String,
+    /// Field path inside the object, e.g. "resourceMap"; empty for the definition
+    pub field: String,
+    pub kind: RenameKind,
+    pub old: String,
+    pub new: String,
+}
+
+/// Result of `rename_vfx_system`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RenameReport {
+    pub dry_run: bool,
+    pub old_name: String,
+    pub new_name: String,
+    pub locations: Vec<RenameLocation>,
+    /// BINs the rename changes
+    pub files: Vec<String>,
+    /// BINs that couldn't be read; references in them are not renamed
+    pub unreadable: Vec<String>,
+    /// Whether `undo_last` can revert the rename; false for a dry run or
+    /// when the old files are too large for the undo journal
+    pub undoable: bool,
+}
+
+/// New contents of the BINs a rename changes
+pub struct RenameWrites {
+    files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl RenameWrites {
+    /// Snapshots of every file `write` changes, including the `.ritobin`
+    /// caches it removes
+    pub fn snapshot(&self) -> Result<Vec<FileSnapshot>> {
+        let mut paths = Vec::new();
+        for (path, _) in &self.files {
+            paths.push(path.clone());
+            paths.push(ritobin_path(path));
+        }
+        FileSnapshot::capture(&paths)
+    }
+
+    /// Write the new BINs and remove their stale `.ritobin` caches
+    pub fn write(self) -> Result<()> {
+        for (path, data) in &self.files {
+            fs::write(path, data).map_err(|e| Error::io_with_path(e, path))?;
+            let cache = ritobin_path(path);
+            if let Err(e) = fs::remove_file(&cache) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to remove stale {}: {}", cache.display(), e);
+                }
+            }
+        }
+        tracing::info!("Renamed references in {} BINs", self.files.len());
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// The old and new forms of the system's name a rename replaces
+struct RenameNames {
+    old_hash: u32,
+    new_hash: u32,
+    /// (old, new, old label, new label) for hash values: the object path,
+    /// and the short name when it changes
+    hashes: Vec<(u32, u32, String, String)>,
+    /// (old lowercased, new) for string values
+    strings: Vec<(String, String)>,
+}
+
+impl RenameNames {
+    /// `old_name` is the known object path, if any
+    fn new(old_hash: u32, old_name: Option<&str>, new_name: &str) -> Self {
+        let new_hash = fnv1a(new_name);
+        let old_label = old_name.map_or_else(|| format!("{:#010x}", old_hash), str::to_string);
+        let mut names = RenameNames {
+            old_hash,
+            new_hash,
+            hashes: vec![(old_hash, new_hash, old_label, new_name.to_string())],
+            strings: Vec::new(),
+        };
+        if let Some(old_name) = old_name {
+            names.strings.push((old_name.to_lowercase(), new_name.to_string()));
+            let (old_short, new_short) = (short_name(old_name), short_name(new_name));
+            if old_short != old_name && !old_short.eq_ignore_ascii_case(new_short) {
+                names.hashes.push((fnv1a(old_short), fnv1a(new_short), old_short.to_string(), new_short.to_string()));
+                names.strings.push((old_short.to_lowercase(), new_short.to_string()));
+            }
+        }
+        names
+    }
+}
+
+/// Work out the rename of the VFX system `old_name` (an object path, or its
+/// hash as "0x…") to `new_name` in every BIN under `content_base`. Fails if
+/// no BIN defines the system or an object named `new_name` already exists.
+/// Nothing is written; the returned writes apply the rename.
+pub fn rename_vfx_system<H: HashProvider>(
+    content_base: &Path,
+    old_name: &str,
+    new_name: &str,
+    hashes: &H,
+    dry_run: bool,
+    cancel: &CancellationToken,
+) -> Result<(RenameReport, RenameWrites)> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name.starts_with("0x") {
+        return Err(Error::InvalidInput("The new system name must be an object path".into()));
+    }
+    let old_hash = parse_object_name(old_name.trim());
+    if fnv1a(new_name) == old_hash {
+        return Err(Error::InvalidInput(format!("'{}' already has that name", old_name)));
+    }
+    let known_name = if old_name.starts_with("0x") { hashes.lookup_entry(old_hash) } else { Some(old_name.trim()) };
+    let names = RenameNames::new(old_hash, known_name, new_name);
+    let system_class = fnv1a("VfxSystemDefinitionData");
+
+    let mut report = RenameReport {
+        dry_run,
+        old_name: old_name.to_string(),
+        new_name: new_name.to_string(),
+        ..Default::default()
+    };
+    let mut writes = RenameWrites { files: Vec::new() };
+    let mut defined = false;
+
+    for entry in WalkDir::new(content_base)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+    {
+        cancel.check()?;
+        let path = entry.path();
+        let file = normalize_path(&path.strip_prefix(content_base).unwrap_or(path).to_string_lossy());
+        let bin = fs::read(path)
+            .map_err(|e| Error::io_with_path(e, path))
+            .and_then(|data| read_bin(&data).map_err(|e| Error::InvalidInput(format!("Failed to parse BIN: {}", e))));
+        let mut bin = match bin {
+            Ok(bin) => bin,
+            Err(e) => {
+                tracing::debug!("Skipping {} for rename: {}", path.display(), e);
+                report.unreadable.push(file);
+                continue;
+            }
+        };
+
+        if bin.objects.contains_key(&names.new_hash) {
+            return Err(Error::InvalidInput(format!("{} already has an object named '{}'", file, new_name)));
+        }
+        let mut locations = Vec::new();
+        if let Some(object) = bin.objects.get(&old_hash) {
+            if object.class_hash != system_class {
+                return Err(Error::InvalidInput(format!("'{}' in {} is not a VFX system", old_name, file)));
+            }
+            defined = true;
+            bin.objects = std::mem::take(&mut bin.objects)
+                .into_iter()
+                .map(|(path_hash, mut object)| {
+                    if path_hash == old_hash {
+                        object.path_hash = names.new_hash;
+                        (names.new_hash, object)
+                    } else {
+                        (path_hash, object)
+                    }
+                })
+                .collect();
+            locations.push(RenameLocation {
+                file: file.clone(),
+                object: new_name.to_string(),
+                field: String::new(),
+                kind: RenameKind::Definition,
+                old: names.hashes[0].2.clone(),
+                new: new_name.to_string(),
+            });
+        }
+
+        for (path_hash, object) in bin.objects.iter_mut() {
+            let mut rename = ValueRename { names: &names, hashes, found: Vec::new() };
+            for (name_hash, prop) in object.properties.iter_mut() {
+                rename.value(&mut prop.value, &field_name(hashes, *name_hash));
+            }
+            let object_name = if *path_hash == names.new_hash {
+                new_name.to_string()
+            } else {
+                hashes.lookup_entry(*path_hash).map_or_else(|| format!("{:#010x}", path_hash), str::to_string)
+            };
+            locations.extend(rename.found.into_iter().map(|(field, kind, old, new)| RenameLocation {
+                file: file.clone(),
+                object: object_name.clone(),
+                field,
+                kind,
+                old,
+                new,
+            }));
+        }
+        if locations.is_empty() {
+            continue;
+        }
+
+        if !dry_run {
+            let data = write_bin(&bin).map_err(|e| Error::InvalidInput(format!("Failed to write BIN: {}", e)))?;
+            writes.files.push((path.to_path_buf(), data));
+        }
+        report.files.push(file);
+        report.locations.extend(locations);
+    }
+
+    if !defined {
+        return Err(Error::NotFound(format!("VFX system '{}' is not in the project's BINs", old_name)));
+    }
+    tracing::info!(
+        "Rename of '{}' to '{}': {} locations in {} BINs",
+        old_name,
+        new_name,
+        report.locations.len(),
+        report.files.len()
+    );
+    Ok((report, writes))
+}
+
+/// Walks a BIN value, renaming references to the system
+struct ValueRename<'a, H> {
+    names: &'a RenameNames,
+    hashes: &'a H,
+    /// (field, kind, old, new) of each rename
+    found: Vec<(String, RenameKind, String, String)>,
+}
+
+impl<H: HashProvider> ValueRename<'_, H> {
+    fn value(&mut self, value: &mut PropertyValueEnum, field: &str) {
+        match value {
+            PropertyValueEnum::ObjectLink(link) if link.0 == self.names.old_hash => {
+                let (_, new, old_label, new_label) = &self.names.hashes[0];
+                link.0 = *new;
+                self.found.push((field.to_string(), RenameKind::Link, old_label.clone(), new_label.clone()));
+            }
+            PropertyValueEnum::Hash(h) => {
+                if let Some((_, new, old_label, new_label)) = self.names.hashes.iter().find(|(old, ..)| *old == h.0) {
+                    h.0 = *new;
+                    self.found.push((field.to_string(), RenameKind::Hash, old_label.clone(), new_label.clone()));
+                }
+            }
+            PropertyValueEnum::String(s) => {
+                let lower = s.0.to_lowercase();
+                if let Some((_, new)) = self.names.strings.iter().find(|(old, _)| *old == lower) {
+                    let old = std::mem::replace(&mut s.0, new.clone());
+                    self.found.push((field.to_string(), RenameKind::String, old, new.clone()));
+                }
+            }
+            PropertyValueEnum::Container(c) => {
+                for item in &mut c.items {
+                    self.value(item, field);
+                }
+            }
+            PropertyValueEnum::UnorderedContainer(c) => {
+                for item in &mut c.0.items {
+                    self.value(item, field);
+                }
+            }
+            PropertyValueEnum::Struct(s) => {
+                for (name_hash, prop) in s.properties.iter_mut() {
+                    let child = format!("{}.{}", field, field_name(self.hashes, *name_hash));
+                    self.value(&mut prop.value, &child);
+                }
+            }
+            PropertyValueEnum::Embedded(e) => {
+                for (name_hash, prop) in e.0.properties.iter_mut() {
+                    let child = format!("{}.{}", field, field_name(self.hashes, *name_hash));
+                    self.value(&mut prop.value, &child);
+                }
+            }
+            PropertyValueEnum::Optional(o) => {
+                if let Some(inner) = &mut o.value {
+                    self.value(inner.as_mut(), field);
+                }
+            }
+            PropertyValueEnum::Map(m) => {
+                // Keys can't be changed in place; rebuild the map in order
+                m.entries = std::mem::take(&mut m.entries)
+                    .into_iter()
+                    .map(|(mut key, mut val)| {
+                        let start = self.found.len();
+                        self.value(&mut key.0, field);
+                        for found in &mut self.found[start..] {
+                            found.1 = RenameKind::MapKey;
+                        }
+                        self.value(&mut val, field);
+                        (key, val)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Last segment of an object path, e.g. "Ahri_Base_Q_Mis" of
+/// "Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis"
+fn short_name(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+fn field_name<H: HashProvider>(hashes: &H, name_hash: u32) -> String {
+    hashes.lookup_field(name_hash).map_or_else(|| format!("{:#010x}", name_hash), str::to_string)
+}
+
+fn ritobin_path(bin_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.ritobin", bin_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_names() {
+        let names = RenameNames::new(
+            fnv1a("Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis"),
+            Some("Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis"),
+            "Characters/Ahri/Skins/Skin0/Particles/Ahri_Skin3_Q_Mis",
+        );
+        assert_eq!(names.new_hash, fnv1a("characters/ahri/skins/skin0/particles/ahri_skin3_q_mis"));
+        assert_eq!(names.hashes.len(), 2);
+        assert_eq!(names.hashes[1].0, fnv1a("Ahri_Base_Q_Mis"));
+        assert_eq!(names.strings[1], ("ahri_base_q_mis".to_string(), "Ahri_Skin3_Q_Mis".to_string()));
+
+        // Unknown names can only be renamed where they are hashed
+        let names = RenameNames::new(0x1a2b_3c4d, None, "Ahri_Skin3_Q_Mis");
+        assert_eq!(names.hashes[0].2, "0x1a2b3c4d");
+        assert!(names.strings.is_empty());
+    }
+}
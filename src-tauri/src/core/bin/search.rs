@@ -0,0 +1,403 @@
+//! Searching every BIN of a project for a name or value
+//!
+//! A query is a "0x…" hash, a number, or text. Text matches resolved field,
+//! object and hash names and string values case-insensitively, and hashes of
+//! the text exactly, so unresolved fields are still found by name. Parsed
+//! BINs are cached with the file's size and modification time, so paging
+//! through results or searching again only re-reads BINs that changed.
+
+use crate::core::bin::{read_bin_ltk, BinTree, PropertyValueEnum, MAX_BIN_SIZE};
+use crate::core::parallel;
+use crate::core::recolor::fnv1a;
+use crate::error::{Error, Result};
+use ltk_ritobin::HashProvider;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// Hits a search keeps; broader queries are cut off here
+pub const MAX_BIN_SEARCH_HITS: usize = 5000;
+
+/// What `search_bins` looks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinQuery {
+    /// Hash values, links and names equal to it
+    Hash(u32),
+    /// Numeric values equal to it
+    Number(f64),
+    /// Names and strings containing `text`, lowercased, or hashed to `hash`
+    Text { text: String, hash: u32 },
+}
+
+impl BinQuery {
+    pub fn parse(query: &str) -> Result<Self> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(Error::InvalidInput("Search query is empty".to_string()));
+        }
+        if let Some(hash) = query.strip_prefix("0x").and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+            return Ok(Self::Hash(hash));
+        }
+        if let Some(number) = query.parse::<f64>().ok().filter(|n| n.is_finite()) {
+            return Ok(Self::Number(number));
+        }
+        Ok(Self::Text { text: query.to_lowercase(), hash: fnv1a(query) })
+    }
+
+    /// Whether a hash, with its resolved name if known, matches
+    fn matches_name(&self, hash: u32, name: Option<&str>) -> bool {
+        match self {
+            BinQuery::Hash(wanted) => hash == *wanted,
+            BinQuery::Text { text, hash: wanted } => {
+                hash == *wanted || name.is_some_and(|name| name.to_lowercase().contains(text.as_str()))
+            }
+            BinQuery::Number(_) => false,
+        }
+    }
+
+    fn matches_string(&self, value: &str) -> bool {
+        matches!(self, BinQuery::Text { text, .. } if value.to_lowercase().contains(text.as_str()))
+    }
+
+    fn matches_number(&self, value: f64) -> bool {
+        // f32 values rarely equal what was typed exactly
+        matches!(self, BinQuery::Number(n) if (n - value).abs() <= 1e-4 * n.abs().max(1.0))
+    }
+}
+
+/// Whether a hit is on a property's name or its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinMatchKind {
+    Name,
+    Value,
+}
+
+/// One property matching the query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinSearchHit {
+    /// BIN path relative to the project, with forward slashes
+    pub file: String,
+    /// Object path, or its hash as "0x…"
+    pub object: String,
+    /// Property path as `get_bin_property` takes it
+    pub path: String,
+    /// The name or value that matched, as text
+    pub value: String,
+    pub matched: BinMatchKind,
+}
+
+/// What a search went through
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BinScanStats {
+    pub files_searched: usize,
+    /// BINs that came from the cache
+    pub cached: usize,
+    /// BINs that couldn't be read, with the reason
+    pub failed: Vec<String>,
+    /// More than `MAX_BIN_SEARCH_HITS` properties matched
+    pub truncated: bool,
+}
+
+struct CachedTree {
+    modified: Option<SystemTime>,
+    len: u64,
+    tree: BinTree,
+}
+
+/// BINs parsed by searches so far
+#[derive(Clone, Default)]
+pub struct BinTreeCache {
+    trees: Arc<Mutex<HashMap<PathBuf, Arc<CachedTree>>>>,
+}
+
+impl BinTreeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The parsed BIN at `path`, read again if the file changed since it was
+    /// cached. The flag tells whether it came from the cache.
+    fn tree(&self, path: &Path) -> Result<(Arc<CachedTree>, bool)> {
+        let metadata = fs::metadata(path).map_err(|e| Error::io_with_path(e, path))?;
+        let (modified, len) = (metadata.modified().ok(), metadata.len());
+        if let Some(cached) = self.trees.lock().get(path) {
+            if cached.modified == modified && cached.len == len {
+                return Ok((Arc::clone(cached), true));
+            }
+        }
+
+        if len as usize > MAX_BIN_SIZE {
+            return Err(Error::bin_conversion_with_path(
+                format!("BIN file too large ({} bytes, max {} bytes), likely corrupt", len, MAX_BIN_SIZE),
+                path,
+            ));
+        }
+        let data = fs::read(path).map_err(|e| Error::io_with_path(e, path))?;
+        let tree = read_bin_ltk(&data).map_err(|e| Error::bin_conversion_with_path(e.to_string(), path))?;
+        let cached = Arc::new(CachedTree { modified, len, tree });
+        self.trees.lock().insert(path.to_path_buf(), Arc::clone(&cached));
+        Ok((cached, false))
+    }
+}
+
+/// Search every BIN under the project's `content` layers for `query`. Hits
+/// are sorted by file, then in file order, and cut off after
+/// `MAX_BIN_SEARCH_HITS`. `progress` gets the searched and total BIN counts.
+pub fn search_bins<H: HashProvider + Sync>(
+    project_path: &Path,
+    query: &BinQuery,
+    hashes: &H,
+    cache: &BinTreeCache,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Result<(Vec<BinSearchHit>, BinScanStats)> {
+    let content = project_path.join("content");
+    if !content.is_dir() {
+        return Err(Error::NotFound(format!("No content folder in {}", project_path.display())));
+    }
+    let mut bins: Vec<PathBuf> = WalkDir::new(&content)
+        .min_depth(2)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+        .collect();
+    bins.sort();
+
+    let searched = AtomicUsize::new(0);
+    let results: Vec<_> = parallel::install(|| {
+        bins.par_iter()
+            .map(|bin_path| {
+                let file = bin_path.strip_prefix(project_path).unwrap_or(bin_path).to_string_lossy().replace('\\', "/");
+                let result = cache
+                    .tree(bin_path)
+                    .map(|(cached, from_cache)| (search_tree(&cached.tree, &file, query, hashes), from_cache));
+                progress(searched.fetch_add(1, Ordering::Relaxed) + 1, bins.len());
+                (bin_path, result)
+            })
+            .collect()
+    });
+
+    let mut hits = Vec::new();
+    let mut stats = BinScanStats { files_searched: bins.len(), ..Default::default() };
+    for (bin_path, result) in results {
+        match result {
+            Ok((file_hits, from_cache)) => {
+                hits.extend(file_hits);
+                stats.cached += usize::from(from_cache);
+            }
+            Err(e) => {
+                tracing::warn!("Skipping {} in search: {}", bin_path.display(), e);
+                stats.failed.push(format!("{}: {}", bin_path.display(), e));
+            }
+        }
+    }
+    if hits.len() > MAX_BIN_SEARCH_HITS {
+        hits.truncate(MAX_BIN_SEARCH_HITS);
+        stats.truncated = true;
+    }
+    tracing::info!(
+        "Found {} properties in {} BINs ({} cached, {} failed{})",
+        hits.len(),
+        stats.files_searched,
+        stats.cached,
+        stats.failed.len(),
+        if stats.truncated { ", truncated" } else { "" }
+    );
+    Ok((hits, stats))
+}
+
+/// Hits in one BIN, at most one past `MAX_BIN_SEARCH_HITS` so truncation
+/// shows
+fn search_tree<H: HashProvider>(tree: &BinTree, file: &str, query: &BinQuery, hashes: &H) -> Vec<BinSearchHit> {
+    let mut hits = Vec::new();
+    for (path_hash, object) in &tree.objects {
+        let object_name =
+            hashes.lookup_entry(*path_hash).map_or_else(|| format!("{:#010x}", path_hash), str::to_string);
+        let mut found = Vec::new();
+        for (name_hash, prop) in &object.properties {
+            search_field(*name_hash, &prop.value, String::new(), query, hashes, &mut found);
+        }
+        hits.extend(found.into_iter().map(|(path, value, matched)| BinSearchHit {
+            file: file.to_string(),
+            object: object_name.clone(),
+            path,
+            value,
+            matched,
+        }));
+        if hits.len() > MAX_BIN_SEARCH_HITS {
+            break;
+        }
+    }
+    hits
+}
+
+type Found = Vec<(String, String, BinMatchKind)>;
+
+/// Search a field of a struct, embed or object, at `parent`
+fn search_field<H: HashProvider>(
+    name_hash: u32,
+    value: &PropertyValueEnum,
+    parent: String,
+    query: &BinQuery,
+    hashes: &H,
+    found: &mut Found,
+) {
+    let name = hashes.lookup_field(name_hash);
+    let field = name.map_or_else(|| format!("{:#010x}", name_hash), str::to_string);
+    let path = if parent.is_empty() { field.clone() } else { format!("{}.{}", parent, field) };
+    if query.matches_name(name_hash, name) {
+        found.push((path.clone(), field, BinMatchKind::Name));
+    }
+    search_value(value, path, query, hashes, found);
+}
+
+fn search_value<H: HashProvider>(
+    value: &PropertyValueEnum,
+    path: String,
+    query: &BinQuery,
+    hashes: &H,
+    found: &mut Found,
+) {
+    if let Some(text) = value_match(value, query, hashes) {
+        found.push((path, text, BinMatchKind::Value));
+        return;
+    }
+    match value {
+        PropertyValueEnum::Container(c) => {
+            for (i, item) in c.items.iter().enumerate() {
+                search_value(item, format!("{}[{}]", path, i), query, hashes, found);
+            }
+        }
+        PropertyValueEnum::UnorderedContainer(c) => {
+            for (i, item) in c.0.items.iter().enumerate() {
+                search_value(item, format!("{}[{}]", path, i), query, hashes, found);
+            }
+        }
+        PropertyValueEnum::Struct(s) => {
+            for (name_hash, prop) in &s.properties {
+                search_field(*name_hash, &prop.value, path.clone(), query, hashes, found);
+            }
+        }
+        PropertyValueEnum::Embedded(e) => {
+            for (name_hash, prop) in &e.0.properties {
+                search_field(*name_hash, &prop.value, path.clone(), query, hashes, found);
+            }
+        }
+        // Options are stepped through as their value
+        PropertyValueEnum::Optional(o) => {
+            if let Some(inner) = &o.value {
+                search_value(inner, path, query, hashes, found);
+            }
+        }
+        PropertyValueEnum::Map(m) => {
+            for (i, (key, val)) in m.entries.iter().enumerate() {
+                let entry = format!("{}[{}]", path, key_text(&key.0, hashes).unwrap_or_else(|| i.to_string()));
+                if let Some(text) = value_match(&key.0, query, hashes) {
+                    found.push((entry.clone(), text, BinMatchKind::Value));
+                }
+                search_value(val, entry, query, hashes, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A scalar value as text, when it matches
+fn value_match<H: HashProvider>(value: &PropertyValueEnum, query: &BinQuery, hashes: &H) -> Option<String> {
+    let number = |n: f64, text: String| query.matches_number(n).then_some(text);
+    match value {
+        PropertyValueEnum::String(s) => query.matches_string(&s.0).then(|| s.0.clone()),
+        PropertyValueEnum::Hash(h) => {
+            let name = hashes.lookup_hash(h.0);
+            query.matches_name(h.0, name).then(|| name.map_or_else(|| format!("{:#010x}", h.0), str::to_string))
+        }
+        // Null links are 0, not a name
+        PropertyValueEnum::ObjectLink(link) if link.0 != 0 => {
+            let name = hashes.lookup_entry(link.0);
+            query.matches_name(link.0, name).then(|| name.map_or_else(|| format!("{:#010x}", link.0), str::to_string))
+        }
+        PropertyValueEnum::U8(v) => number(v.0 as f64, v.0.to_string()),
+        PropertyValueEnum::U16(v) => number(v.0 as f64, v.0.to_string()),
+        PropertyValueEnum::U32(v) => number(v.0 as f64, v.0.to_string()),
+        PropertyValueEnum::U64(v) => number(v.0 as f64, v.0.to_string()),
+        PropertyValueEnum::I8(v) => number(v.0 as f64, v.0.to_string()),
+        PropertyValueEnum::I16(v) => number(v.0 as f64, v.0.to_string()),
+        PropertyValueEnum::I32(v) => number(v.0 as f64, v.0.to_string()),
+        PropertyValueEnum::I64(v) => number(v.0 as f64, v.0.to_string()),
+        PropertyValueEnum::F32(v) => number(v.0 as f64, v.0.to_string()),
+        _ => None,
+    }
+}
+
+/// A map key as a property path writes it: strings as they are, hashes by
+/// name or "0x…", numbers in decimal
+pub(crate) fn key_text<H: HashProvider>(key: &PropertyValueEnum, hashes: &H) -> Option<String> {
+    match key {
+        PropertyValueEnum::String(s) => Some(s.0.clone()),
+        PropertyValueEnum::Hash(h) => {
+            Some(hashes.lookup_hash(h.0).map_or_else(|| format!("{:#010x}", h.0), str::to_string))
+        }
+        PropertyValueEnum::ObjectLink(link) => {
+            Some(hashes.lookup_entry(link.0).map_or_else(|| format!("{:#010x}", link.0), str::to_string))
+        }
+        PropertyValueEnum::U8(v) => Some(v.0.to_string()),
+        PropertyValueEnum::U16(v) => Some(v.0.to_string()),
+        PropertyValueEnum::U32(v) => Some(v.0.to_string()),
+        PropertyValueEnum::U64(v) => Some(v.0.to_string()),
+        PropertyValueEnum::I8(v) => Some(v.0.to_string()),
+        PropertyValueEnum::I16(v) => Some(v.0.to_string()),
+        PropertyValueEnum::I32(v) => Some(v.0.to_string()),
+        PropertyValueEnum::I64(v) => Some(v.0.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bin::{text_to_tree, write_bin_ltk, HashMapProvider};
+
+    #[test]
+    fn test_search_bins_matches_names_and_values() {
+        let temp = tempfile::tempdir().unwrap();
+        let skins = temp.path().join("content/base/data/characters/ahri/skins");
+        fs::create_dir_all(&skins).unwrap();
+        let tree = text_to_tree(
+            "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {}\nentries: map[hash,embed] = {\n    \"Characters/Ahri/Skins/Skin0\" = SkinCharacterDataProperties {\n        skinScale: f32 = 1.25\n        iconSquare: string = \"ASSETS/Characters/Ahri/HUD/Ahri_Square.dds\"\n        tags: list[string] = { \"fox\" \"Mage\" }\n    }\n}\n",
+        )
+        .unwrap();
+        fs::write(skins.join("skin0.bin"), write_bin_ltk(&tree).unwrap()).unwrap();
+        fs::write(skins.join("skin1.bin"), b"not a bin").unwrap();
+        let (hashes, cache) = (HashMapProvider::new(), BinTreeCache::new());
+        let search =
+            |query: &str| search_bins(temp.path(), &BinQuery::parse(query).unwrap(), &hashes, &cache, |_, _| {});
+
+        // Case-insensitive, in lists too
+        let (hits, stats) = search("mage").unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].file, "content/base/data/characters/ahri/skins/skin0.bin");
+        assert_eq!(hits[0].path, format!("{:#010x}[1]", fnv1a("tags")));
+        assert_eq!((hits[0].value.as_str(), hits[0].matched), ("Mage", BinMatchKind::Value));
+        assert_eq!((stats.files_searched, stats.cached, stats.failed.len()), (2, 0, 1));
+
+        // Unresolved field names match by hash
+        let (hits, stats) = search("SkinScale").unwrap();
+        assert_eq!(hits[0].matched, BinMatchKind::Name);
+        assert_eq!(hits[0].object, format!("{:#010x}", fnv1a("Characters/Ahri/Skins/Skin0")));
+        assert_eq!(stats.cached, 1);
+
+        assert_eq!(search("1.25").unwrap().0[0].value, "1.25");
+        assert_eq!(search(&format!("{:#x}", fnv1a("iconSquare"))).unwrap().0.len(), 1);
+        assert!(search("ahri_square").unwrap().0[0].value.ends_with("Ahri_Square.dds"));
+        assert!(BinQuery::parse("  ").is_err());
+    }
+}
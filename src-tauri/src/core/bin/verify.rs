@@ -0,0 +1,224 @@
+//! Checking that a written BIN reads back as the tree it was written from
+//!
+//! Text and JSON are parsed to a tree and written by the ltk writer, which
+//! could drop or mangle values it doesn't handle. Reading the bytes back and
+//! comparing the trees catches that before the BIN is saved. Objects, structs,
+//! lists and maps are walked; other values compare by their JSON form, with
+//! floats allowed to drift by `FLOAT_TOLERANCE`.
+
+use crate::core::bin::search::key_text;
+use crate::core::bin::{read_bin_ltk, BinProperty, BinTree, PropertyValueEnum};
+use crate::error::{Error, Result};
+use ltk_ritobin::HashProvider;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Relative drift allowed between floats
+pub const FLOAT_TOLERANCE: f64 = 1e-5;
+
+/// Read `data` back and compare it with `expected`, the tree it was written
+/// from. Fails with the divergent paths if they differ.
+pub fn verify_round_trip<H: HashProvider>(expected: &BinTree, data: &[u8], path: &Path, hashes: &H) -> Result<()> {
+    let actual = read_bin_ltk(data)
+        .map_err(|e| Error::bin_conversion_with_path(format!("Written BIN doesn't read back: {}", e), path))?;
+    let divergent = diff_trees(expected, &actual, hashes);
+    if divergent.is_empty() {
+        return Ok(());
+    }
+    tracing::warn!("{} doesn't round-trip: {} divergent paths", path.display(), divergent.len());
+    Err(Error::RoundTripMismatch { path: path.to_path_buf(), divergent })
+}
+
+/// Paths where `actual` differs from `expected`, as `object.field[n]…`, with
+/// ": missing" or ": unexpected" for values only one of them has
+pub fn diff_trees<H: HashProvider>(expected: &BinTree, actual: &BinTree, hashes: &H) -> Vec<String> {
+    let mut divergent = Vec::new();
+    if expected.dependencies != actual.dependencies {
+        divergent.push("linked".to_string());
+    }
+    let object_name =
+        |path_hash: u32| hashes.lookup_entry(path_hash).map_or_else(|| format!("{:#010x}", path_hash), str::to_string);
+    for (path_hash, object) in &expected.objects {
+        let name = object_name(*path_hash);
+        match actual.objects.get(path_hash) {
+            None => divergent.push(format!("{}: missing", name)),
+            Some(other) if other.class_hash != object.class_hash => divergent.push(name),
+            Some(other) => {
+                diff_fields(fields(&object.properties), fields(&other.properties), &name, hashes, &mut divergent)
+            }
+        }
+    }
+    for path_hash in actual.objects.keys().filter(|path_hash| !expected.objects.contains_key(*path_hash)) {
+        divergent.push(format!("{}: unexpected", object_name(*path_hash)));
+    }
+    divergent
+}
+
+/// Fields of an object, struct or embed in order, by name hash
+type Fields<'a> = Vec<(u32, &'a PropertyValueEnum)>;
+
+fn fields<'a>(properties: impl IntoIterator<Item = (&'a u32, &'a BinProperty)>) -> Fields<'a> {
+    properties.into_iter().map(|(name_hash, prop)| (*name_hash, &prop.value)).collect()
+}
+
+fn diff_fields<'a, H: HashProvider>(
+    expected: Fields<'a>,
+    actual: Fields<'a>,
+    parent: &str,
+    hashes: &H,
+    divergent: &mut Vec<String>,
+) {
+    let path = |name_hash: u32| {
+        let field = hashes.lookup_field(name_hash).map_or_else(|| format!("{:#010x}", name_hash), str::to_string);
+        format!("{}.{}", parent, field)
+    };
+    let find =
+        |fields: &Fields<'a>, name_hash: u32| fields.iter().find(|(hash, _)| *hash == name_hash).map(|(_, v)| *v);
+    for (name_hash, value) in &expected {
+        match find(&actual, *name_hash) {
+            Some(other) => diff_value(value, other, path(*name_hash), hashes, divergent),
+            None => divergent.push(format!("{}: missing", path(*name_hash))),
+        }
+    }
+    for (name_hash, _) in actual.iter().filter(|(name_hash, _)| find(&expected, *name_hash).is_none()) {
+        divergent.push(format!("{}: unexpected", path(*name_hash)));
+    }
+}
+
+fn diff_items<H: HashProvider>(
+    expected: &[PropertyValueEnum],
+    actual: &[PropertyValueEnum],
+    path: &str,
+    hashes: &H,
+    divergent: &mut Vec<String>,
+) {
+    for (i, item) in expected.iter().enumerate() {
+        match actual.get(i) {
+            Some(other) => diff_value(item, other, format!("{}[{}]", path, i), hashes, divergent),
+            None => divergent.push(format!("{}[{}]: missing", path, i)),
+        }
+    }
+    for i in expected.len()..actual.len() {
+        divergent.push(format!("{}[{}]: unexpected", path, i));
+    }
+}
+
+/// Compare two values at `path`, naming the innermost values that differ,
+/// or `path` itself if the value as a whole does (its type, class or value)
+fn diff_value<H: HashProvider>(
+    expected: &PropertyValueEnum,
+    actual: &PropertyValueEnum,
+    path: String,
+    hashes: &H,
+    divergent: &mut Vec<String>,
+) {
+    match (expected, actual) {
+        (PropertyValueEnum::Container(a), PropertyValueEnum::Container(b)) => {
+            diff_items(&a.items, &b.items, &path, hashes, divergent)
+        }
+        (PropertyValueEnum::UnorderedContainer(a), PropertyValueEnum::UnorderedContainer(b)) => {
+            diff_items(&a.0.items, &b.0.items, &path, hashes, divergent)
+        }
+        (PropertyValueEnum::Struct(a), PropertyValueEnum::Struct(b)) if a.class_hash == b.class_hash => {
+            diff_fields(fields(&a.properties), fields(&b.properties), &path, hashes, divergent)
+        }
+        (PropertyValueEnum::Embedded(a), PropertyValueEnum::Embedded(b)) if a.0.class_hash == b.0.class_hash => {
+            diff_fields(fields(&a.0.properties), fields(&b.0.properties), &path, hashes, divergent)
+        }
+        (PropertyValueEnum::Optional(a), PropertyValueEnum::Optional(b)) => {
+            match (a.value.as_deref(), b.value.as_deref()) {
+                (Some(a), Some(b)) => diff_value(a, b, path, hashes, divergent),
+                (None, None) => {}
+                _ => divergent.push(path),
+            }
+        }
+        (PropertyValueEnum::Map(a), PropertyValueEnum::Map(b)) => {
+            let entry_path = |key: &PropertyValueEnum| {
+                format!("{}[{}]", path, key_text(key, hashes).unwrap_or_else(|| json(key).to_string()))
+            };
+            let actual: HashMap<String, &PropertyValueEnum> =
+                b.entries.iter().map(|(key, value)| (json(&key.0).to_string(), value)).collect();
+            let mut seen = 0;
+            for (key, value) in &a.entries {
+                match actual.get(&json(&key.0).to_string()) {
+                    Some(other) => {
+                        seen += 1;
+                        diff_value(value, other, entry_path(&key.0), hashes, divergent);
+                    }
+                    None => divergent.push(format!("{}: missing", entry_path(&key.0))),
+                }
+            }
+            if seen < actual.len() {
+                let expected: Vec<String> = a.entries.iter().map(|(key, _)| json(&key.0).to_string()).collect();
+                for (key, _) in b.entries.iter().filter(|(key, _)| !expected.contains(&json(&key.0).to_string())) {
+                    divergent.push(format!("{}: unexpected", entry_path(&key.0)));
+                }
+            }
+        }
+        // Structs of different classes, or values of different types
+        (PropertyValueEnum::Struct(_), PropertyValueEnum::Struct(_))
+        | (PropertyValueEnum::Embedded(_), PropertyValueEnum::Embedded(_)) => divergent.push(path),
+        _ if std::mem::discriminant(expected) != std::mem::discriminant(actual) => divergent.push(path),
+        _ if !close(&json(expected), &json(actual)) => divergent.push(path),
+        _ => {}
+    }
+}
+
+fn json(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Whether two JSON values are equal, floats within `FLOAT_TOLERANCE`
+fn close(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) if x.is_f64() || y.is_f64() => match (x.as_f64(), y.as_f64()) {
+            (Some(x), Some(y)) => (x - y).abs() <= FLOAT_TOLERANCE * x.abs().max(y.abs()).max(1.0),
+            _ => false,
+        },
+        (Value::Array(x), Value::Array(y)) => x.len() == y.len() && x.iter().zip(y).all(|(x, y)| close(x, y)),
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len() && x.iter().all(|(key, x)| y.get(key).is_some_and(|y| close(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bin::property::{parse_value, property_mut, set_property};
+    use crate::core::bin::{text_to_tree, write_bin_ltk, HashMapProvider};
+    use crate::core::recolor::fnv1a;
+
+    #[test]
+    fn test_round_trip_reports_divergent_paths() {
+        let text = "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {}\nentries: map[hash,embed] = {\n    \"Skin0\" = SkinCharacterDataProperties {\n        skinScale: f32 = 1.25\n        emitters: list[embed] = {\n            Emitter {\n                rate: f32 = 2\n                name: string = \"a\"\n            }\n        }\n        remap: map[string,string] = {\n            \"missile\" = \"ASSETS/Q.dds\"\n        }\n    }\n}\n";
+        let mut hashes = HashMapProvider::new();
+        hashes.insert_entry(fnv1a("Skin0"), "Skin0");
+        for field in ["skinScale", "emitters", "rate", "remap"] {
+            hashes.insert_field(fnv1a(field), field);
+        }
+        let expected = text_to_tree(text).unwrap();
+        let data = write_bin_ltk(&expected).unwrap();
+        verify_round_trip(&expected, &data, Path::new("skin0.bin"), &hashes).unwrap();
+
+        // Float drift within the tolerance is fine
+        let mut actual = text_to_tree(text).unwrap();
+        set_property(&mut actual, "Skin0", "skinScale", parse_value("f32", "1.2500001").unwrap()).unwrap();
+        assert!(diff_trees(&expected, &actual, &hashes).is_empty());
+
+        set_property(&mut actual, "Skin0", "emitters[0].rate", parse_value("f32", "3").unwrap()).unwrap();
+        set_property(&mut actual, "Skin0", "remap[missile]", parse_value("string", "\"ASSETS/W.dds\"").unwrap())
+            .unwrap();
+        if let PropertyValueEnum::Container(emitters) = property_mut(&mut actual, "Skin0", "emitters").unwrap() {
+            emitters.items.push(parse_value("embed", "Emitter {}").unwrap());
+        }
+        let divergent = diff_trees(&expected, &actual, &hashes);
+        assert_eq!(divergent, ["Skin0.emitters[0].rate", "Skin0.emitters[1]: unexpected", "Skin0.remap[missile]"]);
+
+        let error = verify_round_trip(&actual, &data, Path::new("skin0.bin"), &hashes).unwrap_err();
+        assert!(matches!(error, Error::RoundTripMismatch { divergent, .. } if divergent.len() == 3));
+    }
+}
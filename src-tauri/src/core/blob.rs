@@ -0,0 +1,132 @@
+//! Large binary responses served outside the invoke channel
+//!
+//! Tauri serializes command results to JSON, so a `Vec<u8>` crosses the IPC
+//! boundary as an array of numbers: a 50MB texture becomes a couple hundred
+//! megabytes of text, parsed on both sides. Commands returning more than
+//! `BLOB_THRESHOLD` bytes park them here instead and return a token; the
+//! frontend fetches `flint-blob://localhost/<token>`, which the protocol
+//! handler registered in `main.rs` answers with the raw bytes. Tokens are
+//! single-use and expire if never fetched.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// URI scheme the protocol handler is registered under
+pub const BLOB_SCHEME: &str = "flint-blob";
+
+/// Payloads up to this size are returned inline
+pub const BLOB_THRESHOLD: usize = 2 * 1024 * 1024;
+
+/// How long a blob waits to be fetched before it is dropped
+pub const BLOB_TTL: Duration = Duration::from_secs(60);
+
+/// Total bytes parked at once; the oldest blobs are dropped past it
+pub const BLOB_MEMORY_LIMIT: usize = 512 * 1024 * 1024;
+
+/// Bytes parked for the frontend to fetch
+pub struct Blob {
+    pub data: Vec<u8>,
+    pub mime: &'static str,
+    created: Instant,
+}
+
+/// A command's binary result, inline or behind a blob token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BinaryPayload {
+    Inline { data: Vec<u8> },
+    Blob { token: String, size: u64 },
+}
+
+/// Managed state holding blobs until they are fetched
+#[derive(Clone, Default)]
+pub struct BlobStore {
+    blobs: Arc<Mutex<HashMap<String, Blob>>>,
+}
+
+impl BlobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Park `data` and return the token it can be fetched with
+    pub fn insert(&self, data: Vec<u8>, mime: &'static str) -> String {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let mut blobs = self.blobs.lock();
+        blobs.retain(|_, blob| blob.created.elapsed() < BLOB_TTL);
+        Self::enforce_memory_limit(&mut blobs, data.len());
+        blobs.insert(token.clone(), Blob { data, mime, created: Instant::now() });
+        token
+    }
+
+    /// Remove and return the blob for `token`, if it is still there
+    pub fn take(&self, token: &str) -> Option<Blob> {
+        let blob = self.blobs.lock().remove(token)?;
+        (blob.created.elapsed() < BLOB_TTL).then_some(blob)
+    }
+
+    /// Return `data` inline if it is small, otherwise park it
+    pub fn payload(&self, data: Vec<u8>, mime: &'static str) -> BinaryPayload {
+        if data.len() <= BLOB_THRESHOLD {
+            return BinaryPayload::Inline { data };
+        }
+        let size = data.len() as u64;
+        BinaryPayload::Blob { token: self.insert(data, mime), size }
+    }
+
+    /// Drop the oldest blobs until `incoming` more bytes fit under the limit
+    fn enforce_memory_limit(blobs: &mut HashMap<String, Blob>, incoming: usize) {
+        let mut total: usize = blobs.values().map(|blob| blob.data.len()).sum::<usize>() + incoming;
+        while total > BLOB_MEMORY_LIMIT {
+            let Some(oldest) = blobs.iter().min_by_key(|(_, blob)| blob.created).map(|(token, _)| token.clone()) else {
+                break;
+            };
+            if let Some(blob) = blobs.remove(&oldest) {
+                tracing::debug!("Dropping unfetched blob {} ({} bytes)", oldest, blob.data.len());
+                total -= blob.data.len();
+            }
+        }
+    }
+
+    /// Answer a `flint-blob://localhost/<token>` request with the blob's
+    /// bytes, or 404 if the token is unknown, already fetched or expired
+    pub fn respond(&self, request: &tauri::http::Request<Vec<u8>>) -> tauri::http::Response<Vec<u8>> {
+        let token = request.uri().path().trim_start_matches('/');
+        let builder = tauri::http::Response::builder().header("Access-Control-Allow-Origin", "*");
+        let response = match self.take(token) {
+            Some(blob) => builder
+                .status(200)
+                .header("Content-Type", blob.mime)
+                .header("Content-Length", blob.data.len())
+                .body(blob.data),
+            None => builder.status(404).body(Vec::new()),
+        };
+        response.unwrap_or_else(|e| {
+            tracing::warn!("Failed to build blob response: {}", e);
+            tauri::http::Response::new(Vec::new())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_threshold_and_single_use() {
+        let store = BlobStore::new();
+        assert!(matches!(store.payload(vec![0; 16], "application/octet-stream"), BinaryPayload::Inline { .. }));
+
+        let BinaryPayload::Blob { token, size } = store.payload(vec![7; BLOB_THRESHOLD + 1], "image/png") else {
+            panic!("expected a blob");
+        };
+        assert_eq!(size, BLOB_THRESHOLD as u64 + 1);
+        let blob = store.take(&token).unwrap();
+        assert_eq!(blob.mime, "image/png");
+        assert_eq!(blob.data.len(), BLOB_THRESHOLD + 1);
+        assert!(store.take(&token).is_none());
+    }
+}
@@ -3,6 +3,7 @@
 //! This module provides functionality to scan League of Legends files
 //! and discover available champions and their skins.
 
+use crate::core::wad::{WadKind, WadName};
 use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -18,8 +19,18 @@ pub struct ChampionInfo {
     pub internal_name: String,
     /// List of available skins
     pub skins: Vec<SkinInfo>,
-    /// Path to champion WAD file
+    /// Path to the champion's client WAD
     pub wad_path: Option<String>,
+    /// Every WAD found for the champion, including mobile and locale WADs
+    #[serde(default)]
+    pub wads: Vec<ChampionWad>,
+}
+
+/// A WAD file found for a champion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChampionWad {
+    pub path: String,
+    pub kind: WadKind,
 }
 
 impl ChampionInfo {
@@ -31,6 +42,7 @@ impl ChampionInfo {
             internal_name: internal,
             skins: Vec::new(),
             wad_path: None,
+            wads: Vec::new(),
         }
     }
 
@@ -114,16 +126,10 @@ fn discover_from_directory(champions_dir: &Path) -> Result<Vec<ChampionInfo>> {
             .and_then(|n| n.to_str())
             .unwrap_or("");
 
-        // Look for .wad.client files
-        if file_name.to_lowercase().ends_with(".wad.client") {
-            if let Some(champion_name) = extract_champion_from_wad_name(file_name) {
-                let champion = champions
-                    .entry(champion_name.clone())
-                    .or_insert_with(|| ChampionInfo::new(&champion_name));
-                champion.wad_path = Some(path.to_string_lossy().to_string());
-            }
+        if path.is_file() {
+            record_champion_wad(&mut champions, &path, file_name);
         }
-        
+
         // Also look for champion folders
         if path.is_dir() && !file_name.starts_with('.') {
             let champion_name = file_name.to_string();
@@ -133,14 +139,23 @@ fn discover_from_directory(champions_dir: &Path) -> Result<Vec<ChampionInfo>> {
         }
     }
 
-    // Sort champions alphabetically
-    let mut result: Vec<ChampionInfo> = champions.into_values().collect();
-    result.sort_by(|a, b| a.name.cmp(&b.name));
+    let result = sorted_champions(champions);
 
     tracing::info!("Discovered {} champions", result.len());
     Ok(result)
 }
 
+/// Champions by display name, each with its WADs by path, so results don't
+/// depend on directory or hash map order
+fn sorted_champions(champions: HashMap<String, ChampionInfo>) -> Vec<ChampionInfo> {
+    let mut result: Vec<ChampionInfo> = champions.into_values().collect();
+    for champion in &mut result {
+        champion.wads.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+    result.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.internal_name.cmp(&b.internal_name)));
+    result
+}
+
 /// Discovers champions from WAD files in the Game folder
 fn discover_from_wad_files(league_path: &Path) -> Result<Vec<ChampionInfo>> {
     tracing::debug!("Scanning for WAD files in: {}", league_path.display());
@@ -158,8 +173,7 @@ fn discover_from_wad_files(league_path: &Path) -> Result<Vec<ChampionInfo>> {
     // Walk through looking for champion WAD files
     scan_for_champion_wads(&game_dir, &mut champions)?;
 
-    let mut result: Vec<ChampionInfo> = champions.into_values().collect();
-    result.sort_by(|a, b| a.name.cmp(&b.name));
+    let result = sorted_champions(champions);
 
     tracing::info!("Discovered {} champions from WAD files", result.len());
     Ok(result)
@@ -194,33 +208,43 @@ fn scan_for_champion_wads(dir: &Path, champions: &mut HashMap<String, ChampionIn
             let file_name = path.file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("");
-
-            if file_name.to_lowercase().ends_with(".wad.client") {
-                if let Some(champion_name) = extract_champion_from_wad_name(file_name) {
-                    let champion = champions
-                        .entry(champion_name.clone())
-                        .or_insert_with(|| ChampionInfo::new(&champion_name));
-                    champion.wad_path = Some(path.to_string_lossy().to_string());
-                }
-            }
+            record_champion_wad(champions, &path, file_name);
         }
     }
 
     Ok(())
 }
 
+/// Record a WAD under its champion; only a client WAD becomes the champion's `wad_path`
+fn record_champion_wad(champions: &mut HashMap<String, ChampionInfo>, path: &Path, file_name: &str) {
+    let Some(wad) = WadName::parse(file_name) else {
+        return;
+    };
+    let Some(champion_name) = extract_champion_from_wad_name(file_name) else {
+        return;
+    };
+
+    let champion = champions
+        .entry(champion_name.clone())
+        .or_insert_with(|| ChampionInfo::new(&champion_name));
+    let path = path.to_string_lossy().to_string();
+    if wad.kind == WadKind::Client {
+        champion.wad_path = Some(path.clone());
+    }
+    champion.wads.push(ChampionWad { path, kind: wad.kind });
+}
+
 /// Extracts champion name from a WAD filename
 ///
 /// Examples:
 /// - "Ahri.wad.client" -> Some("Ahri")
 /// - "Ahri_Base.wad.client" -> Some("Ahri")
+/// - "Ahri.en_US.wad.client" -> Some("Ahri")
 /// - "random.wad.client" -> None (not in Champions folder pattern)
 fn extract_champion_from_wad_name(filename: &str) -> Option<String> {
-    // Remove extensions
-    let name = filename
-        .strip_suffix(".wad.client")
-        .or_else(|| filename.strip_suffix(".wad"))
-        .unwrap_or(filename);
+    // Remove the locale and extensions
+    let wad = WadName::parse(filename)?;
+    let name = wad.base.as_str();
 
     // Split by underscore and take the first part
     let base_name = name.split('_').next().unwrap_or(name);
@@ -311,7 +335,7 @@ fn parse_skin_folder_name(name: &str) -> Option<u32> {
 /// - "Ahri" -> "Ahri"
 /// - "AurelionSol" -> "Aurelion Sol"
 /// - "MasterYi" -> "Master Yi"
-fn format_champion_name(internal_name: &str) -> String {
+pub(crate) fn format_champion_name(internal_name: &str) -> String {
     let mut result = String::with_capacity(internal_name.len() + 5);
     let mut prev_was_lowercase = false;
 
@@ -365,6 +389,8 @@ mod tests {
         assert_eq!(extract_champion_from_wad_name("Ahri.wad.client"), Some("Ahri".to_string()));
         assert_eq!(extract_champion_from_wad_name("Ahri_Base.wad.client"), Some("Ahri".to_string()));
         assert_eq!(extract_champion_from_wad_name("MasterYi.wad.client"), Some("MasterYi".to_string()));
+        assert_eq!(extract_champion_from_wad_name("Ahri.en_US.wad.client"), Some("Ahri".to_string()));
+        assert_eq!(extract_champion_from_wad_name("Ahri.wad.mobile"), Some("Ahri".to_string()));
         assert_eq!(extract_champion_from_wad_name("123.wad.client"), None);
     }
 
@@ -378,6 +404,28 @@ mod tests {
         assert_eq!(parse_skin_folder_name("Invalid"), None);
     }
 
+    #[test]
+    fn test_discovery_order_is_stable() {
+        let dir = tempfile::tempdir().unwrap();
+        let champions_dir = dir.path().join("Game/DATA/FINAL/Champions");
+        fs::create_dir_all(&champions_dir).unwrap();
+        for name in ["Zed.wad.client", "Ahri.wad.client", "MasterYi.wad.client", "Ahri.en_US.wad.client"] {
+            fs::write(champions_dir.join(name), b"RW").unwrap();
+        }
+
+        let first = discover_champions(dir.path()).unwrap();
+        let names: Vec<&str> = first.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Ahri", "Master Yi", "Zed"]);
+        assert!(first[0].wads[0].path.ends_with("Ahri.en_US.wad.client"));
+        assert!(first[0].wads[1].path.ends_with("Ahri.wad.client"));
+
+        let second = discover_champions(dir.path()).unwrap();
+        let wads = |champions: &[ChampionInfo]| -> Vec<String> {
+            champions.iter().flat_map(|c| c.wads.iter().map(|w| w.path.clone())).collect()
+        };
+        assert_eq!(wads(&first), wads(&second));
+    }
+
     #[test]
     fn test_champion_add_skin() {
         let mut champion = ChampionInfo::new("Ahri");
@@ -1,4 +1,6 @@
 // Champion discovery module exports
 pub mod discovery;
+pub mod skin_names;
 
 pub use discovery::{discover_champions, get_champion_skins, ChampionInfo, SkinInfo};
+pub use skin_names::{fallback_skin_name, resolve_skin_name};
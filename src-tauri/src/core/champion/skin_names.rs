@@ -0,0 +1,122 @@
+//! Skin display names
+//!
+//! Names are looked up in CommunityDragon's game data, the same source the
+//! new-project dialog uses. When the lookup fails (offline, unknown champion
+//! or skin), a numeric name like "Ahri Skin 85" is used instead; it depends
+//! only on the champion and skin ID.
+
+use super::discovery::format_champion_name;
+use crate::error::{Error, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+const CDRAGON_BASE_URL: &str =
+    "https://raw.communitydragon.org/latest/plugins/rcp-be-lol-game-data/global/default/v1";
+
+/// Don't let a slow network leave a project's name pending for long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize)]
+struct ChampionSummary {
+    id: i64,
+    alias: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChampionData {
+    #[serde(default)]
+    skins: Vec<SkinData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SkinData {
+    id: i64,
+    name: Option<String>,
+}
+
+/// Name used when the skin's real name can't be resolved
+pub fn fallback_skin_name(champion: &str, skin_id: u32) -> String {
+    let champion = format_champion_name(champion);
+    if skin_id == 0 {
+        champion
+    } else {
+        format!("{} Skin {}", champion, skin_id)
+    }
+}
+
+/// Look up the skin's localized name, e.g. "Star Guardian Ahri"
+pub async fn resolve_skin_name(champion: &str, skin_id: u32) -> Result<String> {
+    let client = Client::builder()
+        .user_agent("flint")
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(Error::Network)?;
+
+    let summary_url = format!("{}/champion-summary.json", CDRAGON_BASE_URL);
+    let summary: Vec<ChampionSummary> = fetch_json(&client, &summary_url).await?;
+    let champion_id = find_champion_id(&summary, champion)
+        .ok_or_else(|| Error::NotFound(format!("Champion '{}' not found in game data", champion)))?;
+
+    let champion_url = format!("{}/champions/{}.json", CDRAGON_BASE_URL, champion_id);
+    let data: ChampionData = fetch_json(&client, &champion_url).await?;
+    find_skin_name(&data, champion_id, skin_id)
+        .ok_or_else(|| Error::NotFound(format!("{} skin {} not found in game data", champion, skin_id)))
+}
+
+async fn fetch_json<T: serde::de::DeserializeOwned>(client: &Client, url: &str) -> Result<T> {
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(Error::Network)?
+        .json()
+        .await
+        .map_err(Error::Network)
+}
+
+/// Match the internal name against the summary's aliases ("MonkeyKing", not "Wukong")
+fn find_champion_id(summary: &[ChampionSummary], champion: &str) -> Option<i64> {
+    summary
+        .iter()
+        .find(|c| c.id > 0 && c.alias.eq_ignore_ascii_case(champion))
+        .map(|c| c.id)
+}
+
+/// Skin IDs in the game data are `champion_id * 1000 + skin number`
+fn find_skin_name(data: &ChampionData, champion_id: i64, skin_id: u32) -> Option<String> {
+    let full_id = champion_id * 1000 + i64::from(skin_id);
+    data.skins
+        .iter()
+        .find(|s| s.id == full_id)
+        .and_then(|s| s.name.clone())
+        .filter(|name| !name.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_skin_name() {
+        assert_eq!(fallback_skin_name("Ahri", 0), "Ahri");
+        assert_eq!(fallback_skin_name("Ahri", 85), "Ahri Skin 85");
+        assert_eq!(fallback_skin_name("MasterYi", 3), "Master Yi Skin 3");
+    }
+
+    #[test]
+    fn test_find_skin_name() {
+        let summary: Vec<ChampionSummary> =
+            serde_json::from_str(r#"[{"id": -1, "alias": "None"}, {"id": 103, "alias": "Ahri", "name": "Ahri"}]"#).unwrap();
+        assert_eq!(find_champion_id(&summary, "ahri"), Some(103));
+        assert_eq!(find_champion_id(&summary, "Zed"), None);
+
+        let data: ChampionData = serde_json::from_str(
+            r#"{"skins": [{"id": 103000, "name": "Ahri"}, {"id": 103085, "name": "Star Guardian Ahri"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(find_skin_name(&data, 103, 85).as_deref(), Some("Star Guardian Ahri"));
+        assert_eq!(find_skin_name(&data, 103, 7), None);
+    }
+}
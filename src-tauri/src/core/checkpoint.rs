@@ -1,14 +1,20 @@
 use crate::error::{Error, Result};
+use crate::core::parallel;
+use crate::core::disk::check_disk_space;
+use crate::core::paths::{safe_join, safe_relative};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use parking_lot::Mutex;
 use sha2::{Sha256, Digest};
 use uuid::Uuid;
 use walkdir::WalkDir;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AssetType {
     Texture,
     Model,
@@ -22,9 +28,56 @@ pub enum AssetType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileEntry {
     pub path: String,       // Relative to project root
-    pub hash: String,      // SHA256 of content
-    pub size: u64,
+    pub hash: String,      // SHA256 of the stored object
+    pub size: u64,         // Size of the file in the working tree
     pub asset_type: AssetType,
+    #[serde(default)]
+    pub storage: StorageFormat,
+}
+
+/// How a file's content is kept in the object store
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageFormat {
+    /// Stored byte-for-byte
+    #[default]
+    Raw,
+    /// A `.bin` stored as canonical ritobin text; converted back with `write_bin` on restore
+    BinText,
+}
+
+/// Convert a binary BIN to canonical ritobin text, but only if converting the
+/// text back reproduces the original bytes exactly. Returns `None` otherwise.
+fn bin_to_canonical_text(data: &[u8]) -> Option<String> {
+    use crate::core::bin::ltk_bridge::{read_bin, text_to_tree, tree_to_text, write_bin};
+
+    let tree = read_bin(data).ok()?;
+    let text = tree_to_text(&tree).ok()?;
+    let rebuilt = text_to_tree(&text).ok().and_then(|t| write_bin(&t).ok());
+    if rebuilt.as_deref() == Some(data) {
+        Some(text)
+    } else {
+        tracing::debug!("BIN did not survive text round-trip, storing raw");
+        None
+    }
+}
+
+/// Turn a stored object back into the bytes that belong in the working tree
+fn materialize_object(entry: &FileEntry, data: Vec<u8>) -> Result<Vec<u8>> {
+    use crate::core::bin::ltk_bridge::{text_to_tree, write_bin};
+
+    match entry.storage {
+        StorageFormat::Raw => Ok(data),
+        StorageFormat::BinText => {
+            let convert_err = |e: crate::core::bin::ltk_bridge::BinError| {
+                Error::bin_conversion_with_path(e.to_string(), &entry.path)
+            };
+            let text = String::from_utf8(data)
+                .map_err(|e| Error::bin_conversion_with_path(e.to_string(), &entry.path))?;
+            let tree = text_to_tree(&text).map_err(convert_err)?;
+            write_bin(&tree).map_err(convert_err)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,7 +87,70 @@ pub struct Checkpoint {
     pub message: String,
     pub author: Option<String>,
     pub tags: Vec<String>,
-    pub file_manifest: HashMap<String, FileEntry>, // path -> Entry
+    pub file_manifest: BTreeMap<String, FileEntry>, // path -> Entry, sorted by path
+    /// Flint version that created the checkpoint
+    #[serde(default)]
+    pub flint_version: Option<String>,
+    /// Installed game version at creation time (from the project's League path)
+    #[serde(default)]
+    pub game_version: Option<String>,
+    /// Ignore patterns in effect when the checkpoint was created. Paths matching
+    /// these were intentionally left out and are not touched by a restore.
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// Checkpoint the working tree descended from when this one was created
+    #[serde(default)]
+    pub parent_id: Option<String>,
+}
+
+/// Filter applied by `list_checkpoints`. All set criteria must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckpointFilter {
+    /// Match if the checkpoint has at least one of these tags
+    pub tags_any: Vec<String>,
+    /// Match only if the checkpoint has every one of these tags
+    pub tags_all: Vec<String>,
+    /// Case-insensitive substring of the checkpoint message
+    pub message_contains: Option<String>,
+    /// Inclusive lower bound on the timestamp
+    pub from: Option<DateTime<Utc>>,
+    /// Inclusive upper bound on the timestamp
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl CheckpointFilter {
+    pub fn matches(&self, checkpoint: &Checkpoint) -> bool {
+        let has_tag = |tag: &String| checkpoint.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+
+        if !self.tags_any.is_empty() && !self.tags_any.iter().any(has_tag) {
+            return false;
+        }
+        if !self.tags_all.iter().all(has_tag) {
+            return false;
+        }
+        if let Some(ref needle) = self.message_contains {
+            if !checkpoint.message.to_lowercase().contains(&needle.to_lowercase()) {
+                return false;
+            }
+        }
+        if self.from.is_some_and(|from| checkpoint.timestamp < from) {
+            return false;
+        }
+        if self.to.is_some_and(|to| checkpoint.timestamp > to) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Order of `list_checkpoints` results, by timestamp
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
 }
 
 /// Content types returned when reading a checkpoint file for preview
@@ -52,12 +168,19 @@ pub enum CheckpointFileContent {
     Binary { size: u64 },
 }
 
-/// Progress information emitted during checkpoint creation
+/// Progress information emitted during checkpoint creation and restore
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckpointProgress {
     pub phase: String,
+    /// Files processed so far
     pub current: u64,
+    /// Total files in this phase
     pub total: u64,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    /// Id from the operation registry, filled in by the command layer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
 }
 
 /// Directories/files to skip when scanning or cleaning
@@ -65,17 +188,211 @@ fn should_skip_dir(name: &str) -> bool {
     matches!(name, ".flint" | ".git" | "node_modules" | "output")
 }
 
-/// Collect all project files (excluding internal dirs), returning their paths
-fn collect_project_files(project_path: &Path) -> Vec<PathBuf> {
+/// Contents of a zip entry, or `None` if it holds more than
+/// `MAX_IMPORT_FILE_SIZE` bytes
+fn read_entry(entry: &mut impl std::io::Read, zip_path: &Path) -> Result<Option<Vec<u8>>> {
+    use std::io::Read;
+
+    let mut data = Vec::new();
+    entry.by_ref().take(MAX_IMPORT_FILE_SIZE + 1).read_to_end(&mut data).map_err(|e| Error::io_with_path(e, zip_path))?;
+    Ok((data.len() as u64 <= MAX_IMPORT_FILE_SIZE).then_some(data))
+}
+
+/// Hex-encoded SHA-256 of a byte slice
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
+/// OS-locked file guarding mutating checkpoint operations
+const LOCK_FILE_NAME: &str = ".lock";
+
+/// Describes the current lock holder (kept separate because Windows blocks
+/// reads of a locked file)
+const LOCK_INFO_FILE_NAME: &str = ".lock.info";
+
+/// In-process registry of projects with a checkpoint operation running,
+/// keyed by canonical project path
+fn active_locks() -> &'static Mutex<HashMap<PathBuf, CheckpointOperation>> {
+    static ACTIVE: OnceLock<Mutex<HashMap<PathBuf, CheckpointOperation>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Kind of operation holding a project's checkpoint lock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointOperation {
+    Create,
+    Restore,
+    Delete,
+    Prune,
+    Import,
+}
+
+impl std::fmt::Display for CheckpointOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CheckpointOperation::Create => "create",
+            CheckpointOperation::Restore => "restore",
+            CheckpointOperation::Delete => "delete",
+            CheckpointOperation::Prune => "prune",
+            CheckpointOperation::Import => "import",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Who holds a project's checkpoint lock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointLockInfo {
+    pub operation: CheckpointOperation,
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+    /// The holder is gone; the lock will be broken by the next operation
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// Guard for a project's checkpoint lock; released on drop
+pub struct CheckpointLock {
+    key: PathBuf,
+    file: fs::File,
+    info_path: PathBuf,
+}
+
+impl Drop for CheckpointLock {
+    fn drop(&mut self) {
+        // Remove the info before releasing so the lock never looks stale
+        let _ = fs::remove_file(&self.info_path);
+        let _ = self.file.unlock();
+        active_locks().lock().remove(&self.key);
+    }
+}
+
+/// File in the checkpoints directory holding the id the working tree descends from
+const HEAD_FILE_NAME: &str = "HEAD";
+
+/// Name of the checkpoint metadata entry inside an exported zip
+const ARCHIVE_MANIFEST_NAME: &str = ".flint-checkpoint.json";
+
+/// Largest file `import_checkpoint` reads from a zip, whatever its header says
+const MAX_IMPORT_FILE_SIZE: u64 = 2 << 30;
+
+/// Project-level ignore file, one glob pattern per line (gitignore-style subset)
+pub const IGNORE_FILE_NAME: &str = ".flintignore";
+
+/// Exclusions applied to every checkpoint regardless of `.flintignore`
+pub const BUILTIN_IGNORE_PATTERNS: &[&str] = &[
+    "output/",
+    ".flint/",
+    ".git/",
+    "node_modules/",
+    "Thumbs.db",
+];
+
+/// Compiled ignore rules for walking a project.
+///
+/// Supported syntax: blank lines and `#` comments are skipped, a trailing `/`
+/// matches directories only, and a pattern containing `/` (other than a
+/// trailing one) is anchored to the project root; otherwise it matches at any
+/// depth. Negation (`!pattern`) is not supported.
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    patterns: Vec<String>,
+    compiled: Vec<(glob::Pattern, bool)>, // (pattern, directory only)
+}
+
+impl IgnoreRules {
+    pub fn from_patterns<S: AsRef<str>>(patterns: &[S]) -> Self {
+        let mut rules = Self { patterns: Vec::new(), compiled: Vec::new() };
+        for pattern in patterns {
+            rules.add(pattern.as_ref());
+        }
+        rules
+    }
+
+    /// Built-in exclusions plus the project's `.flintignore`, if any
+    pub fn for_project(project_path: &Path) -> Self {
+        let mut rules = Self::from_patterns(BUILTIN_IGNORE_PATTERNS);
+        if let Ok(content) = fs::read_to_string(project_path.join(IGNORE_FILE_NAME)) {
+            for line in content.lines() {
+                rules.add(line);
+            }
+        }
+        rules
+    }
+
+    /// The rules a checkpoint was created with (built-ins for older checkpoints)
+    pub fn for_checkpoint(checkpoint: &Checkpoint) -> Self {
+        if checkpoint.ignore_patterns.is_empty() {
+            Self::from_patterns(BUILTIN_IGNORE_PATTERNS)
+        } else {
+            Self::from_patterns(&checkpoint.ignore_patterns)
+        }
+    }
+
+    fn add(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || self.patterns.iter().any(|p| p == line) {
+            return;
+        }
+
+        let dir_only = line.ends_with('/');
+        let body = line.trim_end_matches('/');
+        let glob_str = if body.contains('/') {
+            body.trim_start_matches('/').to_string()
+        } else {
+            format!("**/{}", body)
+        };
+
+        match glob::Pattern::new(&glob_str) {
+            Ok(pattern) => {
+                self.patterns.push(line.to_string());
+                self.compiled.push((pattern, dir_only));
+            }
+            Err(e) => tracing::warn!("Ignoring invalid {} pattern '{}': {}", IGNORE_FILE_NAME, line, e),
+        }
+    }
+
+    /// The effective pattern list, as recorded in checkpoint metadata
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Whether a project-relative path ("a/b/c.ext") is ignored
+    pub fn is_ignored(&self, relative: &str, is_dir: bool) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: false,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        self.compiled
+            .iter()
+            .any(|(pattern, dir_only)| (is_dir || !dir_only) && pattern.matches_with(relative, options))
+    }
+
+    /// Whether a project-relative file path, or any directory above it, is ignored
+    pub fn is_file_ignored(&self, relative: &str) -> bool {
+        relative.match_indices('/').any(|(i, _)| self.is_ignored(&relative[..i], true))
+            || self.is_ignored(relative, false)
+    }
+}
+
+/// Project-relative path with forward slashes
+fn relative_path(project_path: &Path, path: &Path) -> Option<String> {
+    path.strip_prefix(project_path)
+        .ok()
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+}
+
+/// Collect all project files not excluded by `rules`, returning their paths
+fn collect_project_files(project_path: &Path, rules: &IgnoreRules) -> Vec<PathBuf> {
     WalkDir::new(project_path)
         .into_iter()
-        .filter_entry(|e| {
-            if e.file_type().is_dir() {
-                let name = e.file_name().to_string_lossy();
-                !should_skip_dir(&name)
-            } else {
-                true
-            }
+        .filter_entry(|e| match relative_path(project_path, e.path()) {
+            Some(rel) if !rel.is_empty() => !rules.is_ignored(&rel, e.file_type().is_dir()),
+            _ => true,
         })
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
@@ -83,10 +400,61 @@ fn collect_project_files(project_path: &Path) -> Vec<PathBuf> {
         .collect()
 }
 
+/// Journal of the moves a restore made in the working tree, so a failed
+/// restore can put every file back where it was
+struct RestoreSwap<'a> {
+    displaced_dir: &'a Path,
+    /// (working tree path, where it was moved aside to) for displaced files,
+    /// (working tree path, None) for staged files moved into place
+    steps: Vec<(PathBuf, Option<PathBuf>)>,
+}
+
+impl RestoreSwap<'_> {
+    /// Move a working tree file out of the way instead of deleting it
+    fn displace(&mut self, relative: &str, path: &Path) -> Result<()> {
+        let aside = safe_join(self.displaced_dir, relative)?;
+        if let Some(parent) = aside.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        }
+        fs::rename(path, &aside).map_err(|e| Error::io_with_path(e, path))?;
+        self.steps.push((path.to_path_buf(), Some(aside)));
+        Ok(())
+    }
+
+    /// Move a staged file into the working tree
+    fn place(&mut self, staged: &Path, target: &Path) -> Result<()> {
+        fs::rename(staged, target).map_err(|e| Error::io_with_path(e, target))?;
+        self.steps.push((target.to_path_buf(), None));
+        Ok(())
+    }
+
+    /// Undo every move in reverse order. Returns false if a displaced file
+    /// could not be moved back, in which case it is still in `displaced_dir`.
+    fn roll_back(&self) -> bool {
+        let mut complete = true;
+        for (path, aside) in self.steps.iter().rev() {
+            match aside {
+                None => {
+                    let _ = fs::remove_file(path);
+                }
+                Some(aside) => {
+                    if let Err(e) = fs::rename(aside, path) {
+                        tracing::error!("Failed to put back {} after a failed restore: {}", path.display(), e);
+                        complete = false;
+                    }
+                }
+            }
+        }
+        complete
+    }
+}
+
 pub struct CheckpointManager {
     pub project_path: PathBuf,
     pub checkpoints_dir: PathBuf, // .flint/checkpoints/
     pub object_store: PathBuf,    // .flint/objects/
+    /// Store `.bin` files as canonical ritobin text where lossless (default: true)
+    pub store_bins_as_text: bool,
 }
 
 impl CheckpointManager {
@@ -96,6 +464,7 @@ impl CheckpointManager {
             project_path,
             checkpoints_dir: flint_dir.join("checkpoints"),
             object_store: flint_dir.join("objects"),
+            store_bins_as_text: true,
         }
     }
 
@@ -107,45 +476,235 @@ impl CheckpointManager {
         Ok(())
     }
 
-    /// Create a checkpoint with optional progress callback.
-    /// The callback receives (phase, current, total).
+    /// Take the project's checkpoint lock for a mutating operation.
+    ///
+    /// Fails fast with `Error::CheckpointBusy` if another operation holds it,
+    /// whether in this process or another one. A lock left behind by a process
+    /// that crashed is detected (the OS releases its file lock) and broken.
+    pub fn lock(&self, operation: CheckpointOperation) -> Result<CheckpointLock> {
+        let key = self.project_path.canonicalize().unwrap_or_else(|_| self.project_path.clone());
+        {
+            let mut active = active_locks().lock();
+            if let Some(running) = active.get(&key) {
+                return Err(Error::CheckpointBusy(running.to_string()));
+            }
+            active.insert(key.clone(), operation);
+        }
+
+        match self.acquire_lock_file(operation) {
+            Ok(file) => Ok(CheckpointLock { key, file, info_path: self.lock_info_path() }),
+            Err(e) => {
+                active_locks().lock().remove(&key);
+                Err(e)
+            }
+        }
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.checkpoints_dir.join(LOCK_FILE_NAME)
+    }
+
+    fn lock_info_path(&self) -> PathBuf {
+        self.checkpoints_dir.join(LOCK_INFO_FILE_NAME)
+    }
+
+    fn read_lock_info(&self) -> Option<CheckpointLockInfo> {
+        let content = fs::read_to_string(self.lock_info_path()).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Open the lock file and take an exclusive OS lock on it without blocking
+    fn try_lock_file(&self) -> Result<Option<fs::File>> {
+        fs::create_dir_all(&self.checkpoints_dir)
+            .map_err(|e| Error::io_with_path(e, &self.checkpoints_dir))?;
+        let path = self.lock_path();
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|e| Error::io_with_path(e, &path))?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(Some(file)),
+            Err(fs::TryLockError::WouldBlock) => Ok(None),
+            Err(fs::TryLockError::Error(e)) => Err(Error::io_with_path(e, &path)),
+        }
+    }
+
+    fn acquire_lock_file(&self, operation: CheckpointOperation) -> Result<fs::File> {
+        let Some(file) = self.try_lock_file()? else {
+            let running = self
+                .read_lock_info()
+                .map(|info| info.operation.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            return Err(Error::CheckpointBusy(running));
+        };
+
+        // We hold the OS lock, so leftover info belongs to a process that died mid-operation
+        if let Some(stale) = self.read_lock_info() {
+            tracing::warn!(
+                "Breaking stale checkpoint lock ({} by pid {} since {})",
+                stale.operation, stale.pid, stale.started_at
+            );
+        }
+
+        let info = CheckpointLockInfo {
+            operation,
+            pid: std::process::id(),
+            started_at: Utc::now(),
+            stale: false,
+        };
+        let info_path = self.lock_info_path();
+        let json = serde_json::to_string(&info)
+            .map_err(|e| Error::InvalidInput(format!("Failed to write checkpoint lock: {}", e)))?;
+        fs::write(&info_path, json).map_err(|e| Error::io_with_path(e, &info_path))?;
+
+        Ok(file)
+    }
+
+    /// Current holder of the checkpoint lock, if any. `stale` is set when the
+    /// lock was left behind by a process that no longer holds it.
+    pub fn lock_status(&self) -> Result<Option<CheckpointLockInfo>> {
+        let key = self.project_path.canonicalize().unwrap_or_else(|_| self.project_path.clone());
+        if active_locks().lock().contains_key(&key) {
+            return Ok(self.read_lock_info());
+        }
+        if !self.checkpoints_dir.exists() {
+            return Ok(None);
+        }
+
+        match self.try_lock_file()? {
+            None => Ok(self.read_lock_info()),
+            Some(file) => {
+                let _ = file.unlock();
+                Ok(self.read_lock_info().map(|info| CheckpointLockInfo { stale: true, ..info }))
+            }
+        }
+    }
+
+    /// Clear a stale lock. Returns false if there was nothing to break;
+    /// fails with `Error::CheckpointBusy` if the lock is actually held.
+    pub fn break_stale_lock(&self) -> Result<bool> {
+        match self.lock_status()? {
+            None => Ok(false),
+            Some(info) if !info.stale => Err(Error::CheckpointBusy(info.operation.to_string())),
+            Some(_) => {
+                // Taking and dropping the lock overwrites and then clears the stale info
+                drop(self.lock(CheckpointOperation::Prune)?);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Create a checkpoint with optional progress reporting and cancellation.
+    ///
+    /// The checkpoint only becomes visible once its manifest is written, which
+    /// happens last. If `cancel` is set mid-way, objects written by this run are
+    /// removed again and `Error::Cancelled` is returned.
     pub fn create_checkpoint_with_progress<F>(
         &self,
         message: String,
         tags: Vec<String>,
         progress: Option<F>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<Checkpoint>
+    where
+        F: Fn(&CheckpointProgress),
+    {
+        let _lock = self.lock(CheckpointOperation::Create)?;
+        self.create_checkpoint_locked(message, tags, progress, cancel)
+    }
+
+    fn create_checkpoint_locked<F>(
+        &self,
+        message: String,
+        tags: Vec<String>,
+        progress: Option<F>,
+        cancel: Option<&AtomicBool>,
     ) -> Result<Checkpoint>
     where
-        F: Fn(&str, u64, u64),
+        F: Fn(&CheckpointProgress),
     {
+        let report = |phase: &str, current: u64, total: u64, bytes_done: u64, bytes_total: u64| {
+            if let Some(ref cb) = progress {
+                cb(&CheckpointProgress {
+                    phase: phase.to_string(),
+                    current,
+                    total,
+                    bytes_done,
+                    bytes_total,
+                    operation_id: None,
+                });
+            }
+        };
+
         // Phase 1: Collect all files first (for progress tracking)
-        if let Some(ref cb) = progress {
-            cb("Scanning files...", 0, 0);
-        }
-        let files = collect_project_files(&self.project_path);
+        report("Scanning files...", 0, 0, 0, 0);
+        let rules = IgnoreRules::for_project(&self.project_path);
+        let files: Vec<(PathBuf, u64)> = collect_project_files(&self.project_path, &rules)
+            .into_iter()
+            .map(|path| {
+                let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                (path, size)
+            })
+            .collect();
         let total = files.len() as u64;
+        let bytes_total = files.iter().map(|(_, size)| size).sum();
+        // Upper bound: objects already in the store are deduplicated
+        check_disk_space(&self.object_store, bytes_total)?;
 
         // Phase 2: Hash and store each file
-        let mut manifest = HashMap::new();
-        for (i, full_path) in files.iter().enumerate() {
-            if let Some(ref cb) = progress {
-                cb("Saving checkpoint...", (i + 1) as u64, total);
-            }
+        let mut manifest = BTreeMap::new();
+        let mut written = Vec::new();
+        let mut bytes_done = 0;
+        let result: Result<()> = (|| {
+            for (i, (full_path, _)) in files.iter().enumerate() {
+                if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                    return Err(Error::Cancelled);
+                }
 
-            let relative_path = full_path.strip_prefix(&self.project_path)
-                .map_err(|_| Error::InvalidInput("Failed to relativize path".into()))?
-                .to_string_lossy()
-                .to_string()
-                .replace('\\', "/");
+                let relative_path = relative_path(&self.project_path, full_path)
+                    .ok_or_else(|| Error::InvalidInput("Failed to relativize path".into()))?;
 
-            let (hash, size) = self.hash_and_store_file(full_path)?;
+                let data = fs::read(full_path).map_err(|e| Error::io_with_path(e, full_path))?;
+                let size = data.len() as u64;
 
-            manifest.insert(relative_path.clone(), FileEntry {
-                path: relative_path,
-                hash,
-                size,
-                asset_type: Self::detect_type(full_path),
-            });
+                let asset_type = Self::detect_type(full_path);
+                let text = (self.store_bins_as_text && asset_type == AssetType::Bin)
+                    .then(|| bin_to_canonical_text(&data))
+                    .flatten();
+                let (stored, storage) = match text {
+                    Some(text) => (text.into_bytes(), StorageFormat::BinText),
+                    None => (data, StorageFormat::Raw),
+                };
+
+                let (hash, is_new) = self.store_object(&stored)?;
+                if is_new {
+                    written.push(hash.clone());
+                }
+
+                bytes_done += size;
+                report("Saving checkpoint...", (i + 1) as u64, total, bytes_done, bytes_total);
+
+                manifest.insert(relative_path.clone(), FileEntry {
+                    path: relative_path,
+                    hash,
+                    size,
+                    asset_type,
+                    storage,
+                });
+            }
+            Ok(())
+        })();
+
+        if let Err(e) = result {
+            // Roll back objects nobody else references yet
+            for hash in &written {
+                let _ = fs::remove_file(self.object_path(hash));
+            }
+            return Err(e);
         }
 
         let checkpoint = Checkpoint {
@@ -155,37 +714,109 @@ impl CheckpointManager {
             author: None,
             tags,
             file_manifest: manifest,
+            flint_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            game_version: self.project_game_version(),
+            ignore_patterns: rules.patterns().to_vec(),
+            parent_id: self.head(),
         };
 
         self.save_checkpoint(&checkpoint)?;
+        self.set_head(&checkpoint.id)?;
 
         Ok(checkpoint)
     }
 
     /// Create a checkpoint (no progress callback)
+    #[cfg(test)]
     pub fn create_checkpoint(&self, message: String, tags: Vec<String>) -> Result<Checkpoint> {
-        self.create_checkpoint_with_progress(message, tags, None::<fn(&str, u64, u64)>)
+        self.create_checkpoint_with_progress(message, tags, None::<fn(&CheckpointProgress)>, None)
     }
 
-    fn hash_and_store_file(&self, path: &Path) -> Result<(String, u64)> {
-        let data = fs::read(path).map_err(|e| Error::io_with_path(e, path))?;
-        let size = data.len() as u64;
+    /// Checkpoint the working tree currently descends from: the last one
+    /// created or restored, falling back to the newest checkpoint.
+    pub fn head(&self) -> Option<String> {
+        let head_path = self.checkpoints_dir.join(HEAD_FILE_NAME);
+        if let Ok(id) = fs::read_to_string(&head_path) {
+            let id = id.trim();
+            if self.checkpoints_dir.join(format!("{}.json", id)).exists() {
+                return Some(id.to_string());
+            }
+        }
+        self.list_checkpoints().ok()?.into_iter().next().map(|cp| cp.id)
+    }
 
-        let mut hasher = Sha256::new();
-        hasher.update(&data);
-        let hash = format!("{:x}", hasher.finalize());
+    fn set_head(&self, id: &str) -> Result<()> {
+        let head_path = self.checkpoints_dir.join(HEAD_FILE_NAME);
+        fs::write(&head_path, id).map_err(|e| Error::io_with_path(e, &head_path))
+    }
 
-        let object_rel_path = PathBuf::from(&hash[..2]).join(&hash);
-        let object_path = self.object_store.join(object_rel_path);
+    /// Lineage of all checkpoints for rendering as a tree
+    pub fn checkpoint_graph(&self) -> Result<CheckpointGraph> {
+        let checkpoints = self.list_checkpoints()?;
+        let ids: HashSet<&str> = checkpoints.iter().map(|cp| cp.id.as_str()).collect();
 
-        if !object_path.exists() {
-            if let Some(parent) = object_path.parent() {
-                fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        let mut children: HashMap<&str, Vec<String>> = HashMap::new();
+        for cp in checkpoints.iter().rev() {
+            if let Some(parent) = cp.parent_id.as_deref().filter(|p| ids.contains(p)) {
+                children.entry(parent).or_default().push(cp.id.clone());
             }
-            fs::write(&object_path, data).map_err(|e| Error::io_with_path(e, &object_path))?;
         }
 
-        Ok((hash, size))
+        let nodes = checkpoints
+            .iter()
+            .map(|cp| CheckpointNode {
+                id: cp.id.clone(),
+                // Parents that no longer exist are reported as roots
+                parent_id: cp.parent_id.clone().filter(|p| ids.contains(p.as_str())),
+                children: children.remove(cp.id.as_str()).unwrap_or_default(),
+                message: cp.message.clone(),
+                timestamp: cp.timestamp,
+                tags: cp.tags.clone(),
+            })
+            .collect();
+
+        Ok(CheckpointGraph { nodes, head: self.head() })
+    }
+
+    /// Game version of the League installation the project was created against
+    fn project_game_version(&self) -> Option<String> {
+        let project = crate::core::project::open_project(&self.project_path).ok()?;
+        crate::core::league::detect_game_version(project.league_path?)
+    }
+
+    /// Location of an object in the store (`objects/ab/abcdef...`)
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.object_store.join(&hash[..2]).join(hash)
+    }
+
+    /// Location of an object whose hash comes from outside (the frontend or
+    /// a manifest on disk), which must be a SHA-256 in lowercase hex
+    fn checked_object_path(&self, hash: &str) -> Result<PathBuf> {
+        if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+            return Err(Error::InvalidInput(format!("Invalid object hash: {:?}", hash)));
+        }
+        Ok(self.object_path(hash))
+    }
+
+    /// Write data into the object store (if not already present).
+    /// Returns its hash and whether the object was newly written.
+    fn store_object(&self, data: &[u8]) -> Result<(String, bool)> {
+        let hash = sha256_hex(data);
+        let object_path = self.object_path(&hash);
+
+        if object_path.exists() {
+            return Ok((hash, false));
+        }
+
+        if let Some(parent) = object_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        }
+        // Write to a temp name first so a crash never leaves a truncated object
+        let tmp_path = object_path.with_extension("tmp");
+        fs::write(&tmp_path, data).map_err(|e| Error::io_with_path(e, &tmp_path))?;
+        fs::rename(&tmp_path, &object_path).map_err(|e| Error::io_with_path(e, &object_path))?;
+
+        Ok((hash, true))
     }
 
     fn detect_type(path: &Path) -> AssetType {
@@ -207,21 +838,37 @@ impl CheckpointManager {
 
     fn save_checkpoint(&self, checkpoint: &Checkpoint) -> Result<()> {
         let path = self.checkpoints_dir.join(format!("{}.json", checkpoint.id));
-        let file = fs::File::create(&path).map_err(|e| Error::io_with_path(e, &path))?;
+        // Written under a temp name and renamed so list_checkpoints never sees a partial file
+        let tmp_path = path.with_extension("json.tmp");
+        let file = fs::File::create(&tmp_path).map_err(|e| Error::io_with_path(e, &tmp_path))?;
         serde_json::to_writer_pretty(file, checkpoint)
             .map_err(|e| Error::InvalidInput(format!("Failed to save checkpoint: {}", e)))?;
+        fs::rename(&tmp_path, &path).map_err(|e| Error::io_with_path(e, &path))?;
         Ok(())
     }
 
     pub fn load_checkpoint(&self, id: &str) -> Result<Checkpoint> {
-        let path = self.checkpoints_dir.join(format!("{}.json", id));
+        // IDs come from the frontend
+        let path = safe_join(&self.checkpoints_dir, &format!("{}.json", id))?;
         let file = fs::File::open(&path).map_err(|e| Error::io_with_path(e, &path))?;
         let checkpoint = serde_json::from_reader(file)
             .map_err(|e| Error::InvalidInput(format!("Failed to load checkpoint: {}", e)))?;
         Ok(checkpoint)
     }
 
+    /// Every checkpoint that loads, skipping manifests that don't
     pub fn list_checkpoints(&self) -> Result<Vec<Checkpoint>> {
+        self.load_checkpoints(false)
+    }
+
+    /// Every checkpoint, failing if any manifest can't be loaded. Anything
+    /// that decides which objects are still referenced must use this: a
+    /// skipped manifest would leave its objects looking unreferenced.
+    fn list_checkpoints_strict(&self) -> Result<Vec<Checkpoint>> {
+        self.load_checkpoints(true)
+    }
+
+    fn load_checkpoints(&self, strict: bool) -> Result<Vec<Checkpoint>> {
         let mut checkpoints = Vec::new();
         if !self.checkpoints_dir.exists() {
             return Ok(checkpoints);
@@ -233,67 +880,210 @@ impl CheckpointManager {
             let entry = entry.map_err(|e| Error::InvalidInput(e.to_string()))?;
             let path = entry.path();
             if path.extension().and_then(|e| e.to_str()) == Some("json") {
-                if let Ok(cp) = self.load_checkpoint(path.file_stem().unwrap().to_str().unwrap()) {
-                    checkpoints.push(cp);
+                let id = path.file_stem().unwrap().to_string_lossy();
+                match self.load_checkpoint(&id) {
+                    Ok(cp) => checkpoints.push(cp),
+                    Err(e) if strict => {
+                        return Err(Error::InvalidInput(format!("Checkpoint '{}' could not be loaded: {}", id, e)));
+                    }
+                    Err(e) => tracing::warn!("Skipping checkpoint '{}': {}", id, e),
                 }
             }
         }
 
-        // Sort by timestamp descending
-        checkpoints.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        // Newest first; the ID breaks ties so equal timestamps keep a stable order
+        checkpoints.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+        Ok(checkpoints)
+    }
+
+    /// List checkpoints matching a filter in the given order
+    pub fn list_checkpoints_filtered(
+        &self,
+        filter: &CheckpointFilter,
+        order: CheckpointOrder,
+    ) -> Result<Vec<Checkpoint>> {
+        let mut checkpoints = self.list_checkpoints()?;
+        checkpoints.retain(|cp| filter.matches(cp));
+        if order == CheckpointOrder::OldestFirst {
+            checkpoints.reverse();
+        }
         Ok(checkpoints)
     }
 
+    /// Rename and/or retag an existing checkpoint. `None` leaves a field unchanged.
+    pub fn update_checkpoint(
+        &self,
+        id: &str,
+        message: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<Checkpoint> {
+        let mut checkpoint = self.load_checkpoint(id)?;
+
+        if let Some(message) = message {
+            if message.trim().is_empty() {
+                return Err(Error::InvalidInput("Checkpoint message cannot be empty".into()));
+            }
+            checkpoint.message = message;
+        }
+        if let Some(tags) = tags {
+            checkpoint.tags = tags;
+        }
+
+        self.save_checkpoint(&checkpoint)?;
+        Ok(checkpoint)
+    }
+
     /// Restore a checkpoint with full snapshot semantics:
     /// 1. Auto-backup current state before restoring
-    /// 2. Delete files not in the checkpoint manifest
-    /// 3. Restore all files from the checkpoint
+    /// 2. Stage every file from the object store under `.flint/staging/`
+    /// 3. Move files not in the checkpoint manifest aside
+    /// 4. Move the staged files into place, moving the files they replace aside
+    ///
+    /// The working tree is only modified once every file has been staged, so a
+    /// missing object or a failed copy leaves the project untouched. If a move
+    /// fails, the ones already made are undone. A crash midway leaves the files
+    /// moved aside under `.flint/staging/`; the auto-backup holds them as well.
+    #[cfg(test)]
     pub fn restore_checkpoint(&self, id: &str) -> Result<()> {
+        self.restore_checkpoint_with_progress(id, None::<fn(&CheckpointProgress)>)
+    }
+
+    /// Restore a checkpoint, reporting progress through `progress`
+    pub fn restore_checkpoint_with_progress<F>(&self, id: &str, progress: Option<F>) -> Result<()>
+    where
+        F: Fn(&CheckpointProgress),
+    {
+        let report = |phase: &str, current: u64, total: u64, bytes_done: u64, bytes_total: u64| {
+            if let Some(ref cb) = progress {
+                cb(&CheckpointProgress {
+                    phase: phase.to_string(),
+                    current,
+                    total,
+                    bytes_done,
+                    bytes_total,
+                    operation_id: None,
+                });
+            }
+        };
+
+        let _lock = self.lock(CheckpointOperation::Restore)?;
         let checkpoint = self.load_checkpoint(id)?;
 
         // 1. Auto-backup current state before restoring
+        report("Backing up current state...", 0, 0, 0, 0);
         let backup_msg = format!("Auto-backup before restore to: {}", checkpoint.message);
-        self.create_checkpoint(backup_msg, vec!["auto-backup".to_string()])?;
-
-        // 2. Delete files NOT in the checkpoint manifest
-        let current_files = collect_project_files(&self.project_path);
-        for file_path in &current_files {
-            let relative = file_path.strip_prefix(&self.project_path)
-                .map_err(|_| Error::InvalidInput("Failed to relativize path".into()))?
-                .to_string_lossy()
-                .to_string()
-                .replace('\\', "/");
-
-            // Skip project.json (metadata shouldn't be reverted)
-            if relative == "project.json" {
-                continue;
-            }
+        self.create_checkpoint_locked(
+            backup_msg,
+            vec!["auto-backup".to_string(), "restore".to_string()],
+            None::<fn(&CheckpointProgress)>,
+            None,
+        )?;
+
+        // 2. Stage all files from the object store
+        let staging_dir = self.project_path.join(".flint").join("staging").join(Uuid::new_v4().to_string());
+        let total = checkpoint.file_manifest.len() as u64;
+        let bytes_total = checkpoint.file_manifest.values().map(|e| e.size).sum();
+
+        let staged: Result<()> = (|| {
+            let mut bytes_done = 0;
+            for (i, (rel_path, entry)) in checkpoint.file_manifest.iter().enumerate() {
+                let object_path = self.checked_object_path(&entry.hash)?;
+                if !object_path.exists() {
+                    return Err(Error::InvalidInput(format!(
+                        "Checkpoint data for '{}' is missing (object {})",
+                        rel_path, entry.hash
+                    )));
+                }
+
+                // Verify the blob before it can reach the working tree
+                let data = fs::read(&object_path).map_err(|e| Error::io_with_path(e, &object_path))?;
+                if sha256_hex(&data) != entry.hash {
+                    return Err(Error::InvalidInput(format!(
+                        "Checkpoint data for '{}' is corrupt (object {} failed verification)",
+                        rel_path, entry.hash
+                    )));
+                }
+                let data = materialize_object(entry, data)?;
+
+                // Manifests can come from an imported zip; stop before writing anything
+                let staged_path = safe_join(&staging_dir, rel_path)?;
+                if let Some(parent) = staged_path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+                }
+                fs::write(&staged_path, &data).map_err(|e| Error::io_with_path(e, &staged_path))?;
 
-            if !checkpoint.file_manifest.contains_key(&relative) {
-                // File doesn't exist in checkpoint - remove it
-                let _ = fs::remove_file(file_path);
+                bytes_done += entry.size;
+                report("Staging files...", (i + 1) as u64, total, bytes_done, bytes_total);
             }
+            Ok(())
+        })();
+
+        if let Err(e) = staged {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(e);
         }
 
-        // 3. Restore all files from manifest
-        for (rel_path, entry) in &checkpoint.file_manifest {
-            let target_path = self.project_path.join(rel_path.replace('/', "\\"));
-            let object_path = self.object_store.join(&entry.hash[..2]).join(&entry.hash);
+        // Files the restore replaces or removes are moved here, not deleted,
+        // until every staged file is in place
+        let displaced_dir = staging_dir.with_extension("displaced");
+        let mut swap = RestoreSwap { displaced_dir: &displaced_dir, steps: Vec::new() };
+
+        let swapped: Result<()> = (|| {
+            // 3. Move aside files NOT in the checkpoint manifest, leaving paths
+            //    the checkpoint deliberately ignored alone
+            report("Applying restore...", 0, total, 0, bytes_total);
+            let rules = IgnoreRules::for_checkpoint(&checkpoint);
+            for file_path in collect_project_files(&self.project_path, &rules) {
+                let Some(relative) = relative_path(&self.project_path, &file_path) else {
+                    continue;
+                };
+
+                // Skip project.json (metadata shouldn't be reverted)
+                if relative == "project.json" {
+                    continue;
+                }
 
-            if !object_path.exists() {
-                return Err(Error::InvalidInput(format!("Object not found for hash: {}", entry.hash)));
+                if !checkpoint.file_manifest.contains_key(&relative) {
+                    swap.displace(&relative, &file_path)?;
+                }
             }
 
-            if let Some(parent) = target_path.parent() {
-                fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+            // 4. Swap staged files into place (same volume, so each move is a rename)
+            let mut bytes_done = 0;
+            for (i, (rel_path, entry)) in checkpoint.file_manifest.iter().enumerate() {
+                let staged_path = safe_join(&staging_dir, rel_path)?;
+                let target_path = safe_join(&self.project_path, rel_path)?;
+
+                if let Some(parent) = target_path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+                }
+                if target_path.is_file() {
+                    swap.displace(rel_path, &target_path)?;
+                }
+                swap.place(&staged_path, &target_path)?;
+
+                bytes_done += entry.size;
+                report("Applying restore...", (i + 1) as u64, total, bytes_done, bytes_total);
             }
+            Ok(())
+        })();
 
-            fs::copy(&object_path, &target_path).map_err(|e| Error::io_with_path(e, &target_path))?;
+        if let Err(e) = swapped {
+            if swap.roll_back() {
+                let _ = fs::remove_dir_all(&displaced_dir);
+            }
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(e);
         }
+        let _ = fs::remove_dir_all(&staging_dir);
+        let _ = fs::remove_dir_all(&displaced_dir);
 
-        // 4. Clean up empty directories left after file deletion
+        // 5. Clean up empty directories left after file deletion
         self.cleanup_empty_dirs()?;
 
+        // New checkpoints now descend from the restored one
+        self.set_head(&checkpoint.id)?;
+
         Ok(())
     }
 
@@ -354,18 +1144,493 @@ impl CheckpointManager {
         Ok(diff)
     }
 
-    pub fn delete_checkpoint(&self, id: &str) -> Result<()> {
-        let path = self.checkpoints_dir.join(format!("{}.json", id));
-        if path.exists() {
-            fs::remove_file(&path).map_err(|e| Error::io_with_path(e, &path))?;
+    /// Delete a checkpoint and garbage-collect objects no other checkpoint references.
+    /// Returns the number of bytes freed from the object store.
+    pub fn delete_checkpoint(&self, id: &str) -> Result<u64> {
+        let _lock = self.lock(CheckpointOperation::Delete)?;
+        if !self.delete_checkpoint_locked(id)? {
+            return Ok(0);
         }
-        Ok(())
+        self.collect_garbage_locked()
+    }
+
+    /// Delete the oldest `count` checkpoints and garbage-collect their objects.
+    /// Returns the number of bytes freed.
+    pub fn prune_oldest(&self, count: usize) -> Result<u64> {
+        let _lock = self.lock(CheckpointOperation::Prune)?;
+        // Newest first
+        for checkpoint in self.list_checkpoints_strict()?.iter().rev().take(count) {
+            self.delete_checkpoint_locked(&checkpoint.id)?;
+        }
+        self.collect_garbage_locked()
+    }
+
+    /// Remove a checkpoint's manifest and fix up lineage. Returns false if it didn't exist.
+    fn delete_checkpoint_locked(&self, id: &str) -> Result<bool> {
+        let path = safe_join(&self.checkpoints_dir, &format!("{}.json", id))?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        let deleted = self.load_checkpoint(id)?;
+        let was_head = self.head().as_deref() == Some(id);
+
+        // Splice the checkpoint out of the lineage: children adopt its parent.
+        // Strict, so an unreadable manifest stops the delete before anything
+        // changes rather than failing the garbage collection that follows.
+        for mut child in self.list_checkpoints_strict()? {
+            if child.parent_id.as_deref() == Some(id) {
+                child.parent_id = deleted.parent_id.clone();
+                self.save_checkpoint(&child)?;
+            }
+        }
+
+        fs::remove_file(&path).map_err(|e| Error::io_with_path(e, &path))?;
+
+        if was_head {
+            match deleted.parent_id {
+                Some(ref parent) => self.set_head(parent)?,
+                None => {
+                    let _ = fs::remove_file(self.checkpoints_dir.join(HEAD_FILE_NAME));
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Number of checkpoints referencing each object hash.
+    /// A checkpoint counts once per object even if several paths share it.
+    fn object_ref_counts(checkpoints: &[Checkpoint]) -> HashMap<&str, usize> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for checkpoint in checkpoints {
+            let unique: HashSet<&str> = checkpoint.file_manifest.values().map(|e| e.hash.as_str()).collect();
+            for hash in unique {
+                *counts.entry(hash).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    /// All objects currently in the store as (hash, size on disk)
+    fn stored_objects(&self) -> Vec<(String, u64)> {
+        WalkDir::new(&self.object_store)
+            .min_depth(2)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let size = e.metadata().ok()?.len();
+                Some((e.file_name().to_string_lossy().to_string(), size))
+            })
+            .collect()
+    }
+
+    /// Remove every object that no checkpoint references. Returns bytes freed.
+    /// Refuses to run if any manifest can't be loaded.
+    fn collect_garbage_locked(&self) -> Result<u64> {
+        let checkpoints = self.list_checkpoints_strict()?;
+        let refs = Self::object_ref_counts(&checkpoints);
+
+        let mut freed = 0;
+        for (hash, size) in self.stored_objects() {
+            if refs.contains_key(hash.as_str()) || hash.len() < 2 {
+                continue;
+            }
+            let object_path = self.object_path(&hash);
+            fs::remove_file(&object_path).map_err(|e| Error::io_with_path(e, &object_path))?;
+            freed += size;
+        }
+
+        // Drop empty fan-out directories
+        if let Ok(entries) = fs::read_dir(&self.object_store) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                let _ = fs::remove_dir(entry.path()); // fails if not empty
+            }
+        }
+
+        Ok(freed)
+    }
+
+    /// Re-hash every stored object and check that every manifest entry points
+    /// at an existing object. Returns the problems found and which checkpoints
+    /// (and paths within them) each one affects.
+    pub fn verify(&self) -> Result<CheckpointVerifyReport> {
+        use rayon::prelude::*;
+
+        let checkpoints = self.list_checkpoints()?;
+        let objects = self.stored_objects();
+
+        let corrupt: HashSet<String> = parallel::install(|| {
+            objects
+                .par_iter()
+                .filter(|(hash, _)| {
+                    let _io = parallel::io_permit();
+                    match fs::read(self.object_path(hash)) {
+                        Ok(data) => sha256_hex(&data) != *hash,
+                        Err(_) => true,
+                    }
+                })
+                .map(|(hash, _)| hash.clone())
+                .collect()
+        });
+        let stored: HashSet<&str> = objects.iter().map(|(hash, _)| hash.as_str()).collect();
+
+        let mut issues: HashMap<String, ObjectIssue> = HashMap::new();
+        for cp in &checkpoints {
+            for entry in cp.file_manifest.values() {
+                let kind = if !stored.contains(entry.hash.as_str()) {
+                    ObjectIssueKind::Missing
+                } else if corrupt.contains(&entry.hash) {
+                    ObjectIssueKind::Corrupt
+                } else {
+                    continue;
+                };
+                issues
+                    .entry(entry.hash.clone())
+                    .or_insert_with(|| ObjectIssue { hash: entry.hash.clone(), kind, affected: Vec::new() })
+                    .affected
+                    .push(AffectedFile { checkpoint_id: cp.id.clone(), path: entry.path.clone() });
+            }
+        }
+
+        // Corrupt objects no checkpoint references are still worth reporting
+        for hash in &corrupt {
+            issues.entry(hash.clone()).or_insert_with(|| ObjectIssue {
+                hash: hash.clone(),
+                kind: ObjectIssueKind::Corrupt,
+                affected: Vec::new(),
+            });
+        }
+
+        let mut issues: Vec<ObjectIssue> = issues.into_values().collect();
+        for issue in &mut issues {
+            issue.affected.sort_by(|a, b| (&a.checkpoint_id, &a.path).cmp(&(&b.checkpoint_id, &b.path)));
+        }
+        issues.sort_by(|a, b| a.hash.cmp(&b.hash));
+
+        let affected: HashSet<&str> = issues
+            .iter()
+            .flat_map(|i| i.affected.iter().map(|a| a.checkpoint_id.as_str()))
+            .collect();
+        // Keep list_checkpoints order (newest first)
+        let affected_checkpoints = checkpoints
+            .iter()
+            .filter(|cp| affected.contains(cp.id.as_str()))
+            .map(|cp| cp.id.clone())
+            .collect();
+
+        Ok(CheckpointVerifyReport {
+            objects_checked: objects.len(),
+            checkpoints_checked: checkpoints.len(),
+            issues,
+            affected_checkpoints,
+        })
+    }
+
+    /// Disk usage of the checkpoint store, including what pruning would reclaim
+    pub fn storage_stats(&self) -> Result<CheckpointStorageStats> {
+        let checkpoints = self.list_checkpoints_strict()?;
+        let refs = Self::object_ref_counts(&checkpoints);
+
+        let objects = self.stored_objects();
+        let total_bytes = objects.iter().map(|(_, size)| size).sum();
+        let orphaned_bytes = objects
+            .iter()
+            .filter(|(hash, _)| !refs.contains_key(hash.as_str()))
+            .map(|(_, size)| size)
+            .sum();
+
+        // Stored size can differ from the file size (e.g. BINs kept as text)
+        let blob_sizes: HashMap<&str, u64> = objects.iter().map(|(hash, size)| (hash.as_str(), *size)).collect();
+        let blob_size = |hash: &str| blob_sizes.get(hash).copied().unwrap_or(0);
+
+        // list_checkpoints is newest first
+        let usage = checkpoints
+            .iter()
+            .map(|cp| {
+                let hashes: HashSet<&str> = cp.file_manifest.values().map(|e| e.hash.as_str()).collect();
+                CheckpointUsage {
+                    id: cp.id.clone(),
+                    message: cp.message.clone(),
+                    timestamp: cp.timestamp,
+                    logical_size: cp.file_manifest.values().map(|e| e.size).sum(),
+                    unique_size: hashes
+                        .into_iter()
+                        .filter(|h| refs.get(h) == Some(&1))
+                        .map(blob_size)
+                        .sum(),
+                }
+            })
+            .collect();
+
+        // Simulate pruning oldest-first; entry i = bytes freed by pruning the oldest i + 1
+        let mut remaining = refs.clone();
+        let mut reclaimed = orphaned_bytes;
+        let mut reclaimable_by_pruning_oldest = Vec::with_capacity(checkpoints.len());
+        for cp in checkpoints.iter().rev() {
+            let hashes: HashSet<&str> = cp.file_manifest.values().map(|e| e.hash.as_str()).collect();
+            for hash in hashes {
+                if let Some(count) = remaining.get_mut(hash) {
+                    *count -= 1;
+                    if *count == 0 {
+                        reclaimed += blob_size(hash);
+                    }
+                }
+            }
+            reclaimable_by_pruning_oldest.push(reclaimed);
+        }
+
+        Ok(CheckpointStorageStats {
+            total_bytes,
+            blob_count: objects.len(),
+            orphaned_bytes,
+            checkpoints: usage,
+            reclaimable_by_pruning_oldest,
+        })
+    }
+
+    /// Export a checkpoint as a standalone zip of its files.
+    ///
+    /// Every object is re-hashed while it is written; if any blob in the store is
+    /// missing or does not match its manifest hash, the partial zip is removed and
+    /// the corrupt paths are reported. The checkpoint metadata is embedded as
+    /// `.flint-checkpoint.json` so the importer can verify the contents.
+    ///
+    /// Returns the number of files written.
+    pub fn export_checkpoint(&self, id: &str, output_zip: &Path) -> Result<usize> {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let checkpoint = self.load_checkpoint(id)?;
+
+        let rules = IgnoreRules::for_checkpoint(&checkpoint);
+        let mut entries: Vec<&FileEntry> = checkpoint
+            .file_manifest
+            .values()
+            .filter(|e| !rules.is_file_ignored(&e.path))
+            .collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        if let Some(parent) = output_zip.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        }
+        let file = fs::File::create(output_zip).map_err(|e| Error::io_with_path(e, output_zip))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let zip_err = |e: zip::result::ZipError| Error::InvalidInput(format!("Failed to write checkpoint zip: {}", e));
+
+        // The exported manifest describes the files as written to the zip
+        let mut exported = checkpoint.clone();
+        let mut corrupt = Vec::new();
+        let result: Result<()> = (|| {
+            for entry in &entries {
+                let data = match self.read_object_file(&entry.hash) {
+                    Ok(data) => data,
+                    Err(_) => {
+                        corrupt.push(format!("{} (missing object)", entry.path));
+                        continue;
+                    }
+                };
+                if sha256_hex(&data) != entry.hash {
+                    corrupt.push(format!("{} (hash mismatch)", entry.path));
+                    continue;
+                }
+                let data = match materialize_object(entry, data) {
+                    Ok(data) => data,
+                    Err(_) => {
+                        corrupt.push(format!("{} (BIN conversion failed)", entry.path));
+                        continue;
+                    }
+                };
+                if entry.storage != StorageFormat::Raw {
+                    if let Some(e) = exported.file_manifest.get_mut(&entry.path) {
+                        e.hash = sha256_hex(&data);
+                        e.storage = StorageFormat::Raw;
+                    }
+                }
+
+                zip.start_file(entry.path.as_str(), options).map_err(zip_err)?;
+                zip.write_all(&data).map_err(|e| Error::io_with_path(e, output_zip))?;
+            }
+
+            let manifest = serde_json::to_vec_pretty(&exported)
+                .map_err(|e| Error::InvalidInput(format!("Failed to serialize checkpoint: {}", e)))?;
+            zip.start_file(ARCHIVE_MANIFEST_NAME, options).map_err(zip_err)?;
+            zip.write_all(&manifest).map_err(|e| Error::io_with_path(e, output_zip))?;
+            zip.finish().map_err(zip_err)?;
+            Ok(())
+        })();
+
+        if result.is_err() || !corrupt.is_empty() {
+            let _ = fs::remove_file(output_zip);
+        }
+        result?;
+
+        if !corrupt.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Checkpoint {} has {} corrupt file(s): {}",
+                id,
+                corrupt.len(),
+                corrupt.join(", ")
+            )));
+        }
+
+        Ok(entries.len())
+    }
+
+    /// Import a zip (usually produced by `export_checkpoint`) as a new checkpoint.
+    ///
+    /// The working tree is not touched: files go straight into the object store
+    /// and a new checkpoint is written referencing them. When the zip carries
+    /// `.flint-checkpoint.json`, every file is checked against the recorded hash
+    /// and the import fails (without creating a checkpoint or storing anything)
+    /// on any mismatch, missing or oversized file.
+    pub fn import_checkpoint(&self, zip_path: &Path, message: String) -> Result<Checkpoint> {
+        use std::io::Read;
+
+        self.init()?;
+        let _lock = self.lock(CheckpointOperation::Import)?;
+
+        let file = fs::File::open(zip_path).map_err(|e| Error::io_with_path(e, zip_path))?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| Error::InvalidInput(format!("Failed to open checkpoint zip: {}", e)))?;
+        let zip_err = |e: zip::result::ZipError| Error::InvalidInput(format!("Failed to read checkpoint zip: {}", e));
+
+        // Metadata of the exported checkpoint, if present
+        let source: Option<Checkpoint> = match archive.by_name(ARCHIVE_MANIFEST_NAME) {
+            Ok(mut entry) => {
+                let mut json = String::new();
+                entry.read_to_string(&mut json).map_err(|e| Error::io_with_path(e, zip_path))?;
+                Some(serde_json::from_str(&json).map_err(|e| {
+                    Error::InvalidInput(format!("Invalid checkpoint metadata in zip: {}", e))
+                })?)
+            }
+            Err(_) => None,
+        };
+        let rules = match source {
+            Some(ref cp) => IgnoreRules::for_checkpoint(cp),
+            None => IgnoreRules::from_patterns(BUILTIN_IGNORE_PATTERNS),
+        };
+
+        // Entries are only hashed here and stored once all of them check out,
+        // so a bad entry late in the zip leaves no orphaned objects
+        let mut accepted = Vec::new();
+        let mut seen = HashSet::new();
+        let mut corrupt = Vec::new();
+
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(zip_err)?;
+            if entry.is_dir() || entry.name() == ARCHIVE_MANIFEST_NAME {
+                continue;
+            }
+
+            // Reject absolute paths, `..` components and names Windows can't create
+            let relative = safe_relative(entry.name())?.to_string_lossy().replace('\\', "/");
+            if rules.is_file_ignored(&relative) {
+                continue;
+            }
+            seen.insert(relative.clone());
+
+            // The header's size is the archive's claim; only read what's really there
+            let Some(data) = read_entry(&mut entry, zip_path)? else {
+                corrupt.push(format!("{} (larger than {} bytes)", relative, MAX_IMPORT_FILE_SIZE));
+                continue;
+            };
+
+            let hash = sha256_hex(&data);
+            if let Some(expected) = source.as_ref().and_then(|cp| cp.file_manifest.get(&relative)) {
+                if expected.hash != hash {
+                    corrupt.push(format!("{} (hash mismatch)", relative));
+                    continue;
+                }
+            }
+            accepted.push((i, relative, hash));
+        }
+
+        if let Some(ref cp) = source {
+            for path in cp.file_manifest.keys() {
+                if !rules.is_file_ignored(path) && !seen.contains(path) {
+                    corrupt.push(format!("{} (missing from zip)", path));
+                }
+            }
+        }
+
+        if !corrupt.is_empty() {
+            corrupt.sort();
+            return Err(Error::InvalidInput(format!(
+                "Checkpoint zip has {} corrupt file(s): {}",
+                corrupt.len(),
+                corrupt.join(", ")
+            )));
+        }
+
+        // Objects this import added, removed again if it fails from here on
+        let mut stored = Vec::new();
+        let imported: Result<Checkpoint> = (|| {
+            let mut manifest = BTreeMap::new();
+            for (i, relative, hash) in accepted {
+                let mut entry = archive.by_index(i).map_err(zip_err)?;
+                let data = read_entry(&mut entry, zip_path)?.filter(|data| sha256_hex(data) == hash).ok_or_else(|| {
+                    Error::InvalidInput(format!("Checkpoint zip changed while importing '{}'", relative))
+                })?;
+                if self.store_object(&data)?.1 {
+                    stored.push(hash.clone());
+                }
+                manifest.insert(relative.clone(), FileEntry {
+                    asset_type: Self::detect_type(Path::new(&relative)),
+                    path: relative,
+                    hash,
+                    size: data.len() as u64,
+                    storage: StorageFormat::Raw,
+                });
+            }
+            self.new_imported_checkpoint(source, &rules, manifest, message)
+        })();
+
+        if imported.is_err() {
+            for hash in &stored {
+                let _ = fs::remove_file(self.object_path(hash));
+            }
+        }
+        imported
+    }
+
+    /// Write the checkpoint for an import whose objects are all stored
+    fn new_imported_checkpoint(
+        &self,
+        source: Option<Checkpoint>,
+        rules: &IgnoreRules,
+        manifest: BTreeMap<String, FileEntry>,
+        message: String,
+    ) -> Result<Checkpoint> {
+        let mut tags = source.as_ref().map(|cp| cp.tags.clone()).unwrap_or_default();
+        if !tags.iter().any(|t| t == "imported") {
+            tags.push("imported".to_string());
+        }
+
+        let checkpoint = Checkpoint {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            message,
+            author: source.as_ref().and_then(|cp| cp.author.clone()),
+            tags,
+            file_manifest: manifest,
+            flint_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            game_version: source.as_ref().and_then(|cp| cp.game_version.clone()),
+            ignore_patterns: rules.patterns().to_vec(),
+            // Imported snapshots don't descend from this working tree
+            parent_id: None,
+        };
+
+        self.save_checkpoint(&checkpoint)?;
+        Ok(checkpoint)
     }
 
     /// Read a stored object file by its hash for preview purposes.
     /// Returns raw bytes of the file from the object store.
     pub fn read_object_file(&self, hash: &str) -> Result<Vec<u8>> {
-        let object_path = self.object_store.join(&hash[..2]).join(hash);
+        let object_path = self.checked_object_path(hash)?;
         if !object_path.exists() {
             return Err(Error::InvalidInput(format!("Object not found for hash: {}", hash)));
         }
@@ -418,6 +1683,11 @@ impl CheckpointManager {
                     Err(_) => Ok(CheckpointFileContent::Binary { size: data.len() as u64 }),
                 }
             }
+            // BINs stored as ritobin text can be previewed directly
+            "bin" if data.starts_with(b"#PROP_text") => match String::from_utf8(data) {
+                Ok(text) => Ok(CheckpointFileContent::Text { data: text }),
+                Err(e) => Ok(CheckpointFileContent::Binary { size: e.into_bytes().len() as u64 }),
+            },
             // Everything else
             _ => Ok(CheckpointFileContent::Binary { size: data.len() as u64 }),
         }
@@ -462,9 +1732,649 @@ impl CheckpointManager {
     }
 }
 
+/// A checkpoint in the lineage graph
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckpointNode {
+    pub id: String,
+    pub parent_id: Option<String>,
+    /// Direct descendants, oldest first
+    pub children: Vec<String>,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+    pub tags: Vec<String>,
+}
+
+/// Checkpoint lineage for rendering as a tree
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckpointGraph {
+    /// All checkpoints, newest first
+    pub nodes: Vec<CheckpointNode>,
+    /// Checkpoint the working tree currently descends from
+    pub head: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectIssueKind {
+    /// Stored bytes no longer match the object's hash
+    Corrupt,
+    /// Referenced by a manifest but absent from the store
+    Missing,
+}
+
+/// A checkpoint file that depends on a bad object
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AffectedFile {
+    pub checkpoint_id: String,
+    pub path: String,
+}
+
+/// A corrupt or missing object and everything that references it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ObjectIssue {
+    pub hash: String,
+    pub kind: ObjectIssueKind,
+    pub affected: Vec<AffectedFile>,
+}
+
+/// Result of `verify_checkpoints`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckpointVerifyReport {
+    pub objects_checked: usize,
+    pub checkpoints_checked: usize,
+    pub issues: Vec<ObjectIssue>,
+    /// Ids of checkpoints that cannot be fully restored, newest first
+    pub affected_checkpoints: Vec<String>,
+}
+
+/// Per-checkpoint disk usage
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckpointUsage {
+    pub id: String,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+    /// Sum of all file sizes in the manifest (what a restore would write)
+    pub logical_size: u64,
+    /// Bytes referenced only by this checkpoint (freed if it alone is deleted)
+    pub unique_size: u64,
+}
+
+/// Storage report for a project's checkpoint store
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckpointStorageStats {
+    /// Bytes used by the object store on disk
+    pub total_bytes: u64,
+    /// Number of stored objects
+    pub blob_count: usize,
+    /// Bytes held by objects no checkpoint references
+    pub orphaned_bytes: u64,
+    /// Usage per checkpoint, newest first
+    pub checkpoints: Vec<CheckpointUsage>,
+    /// `reclaimable_by_pruning_oldest[n]` = bytes freed by pruning the oldest `n + 1` checkpoints
+    pub reclaimable_by_pruning_oldest: Vec<u64>,
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct CheckpointDiff {
     pub added: Vec<FileEntry>,
     pub modified: Vec<(FileEntry, FileEntry)>, // (old, new)
     pub deleted: Vec<FileEntry>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    fn checkpoint(message: &str, tags: &[&str], day: u32) -> Checkpoint {
+        Checkpoint {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap(),
+            message: message.to_string(),
+            author: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            file_manifest: BTreeMap::new(),
+            flint_version: None,
+            game_version: None,
+            ignore_patterns: Vec::new(),
+            parent_id: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_tags() {
+        let cp = checkpoint("Before recolor", &["auto", "recolor"], 1);
+
+        let any = CheckpointFilter { tags_any: vec!["export".into(), "Recolor".into()], ..Default::default() };
+        assert!(any.matches(&cp));
+
+        let all = CheckpointFilter { tags_all: vec!["auto".into(), "export".into()], ..Default::default() };
+        assert!(!all.matches(&cp));
+    }
+
+    #[test]
+    fn test_filter_message_and_dates() {
+        let cp = checkpoint("Tweaked the Recall VFX", &[], 10);
+
+        let by_message = CheckpointFilter { message_contains: Some("recall".into()), ..Default::default() };
+        assert!(by_message.matches(&cp));
+
+        let in_range = CheckpointFilter {
+            from: Some(Utc.with_ymd_and_hms(2024, 1, 5, 0, 0, 0).unwrap()),
+            to: Some(Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        assert!(in_range.matches(&cp));
+
+        let too_late = CheckpointFilter {
+            from: Some(Utc.with_ymd_and_hms(2024, 1, 11, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        assert!(!too_late.matches(&cp));
+    }
+
+    #[test]
+    fn test_list_order_is_stable() {
+        let dir = tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+        for (message, day) in [("a", 1), ("b", 2), ("c", 2), ("d", 3)] {
+            manager.save_checkpoint(&checkpoint(message, &[], day)).unwrap();
+        }
+
+        let ids = |order| -> Vec<String> {
+            manager
+                .list_checkpoints_filtered(&CheckpointFilter::default(), order)
+                .unwrap()
+                .into_iter()
+                .map(|cp| cp.id)
+                .collect()
+        };
+        let newest = ids(CheckpointOrder::NewestFirst);
+        assert_eq!(newest, ids(CheckpointOrder::NewestFirst));
+        assert_eq!(manager.load_checkpoint(&newest[0]).unwrap().message, "d");
+
+        let mut oldest = ids(CheckpointOrder::OldestFirst);
+        oldest.reverse();
+        assert_eq!(oldest, newest);
+    }
+
+    #[test]
+    fn test_update_checkpoint() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+        let cp = manager.create_checkpoint("first".into(), vec![]).unwrap();
+        assert_eq!(cp.flint_version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+
+        let updated = manager
+            .update_checkpoint(&cp.id, Some("renamed".into()), Some(vec!["keep".into()]))
+            .unwrap();
+        assert_eq!(updated.message, "renamed");
+
+        let reloaded = manager.load_checkpoint(&cp.id).unwrap();
+        assert_eq!(reloaded.message, "renamed");
+        assert_eq!(reloaded.tags, vec!["keep".to_string()]);
+        assert!(manager.update_checkpoint(&cp.id, Some("  ".into()), None).is_err());
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let src = tempdir().unwrap();
+        fs::create_dir_all(src.path().join("content/base")).unwrap();
+        fs::write(src.path().join("content/base/skin.bin"), [1u8, 2, 3]).unwrap();
+        fs::write(src.path().join("mod.config.json"), "{}").unwrap();
+
+        let source = CheckpointManager::new(src.path().to_path_buf());
+        source.init().unwrap();
+        let cp = source.create_checkpoint("share".into(), vec!["wip".into()]).unwrap();
+
+        let zip_path = src.path().join("output/share.zip");
+        assert_eq!(source.export_checkpoint(&cp.id, &zip_path).unwrap(), 2);
+
+        let dst = tempdir().unwrap();
+        let target = CheckpointManager::new(dst.path().to_path_buf());
+        let imported = target.import_checkpoint(&zip_path, "from collaborator".into()).unwrap();
+
+        assert_ne!(imported.id, cp.id);
+        assert!(imported.tags.contains(&"imported".to_string()));
+        assert_eq!(imported.file_manifest.len(), 2);
+        for (path, entry) in &cp.file_manifest {
+            assert_eq!(imported.file_manifest[path].hash, entry.hash);
+        }
+        // Working tree is untouched
+        assert!(!dst.path().join("content").exists());
+    }
+
+    #[test]
+    fn test_import_applies_checkpoint_ignore_rules() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let src = tempdir().unwrap();
+        fs::write(src.path().join(IGNORE_FILE_NAME), "*.psd\n").unwrap();
+        fs::write(src.path().join("skin.bin"), "bin").unwrap();
+        fs::write(src.path().join("cover.psd"), "psd").unwrap();
+
+        let source = CheckpointManager::new(src.path().to_path_buf());
+        source.init().unwrap();
+        let cp = source.create_checkpoint("share".into(), vec![]).unwrap();
+        let zip_path = src.path().join("output/share.zip");
+        assert_eq!(source.export_checkpoint(&cp.id, &zip_path).unwrap(), 2);
+
+        // A file the checkpoint's .flintignore excludes, added to the zip by hand
+        let mut zip = zip::ZipWriter::new_append(
+            fs::OpenOptions::new().read(true).write(true).open(&zip_path).unwrap(),
+        )
+        .unwrap();
+        zip.start_file("art/cover.psd", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"psd").unwrap();
+        zip.finish().unwrap();
+
+        let dst = tempdir().unwrap();
+        let target = CheckpointManager::new(dst.path().to_path_buf());
+        let imported = target.import_checkpoint(&zip_path, "from collaborator".into()).unwrap();
+
+        assert_eq!(imported.file_manifest.keys().collect::<Vec<_>>(), vec![IGNORE_FILE_NAME, "skin.bin"]);
+        assert!(imported.ignore_patterns.contains(&"*.psd".to_string()));
+        assert_eq!(target.stored_objects().len(), 2);
+    }
+
+    #[test]
+    fn test_hostile_checkpoints_are_rejected() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let sandbox = tempdir().unwrap();
+        let project = sandbox.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        let manager = CheckpointManager::new(project.clone());
+        manager.init().unwrap();
+
+        for hostile in ["../evil.txt", "content/../../evil.txt", "/evil.txt", "C:/evil.txt", "content/CON"] {
+            let zip_path = sandbox.path().join("hostile.zip");
+            let mut zip = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+            zip.start_file("content/base/fine.bin", SimpleFileOptions::default()).unwrap();
+            zip.write_all(b"fine").unwrap();
+            zip.start_file(hostile, SimpleFileOptions::default()).unwrap();
+            zip.write_all(b"evil").unwrap();
+            zip.finish().unwrap();
+
+            let result = manager.import_checkpoint(&zip_path, "hostile".into());
+            assert!(matches!(result, Err(Error::UnsafePath { .. })), "{}: {:?}", hostile, result);
+        }
+        assert!(manager.list_checkpoints().unwrap().is_empty());
+        // The fine entry before the hostile one wasn't stored either
+        assert!(manager.stored_objects().is_empty());
+
+        // An entry whose header claims ~4 GiB is read for what it holds
+        let zip_path = sandbox.path().join("lying.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("content/base/lying.bin", stored).unwrap();
+        zip.write_all(b"tiny").unwrap();
+        zip.finish().unwrap();
+        let mut bytes = fs::read(&zip_path).unwrap();
+        for (signature, offset) in [(b"PK\x03\x04", 22), (b"PK\x01\x02", 24)] {
+            let at = bytes.windows(4).position(|w| w == signature).unwrap() + offset;
+            bytes[at..at + 4].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        }
+        fs::write(&zip_path, bytes).unwrap();
+        let imported = manager.import_checkpoint(&zip_path, "lying".into()).unwrap();
+        assert_eq!(imported.file_manifest["content/base/lying.bin"].size, 4);
+
+        for hash in ["", "ab", "../../../evil", "é", &"A".repeat(64), &format!("{}/..", "a".repeat(61))] {
+            assert!(matches!(manager.read_object_file(hash), Err(Error::InvalidInput(_))), "{:?}", hash);
+        }
+
+        // A manifest edited on disk can't make restore write outside the project
+        fs::write(project.join("a.txt"), "a").unwrap();
+        let mut cp = manager.create_checkpoint("ok".into(), vec![]).unwrap();
+        let entry = cp.file_manifest["a.txt"].clone();
+        cp.file_manifest.insert("../evil.txt".into(), entry);
+        manager.save_checkpoint(&cp).unwrap();
+        assert!(matches!(manager.restore_checkpoint(&cp.id), Err(Error::UnsafePath { .. })));
+        assert!(matches!(manager.load_checkpoint("../../project/a"), Err(Error::UnsafePath { .. })));
+
+        let outside: Vec<_> = fs::read_dir(sandbox.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert!(!outside.iter().any(|name| name == "evil.txt"), "{:?}", outside);
+        assert_eq!(fs::read_to_string(project.join("a.txt")).unwrap(), "a");
+    }
+
+    #[test]
+    fn test_storage_stats_and_gc() {
+        let dir = tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+
+        fs::write(dir.path().join("shared.txt"), "shared").unwrap();
+        fs::write(dir.path().join("a.txt"), "aaaa").unwrap();
+        let first = manager.create_checkpoint("first".into(), vec![]).unwrap();
+
+        fs::write(dir.path().join("a.txt"), "bbbbbbbb").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let second = manager.create_checkpoint("second".into(), vec![]).unwrap();
+
+        let stats = manager.storage_stats().unwrap();
+        assert_eq!(stats.blob_count, 3);
+        assert_eq!(stats.total_bytes, 6 + 4 + 8);
+        assert_eq!(stats.checkpoints[0].id, second.id);
+        assert_eq!(stats.checkpoints[0].logical_size, 6 + 8);
+        assert_eq!(stats.checkpoints[0].unique_size, 8);
+        assert_eq!(stats.checkpoints[1].unique_size, 4);
+        assert_eq!(stats.reclaimable_by_pruning_oldest, vec![4, 18]);
+
+        assert_eq!(manager.delete_checkpoint(&first.id).unwrap(), 4);
+        let stats = manager.storage_stats().unwrap();
+        assert_eq!(stats.blob_count, 2);
+        assert_eq!(stats.orphaned_bytes, 0);
+    }
+
+    #[test]
+    fn test_gc_refuses_unreadable_manifest() {
+        let dir = tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+
+        fs::write(dir.path().join("a.txt"), "aaaa").unwrap();
+        let first = manager.create_checkpoint("first".into(), vec![]).unwrap();
+        fs::write(dir.path().join("a.txt"), "bbbbbbbb").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let second = manager.create_checkpoint("second".into(), vec![]).unwrap();
+
+        // The second manifest is the only reference to the "bbbbbbbb" object
+        let manifest = manager.checkpoints_dir.join(format!("{}.json", second.id));
+        fs::write(&manifest, "{ not json").unwrap();
+
+        assert_eq!(manager.list_checkpoints().unwrap().len(), 1);
+        assert!(manager.storage_stats().is_err());
+        assert!(manager.delete_checkpoint(&first.id).is_err());
+        assert!(manager.prune_oldest(1).is_err());
+
+        // Nothing was deleted
+        assert!(manager.load_checkpoint(&first.id).is_ok());
+        assert_eq!(manager.stored_objects().len(), 2);
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let rules = IgnoreRules::from_patterns(&["output/", "*.fantome", "/content/scratch/", "# comment", ""]);
+        assert_eq!(rules.patterns().len(), 3);
+
+        assert!(rules.is_ignored("output", true));
+        assert!(!rules.is_ignored("output", false));
+        assert!(rules.is_ignored("content/old.fantome", false));
+        assert!(rules.is_ignored("content/scratch", true));
+        assert!(!rules.is_ignored("other/content/scratch", true));
+        assert!(!rules.is_ignored("content/base/skin.bin", false));
+    }
+
+    #[test]
+    fn test_checkpoint_respects_flintignore() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("output")).unwrap();
+        fs::create_dir_all(dir.path().join("content/wip")).unwrap();
+        fs::write(dir.path().join("output/mod.fantome"), "old build").unwrap();
+        fs::write(dir.path().join("content/wip/notes.txt"), "scratch").unwrap();
+        fs::write(dir.path().join("content/skin.bin"), "bin").unwrap();
+        fs::write(dir.path().join(IGNORE_FILE_NAME), "# local only\nwip/\n").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+        let cp = manager.create_checkpoint("snap".into(), vec![]).unwrap();
+
+        let mut paths: Vec<&str> = cp.file_manifest.keys().map(|s| s.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec![IGNORE_FILE_NAME, "content/skin.bin"]);
+        assert!(cp.ignore_patterns.contains(&"wip/".to_string()));
+        assert!(cp.ignore_patterns.contains(&"output/".to_string()));
+    }
+
+    #[test]
+    fn test_cancelled_create_leaves_nothing_behind() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+
+        let cancel = AtomicBool::new(true);
+        let result = manager.create_checkpoint_with_progress(
+            "cancelled".into(),
+            vec![],
+            None::<fn(&CheckpointProgress)>,
+            Some(&cancel),
+        );
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert!(manager.list_checkpoints().unwrap().is_empty());
+        assert_eq!(manager.storage_stats().unwrap().blob_count, 0);
+    }
+
+    #[test]
+    fn test_restore_checkpoint() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("content/base")).unwrap();
+        fs::write(dir.path().join("content/base/skin.bin"), "v1").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+        let cp = manager.create_checkpoint("v1".into(), vec![]).unwrap();
+
+        fs::write(dir.path().join("content/base/skin.bin"), "v2").unwrap();
+        fs::create_dir_all(dir.path().join("content/extra")).unwrap();
+        fs::write(dir.path().join("content/extra/new.txt"), "new").unwrap();
+        fs::create_dir_all(dir.path().join("output")).unwrap();
+        fs::write(dir.path().join("output/mod.fantome"), "build").unwrap();
+
+        let phases = std::cell::RefCell::new(Vec::new());
+        manager
+            .restore_checkpoint_with_progress(&cp.id, Some(|p: &CheckpointProgress| phases.borrow_mut().push(p.phase.clone())))
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(dir.path().join("content/base/skin.bin")).unwrap(), "v1");
+        assert!(!dir.path().join("content/extra").exists());
+        // Ignored paths are left alone
+        assert!(dir.path().join("output/mod.fantome").exists());
+        assert!(!dir.path().join(".flint/staging").read_dir().unwrap().any(|_| true));
+        assert!(phases.borrow().iter().any(|p| p == "Staging files..."));
+    }
+
+    #[test]
+    fn test_failed_restore_puts_working_tree_back() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "v1").unwrap();
+        fs::write(dir.path().join("x"), "file").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+        let cp = manager.create_checkpoint("v1".into(), vec![]).unwrap();
+
+        // `x` is now a directory, so moving the staged file `x` into place fails
+        fs::write(dir.path().join("a.txt"), "v2").unwrap();
+        fs::remove_file(dir.path().join("x")).unwrap();
+        fs::create_dir_all(dir.path().join("x")).unwrap();
+        fs::write(dir.path().join("x/inner.txt"), "inner").unwrap();
+        fs::write(dir.path().join("extra.txt"), "extra").unwrap();
+
+        assert!(manager.restore_checkpoint(&cp.id).is_err());
+
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "v2");
+        assert_eq!(fs::read_to_string(dir.path().join("x/inner.txt")).unwrap(), "inner");
+        assert_eq!(fs::read_to_string(dir.path().join("extra.txt")).unwrap(), "extra");
+        assert!(!dir.path().join(".flint/staging").read_dir().unwrap().any(|_| true));
+    }
+
+    #[test]
+    fn test_lineage_follows_restores() {
+        let dir = tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+
+        let pause = || std::thread::sleep(std::time::Duration::from_millis(10));
+
+        fs::write(dir.path().join("a.txt"), "1").unwrap();
+        let first = manager.create_checkpoint("first".into(), vec![]).unwrap();
+        assert_eq!(first.parent_id, None);
+        pause();
+
+        fs::write(dir.path().join("a.txt"), "2").unwrap();
+        let second = manager.create_checkpoint("second".into(), vec![]).unwrap();
+        assert_eq!(second.parent_id.as_deref(), Some(first.id.as_str()));
+        pause();
+
+        // Restoring records the pre-restore state, then new work branches off `first`
+        manager.restore_checkpoint(&first.id).unwrap();
+        pause();
+        fs::write(dir.path().join("a.txt"), "3").unwrap();
+        let branch = manager.create_checkpoint("branch".into(), vec![]).unwrap();
+        assert_eq!(branch.parent_id.as_deref(), Some(first.id.as_str()));
+
+        let graph = manager.checkpoint_graph().unwrap();
+        assert_eq!(graph.head.as_deref(), Some(branch.id.as_str()));
+        let first_node = graph.nodes.iter().find(|n| n.id == first.id).unwrap();
+        assert_eq!(first_node.children.len(), 2);
+        assert!(first_node.children.contains(&second.id));
+        assert!(first_node.children.contains(&branch.id));
+        let backup = graph.nodes.iter().find(|n| n.tags.contains(&"auto-backup".to_string())).unwrap();
+        assert_eq!(backup.parent_id.as_deref(), Some(second.id.as_str()));
+
+        // Deleting a checkpoint re-parents its children
+        manager.delete_checkpoint(&first.id).unwrap();
+        let graph = manager.checkpoint_graph().unwrap();
+        assert_eq!(graph.nodes.iter().find(|n| n.id == branch.id).unwrap().parent_id, None);
+    }
+
+    #[test]
+    fn test_verify_and_restore_detect_corruption() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        fs::write(dir.path().join("b.txt"), "world").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+        let cp = manager.create_checkpoint("first".into(), vec![]).unwrap();
+
+        let report = manager.verify().unwrap();
+        assert_eq!(report.objects_checked, 2);
+        assert!(report.issues.is_empty());
+
+        let a_hash = cp.file_manifest["a.txt"].hash.clone();
+        let b_hash = cp.file_manifest["b.txt"].hash.clone();
+        fs::write(manager.object_path(&a_hash), "bit rot").unwrap();
+        fs::remove_file(manager.object_path(&b_hash)).unwrap();
+
+        let report = manager.verify().unwrap();
+        assert_eq!(report.issues.len(), 2);
+        assert_eq!(report.affected_checkpoints, vec![cp.id.clone()]);
+        let a_issue = report.issues.iter().find(|i| i.hash == a_hash).unwrap();
+        assert_eq!(a_issue.kind, ObjectIssueKind::Corrupt);
+        assert_eq!(a_issue.affected[0].path, "a.txt");
+        let b_issue = report.issues.iter().find(|i| i.hash == b_hash).unwrap();
+        assert_eq!(b_issue.kind, ObjectIssueKind::Missing);
+
+        // Restore refuses and leaves the working tree untouched
+        fs::write(dir.path().join("a.txt"), "edited").unwrap();
+        fs::write(dir.path().join("b.txt"), "edited").unwrap();
+        let err = manager.restore_checkpoint(&cp.id).unwrap_err().to_string();
+        assert!(err.contains("a.txt") || err.contains("b.txt"));
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "edited");
+    }
+
+    #[test]
+    fn test_bins_stored_as_text_roundtrip() {
+        use crate::core::bin::ltk_bridge::{text_to_tree, write_bin};
+
+        let dir = tempdir().unwrap();
+        // Already canonical: the text the checkpoint should store for it
+        let text = "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\n";
+        let bin = write_bin(&text_to_tree(text).unwrap()).unwrap();
+        fs::write(dir.path().join("skin.bin"), &bin).unwrap();
+        fs::write(dir.path().join("broken.bin"), "not a bin").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+        let cp = manager.create_checkpoint("bins".into(), vec![]).unwrap();
+
+        // Lossy or unparsable BINs always fall back to raw storage
+        assert_eq!(cp.file_manifest["broken.bin"].storage, StorageFormat::Raw);
+        let entry = &cp.file_manifest["skin.bin"];
+        assert_eq!(entry.storage, StorageFormat::BinText);
+        assert_eq!(entry.size, bin.len() as u64);
+
+        // The object holds the ritobin text, not the binary BIN
+        let stored = manager.read_object_file(&entry.hash).unwrap();
+        assert_ne!(stored, bin);
+        assert_eq!(String::from_utf8(stored).unwrap(), text);
+
+        fs::write(dir.path().join("skin.bin"), "edited").unwrap();
+        manager.restore_checkpoint(&cp.id).unwrap();
+        assert_eq!(fs::read(dir.path().join("skin.bin")).unwrap(), bin);
+        assert_eq!(fs::read_to_string(dir.path().join("broken.bin")).unwrap(), "not a bin");
+    }
+
+    #[test]
+    fn test_concurrent_operations_fail_fast() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+
+        let held = manager.lock(CheckpointOperation::Restore).unwrap();
+        // A second manager for the same project sees the lock too
+        let other = CheckpointManager::new(dir.path().join(".").to_path_buf());
+        let err = other.create_checkpoint("blocked".into(), vec![]).unwrap_err();
+        assert!(matches!(err, Error::CheckpointBusy(ref op) if op == "restore"));
+        assert_eq!(manager.lock_status().unwrap().unwrap().operation, CheckpointOperation::Restore);
+
+        drop(held);
+        assert!(manager.lock_status().unwrap().is_none());
+        manager.create_checkpoint("ok".into(), vec![]).unwrap();
+    }
+
+    #[test]
+    fn test_stale_lock_is_broken() {
+        let dir = tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+
+        // Simulate a process that crashed while holding the lock
+        let info = CheckpointLockInfo {
+            operation: CheckpointOperation::Create,
+            pid: 0,
+            started_at: Utc::now(),
+            stale: false,
+        };
+        fs::write(manager.lock_info_path(), serde_json::to_string(&info).unwrap()).unwrap();
+
+        assert!(manager.lock_status().unwrap().unwrap().stale);
+        assert!(manager.break_stale_lock().unwrap());
+        assert!(manager.lock_status().unwrap().is_none());
+
+        fs::write(manager.lock_info_path(), serde_json::to_string(&info).unwrap()).unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        manager.create_checkpoint("after crash".into(), vec![]).unwrap();
+        assert!(!manager.lock_info_path().exists());
+    }
+
+    #[test]
+    fn test_export_reports_corrupt_object() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+        let manager = CheckpointManager::new(dir.path().to_path_buf());
+        manager.init().unwrap();
+        let cp = manager.create_checkpoint("first".into(), vec![]).unwrap();
+
+        let hash = &cp.file_manifest["a.txt"].hash;
+        fs::write(manager.object_store.join(&hash[..2]).join(hash), "tampered").unwrap();
+
+        let zip_path = dir.path().join("out.zip");
+        let err = manager.export_checkpoint(&cp.id, &zip_path).unwrap_err();
+        assert!(err.to_string().contains("a.txt"));
+        assert!(!zip_path.exists());
+    }
+}
@@ -0,0 +1,421 @@
+//! Diagnostic bundles for bug reports
+//!
+//! A bundle is a zip with environment details, settings, log files and,
+//! optionally, a project's metadata and reports. Asset content is never
+//! included.
+
+use crate::core::league::{detect_game_version, detect_league_installation, validate_league_path};
+use crate::core::settings::AppSettings;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Reports written inside a project (repath, validation, export history),
+/// relative to the project root
+pub const PROJECT_REPORTS_DIR: &str = ".flint/reports";
+
+/// Report of the last repath, inside `PROJECT_REPORTS_DIR`
+pub const REPATH_REPORT_FILE: &str = "repath.json";
+
+/// Report of the last asset validation, inside `PROJECT_REPORTS_DIR`
+pub const VALIDATION_REPORT_FILE: &str = "validation.json";
+
+/// Recent exports of the project, inside `PROJECT_REPORTS_DIR`
+pub const EXPORT_HISTORY_FILE: &str = "export-history.json";
+
+/// Exports kept in the history; older ones are dropped
+const EXPORT_HISTORY_LIMIT: usize = 50;
+
+/// Project metadata files included in the bundle
+const PROJECT_METADATA_FILES: &[&str] = &["mod.config.json", "flint.json"];
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    serde_json::to_vec_pretty(value)
+        .map_err(|e| Error::InvalidInput(format!("Failed to serialize diagnostics: {}", e)))
+}
+
+/// Shown in place of a path the user chose not to share
+fn redacted(path: &Option<PathBuf>) -> Value {
+    Value::from(if path.is_some() { "set" } else { "unset" })
+}
+
+/// Write `report` as `name` in the project's reports directory, replacing
+/// the previous one
+pub fn save_project_report<T: Serialize>(project_path: &Path, name: &str, report: &T) -> Result<PathBuf> {
+    let dir = project_path.join(PROJECT_REPORTS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| Error::io_with_path(e, &dir))?;
+    let path = dir.join(name);
+    std::fs::write(&path, to_json(report)?).map_err(|e| Error::io_with_path(e, &path))?;
+    Ok(path)
+}
+
+/// One export in a project's export history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub exported_at: String,
+    /// Package format, e.g. "fantome" or "modpkg"
+    pub format: String,
+    pub output_path: String,
+    pub file_count: usize,
+    pub total_size: u64,
+    pub warnings: Vec<String>,
+    /// Why the export failed; `None` if it succeeded
+    pub error: Option<String>,
+}
+
+/// Append `record` to the project's export history, newest last. An
+/// unreadable history is started over.
+pub fn record_export(project_path: &Path, record: ExportRecord) -> Result<PathBuf> {
+    let path = project_path.join(PROJECT_REPORTS_DIR).join(EXPORT_HISTORY_FILE);
+    let mut history: Vec<ExportRecord> = std::fs::read(&path)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default();
+    history.push(record);
+    let excess = history.len().saturating_sub(EXPORT_HISTORY_LIMIT);
+    history.drain(..excess);
+    save_project_report(project_path, EXPORT_HISTORY_FILE, &history)
+}
+
+/// Environment details captured when the bundle is created
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvironmentReport {
+    pub flint_version: String,
+    pub os: String,
+    pub arch: String,
+    pub generated_at: String,
+    pub hashes: HashReport,
+    pub league: LeagueReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HashReport {
+    pub dir: Option<PathBuf>,
+    pub loaded_count: usize,
+    pub files: Vec<HashFileReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HashFileReport {
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LeagueReport {
+    /// The path from settings, or "set"/"unset" when redacted
+    pub configured: Value,
+    pub configured_valid: Option<bool>,
+    /// Auto-detected installation root, or "set"/"unset" when redacted
+    pub detected: Value,
+    pub detection_error: Option<String>,
+    pub game_version: Option<String>,
+}
+
+/// One file written to the bundle
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleEntry {
+    pub name: String,
+    pub size: u64,
+}
+
+/// What went into a bundle, so users can review it before sharing
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticBundle {
+    pub path: PathBuf,
+    pub entries: Vec<BundleEntry>,
+    /// Things that were expected but left out, with the reason
+    pub notes: Vec<String>,
+}
+
+/// Everything a bundle is built from
+pub struct BundleContents<'a> {
+    pub environment: &'a EnvironmentReport,
+    pub settings: &'a AppSettings,
+    pub log_files: &'a [PathBuf],
+    pub project: Option<&'a Path>,
+    pub include_league_path: bool,
+}
+
+/// Gather hash and League installation details
+pub fn collect_environment(
+    settings: &AppSettings,
+    hash_dir: Option<PathBuf>,
+    loaded_count: usize,
+    include_league_path: bool,
+) -> EnvironmentReport {
+    let files = hash_dir
+        .as_deref()
+        .and_then(|dir| std::fs::read_dir(dir).ok())
+        .map(|entries| {
+            let mut files: Vec<HashFileReport> = entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let metadata = e.metadata().ok().filter(|m| m.is_file())?;
+                    Some(HashFileReport {
+                        name: e.file_name().to_string_lossy().to_string(),
+                        size: metadata.len(),
+                        modified: metadata
+                            .modified()
+                            .ok()
+                            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+                    })
+                })
+                .collect();
+            files.sort_by(|a, b| a.name.cmp(&b.name));
+            files
+        })
+        .unwrap_or_default();
+
+    let configured_valid = settings
+        .league_path
+        .as_ref()
+        .map(|p| validate_league_path(p).is_ok());
+    let (detected, detection_error) = match detect_league_installation() {
+        Ok(installation) => (Some(installation.path), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let game_version = settings
+        .league_path
+        .as_ref()
+        .or(detected.as_ref())
+        .and_then(detect_game_version);
+
+    let show = |path: &Option<PathBuf>| {
+        if include_league_path {
+            serde_json::to_value(path).unwrap_or(Value::Null)
+        } else {
+            redacted(path)
+        }
+    };
+
+    EnvironmentReport {
+        flint_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        hashes: HashReport {
+            dir: hash_dir,
+            loaded_count,
+            files,
+        },
+        league: LeagueReport {
+            configured: show(&settings.league_path),
+            configured_valid,
+            detected: show(&detected),
+            detection_error,
+            game_version,
+        },
+    }
+}
+
+/// Write the bundle zip to `output`
+pub fn write_bundle(output: &Path, contents: &BundleContents) -> Result<DiagnosticBundle> {
+    use zip::write::SimpleFileOptions;
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+    }
+    let file = std::fs::File::create(output).map_err(|e| Error::io_with_path(e, output))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let zip_err = |e: zip::result::ZipError| {
+        Error::InvalidInput(format!("Failed to write diagnostic bundle: {}", e))
+    };
+
+    let mut entries = Vec::new();
+    let mut notes = Vec::new();
+    let mut add = |zip: &mut zip::ZipWriter<std::fs::File>, name: String, data: &[u8]| -> Result<()> {
+        zip.start_file(name.as_str(), options).map_err(zip_err)?;
+        zip.write_all(data).map_err(|e| Error::io_with_path(e, output))?;
+        entries.push(BundleEntry { name, size: data.len() as u64 });
+        Ok(())
+    };
+
+    add(&mut zip, "environment.json".to_string(), &to_json(contents.environment)?)?;
+
+    let mut settings = serde_json::to_value(contents.settings)
+        .map_err(|e| Error::InvalidInput(format!("Failed to serialize settings: {}", e)))?;
+    if !contents.include_league_path {
+        settings["league_path"] = redacted(&contents.settings.league_path);
+    }
+    add(&mut zip, "settings.json".to_string(), &to_json(&settings)?)?;
+
+    if contents.log_files.is_empty() {
+        notes.push("No log files were available".to_string());
+    }
+    for log in contents.log_files {
+        let name = log.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        match std::fs::read(log) {
+            Ok(data) => add(&mut zip, format!("logs/{}", name), &data)?,
+            Err(e) => notes.push(format!("Skipped log {}: {}", name, e)),
+        }
+    }
+
+    if let Some(project) = contents.project {
+        for name in PROJECT_METADATA_FILES {
+            let path = project.join(name);
+            let data = match std::fs::read(&path) {
+                Ok(data) => data,
+                Err(e) => {
+                    notes.push(format!("Skipped project {}: {}", name, e));
+                    continue;
+                }
+            };
+            let data = match serde_json::from_slice::<Value>(&data) {
+                Ok(mut json) if !contents.include_league_path && json.get("league_path").is_some() => {
+                    let path = json["league_path"].as_str().map(PathBuf::from);
+                    json["league_path"] = redacted(&path);
+                    to_json(&json)?
+                }
+                _ => data,
+            };
+            add(&mut zip, format!("project/{}", name), &data)?;
+        }
+
+        let reports_dir = project.join(PROJECT_REPORTS_DIR);
+        let mut reports: Vec<PathBuf> = WalkDir::new(&reports_dir)
+            .max_depth(1)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        reports.sort();
+        if reports.is_empty() {
+            notes.push("The project has no repath, validation or export reports yet".to_string());
+        }
+        for report in reports {
+            let name = report.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            match std::fs::read(&report) {
+                Ok(data) => add(&mut zip, format!("project/reports/{}", name), &data)?,
+                Err(e) => notes.push(format!("Skipped report {}: {}", name, e)),
+            }
+        }
+    }
+
+    zip.finish().map_err(zip_err)?;
+
+    Ok(DiagnosticBundle {
+        path: output.to_path_buf(),
+        entries,
+        notes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::validation::{validate_assets, AssetReference};
+    use std::collections::HashSet;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    fn export_record(error: Option<&str>) -> ExportRecord {
+        ExportRecord {
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            format: "fantome".to_string(),
+            output_path: "MyMod.fantome".to_string(),
+            file_count: 1,
+            total_size: 5,
+            warnings: vec![],
+            error: error.map(str::to_string),
+        }
+    }
+
+    fn environment() -> EnvironmentReport {
+        EnvironmentReport {
+            flint_version: "0.0.0".to_string(),
+            os: "test".to_string(),
+            arch: "test".to_string(),
+            generated_at: String::new(),
+            hashes: HashReport { dir: None, loaded_count: 0, files: vec![] },
+            league: LeagueReport {
+                configured: Value::from("set"),
+                configured_valid: Some(true),
+                detected: Value::from("unset"),
+                detection_error: None,
+                game_version: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_bundle_redacts_paths_and_skips_assets() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("MyMod");
+        std::fs::create_dir_all(project.join("content/base")).unwrap();
+        std::fs::write(project.join("content/base/skin.bin"), b"asset").unwrap();
+        std::fs::write(project.join("mod.config.json"), br#"{"name": "MyMod"}"#).unwrap();
+        std::fs::write(
+            project.join("flint.json"),
+            br#"{"champion": "Ahri", "league_path": "C:/Users/me/Riot Games"}"#,
+        )
+        .unwrap();
+        let references = [AssetReference::new("assets/skin.dds", 1)];
+        let report = validate_assets(&references, &HashSet::new(), "skin.bin");
+        save_project_report(&project, VALIDATION_REPORT_FILE, &report).unwrap();
+        record_export(&project, export_record(None)).unwrap();
+        let log = dir.path().join("flint.log");
+        std::fs::write(&log, b"INFO hello").unwrap();
+
+        let settings = AppSettings {
+            league_path: Some(PathBuf::from("C:/Users/me/Riot Games")),
+            ..Default::default()
+        };
+        let output = dir.path().join("bundle.zip");
+        let bundle = write_bundle(
+            &output,
+            &BundleContents {
+                environment: &environment(),
+                settings: &settings,
+                log_files: &[log],
+                project: Some(&project),
+                include_league_path: false,
+            },
+        )
+        .unwrap();
+
+        let names: Vec<&str> = bundle.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "environment.json",
+                "settings.json",
+                "logs/flint.log",
+                "project/mod.config.json",
+                "project/flint.json",
+                "project/reports/export-history.json",
+                "project/reports/validation.json",
+            ]
+        );
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        for name in ["settings.json", "project/flint.json"] {
+            let mut text = String::new();
+            archive.by_name(name).unwrap().read_to_string(&mut text).unwrap();
+            assert!(!text.contains("Riot Games"), "{} leaks the league path", name);
+            assert!(text.contains("\"set\""));
+        }
+    }
+
+    #[test]
+    fn test_export_history_keeps_the_newest() {
+        let dir = tempdir().unwrap();
+        for i in 0..EXPORT_HISTORY_LIMIT + 2 {
+            let error = format!("failure {}", i);
+            record_export(dir.path(), export_record(Some(&error))).unwrap();
+        }
+
+        let path = dir.path().join(PROJECT_REPORTS_DIR).join(EXPORT_HISTORY_FILE);
+        let history: Vec<ExportRecord> = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+        assert_eq!(history.len(), EXPORT_HISTORY_LIMIT);
+        assert_eq!(history[0].error.as_deref(), Some("failure 2"));
+        assert_eq!(history.last().unwrap().error, Some(format!("failure {}", EXPORT_HISTORY_LIMIT + 1)));
+    }
+}
@@ -0,0 +1,167 @@
+//! Free disk space checks and project disk usage
+//!
+//! Extraction, checkpoints and exports write a lot of data. Running out of
+//! space halfway leaves a half-written project or a corrupt package behind
+//! an IO error that doesn't say why, so these operations estimate what they
+//! will write and check the target volume first.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use sysinfo::Disks;
+use walkdir::WalkDir;
+
+/// Kept free on top of every estimate for filesystem overhead and temp files
+pub const DISK_SPACE_HEADROOM: u64 = 64 * 1024 * 1024;
+
+/// A project's footprint on disk, in bytes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskUsage {
+    /// `content/`
+    pub content: u64,
+    /// `output/`
+    pub output: u64,
+    /// Checkpoint metadata and the object store under `.flint/`
+    pub checkpoints: u64,
+    /// Everything else: project files, BIN caches, reports, quarantine
+    pub other: u64,
+    pub total: u64,
+    /// Free space on the project's volume, if it could be determined
+    pub available: Option<u64>,
+}
+
+/// Fail with `InsufficientDiskSpace` unless the volume holding `path` has
+/// `required_bytes` (plus headroom) free. `path` doesn't have to exist yet.
+/// When free space can't be determined, the check passes.
+pub fn check_disk_space(path: &Path, required_bytes: u64) -> Result<()> {
+    let required = required_bytes.saturating_add(DISK_SPACE_HEADROOM);
+    match available_space(path) {
+        Some(available) if available < required => {
+            tracing::warn!(
+                "Not enough disk space at {}: {} needed, {} available",
+                path.display(),
+                format_bytes(required),
+                format_bytes(available)
+            );
+            Err(Error::InsufficientDiskSpace { required, available, path: path.to_path_buf() })
+        }
+        Some(_) => Ok(()),
+        None => {
+            tracing::debug!("Couldn't determine free space for {}; skipping check", path.display());
+            Ok(())
+        }
+    }
+}
+
+/// Free bytes on the volume holding `path` (or its nearest existing ancestor)
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = path.ancestors().find(|p| p.exists())?.canonicalize().ok()?;
+    let path = strip_verbatim_prefix(path);
+    let disks = Disks::new_with_refreshed_list();
+    space_on_mount(&path, disks.list().iter().map(|d| (d.mount_point(), d.available_space())))
+}
+
+/// Pick the most specific mount point containing `path`
+fn space_on_mount<'a>(path: &Path, mounts: impl Iterator<Item = (&'a Path, u64)>) -> Option<u64> {
+    mounts
+        .filter(|(mount, _)| path.starts_with(mount))
+        .max_by_key(|(mount, _)| mount.components().count())
+        .map(|(_, available)| available)
+}
+
+/// `canonicalize` returns `\\?\C:\...` on Windows, which doesn't match `C:\` mount points
+fn strip_verbatim_prefix(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(|s| s.strip_prefix(r"\\?\")) {
+        Some(rest) if !rest.starts_with("UNC") => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+/// Total size of the files under `dir`; 0 if it doesn't exist
+pub fn directory_size(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Break a project's footprint down by content, output and checkpoints
+pub fn disk_usage(project_path: &Path) -> Result<DiskUsage> {
+    if !project_path.is_dir() {
+        return Err(Error::NotFound(format!("Project not found: {}", project_path.display())));
+    }
+
+    let flint_dir = project_path.join(".flint");
+    let content = directory_size(&project_path.join("content"));
+    let output = directory_size(&project_path.join("output"));
+    let checkpoints = directory_size(&flint_dir.join("checkpoints")) + directory_size(&flint_dir.join("objects"));
+    let total = directory_size(project_path);
+
+    Ok(DiskUsage {
+        content,
+        output,
+        checkpoints,
+        other: total.saturating_sub(content + output + checkpoints),
+        total,
+        available: available_space(project_path),
+    })
+}
+
+/// "1.5 GB"-style sizes for messages
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_space_on_most_specific_mount() {
+        let mounts = [(Path::new("/"), 10), (Path::new("/home"), 20), (Path::new("/home/me/other"), 30)];
+        let space = |path: &str| space_on_mount(Path::new(path), mounts.iter().copied());
+        assert_eq!(space("/home/me/project"), Some(20));
+        assert_eq!(space("/tmp"), Some(10));
+        assert_eq!(space_on_mount(Path::new("/tmp"), std::iter::empty()), None);
+
+        assert_eq!(strip_verbatim_prefix(PathBuf::from(r"\\?\C:\mods")), PathBuf::from(r"C:\mods"));
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.5 MB");
+    }
+
+    #[test]
+    fn test_disk_usage_breakdown() {
+        let dir = tempdir().unwrap();
+        let write = |rel: &str, len: usize| {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, vec![0u8; len]).unwrap();
+        };
+        write("content/base/ahri.wad.client/a.dds", 100);
+        write("output/mod.fantome", 40);
+        write(".flint/objects/ab/cdef", 30);
+        write(".flint/checkpoints/1.json", 5);
+        write(".flint/reports/setup.json", 3);
+        write("mod.config.json", 2);
+
+        let usage = disk_usage(dir.path()).unwrap();
+        assert_eq!((usage.content, usage.output, usage.checkpoints, usage.other), (100, 40, 35, 5));
+        assert_eq!(usage.total, 180);
+        assert!(disk_usage(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Several projects exported as one package
+//!
+//! Skin packs are kept as one project per champion. A bundle stages the
+//! `content/base` of every project into a single tree, WAD folders merged by
+//! name, and packs that. Two projects may ship the same file only if it is
+//! byte for byte the same; anything else is a conflict the author has to
+//! resolve, since one of them would silently win in the package.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// One project's content going into a bundle
+pub struct BundlePart<'a> {
+    /// Shown in conflicts and credits
+    pub name: &'a str,
+    pub content_base: &'a Path,
+}
+
+/// A file that two or more projects ship with different contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleConflict {
+    /// Relative to `content/base`, e.g. "Map11.wad.client/data/maps/shipping/map11/map11.bin"
+    pub file: String,
+    pub projects: Vec<String>,
+}
+
+/// Every file in each part, keyed case-insensitively by its path relative
+/// to `content/base`; the first spelling seen is kept
+fn bundle_files(parts: &[BundlePart]) -> BTreeMap<String, (String, Vec<(usize, PathBuf)>)> {
+    let mut files: BTreeMap<String, (String, Vec<(usize, PathBuf)>)> = BTreeMap::new();
+    for (index, part) in parts.iter().enumerate() {
+        for entry in WalkDir::new(part.content_base)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let Ok(rel_path) = entry.path().strip_prefix(part.content_base) else {
+                continue;
+            };
+            let file = rel_path.to_string_lossy().replace('\\', "/");
+            files
+                .entry(file.to_lowercase())
+                .or_insert_with(|| (file, Vec::new()))
+                .1
+                .push((index, entry.into_path()));
+        }
+    }
+    files
+}
+
+/// Files that more than one part ships with different contents
+pub fn find_bundle_conflicts(parts: &[BundlePart]) -> Result<Vec<BundleConflict>> {
+    let mut conflicts = Vec::new();
+    for (file, sources) in bundle_files(parts).into_values() {
+        let Some(((_, first), rest)) = sources.split_first() else {
+            continue;
+        };
+        let mut differs = false;
+        for (_, other) in rest {
+            if !same_contents(first, other)? {
+                differs = true;
+                break;
+            }
+        }
+        if differs {
+            let mut projects: Vec<String> = sources.iter().map(|(index, _)| parts[*index].name.to_string()).collect();
+            projects.dedup();
+            conflicts.push(BundleConflict { file, projects });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Link (or copy, across devices) every part's files into `staging_base`.
+/// Files several parts share are staged once. Returns the number of files.
+pub fn stage_bundle(parts: &[BundlePart], staging_base: &Path) -> Result<usize> {
+    let files = bundle_files(parts);
+    for (file, sources) in files.values() {
+        let Some((_, source)) = sources.first() else {
+            continue;
+        };
+        let dest = staging_base.join(file);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        }
+        if fs::hard_link(source, &dest).is_err() {
+            fs::copy(source, &dest).map_err(|e| Error::io_with_path(e, source))?;
+        }
+    }
+    tracing::info!("Staged {} files from {} projects in {}", files.len(), parts.len(), staging_base.display());
+    Ok(files.len())
+}
+
+/// The bundle's description: `description` followed by a credit line for
+/// each project, as (display name, authors)
+pub fn bundle_description(description: &str, credits: &[(String, Vec<String>)]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    if !description.trim().is_empty() {
+        lines.push(description.trim().to_string());
+        lines.push(String::new());
+    }
+    lines.push("Includes:".to_string());
+    for (name, authors) in credits {
+        if authors.is_empty() {
+            lines.push(format!("- {}", name));
+        } else {
+            lines.push(format!("- {} by {}", name, authors.join(", ")));
+        }
+    }
+    lines.join("\n")
+}
+
+fn same_contents(a: &Path, b: &Path) -> Result<bool> {
+    let size = |path: &Path| fs::metadata(path).map(|m| m.len()).map_err(|e| Error::io_with_path(e, path));
+    if size(a)? != size(b)? {
+        return Ok(false);
+    }
+    let read = |path: &Path| fs::read(path).map_err(|e| Error::io_with_path(e, path));
+    Ok(read(a)? == read(b)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_bundle_conflicts_and_staging() {
+        let dir = tempdir().unwrap();
+        let write = |project: &str, file: &str, data: &[u8]| {
+            let path = dir.path().join(project).join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, data).unwrap();
+        };
+        write("ahri", "Ahri.wad.client/data/characters/ahri/skins/skin3.bin", b"ahri");
+        write("ahri", "Map11.wad.client/data/maps/shared.bin", b"same");
+        write("zed", "Zed.wad.client/data/characters/zed/skins/skin1.bin", b"zed");
+        write("zed", "map11.wad.client/data/maps/shared.bin", b"same");
+        let (ahri, zed) = (dir.path().join("ahri"), dir.path().join("zed"));
+        let parts = [
+            BundlePart { name: "Ahri", content_base: &ahri },
+            BundlePart { name: "Zed", content_base: &zed },
+        ];
+
+        assert!(find_bundle_conflicts(&parts).unwrap().is_empty());
+        let staging = dir.path().join("staging");
+        assert_eq!(stage_bundle(&parts, &staging).unwrap(), 3);
+        assert!(staging.join("Map11.wad.client/data/maps/shared.bin").is_file());
+
+        write("zed", "map11.wad.client/data/maps/shared.bin", b"different");
+        let conflicts = find_bundle_conflicts(&parts).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].file, "Map11.wad.client/data/maps/shared.bin");
+        assert_eq!(conflicts[0].projects, ["Ahri", "Zed"]);
+
+        let credits = [("Star Guardian Ahri".to_string(), vec!["Dexal".to_string()]), ("Zed".to_string(), vec![])];
+        assert_eq!(
+            bundle_description("A pack", &credits),
+            "A pack\n\nIncludes:\n- Star Guardian Ahri by Dexal\n- Zed"
+        );
+    }
+}
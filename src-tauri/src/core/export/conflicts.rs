@@ -0,0 +1,300 @@
+//! Files an exported mod shares with other installed mods
+//!
+//! Mod managers overlay every installed mod onto the game's WADs, and when
+//! two mods ship the same path in the same WAD the one loaded last wins
+//! without a word. `check_mod_conflicts` reads the path hashes a package
+//! ships, per WAD, and reports the ones other packages ship as well, grouped
+//! by the other mod.
+//!
+//! Packages are `.fantome`/`.zip` files, mod folders with a `WAD` directory
+//! (how cslol-manager keeps installed mods), or Flint projects. A WAD is
+//! either packed (`WAD/Ahri.wad.client` as one file) or raw (a folder of
+//! files); extracted files named by their hash count as that hash.
+
+use crate::core::hash::Hashtable;
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::reader::WadReader;
+use crate::error::{Error, Result};
+use league_toolkit::wad::Wad;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Conflicting paths listed per mod, at most; `count` has the full number
+const CONFLICT_LIST_LIMIT: usize = 200;
+
+/// The files one package ships
+struct ModContents {
+    /// From `META/info.json`, else the file or folder name
+    name: String,
+    /// Lowercased WAD name -> path hash -> path, when the package has it
+    wads: BTreeMap<String, HashMap<u64, Option<String>>>,
+}
+
+/// Paths a package shares with one other mod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModConflictGroup {
+    pub mod_name: String,
+    pub package: String,
+    /// Shared files as "Ahri.wad.client: assets/...", hex where the path is unknown
+    pub files: Vec<String>,
+    /// Shared files in total; `files` is cut off past a limit
+    pub count: usize,
+}
+
+/// Result of `check_mod_conflicts`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModConflictReport {
+    /// Other packages read
+    pub checked: usize,
+    /// Mods sharing files with the package, most shared files first
+    pub conflicts: Vec<ModConflictGroup>,
+    /// Packages that couldn't be read, with why
+    pub unreadable: Vec<String>,
+}
+
+/// Mod packages directly in `dir`: `.fantome` and `.zip` files, and folders
+/// holding a `WAD` directory
+pub fn installed_mods(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut mods: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| Error::io_with_path(e, dir))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            if p.is_dir() {
+                p.join("WAD").is_dir()
+            } else {
+                p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("fantome") || ext.eq_ignore_ascii_case("zip"))
+            }
+        })
+        .collect();
+    mods.sort();
+    Ok(mods)
+}
+
+/// Compare `package` with `others`. A directory in `others` that isn't a
+/// mod itself is searched for mods with `installed_mods`. Mods with the
+/// package's name are earlier installs of it and are skipped. Unknown paths
+/// are resolved with `hashtable` when given.
+pub fn check_mod_conflicts(
+    package: &Path,
+    others: &[PathBuf],
+    hashtable: Option<&Hashtable>,
+) -> Result<ModConflictReport> {
+    let contents = read_mod_contents(package)?;
+    let mut candidates = Vec::new();
+    for other in others {
+        if other.is_dir() && !is_mod_folder(other) {
+            candidates.extend(installed_mods(other)?);
+        } else {
+            candidates.push(other.clone());
+        }
+    }
+    let package_path = fs::canonicalize(package).unwrap_or_else(|_| package.to_path_buf());
+    candidates.retain(|c| fs::canonicalize(c).unwrap_or_else(|_| c.clone()) != package_path);
+
+    let mut report = ModConflictReport::default();
+    for candidate in candidates {
+        let other = match read_mod_contents(&candidate) {
+            Ok(other) => other,
+            Err(e) => {
+                tracing::warn!("Skipping {} in conflict check: {}", candidate.display(), e);
+                report.unreadable.push(format!("{}: {}", candidate.display(), e));
+                continue;
+            }
+        };
+        report.checked += 1;
+        if other.name.eq_ignore_ascii_case(&contents.name) {
+            tracing::debug!("Skipping {}, an install of the same mod", candidate.display());
+            continue;
+        }
+
+        let mut files = Vec::new();
+        for (wad, hashes) in &contents.wads {
+            let Some(other_hashes) = other.wads.get(wad) else {
+                continue;
+            };
+            for (hash, path) in hashes {
+                let Some(other_path) = other_hashes.get(hash) else {
+                    continue;
+                };
+                let path = path
+                    .clone()
+                    .or_else(|| other_path.clone())
+                    .or_else(|| hashtable.map(|h| h.resolve(*hash).into_owned()))
+                    .unwrap_or_else(|| format!("{:016x}", hash));
+                files.push(format!("{}: {}", wad, path));
+            }
+        }
+        if files.is_empty() {
+            continue;
+        }
+        files.sort();
+        let count = files.len();
+        files.truncate(CONFLICT_LIST_LIMIT);
+        report.conflicts.push(ModConflictGroup {
+            mod_name: other.name,
+            package: candidate.to_string_lossy().to_string(),
+            files,
+            count,
+        });
+    }
+    report.conflicts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.mod_name.cmp(&b.mod_name)));
+
+    tracing::info!(
+        "Conflict check of {}: {} of {} mods share files",
+        package.display(),
+        report.conflicts.len(),
+        report.checked
+    );
+    Ok(report)
+}
+
+fn is_mod_folder(dir: &Path) -> bool {
+    dir.join("WAD").is_dir() || dir.join("content").join("base").is_dir()
+}
+
+/// Read what `path` ships: a zip package, a mod folder or a Flint project
+fn read_mod_contents(path: &Path) -> Result<ModContents> {
+    let fallback_name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    if path.is_file() {
+        return read_zip_contents(path, fallback_name);
+    }
+
+    let wad_root = if path.join("WAD").is_dir() { path.join("WAD") } else { path.join("content").join("base") };
+    if !wad_root.is_dir() {
+        return Err(Error::InvalidInput(format!("{} is not a mod package or folder", path.display())));
+    }
+    let name = fs::read(path.join("META").join("info.json"))
+        .ok()
+        .and_then(|data| meta_name(&data))
+        .unwrap_or(fallback_name);
+
+    let mut wads: BTreeMap<String, HashMap<u64, Option<String>>> = BTreeMap::new();
+    for entry in fs::read_dir(&wad_root).map_err(|e| Error::io_with_path(e, &wad_root))?.filter_map(|e| e.ok()) {
+        let wad_path = entry.path();
+        let wad = entry.file_name().to_string_lossy().to_lowercase();
+        if !wad.ends_with(".wad.client") {
+            continue;
+        }
+        let hashes = wads.entry(wad).or_default();
+        if wad_path.is_file() {
+            hashes.extend(WadReader::open(&wad_path)?.chunks().keys().map(|hash| (*hash, None)));
+            continue;
+        }
+        for file in WalkDir::new(&wad_path).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+            if let Ok(rel_path) = file.path().strip_prefix(&wad_path) {
+                let (hash, path) = chunk_hash(&rel_path.to_string_lossy().replace('\\', "/"));
+                hashes.insert(hash, path);
+            }
+        }
+    }
+    Ok(ModContents { name, wads })
+}
+
+fn read_zip_contents(path: &Path, fallback_name: String) -> Result<ModContents> {
+    let file = fs::File::open(path).map_err(|e| Error::io_with_path(e, path))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| Error::InvalidInput(format!("Failed to open {}: {}", path.display(), e)))?;
+    let zip_err = |e: zip::result::ZipError| Error::InvalidInput(format!("Failed to read {}: {}", path.display(), e));
+
+    let mut name = None;
+    let mut wads: BTreeMap<String, HashMap<u64, Option<String>>> = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let entry_name = entry.name().replace('\\', "/");
+        if entry_name.eq_ignore_ascii_case("META/info.json") {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).map_err(|e| Error::io_with_path(e, path))?;
+            name = meta_name(&data);
+            continue;
+        }
+        let Some(rest) = entry_name.strip_prefix("WAD/") else {
+            continue;
+        };
+
+        match rest.split_once('/') {
+            // Raw WAD folder: one entry per file
+            Some((wad, file)) => {
+                let (hash, path) = chunk_hash(file);
+                wads.entry(wad.to_lowercase()).or_default().insert(hash, path);
+            }
+            // Packed WAD: read its table of contents
+            None => {
+                let mut data = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut data).map_err(|e| Error::io_with_path(e, path))?;
+                let wad = Wad::mount(Cursor::new(data)).map_err(|e| {
+                    Error::wad_with_path(format!("Failed to mount {} in package: {}", rest, e), path)
+                })?;
+                let hashes = wads.entry(rest.to_lowercase()).or_default();
+                hashes.extend(wad.chunks().keys().map(|hash| (*hash, None)));
+            }
+        }
+    }
+    Ok(ModContents { name: name.unwrap_or(fallback_name), wads })
+}
+
+/// The mod name in a `META/info.json`
+fn meta_name(data: &[u8]) -> Option<String> {
+    let info: serde_json::Value = serde_json::from_slice(data).ok()?;
+    let name = info.get("Name").or_else(|| info.get("name"))?.as_str()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Path hash of a file in a raw WAD folder, with its path unless the file
+/// is named by its hash ("0123456789abcdef" or "0123456789abcdef.tex")
+fn chunk_hash(file: &str) -> (u64, Option<String>) {
+    let stem = file.split_once('.').map_or(file, |(stem, _)| stem);
+    if !file.contains('/') && stem.len() == 16 {
+        if let Ok(hash) = u64::from_str_radix(stem, 16) {
+            return (hash, None);
+        }
+    }
+    (compute_path_hash(file), Some(file.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_mod(dir: &Path, name: &str, files: &[&str]) -> PathBuf {
+        let root = dir.join(name);
+        fs::create_dir_all(root.join("META")).unwrap();
+        fs::write(root.join("META/info.json"), format!(r#"{{"Name":"{}","Author":"x"}}"#, name)).unwrap();
+        for file in files {
+            let path = root.join("WAD").join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"data").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_conflicts_grouped_by_mod() {
+        let dir = tempdir().unwrap();
+        let package = write_mod(
+            dir.path(),
+            "Arcade Ahri",
+            &["Ahri.wad.client/assets/characters/ahri/skins/base/ahri.tex", "Ahri.wad.client/data/a.bin"],
+        );
+        let installed = dir.path().join("installed");
+        let hash = format!("{:016x}", compute_path_hash("data/a.bin"));
+        write_mod(&installed, "Other Ahri", &[&format!("ahri.wad.client/{}.bin", hash), "Zed.wad.client/data/a.bin"]);
+        write_mod(&installed, "Arcade Ahri", &["Ahri.wad.client/data/a.bin"]);
+        write_mod(&installed, "Unrelated", &["Map11.wad.client/data/a.bin"]);
+
+        let report = check_mod_conflicts(&package, &[installed], None).unwrap();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].mod_name, "Other Ahri");
+        assert_eq!(report.conflicts[0].files, ["ahri.wad.client: data/a.bin"]);
+        assert_eq!(chunk_hash("0123456789abcdef.tex"), (0x0123456789abcdef, None));
+    }
+}
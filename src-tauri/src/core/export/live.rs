@@ -0,0 +1,335 @@
+//! Live sync of a project into a mod folder a running game reads
+//!
+//! Exporting, installing and restarting the game for every tweak makes VFX
+//! tuning slow. A live sync keeps an unpacked copy of the project's WAD
+//! folders in a mod folder laid out the way cslol-style managers install
+//! mods (`META/info.json` and `WAD/{name}.wad.client/...`) and copies over
+//! each file as it changes. Changed BINs first get the repath prefix on
+//! references the initial repath already relocated.
+//!
+//! Whether a change shows without a restart is up to the runtime. Textures
+//! and BINs can be reread while the game runs; meshes, skeletons,
+//! animations and audio are loaded once with the champion, so they are
+//! synced but reported as needing a restart.
+
+use crate::core::paths::safe_join;
+use crate::core::repath::{repath_bin_references, RepathConfig};
+use crate::error::{Error, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+use walkdir::WalkDir;
+
+/// How long the project must stay quiet before a batch of changes is synced;
+/// image editors save in several steps
+pub const LIVE_SYNC_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Marks a folder as a live sync overlay, holding the project's path. Only
+/// such folders (or empty ones) are overwritten.
+const OVERLAY_MARKER: &str = ".flint-live-sync";
+
+/// What kind of file a sync copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveSyncKind {
+    Texture,
+    Bin,
+    Other,
+}
+
+/// One file a sync copied or removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveSyncFile {
+    /// Relative to `content/base`, e.g. "Ahri.wad.client/assets/.../ahri_base_tx_cm.dds"
+    pub path: String,
+    pub kind: LiveSyncKind,
+    /// The game only reads the file when it loads the champion
+    pub restart_required: bool,
+    /// Deleted from the project, so removed from the overlay
+    pub removed: bool,
+    /// References the repath prefix was added to, for BINs
+    pub paths_repathed: usize,
+}
+
+/// One batch of synced changes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LiveSyncBatch {
+    pub files: Vec<LiveSyncFile>,
+    /// Files that couldn't be synced, with why
+    pub failed: Vec<String>,
+}
+
+impl LiveSyncBatch {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.failed.is_empty()
+    }
+
+    pub fn restart_required(&self) -> bool {
+        self.files.iter().any(|f| f.restart_required)
+    }
+}
+
+/// The sync kind of a file, and whether the game needs a restart to see it
+pub fn sync_kind(path: &str) -> (LiveSyncKind, bool) {
+    let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "dds" | "tex" => (LiveSyncKind::Texture, false),
+        "bin" => (LiveSyncKind::Bin, false),
+        _ => (LiveSyncKind::Other, true),
+    }
+}
+
+/// Files in `content/base` that never go into the overlay: BIN text caches
+/// and editors' temporary files
+fn is_ignored(rel_path: &str) -> bool {
+    let lower = rel_path.to_lowercase();
+    [".ritobin", ".part", ".tmp", "~"].iter().any(|suffix| lower.ends_with(suffix))
+}
+
+/// The WAD folder a path relative to `content/base` is in
+fn wad_folder(rel_path: &str) -> Option<&str> {
+    rel_path
+        .split_once('/')
+        .map(|(wad, _)| wad)
+        .filter(|wad| wad.to_lowercase().ends_with(".wad.client"))
+}
+
+/// Fill `overlay_dir` with every file in the project's WAD folders,
+/// replacing what an earlier sync left there. Refuses a folder that has
+/// other contents. Returns the number of files copied.
+pub fn populate_overlay(content_base: &Path, overlay_dir: &Path, name: &str, author: &str) -> Result<usize> {
+    let marker = overlay_dir.join(OVERLAY_MARKER);
+    let is_empty = fs::read_dir(overlay_dir).map_or(true, |mut entries| entries.next().is_none());
+    if !is_empty && !marker.is_file() {
+        return Err(Error::InvalidInput(format!(
+            "{} is not empty and wasn't created by a live sync",
+            overlay_dir.display()
+        )));
+    }
+
+    let wad_root = overlay_dir.join("WAD");
+    if wad_root.exists() {
+        fs::remove_dir_all(&wad_root).map_err(|e| Error::io_with_path(e, &wad_root))?;
+    }
+    let meta_dir = overlay_dir.join("META");
+    fs::create_dir_all(&meta_dir).map_err(|e| Error::io_with_path(e, &meta_dir))?;
+    let info = serde_json::json!({
+        "Name": name,
+        "Author": author,
+        "Version": "0.0.0",
+        "Description": "Live sync from Flint",
+    });
+    let info_path = meta_dir.join("info.json");
+    fs::write(&info_path, serde_json::to_string_pretty(&info).unwrap_or_default())
+        .map_err(|e| Error::io_with_path(e, &info_path))?;
+    fs::write(&marker, content_base.to_string_lossy().as_bytes()).map_err(|e| Error::io_with_path(e, &marker))?;
+
+    let mut copied = 0;
+    for entry in WalkDir::new(content_base).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+        let Ok(rel_path) = entry.path().strip_prefix(content_base) else {
+            continue;
+        };
+        let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+        if is_ignored(&rel_path) || wad_folder(&rel_path).is_none() {
+            continue;
+        }
+        let dest = safe_join(&wad_root, &rel_path)?;
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        }
+        fs::copy(entry.path(), &dest).map_err(|e| Error::io_with_path(e, entry.path()))?;
+        copied += 1;
+    }
+    tracing::info!("Live sync: copied {} files to {}", copied, overlay_dir.display());
+    Ok(copied)
+}
+
+/// Sync the `changed` paths under `content_base` into the overlay. Changed
+/// BINs are repathed with `repath` first, when given. Files outside WAD
+/// folders and files the overlay already has as they are are skipped.
+pub fn sync_changes(
+    content_base: &Path,
+    overlay_dir: &Path,
+    changed: &[PathBuf],
+    repath: Option<&RepathConfig>,
+) -> LiveSyncBatch {
+    let mut batch = LiveSyncBatch::default();
+    for path in changed {
+        let Ok(rel_path) = path.strip_prefix(content_base) else {
+            continue;
+        };
+        let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+        if is_ignored(&rel_path) {
+            continue;
+        }
+        let Some(wad) = wad_folder(&rel_path) else {
+            tracing::debug!("Live sync: {} is not in a WAD folder", rel_path);
+            continue;
+        };
+        let file_base = content_base.join(wad);
+        match sync_file(path, &rel_path, &file_base, overlay_dir, repath) {
+            Ok(Some(file)) => batch.files.push(file),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!("Live sync of {} failed: {}", rel_path, e);
+                batch.failed.push(format!("{}: {}", rel_path, e));
+            }
+        }
+    }
+    if !batch.is_empty() {
+        tracing::info!("Live sync: {} files synced, {} failed", batch.files.len(), batch.failed.len());
+    }
+    batch
+}
+
+fn sync_file(
+    path: &Path,
+    rel_path: &str,
+    file_base: &Path,
+    overlay_dir: &Path,
+    repath: Option<&RepathConfig>,
+) -> Result<Option<LiveSyncFile>> {
+    let dest = safe_join(&overlay_dir.join("WAD"), rel_path)?;
+    let (kind, restart_required) = sync_kind(rel_path);
+    let mut file = LiveSyncFile { path: rel_path.to_string(), kind, restart_required, removed: false, paths_repathed: 0 };
+
+    if !path.exists() {
+        if dest.is_dir() {
+            fs::remove_dir_all(&dest).map_err(|e| Error::io_with_path(e, &dest))?;
+        } else if dest.is_file() {
+            fs::remove_file(&dest).map_err(|e| Error::io_with_path(e, &dest))?;
+        } else {
+            return Ok(None);
+        }
+        file.removed = true;
+        return Ok(Some(file));
+    }
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    if let (LiveSyncKind::Bin, Some(config)) = (kind, repath) {
+        file.paths_repathed = repath_bin_references(path, file_base, config)?;
+    }
+    let data = fs::read(path).map_err(|e| Error::io_with_path(e, path))?;
+    // Also skips the second event of a BIN the repath just rewrote
+    if fs::read(&dest).is_ok_and(|old| old == data) {
+        return Ok(None);
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+    }
+    fs::write(&dest, data).map_err(|e| Error::io_with_path(e, &dest))?;
+    Ok(Some(file))
+}
+
+/// Watch `content_base` and call `on_change` with each debounced batch of
+/// changed paths. Dropping the watcher stops it.
+pub fn watch_project(
+    content_base: &Path,
+    mut on_change: impl FnMut(Vec<PathBuf>) + Send + 'static,
+) -> Result<RecommendedWatcher> {
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("Live sync watch error: {}", e);
+                return;
+            }
+        };
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+            return;
+        }
+        for path in event.paths {
+            let _ = tx.send(path);
+        }
+    })
+    .map_err(|e| Error::InvalidInput(format!("Failed to watch {}: {}", content_base.display(), e)))?;
+    watcher
+        .watch(content_base, RecursiveMode::Recursive)
+        .map_err(|e| Error::InvalidInput(format!("Failed to watch {}: {}", content_base.display(), e)))?;
+
+    std::thread::Builder::new()
+        .name("flint-live-sync".to_string())
+        .spawn(move || crate::core::hash::watcher::debounce(rx, LIVE_SYNC_DEBOUNCE, move |paths, _| on_change(paths)))
+        .map_err(|e| Error::io_with_path(e, content_base))?;
+    Ok(watcher)
+}
+
+/// A running live sync
+struct LiveSync {
+    overlay_dir: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+/// Live syncs running, by project path
+#[derive(Default)]
+pub struct LiveSyncRegistry {
+    active: Mutex<HashMap<PathBuf, LiveSync>>,
+}
+
+impl LiveSyncRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `watcher` running for the project, stopping its previous sync
+    pub fn insert(&self, project_path: PathBuf, overlay_dir: PathBuf, watcher: RecommendedWatcher) {
+        self.active.lock().insert(project_path, LiveSync { overlay_dir, _watcher: watcher });
+    }
+
+    /// Stop the project's sync, returning its overlay folder
+    pub fn stop(&self, project_path: &Path) -> Option<PathBuf> {
+        self.active.lock().remove(project_path).map(|sync| sync.overlay_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_overlay_sync() {
+        let dir = tempdir().unwrap();
+        let content_base = dir.path().join("content/base");
+        let write = |rel_path: &str, data: &[u8]| {
+            let path = content_base.join(rel_path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, data).unwrap();
+            path
+        };
+        let texture = write("Ahri.wad.client/assets/ahri/tx.dds", b"old");
+        let mesh = write("Ahri.wad.client/assets/ahri/ahri.skn", b"mesh");
+        write("Ahri.wad.client/data/skin3.bin.ritobin", b"cache");
+        let loose = write("readme.txt", b"not packed");
+
+        let overlay = dir.path().join("overlay");
+        assert_eq!(populate_overlay(&content_base, &overlay, "Ahri Live", "Dexal").unwrap(), 2);
+        assert!(overlay.join("META/info.json").is_file());
+
+        fs::write(&texture, b"new").unwrap();
+        fs::remove_file(&mesh).unwrap();
+        let batch = sync_changes(&content_base, &overlay, &[texture.clone(), mesh, loose], None);
+        assert_eq!(batch.files.len(), 2);
+        assert_eq!(batch.files[0].kind, LiveSyncKind::Texture);
+        assert!(!batch.files[0].restart_required);
+        assert!(batch.files[1].removed && batch.files[1].restart_required);
+        assert_eq!(fs::read(overlay.join("WAD/Ahri.wad.client/assets/ahri/tx.dds")).unwrap(), b"new");
+        assert!(!overlay.join("WAD/Ahri.wad.client/assets/ahri/ahri.skn").exists());
+
+        // Nothing changed since, so nothing is synced again
+        assert!(sync_changes(&content_base, &overlay, &[texture], None).is_empty());
+
+        let other = dir.path().join("other");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("keep.txt"), b"x").unwrap();
+        assert!(populate_overlay(&content_base, &other, "Ahri Live", "Dexal").is_err());
+    }
+}
@@ -3,6 +3,16 @@
 //! This module uses ltk_fantome and ltk_modpkg for league-mod compatible exports:
 //! - `.fantome` format (legacy, widely supported) via ltk_fantome
 //! - `.modpkg` format (modern format) via ltk_modpkg
+//!
+//! `bundle` merges several projects into one package; `conflicts` compares
+//! a package with other installed mods; `live` keeps an unpacked copy of a
+//! project in sync for a running game; `packed` writes fantomes whose WAD
+//! folders are built into archives.
+
+pub mod bundle;
+pub mod conflicts;
+pub mod live;
+pub mod packed;
 
 // Re-export from ltk crates for convenience
 #[allow(unused_imports)]
@@ -10,6 +20,19 @@ pub use ltk_fantome::{pack_to_fantome, FantomeInfo, create_file_name, FantomeExt
 #[allow(unused_imports)]
 pub use ltk_modpkg::builder::ModpkgBuilder;
 
+pub use bundle::{bundle_description, find_bundle_conflicts, stage_bundle, BundleConflict, BundlePart};
+pub use conflicts::{check_mod_conflicts, ModConflictReport};
+pub use live::{populate_overlay, sync_changes, watch_project, LiveSyncBatch, LiveSyncRegistry};
+pub use packed::pack_fantome_with_wads;
+
+use crate::core::file::is_stringtable;
+use crate::core::repath::maps::{is_map_wad, split_wad_folder};
+use crate::core::wad::{WadKind, WadName};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
 /// Generate a default filename for the fantome package
 /// (Convenience wrapper around ltk_fantome)
 pub fn generate_fantome_filename(name: &str, version: &str) -> String {
@@ -24,3 +47,307 @@ pub fn generate_fantome_filename(name: &str, version: &str) -> String {
 
     format!("{}_{}.fantome", slug, version)
 }
+
+/// A file the export packs, and the WAD it goes into
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifestEntry {
+    /// Relative to `content/base`, with `/` separators
+    pub file: String,
+    /// WAD folder it is packed into; `None` for files outside one
+    pub wad: Option<String>,
+    /// Why the file is packed where it is, when that isn't the champion's WAD
+    pub note: Option<String>,
+}
+
+/// Every file under `content_base`, sorted, with the WAD it is packed into.
+/// Files bound for a map WAD say why they are there.
+pub fn export_manifest(content_base: &Path) -> Vec<ExportManifestEntry> {
+    let mut entries: Vec<ExportManifestEntry> = walkdir::WalkDir::new(content_base)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.path().strip_prefix(content_base).ok().map(|p| p.to_string_lossy().replace('\\', "/")))
+        .map(|file| {
+            let wad = split_wad_folder(&file).0.map(str::to_string);
+            let note = wad.as_deref().filter(|wad| is_map_wad(wad)).map(|wad| {
+                format!("Map data for the champion's effects; the game only loads it from {} on that map", wad)
+            });
+            ExportManifestEntry { file, wad, note }
+        })
+        .collect();
+    entries.sort_by(|a, b| a.file.cmp(&b.file));
+    entries
+}
+
+/// Check the WAD folders in `content_base` against the installation's client
+/// WADs (`installed`). Returns a warning for every folder that targets a
+/// mobile or locale WAD, or a name the game doesn't have; such a WAD is
+/// packed but the game never loads it. `Global.{locale}.wad.client`, the
+/// locale text WAD, is accepted, and string tables anywhere else are
+/// reported. Name checks are skipped when `installed` is empty.
+pub fn check_wad_targets(content_base: &Path, installed: &[PathBuf]) -> Vec<String> {
+    let installed: HashSet<String> = installed
+        .iter()
+        .filter_map(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .collect();
+
+    let Ok(entries) = std::fs::read_dir(content_base) else {
+        return Vec::new();
+    };
+    let mut targets: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    targets.sort();
+
+    let mut warnings: Vec<String> = targets
+        .iter()
+        .filter_map(|name| match WadName::parse(name)?.kind {
+            // The locale text WAD, where string tables go
+            WadKind::LocaleAudio { .. } if is_locale_text_wad(name) => None,
+            WadKind::Client if installed.is_empty() || installed.contains(&name.to_lowercase()) => None,
+            WadKind::Client => Some(format!("{} doesn't match any client WAD in the installation", name)),
+            WadKind::Mobile => Some(format!("{} targets a mobile WAD; the PC client won't load it", name)),
+            WadKind::LocaleAudio { locale } => Some(format!(
+                "{} targets the {} voice-over WAD instead of the client WAD",
+                name, locale
+            )),
+            WadKind::Other => Some(format!("{} is not a .wad.client folder", name)),
+        })
+        .collect();
+
+    for name in targets.iter().filter(|name| !is_locale_text_wad(name)) {
+        warnings.extend(misplaced_stringtables(content_base, name).into_iter().map(|file| {
+            format!("{} is a string table outside Global.{{locale}}.wad.client; the game won't load it", file)
+        }));
+    }
+    warnings
+}
+
+/// Champions whose content lives in a WAD named differently from the
+/// champion, by normalized name
+const CHAMPION_WAD_ALIASES: &[(&str, &str)] = &[
+    ("wukong", "monkeyking"),
+    ("gnarbig", "gnar"),
+    ("nunuwillump", "nunu"),
+    ("renataglasc", "renata"),
+];
+
+/// WAD candidates listed when nothing matches
+const MAX_WAD_CANDIDATES: usize = 5;
+
+/// Resolve the installed WAD the champion's content is packed as, e.g.
+/// "Wukong" -> "MonkeyKing.wad.client", with the installation's casing.
+/// `wad_name` overrides the champion; it must still be an installed client
+/// WAD. Only WADs in a `Champions` folder of `installed` are considered.
+/// Without any, the name is derived from the champion or taken from
+/// `wad_name` unchecked. Fails with the closest WAD names when nothing matches.
+pub fn resolve_champion_wad(champion: &str, wad_name: Option<&str>, installed: &[PathBuf]) -> Result<String> {
+    let wanted = match wad_name {
+        Some(name) => match WadName::parse(name) {
+            Some(wad) if wad.kind == WadKind::Client => wad.base.to_lowercase(),
+            _ => return Err(Error::InvalidInput(format!("{} is not a .wad.client name", name))),
+        },
+        None => {
+            let normalized: String = champion
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase();
+            CHAMPION_WAD_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == normalized)
+                .map_or(normalized, |(_, wad)| wad.to_string())
+        }
+    };
+    if wanted.is_empty() {
+        return Err(Error::InvalidInput("No champion to resolve the WAD name for".to_string()));
+    }
+
+    let champion_wads: Vec<String> = installed
+        .iter()
+        .filter(|p| {
+            p.parent()
+                .and_then(|dir| dir.file_name())
+                .is_some_and(|dir| dir.to_string_lossy().eq_ignore_ascii_case("champions"))
+        })
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .filter(|n| WadName::parse(n).is_some_and(|wad| wad.kind == WadKind::Client))
+        .collect();
+    if champion_wads.is_empty() {
+        return Ok(wad_name.map_or_else(|| format!("{}.wad.client", wanted), str::to_string));
+    }
+
+    let base = |name: &str| WadName::parse(name).map(|wad| wad.base.to_lowercase()).unwrap_or_default();
+    if let Some(found) = champion_wads.iter().find(|name| base(name) == wanted) {
+        return Ok(found.clone());
+    }
+
+    let mut candidates: Vec<(usize, &String)> =
+        champion_wads.iter().map(|name| (edit_distance(&wanted, &base(name)), name)).collect();
+    candidates.sort();
+    let candidates: Vec<&str> = candidates.iter().take(MAX_WAD_CANDIDATES).map(|(_, name)| name.as_str()).collect();
+    Err(Error::InvalidInput(format!(
+        "No champion WAD in the installation matches '{}'; closest: {}. Pick one as the WAD name to export to.",
+        wad_name.unwrap_or(champion),
+        candidates.join(", ")
+    )))
+}
+
+/// Rename the project's champion WAD folder in `content_base`, the one
+/// extraction named after `champion`, to `wad`. Folders that only differ in
+/// case are left alone. Returns a note when the folder was renamed.
+pub fn rename_champion_wad(content_base: &Path, champion: &str, wad: &str) -> Result<Option<String>> {
+    let champion_base: String = champion.chars().filter(|c| !matches!(c, '\'' | ' ' | '.')).collect();
+    let Ok(entries) = std::fs::read_dir(content_base) else {
+        return Ok(None);
+    };
+    let current = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .find(|name| {
+            WadName::parse(name)
+                .is_some_and(|parsed| parsed.kind == WadKind::Client && parsed.base.eq_ignore_ascii_case(&champion_base))
+        });
+    let Some(current) = current else {
+        return Ok(None);
+    };
+    if current.eq_ignore_ascii_case(wad) {
+        return Ok(None);
+    }
+
+    let dest = content_base.join(wad);
+    if dest.exists() {
+        return Err(Error::InvalidInput(format!(
+            "Both {} and {} exist in the project; merge them before exporting",
+            current, wad
+        )));
+    }
+    let source = content_base.join(&current);
+    std::fs::rename(&source, &dest).map_err(|e| Error::io_with_path(e, &source))?;
+    tracing::info!("Renamed WAD folder {} -> {}", current, wad);
+    Ok(Some(format!("Renamed {} to {}, the WAD the game loads {} from", current, wad, champion)))
+}
+
+/// Levenshtein distance, for suggesting WAD names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// `Global.{locale}.wad.client`, which holds the localized string tables
+fn is_locale_text_wad(folder: &str) -> bool {
+    WadName::parse(folder).is_some_and(|wad| {
+        wad.base.eq_ignore_ascii_case("global") && matches!(wad.kind, WadKind::LocaleAudio { .. })
+    })
+}
+
+/// String tables in `content_base/{folder}`, relative to `content_base`
+fn misplaced_stringtables(content_base: &Path, folder: &str) -> Vec<String> {
+    walkdir::WalkDir::new(content_base.join(folder))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_stringtable(e.path()))
+        .filter_map(|e| e.path().strip_prefix(content_base).ok().map(|p| p.to_string_lossy().replace('\\', "/")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_wad_targets() {
+        let dir = tempdir().unwrap();
+        for name in ["ahri.wad.client", "Ahri.en_US.wad.client", "zed.wad.client", "data"] {
+            std::fs::create_dir_all(dir.path().join(name)).unwrap();
+        }
+        let installed = [PathBuf::from("Game/DATA/FINAL/Champions/Ahri.wad.client")];
+
+        let warnings = check_wad_targets(dir.path(), &installed);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("Ahri.en_US.wad.client targets the en_US"));
+        assert!(warnings[1].starts_with("zed.wad.client doesn't match"));
+
+        assert_eq!(check_wad_targets(dir.path(), &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_champion_wad() {
+        let installed: Vec<PathBuf> = [
+            "Champions/Ahri.wad.client",
+            "Champions/MonkeyKing.wad.client",
+            "Champions/Gnar.wad.client",
+            "Maps/Map11.wad.client",
+        ]
+        .iter()
+        .map(|p| PathBuf::from("Game/DATA/FINAL").join(p))
+        .collect();
+
+        assert_eq!(resolve_champion_wad("ahri", None, &installed).unwrap(), "Ahri.wad.client");
+        assert_eq!(resolve_champion_wad("Wukong", None, &installed).unwrap(), "MonkeyKing.wad.client");
+        assert_eq!(resolve_champion_wad("GnarBig", None, &installed).unwrap(), "Gnar.wad.client");
+        assert_eq!(resolve_champion_wad("Lux", Some("ahri.wad.client"), &installed).unwrap(), "Ahri.wad.client");
+        assert!(resolve_champion_wad("Ahri", Some("Map11.wad.client"), &installed).is_err());
+        assert!(resolve_champion_wad("Ahri", Some("ahri.bin"), &installed).is_err());
+
+        let Err(Error::InvalidInput(message)) = resolve_champion_wad("Ahrii", None, &installed) else {
+            panic!("expected no match");
+        };
+        assert!(message.contains("closest: Ahri.wad.client"));
+
+        assert_eq!(resolve_champion_wad("Kai'Sa", None, &[]).unwrap(), "kaisa.wad.client");
+    }
+
+    #[test]
+    fn test_export_manifest_marks_map_files() {
+        let dir = tempdir().unwrap();
+        for file in [
+            "ahri.wad.client/data/characters/ahri/skins/skin0.bin",
+            "Map11.wad.client/data/maps/shipping/map11/map11.bin",
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"PROP").unwrap();
+        }
+
+        let manifest = export_manifest(dir.path());
+        assert_eq!(manifest.len(), 2);
+        assert_eq!(manifest[0].file, "Map11.wad.client/data/maps/shipping/map11/map11.bin");
+        assert_eq!(manifest[0].wad.as_deref(), Some("Map11.wad.client"));
+        assert!(manifest[0].note.is_some());
+        assert_eq!(manifest[1].wad.as_deref(), Some("ahri.wad.client"));
+        assert!(manifest[1].note.is_none());
+    }
+
+    #[test]
+    fn test_stringtables_belong_in_locale_text_wad() {
+        let dir = tempdir().unwrap();
+        for file in [
+            "Global.en_US.wad.client/data/menu/en_us/lol.stringtable",
+            "ahri.wad.client/data/menu/en_us/lol.stringtable",
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"RST").unwrap();
+        }
+
+        let warnings = check_wad_targets(dir.path(), &[]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("ahri.wad.client/data/menu/en_us/lol.stringtable is a string table"));
+    }
+}
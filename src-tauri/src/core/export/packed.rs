@@ -0,0 +1,147 @@
+//! Fantome packages with built WAD archives
+//!
+//! `pack_to_fantome` stores a project's WAD folders as loose files under
+//! `WAD/<name>.wad.client/`, which newer mod managers reject. With
+//! `pack_as_wad` each WAD folder in `content/base` is built into a real
+//! archive instead and the package is written here: `META/info.json` and
+//! one `WAD/<name>.wad.client` file per folder.
+
+use crate::core::wad::writer::write_wad;
+use crate::error::{Error, Result};
+use ltk_mod_project::{ModProject, ModProjectAuthor};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+
+/// Result of `pack_fantome_with_wads`
+#[derive(Debug, Clone, Default)]
+pub struct PackedFantome {
+    /// Chunks across all archives
+    pub file_count: usize,
+    /// Size of the package
+    pub total_size: u64,
+    /// Files left out of an archive because another file has the same path hash
+    pub duplicates: Vec<String>,
+}
+
+/// Write a .fantome for the project at `project_path` to `output`, building
+/// each WAD folder into an archive. WADs already packed in `content/base`
+/// are stored as they are. The partial package is removed on failure.
+pub fn pack_fantome_with_wads(project_path: &Path, output: &Path, mod_project: &ModProject) -> Result<PackedFantome> {
+    let content_base = project_path.join("content").join("base");
+    let file = File::create(output).map_err(|e| Error::io_with_path(e, output))?;
+    let result = write_package(&content_base, file, mod_project);
+    if result.is_err() {
+        let _ = fs::remove_file(output);
+    }
+    let mut packed = result?;
+    packed.total_size = fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    Ok(packed)
+}
+
+fn write_package(content_base: &Path, file: File, mod_project: &ModProject) -> Result<PackedFantome> {
+    let mut zip = zip::ZipWriter::new(file);
+    let zip_err = |e: zip::result::ZipError| Error::InvalidInput(format!("Failed to write fantome: {}", e));
+    let io_err = |e| Error::io_with_path(e, content_base);
+    let deflated = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    // WAD chunks are compressed already
+    let stored = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .large_file(true);
+
+    zip.start_file("META/info.json", deflated).map_err(zip_err)?;
+    zip.write_all(&fantome_info(mod_project)).map_err(io_err)?;
+
+    let mut entries: Vec<_> = fs::read_dir(content_base)
+        .map_err(io_err)?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+
+    let mut packed = PackedFantome::default();
+    for path in entries {
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if !name.to_lowercase().ends_with(".wad.client") && !name.to_lowercase().ends_with(".wad") {
+            tracing::warn!("Not packing {}: only WADs go into a fantome", path.display());
+            continue;
+        }
+        zip.start_file(format!("WAD/{}", name), stored).map_err(zip_err)?;
+        if path.is_dir() {
+            let stats = write_wad(&path, &mut zip)?;
+            packed.file_count += stats.chunk_count;
+            packed.duplicates.extend(stats.duplicates.into_iter().map(|d| format!("{}/{}", name, d)));
+        } else {
+            let mut wad = File::open(&path).map_err(|e| Error::io_with_path(e, &path))?;
+            std::io::copy(&mut wad, &mut zip).map_err(|e| Error::io_with_path(e, &path))?;
+            packed.file_count += 1;
+        }
+    }
+
+    zip.finish().map_err(zip_err)?;
+    Ok(packed)
+}
+
+/// `META/info.json` as fantome mod managers read it
+fn fantome_info(mod_project: &ModProject) -> Vec<u8> {
+    let authors: Vec<&str> = mod_project
+        .authors
+        .iter()
+        .map(|author| match author {
+            ModProjectAuthor::Name(name) => name.as_str(),
+            ModProjectAuthor::Role { name, .. } => name.as_str(),
+        })
+        .collect();
+    let info = serde_json::json!({
+        "Name": mod_project.display_name,
+        "Author": authors.join(", "),
+        "Version": mod_project.version,
+        "Description": mod_project.description,
+    });
+    serde_json::to_vec_pretty(&info).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wad::reader::WadReader;
+    use std::io::Read;
+
+    #[test]
+    fn test_package_holds_built_wads() {
+        let temp = tempfile::tempdir().unwrap();
+        let wad_dir = temp.path().join("content/base/Ahri.wad.client/assets");
+        fs::create_dir_all(&wad_dir).unwrap();
+        fs::write(wad_dir.join("a.bin"), b"data").unwrap();
+        fs::write(wad_dir.join("b.bin"), b"").unwrap();
+        let mod_project = ModProject {
+            name: "ahri-mod".to_string(),
+            display_name: "Ahri Mod".to_string(),
+            version: "1.0.0".to_string(),
+            description: String::new(),
+            authors: vec![ModProjectAuthor::Name("Dexal".to_string())],
+            license: None,
+            transformers: vec![],
+            layers: ltk_mod_project::default_layers(),
+            thumbnail: None,
+        };
+
+        let output = temp.path().join("ahri.fantome");
+        let packed = pack_fantome_with_wads(temp.path(), &output, &mod_project).unwrap();
+        assert_eq!(packed.file_count, 2);
+        assert!(packed.duplicates.is_empty());
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let mut info = String::new();
+        archive.by_name("META/info.json").unwrap().read_to_string(&mut info).unwrap();
+        assert!(info.contains("\"Author\": \"Dexal\""));
+        let mut wad = Vec::new();
+        archive.by_name("WAD/Ahri.wad.client").unwrap().read_to_end(&mut wad).unwrap();
+        let wad_path = temp.path().join("Ahri.wad.client");
+        fs::write(&wad_path, wad).unwrap();
+        assert_eq!(WadReader::open(&wad_path).unwrap().chunk_count(), 2);
+    }
+}
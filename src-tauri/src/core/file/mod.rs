@@ -0,0 +1,6 @@
+// Game file formats that aren't WADs, BINs or meshes
+pub mod stringtable;
+pub mod texture;
+
+pub use stringtable::{is_stringtable, load_rst_keys, StringTable, StringTableEntry};
+pub use texture::TextureAnalysis;
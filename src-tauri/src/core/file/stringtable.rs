@@ -0,0 +1,293 @@
+//! RST string tables (`.stringtable`)
+//!
+//! Skin names, tooltips and announcer text live in the locale text WADs
+//! (`Global.{locale}.wad.client`) as RST files: a table of hash/offset pairs
+//! followed by NUL-terminated UTF-8 strings. Keys are lowercase xxh64 hashes
+//! truncated to 40 (v2, v3), 39 (v4) or 38 (v5) bits, so names can only be
+//! shown for keys found in the downloaded `hashes.rst.txt`.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use xxhash_rust::xxh64::xxh64;
+
+const MAGIC: &[u8; 3] = b"RST";
+
+/// Hash list holding known string table keys
+pub const RST_HASH_FILE: &str = "hashes.rst.txt";
+
+/// A parsed string table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringTable {
+    pub version: u8,
+    /// Font configuration embedded in v2 files
+    pub font_config: Option<String>,
+    /// Flag byte after the entry table in v2–v4; kept as read
+    pub mode: u8,
+    /// (key hash, text) in file order
+    pub entries: Vec<(u64, String)>,
+}
+
+/// One entry as reported to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StringTableEntry {
+    /// Key hash as 10 hex digits; `set_stringtable_entry` accepts it as `{hash}`
+    pub hash: String,
+    /// Key name, if it is in the hash list
+    pub key: Option<String>,
+    pub value: String,
+}
+
+impl StringTable {
+    pub fn read(path: &Path) -> Result<Self> {
+        let data = fs::read(path).map_err(|e| Error::io_with_path(e, path))?;
+        Self::parse(&data).map_err(|e| match e {
+            Error::InvalidInput(message) => Error::InvalidInput(format!("{}: {}", path.display(), message)),
+            other => other,
+        })
+    }
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let mut reader = Reader { data, pos: 0 };
+        if reader.take(3)? != MAGIC {
+            return Err(Error::InvalidInput("Not an RST string table".to_string()));
+        }
+        let version = reader.u8()?;
+        if !(2..=5).contains(&version) {
+            return Err(Error::InvalidInput(format!("Unsupported string table version {}", version)));
+        }
+
+        let font_config = if version == 2 && reader.u8()? != 0 {
+            let len = reader.u32()? as usize;
+            Some(String::from_utf8_lossy(reader.take(len)?).to_string())
+        } else {
+            None
+        };
+
+        let bits = hash_bits(version);
+        let count = reader.u32()? as usize;
+        let mut raw_entries = Vec::with_capacity(count.min(data.len() / 8));
+        for _ in 0..count {
+            let packed = reader.u64()?;
+            raw_entries.push((packed & hash_mask(bits), packed >> bits));
+        }
+        let mode = if version < 5 { reader.u8()? } else { 0 };
+
+        let strings = &data[reader.pos..];
+        let entries = raw_entries
+            .into_iter()
+            .map(|(hash, offset)| {
+                let start = usize::try_from(offset).ok().filter(|&o| o <= strings.len()).ok_or_else(|| {
+                    Error::InvalidInput(format!("String offset {} is past the end of the file", offset))
+                })?;
+                let len = strings[start..].iter().position(|&b| b == 0).unwrap_or(strings.len() - start);
+                Ok((hash, String::from_utf8_lossy(&strings[start..start + len]).to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { version, font_config, mode, entries })
+    }
+
+    /// Serialize in the table's own version. Identical strings are stored once.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bits = hash_bits(self.version);
+        let mut strings = Vec::new();
+        let mut offsets: HashMap<&str, u64> = HashMap::new();
+        let packed: Vec<u64> = self
+            .entries
+            .iter()
+            .map(|(hash, value)| {
+                let offset = *offsets.entry(value.as_str()).or_insert_with(|| {
+                    let offset = strings.len() as u64;
+                    strings.extend_from_slice(value.as_bytes());
+                    strings.push(0);
+                    offset
+                });
+                (offset << bits) | (hash & hash_mask(bits))
+            })
+            .collect();
+
+        let mut out = Vec::with_capacity(16 + packed.len() * 8 + strings.len());
+        out.extend_from_slice(MAGIC);
+        out.push(self.version);
+        if self.version == 2 {
+            match &self.font_config {
+                Some(config) => {
+                    out.push(1);
+                    out.extend_from_slice(&(config.len() as u32).to_le_bytes());
+                    out.extend_from_slice(config.as_bytes());
+                }
+                None => out.push(0),
+            }
+        }
+        out.extend_from_slice(&(packed.len() as u32).to_le_bytes());
+        for value in packed {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        if self.version < 5 {
+            out.push(self.mode);
+        }
+        out.extend_from_slice(&strings);
+        out
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_bytes()).map_err(|e| Error::io_with_path(e, path))
+    }
+
+    /// Hash a key name the way this table's version does
+    pub fn hash_key(&self, key: &str) -> u64 {
+        xxh64(key.to_lowercase().as_bytes(), 0) & hash_mask(hash_bits(self.version))
+    }
+
+    /// Resolve a key given as a name or as a `{hex}` hash
+    pub fn key_hash(&self, key: &str) -> Result<u64> {
+        match key.strip_prefix('{').and_then(|k| k.strip_suffix('}')) {
+            Some(hex) => u64::from_str_radix(hex, 16)
+                .map(|hash| hash & hash_mask(hash_bits(self.version)))
+                .map_err(|_| Error::InvalidInput(format!("Invalid key hash '{}'", key))),
+            None => Ok(self.hash_key(key)),
+        }
+    }
+
+    /// Set the text for `hash`, appending a new entry if it has none.
+    /// Returns the previous text.
+    pub fn set(&mut self, hash: u64, value: impl Into<String>) -> Option<String> {
+        let value = value.into();
+        match self.entries.iter_mut().find(|(h, _)| *h == hash) {
+            Some((_, existing)) => Some(std::mem::replace(existing, value)),
+            None => {
+                self.entries.push((hash, value));
+                None
+            }
+        }
+    }
+
+    /// Entries with key names from `keys` (see `load_rst_keys`)
+    pub fn resolved_entries(&self, keys: &HashMap<u64, String>) -> Vec<StringTableEntry> {
+        self.entries
+            .iter()
+            .map(|(hash, value)| StringTableEntry {
+                hash: format!("{:010x}", hash),
+                key: keys.get(hash).cloned(),
+                value: value.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Key names from `hashes.rst.txt` in `hash_dir`, hashed for a table of
+/// `version`. The list's own hash column is ignored: it may use a different
+/// bit width than the table. Missing lists give an empty map.
+pub fn load_rst_keys(hash_dir: &Path, version: u8) -> HashMap<u64, String> {
+    let path = hash_dir.join(RST_HASH_FILE);
+    let Ok(content) = fs::read_to_string(&path) else {
+        tracing::debug!("No string table hash list at {}", path.display());
+        return HashMap::new();
+    };
+
+    let mask = hash_mask(hash_bits(version));
+    content
+        .lines()
+        .filter_map(|line| line.split_once(' ').map(|(_, key)| key.trim()))
+        .filter(|key| !key.is_empty())
+        .map(|key| (xxh64(key.to_lowercase().as_bytes(), 0) & mask, key.to_string()))
+        .collect()
+}
+
+/// Whether `path` is a string table file
+pub fn is_stringtable(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("stringtable"))
+}
+
+fn hash_bits(version: u8) -> u32 {
+    match version {
+        0..=3 => 40,
+        4 => 39,
+        _ => 38,
+    }
+}
+
+fn hash_mask(bits: u32) -> u64 {
+    (1u64 << bits) - 1
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.data.len()).ok_or_else(|| {
+            Error::InvalidInput("String table is truncated".to_string())
+        })?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn table(version: u8) -> StringTable {
+        let mut table = StringTable { version, font_config: None, mode: 0, entries: Vec::new() };
+        let name = table.hash_key("game_character_skin_displayname_Ahri_1");
+        table.set(name, "Dynasty Ahri");
+        table.set(table.hash_key("shared_ok"), "OK");
+        table.set(table.hash_key("shared_confirm"), "OK");
+        table
+    }
+
+    #[test]
+    fn test_round_trip_all_versions() {
+        for version in 2..=5 {
+            let mut original = table(version);
+            if version == 2 {
+                original.font_config = Some("[FontConfig \"English\"]".to_string());
+            }
+            let bytes = original.to_bytes();
+            assert_eq!(StringTable::parse(&bytes).unwrap(), original, "version {}", version);
+        }
+        // Shared strings are stored once
+        assert_eq!(table(5).to_bytes().len(), 4 + 4 + 3 * 8 + "Dynasty Ahri\0OK\0".len());
+    }
+
+    #[test]
+    fn test_set_and_resolve_keys() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(RST_HASH_FILE), "0123456789 game_character_skin_displayname_Ahri_1\n").unwrap();
+
+        let mut table = table(4);
+        let hash = table.key_hash("GAME_CHARACTER_SKIN_DISPLAYNAME_AHRI_1").unwrap();
+        assert_eq!(table.set(hash, "Kitsune Ahri").as_deref(), Some("Dynasty Ahri"));
+        assert_eq!(table.key_hash(&format!("{{{:x}}}", hash)).unwrap(), hash);
+        assert!(table.key_hash("{xyz}").is_err());
+
+        let keys = load_rst_keys(dir.path(), table.version);
+        let entries = table.resolved_entries(&keys);
+        assert_eq!(entries[0].key.as_deref(), Some("game_character_skin_displayname_Ahri_1"));
+        assert_eq!(entries[0].value, "Kitsune Ahri");
+        assert_eq!(entries[1].key, None);
+
+        assert!(StringTable::parse(b"RST\x09").is_err());
+        assert!(StringTable::parse(b"RST\x05\x02\x00\x00\x00").is_err());
+    }
+}
@@ -0,0 +1,363 @@
+//! Texture channel and colorspace inspection (`.dds`, `.tex`)
+//!
+//! Recoloring needs to know how a texture stores its data before editing it:
+//! whether color is sRGB or linear, whether alpha carries anything, and which
+//! channels a packed mask actually uses. The format and colorspace come from
+//! the file header; channel statistics are computed from a mip no larger than
+//! `ANALYSIS_SIZE` so large textures stay fast.
+
+use crate::error::{Error, Result};
+use ltk_texture::Texture;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+/// Largest side of the mip the statistics are computed from
+pub const ANALYSIS_SIZE: u32 = 256;
+
+/// Channel values within this distance count as equal (block compression noise)
+const CHANNEL_TOLERANCE: u8 = 8;
+
+/// How the texture's color values are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Colorspace {
+    Srgb,
+    Linear,
+    /// The format doesn't say (TEX files and DDS files without a DX10 header)
+    Unknown,
+}
+
+/// What the alpha channel holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlphaUsage {
+    /// Fully opaque everywhere
+    None,
+    /// Only fully transparent or fully opaque pixels (cutout)
+    Binary,
+    /// Partial transparency
+    Gradient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStats {
+    pub min: u8,
+    pub max: u8,
+    pub mean: f32,
+}
+
+/// Result of `analyze_texture`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextureAnalysis {
+    /// "DDS" or "TEX"
+    pub container: String,
+    /// Pixel format, e.g. "BC3" or "BC7_SRGB"
+    pub format: String,
+    pub colorspace: Colorspace,
+    pub width: u32,
+    pub height: u32,
+    pub mip_count: u32,
+    /// Size of the mip the statistics come from
+    pub analyzed_size: (u32, u32),
+    pub red: ChannelStats,
+    pub green: ChannelStats,
+    pub blue: ChannelStats,
+    pub alpha: ChannelStats,
+    pub alpha_usage: AlphaUsage,
+    /// Channels whose values vary, e.g. "RGA"
+    pub used_channels: String,
+    /// Red, green and blue are equal everywhere
+    pub grayscale: bool,
+    /// Color channels that vary hold mostly near-black or near-white values,
+    /// as in channel-packed masks
+    pub mask_like: bool,
+}
+
+/// Format details read from a texture header
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TextureHeader {
+    container: &'static str,
+    format: String,
+    colorspace: Colorspace,
+    width: u32,
+    height: u32,
+    mip_count: u32,
+}
+
+pub fn analyze_texture(path: &Path) -> Result<TextureAnalysis> {
+    let data = fs::read(path).map_err(|e| Error::io_with_path(e, path))?;
+    analyze_texture_bytes(&data).map_err(|e| match e {
+        Error::InvalidInput(message) => Error::InvalidInput(format!("{}: {}", path.display(), message)),
+        other => other,
+    })
+}
+
+pub fn analyze_texture_bytes(data: &[u8]) -> Result<TextureAnalysis> {
+    let header = parse_header(data)?;
+    let texture = Texture::from_reader(&mut Cursor::new(data))
+        .map_err(|e| Error::InvalidInput(format!("Failed to parse texture: {:?}", e)))?;
+
+    let level = analysis_mip(header.width.max(header.height), header.mip_count);
+    let surface = texture
+        .decode_mipmap(level)
+        .or_else(|_| texture.decode_mipmap(0))
+        .map_err(|e| Error::InvalidInput(format!("Failed to decode texture: {:?}", e)))?;
+    let image = surface
+        .into_rgba_image()
+        .map_err(|e| Error::InvalidInput(format!("Failed to convert to RGBA: {:?}", e)))?;
+
+    Ok(analyze_pixels(header, (image.width(), image.height()), image.as_raw()))
+}
+
+/// Problems with replacing `original` by `replacement` in the game
+pub fn compare_textures(original: &TextureAnalysis, replacement: &TextureAnalysis) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if original.format != replacement.format {
+        warnings.push(format!("Format is {} but the original is {}", replacement.format, original.format));
+    }
+    let known = [original.colorspace, replacement.colorspace].iter().all(|c| *c != Colorspace::Unknown);
+    if known && original.colorspace != replacement.colorspace {
+        warnings.push(format!(
+            "Colorspace is {:?} but the original is {:?}",
+            replacement.colorspace, original.colorspace
+        ));
+    }
+    match (original.alpha_usage, replacement.alpha_usage) {
+        (a, b) if a == b => {}
+        (_, AlphaUsage::None) => warnings.push("Alpha is unused but the original uses it".to_string()),
+        (AlphaUsage::None, _) => warnings.push("Alpha is used but the original is opaque".to_string()),
+        (a, b) => warnings.push(format!("Alpha is {:?} but the original's is {:?}", b, a)),
+    }
+    warnings
+}
+
+fn parse_header(data: &[u8]) -> Result<TextureHeader> {
+    let u32_at = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .ok_or_else(|| Error::InvalidInput("Texture header is truncated".to_string()))
+    };
+
+    match data.get(0..4) {
+        Some(b"DDS ") => {
+            const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+            let flags = u32_at(8)?;
+            let mip_count = if flags & DDSD_MIPMAPCOUNT != 0 { u32_at(28)?.max(1) } else { 1 };
+            let four_cc = data.get(84..88).unwrap_or_default();
+            let (format, colorspace) = match four_cc {
+                b"DX10" => dxgi_format(u32_at(128)?),
+                b"DXT1" => ("BC1".to_string(), Colorspace::Unknown),
+                b"DXT3" => ("BC2".to_string(), Colorspace::Unknown),
+                b"DXT5" => ("BC3".to_string(), Colorspace::Unknown),
+                b"ATI1" | b"BC4U" => ("BC4".to_string(), Colorspace::Unknown),
+                b"ATI2" | b"BC5U" => ("BC5".to_string(), Colorspace::Unknown),
+                [0, 0, 0, 0] => (format!("RGB{}", u32_at(88)?), Colorspace::Unknown),
+                other => (String::from_utf8_lossy(other).trim().to_string(), Colorspace::Unknown),
+            };
+            Ok(TextureHeader {
+                container: "DDS",
+                format,
+                colorspace,
+                width: u32_at(16)?,
+                height: u32_at(12)?,
+                mip_count,
+            })
+        }
+        Some(b"TEX\0") if data.len() >= 12 => {
+            let (width, height) = (
+                u16::from_le_bytes([data[4], data[5]]) as u32,
+                u16::from_le_bytes([data[6], data[7]]) as u32,
+            );
+            let format = match data[9] {
+                1 => "ETC1".to_string(),
+                2 => "ETC2_EAC".to_string(),
+                3 => "ETC2".to_string(),
+                10 => "BC1".to_string(),
+                12 => "BC3".to_string(),
+                20 => "BGRA8".to_string(),
+                other => format!("TEX format {}", other),
+            };
+            let has_mips = data[11] & 1 != 0;
+            let mip_count = if has_mips { 32 - width.max(height).max(1).leading_zeros() } else { 1 };
+            Ok(TextureHeader { container: "TEX", format, colorspace: Colorspace::Unknown, width, height, mip_count })
+        }
+        _ => Err(Error::InvalidInput("Not a DDS or TEX texture".to_string())),
+    }
+}
+
+fn dxgi_format(format: u32) -> (String, Colorspace) {
+    let (name, srgb) = match format {
+        28 => ("RGBA8", false),
+        29 => ("RGBA8", true),
+        71 => ("BC1", false),
+        72 => ("BC1", true),
+        74 => ("BC2", false),
+        75 => ("BC2", true),
+        77 => ("BC3", false),
+        78 => ("BC3", true),
+        80 => ("BC4", false),
+        83 => ("BC5", false),
+        87 => ("BGRA8", false),
+        91 => ("BGRA8", true),
+        95 => ("BC6H", false),
+        98 => ("BC7", false),
+        99 => ("BC7", true),
+        other => return (format!("DXGI {}", other), Colorspace::Unknown),
+    };
+    if srgb {
+        (format!("{}_SRGB", name), Colorspace::Srgb)
+    } else {
+        (name.to_string(), Colorspace::Linear)
+    }
+}
+
+/// First mip whose larger side is at most `ANALYSIS_SIZE`
+fn analysis_mip(size: u32, mip_count: u32) -> u32 {
+    let mut level = 0;
+    while (size >> level) > ANALYSIS_SIZE && level + 1 < mip_count {
+        level += 1;
+    }
+    level
+}
+
+fn analyze_pixels(header: TextureHeader, analyzed_size: (u32, u32), rgba: &[u8]) -> TextureAnalysis {
+    let pixels = rgba.chunks_exact(4);
+    let count = pixels.len().max(1) as f32;
+    let mut min = [u8::MAX; 4];
+    let mut max = [u8::MIN; 4];
+    let mut sum = [0u64; 4];
+    let mut extreme = [0usize; 3];
+    let mut grayscale = true;
+    let mut alpha_binary = true;
+
+    for pixel in pixels {
+        for c in 0..4 {
+            min[c] = min[c].min(pixel[c]);
+            max[c] = max[c].max(pixel[c]);
+            sum[c] += pixel[c] as u64;
+        }
+        for c in 0..3 {
+            if pixel[c] <= 16 || pixel[c] >= 239 {
+                extreme[c] += 1;
+            }
+        }
+        grayscale &= pixel[0].abs_diff(pixel[1]) <= CHANNEL_TOLERANCE && pixel[1].abs_diff(pixel[2]) <= CHANNEL_TOLERANCE;
+        alpha_binary &= pixel[3] <= CHANNEL_TOLERANCE || pixel[3] >= 255 - CHANNEL_TOLERANCE;
+    }
+
+    let stats = |c: usize| ChannelStats {
+        min: min[c].min(max[c]),
+        max: max[c],
+        mean: sum[c] as f32 / count,
+    };
+    let varies = |c: usize| max[c].saturating_sub(min[c]) > CHANNEL_TOLERANCE;
+
+    let alpha_usage = if min[3] >= 255 - CHANNEL_TOLERANCE {
+        AlphaUsage::None
+    } else if alpha_binary {
+        AlphaUsage::Binary
+    } else {
+        AlphaUsage::Gradient
+    };
+    let used_channels: String = ['R', 'G', 'B']
+        .into_iter()
+        .enumerate()
+        .filter(|(c, _)| varies(*c))
+        .map(|(_, name)| name)
+        .chain((alpha_usage != AlphaUsage::None).then_some('A'))
+        .collect();
+    let used_color: Vec<usize> = (0..3).filter(|c| varies(*c)).collect();
+    let mask_like = !grayscale
+        && !used_color.is_empty()
+        && used_color.iter().all(|c| extreme[*c] as f32 >= 0.8 * count);
+
+    TextureAnalysis {
+        container: header.container.to_string(),
+        format: header.format,
+        colorspace: header.colorspace,
+        width: header.width,
+        height: header.height,
+        mip_count: header.mip_count,
+        analyzed_size,
+        red: stats(0),
+        green: stats(1),
+        blue: stats(2),
+        alpha: stats(3),
+        alpha_usage,
+        used_channels,
+        grayscale,
+        mask_like,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dds_header(four_cc: &[u8; 4], dxgi: Option<u32>, mips: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 148];
+        data[0..4].copy_from_slice(b"DDS ");
+        data[8..12].copy_from_slice(&0x20000u32.to_le_bytes());
+        data[12..16].copy_from_slice(&512u32.to_le_bytes());
+        data[16..20].copy_from_slice(&1024u32.to_le_bytes());
+        data[28..32].copy_from_slice(&mips.to_le_bytes());
+        data[84..88].copy_from_slice(four_cc);
+        if let Some(format) = dxgi {
+            data[128..132].copy_from_slice(&format.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        let header = parse_header(&dds_header(b"DX10", Some(99), 11)).unwrap();
+        assert_eq!((header.format.as_str(), header.colorspace), ("BC7_SRGB", Colorspace::Srgb));
+        assert_eq!((header.width, header.height, header.mip_count), (1024, 512, 11));
+        assert_eq!(analysis_mip(1024, header.mip_count), 2);
+        assert_eq!(analysis_mip(1024, 1), 0);
+
+        let header = parse_header(&dds_header(b"DXT5", None, 1)).unwrap();
+        assert_eq!((header.format.as_str(), header.colorspace), ("BC3", Colorspace::Unknown));
+
+        let mut tex = b"TEX\0".to_vec();
+        tex.extend_from_slice(&[0x00, 0x02, 0x00, 0x01, 1, 12, 0, 1]);
+        let header = parse_header(&tex).unwrap();
+        assert_eq!((header.container, header.format.as_str()), ("TEX", "BC3"));
+        assert_eq!((header.width, header.height, header.mip_count), (512, 256, 10));
+
+        assert!(parse_header(b"PNG!").is_err());
+        assert!(parse_header(b"DDS \0\0").is_err());
+    }
+
+    #[test]
+    fn test_analyze_and_compare() {
+        let header = || parse_header(&dds_header(b"DX10", Some(77), 1)).unwrap();
+        // Red/green mask with a cutout alpha
+        let mask: Vec<u8> = [[255, 0, 0, 255], [0, 255, 0, 0], [0, 0, 0, 255], [255, 255, 0, 255]].concat();
+        let analysis = analyze_pixels(header(), (2, 2), &mask);
+        assert_eq!(analysis.used_channels, "RGA");
+        assert_eq!(analysis.alpha_usage, AlphaUsage::Binary);
+        assert!(analysis.mask_like && !analysis.grayscale);
+        assert_eq!((analysis.red.min, analysis.red.max, analysis.red.mean), (0, 255, 127.5));
+
+        let gray: Vec<u8> = [[10, 12, 10, 255], [128, 128, 130, 255], [200, 200, 200, 255], [60, 60, 60, 255]].concat();
+        let opaque = analyze_pixels(header(), (2, 2), &gray);
+        assert!(opaque.grayscale && !opaque.mask_like);
+        assert_eq!((opaque.alpha_usage, opaque.used_channels.as_str()), (AlphaUsage::None, "RGB"));
+
+        assert!(compare_textures(&analysis, &analysis).is_empty());
+        let mut replacement = opaque.clone();
+        replacement.format = "BC1".to_string();
+        replacement.colorspace = Colorspace::Srgb;
+        assert_eq!(
+            compare_textures(&analysis, &replacement),
+            [
+                "Format is BC1 but the original is BC3",
+                "Colorspace is Srgb but the original is Linear",
+                "Alpha is unused but the original uses it",
+            ]
+        );
+    }
+}
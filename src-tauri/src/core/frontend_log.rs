@@ -67,7 +67,7 @@ where
 }
 
 /// Visitor to extract message from tracing fields
-struct MessageVisitor<'a>(&'a mut String);
+pub(crate) struct MessageVisitor<'a>(pub(crate) &'a mut String);
 
 impl<'a> tracing::field::Visit for MessageVisitor<'a> {
     fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
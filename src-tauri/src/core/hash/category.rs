@@ -0,0 +1,68 @@
+//! Groups of CommunityDragon hash lists
+//!
+//! The lists cover different parts of the game, and not every user needs
+//! all of them. Each category can be left out of `download_hashes`, and the
+//! loaded entries are counted per category. Map and TFT arena paths are
+//! game files like champion assets, so CommunityDragon lists them in
+//! `hashes.game.txt` rather than in a list of their own.
+
+use serde::{Deserialize, Serialize};
+
+/// A group of CommunityDragon hash lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashCategory {
+    /// WAD paths of game files: champions, maps and TFT arenas
+    Game,
+    /// WAD paths of the League client
+    Lcu,
+    /// BIN object, field, hash and class names
+    Bin,
+    /// String table keys
+    Rst,
+}
+
+impl HashCategory {
+    pub const ALL: [HashCategory; 4] = [HashCategory::Game, HashCategory::Lcu, HashCategory::Bin, HashCategory::Rst];
+
+    /// Files CommunityDragon serves for the category. The game list comes
+    /// in two halves that are merged into `hashes.game.txt` after download.
+    pub fn files(self) -> &'static [&'static str] {
+        match self {
+            HashCategory::Game => &["hashes.game.txt.0", "hashes.game.txt.1"],
+            HashCategory::Lcu => &["hashes.lcu.txt"],
+            HashCategory::Bin => {
+                &["hashes.binentries.txt", "hashes.binhashes.txt", "hashes.bintypes.txt", "hashes.binfields.txt"]
+            }
+            HashCategory::Rst => &["hashes.rst.txt"],
+        }
+    }
+
+    /// The category of a list in the hash directory; `None` for the user
+    /// list and lists of other origin
+    pub fn of_file(name: &str) -> Option<Self> {
+        let name = merged_name(name);
+        Self::ALL.into_iter().find(|category| category.files().iter().any(|file| merged_name(file) == name))
+    }
+}
+
+/// `name` without the `.0`/`.1` of a split half
+fn merged_name(name: &str) -> &str {
+    name.strip_suffix(".0").or_else(|| name.strip_suffix(".1")).unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories_cover_their_files() {
+        assert_eq!(HashCategory::of_file("hashes.game.txt"), Some(HashCategory::Game));
+        assert_eq!(HashCategory::of_file("hashes.game.txt.1"), Some(HashCategory::Game));
+        assert_eq!(HashCategory::of_file("hashes.bintypes.txt"), Some(HashCategory::Bin));
+        assert_eq!(HashCategory::of_file("hashes.lcu.txt"), Some(HashCategory::Lcu));
+        assert_eq!(HashCategory::of_file("hashes.user.txt"), None);
+        assert_eq!(HashCategory::ALL.iter().map(|c| c.files().len()).sum::<usize>(), 8);
+        assert_eq!(serde_json::to_string(&HashCategory::Lcu).unwrap(), "\"lcu\"");
+    }
+}
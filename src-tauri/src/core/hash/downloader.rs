@@ -1,6 +1,14 @@
+use crate::core::hash::HashCategory;
+use crate::core::operation::CancellationToken;
+use crate::core::paths::safe_relative;
 use crate::error::{Error, Result};
-use reqwest::Client;
+use futures::stream::{self, StreamExt};
+use parking_lot::Mutex;
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::path::Path;
 use std::time::{Duration, SystemTime};
 use tokio::fs;
@@ -10,8 +18,40 @@ use tokio::io::AsyncWriteExt;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadStats {
     pub downloaded: usize,
+    /// Files left as they are: `not_modified` plus `age_skipped`
     pub skipped: usize,
+    /// Files the server answered 304 Not Modified for
+    pub not_modified: usize,
+    /// Files without a stored ETag or Last-Modified that were recent enough to keep
+    pub age_skipped: usize,
     pub errors: usize,
+    /// Downloads that failed validation, as "name: reason"; the previous
+    /// files were kept. Also counted in `errors`.
+    pub rejected: Vec<String>,
+    /// Stopped early on request; files finished before are kept
+    pub cancelled: bool,
+}
+
+/// Progress of one file in `download_hashes`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashDownloadProgress {
+    pub file: String,
+    /// Position of this file among `file_count`, from 0
+    pub file_index: usize,
+    pub file_count: usize,
+    pub downloaded: u64,
+    /// From Content-Length; `None` when the server doesn't send one
+    pub total: Option<u64>,
+}
+
+/// A hash list to download from a mirror instead of CommunityDragon
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HashSource {
+    /// Shown in logs and errors, e.g. "Game hashes (mirror)"
+    pub name: String,
+    pub url: String,
+    /// File name in the hash directory, e.g. "hashes.game.txt"
+    pub file: String,
 }
 
 /// GitHub API response for file content
@@ -23,9 +63,72 @@ struct GitHubFile {
     sha: String,
 }
 
+/// ETag and Last-Modified a server sent with a hash file, sent back as
+/// If-None-Match and If-Modified-Since on the next download
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        Self { etag: header(ETAG), last_modified: header(LAST_MODIFIED) }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Stored validators by hash file name
+type ValidatorMap = BTreeMap<String, Validators>;
+
+/// What happened to one hash file
+#[derive(Debug, Clone, PartialEq)]
+enum FileOutcome {
+    Downloaded,
+    NotModified,
+    AgeSkipped,
+    /// Downloaded but not a complete hash list, for the given reason
+    Rejected(String),
+}
+
+/// Response to one hash list request
+enum Fetched {
+    NotModified,
+    /// Written in place, with the validators the server sent
+    Downloaded(Validators),
+    /// Failed `check_download`; the previous file is untouched
+    Rejected(String),
+}
+
 const GITHUB_API_BASE: &str = "https://api.github.com/repos/CommunityDragon/Data/contents/hashes/lol";
+/// Files the server gave no validators for are re-downloaded after this age
 const FILE_AGE_THRESHOLD: Duration = Duration::from_secs(14 * 24 * 60 * 60); // 14 days
 
+/// Sidecar in the hash directory holding each file's `Validators`
+const VALIDATORS_FILE: &str = "hash-validators.json";
+
+/// Lines checked by `validate_hash_list`
+const VALIDATE_LINES: usize = 20;
+
+/// Bytes from the start of a download that `validate_hash_list` looks at
+const VALIDATE_SAMPLE_LEN: usize = 64 * 1024;
+
+/// Bytes between two progress reports for the same file
+const PROGRESS_STEP: u64 = 512 * 1024;
+
+/// Files downloaded at the same time, so the small lists don't wait behind
+/// the game list
+const CONCURRENT_DOWNLOADS: usize = 3;
+
+/// How often a request waiting on the network checks for cancellation
+const CANCEL_POLL: Duration = Duration::from_millis(200);
+
 /// Gets the RitoShark hash directory path
 ///
 /// Returns the standard RitoShark directory: %APPDATA%/RitoShark/Requirements/Hashes
@@ -40,27 +143,34 @@ pub fn get_ritoshark_hash_dir() -> Result<std::path::PathBuf> {
         .join("Hashes"))
 }
 
-/// List of hash files to download from CommunityDragon
-const HASH_FILES: &[&str] = &[
-    "hashes.binentries.txt",
-    "hashes.binhashes.txt",
-    "hashes.bintypes.txt",
-    "hashes.binfields.txt",
-    "hashes.game.txt.0",
-    "hashes.game.txt.1",
-    "hashes.lcu.txt",
-    "hashes.rst.txt",
-];
-
-/// Downloads hash files from CommunityDragon repository
+/// Downloads hash files from CommunityDragon repository, or from `sources`
+/// when any are given. Each file is checked to be a hash list before it
+/// replaces the existing one. Files downloaded before are requested
+/// conditionally with the ETag or Last-Modified the server sent then, kept
+/// in `hash-validators.json`; files without either fall back to an age check.
+/// Up to `CONCURRENT_DOWNLOADS` files download at once, each to a `.part`
+/// renamed into place when complete; one failing doesn't stop the others.
 ///
 /// # Arguments
 /// * `output_dir` - Directory where hash files will be saved
-/// * `force` - If true, downloads all files regardless of age
+/// * `force` - If true, downloads all files unconditionally
+/// * `sources` - Mirrors to download from instead; empty for CommunityDragon
+/// * `categories` - CommunityDragon lists to download; mirrors are not filtered
+/// * `cancel` - Stops the download, also while waiting on a stalled server;
+///   files already written are kept and the in-flight `.part` is removed
+/// * `progress` - Called as each file that needs updating is downloaded
 ///
 /// # Returns
-/// Statistics about the download operation
-pub async fn download_hashes(output_dir: impl AsRef<Path>, force: bool) -> Result<DownloadStats> {
+/// Statistics about the download operation, with `cancelled` set if it was
+/// stopped
+pub async fn download_hashes(
+    output_dir: impl AsRef<Path>,
+    force: bool,
+    sources: &[HashSource],
+    categories: &[HashCategory],
+    cancel: Option<&CancellationToken>,
+    progress: impl Fn(&HashDownloadProgress) + Send + Sync,
+) -> Result<DownloadStats> {
     let output_dir = output_dir.as_ref();
     
     tracing::info!("Downloading hash files to: {}", output_dir.display());
@@ -83,34 +193,70 @@ pub async fn download_hashes(output_dir: impl AsRef<Path>, force: bool) -> Resul
     let mut stats = DownloadStats {
         downloaded: 0,
         skipped: 0,
+        not_modified: 0,
+        age_skipped: 0,
         errors: 0,
+        rejected: Vec::new(),
+        cancelled: false,
     };
-    
-    // Get list of files from GitHub API
-    tracing::debug!("Fetching file list from GitHub API");
-    let files = fetch_file_list(&client).await?;
-    tracing::debug!("Found {} files in repository", files.len());
-    
-    // Download each required hash file
-    for file_name in HASH_FILES {
-        tracing::debug!("Processing file: {}", file_name);
-        match download_file(&client, &files, file_name, output_dir, force).await {
-            Ok(downloaded) => {
-                if downloaded {
-                    tracing::info!("Downloaded: {}", file_name);
-                    stats.downloaded += 1;
-                } else {
-                    tracing::debug!("Skipped (up to date): {}", file_name);
-                    stats.skipped += 1;
-                }
-            }
-            Err(e) => {
-                tracing::error!("Error downloading {}: {}", file_name, e);
-                stats.errors += 1;
+    let stored = load_validators(output_dir).await;
+    let validators = Mutex::new(stored.clone());
+    let progress = &progress;
+    
+    if sources.is_empty() {
+        // Get list of files from GitHub API
+        tracing::debug!("Fetching file list from GitHub API");
+        let files = match cancellable(fetch_file_list(&client), cancel).await {
+            Err(Error::Cancelled) => {
+                tracing::info!("Hash download cancelled before any file");
+                stats.cancelled = true;
+                return Ok(stats);
             }
+            files => files?,
+        };
+        tracing::debug!("Found {} files in repository", files.len());
+
+        // Download each required hash file
+        let hash_files: Vec<&str> = HashCategory::ALL
+            .into_iter()
+            .filter(|category| categories.contains(category))
+            .flat_map(|category| category.files().iter().copied())
+            .collect();
+        tracing::debug!("Downloading {} hash files for {:?}", hash_files.len(), categories);
+        let (client, files, validators, file_count) = (&client, &files, &validators, hash_files.len());
+        // Collected first: a lazy `map` makes the future fail the `Send` check
+        let downloads: Vec<_> = hash_files.into_iter().enumerate().map(|(index, file_name)| async move {
+            tracing::debug!("Processing file: {}", file_name);
+            let report = file_progress(progress, file_name, index, file_count);
+            let result = download_file(client, files, file_name, output_dir, force, validators, cancel, &report).await;
+            (file_name.to_string(), result)
+        }).collect();
+        let results: Vec<_> = stream::iter(downloads).buffer_unordered(CONCURRENT_DOWNLOADS).collect().await;
+        for (name, result) in results {
+            record_result(&mut stats, &name, result);
+        }
+    } else {
+        tracing::info!("Downloading from {} custom hash sources", sources.len());
+        let (client, validators) = (&client, &validators);
+        let downloads: Vec<_> = sources.iter().enumerate().map(|(index, source)| async move {
+            tracing::debug!("Processing source: {} ({})", source.name, source.url);
+            let report = file_progress(progress, &source.file, index, sources.len());
+            let result = download_source(client, source, output_dir, force, validators, cancel, &report).await;
+            (source.name.clone(), result)
+        }).collect();
+        let results: Vec<_> = stream::iter(downloads).buffer_unordered(CONCURRENT_DOWNLOADS).collect().await;
+        for (name, result) in results {
+            record_result(&mut stats, &name, result);
         }
     }
     
+    let validators = validators.into_inner();
+    if validators != stored {
+        if let Err(e) = save_validators(output_dir, &validators).await {
+            tracing::warn!("Failed to save {}: {}", VALIDATORS_FILE, e);
+        }
+    }
+
     // Merge split game hash files if both exist
     tracing::debug!("Checking for split files to merge");
     if let Err(e) = merge_split_files(output_dir).await {
@@ -121,15 +267,81 @@ pub async fn download_hashes(output_dir: impl AsRef<Path>, force: bool) -> Resul
     }
     
     tracing::info!(
-        "Hash download complete: {} downloaded, {} skipped, {} errors",
+        "Hash download {}: {} downloaded, {} not modified, {} skipped by age, {} errors ({} rejected)",
+        if stats.cancelled { "cancelled" } else { "complete" },
         stats.downloaded,
-        stats.skipped,
-        stats.errors
+        stats.not_modified,
+        stats.age_skipped,
+        stats.errors,
+        stats.rejected.len()
     );
     
     Ok(stats)
 }
 
+/// Progress callback for one file, taking bytes so far and the total
+fn file_progress<'a>(
+    progress: &'a (impl Fn(&HashDownloadProgress) + Sync),
+    file: &'a str,
+    file_index: usize,
+    file_count: usize,
+) -> impl Fn(u64, Option<u64>) + Sync + 'a {
+    move |downloaded, total| {
+        progress(&HashDownloadProgress { file: file.to_string(), file_index, file_count, downloaded, total })
+    }
+}
+
+/// Count one file's outcome; a file stopped by cancellation isn't an error
+fn record_result(stats: &mut DownloadStats, name: &str, result: Result<FileOutcome>) {
+    match result {
+        Ok(FileOutcome::Downloaded) => {
+            tracing::info!("Downloaded: {}", name);
+            stats.downloaded += 1;
+        }
+        Ok(FileOutcome::NotModified) => {
+            tracing::debug!("Skipped (not modified): {}", name);
+            stats.skipped += 1;
+            stats.not_modified += 1;
+        }
+        Ok(FileOutcome::AgeSkipped) => {
+            tracing::debug!("Skipped (up to date): {}", name);
+            stats.skipped += 1;
+            stats.age_skipped += 1;
+        }
+        Ok(FileOutcome::Rejected(reason)) => {
+            tracing::warn!("Kept the previous {}: the download {}", name, reason);
+            stats.errors += 1;
+            stats.rejected.push(format!("{}: {}", name, reason));
+        }
+        Err(Error::Cancelled) => {
+            tracing::info!("Cancelled while downloading {}", name);
+            stats.cancelled = true;
+        }
+        Err(e) => {
+            tracing::error!("Error downloading {}: {}", name, e);
+            stats.errors += 1;
+        }
+    }
+}
+
+/// Run `future` until it finishes or `cancel` trips, so a stalled
+/// connection doesn't hold off cancellation
+async fn cancellable<T>(future: impl Future<Output = Result<T>>, cancel: Option<&CancellationToken>) -> Result<T> {
+    let Some(token) = cancel else {
+        return future.await;
+    };
+    token.check()?;
+    let cancelled = async {
+        while !token.is_cancelled() {
+            tokio::time::sleep(CANCEL_POLL).await;
+        }
+    };
+    tokio::select! {
+        result = future => result,
+        _ = cancelled => Err(Error::Cancelled),
+    }
+}
+
 /// Fetches the list of files from GitHub API
 async fn fetch_file_list(client: &Client) -> Result<Vec<GitHubFile>> {
     let response = client
@@ -154,22 +366,19 @@ async fn fetch_file_list(client: &Client) -> Result<Vec<GitHubFile>> {
 }
 
 /// Downloads a single file if needed
-///
-/// Returns true if the file was downloaded, false if it was skipped
+#[allow(clippy::too_many_arguments)]
 async fn download_file(
     client: &Client,
     files: &[GitHubFile],
     file_name: &str,
     output_dir: &Path,
     force: bool,
-) -> Result<bool> {
+    validators: &Mutex<ValidatorMap>,
+    cancel: Option<&CancellationToken>,
+    progress: &(dyn Fn(u64, Option<u64>) + Sync),
+) -> Result<FileOutcome> {
     let output_path = output_dir.join(file_name);
-    
-    // Check if file needs updating
-    if !force && !needs_update(&output_path).await? {
-        return Ok(false);
-    }
-    
+
     // Find file in GitHub API response
     let github_file = files
         .iter()
@@ -181,32 +390,225 @@ async fn download_file(
         .as_ref()
         .ok_or_else(|| Error::Hash(format!("No download URL for {}", file_name)))?;
     
-    // Download file content
-    let response = client
-        .get(download_url)
-        .send()
-        .await
-        .map_err(Error::Network)?;
-    
+    // Note: GitHub API returns git blob SHA (includes header), not raw file SHA1
+    // So checksum verification would fail. We skip it since HTTPS ensures integrity.
+    update_file(client, download_url, file_name, &output_path, force, validators, cancel, progress).await
+}
+
+/// Downloads a custom source's file if needed
+async fn download_source(
+    client: &Client,
+    source: &HashSource,
+    output_dir: &Path,
+    force: bool,
+    validators: &Mutex<ValidatorMap>,
+    cancel: Option<&CancellationToken>,
+    progress: &(dyn Fn(u64, Option<u64>) + Sync),
+) -> Result<FileOutcome> {
+    let file = safe_relative(&source.file)?;
+    if file.components().count() != 1 {
+        return Err(Error::UnsafePath {
+            path: source.file.clone(),
+            reason: "must be a file name in the hash directory".to_string(),
+        });
+    }
+    if !source.url.starts_with("https://") && !source.url.starts_with("http://") {
+        return Err(Error::Hash(format!("{}: {} is not an HTTP(S) URL", source.name, source.url)));
+    }
+
+    let output_path = output_dir.join(file);
+    update_file(client, &source.url, &source.name, &output_path, force, validators, cancel, progress).await
+}
+
+/// Download `url` to `output_path` unless it is up to date. With validators
+/// stored for the file the server decides; without them, files younger than
+/// `FILE_AGE_THRESHOLD` are kept. `force` skips both checks.
+#[allow(clippy::too_many_arguments)]
+async fn update_file(
+    client: &Client,
+    url: &str,
+    name: &str,
+    output_path: &Path,
+    force: bool,
+    validators: &Mutex<ValidatorMap>,
+    cancel: Option<&CancellationToken>,
+    progress: &(dyn Fn(u64, Option<u64>) + Sync),
+) -> Result<FileOutcome> {
+    let key = output_path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+    let stored = validators.lock().get(&key).filter(|_| !force && output_path.exists()).cloned();
+    if stored.is_none() && !force && !needs_update(output_path).await? {
+        return Ok(FileOutcome::AgeSkipped);
+    }
+
+    match fetch_hash_list(client, url, name, output_path, stored.as_ref(), cancel, progress).await? {
+        Fetched::NotModified => Ok(FileOutcome::NotModified),
+        Fetched::Downloaded(received) => {
+            if received.is_empty() {
+                validators.lock().remove(&key);
+            } else {
+                validators.lock().insert(key, received);
+            }
+            Ok(FileOutcome::Downloaded)
+        }
+        Fetched::Rejected(reason) => Ok(FileOutcome::Rejected(reason)),
+    }
+}
+
+/// Download `url` and write it to `output_path` if it is a hash list. The
+/// body is streamed to `<output_path>.part` and only renamed into place once
+/// `check_download` passes. With `validators` the request is conditional,
+/// and the server may answer that the file hasn't changed. Cancelling or a
+/// rejected download removes the `.part` and leaves `output_path` as it was.
+async fn fetch_hash_list(
+    client: &Client,
+    url: &str,
+    name: &str,
+    output_path: &Path,
+    validators: Option<&Validators>,
+    cancel: Option<&CancellationToken>,
+    progress: &(dyn Fn(u64, Option<u64>) + Sync),
+) -> Result<Fetched> {
+    let mut request = client.get(url);
+    if let Some(validators) = validators {
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let mut response = cancellable(async { request.send().await.map_err(Error::Network) }, cancel).await?;
+
+    if validators.is_some() && response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
     if !response.status().is_success() {
         return Err(Error::Hash(format!(
             "Failed to download {}: status {}",
-            file_name,
+            name,
             response.status()
         )));
     }
-    
-    let content = response.bytes().await.map_err(Error::Network)?;
-    
-    // Note: GitHub API returns git blob SHA (includes header), not raw file SHA1
-    // So checksum verification would fail. We skip it since HTTPS ensures integrity.
-    
-    // Write to file
-    let mut file = fs::File::create(&output_path).await?;
-    file.write_all(&content).await?;
+
+    let received = Validators::from_headers(response.headers());
+    let total = response.content_length();
+    let part = part_path(output_path);
+    let mut file = fs::File::create(&part).await?;
+    // One byte past the sample tells `validate_hash_list` there is more
+    let mut head = Vec::with_capacity(VALIDATE_SAMPLE_LEN + 1);
+    let (mut downloaded, mut reported) = (0u64, 0u64);
+    progress(0, total);
+
+    let streamed: Result<()> = async {
+        while let Some(chunk) = cancellable(async { response.chunk().await.map_err(Error::Network) }, cancel).await? {
+            let wanted = (VALIDATE_SAMPLE_LEN + 1).saturating_sub(head.len()).min(chunk.len());
+            head.extend_from_slice(&chunk[..wanted]);
+            file.write_all(&chunk).await?;
+            downloaded += chunk.len() as u64;
+            if downloaded - reported >= PROGRESS_STEP {
+                reported = downloaded;
+                progress(downloaded, total);
+            }
+        }
+        file.flush().await?;
+        Ok(())
+    }
+    .await;
+    drop(file);
+    let checked = streamed.map(|()| check_download(&head, downloaded, total));
+    if !matches!(checked, Ok(Ok(()))) {
+        let _ = fs::remove_file(&part).await;
+    }
+    if let Err(reason) = checked? {
+        return Ok(Fetched::Rejected(reason));
+    }
+    if reported != downloaded {
+        progress(downloaded, total);
+    }
+    fs::rename(&part, output_path).await?;
+    Ok(Fetched::Downloaded(received))
+}
+
+/// Check a finished download before it replaces the previous file: it has
+/// `total` bytes when the server sent a Content-Length, and `head`, its
+/// start, passes `validate_hash_list`. Returns why it doesn't.
+fn check_download(head: &[u8], downloaded: u64, total: Option<u64>) -> std::result::Result<(), String> {
+    if downloaded == 0 {
+        return Err("is empty".to_string());
+    }
+    if let Some(total) = total.filter(|total| *total != downloaded) {
+        return Err(format!("is truncated: {} of {} bytes", downloaded, total));
+    }
+    validate_hash_list(head).map_err(|reason| format!("is not a hash list: {}", reason))
+}
+
+/// Stored validators from the sidecar in `output_dir`; none if it is
+/// missing or unreadable, which only costs one full download per file
+async fn load_validators(output_dir: &Path) -> ValidatorMap {
+    let path = output_dir.join(VALIDATORS_FILE);
+    match fs::read(&path).await {
+        Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable {}: {}", path.display(), e);
+            ValidatorMap::new()
+        }),
+        Err(_) => ValidatorMap::new(),
+    }
+}
+
+async fn save_validators(output_dir: &Path, validators: &ValidatorMap) -> Result<()> {
+    let data = serde_json::to_vec_pretty(validators).map_err(|e| Error::Hash(e.to_string()))?;
+    write_via_part(&output_dir.join(VALIDATORS_FILE), &data).await
+}
+
+/// Check that `content` starts like a hash list ("<hex hash> <path>" per
+/// line), so an error page or a bad mirror never replaces a good file
+fn validate_hash_list(content: &[u8]) -> std::result::Result<(), String> {
+    let sample = String::from_utf8_lossy(&content[..content.len().min(VALIDATE_SAMPLE_LEN)]);
+    // A cut-off sample may end mid-line, so only complete lines are checked
+    let sample = if content.len() > VALIDATE_SAMPLE_LEN {
+        sample.rsplit_once('\n').map_or("", |(head, _)| head)
+    } else {
+        &sample
+    };
+
+    let lines: Vec<&str> = sample
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .take(VALIDATE_LINES)
+        .collect();
+    if lines.is_empty() {
+        return Err("it has no lines".to_string());
+    }
+    for (index, line) in lines.iter().enumerate() {
+        let valid = line.split_once(' ').is_some_and(|(hash, path)| {
+            let hex = hash.strip_prefix("0x").unwrap_or(hash);
+            (1..=16).contains(&hex.len()) && hex.bytes().all(|b| b.is_ascii_hexdigit()) && !path.trim().is_empty()
+        });
+        if !valid {
+            return Err(format!("line {} is '{}'", index + 1, line.chars().take(80).collect::<String>()));
+        }
+    }
+    Ok(())
+}
+
+/// Write `content` to `<path>.part` and rename it into place, so readers
+/// (including the hash directory watcher) never see a half-written list
+async fn write_via_part(path: &Path, content: &[u8]) -> Result<()> {
+    let part = part_path(path);
+    let mut file = fs::File::create(&part).await?;
+    file.write_all(content).await?;
     file.flush().await?;
-    
-    Ok(true)
+    drop(file);
+    fs::rename(&part, path).await?;
+    Ok(())
+}
+
+/// `<path>.part`, where a file is written before it replaces `path`
+fn part_path(path: &Path) -> std::path::PathBuf {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    std::path::PathBuf::from(part)
 }
 
 /// Checks if a file needs to be updated based on age
@@ -266,7 +668,7 @@ async fn merge_split_files(output_dir: &Path) -> Result<()> {
     merged_content.push_str(&content1);
 
     // Write merged file
-    fs::write(&merged_path, merged_content).await?;
+    write_via_part(&merged_path, merged_content.as_bytes()).await?;
     
     // We KEEP the split files so we can check their age next time
     // fs::remove_file(&file0_path).await?;
@@ -285,7 +687,11 @@ mod tests {
         let stats = DownloadStats {
             downloaded: 5,
             skipped: 2,
+            not_modified: 1,
+            age_skipped: 1,
             errors: 1,
+            rejected: Vec::new(),
+            cancelled: false,
         };
         
         assert_eq!(stats.downloaded, 5);
@@ -308,6 +714,120 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_validate_hash_list() {
+        assert!(validate_hash_list(b"0a1b2c3d assets/characters/ahri/ahri.dds\r\n1234 data/a.bin\n").is_ok());
+        assert!(validate_hash_list(b"0x0000000000000001 some/path\n").is_ok());
+        assert!(validate_hash_list(b"").is_err());
+        assert!(validate_hash_list(b"<!DOCTYPE html>\n<html></html>").is_err());
+        assert!(validate_hash_list(b"0a1b2c3d\tassets/a.dds\n").is_err());
+
+        let list = b"0a1b2c3d assets/a.dds\n";
+        assert!(check_download(list, 22, Some(22)).is_ok());
+        assert!(check_download(list, 22, None).is_ok());
+        assert_eq!(check_download(list, 22, Some(4096)).unwrap_err(), "is truncated: 22 of 4096 bytes");
+        assert!(check_download(b"", 0, Some(0)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validators_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(load_validators(temp_dir.path()).await.is_empty());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"abc123\"".parse().unwrap());
+        let received = Validators::from_headers(&headers);
+        assert_eq!(received.etag.as_deref(), Some("\"abc123\""));
+        assert!(received.last_modified.is_none());
+        assert!(Validators::from_headers(&HeaderMap::new()).is_empty());
+
+        let validators = ValidatorMap::from([("hashes.game.txt.0".to_string(), received)]);
+        save_validators(temp_dir.path(), &validators).await.unwrap();
+        assert_eq!(load_validators(temp_dir.path()).await, validators);
+
+        fs::write(temp_dir.path().join(VALIDATORS_FILE), "not json").await.unwrap();
+        assert!(load_validators(temp_dir.path()).await.is_empty());
+    }
+
+    /// Serve a hash list on every path but `/missing` and `/html`, one thread
+    /// per connection
+    fn serve_hash_lists() -> String {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request = String::new();
+                    reader.read_line(&mut request).unwrap();
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        line.clear();
+                    }
+                    let (status, body) = if request.contains("/missing") {
+                        ("404 Not Found", String::new())
+                    } else if request.contains("/html") {
+                        ("200 OK", "<!DOCTYPE html>\n<html></html>\n".to_string())
+                    } else {
+                        ("200 OK", "0a1b2c3d assets/a.dds\n".repeat(100))
+                    };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_sources_download_concurrently() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = serve_hash_lists();
+        let source = |file: &str, path: &str| HashSource {
+            name: file.to_string(),
+            url: format!("{}/{}", base, path),
+            file: file.to_string(),
+        };
+        let sources = [
+            source("hashes.game.txt", "game"),
+            source("hashes.lcu.txt", "missing"),
+            source("hashes.binentries.txt", "bin"),
+            source("hashes.binfields.txt", "fields"),
+            source("hashes.bintypes.txt", "html"),
+        ];
+        std::fs::write(temp_dir.path().join("hashes.bintypes.txt"), "00000010 Old\n").unwrap();
+
+        let stats = download_hashes(temp_dir.path(), true, &sources, &HashCategory::ALL, None, |_| {}).await.unwrap();
+        assert_eq!((stats.downloaded, stats.errors, stats.cancelled), (3, 2, false));
+        assert_eq!(stats.rejected.len(), 1);
+        assert!(stats.rejected[0].starts_with("hashes.bintypes.txt: is not a hash list"));
+        let kept = std::fs::read_to_string(temp_dir.path().join("hashes.bintypes.txt")).unwrap();
+        assert_eq!(kept, "00000010 Old\n");
+        assert!(temp_dir.path().join("hashes.binfields.txt").exists());
+        assert!(!temp_dir.path().join("hashes.lcu.txt").exists());
+        assert!(!part_path(&temp_dir.path().join("hashes.lcu.txt")).exists());
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_stops_a_stalled_future() {
+        let token = CancellationToken::new();
+        assert_eq!(cancellable(async { Ok(1) }, Some(&token)).await.unwrap(), 1);
+
+        let trip = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            trip.cancel();
+        });
+        let stalled = cancellable(std::future::pending::<Result<()>>(), Some(&token)).await;
+        assert!(matches!(stalled, Err(Error::Cancelled)));
+    }
+
     #[tokio::test]
     async fn test_needs_update_nonexistent_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -363,6 +883,7 @@ mod tests {
         
         // Check merged file exists
         assert!(dir.join("hashes.game.txt").exists());
+        assert!(!dir.join("hashes.game.txt.part").exists());
         
         // Check split files are KEPT (to handle caching)
         assert!(dir.join("hashes.game.txt.0").exists());
@@ -0,0 +1,220 @@
+//! Guessing the paths of unresolved WAD hashes
+//!
+//! Assets in one WAD follow a few naming patterns: textures come in glow,
+//! mask and `2x_` variants, skin folders differ only by their number, and
+//! the same file names repeat across directories. Candidates are built from
+//! the resolved paths of the WAD with those patterns, hashed, and compared
+//! with the unresolved hashes. Matches are only reported; the caller decides
+//! which ones go into the user hash list.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::validation::engine::compute_path_hash;
+
+/// Suffixes texture and mesh variants add to the file stem
+const SUFFIXES: &[&str] = &[
+    "_glow", "_mult", "_alpha", "_color", "_mask", "_erode", "_distort", "_noise", "_ramp", "_add", "_blend",
+    "_trail", "_tx_cm", "_tx_cm_mask",
+];
+
+/// Resolution prefixes of texture file names
+const PREFIXES: &[&str] = &["2x_", "4x_"];
+
+/// Extensions one texture is shipped under
+const TEXTURE_EXTENSIONS: &[&str] = &["tex", "dds"];
+
+/// Highest skin number substituted into `skinNN`
+const MAX_SKIN: u32 = 99;
+
+/// Directory × file name pairs are skipped above this many candidates
+const MAX_DIRECTORY_CANDIDATES: usize = 2_000_000;
+
+/// How a guessed path was built
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuessRule {
+    /// A variant suffix added to or removed from the file stem
+    Suffix,
+    /// A `2x_`/`4x_` prefix added to or removed from the file name
+    Prefix,
+    /// Another number in a `skinNN` segment
+    SkinNumber,
+    /// The `.tex`/`.dds` twin of a texture
+    Extension,
+    /// A known file name in another known directory
+    Directory,
+}
+
+/// An unresolved hash whose path was guessed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashGuess {
+    /// Path hash as a 16-char lowercase hex string
+    pub hash: String,
+    pub path: String,
+    pub rule: GuessRule,
+    /// The resolved path the guess was built from
+    pub from: String,
+}
+
+/// Result of `guess_unknown_hashes`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GuessReport {
+    /// Matches, sorted by hash
+    pub guesses: Vec<HashGuess>,
+    /// Number of candidate paths hashed
+    pub candidates: usize,
+    /// Hashes still unresolved after guessing
+    pub unresolved: usize,
+}
+
+/// Guess paths for `unknown` from the resolved `known` paths of the same WAD
+pub fn guess_unknown_hashes<S: AsRef<str>>(unknown: &[u64], known: &[S]) -> GuessReport {
+    let mut remaining: HashSet<u64> = unknown.iter().copied().collect();
+    let mut found: HashMap<u64, HashGuess> = HashMap::new();
+    let mut candidates = 0;
+
+    let mut check = |path: String, rule: GuessRule, from: &str| {
+        candidates += 1;
+        let hash = compute_path_hash(&path);
+        if remaining.remove(&hash) {
+            found.insert(hash, HashGuess { hash: format!("{:016x}", hash), path, rule, from: from.to_string() });
+        }
+    };
+
+    let known: Vec<String> = known.iter().map(|p| p.as_ref().to_lowercase().replace('\\', "/")).collect();
+    for path in &known {
+        variants(path, &mut |candidate, rule| check(candidate, rule, path));
+    }
+
+    let directories: BTreeSet<&str> = known.iter().map(|p| split_dir(p).0).collect();
+    let names: BTreeMap<&str, &str> = known.iter().map(|p| (split_dir(p).1, p.as_str())).collect();
+    if directories.len().saturating_mul(names.len()) <= MAX_DIRECTORY_CANDIDATES {
+        for dir in &directories {
+            for (name, from) in &names {
+                check(join(dir, name), GuessRule::Directory, from);
+            }
+        }
+    } else {
+        tracing::debug!(
+            "Skipping directory guesses: {} directories × {} names",
+            directories.len(),
+            names.len()
+        );
+    }
+
+    let mut guesses: Vec<HashGuess> = found.into_values().collect();
+    guesses.sort_by(|a, b| a.hash.cmp(&b.hash));
+    GuessReport { unresolved: remaining.len(), guesses, candidates }
+}
+
+/// Candidates derived from a single path by the per-path rules
+fn variants(path: &str, emit: &mut impl FnMut(String, GuessRule)) {
+    let (dir, name) = split_dir(path);
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) => (stem, Some(ext)),
+        None => (name, None),
+    };
+    let with_ext = |stem: &str| match ext {
+        Some(ext) => join(dir, &format!("{}.{}", stem, ext)),
+        None => join(dir, stem),
+    };
+
+    for suffix in SUFFIXES {
+        match stem.strip_suffix(suffix) {
+            Some(base) if !base.is_empty() => emit(with_ext(base), GuessRule::Suffix),
+            _ => emit(with_ext(&format!("{}{}", stem, suffix)), GuessRule::Suffix),
+        }
+    }
+
+    for prefix in PREFIXES {
+        match name.strip_prefix(prefix) {
+            Some(base) if !base.is_empty() => emit(join(dir, base), GuessRule::Prefix),
+            _ => emit(join(dir, &format!("{}{}", prefix, name)), GuessRule::Prefix),
+        }
+    }
+
+    if let Some(ext) = ext.filter(|ext| TEXTURE_EXTENSIONS.contains(ext)) {
+        for other in TEXTURE_EXTENSIONS.iter().filter(|other| **other != ext) {
+            emit(join(dir, &format!("{}.{}", stem, other)), GuessRule::Extension);
+        }
+    }
+
+    let segments = skin_numbers(path);
+    if let Some(&(_, _, current)) = segments.first() {
+        for number in (0..=MAX_SKIN).filter(|n| *n != current) {
+            emit(with_skin_number(path, &segments, number), GuessRule::SkinNumber);
+        }
+    }
+}
+
+/// `(start, end, number)` of the digits after each `skin` in `path`
+fn skin_numbers(path: &str) -> Vec<(usize, usize, u32)> {
+    let mut segments = Vec::new();
+    for (index, _) in path.match_indices("skin") {
+        let start = index + "skin".len();
+        let digits = path[start..].bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            continue;
+        }
+        if let Ok(number) = path[start..start + digits].parse() {
+            segments.push((start, start + digits, number));
+        }
+    }
+    segments
+}
+
+/// `path` with every `skinNN` set to `number`, zero-padded to two digits
+/// like the game's skin folders
+fn with_skin_number(path: &str, segments: &[(usize, usize, u32)], number: u32) -> String {
+    let mut out = String::with_capacity(path.len() + 2);
+    let mut last = 0;
+    for &(start, end, _) in segments {
+        out.push_str(&path[last..start]);
+        out.push_str(&format!("{:02}", number));
+        last = end;
+    }
+    out.push_str(&path[last..]);
+    out
+}
+
+fn split_dir(path: &str) -> (&str, &str) {
+    path.rsplit_once('/').unwrap_or(("", path))
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guesses_match_only_unknown_hashes() {
+        let known = ["ASSETS/Characters/Ahri/Skins/Skin01/Particles/Ahri_Skin01_Glow.tex", "data/menu/en_us/main.stringtable"];
+        let guessed = [
+            "assets/characters/ahri/skins/skin02/particles/ahri_skin02_glow.tex",
+            "assets/characters/ahri/skins/skin01/particles/ahri_skin01.tex",
+            "assets/characters/ahri/skins/skin01/particles/2x_ahri_skin01_glow.tex",
+            "data/menu/en_us/ahri_skin01_glow.tex",
+        ];
+        let mut unknown: Vec<u64> = guessed.iter().map(|p| compute_path_hash(p)).collect();
+        unknown.push(0x1234);
+
+        let report = guess_unknown_hashes(&unknown, &known);
+        assert_eq!(report.unresolved, 1);
+        assert!(report.candidates > 100);
+        let rule = |path: &str| report.guesses.iter().find(|g| g.path == path).map(|g| g.rule);
+        assert_eq!(rule(guessed[0]), Some(GuessRule::SkinNumber));
+        assert_eq!(rule(guessed[1]), Some(GuessRule::Suffix));
+        assert_eq!(rule(guessed[2]), Some(GuessRule::Prefix));
+        assert_eq!(rule(guessed[3]), Some(GuessRule::Directory));
+        assert_eq!(report.guesses[0].hash.len(), 16);
+    }
+}
@@ -1,36 +1,129 @@
 use std::fs;
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Instant;
+use crate::core::parallel;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::error::{Error, Result};
 
+/// Hash list in the hash directory holding entries users imported; it
+/// wins over every other list for hashes they share
+pub const USER_HASH_FILE: &str = "hashes.user.txt";
+
+/// Whether `path` is a hash list the table loads (`.txt`). In-progress
+/// downloads (`.part`) and the split `hashes.game.txt.N` halves don't count.
+pub fn is_hash_file(path: &Path) -> bool {
+    path.extension().and_then(|s| s.to_str()) == Some("txt")
+}
+
+fn is_user_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|n| n == USER_HASH_FILE)
+}
+
+/// A full load reports progress each time this many more entries are parsed
+pub const PROGRESS_INTERVAL: usize = 250_000;
+
+/// Entries a file parses between updates of the shared progress counters
+const PROGRESS_BATCH: usize = 16_384;
+
+/// Line numbers of malformed lines kept (and logged) per file, at most
+const REJECTED_LINES_LIMIT: usize = 5;
+
+/// Entries of one list, its malformed line count and the first of their numbers
+type ParsedFile = (Vec<(u64, String)>, usize, Vec<usize>);
+
+/// How far a full load is, across all lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashLoadProgress {
+    pub entries: usize,
+    pub bytes_read: u64,
+    /// Size of all lists being loaded
+    pub total_bytes: u64,
+}
+
+/// Progress shared by the lists parsing in parallel
+struct Progress<'a> {
+    entries: AtomicUsize,
+    bytes_read: AtomicU64,
+    total_bytes: u64,
+    report: &'a (dyn Fn(HashLoadProgress) + Sync),
+}
+
+impl Progress<'_> {
+    /// Count a batch, reporting when the total crosses a `PROGRESS_INTERVAL`
+    fn add(&self, entries: usize, bytes: u64) {
+        let before = self.entries.fetch_add(entries, Ordering::Relaxed);
+        let bytes_read = self.bytes_read.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if before / PROGRESS_INTERVAL != (before + entries) / PROGRESS_INTERVAL {
+            (self.report)(HashLoadProgress { entries: before + entries, bytes_read, total_bytes: self.total_bytes });
+        }
+    }
+}
+
+/// What loading one hash list found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashFileStats {
+    /// File name in the hash directory
+    pub name: String,
+    /// Entries parsed, including ones another list overrides
+    pub entries: usize,
+    /// Lines that aren't "hash path", skipped
+    pub rejected: usize,
+    /// 1-based numbers of the first rejected lines
+    pub rejected_lines: Vec<usize>,
+    /// Time spent reading and parsing it
+    pub duration_ms: u64,
+    /// Why the file couldn't be read; it then contributes nothing
+    pub error: Option<String>,
+}
+
 /// Compact hash-to-path lookup table.
 ///
 /// Instead of `HashMap<u64, String>` (one heap allocation per path + bucket
 /// overhead), all path strings are packed into a single contiguous byte arena.
 /// Keys are stored in a sorted `Vec<u64>` so `binary_search` gives the index
 /// into the parallel `values` vec holding `(byte_offset, byte_length)` into
-/// the arena.
+/// the arena. Each entry also records the list it came from, so
+/// `with_files` can replace one list's entries without reparsing the others.
 ///
 /// Memory vs HashMap at ~4 M entries / ~50-char avg path:
 ///   HashMap  ≈ 420 MB (128 MB buckets + 96 MB String headers + ~200 MB data)
-///   This     ≈ 264 MB ( 32 MB keys   + 32 MB offsets        + ~200 MB data)
+///   This     ≈ 272 MB ( 32 MB keys   + 32 MB offsets + 8 MB sources + ~200 MB data)
 pub struct Hashtable {
     /// Sorted hash keys (index aligns with `values`).
     keys:   Vec<u64>,
     /// (byte_offset, byte_length) into `arena` for each key.
     values: Vec<(u32, u32)>,
+    /// Index into `lists` of the list each key came from.
+    sources: Vec<u16>,
     /// All path strings packed as UTF-8 bytes.
     arena:  Vec<u8>,
+    /// Every list the entries were loaded from, in load order.
+    lists:  Vec<PathBuf>,
+    /// Per-list load results, sorted by name.
+    files:  Vec<HashFileStats>,
 }
 
 impl Hashtable {
     /// Empty table used as a no-op fallback.
     pub fn empty() -> Self {
-        Self { keys: Vec::new(), values: Vec::new(), arena: Vec::new() }
+        Self::build(std::iter::empty(), 0, 0)
     }
 
     /// Load all `.txt` hash files from `dir` in parallel and build the table.
+    #[cfg(test)]
     pub fn from_directory(dir: impl AsRef<Path>) -> Result<Self> {
+        Self::from_directory_with_progress(dir, |_| {})
+    }
+
+    /// `from_directory`, calling `progress` every `PROGRESS_INTERVAL` entries
+    /// while the lists are parsed.
+    pub fn from_directory_with_progress(
+        dir: impl AsRef<Path>,
+        progress: impl Fn(HashLoadProgress) + Sync,
+    ) -> Result<Self> {
         let dir = dir.as_ref();
 
         if !dir.is_dir() {
@@ -42,69 +135,276 @@ impl Hashtable {
         let txt_files: Vec<PathBuf> = fs::read_dir(dir)?
             .filter_map(|e| e.ok())
             .map(|e| e.path())
-            .filter(|p| p.is_file() && p.extension().and_then(|s| s.to_str()) == Some("txt"))
+            .filter(|p| p.is_file() && is_hash_file(p))
             .collect();
 
         tracing::debug!("Loading {} hash files in parallel", txt_files.len());
 
-        // Parse each file in parallel into flat Vec<(hash, path)>.
-        let partial: Vec<Vec<(u64, String)>> = txt_files
-            .par_iter()
-            .filter_map(|path| match Self::parse_file(path) {
-                Ok(v)  => { tracing::trace!("Loaded {} hashes from {:?}", v.len(), path.file_name()); Some(v) }
-                Err(e) => { tracing::warn!("Skipped {:?}: {}", path, e); None }
+        let (flat, mut files) = Self::parse_files(&txt_files, &progress);
+        let arena_bytes: usize = flat.iter().map(|(_, s, _)| s.len()).sum();
+        let entries = flat.iter().map(|(k, s, index)| (*k, s.as_str(), *index as u16));
+        let mut table = Self::build(entries, flat.len(), arena_bytes);
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        table.files = files;
+        table.lists = txt_files;
+
+        tracing::info!(
+            "Hashtable loaded: {} entries, {} MB on the heap (~{} MB as a HashMap)",
+            table.len(),
+            table.heap_size() / (1024 * 1024),
+            table.hashmap_estimate() / (1024 * 1024)
+        );
+
+        Ok(table)
+    }
+
+    /// A copy of this table with the entries of `files` re-read, and the
+    /// number of hashes that added, removed or changed a path.
+    ///
+    /// Only the reparsed lists' entries are replaced, so a small list reloads
+    /// without touching the big ones. Their entries win over other lists'
+    /// for the same hash, except over the user list's. A hash another list
+    /// also had drops out when the list it was taken from removes it, until
+    /// the next full load.
+    pub fn with_files(&self, files: &[PathBuf]) -> (Self, usize) {
+        let (added, stats) = Self::parse_files(files, &|_| {});
+
+        let mut lists = self.lists.clone();
+        let ids: Vec<u16> = files
+            .iter()
+            .map(|file| match lists.iter().position(|l| l == file) {
+                Some(id) => id as u16,
+                None => {
+                    lists.push(file.clone());
+                    (lists.len() - 1) as u16
+                }
             })
             .collect();
+        let is_user: Vec<bool> = lists.iter().map(|l| is_user_file(l)).collect();
+        let reparsed = |source: u16| ids.contains(&source);
+
+        // Both sides are sorted, so a single merge pass keeps the result sorted
+        let mut merged: Vec<(u64, &str, u16)> = Vec::with_capacity(self.keys.len() + added.len());
+        let mut changed = 0;
+        let mut existing = self.entries().peekable();
+        for (hash, path, index) in &added {
+            let source = ids[*index];
+            while let Some(entry) = existing.next_if(|(k, ..)| k < hash) {
+                if reparsed(entry.2) {
+                    changed += 1;
+                } else {
+                    merged.push(entry);
+                }
+            }
+            match existing.next_if(|(k, ..)| k == hash) {
+                Some(entry) if !reparsed(entry.2) && is_user[entry.2 as usize] && !is_user[source as usize] => {
+                    merged.push(entry);
+                }
+                Some(entry) => {
+                    changed += usize::from(entry.1 != path);
+                    merged.push((*hash, path.as_str(), source));
+                }
+                None => {
+                    changed += 1;
+                    merged.push((*hash, path.as_str(), source));
+                }
+            }
+        }
+        for entry in existing {
+            if reparsed(entry.2) {
+                changed += 1;
+            } else {
+                merged.push(entry);
+            }
+        }
+
+        let arena_bytes = merged.iter().map(|(_, s, _)| s.len()).sum();
+        let mut table = Self::build(merged.iter().copied(), merged.len(), arena_bytes);
+        table.lists = lists;
+
+        // Reparsed lists replace their old stats; deleted ones drop out
+        table.files = self.files.clone();
+        for (file, stat) in files.iter().zip(stats) {
+            table.files.retain(|f| f.name != stat.name);
+            if file.is_file() {
+                table.files.push(stat);
+            }
+        }
+        table.files.sort_by(|a, b| a.name.cmp(&b.name));
+        (table, changed)
+    }
+
+    /// Hashes whose path differs between this table and `other`: in one and
+    /// not the other, or with another path.
+    pub fn count_changes(&self, other: &Hashtable) -> usize {
+        let mut changed = 0;
+        let mut theirs = other.entries().peekable();
+        for (hash, path, _) in self.entries() {
+            while theirs.next_if(|(k, ..)| *k < hash).is_some() {
+                changed += 1;
+            }
+            match theirs.next_if(|(k, ..)| *k == hash) {
+                Some((_, other_path, _)) => changed += usize::from(other_path != path),
+                None => changed += 1,
+            }
+        }
+        changed + theirs.count()
+    }
+
+    /// Parse `files` in parallel into one list sorted and deduplicated by
+    /// hash, each entry with the index of its file in `files`. Entries from
+    /// the user list win over the others. Stats come back in the order of
+    /// `files`; unreadable files are skipped with a warning.
+    fn parse_files(
+        files: &[PathBuf],
+        report: &(dyn Fn(HashLoadProgress) + Sync),
+    ) -> (Vec<(u64, String, usize)>, Vec<HashFileStats>) {
+        let progress = Progress {
+            entries: AtomicUsize::new(0),
+            bytes_read: AtomicU64::new(0),
+            total_bytes: files.iter().filter_map(|f| fs::metadata(f).ok()).map(|m| m.len()).sum(),
+            report,
+        };
+        let partial: Vec<(Vec<(u64, String)>, HashFileStats)> = parallel::install(|| {
+            files
+                .par_iter()
+                .map(|path| {
+                    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    let started = Instant::now();
+                    match Self::parse_file(path, &progress) {
+                        Ok((v, rejected, rejected_lines)) => {
+                            tracing::trace!("Loaded {} hashes from {}", v.len(), name);
+                            if rejected > 0 {
+                                tracing::warn!(
+                                    "Skipped {} malformed lines in {} (lines {:?}{})",
+                                    rejected,
+                                    name,
+                                    rejected_lines,
+                                    if rejected > rejected_lines.len() { ", …" } else { "" }
+                                );
+                            }
+                            let stats = HashFileStats {
+                                name,
+                                entries: v.len(),
+                                rejected,
+                                rejected_lines,
+                                duration_ms: started.elapsed().as_millis() as u64,
+                                error: None,
+                            };
+                            (v, stats)
+                        }
+                        Err(e) => {
+                            tracing::warn!("Skipped {:?}: {}", path, e);
+                            let stats = HashFileStats {
+                                name,
+                                entries: 0,
+                                rejected: 0,
+                                rejected_lines: Vec::new(),
+                                duration_ms: started.elapsed().as_millis() as u64,
+                                error: Some(e.to_string()),
+                            };
+                            (Vec::new(), stats)
+                        }
+                    }
+                })
+                .collect()
+        });
 
-        // Merge, sort by hash, deduplicate.
-        let total: usize = partial.iter().map(|v| v.len()).sum();
-        let mut flat: Vec<(u64, String)> = Vec::with_capacity(total);
-        for v in partial { flat.extend(v); }
-        flat.sort_unstable_by_key(|(k, _)| *k);
-        flat.dedup_by_key(|(k, _)| *k);
+        let total: usize = partial.iter().map(|(v, _)| v.len()).sum();
+        let mut flat: Vec<(u64, String, usize)> = Vec::with_capacity(total);
+        let mut stats = Vec::with_capacity(partial.len());
+        for (index, (v, s)) in partial.into_iter().enumerate() {
+            flat.extend(v.into_iter().map(|(hash, path)| (hash, path, index)));
+            stats.push(s);
+        }
+        let is_user: Vec<bool> = files.iter().map(|f| is_user_file(f)).collect();
+        // The user list's entry sorts first among equal hashes and survives the dedup
+        flat.sort_unstable_by_key(|(k, _, index)| (*k, !is_user[*index]));
+        flat.dedup_by_key(|(k, ..)| *k);
+        (flat, stats)
+    }
 
-        // Build sorted keys, offset/length index, and arena in one pass.
-        let arena_bytes: usize = flat.iter().map(|(_, s)| s.len()).sum();
-        let mut keys:   Vec<u64>      = Vec::with_capacity(flat.len());
-        let mut values: Vec<(u32,u32)>= Vec::with_capacity(flat.len());
-        let mut arena:  Vec<u8>       = Vec::with_capacity(arena_bytes);
+    /// Build sorted keys, offset/length index, and arena in one pass.
+    /// `entries` must be sorted by hash without duplicates.
+    fn build<'a>(entries: impl Iterator<Item = (u64, &'a str, u16)>, len: usize, arena_bytes: usize) -> Self {
+        let mut keys:    Vec<u64>      = Vec::with_capacity(len);
+        let mut values:  Vec<(u32,u32)>= Vec::with_capacity(len);
+        let mut sources: Vec<u16>      = Vec::with_capacity(len);
+        let mut arena:   Vec<u8>       = Vec::with_capacity(arena_bytes);
 
-        for (hash, path) in &flat {
+        for (hash, path, source) in entries {
             values.push((arena.len() as u32, path.len() as u32));
             arena.extend_from_slice(path.as_bytes());
-            keys.push(*hash);
+            keys.push(hash);
+            sources.push(source);
         }
 
-        tracing::info!("Hashtable loaded: {} entries, {} KB arena", keys.len(), arena.len() / 1024);
-
-        Ok(Self { keys, values, arena })
+        Self { keys, values, sources, arena, lists: Vec::new(), files: Vec::new() }
     }
 
-    fn parse_file(path: &Path) -> Result<Vec<(u64, String)>> {
-        let content = fs::read_to_string(path)?;
-        let mut out = Vec::with_capacity(content.len() / 50);
+    fn entries(&self) -> impl Iterator<Item = (u64, &str, u16)> + '_ {
+        self.keys.iter().zip(&self.values).zip(&self.sources).map(|((hash, &(off, len)), &source)| {
+            let bytes = &self.arena[off as usize..(off + len) as usize];
+            // SAFETY: only valid UTF-8 strings are pushed into the arena.
+            (*hash, unsafe { std::str::from_utf8_unchecked(bytes) }, source)
+        })
+    }
 
-        for (line_idx, line) in content.lines().enumerate() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') { continue; }
+    /// Entries of one list, read line by line, with the number of malformed
+    /// lines and the first few of their line numbers. A line that isn't
+    /// UTF-8 is malformed like any other rather than failing the file.
+    fn parse_file(path: &Path, progress: &Progress) -> Result<ParsedFile> {
+        let file = fs::File::open(path).map_err(|e| Error::io_with_path(e, path))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let mut reader = BufReader::new(file);
+        let mut out = Vec::with_capacity(size as usize / 50);
+        let mut rejected = 0;
+        let mut rejected_lines = Vec::new();
+        let mut buf = Vec::new();
+        let (mut line_number, mut batch_start, mut batch_bytes) = (0, 0, 0u64);
 
-            let Some((hash_str, path_str)) = line.split_once(' ') else { continue; };
+        loop {
+            buf.clear();
+            let read = reader.read_until(b'\n', &mut buf).map_err(|e| Error::io_with_path(e, path))?;
+            if read == 0 { break; }
+            line_number += 1;
+            batch_bytes += read as u64;
+            if out.len() - batch_start >= PROGRESS_BATCH {
+                progress.add(out.len() - batch_start, batch_bytes);
+                (batch_start, batch_bytes) = (out.len(), 0);
+            }
 
-            let hash = if let Some(hex) = hash_str.strip_prefix("0x").or_else(|| hash_str.strip_prefix("0X")) {
-                u64::from_str_radix(hex, 16)
-            } else if hash_str.bytes().all(|b| b.is_ascii_hexdigit()) {
-                u64::from_str_radix(hash_str, 16)
-            } else {
-                hash_str.parse::<u64>()
-            }.map_err(|e| Error::parse_with_path(
-                line_idx + 1,
-                format!("Invalid hash value: '{}' - {}", hash_str, e),
-                path,
-            ))?;
-
-            out.push((hash, path_str.to_string()));
+            match std::str::from_utf8(&buf).ok().and_then(Self::parse_line) {
+                Some(Some(entry)) => out.push(entry),
+                Some(None) => {}
+                None => {
+                    rejected += 1;
+                    if rejected_lines.len() < REJECTED_LINES_LIMIT {
+                        rejected_lines.push(line_number);
+                    }
+                }
+            }
         }
-        Ok(out)
+        progress.add(out.len() - batch_start, batch_bytes);
+        Ok((out, rejected, rejected_lines))
+    }
+
+    /// One "hash path" line; `Some(None)` for blank lines and `#` comments,
+    /// `None` for malformed ones
+    fn parse_line(line: &str) -> Option<Option<(u64, String)>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { return Some(None); }
+
+        let (hash_str, path_str) = line.split_once(' ')?;
+
+        let hash = if let Some(hex) = hash_str.strip_prefix("0x").or_else(|| hash_str.strip_prefix("0X")) {
+            u64::from_str_radix(hex, 16)
+        } else if hash_str.bytes().all(|b| b.is_ascii_hexdigit()) {
+            u64::from_str_radix(hash_str, 16)
+        } else {
+            hash_str.parse::<u64>()
+        };
+        hash.ok().map(|hash| Some((hash, path_str.to_string())))
     }
 
     /// Resolve a hash to its path string.
@@ -123,8 +423,39 @@ impl Hashtable {
         }
     }
 
+    /// The path for `hash`, if the table knows it.
+    pub fn get(&self, hash: u64) -> Option<&str> {
+        let idx = self.keys.binary_search(&hash).ok()?;
+        let (off, len) = self.values[idx];
+        // SAFETY: only valid UTF-8 strings are pushed into the arena.
+        Some(unsafe { std::str::from_utf8_unchecked(&self.arena[off as usize..(off + len) as usize]) })
+    }
+
+    /// Heap bytes the table holds: keys, offsets, sources and the arena.
+    pub fn heap_size(&self) -> usize {
+        self.keys.capacity() * std::mem::size_of::<u64>()
+            + self.values.capacity() * std::mem::size_of::<(u32, u32)>()
+            + self.sources.capacity() * std::mem::size_of::<u16>()
+            + self.arena.capacity()
+    }
+
+    /// Rough heap bytes the same entries would take as `HashMap<u64, String>`,
+    /// for comparison in the load log: buckets at the 7/8 load factor with a
+    /// control byte each, plus one allocation per path rounded up to 16 bytes.
+    fn hashmap_estimate(&self) -> usize {
+        let buckets = (self.len() * 8 / 7).next_power_of_two();
+        let strings: usize = self.values.iter().map(|&(_, len)| (len as usize).div_ceil(16) * 16).sum();
+        buckets * (std::mem::size_of::<(u64, String)>() + 1) + strings
+    }
+
+    /// What loading each list found, sorted by file name.
+    pub fn file_stats(&self) -> &[HashFileStats] { &self.files }
+
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize { self.keys.len() }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool { self.keys.is_empty() }
 }
 
 // =============================================================================
@@ -197,15 +528,100 @@ mod tests {
     #[test]
     fn test_invalid_hash() {
         let tmp = TempDir::new().unwrap();
-        write(tmp.path(), "h.txt", "invalid_hash test.bin\n");
-        let r = Hashtable::from_directory(tmp.path());
-        assert!(r.is_err());
-        if let Err(Error::Parse { line, message, .. }) = r {
-            assert_eq!(line, 1);
-            assert!(message.contains("Invalid hash value"));
-        } else {
-            panic!("Expected Parse error");
-        }
+        write(tmp.path(), "h.txt", "invalid_hash test.bin\n0x1a2b3c4d t.bin\nno-path\n");
+        let ht = Hashtable::from_directory(tmp.path()).unwrap();
+        assert_eq!(ht.len(), 1);
+        let stats = &ht.file_stats()[0];
+        assert_eq!((stats.name.as_str(), stats.entries, stats.rejected), ("h.txt", 1, 2));
+        assert_eq!(stats.rejected_lines, [1, 3]);
+        assert_eq!(stats.error, None);
+    }
+
+    #[test]
+    fn test_load_reports_progress_and_tolerates_bad_lines() {
+        let tmp = TempDir::new().unwrap();
+        let mut content: Vec<u8> = (0..PROGRESS_INTERVAL as u64 + 10)
+            .flat_map(|i| format!("{:016x} p/{}\n", i, i).into_bytes())
+            .collect();
+        content.extend_from_slice(b"0x10 \xff\xfe.bin\nnot a hash line\n");
+        fs::write(tmp.path().join("big.txt"), &content).unwrap();
+
+        let reports = std::sync::Mutex::new(Vec::new());
+        let ht = Hashtable::from_directory_with_progress(tmp.path(), |p| reports.lock().unwrap().push(p)).unwrap();
+        assert_eq!(ht.len(), PROGRESS_INTERVAL + 10);
+        let reports = reports.into_inner().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].entries >= PROGRESS_INTERVAL);
+        assert_eq!(reports[0].total_bytes, content.len() as u64);
+        let stats = &ht.file_stats()[0];
+        assert_eq!(stats.rejected, 2);
+        assert_eq!(stats.rejected_lines, [PROGRESS_INTERVAL + 11, PROGRESS_INTERVAL + 12]);
+    }
+
+    #[test]
+    fn test_with_files_merges_changes() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "a.txt", "0x10 old/a.bin\n0x30 c.bin\n");
+        write(tmp.path(), "b.txt", "0x20 b.bin\n");
+        let ht = Hashtable::from_directory(tmp.path()).unwrap();
+
+        write(tmp.path(), "b.txt", "0x10 new/a.bin\n0x20 b.bin\n0x40 d.bin\n");
+        let (merged, changed) = ht.with_files(&[tmp.path().join("b.txt"), tmp.path().join("gone.txt")]);
+
+        assert_eq!(merged.len(), 4);
+        assert_eq!(changed, 2);
+        assert_eq!(ht.count_changes(&merged), 2);
+        assert_eq!(merged.resolve(0x10), "new/a.bin");
+        assert_eq!(merged.resolve(0x30), "c.bin");
+        assert_eq!(merged.resolve(0x40), "d.bin");
+        let entries: Vec<(&str, usize)> = merged.file_stats().iter().map(|f| (f.name.as_str(), f.entries)).collect();
+        assert_eq!(entries, [("a.txt", 2), ("b.txt", 3)]);
+        // The original snapshot is untouched
+        assert_eq!(ht.resolve(0x10), "old/a.bin");
+
+        // Only the reparsed list's entries are replaced, so removals apply too
+        write(tmp.path(), "b.txt", "0x10 new/a.bin\n0x20 b.bin\n");
+        let (merged, changed) = merged.with_files(&[tmp.path().join("b.txt")]);
+        assert_eq!((merged.len(), changed), (3, 1));
+        assert_eq!(merged.get(0x40), None);
+        assert_eq!(merged.resolve(0x30), "c.bin");
+        assert!(!is_hash_file(&tmp.path().join("hashes.game.txt.part")));
+    }
+
+    #[test]
+    fn test_user_file_wins() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "hashes.game.txt", "0x10 cdragon/a.bin
+0x20 b.bin
+");
+        write(tmp.path(), "z.txt", "0x10 other/a.bin
+");
+        write(tmp.path(), USER_HASH_FILE, "0x10 private/a.bin
+0x30 private/c.bin
+");
+        let ht = Hashtable::from_directory(tmp.path()).unwrap();
+        assert_eq!(ht.len(), 3);
+        assert_eq!(ht.resolve(0x10), "private/a.bin");
+
+        // A downloaded list changing doesn't take the hash back
+        write(tmp.path(), "hashes.game.txt", "0x10 cdragon/new.bin
+0x20 b.bin
+");
+        let (merged, changed) = ht.with_files(&[tmp.path().join("hashes.game.txt")]);
+        assert_eq!(merged.resolve(0x10), "private/a.bin");
+        assert_eq!(changed, 0);
+    }
+
+    #[test]
+    fn test_heap_size_below_hashmap() {
+        let tmp = TempDir::new().unwrap();
+        let lines: String = (0..1000u64)
+            .map(|i| format!("{:016x} assets/characters/ahri/skins/skin{:02}/particles/fx_{}.tex\n", i * 7919, i % 80, i))
+            .collect();
+        write(tmp.path(), "hashes.game.txt", &lines);
+        let ht = Hashtable::from_directory(tmp.path()).unwrap();
+        assert_eq!(ht.len(), 1000);
+        assert!(ht.heap_size() * 3 < ht.hashmap_estimate() * 2, "{} vs {}", ht.heap_size(), ht.hashmap_estimate());
     }
 
     #[test]
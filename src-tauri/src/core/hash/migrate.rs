@@ -0,0 +1,148 @@
+//! Moving hashes out of the fallback directory
+//!
+//! Without a RitoShark directory Flint keeps its hash lists in the app data
+//! directory. Once the RitoShark directory can be used, the fallback lists
+//! are moved there so Flint and the other RitoShark tools share one set:
+//! lists newer than the RitoShark copy replace it, the user list is merged,
+//! and the fallback directory is renamed so it is not picked up again.
+
+use super::hashtable::{is_hash_file, USER_HASH_FILE};
+use super::user::{import_hash_file, user_hash_count};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What the fallback directory is renamed to once migrated
+pub const MIGRATED_DIR_NAME: &str = "hashes.migrated";
+
+/// Result of `migrate_hash_dir`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HashDirMigration {
+    pub from: String,
+    pub to: String,
+    /// Lists copied over, being newer than the target's or missing there
+    pub copied: Vec<String>,
+    /// Lists left alone because the target's copy is as new or newer
+    pub kept: Vec<String>,
+    /// Fallback user entries merged into the target's user list
+    pub user_entries: usize,
+    /// Where the fallback directory was moved to
+    pub migrated_to: Option<String>,
+}
+
+/// The directory used when there is no RitoShark directory
+pub fn fallback_hash_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("hashes")
+}
+
+/// Whether `dir` holds any hash list worth migrating
+pub fn has_hash_lists(dir: &Path) -> bool {
+    fs::read_dir(dir).is_ok_and(|entries| entries.flatten().any(|entry| is_hash_file(&entry.path())))
+}
+
+/// Move the hash lists in `from` to `to` and rename `from` to
+/// `MIGRATED_DIR_NAME` next to it, replacing an earlier migrated copy.
+pub fn migrate_hash_dir(from: &Path, to: &Path) -> Result<HashDirMigration> {
+    if from == to {
+        return Err(Error::InvalidInput(format!("{} is already the hash directory", to.display())));
+    }
+    fs::create_dir_all(to).map_err(|e| Error::io_with_path(e, to))?;
+
+    let mut migration = HashDirMigration {
+        from: from.display().to_string(),
+        to: to.display().to_string(),
+        ..Default::default()
+    };
+    let mut lists: Vec<PathBuf> = fs::read_dir(from)
+        .map_err(|e| Error::io_with_path(e, from))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_hash_file(path))
+        .collect();
+    lists.sort();
+
+    for source in lists {
+        let Some(name) = source.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if name == USER_HASH_FILE {
+            if user_hash_count(from) > 0 {
+                let merged = import_hash_file(&source, to)?;
+                migration.user_entries = merged.added + merged.merged;
+                migration.copied.push(name);
+            }
+            continue;
+        }
+        let target = to.join(&name);
+        if is_newer(&source, &target) {
+            fs::copy(&source, &target).map_err(|e| Error::io_with_path(e, &target))?;
+            migration.copied.push(name);
+        } else {
+            migration.kept.push(name);
+        }
+    }
+
+    let migrated = from.with_file_name(MIGRATED_DIR_NAME);
+    if migrated.exists() {
+        fs::remove_dir_all(&migrated).map_err(|e| Error::io_with_path(e, &migrated))?;
+    }
+    fs::rename(from, &migrated).map_err(|e| Error::io_with_path(e, from))?;
+    migration.migrated_to = Some(migrated.display().to_string());
+
+    tracing::info!(
+        "Migrated hashes from {} to {}: {} copied, {} kept, {} user entries",
+        from.display(),
+        to.display(),
+        migration.copied.len(),
+        migration.kept.len(),
+        migration.user_entries
+    );
+    Ok(migration)
+}
+
+/// `source` was modified after `target`, or `target` doesn't exist
+fn is_newer(source: &Path, target: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(source), modified(target)) {
+        (_, None) => true,
+        (Some(source), Some(target)) => source > target,
+        (None, Some(_)) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_migrate_copies_newer_lists_and_merges_user_list() {
+        let temp = tempfile::tempdir().unwrap();
+        let from = fallback_hash_dir(temp.path());
+        let to = temp.path().join("RitoShark");
+        fs::create_dir_all(&from).unwrap();
+        fs::create_dir_all(&to).unwrap();
+
+        fs::write(to.join("hashes.game.txt"), "0000000000000001 old/path\n").unwrap();
+        fs::write(to.join("hashes.lcu.txt"), "0000000000000002 newer/path\n").unwrap();
+        fs::write(to.join(USER_HASH_FILE), "0000000000000003 kept/user\n").unwrap();
+        fs::write(from.join("hashes.game.txt"), "0000000000000001 new/path\n").unwrap();
+        fs::write(from.join("hashes.lcu.txt"), "0000000000000002 older/path\n").unwrap();
+        fs::write(from.join(USER_HASH_FILE), "0000000000000004 fallback/user\n").unwrap();
+        let past = SystemTime::now() - Duration::from_secs(3600);
+        fs::File::options().write(true).open(from.join("hashes.lcu.txt")).unwrap().set_modified(past).unwrap();
+        fs::File::options().write(true).open(to.join("hashes.game.txt")).unwrap().set_modified(past).unwrap();
+        assert!(has_hash_lists(&from));
+
+        let migration = migrate_hash_dir(&from, &to).unwrap();
+        assert_eq!(migration.copied, vec!["hashes.game.txt", USER_HASH_FILE]);
+        assert_eq!(migration.kept, vec!["hashes.lcu.txt"]);
+        assert_eq!(migration.user_entries, 1);
+        assert!(fs::read_to_string(to.join("hashes.game.txt")).unwrap().contains("new/path"));
+        assert!(fs::read_to_string(to.join("hashes.lcu.txt")).unwrap().contains("newer/path"));
+        assert_eq!(user_hash_count(&to), 2);
+        assert!(!from.exists());
+        assert!(temp.path().join(MIGRATED_DIR_NAME).join("hashes.game.txt").exists());
+    }
+}
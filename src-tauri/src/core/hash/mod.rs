@@ -1,6 +1,18 @@
 // Hash module exports
+pub mod category;
 pub mod downloader;
+pub mod guess;
 pub mod hashtable;
+pub mod migrate;
+pub mod user;
+pub mod version;
+pub mod watcher;
 
-pub use downloader::{download_hashes, get_ritoshark_hash_dir, DownloadStats};
-pub use hashtable::Hashtable;
+pub use category::HashCategory;
+pub use downloader::{download_hashes, get_ritoshark_hash_dir, DownloadStats, HashDownloadProgress, HashSource};
+pub use guess::{guess_unknown_hashes, GuessReport};
+pub use hashtable::{is_hash_file, HashLoadProgress, Hashtable, USER_HASH_FILE};
+pub use migrate::{fallback_hash_dir, has_hash_lists, migrate_hash_dir, HashDirMigration};
+pub use user::{add_hash_mapping, import_hash_file, user_hash_count, HashImportResult, HashMapping};
+pub use version::{is_newer_version, record_after_download, recorded_game_version};
+pub use watcher::{watch_hash_dir, HashDirWatcher};
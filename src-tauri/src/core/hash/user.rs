@@ -0,0 +1,216 @@
+//! Hash lists users maintain themselves
+//!
+//! Private lists for unreleased content never reach CommunityDragon, and a
+//! download would overwrite them if they were merged into its files.
+//! `import_hash_file` merges them into `hashes.user.txt` instead, which the
+//! table ranks above every other list, and `add_hash_mapping` records a
+//! single mapping there. Downloads only ever write the lists they fetch.
+
+use crate::core::hash::hashtable::USER_HASH_FILE;
+use crate::core::recolor::fnv1a;
+use crate::core::validation::engine::compute_path_hash;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Line numbers of malformed lines reported back, at most
+const REJECTED_LINES_LIMIT: usize = 50;
+
+/// Result of `import_hash_file`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HashImportResult {
+    /// Hashes that weren't in the user list yet
+    pub added: usize,
+    /// Hashes already in the user list; the imported path replaced the old one
+    pub merged: usize,
+    /// Lines that aren't "hex<TAB>path" or "hex path"
+    pub rejected: usize,
+    /// 1-based numbers of the first rejected lines
+    pub rejected_lines: Vec<usize>,
+    /// Entries in the user list after the import
+    pub total: usize,
+}
+
+/// Result of `add_hash_mapping`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashMapping {
+    /// The hash as written to the list
+    pub hash: String,
+    pub path: String,
+    /// Path the user list had for the hash before
+    pub replaced: Option<String>,
+    /// Whether `path` hashes to `hash`: as a WAD path for 16-digit hashes,
+    /// as a BIN name for 8-digit ones. Lists such as string table keys use
+    /// other functions, so a mismatch is only reported.
+    pub verified: bool,
+    /// Entries in the user list after adding it
+    pub total: usize,
+}
+
+/// A hash in hex, with an optional "0x"
+fn parse_hex(hex: &str) -> Option<u64> {
+    let hex = hex.strip_prefix("0x").or_else(|| hex.strip_prefix("0X")).unwrap_or(hex);
+    let valid = (1..=16).contains(&hex.len()) && hex.bytes().all(|b| b.is_ascii_hexdigit());
+    valid.then(|| u64::from_str_radix(hex, 16).ok()).flatten()
+}
+
+/// Parse one hash list line, "hex<TAB>path" or "hex path" with an optional
+/// "0x". `None` for malformed lines; blank lines and `#` comments are
+/// `Some(None)`.
+fn parse_line(line: &str) -> Option<Option<(u64, String)>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Some(None);
+    }
+    let (hex, path) = line.split_once(['\t', ' '])?;
+    let path = path.trim();
+    if path.is_empty() || path.chars().any(char::is_control) {
+        return None;
+    }
+    let hash = parse_hex(hex)?;
+    Some(Some((hash, path.to_string())))
+}
+
+/// Validate `source` line by line and merge its entries into the user list
+/// in `hash_dir`, imported paths replacing ones already there. Malformed
+/// lines are counted and skipped. Fails without touching the user list if
+/// `source` has no valid entry at all.
+pub fn import_hash_file(source: &Path, hash_dir: &Path) -> Result<HashImportResult> {
+    let content = fs::read(source).map_err(|e| Error::io_with_path(e, source))?;
+    let content = String::from_utf8_lossy(&content);
+
+    let mut result = HashImportResult::default();
+    let mut imported = BTreeMap::new();
+    for (index, line) in content.lines().enumerate() {
+        match parse_line(line) {
+            Some(Some((hash, path))) => {
+                imported.insert(hash, path);
+            }
+            Some(None) => {}
+            None => {
+                result.rejected += 1;
+                if result.rejected_lines.len() < REJECTED_LINES_LIMIT {
+                    result.rejected_lines.push(index + 1);
+                }
+            }
+        }
+    }
+    if imported.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "{} has no hash entries ({} malformed lines)",
+            source.display(),
+            result.rejected
+        )));
+    }
+
+    let mut entries = read_user_list(hash_dir)?;
+    for (hash, path) in imported {
+        if entries.insert(hash, path).is_some() {
+            result.merged += 1;
+        } else {
+            result.added += 1;
+        }
+    }
+    result.total = entries.len();
+    write_user_list(hash_dir, &entries)?;
+
+    tracing::info!(
+        "Imported {}: {} added, {} merged, {} rejected",
+        source.display(),
+        result.added,
+        result.merged,
+        result.rejected
+    );
+    Ok(result)
+}
+
+/// Record that `hash` (hex, 8 or 16 digits) is `path` in the user list in
+/// `hash_dir`, replacing a path it had for the hash
+pub fn add_hash_mapping(hash_dir: &Path, hash: &str, path: &str) -> Result<HashMapping> {
+    let hex = hash.trim();
+    let path = path.trim().replace('\\', "/");
+    let value = parse_hex(hex).ok_or_else(|| Error::InvalidInput(format!("'{}' is not a hex hash", hash)))?;
+    if path.is_empty() || path.chars().any(char::is_control) {
+        return Err(Error::InvalidInput(format!("'{}' is not a valid path", path)));
+    }
+    let digits = hex.trim_start_matches("0x").trim_start_matches("0X").len();
+    let verified = if digits > 8 { compute_path_hash(&path) == value } else { fnv1a(&path) as u64 == value };
+
+    let mut entries = read_user_list(hash_dir)?;
+    let replaced = entries.insert(value, path.clone()).filter(|old| *old != path);
+    write_user_list(hash_dir, &entries)?;
+    tracing::info!("Added hash mapping {:x} -> {} (verified: {})", value, path, verified);
+    Ok(HashMapping { hash: format!("{:x}", value), path, replaced, verified, total: entries.len() })
+}
+
+/// Entries in the user list in `hash_dir`; 0 when there is none
+pub fn user_hash_count(hash_dir: &Path) -> usize {
+    read_user_list(hash_dir).map_or(0, |entries| entries.len())
+}
+
+fn read_user_list(hash_dir: &Path) -> Result<BTreeMap<u64, String>> {
+    let user_path = hash_dir.join(USER_HASH_FILE);
+    let mut entries = BTreeMap::new();
+    if user_path.exists() {
+        let existing = fs::read_to_string(&user_path).map_err(|e| Error::io_with_path(e, &user_path))?;
+        entries.extend(existing.lines().filter_map(|line| parse_line(line).flatten()));
+    }
+    Ok(entries)
+}
+
+fn write_user_list(hash_dir: &Path, entries: &BTreeMap<u64, String>) -> Result<()> {
+    // Same widths as CommunityDragon: 8 digits for BIN hashes, 16 for WAD paths
+    let mut text = String::with_capacity(entries.values().map(|p| p.len() + 18).sum());
+    for (hash, path) in entries {
+        if *hash > u32::MAX as u64 {
+            text.push_str(&format!("{:016x} {}\n", hash, path));
+        } else {
+            text.push_str(&format!("{:08x} {}\n", hash, path));
+        }
+    }
+    fs::create_dir_all(hash_dir).map_err(|e| Error::io_with_path(e, hash_dir))?;
+    let user_path = hash_dir.join(USER_HASH_FILE);
+    let part = hash_dir.join(format!("{}.part", USER_HASH_FILE));
+    fs::write(&part, text).map_err(|e| Error::io_with_path(e, &part))?;
+    fs::rename(&part, &user_path).map_err(|e| Error::io_with_path(e, &user_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_import_merges_and_counts() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("private.txt");
+        fs::write(&source, "0x1a2b\tassets/new.tex\n# comment\n\n3c4d data/a.bin\nnot a hash\nzz path\n").unwrap();
+
+        let first = import_hash_file(&source, dir.path()).unwrap();
+        assert_eq!((first.added, first.merged, first.rejected), (2, 0, 2));
+        assert_eq!(first.rejected_lines, [5, 6]);
+
+        fs::write(&source, "3c4d data/renamed.bin\n0x0123456789abcdef c.bin\n").unwrap();
+        let second = import_hash_file(&source, dir.path()).unwrap();
+        assert_eq!((second.added, second.merged, second.total), (1, 1, 3));
+        let written = fs::read_to_string(dir.path().join(USER_HASH_FILE)).unwrap();
+        assert_eq!(
+            written,
+            "00001a2b assets/new.tex\n00003c4d data/renamed.bin\n0123456789abcdef c.bin\n"
+        );
+
+        fs::write(&source, "<html>\n").unwrap();
+        assert!(import_hash_file(&source, dir.path()).is_err());
+
+        let wad_hash = format!("{:016x}", compute_path_hash("assets/new.tex"));
+        let mapping = add_hash_mapping(dir.path(), &wad_hash, "assets\\new.tex").unwrap();
+        assert!(mapping.verified && mapping.replaced.is_none());
+        let mapping = add_hash_mapping(dir.path(), "0x3c4d", "data/other.bin").unwrap();
+        assert_eq!(mapping.replaced.as_deref(), Some("data/renamed.bin"));
+        assert!(!mapping.verified);
+        assert_eq!(user_hash_count(dir.path()), 4);
+        assert!(add_hash_mapping(dir.path(), "xyz", "a.bin").is_err());
+    }
+}
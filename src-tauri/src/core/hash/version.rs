@@ -0,0 +1,88 @@
+//! Which game version the hash lists were last refreshed against
+//!
+//! CommunityDragon catches up with a patch some time after it ships, and
+//! until then new assets resolve to hex. The game version is recorded in the
+//! hash directory when a download brings in new lists, so a later install can
+//! be recognized as newer than the hashes.
+
+use crate::core::hash::DownloadStats;
+use crate::core::league::detect_game_version;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+/// Game version the lists were last refreshed against, next to them. Not a
+/// `.txt`, which the hashtable would load as a list.
+pub const GAME_VERSION_FILE: &str = "hash-game-version.json";
+
+#[derive(Serialize, Deserialize)]
+struct RecordedVersion {
+    game_version: String,
+}
+
+/// The recorded game version, if any
+pub fn recorded_game_version(hash_dir: &Path) -> Option<String> {
+    let data = fs::read(hash_dir.join(GAME_VERSION_FILE)).ok()?;
+    serde_json::from_slice::<RecordedVersion>(&data).ok().map(|r| r.game_version)
+}
+
+/// Record `version` as the game version the lists in `hash_dir` match
+pub fn record_game_version(hash_dir: &Path, version: &str) -> Result<()> {
+    let path = hash_dir.join(GAME_VERSION_FILE);
+    let recorded = RecordedVersion { game_version: version.to_string() };
+    let data = serde_json::to_vec_pretty(&recorded).map_err(|e| Error::Hash(e.to_string()))?;
+    fs::write(&path, data).map_err(|e| Error::io_with_path(e, &path))?;
+    tracing::info!("Hash lists recorded as current for game version {}", version);
+    Ok(())
+}
+
+/// Record the game version installed at `league_path` after a download into
+/// `hash_dir` that finished without errors and brought in new lists, or when
+/// nothing is recorded yet. A check that found nothing new doesn't move the
+/// record, since CommunityDragon may not have caught up with the patch yet.
+pub fn record_after_download(hash_dir: &Path, league_path: Option<&Path>, stats: &DownloadStats) {
+    if stats.cancelled || stats.errors > 0 {
+        return;
+    }
+    if stats.downloaded == 0 && recorded_game_version(hash_dir).is_some() {
+        return;
+    }
+    let Some(version) = league_path.and_then(detect_game_version) else {
+        return;
+    };
+    if let Err(e) = record_game_version(hash_dir, &version) {
+        tracing::warn!("Failed to record the hash lists' game version: {}", e);
+    }
+}
+
+/// Whether `installed` is a later game version than `recorded`, comparing
+/// the dot-separated numbers ("14.10.1" is newer than "14.9.3"). Versions
+/// that don't parse never count as newer.
+pub fn is_newer_version(installed: &str, recorded: &str) -> bool {
+    let parse = |version: &str| -> Option<Vec<u64>> { version.split('.').map(|part| part.parse().ok()).collect() };
+    match (parse(installed), parse(recorded)) {
+        (Some(installed), Some(recorded)) => installed.cmp(&recorded) == Ordering::Greater,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_recorded_version_and_comparison() {
+        let dir = tempdir().unwrap();
+        assert_eq!(recorded_game_version(dir.path()), None);
+        record_game_version(dir.path(), "14.9.586.9467").unwrap();
+        assert_eq!(recorded_game_version(dir.path()).as_deref(), Some("14.9.586.9467"));
+
+        assert!(is_newer_version("14.10.590.1", "14.9.586.9467"));
+        assert!(!is_newer_version("14.9.586.9467", "14.9.586.9467"));
+        assert!(!is_newer_version("14.8.1", "14.9.586.9467"));
+        assert!(!is_newer_version("unknown", "14.9.586.9467"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Watches the hash directory for lists updated outside Flint
+//!
+//! The directory is shared with other RitoShark tools, which may replace the
+//! lists while Flint is open. Changes are debounced so a tool rewriting
+//! several files triggers one reload.
+
+use super::hashtable::is_hash_file;
+use crate::error::{Error, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long the directory must stay quiet before a batch of changes is applied
+pub const DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// Keeps the watch alive; dropping it stops watching
+pub struct HashDirWatcher {
+    watcher: Mutex<RecommendedWatcher>,
+    dir: Mutex<PathBuf>,
+}
+
+impl HashDirWatcher {
+    /// Watch `dir` instead of the current directory, keeping the callback
+    pub fn switch_to(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir).map_err(|e| Error::io_with_path(e, dir))?;
+        let mut current = self.dir.lock();
+        let mut watcher = self.watcher.lock();
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::Hash(format!("Failed to watch {}: {}", dir.display(), e)))?;
+        // The old directory may be gone already after a migration
+        let _ = watcher.unwatch(&current);
+        tracing::info!("Watching {} for hash updates", dir.display());
+        *current = dir.to_path_buf();
+        Ok(())
+    }
+}
+
+/// Watch `dir` and call `on_change` with the changed hash files and the time
+/// of the last change in the batch.
+///
+/// Only `.txt` lists are reported, so in-progress downloads (`.part`) are
+/// ignored until they are renamed into place.
+pub fn watch_hash_dir(
+    dir: &Path,
+    on_change: impl FnMut(Vec<PathBuf>, Instant) + Send + 'static,
+) -> Result<HashDirWatcher> {
+    std::fs::create_dir_all(dir).map_err(|e| Error::io_with_path(e, dir))?;
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("Hash directory watch error: {}", e);
+                return;
+            }
+        };
+        // Reads (including our own loads) show up as access events
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+            return;
+        }
+        for path in event.paths.into_iter().filter(|p| is_hash_file(p)) {
+            let _ = tx.send(path);
+        }
+    })
+    .map_err(|e| Error::Hash(format!("Failed to watch {}: {}", dir.display(), e)))?;
+
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| Error::Hash(format!("Failed to watch {}: {}", dir.display(), e)))?;
+
+    std::thread::Builder::new()
+        .name("flint-hash-watcher".to_string())
+        .spawn(move || debounce(rx, DEBOUNCE, on_change))
+        .map_err(|e| Error::io_with_path(e, dir))?;
+
+    tracing::info!("Watching {} for hash updates", dir.display());
+    Ok(HashDirWatcher { watcher: Mutex::new(watcher), dir: Mutex::new(dir.to_path_buf()) })
+}
+
+/// Group changes until `quiet` passes without a new one. Returns once the
+/// sender is gone, dropping any batch still in progress.
+pub(crate) fn debounce(rx: Receiver<PathBuf>, quiet: Duration, mut on_change: impl FnMut(Vec<PathBuf>, Instant)) {
+    while let Ok(first) = rx.recv() {
+        let mut changed = BTreeSet::from([first]);
+        let mut last = Instant::now();
+        loop {
+            match rx.recv_timeout(quiet) {
+                Ok(path) => {
+                    changed.insert(path);
+                    last = Instant::now();
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        on_change(changed.into_iter().collect(), last);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_batches_bursts() {
+        let (tx, rx) = channel();
+        let (batches_tx, batches) = channel();
+        let worker = std::thread::spawn(move || {
+            debounce(rx, Duration::from_millis(100), move |files, _| {
+                batches_tx.send(files).unwrap();
+            })
+        });
+
+        for name in ["b.txt", "a.txt", "b.txt"] {
+            tx.send(PathBuf::from(name)).unwrap();
+        }
+        let batch = batches.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(batch, [PathBuf::from("a.txt"), PathBuf::from("b.txt")]);
+
+        tx.send(PathBuf::from("c.txt")).unwrap();
+        let batch = batches.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(batch, [PathBuf::from("c.txt")]);
+
+        drop(tx);
+        worker.join().unwrap();
+    }
+}
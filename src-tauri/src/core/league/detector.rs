@@ -88,6 +88,18 @@ pub fn detect_league_installation() -> Result<LeagueInstallation> {
     ))
 }
 
+/// Returns the configured installation while it is still valid, otherwise
+/// falls back to auto-detection
+pub fn resolve_league_installation(configured: Option<&Path>) -> Result<LeagueInstallation> {
+    if let Some(path) = configured {
+        match validate_league_path(path) {
+            Ok(installation) => return Ok(installation),
+            Err(e) => tracing::warn!("Configured League path is no longer valid: {}", e),
+        }
+    }
+    detect_league_installation()
+}
+
 /// Validates a manually specified League path
 ///
 /// # Arguments
@@ -102,6 +114,25 @@ pub fn validate_league_path(path: impl AsRef<Path>) -> Result<LeagueInstallation
     validate_and_create(path, false)
 }
 
+/// Reads the installed game version from `Game/content-metadata.json`
+///
+/// # Arguments
+/// * `league_path` - Path to the League installation root
+///
+/// # Returns
+/// * `Some(version)` - e.g. "14.3.558.2473"
+/// * `None` - If the metadata file is missing or unreadable
+pub fn detect_game_version(league_path: impl AsRef<Path>) -> Option<String> {
+    let metadata_path = league_path.as_ref().join("Game").join("content-metadata.json");
+    let content = std::fs::read_to_string(&metadata_path).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+
+    // The version string may carry a "+branch..." suffix - keep only the numeric part
+    json.get("version")
+        .and_then(|v| v.as_str())
+        .map(|v| v.split('+').next().unwrap_or(v).to_string())
+}
+
 /// Validates a path and creates a LeagueInstallation if valid
 fn validate_and_create(path: &Path, auto_detected: bool) -> Result<LeagueInstallation> {
     // Check path exists
@@ -188,6 +219,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_detect_game_version() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let game_dir = temp_dir.path().join("Game");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        assert_eq!(detect_game_version(temp_dir.path()), None);
+
+        std::fs::write(
+            game_dir.join("content-metadata.json"),
+            r#"{"version":"14.3.558.2473+branch.releases-14-3"}"#,
+        ).unwrap();
+        assert_eq!(detect_game_version(temp_dir.path()), Some("14.3.558.2473".to_string()));
+    }
+
     #[test]
     fn test_required_files_not_empty() {
         assert!(!REQUIRED_FILES.is_empty());
@@ -1,4 +1,9 @@
 // League detection module exports
 pub mod detector;
+pub mod process;
 
-pub use detector::{detect_league_installation, validate_league_path, LeagueInstallation};
+pub use detector::{
+    detect_game_version, detect_league_installation, resolve_league_installation, validate_league_path,
+    LeagueInstallation,
+};
+pub use process::check_game_running;
@@ -0,0 +1,72 @@
+//! Whether League or the Riot Client is running
+//!
+//! While the game runs, or the Riot Client patches it, its WADs can be held
+//! open without sharing. Commands check this before long operations on game
+//! files, and a WAD that can't be opened for that reason is reported as
+//! `Error::GameFilesLocked` rather than a bare OS error.
+
+use std::io;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Executables of the game, the League client and the Riot Client
+const GAME_PROCESSES: &[&str] = &[
+    "league of legends",
+    "leagueclient",
+    "leagueclientux",
+    "leagueclientuxrender",
+    "riotclientservices",
+    "riotclientux",
+    "riot client",
+];
+
+/// Whether `name` is the executable of a League or Riot Client process
+pub fn is_game_process(name: &str) -> bool {
+    let name = name.to_lowercase();
+    let name = name.strip_suffix(".exe").unwrap_or(&name);
+    GAME_PROCESSES.contains(&name)
+}
+
+/// Names of the League and Riot Client processes running, sorted, each once
+pub fn check_game_running() -> Vec<String> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::new());
+    let mut running: Vec<String> = system
+        .processes()
+        .values()
+        .map(|process| process.name().to_string_lossy().to_string())
+        .filter(|name| is_game_process(name))
+        .collect();
+    running.sort();
+    running.dedup();
+    running
+}
+
+/// Whether opening a file failed because another process holds it: a
+/// sharing or lock violation, or access denied, which Windows also reports
+/// for a file being replaced
+pub fn is_lock_error(error: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+    #[cfg(target_os = "windows")]
+    if matches!(error.raw_os_error(), Some(32 | 33)) {
+        return true;
+    }
+    error.kind() == io::ErrorKind::PermissionDenied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_processes_match_by_name() {
+        assert!(is_game_process("League of Legends.exe"));
+        assert!(is_game_process("RiotClientServices.exe"));
+        assert!(is_game_process("LeagueClientUx"));
+        assert!(!is_game_process("LeagueClientUx.exe.bak"));
+        assert!(!is_game_process("flint.exe"));
+
+        assert!(check_game_running().iter().all(|name| is_game_process(name)));
+        assert!(is_lock_error(&io::Error::from(io::ErrorKind::PermissionDenied)));
+        assert!(!is_lock_error(&io::Error::from(io::ErrorKind::NotFound)));
+    }
+}
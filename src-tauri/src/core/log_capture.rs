@@ -0,0 +1,312 @@
+//! Log Capture Layer
+//! Keeps recent log lines in memory and writes them to rotated files in the
+//! app log directory, so bug reports can include logs without a console.
+//!
+//! Events emitted inside an operation span (see `OperationHandle::span`) are
+//! tagged with the operation id, which lets the UI filter a failed export's
+//! lines out of the rest.
+
+use crate::core::frontend_log::MessageVisitor;
+use crate::error::{Error, Result};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Number of lines kept in memory
+const RING_CAPACITY: usize = 5000;
+
+/// Size at which the active log file is rotated
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept next to the active one (flint.log.1 … flint.log.N)
+const MAX_ROTATED_FILES: usize = 3;
+
+pub const LOG_FILE_NAME: &str = "flint.log";
+
+/// A captured log line
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub operation_id: Option<String>,
+}
+
+impl LogLine {
+    fn level(&self) -> Level {
+        self.level.parse().unwrap_or(Level::TRACE)
+    }
+}
+
+/// Bounded buffer of the most recent lines
+struct LogRing {
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+}
+
+impl LogRing {
+    const fn new(capacity: usize) -> Self {
+        Self { lines: VecDeque::new(), capacity }
+    }
+
+    fn push(&mut self, line: LogLine) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Newest `limit` lines at `min_level` or more severe, oldest first
+    fn recent(&self, limit: usize, min_level: Level, operation_id: Option<&str>) -> Vec<LogLine> {
+        let mut lines: Vec<LogLine> = self
+            .lines
+            .iter()
+            .rev()
+            // Level ordering in tracing: ERROR < WARN < ... < TRACE
+            .filter(|l| l.level() <= min_level)
+            .filter(|l| operation_id.is_none() || l.operation_id.as_deref() == operation_id)
+            .take(limit)
+            .cloned()
+            .collect();
+        lines.reverse();
+        lines
+    }
+}
+
+/// Append-only log file that rotates by size
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| Error::io_with_path(e, &path))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > MAX_FILE_SIZE {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+static RECENT: Mutex<LogRing> = Mutex::new(LogRing::new(RING_CAPACITY));
+static LOG_FILE: Mutex<Option<RotatingFile>> = Mutex::new(None);
+
+/// Start writing captured lines to `log_dir/flint.log` (called from setup).
+/// Lines logged before this are only in memory.
+pub fn init_log_file(log_dir: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(log_dir).map_err(|e| Error::io_with_path(e, log_dir))?;
+    let path = log_dir.join(LOG_FILE_NAME);
+    *LOG_FILE.lock() = Some(RotatingFile::open(path.clone())?);
+    Ok(path)
+}
+
+/// Path of the active log file, if file logging is set up
+pub fn log_file_path() -> Option<PathBuf> {
+    LOG_FILE.lock().as_ref().map(|f| f.path.clone())
+}
+
+/// The active log file followed by its rotated predecessors, newest first
+pub fn log_files() -> Vec<PathBuf> {
+    let Some(path) = log_file_path() else {
+        return Vec::new();
+    };
+    std::iter::once(path.clone())
+        .chain((1..=MAX_ROTATED_FILES).map(|index| rotated_path(&path, index)))
+        .filter(|p| p.exists())
+        .collect()
+}
+
+/// Most recent captured lines, oldest first
+///
+/// # Arguments
+/// * `limit` - Maximum number of lines
+/// * `min_level` - Least severe level to include
+/// * `operation_id` - Only lines logged inside this operation
+pub fn recent_logs(limit: usize, min_level: Level, operation_id: Option<&str>) -> Vec<LogLine> {
+    RECENT.lock().recent(limit, min_level, operation_id)
+}
+
+/// Operation id recorded on an operation span
+struct OperationId(String);
+
+/// Tracing layer feeding the in-memory buffer and the log file
+pub struct LogCaptureLayer;
+
+impl<S> Layer<S> for LogCaptureLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut operation_id = String::new();
+        attrs.record(&mut FieldVisitor("operation_id", &mut operation_id));
+        if operation_id.is_empty() {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(OperationId(operation_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+
+        let mut message = String::new();
+        event.record(&mut MessageVisitor(&mut message));
+
+        let operation_id = ctx.event_scope(event).and_then(|scope| {
+            scope
+                .into_iter()
+                .find_map(|span| span.extensions().get::<OperationId>().map(|o| o.0.clone()))
+        });
+
+        let line = LogLine {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            level: metadata.level().as_str().to_string(),
+            target: metadata.target().to_string(),
+            message,
+            operation_id,
+        };
+
+        if let Some(file) = LOG_FILE.lock().as_mut() {
+            let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+            let formatted = match &line.operation_id {
+                Some(op) => format!("{} {:5} [{}] {}: {}", time, line.level, op, line.target, line.message),
+                None => format!("{} {:5} {}: {}", time, line.level, line.target, line.message),
+            };
+            // Nowhere to report a failed log write; the line stays in memory
+            let _ = file.write_line(&formatted);
+        }
+
+        RECENT.lock().push(line);
+    }
+}
+
+/// Visitor that reads a single named field as a string
+struct FieldVisitor<'a>(&'static str, &'a mut String);
+
+impl tracing::field::Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == self.0 {
+            *self.1 = format!("{:?}", value).trim_matches('"').to_string();
+        }
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == self.0 {
+            *self.1 = value.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn line(level: Level, message: &str, operation_id: Option<&str>) -> LogLine {
+        LogLine {
+            timestamp: 0,
+            level: level.as_str().to_string(),
+            target: "flint".to_string(),
+            message: message.to_string(),
+            operation_id: operation_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_ring_drops_oldest_and_filters() {
+        let mut ring = LogRing::new(3);
+        ring.push(line(Level::INFO, "a", None));
+        ring.push(line(Level::WARN, "b", Some("op")));
+        ring.push(line(Level::DEBUG, "c", None));
+        ring.push(line(Level::ERROR, "d", Some("op")));
+
+        let all: Vec<_> = ring.recent(10, Level::TRACE, None).into_iter().map(|l| l.message).collect();
+        assert_eq!(all, ["b", "c", "d"]);
+
+        let warnings: Vec<_> = ring.recent(10, Level::WARN, None).into_iter().map(|l| l.message).collect();
+        assert_eq!(warnings, ["b", "d"]);
+
+        let newest: Vec<_> = ring.recent(1, Level::TRACE, Some("op")).into_iter().map(|l| l.message).collect();
+        assert_eq!(newest, ["d"]);
+    }
+
+    #[test]
+    fn test_events_in_operation_span_are_tagged() {
+        use tracing_subscriber::prelude::*;
+
+        let subscriber = tracing_subscriber::registry().with(LogCaptureLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("operation", operation_id = "op-tagged");
+            span.in_scope(|| {
+                let _nested = tracing::debug_span!("step").entered();
+                tracing::warn!("inside");
+            });
+            tracing::warn!("outside");
+        });
+
+        let tagged = recent_logs(10, Level::TRACE, Some("op-tagged"));
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].message, "inside");
+        assert_eq!(tagged[0].level, "WARN");
+    }
+
+    #[test]
+    fn test_file_rotates_by_size() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(LOG_FILE_NAME);
+        let mut file = RotatingFile::open(path.clone()).unwrap();
+
+        let chunk = "x".repeat(MAX_FILE_SIZE as usize / 2);
+        for _ in 0..(MAX_ROTATED_FILES * 2 + 2) {
+            file.write_line(&chunk).unwrap();
+        }
+
+        assert!(path.exists());
+        assert!(rotated_path(&path, MAX_ROTATED_FILES).exists());
+        assert!(!rotated_path(&path, MAX_ROTATED_FILES + 1).exists());
+        assert!(std::fs::metadata(&path).unwrap().len() <= MAX_FILE_SIZE);
+    }
+}
@@ -0,0 +1,278 @@
+//! Path mapping files shared with other RitoShark tools
+//!
+//! Extraction can't always write a file under its game path (Windows path
+//! limits), so it stores such files under another name and records where
+//! they went. The record is kept in the project so any tool that repaths or
+//! exports the project can find them again.
+//!
+//! File format (`path-mappings.json` in the project root):
+//!
+//! ```json
+//! {
+//!   "format": "ritoshark-path-mappings",
+//!   "version": 1,
+//!   "generator": "flint 0.4.0",
+//!   "game_version": "14.23.1",
+//!   "mappings": [
+//!     {
+//!       "path": "assets/characters/ahri/skins/skin0/a_very_long_name.dds",
+//!       "file": "assets/characters/ahri/skins/skin0/0123456789abcdef.dds",
+//!       "wad": "ahri.wad.client"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! - `path` is the game path, lowercase with `/` separators.
+//! - `file` is where the file actually is, relative to the WAD folder
+//!   (`content/base/{wad}/`), normalized the same way.
+//! - `wad` is the client WAD the file came from. `game_version` is the patch
+//!   it was extracted from, when known.
+//!
+//! `version` only increases for incompatible changes; readers reject newer
+//! versions and keep fields they don't know when rewriting a file.
+
+use crate::core::paths::{safe_join, safe_relative};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Identifies the file among other JSON files
+pub const PATH_MAPPINGS_FORMAT: &str = "ritoshark-path-mappings";
+
+/// Current schema version
+pub const PATH_MAPPINGS_VERSION: u32 = 1;
+
+/// File name inside the project root
+pub const PATH_MAPPINGS_FILE: &str = "path-mappings.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathMappings {
+    pub format: String,
+    pub version: u32,
+    /// Tool that last wrote the file
+    pub generator: Option<String>,
+    pub game_version: Option<String>,
+    pub mappings: Vec<PathMapping>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathMapping {
+    /// Normalized game path
+    pub path: String,
+    /// Normalized on-disk path, relative to the WAD folder
+    pub file: String,
+    /// Source WAD, e.g. "ahri.wad.client"
+    pub wad: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for PathMappings {
+    fn default() -> Self {
+        Self {
+            format: PATH_MAPPINGS_FORMAT.to_string(),
+            version: PATH_MAPPINGS_VERSION,
+            generator: None,
+            game_version: None,
+            mappings: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+/// Lowercase with `/` separators, as game paths are hashed
+pub fn normalize_path(path: &str) -> String {
+    path.to_lowercase().replace('\\', "/")
+}
+
+impl PathMappings {
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.mappings.len()
+    }
+
+    /// Record that the game file `path` from `wad` is stored at `file`
+    pub fn insert(&mut self, path: &str, file: &str, wad: &str) {
+        let path = normalize_path(path);
+        let mapping = PathMapping {
+            path: path.clone(),
+            file: normalize_path(file),
+            wad: wad.to_lowercase(),
+            extra: Map::new(),
+        };
+        match self.mappings.iter_mut().find(|m| m.path == path) {
+            Some(existing) => *existing = mapping,
+            None => self.mappings.push(mapping),
+        }
+    }
+
+    /// Add `other`'s mappings; on conflicts `other` wins. Returns how many
+    /// entries were added or changed.
+    pub fn merge(&mut self, other: &PathMappings) -> usize {
+        let mut changed = 0;
+        for mapping in &other.mappings {
+            let current = self.mappings.iter().find(|m| m.path == mapping.path);
+            if current.map(|m| (&m.file, &m.wad)) != Some((&mapping.file, &mapping.wad)) {
+                self.insert(&mapping.path, &mapping.file, &mapping.wad);
+                changed += 1;
+            }
+        }
+        if self.game_version.is_none() {
+            self.game_version = other.game_version.clone();
+        }
+        changed
+    }
+
+    /// Game path -> on-disk path, for the repath and concat steps
+    pub fn lookup(&self) -> HashMap<String, String> {
+        self.mappings
+            .iter()
+            .map(|m| (m.path.clone(), m.file.clone()))
+            .collect()
+    }
+
+    /// Game paths whose mapped file isn't under `content_base/{wad}/`
+    pub fn missing_files(&self, content_base: &Path) -> Vec<String> {
+        self.mappings
+            .iter()
+            .filter(|m| !safe_join(content_base, &format!("{}/{}", m.wad, m.file)).is_ok_and(|p| p.exists()))
+            .map(|m| m.path.clone())
+            .collect()
+    }
+
+    /// Read a mapping file, rejecting other formats and newer versions
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| Error::io_with_path(e, path))?;
+        let mappings: PathMappings = serde_json::from_str(&text)
+            .map_err(|e| Error::parse_with_path(e.line(), e.to_string(), path))?;
+
+        if mappings.format != PATH_MAPPINGS_FORMAT {
+            return Err(Error::InvalidInput(format!(
+                "{} is not a path mapping file (format '{}')",
+                path.display(),
+                mappings.format
+            )));
+        }
+        if mappings.version > PATH_MAPPINGS_VERSION {
+            return Err(Error::InvalidInput(format!(
+                "{} uses path mapping version {}, this Flint supports up to {}",
+                path.display(),
+                mappings.version,
+                PATH_MAPPINGS_VERSION
+            )));
+        }
+        // Mapped files are joined onto the project's content directory
+        for mapping in &mappings.mappings {
+            safe_relative(&mapping.wad)?;
+            safe_relative(&mapping.file)?;
+        }
+        Ok(mappings)
+    }
+
+    /// Write the file via a temp file so readers never see it half-written
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        }
+        let mut mappings = self.clone();
+        mappings.generator = Some(format!("flint {}", env!("CARGO_PKG_VERSION")));
+        let json = serde_json::to_string_pretty(&mappings)
+            .map_err(|e| Error::InvalidInput(format!("Failed to serialize path mappings: {}", e)))?;
+
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(|e| Error::io_with_path(e, &tmp))?;
+        std::fs::rename(&tmp, path).map_err(|e| Error::io_with_path(e, path))
+    }
+
+    /// The project's mappings; a project without a mapping file has none
+    pub fn load(project_path: &Path) -> Result<Self> {
+        let path = project_path.join(PATH_MAPPINGS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::read(&path)
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<()> {
+        self.write(&project_path.join(PATH_MAPPINGS_FILE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_round_trip_keeps_unknown_fields() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(PATH_MAPPINGS_FILE),
+            r#"{"format": "ritoshark-path-mappings", "version": 1, "game_version": "14.23.1",
+                "source_tool": "other", "mappings": [
+                {"path": "data/a.bin", "file": "data/0011.bin", "wad": "ahri.wad.client", "size": 12}]}"#,
+        )
+        .unwrap();
+
+        let mut mappings = PathMappings::load(dir.path()).unwrap();
+        assert_eq!(mappings.lookup()["data/a.bin"], "data/0011.bin");
+        mappings.insert("DATA\\B.bin", "data/0022.bin", "Ahri.wad.client");
+        mappings.save(dir.path()).unwrap();
+
+        let saved: Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join(PATH_MAPPINGS_FILE)).unwrap()).unwrap();
+        assert_eq!(saved["source_tool"], "other");
+        assert_eq!(saved["mappings"][0]["size"], 12);
+        assert_eq!(saved["mappings"][1]["path"], "data/b.bin");
+        assert_eq!(saved["mappings"][1]["wad"], "ahri.wad.client");
+    }
+
+    #[test]
+    fn test_rejects_newer_versions_and_other_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("mappings.json");
+
+        std::fs::write(&path, r#"{"format": "ritoshark-path-mappings", "version": 2, "mappings": []}"#).unwrap();
+        assert!(PathMappings::read(&path).is_err());
+        std::fs::write(&path, r#"{"format": "something-else", "version": 1, "mappings": []}"#).unwrap();
+        assert!(PathMappings::read(&path).is_err());
+        std::fs::write(
+            &path,
+            r#"{"format": "ritoshark-path-mappings", "version": 1, "mappings": [
+                {"path": "data/a.bin", "file": "../../evil.bin", "wad": "ahri.wad.client"}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(PathMappings::read(&path), Err(Error::UnsafePath { .. })));
+
+        assert!(PathMappings::load(dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_counts_changes() {
+        let mut ours = PathMappings::default();
+        ours.insert("data/a.bin", "data/1.bin", "ahri.wad.client");
+
+        let mut theirs = PathMappings { game_version: Some("14.1".to_string()), ..Default::default() };
+        theirs.insert("data/a.bin", "data/1.bin", "ahri.wad.client");
+        theirs.insert("data/b.bin", "data/2.bin", "ahri.wad.client");
+
+        assert_eq!(ours.merge(&theirs), 1);
+        assert_eq!(ours.len(), 2);
+        assert_eq!(ours.game_version.as_deref(), Some("14.1"));
+
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("ahri.wad.client/data")).unwrap();
+        std::fs::write(dir.path().join("ahri.wad.client/data/1.bin"), b"PROP").unwrap();
+        assert_eq!(ours.missing_files(dir.path()), ["data/b.bin"]);
+    }
+}
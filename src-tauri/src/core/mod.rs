@@ -11,3 +11,16 @@ pub mod export;
 pub mod mesh;
 pub mod checkpoint;
 pub mod frontend_log;
+pub mod log_capture;
+pub mod operation;
+pub mod settings;
+pub mod diagnostics;
+pub mod parallel;
+pub mod startup;
+pub mod mapping;
+pub mod disk;
+pub mod undo;
+pub mod recolor;
+pub mod paths;
+pub mod file;
+pub mod blob;
@@ -0,0 +1,638 @@
+//! Registry of long-running operations
+//!
+//! Long commands (extraction, export, repath, checkpoints, downloads) register
+//! here and get a [`CancellationToken`] they check between units of work.
+//! The frontend lists operations and cancels them by id, so a single cancel
+//! button works for every feature.
+//!
+//! Operations that mutate a project's files (repath, export-with-repath,
+//! restore) are registered as *exclusive*: only one may run per project at a
+//! time. Read-only operations never take the slot and are never blocked.
+
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// How long finished operations stay visible in `list()`
+pub const FINISHED_RETENTION: Duration = Duration::from_secs(60);
+
+/// Shared flag a running operation polls to see whether it should stop
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// `Err(Error::Cancelled)` once cancellation has been requested
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The underlying flag, for APIs that take `&AtomicBool`
+    pub fn as_flag(&self) -> &AtomicBool {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    Extraction,
+    Export,
+    Repath,
+    Checkpoint,
+    Download,
+    Setup,
+    Validation,
+    Recolor,
+}
+
+impl std::fmt::Display for OperationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            OperationKind::Extraction => "extraction",
+            OperationKind::Export => "export",
+            OperationKind::Repath => "repath",
+            OperationKind::Checkpoint => "checkpoint",
+            OperationKind::Download => "download",
+            OperationKind::Setup => "setup",
+            OperationKind::Validation => "validation",
+            OperationKind::Recolor => "recolor",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Snapshot of an operation as returned to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: OperationKind,
+    /// Project path the operation works on, if any
+    pub project: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub status: OperationStatus,
+    /// Fraction complete (0.0–1.0), if the operation reports it
+    pub progress: Option<f32>,
+    pub message: Option<String>,
+    /// False for operations that can't be stopped safely midway (e.g. restores)
+    pub cancellable: bool,
+    /// Holds the project's mutating-operation slot
+    pub exclusive: bool,
+    /// Time spent in each phase, in the order they ran; empty while running
+    pub timings: Vec<PhaseTiming>,
+}
+
+/// How long one phase of an operation took
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub millis: u64,
+}
+
+/// Times an operation's phases. Call `phase` at each existing phase
+/// boundary; it only takes an `Instant`, so the overhead is negligible.
+#[derive(Debug, Default)]
+pub struct PhaseTimer {
+    timings: Vec<PhaseTiming>,
+    current: Option<(String, Instant)>,
+}
+
+impl PhaseTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// End the running phase, if any, and start `phase`
+    pub fn phase(&mut self, phase: impl Into<String>) {
+        self.end();
+        self.current = Some((phase.into(), Instant::now()));
+    }
+
+    /// End the running phase without starting another
+    pub fn end(&mut self) {
+        if let Some((phase, started)) = self.current.take() {
+            let millis = started.elapsed().as_millis() as u64;
+            self.timings.push(PhaseTiming { phase, millis });
+        }
+    }
+
+    /// End the running phase and return every phase in the order they ran
+    pub fn finish(mut self) -> Vec<PhaseTiming> {
+        self.end();
+        self.timings
+    }
+}
+
+/// Whether a project currently has a mutating operation running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBusyStatus {
+    pub busy: bool,
+    /// The operation holding the project's exclusive slot
+    pub operation: Option<OperationInfo>,
+}
+
+struct OperationEntry {
+    info: OperationInfo,
+    token: CancellationToken,
+    finished: Option<Instant>,
+    /// Canonical project path whose exclusive slot this operation holds
+    slot: Option<PathBuf>,
+}
+
+/// Key for a project's exclusive slot, so different spellings of the same
+/// directory share one slot
+fn slot_key(project: &str) -> PathBuf {
+    Path::new(project).canonicalize().unwrap_or_else(|_| PathBuf::from(project))
+}
+
+/// Managed state tracking all running and recently finished operations
+#[derive(Clone, Default)]
+pub struct OperationRegistry {
+    entries: Arc<Mutex<HashMap<String, OperationEntry>>>,
+    /// Most recently finished operation of each kind, kept past `FINISHED_RETENTION`
+    last_finished: Arc<Mutex<HashMap<OperationKind, OperationInfo>>>,
+    /// Woken whenever an exclusive operation finishes, for queued callers
+    released: Arc<Notify>,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a cancellable operation
+    pub fn start(&self, kind: OperationKind, project: Option<&str>) -> OperationHandle {
+        let mut entries = self.entries.lock();
+        self.register(&mut entries, kind, project, true, None)
+    }
+
+    /// Register an operation that reports progress but ignores cancel requests
+    pub fn start_uncancellable(&self, kind: OperationKind, project: Option<&str>) -> OperationHandle {
+        let mut entries = self.entries.lock();
+        self.register(&mut entries, kind, project, false, None)
+    }
+
+    /// Take the project's exclusive slot, failing with `Error::Busy` if another
+    /// mutating operation holds it
+    pub fn try_start_exclusive(
+        &self,
+        kind: OperationKind,
+        project: &str,
+        cancellable: bool,
+    ) -> Result<OperationHandle> {
+        let slot = slot_key(project);
+        let mut entries = self.entries.lock();
+        Self::prune(&mut entries);
+        if let Some(holder) = Self::exclusive_holder_in(&entries, &slot) {
+            return Err(Error::Busy(format!(
+                "{} {} is already running (started {})",
+                holder.kind,
+                holder.id,
+                holder.started_at.format("%H:%M:%S")
+            )));
+        }
+        Ok(self.register(&mut entries, kind, Some(project), cancellable, Some(slot)))
+    }
+
+    /// Like `try_start_exclusive`, but with `queue` set waits for the slot
+    /// instead of failing
+    pub async fn start_exclusive(
+        &self,
+        kind: OperationKind,
+        project: &str,
+        cancellable: bool,
+        queue: bool,
+    ) -> Result<OperationHandle> {
+        loop {
+            // Register interest before checking so a release in between isn't missed
+            let released = self.released.notified();
+            match self.try_start_exclusive(kind, project, cancellable) {
+                Err(Error::Busy(holder)) if queue => {
+                    tracing::info!("Queued {} on {} behind {}", kind, project, holder);
+                    released.await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Current holder of the project's exclusive slot
+    pub fn busy_status(&self, project: &str) -> ProjectBusyStatus {
+        let slot = slot_key(project);
+        let mut entries = self.entries.lock();
+        Self::prune(&mut entries);
+        let operation = Self::exclusive_holder_in(&entries, &slot).cloned();
+        ProjectBusyStatus { busy: operation.is_some(), operation }
+    }
+
+    fn exclusive_holder_in<'a>(
+        entries: &'a HashMap<String, OperationEntry>,
+        slot: &Path,
+    ) -> Option<&'a OperationInfo> {
+        entries
+            .values()
+            .find(|e| e.info.status == OperationStatus::Running && e.slot.as_deref() == Some(slot))
+            .map(|e| &e.info)
+    }
+
+    fn register(
+        &self,
+        entries: &mut HashMap<String, OperationEntry>,
+        kind: OperationKind,
+        project: Option<&str>,
+        cancellable: bool,
+        slot: Option<PathBuf>,
+    ) -> OperationHandle {
+        let id = uuid::Uuid::new_v4().to_string();
+        let token = CancellationToken::new();
+        let info = OperationInfo {
+            id: id.clone(),
+            kind,
+            project: project.map(str::to_string),
+            started_at: Utc::now(),
+            finished_at: None,
+            status: OperationStatus::Running,
+            progress: None,
+            message: None,
+            cancellable,
+            exclusive: slot.is_some(),
+            timings: Vec::new(),
+        };
+
+        Self::prune(entries);
+        entries.insert(id.clone(), OperationEntry { info, token: token.clone(), finished: None, slot });
+
+        let span = tracing::info_span!("operation", operation_id = %id, kind = %kind);
+        span.in_scope(|| tracing::debug!("Operation {} started ({:?})", id, kind));
+
+        OperationHandle {
+            id,
+            token,
+            registry: self.clone(),
+            span,
+            finished: false,
+        }
+    }
+
+    /// Running operations plus those finished within `FINISHED_RETENTION`, oldest first
+    pub fn list(&self) -> Vec<OperationInfo> {
+        let mut entries = self.entries.lock();
+        Self::prune(&mut entries);
+        let mut list: Vec<OperationInfo> = entries.values().map(|e| e.info.clone()).collect();
+        list.sort_by(|a, b| a.started_at.cmp(&b.started_at).then_with(|| a.id.cmp(&b.id)));
+        list
+    }
+
+    /// The last finished operation of each kind, most recent first, for
+    /// results the frontend has already discarded
+    pub fn last_finished(&self) -> Vec<OperationInfo> {
+        let mut list: Vec<OperationInfo> = self.last_finished.lock().values().cloned().collect();
+        list.sort_by(|a, b| b.finished_at.cmp(&a.finished_at).then_with(|| a.id.cmp(&b.id)));
+        list
+    }
+
+    #[cfg(test)]
+    pub fn get(&self, id: &str) -> Option<OperationInfo> {
+        self.entries.lock().get(id).map(|e| e.info.clone())
+    }
+
+    /// Request cancellation. Returns false if the operation already finished.
+    pub fn cancel(&self, id: &str) -> Result<bool> {
+        let entries = self.entries.lock();
+        let entry = entries
+            .get(id)
+            .ok_or_else(|| Error::NotFound(format!("operation {}", id)))?;
+
+        if !entry.info.cancellable {
+            return Err(Error::InvalidInput(format!(
+                "{:?} operation {} cannot be cancelled",
+                entry.info.kind, id
+            )));
+        }
+        if entry.info.status != OperationStatus::Running {
+            return Ok(false);
+        }
+
+        entry.token.cancel();
+        tracing::info!("Cancellation requested for operation {}", id);
+        Ok(true)
+    }
+
+    /// Request cancellation of every running, cancellable operation of
+    /// `kind`, whatever project it is on. Returns how many there were.
+    pub fn cancel_kind(&self, kind: OperationKind) -> usize {
+        let entries = self.entries.lock();
+        let running = entries
+            .values()
+            .filter(|e| e.info.kind == kind && e.info.cancellable && e.info.status == OperationStatus::Running);
+        let mut count = 0;
+        for entry in running {
+            entry.token.cancel();
+            tracing::info!("Cancellation requested for operation {}", entry.info.id);
+            count += 1;
+        }
+        count
+    }
+
+    /// Id of the running operation of `kind` on `project`, if any
+    pub fn find_running(&self, kind: OperationKind, project: &str) -> Option<String> {
+        self.entries
+            .lock()
+            .values()
+            .find(|e| {
+                e.info.kind == kind
+                    && e.info.status == OperationStatus::Running
+                    && e.info.project.as_deref() == Some(project)
+            })
+            .map(|e| e.info.id.clone())
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut OperationEntry)) {
+        if let Some(entry) = self.entries.lock().get_mut(id) {
+            f(entry);
+        }
+    }
+
+    fn prune(entries: &mut HashMap<String, OperationEntry>) {
+        entries.retain(|_, e| e.finished.is_none_or(|at| at.elapsed() < FINISHED_RETENTION));
+    }
+}
+
+/// Held by the command running an operation; marks it finished when done.
+///
+/// Dropping the handle without calling `finish` records the operation as
+/// failed (or cancelled, if that was requested), which covers early returns
+/// and panics in the task.
+pub struct OperationHandle {
+    id: String,
+    token: CancellationToken,
+    registry: OperationRegistry,
+    span: tracing::Span,
+    finished: bool,
+}
+
+impl OperationHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Span carrying the operation id; log lines emitted inside it can be
+    /// filtered by operation in `get_recent_logs`
+    pub fn span(&self) -> tracing::Span {
+        self.span.clone()
+    }
+
+    pub fn set_progress(&self, progress: f32, message: impl Into<String>) {
+        let message = message.into();
+        self.registry.update(&self.id, |e| {
+            e.info.progress = Some(progress.clamp(0.0, 1.0));
+            e.info.message = Some(message);
+        });
+    }
+
+    /// Attach phase timings; call before `finish`
+    pub fn set_timings(&self, timings: &[PhaseTiming]) {
+        self.registry.update(&self.id, |e| e.info.timings = timings.to_vec());
+    }
+
+    /// Record an operation that stopped on a cancel request but still
+    /// returns what it finished
+    pub fn finish_cancelled(mut self) {
+        self.mark(OperationStatus::Cancelled);
+    }
+
+    /// Record the outcome; errors after a cancel request count as cancelled
+    pub fn finish<T, E: std::fmt::Display>(mut self, result: &std::result::Result<T, E>) {
+        let status = match result {
+            Ok(_) => OperationStatus::Completed,
+            Err(_) if self.token.is_cancelled() => OperationStatus::Cancelled,
+            Err(e) => {
+                self.span.in_scope(|| tracing::warn!("Operation failed: {}", e));
+                OperationStatus::Failed
+            }
+        };
+        self.mark(status);
+    }
+
+    fn mark(&mut self, status: OperationStatus) {
+        self.finished = true;
+        let mut exclusive = false;
+        let mut finished_info = None;
+        self.registry.update(&self.id, |e| {
+            exclusive = e.info.exclusive;
+            e.info.status = status;
+            e.info.finished_at = Some(Utc::now());
+            if status == OperationStatus::Completed {
+                e.info.progress = Some(1.0);
+            }
+            e.finished = Some(Instant::now());
+            finished_info = Some(e.info.clone());
+        });
+        if let Some(info) = finished_info {
+            self.registry.last_finished.lock().insert(info.kind, info);
+        }
+        if exclusive {
+            self.registry.released.notify_waiters();
+        }
+        self.span.in_scope(|| tracing::debug!("Operation {} finished: {:?}", self.id, status));
+    }
+}
+
+impl Drop for OperationHandle {
+    fn drop(&mut self) {
+        if !self.finished {
+            let status = if self.token.is_cancelled() {
+                OperationStatus::Cancelled
+            } else {
+                OperationStatus::Failed
+            };
+            self.mark(status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_running_operation() {
+        let registry = OperationRegistry::new();
+        let handle = registry.start(OperationKind::Extraction, Some("/proj"));
+        let token = handle.token();
+        let id = handle.id().to_string();
+
+        assert_eq!(registry.find_running(OperationKind::Extraction, "/proj"), Some(id.clone()));
+        assert!(registry.cancel(&id).unwrap());
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(Error::Cancelled)));
+
+        handle.finish::<(), _>(&token.check());
+        let info = registry.get(&id).unwrap();
+        assert_eq!(info.status, OperationStatus::Cancelled);
+        assert!(info.finished_at.is_some());
+
+        // Finished operations stay listed for the grace period but can't be re-cancelled
+        assert_eq!(registry.list().len(), 1);
+        assert!(!registry.cancel(&id).unwrap());
+        assert!(registry.find_running(OperationKind::Extraction, "/proj").is_none());
+
+        let download = registry.start(OperationKind::Download, None);
+        assert_eq!(registry.cancel_kind(OperationKind::Download), 1);
+        assert!(download.token().is_cancelled());
+        assert_eq!(registry.cancel_kind(OperationKind::Extraction), 0);
+    }
+
+    #[test]
+    fn test_phase_timings_are_kept_in_order() {
+        let registry = OperationRegistry::new();
+        let handle = registry.start(OperationKind::Export, Some("/proj"));
+        let id = handle.id().to_string();
+
+        let mut timer = PhaseTimer::new();
+        for phase in ["repath", "check_wads", "pack"] {
+            timer.phase(phase);
+        }
+        let timings = timer.finish();
+        let phases: Vec<&str> = timings.iter().map(|t| t.phase.as_str()).collect();
+        assert_eq!(phases, ["repath", "check_wads", "pack"]);
+
+        handle.set_timings(&timings);
+        handle.finish::<(), Error>(&Ok(()));
+        assert_eq!(registry.get(&id).unwrap().timings, timings);
+
+        // Still available once the entry itself is gone
+        registry.entries.lock().clear();
+        let last = registry.last_finished();
+        assert_eq!((last.len(), last[0].id.as_str()), (1, id.as_str()));
+        assert_eq!(last[0].timings, timings);
+    }
+
+    #[test]
+    fn test_uncancellable_and_unknown_operations() {
+        let registry = OperationRegistry::new();
+        let handle = registry.start_uncancellable(OperationKind::Checkpoint, None);
+
+        assert!(matches!(registry.cancel(handle.id()), Err(Error::InvalidInput(_))));
+        assert!(!handle.token().is_cancelled());
+        assert!(matches!(registry.cancel("nope"), Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn test_exclusive_slot_is_per_project() {
+        let registry = OperationRegistry::new();
+        let repath = registry.try_start_exclusive(OperationKind::Repath, "/a", true).unwrap();
+
+        let err = registry.try_start_exclusive(OperationKind::Export, "/a", true).err().unwrap();
+        match err {
+            Error::Busy(msg) => assert!(msg.contains("repath") && msg.contains(repath.id())),
+            other => panic!("expected Busy, got {:?}", other),
+        }
+
+        // Other projects and read-only operations are unaffected
+        let other = registry.try_start_exclusive(OperationKind::Export, "/b", true).unwrap();
+        let _read = registry.start(OperationKind::Extraction, Some("/a"));
+
+        let status = registry.busy_status("/a");
+        assert!(status.busy);
+        assert_eq!(status.operation.unwrap().id, repath.id());
+
+        drop(repath);
+        drop(other);
+        assert!(!registry.busy_status("/a").busy);
+        assert!(registry.try_start_exclusive(OperationKind::Export, "/a", true).is_ok());
+    }
+
+    #[test]
+    fn test_exclusive_slot_ignores_path_spelling() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("project")).unwrap();
+        let plain = dir.path().join("project");
+        let dotted = dir.path().join(".").join("project").join("..").join("project");
+
+        let registry = OperationRegistry::new();
+        let _repath = registry.try_start_exclusive(OperationKind::Repath, plain.to_str().unwrap(), true).unwrap();
+
+        let err = registry.try_start_exclusive(OperationKind::Export, dotted.to_str().unwrap(), true).err();
+        assert!(matches!(err, Some(Error::Busy(_))));
+        assert!(registry.busy_status(dotted.to_str().unwrap()).busy);
+    }
+
+    #[tokio::test]
+    async fn test_queued_exclusive_waits_for_release() {
+        let registry = OperationRegistry::new();
+        let first = registry.try_start_exclusive(OperationKind::Repath, "/a", true).unwrap();
+
+        let waiter = {
+            let registry = registry.clone();
+            tokio::spawn(async move {
+                registry.start_exclusive(OperationKind::Export, "/a", true, true).await.map(|h| h.id().to_string())
+            })
+        };
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        first.finish::<(), Error>(&Ok(()));
+        let id = tokio::time::timeout(Duration::from_secs(5), waiter).await.unwrap().unwrap().unwrap();
+        assert_eq!(registry.get(&id).unwrap().kind, OperationKind::Export);
+    }
+
+    #[test]
+    fn test_progress_and_drop_without_finish() {
+        let registry = OperationRegistry::new();
+        let handle = registry.start(OperationKind::Export, None);
+        let id = handle.id().to_string();
+
+        handle.set_progress(1.5, "packing");
+        let info = registry.get(&id).unwrap();
+        assert_eq!(info.progress, Some(1.0));
+        assert_eq!(info.message.as_deref(), Some("packing"));
+        assert_eq!(info.status, OperationStatus::Running);
+
+        drop(handle);
+        assert_eq!(registry.get(&id).unwrap().status, OperationStatus::Failed);
+
+        let handle = registry.start(OperationKind::Export, None);
+        let id = handle.id().to_string();
+        registry.cancel(&id).unwrap();
+        drop(handle);
+        assert_eq!(registry.get(&id).unwrap().status, OperationStatus::Cancelled);
+    }
+}
@@ -0,0 +1,184 @@
+//! Shared limits for parallel work
+//!
+//! Heavy loops run on a Flint-wide rayon pool via [`install`] instead of
+//! rayon's global pool, and IO-heavy steps additionally hold an [`io_permit`].
+//! Both follow the `performance` settings; a change builds a new pool for
+//! operations started afterwards while running ones finish on the old one.
+
+use crate::core::settings::PerformanceSettings;
+use parking_lot::{Condvar, Mutex, RwLock};
+use std::sync::Arc;
+
+/// Effective limits derived from `PerformanceSettings`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelLimits {
+    pub threads: usize,
+    pub io_tasks: usize,
+}
+
+impl ParallelLimits {
+    /// Resolve "auto" (0) values against the machine's core count.
+    /// Low-priority mode uses at most half the cores so the machine stays responsive.
+    pub fn from_settings(settings: &PerformanceSettings, available: usize) -> Self {
+        let available = available.max(1);
+        let ceiling = if settings.low_priority { (available / 2).max(1) } else { available };
+
+        let threads = match settings.max_threads {
+            0 => ceiling,
+            n => n.min(ceiling),
+        };
+        let io_tasks = match settings.max_io_tasks {
+            0 => threads,
+            n => n,
+        };
+        Self { threads, io_tasks: io_tasks.max(1) }
+    }
+}
+
+struct Pool {
+    threads: usize,
+    pool: Arc<rayon::ThreadPool>,
+}
+
+static LIMITS: RwLock<Option<ParallelLimits>> = RwLock::new(None);
+static POOL: Mutex<Option<Pool>> = Mutex::new(None);
+static IO_SLOTS: IoSemaphore = IoSemaphore::new();
+
+fn available_cores() -> usize {
+    std::thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+/// Apply new performance settings to operations started from now on
+pub fn configure(settings: &PerformanceSettings) {
+    let limits = ParallelLimits::from_settings(settings, available_cores());
+    tracing::info!("Parallel limits: {} threads, {} IO tasks", limits.threads, limits.io_tasks);
+    *LIMITS.write() = Some(limits);
+    IO_SLOTS.notify();
+}
+
+pub fn limits() -> ParallelLimits {
+    LIMITS
+        .read()
+        .unwrap_or_else(|| ParallelLimits::from_settings(&PerformanceSettings::default(), available_cores()))
+}
+
+/// The pool for the current limits, rebuilt if the thread count changed
+fn pool() -> Arc<rayon::ThreadPool> {
+    let threads = limits().threads;
+    let mut current = POOL.lock();
+    if let Some(pool) = current.as_ref().filter(|p| p.threads == threads) {
+        return Arc::clone(&pool.pool);
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("flint-worker-{}", i))
+        .build()
+        .map(Arc::new)
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to build worker pool ({}), using a single thread", e);
+            Arc::new(rayon::ThreadPoolBuilder::new().num_threads(1).build().expect("single-thread pool"))
+        });
+    *current = Some(Pool { threads, pool: Arc::clone(&pool) });
+    pool
+}
+
+/// Run `f` on the shared worker pool; `par_iter` calls inside it are bound by
+/// the configured thread limit
+pub fn install<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+    pool().install(f)
+}
+
+/// Wait for a slot among the IO-heavy tasks (file reads and writes of whole
+/// assets). The slot is released when the permit is dropped.
+///
+/// Hold it only around leaf work, never across nested parallel calls: a
+/// worker blocked in a stolen task could otherwise wait on its own permit.
+pub fn io_permit() -> IoPermit {
+    IO_SLOTS.acquire(|| limits().io_tasks);
+    IoPermit(())
+}
+
+pub struct IoPermit(());
+
+impl Drop for IoPermit {
+    fn drop(&mut self) {
+        IO_SLOTS.release();
+    }
+}
+
+/// Counting semaphore whose limit is read on every acquire, so lowering the
+/// limit takes effect as running tasks finish
+struct IoSemaphore {
+    in_use: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl IoSemaphore {
+    const fn new() -> Self {
+        Self { in_use: Mutex::new(0), freed: Condvar::new() }
+    }
+
+    fn acquire(&self, limit: impl Fn() -> usize) {
+        let mut in_use = self.in_use.lock();
+        while *in_use >= limit() {
+            self.freed.wait(&mut in_use);
+        }
+        *in_use += 1;
+    }
+
+    fn release(&self) {
+        *self.in_use.lock() -= 1;
+        self.freed.notify_one();
+    }
+
+    /// Wake waiters after the limit changed
+    fn notify(&self) {
+        self.freed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_limits_from_settings() {
+        let auto = PerformanceSettings::default();
+        assert_eq!(ParallelLimits::from_settings(&auto, 8), ParallelLimits { threads: 8, io_tasks: 8 });
+
+        let capped = PerformanceSettings { max_threads: 16, max_io_tasks: 2, ..Default::default() };
+        assert_eq!(ParallelLimits::from_settings(&capped, 8), ParallelLimits { threads: 8, io_tasks: 2 });
+
+        let low = PerformanceSettings { low_priority: true, ..Default::default() };
+        assert_eq!(ParallelLimits::from_settings(&low, 8), ParallelLimits { threads: 4, io_tasks: 4 });
+        assert_eq!(ParallelLimits::from_settings(&low, 1), ParallelLimits { threads: 1, io_tasks: 1 });
+    }
+
+    #[test]
+    fn test_io_semaphore_bounds_concurrency() {
+        let semaphore = Arc::new(IoSemaphore::new());
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let (semaphore, active, peak) = (semaphore.clone(), active.clone(), peak.clone());
+                std::thread::spawn(move || {
+                    semaphore.acquire(|| 2);
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    semaphore.release();
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+}
@@ -0,0 +1,97 @@
+//! Joining untrusted relative paths
+//!
+//! Checkpoint zips and manifests, quarantine records, path mappings, WAD
+//! hash lists and BIN strings all name files relative to some directory, and
+//! any of them can come from someone else. `safe_join` is how such a name
+//! becomes a path to write to: it refuses anything that could land outside
+//! the base directory or that Windows can't create.
+
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// Device names Windows reserves in every directory, with any extension
+const RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9", "lpt1",
+    "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Join `relative` (with `/` or `\` separators) onto `base`, rejecting
+/// absolute paths, drive letters, `..` components and reserved Windows names.
+/// Empty and `.` components are dropped.
+pub fn safe_join(base: &Path, relative: &str) -> Result<PathBuf> {
+    Ok(base.join(safe_relative(relative)?))
+}
+
+/// Check an untrusted relative path, returning it as a `PathBuf` of its
+/// components
+pub fn safe_relative(relative: &str) -> Result<PathBuf> {
+    let unsafe_path = |reason: &str| Error::UnsafePath { path: relative.to_string(), reason: reason.to_string() };
+
+    if relative.starts_with(['/', '\\']) {
+        return Err(unsafe_path("absolute path"));
+    }
+    if relative.contains('\0') {
+        return Err(unsafe_path("contains a NUL character"));
+    }
+
+    let mut path = PathBuf::new();
+    for component in relative.split(['/', '\\']) {
+        match component {
+            "" | "." => continue,
+            ".." => return Err(unsafe_path("leaves its directory")),
+            _ if component.contains(':') => return Err(unsafe_path("drive letter or stream name")),
+            _ if is_reserved_name(component) => return Err(unsafe_path("reserved Windows name")),
+            _ => path.push(component),
+        }
+    }
+    if path.as_os_str().is_empty() {
+        return Err(unsafe_path("empty path"));
+    }
+    Ok(path)
+}
+
+/// `CON`, `nul.txt`, `com1.tar.gz`, ... Windows also ignores trailing dots
+/// and spaces, so `aux. ` counts too.
+fn is_reserved_name(component: &str) -> bool {
+    let stem = component.split('.').next().unwrap_or(component).trim_end_matches([' ', '.']);
+    RESERVED_NAMES.iter().any(|name| stem.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_join() {
+        let base = Path::new("/project");
+        assert_eq!(
+            safe_join(base, "assets/Characters\\Ahri/./ahri.dds").unwrap(),
+            base.join("assets").join("Characters").join("Ahri").join("ahri.dds")
+        );
+        assert_eq!(safe_join(base, "data//skin0.bin").unwrap(), base.join("data").join("skin0.bin"));
+        assert_eq!(safe_join(base, "console.dds").unwrap(), base.join("console.dds"));
+
+        for hostile in [
+            "../outside.txt",
+            "assets/../../outside.txt",
+            "..\\..\\outside.txt",
+            "/etc/passwd",
+            "\\\\server\\share\\file",
+            "C:\\Windows\\system32\\x.dll",
+            "c:relative.txt",
+            "assets/file.txt:stream",
+            "assets/CON",
+            "assets/nul.txt",
+            "assets/Com1.tar.gz",
+            "assets/aux. ",
+            "assets/a\0b",
+            "",
+            "./.",
+        ] {
+            match safe_join(base, hostile) {
+                Err(Error::UnsafePath { path, .. }) => assert_eq!(path, hostile),
+                other => panic!("{:?} was accepted: {:?}", hostile, other),
+            }
+        }
+    }
+}
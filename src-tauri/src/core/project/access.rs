@@ -0,0 +1,110 @@
+//! Projects on read-only media
+//!
+//! A project opened from a network share, a backup disc or another user's
+//! folder can be inspected but not changed. `is_writable` probes the project
+//! directory by creating and removing a file; mutating commands call
+//! `ensure_writable` before they touch anything, so they fail with
+//! `Error::ProjectReadOnly` instead of an IO error halfway through. Caches
+//! that reading normally writes next to the project fall back to memory.
+
+use crate::core::project::find_project_root;
+use crate::error::{Error, Result};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+/// Text kept in memory for BINs whose `.ritobin` cache can't be written
+const TEXT_FALLBACK_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Whether files can be created in `dir`. A directory that doesn't exist
+/// counts as writable; whatever needs it reports that itself.
+pub fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".flint-write-probe-{}", uuid::Uuid::new_v4().simple()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(file) => {
+            drop(file);
+            if let Err(e) = std::fs::remove_file(&probe) {
+                tracing::warn!("Failed to remove write probe {}: {}", probe.display(), e);
+            }
+            true
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => true,
+        Err(e) => {
+            tracing::debug!("{} is not writable: {}", dir.display(), e);
+            false
+        }
+    }
+}
+
+/// Fail with `Error::ProjectReadOnly` if the project directory can't be written
+pub fn ensure_writable(project_path: &Path) -> Result<()> {
+    if is_writable(project_path) {
+        Ok(())
+    } else {
+        Err(Error::ProjectReadOnly(project_path.to_path_buf()))
+    }
+}
+
+/// `ensure_writable` for the project containing `path`, or for `path`'s
+/// directory when it isn't in a project
+pub fn ensure_path_writable(path: &Path) -> Result<()> {
+    match find_project_root(path) {
+        Some(root) => ensure_writable(&root),
+        None => match path.parent().filter(|_| path.is_file()).unwrap_or(path) {
+            dir if is_writable(dir) => Ok(()),
+            dir => Err(Error::ProjectReadOnly(dir.to_path_buf())),
+        },
+    }
+}
+
+type TextFallback = Mutex<HashMap<PathBuf, (SystemTime, String)>>;
+
+fn text_fallback() -> &'static TextFallback {
+    static TEXT_FALLBACK: OnceLock<TextFallback> = OnceLock::new();
+    TEXT_FALLBACK.get_or_init(Default::default)
+}
+
+/// Converted text of `bin_path` kept in memory, if it is as new as `modified`
+pub fn fallback_text(bin_path: &Path, modified: SystemTime) -> Option<String> {
+    let cache = text_fallback().lock();
+    let (cached_at, text) = cache.get(bin_path)?;
+    (*cached_at >= modified).then(|| text.clone())
+}
+
+/// Keep `text` for `bin_path` in memory after its `.ritobin` cache couldn't
+/// be written. Everything is dropped once the total passes the limit.
+pub fn store_fallback_text(bin_path: &Path, modified: SystemTime, text: &str) {
+    let mut cache = text_fallback().lock();
+    let total: usize = cache.values().map(|(_, text)| text.len()).sum();
+    if total + text.len() > TEXT_FALLBACK_LIMIT {
+        cache.clear();
+    }
+    cache.insert(bin_path.to_path_buf(), (modified, text.to_string()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_writable_probe_leaves_nothing_behind() {
+        let dir = tempdir().unwrap();
+        assert!(is_writable(dir.path()));
+        assert!(ensure_writable(dir.path()).is_ok());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+        assert!(is_writable(&dir.path().join("missing")));
+    }
+
+    #[test]
+    fn test_fallback_text_goes_stale() {
+        let path = Path::new("/read-only/skin0.bin");
+        let then = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(100);
+        store_fallback_text(path, then, "text");
+        assert_eq!(fallback_text(path, then).as_deref(), Some("text"));
+        assert!(fallback_text(path, then + std::time::Duration::from_secs(1)).is_none());
+    }
+}
@@ -0,0 +1,379 @@
+//! Hash names a project uses, saved with it
+//!
+//! Hash lists differ between machines, so a BIN converted where a name is
+//! missing shows it as hex and text diffs between collaborators don't line
+//! up. `collect_project_hashes` gathers every name the local lists resolve
+//! for the project's files and BINs; the project carries them in
+//! `project.hashes.txt`, and opening the project merges them back in.
+
+use crate::core::bin::{add_bin_hash_overlay, read_bin_ltk as read_bin, PropertyValueEnum};
+use crate::core::hash::Hashtable;
+use crate::core::mapping::normalize_path;
+use crate::core::operation::CancellationToken;
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::WadName;
+use crate::error::{Error, Result};
+use ltk_ritobin::HashProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Saved hash names, in the project folder
+pub const PROJECT_HASH_FILE: &str = "project.hashes.txt";
+
+/// Names by the list they belong to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectHashes {
+    /// WAD path hash -> path
+    pub paths: BTreeMap<u64, String>,
+    /// BIN object paths
+    pub entries: BTreeMap<u32, String>,
+    /// BIN property names
+    pub fields: BTreeMap<u32, String>,
+    /// BIN hash values
+    pub hashes: BTreeMap<u32, String>,
+    /// BIN class names
+    pub types: BTreeMap<u32, String>,
+}
+
+/// Result of exporting a project's hashes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectHashExport {
+    /// The written file
+    pub path: String,
+    pub paths: usize,
+    /// BIN names over all four lists
+    pub bin_names: usize,
+    /// Hashes the local lists don't know either
+    pub unresolved: usize,
+    /// BINs that couldn't be read
+    pub unreadable: Vec<String>,
+}
+
+/// BIN list names with their section in the file and their file in a hash
+/// directory
+const BIN_SECTIONS: [(&str, &str); 4] = [
+    ("entries", "hashes.binentries.txt"),
+    ("fields", "hashes.binfields.txt"),
+    ("hashes", "hashes.binhashes.txt"),
+    ("types", "hashes.bintypes.txt"),
+];
+
+impl ProjectHashes {
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.paths.len() + self.bin_len()
+    }
+
+    fn bin_len(&self) -> usize {
+        self.entries.len() + self.fields.len() + self.hashes.len() + self.types.len()
+    }
+
+    fn bin_lists(&self) -> [&BTreeMap<u32, String>; 4] {
+        [&self.entries, &self.fields, &self.hashes, &self.types]
+    }
+
+    /// The file: "hex name" lines under `# paths`, `# entries`, `# fields`,
+    /// `# hashes` and `# types` headers. Hash list readers skip the headers
+    /// as comments.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# paths\n");
+        for (hash, path) in &self.paths {
+            text.push_str(&format!("{:016x} {}\n", hash, path));
+        }
+        for ((section, _), list) in BIN_SECTIONS.iter().zip(self.bin_lists()) {
+            text.push_str(&format!("# {}\n", section));
+            for (hash, name) in list {
+                text.push_str(&format!("{:08x} {}\n", hash, name));
+            }
+        }
+        text
+    }
+
+    /// Parse `to_text` output; unknown sections and malformed lines are skipped
+    pub fn parse(text: &str) -> Self {
+        let mut parsed = Self::default();
+        let mut section = "";
+        for line in text.lines().map(str::trim) {
+            if let Some(header) = line.strip_prefix('#') {
+                section = header.trim();
+                continue;
+            }
+            let Some((hex, name)) = line.split_once(' ') else {
+                continue;
+            };
+            let Ok(hash) = u64::from_str_radix(hex, 16) else {
+                continue;
+            };
+            let name = name.trim().to_string();
+            let list = match section {
+                "paths" => {
+                    parsed.paths.insert(hash, name);
+                    continue;
+                }
+                "entries" => &mut parsed.entries,
+                "fields" => &mut parsed.fields,
+                "hashes" => &mut parsed.hashes,
+                "types" => &mut parsed.types,
+                _ => continue,
+            };
+            if let Ok(hash) = u32::try_from(hash) {
+                list.insert(hash, name);
+            }
+        }
+        parsed
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| Error::io_with_path(e, path))?;
+        Ok(Self::parse(&text))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_text()).map_err(|e| Error::io_with_path(e, path))
+    }
+
+    /// Write the BIN names as the four BIN lists in `dir`, for loaders that
+    /// read a hash directory
+    pub fn write_bin_lists(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir).map_err(|e| Error::io_with_path(e, dir))?;
+        for ((_, file), list) in BIN_SECTIONS.iter().zip(self.bin_lists()) {
+            let text: String = list.iter().map(|(hash, name)| format!("{:08x} {}\n", hash, name)).collect();
+            let path = dir.join(file);
+            fs::write(&path, text).map_err(|e| Error::io_with_path(e, &path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Names the local lists resolve for the files under `content_base` and
+/// the hashes in its BINs, with the count of distinct hashes they don't
+/// resolve and the BINs that couldn't be read
+pub fn collect_project_hashes<H: HashProvider>(
+    content_base: &Path,
+    hashtable: Option<&Hashtable>,
+    bin_hashes: &H,
+    cancel: &CancellationToken,
+) -> Result<(ProjectHashes, usize, Vec<String>)> {
+    let mut collector = Collector { names: ProjectHashes::default(), unresolved: BTreeSet::new(), hashes: bin_hashes };
+    let mut unreadable = Vec::new();
+    let wad_dirs = fs::read_dir(content_base)
+        .map_err(|e| Error::io_with_path(e, content_base))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && p.file_name().and_then(|n| n.to_str()).and_then(WadName::parse).is_some());
+
+    for wad_dir in wad_dirs {
+        for file in WalkDir::new(&wad_dir).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+            if !file.file_type().is_file() {
+                continue;
+            }
+            cancel.check()?;
+            let Ok(rel_path) = file.path().strip_prefix(&wad_dir) else {
+                continue;
+            };
+            let rel_path = normalize_path(&rel_path.to_string_lossy());
+            collector.path(&rel_path, hashtable);
+
+            if !rel_path.ends_with(".bin") {
+                continue;
+            }
+            let bin = fs::read(file.path())
+                .map_err(|e| Error::io_with_path(e, file.path()))
+                .and_then(|data| read_bin(&data).map_err(|e| Error::InvalidInput(e.to_string())));
+            match bin {
+                Ok(bin) => {
+                    for (path_hash, object) in &bin.objects {
+                        collector.entry(*path_hash);
+                        collector.bin_type(object.class_hash);
+                        for (name_hash, prop) in &object.properties {
+                            collector.field(*name_hash);
+                            collector.value(&prop.value);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::debug!("Skipping {} for project hashes: {}", file.path().display(), e);
+                    unreadable.push(rel_path);
+                }
+            }
+        }
+    }
+
+    tracing::info!(
+        "Collected {} project hash names under {} ({} unresolved)",
+        collector.names.len(),
+        content_base.display(),
+        collector.unresolved.len()
+    );
+    Ok((collector.names, collector.unresolved.len(), unreadable))
+}
+
+/// Collect and write the project's hashes to `PROJECT_HASH_FILE` in
+/// `project_path`
+pub fn export_project_hashes<H: HashProvider>(
+    project_path: &Path,
+    content_base: &Path,
+    hashtable: Option<&Hashtable>,
+    bin_hashes: &H,
+    cancel: &CancellationToken,
+) -> Result<ProjectHashExport> {
+    let (names, unresolved, unreadable) = collect_project_hashes(content_base, hashtable, bin_hashes, cancel)?;
+    let path = project_path.join(PROJECT_HASH_FILE);
+    names.write(&path)?;
+    Ok(ProjectHashExport {
+        path: path.to_string_lossy().to_string(),
+        paths: names.paths.len(),
+        bin_names: names.bin_len(),
+        unresolved,
+        unreadable,
+    })
+}
+
+/// Load the BIN names saved with the project at `project_path` into the BIN
+/// hash cache. Returns the file for the WAD path hashtable to merge, or
+/// `None` if the project has none.
+pub fn load_project_hashes(project_path: &Path) -> Result<Option<PathBuf>> {
+    let path = project_path.join(PROJECT_HASH_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let names = ProjectHashes::read(&path)?;
+    // The BIN cache only loads whole hash directories
+    let overlay_dir = std::env::temp_dir()
+        .join("flint-project-hashes")
+        .join(format!("{:016x}", compute_path_hash(&project_path.to_string_lossy())));
+    names.write_bin_lists(&overlay_dir)?;
+    add_bin_hash_overlay(overlay_dir);
+    tracing::info!("Loaded {} hash names saved with {}", names.len(), project_path.display());
+    Ok(Some(path))
+}
+
+struct Collector<'a, H> {
+    names: ProjectHashes,
+    /// (list, hash) of hashes nothing resolved
+    unresolved: BTreeSet<(u8, u64)>,
+    hashes: &'a H,
+}
+
+impl<H: HashProvider> Collector<'_, H> {
+    /// A file in a WAD folder: its own path, or the path the hashtable has
+    /// for a file named by its hash
+    fn path(&mut self, rel_path: &str, hashtable: Option<&Hashtable>) {
+        let stem = rel_path.split_once('.').map_or(rel_path, |(stem, _)| stem);
+        if !rel_path.contains('/') && stem.len() == 16 {
+            if let Ok(hash) = u64::from_str_radix(stem, 16) {
+                match hashtable.and_then(|ht| ht.get(hash)) {
+                    Some(path) => {
+                        self.names.paths.insert(hash, path.to_string());
+                    }
+                    None => {
+                        self.unresolved.insert((0, hash));
+                    }
+                }
+                return;
+            }
+        }
+        self.names.paths.insert(compute_path_hash(rel_path), rel_path.to_string());
+    }
+
+    fn entry(&mut self, hash: u32) {
+        let name = self.hashes.lookup_entry(hash);
+        Self::record(&mut self.names.entries, &mut self.unresolved, 1, hash, name);
+    }
+
+    fn field(&mut self, hash: u32) {
+        let name = self.hashes.lookup_field(hash);
+        Self::record(&mut self.names.fields, &mut self.unresolved, 2, hash, name);
+    }
+
+    fn hash(&mut self, hash: u32) {
+        let name = self.hashes.lookup_hash(hash);
+        Self::record(&mut self.names.hashes, &mut self.unresolved, 3, hash, name);
+    }
+
+    fn bin_type(&mut self, hash: u32) {
+        let name = self.hashes.lookup_type(hash);
+        Self::record(&mut self.names.types, &mut self.unresolved, 4, hash, name);
+    }
+
+    fn record(
+        list: &mut BTreeMap<u32, String>,
+        unresolved: &mut BTreeSet<(u8, u64)>,
+        kind: u8,
+        hash: u32,
+        name: Option<&str>,
+    ) {
+        match name {
+            Some(name) => {
+                list.insert(hash, name.to_string());
+            }
+            None => {
+                unresolved.insert((kind, hash as u64));
+            }
+        }
+    }
+
+    fn value(&mut self, value: &PropertyValueEnum) {
+        match value {
+            PropertyValueEnum::Hash(h) => self.hash(h.0),
+            // Null links are 0, not a name
+            PropertyValueEnum::ObjectLink(link) if link.0 != 0 => self.entry(link.0),
+            PropertyValueEnum::Container(c) => c.items.iter().for_each(|item| self.value(item)),
+            PropertyValueEnum::UnorderedContainer(c) => c.0.items.iter().for_each(|item| self.value(item)),
+            PropertyValueEnum::Struct(s) => {
+                self.bin_type(s.class_hash);
+                for (name_hash, prop) in &s.properties {
+                    self.field(*name_hash);
+                    self.value(&prop.value);
+                }
+            }
+            PropertyValueEnum::Embedded(e) => {
+                self.bin_type(e.0.class_hash);
+                for (name_hash, prop) in &e.0.properties {
+                    self.field(*name_hash);
+                    self.value(&prop.value);
+                }
+            }
+            PropertyValueEnum::Optional(o) => {
+                if let Some(inner) = &o.value {
+                    self.value(inner);
+                }
+            }
+            PropertyValueEnum::Map(m) => {
+                for (key, val) in &m.entries {
+                    self.value(&key.0);
+                    self.value(val);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut names = ProjectHashes::default();
+        names.paths.insert(compute_path_hash("assets/a.tex"), "assets/a.tex".to_string());
+        names.entries.insert(0x1a2b, "Characters/Ahri/Skins/Skin0".to_string());
+        names.fields.insert(0xffff_0000, "mSpellName".to_string());
+        names.types.insert(0x10, "SkinCharacterDataProperties".to_string());
+
+        let text = names.to_text();
+        assert!(text.contains("\n# fields\nffff0000 mSpellName\n# hashes\n# types\n00000010"));
+        assert_eq!(ProjectHashes::parse(&text), names);
+
+        let dir = tempdir().unwrap();
+        names.write_bin_lists(dir.path()).unwrap();
+        let entries = fs::read_to_string(dir.path().join("hashes.binentries.txt")).unwrap();
+        assert_eq!(entries, "00001a2b Characters/Ahri/Skins/Skin0\n");
+        assert_eq!(fs::read_to_string(dir.path().join("hashes.binhashes.txt")).unwrap(), "");
+    }
+}
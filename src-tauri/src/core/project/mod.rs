@@ -1,6 +1,9 @@
 // Project management module exports
 #[allow(clippy::module_inception)]
 pub mod project;
+pub mod setup;
+pub mod access;
+pub mod hashes;
 
 // Re-export from ltk_mod_project for league-mod compatibility
 #[allow(unused_imports)]
@@ -9,4 +12,7 @@ pub use ltk_mod_project::{
     ModProjectLicense, FileTransformer, default_layers
 };
 #[allow(unused_imports)]
-pub use project::{create_project, open_project, save_project, Project, FlintMetadata};
+pub use project::{create_project, find_project_root, open_project, save_project, Project, FlintMetadata};
+pub use setup::{setup_project_content, SetupOptions, SetupReport, SetupTarget};
+pub use access::{ensure_path_writable, ensure_writable, is_writable};
+pub use hashes::{export_project_hashes, load_project_hashes, ProjectHashExport};
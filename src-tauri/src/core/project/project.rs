@@ -3,6 +3,7 @@
 //! This module provides data structures and logic for creating, loading,
 //! and saving Flint mod projects using the league-mod compatible format.
 
+use crate::core::champion::fallback_skin_name;
 use crate::error::{Error, Result};
 use chrono::{DateTime, Utc};
 use ltk_mod_project::{ModProject, ModProjectAuthor, ModProjectLayer, default_layers};
@@ -35,6 +36,11 @@ pub struct FlintMetadata {
 
     /// When the project was last modified (ISO 8601)
     pub modified_at: DateTime<Utc>,
+
+    /// Repath prefix ("creator/project") of the mod this project's content
+    /// came from, if it had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreign_prefix: Option<String>,
 }
 
 impl FlintMetadata {
@@ -48,6 +54,7 @@ impl FlintMetadata {
             league_path,
             created_at: now,
             modified_at: now,
+            foreign_prefix: None,
         }
     }
 }
@@ -97,6 +104,14 @@ pub struct Project {
     /// Path to the project directory
     #[serde(default)]
     pub project_path: PathBuf,
+
+    /// The project directory can't be written to; set by the open command
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// Repath prefix of the mod the content came from - Flint specific
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreign_prefix: Option<String>,
     
     /// When the project was created
     #[serde(skip)]
@@ -121,32 +136,37 @@ impl Project {
         let name_str = name.into();
         let champion_str = champion.into();
         
-        // Create display name from champion and skin
-        let _display_name = if skin_id == 0 {
-            format!("{} Base Skin", champion_str)
-        } else {
-            format!("{} Skin {}", champion_str, skin_id)
-        };
+        // Numeric until `set_skin_name` supplies the real name
+        let display_name = fallback_skin_name(&champion_str, skin_id);
         
         // Store author as simple string
         let authors = author.into_iter().collect::<Vec<_>>();
         
         Self {
             name: slugify(&name_str),
-            display_name: name_str,
+            description: format!("Mod for {}", display_name),
+            display_name,
             version: "0.1.0".to_string(),
-            description: format!("Mod for {} skin {}", champion_str, skin_id),
             layers: default_layers(),
             authors,
             champion: champion_str,
             skin_id,
             league_path: Some(league_path.into()),
             project_path: project_path.into(),
+            read_only: false,
+            foreign_prefix: None,
             created_at: now,
             modified_at: now,
         }
     }
     
+    /// Use the skin's resolved name (e.g. "Star Guardian Ahri") for the
+    /// display name and description
+    pub fn set_skin_name(&mut self, skin_name: &str) {
+        self.display_name = skin_name.to_string();
+        self.description = format!("Mod for {}", skin_name);
+    }
+
     /// Convert to ltk_mod_project::ModProject for export compatibility
     pub fn to_mod_project(&self) -> ModProject {
         ModProject {
@@ -170,6 +190,7 @@ impl Project {
             league_path: self.league_path.clone(),
             created_at: self.created_at,
             modified_at: self.modified_at,
+            foreign_prefix: self.foreign_prefix.clone(),
         }
     }
 
@@ -215,6 +236,7 @@ impl Project {
 /// * `league_path` - Path to League installation
 /// * `output_dir` - Directory where project folder will be created
 /// * `author` - Optional author/creator name
+/// * `skin_name` - Resolved skin name for the display name; numeric when `None`
 pub fn create_project(
     name: &str,
     champion: &str,
@@ -222,6 +244,7 @@ pub fn create_project(
     league_path: &Path,
     output_dir: &Path,
     author: Option<String>,
+    skin_name: Option<&str>,
 ) -> Result<Project> {
     tracing::info!("Creating project '{}' for {} skin {}", name, champion, skin_id);
 
@@ -259,7 +282,7 @@ pub fn create_project(
     }
 
     // Create project
-    let project = Project::new(
+    let mut project = Project::new(
         name,
         champion,
         skin_id,
@@ -267,6 +290,9 @@ pub fn create_project(
         &project_path,
         author,
     );
+    if let Some(skin_name) = skin_name {
+        project.set_skin_name(skin_name);
+    }
 
     // Create directories
     fs::create_dir_all(&project_path)
@@ -286,6 +312,12 @@ pub fn create_project(
     Ok(project)
 }
 
+/// The project directory containing `path`, i.e. the nearest ancestor with
+/// a mod.config.json
+pub fn find_project_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|dir| dir.join(PROJECT_FILE).is_file()).map(Path::to_path_buf)
+}
+
 /// Opens an existing project from a path
 ///
 /// # Arguments
@@ -330,6 +362,7 @@ pub fn open_project(path: &Path) -> Result<Project> {
                 project.league_path = flint.league_path;
                 project.created_at = flint.created_at;
                 project.modified_at = flint.modified_at;
+                project.foreign_prefix = flint.foreign_prefix;
             }
         }
     }
@@ -412,7 +445,7 @@ mod tests {
         );
 
         assert_eq!(project.name, "test-project");
-        assert_eq!(project.display_name, "Test Project");
+        assert_eq!(project.display_name, "Ahri");
         assert_eq!(project.champion, "Ahri");
         assert_eq!(project.skin_id, 0);
         assert_eq!(project.layers.len(), 1);
@@ -446,6 +479,13 @@ mod tests {
         assert_eq!(mod_project.version, project.version);
     }
 
+    #[test]
+    fn test_numeric_display_name() {
+        let project = Project::new("Test", "MasterYi", 85, "C:\\League", "C:\\test", None);
+        assert_eq!(project.display_name, "Master Yi Skin 85");
+        assert_eq!(project.description, "Mod for Master Yi Skin 85");
+    }
+
     #[test]
     fn test_flint_metadata() {
         let project = Project::new("Test", "Ahri", 5, "C:\\League", "C:\\test", None);
@@ -499,9 +539,12 @@ mod tests {
             &league_dir,
             temp_dir.path(),
             None,
+            Some("Star Guardian Ahri"),
         ).unwrap();
 
-        assert_eq!(project.display_name, "Test Project");
+        assert_eq!(project.name, "test-project");
+        assert_eq!(project.display_name, "Star Guardian Ahri");
+        assert_eq!(project.description, "Mod for Star Guardian Ahri");
         assert!(project.project_path.exists());
         assert!(project.assets_path().exists());
         assert!(project.output_path().exists());
@@ -518,17 +561,37 @@ mod tests {
         assert_eq!(loaded.skin_id, project.skin_id);
     }
 
+    #[test]
+    fn test_mod_config_format() {
+        let temp_dir = tempdir().unwrap();
+        let project = create_project("Test", "Ahri", 3, temp_dir.path(), temp_dir.path(), None, None).unwrap();
+
+        // mod.config.json stays league-mod's format; Flint's own fields live in flint.json
+        let config: serde_json::Value = serde_json::from_slice(&fs::read(project.config_path()).unwrap()).unwrap();
+        for key in ["name", "display_name", "version", "description", "authors", "layers"] {
+            assert!(config.get(key).is_some(), "mod.config.json is missing '{}'", key);
+        }
+        for key in ["champion", "skin_id", "league_path", "project_path"] {
+            assert!(config.get(key).is_none(), "mod.config.json has Flint field '{}'", key);
+        }
+        assert_eq!(config["layers"][0]["name"], "base");
+
+        let flint: serde_json::Value = serde_json::from_slice(&fs::read(project.flint_path()).unwrap()).unwrap();
+        assert_eq!(flint["champion"], "Ahri");
+        assert_eq!(flint["skin_id"], 3);
+    }
+
     #[test]
     fn test_create_project_empty_name() {
         let temp_dir = tempdir().unwrap();
-        let result = create_project("", "Ahri", 0, temp_dir.path(), temp_dir.path(), None);
+        let result = create_project("", "Ahri", 0, temp_dir.path(), temp_dir.path(), None, None);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_create_project_empty_champion() {
         let temp_dir = tempdir().unwrap();
-        let result = create_project("Test", "", 0, temp_dir.path(), temp_dir.path(), None);
+        let result = create_project("Test", "", 0, temp_dir.path(), temp_dir.path(), None, None);
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,319 @@
+//! One-step content setup for a new project
+//!
+//! Chains the steps a new project needs, in order: extract the champion's
+//! assets into `content/base`, concat/repath the skin BINs, pre-convert BINs
+//! to `.ritobin`, and take an initial checkpoint. Preconversion runs after
+//! the organize step so it caches the BINs that step rewrote.
+//!
+//! The first failing step stops the pipeline; the report says which phase it
+//! was and what the earlier phases left on disk.
+
+use crate::core::bin::preconvert_bins;
+use crate::core::checkpoint::{CheckpointManager, CheckpointProgress};
+use crate::core::diagnostics::save_project_report;
+use crate::core::hash::Hashtable;
+use crate::core::operation::CancellationToken;
+use crate::core::project::Project;
+use crate::core::repath::{organize_project, MapOverrideMode, OrganizerConfig};
+use crate::core::wad::extractor::extract_skin_assets;
+use crate::core::wad::reader::WadReader;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Report file inside `PROJECT_REPORTS_DIR`
+pub const SETUP_REPORT_FILE: &str = "setup.json";
+
+/// Which optional steps to run; extraction always runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SetupOptions {
+    pub concat: bool,
+    pub repath: bool,
+    /// Repath prefix creator; repath is skipped without one
+    pub creator_name: Option<String>,
+    pub preconvert: bool,
+    pub checkpoint: bool,
+}
+
+impl Default for SetupOptions {
+    fn default() -> Self {
+        Self {
+            concat: true,
+            repath: true,
+            creator_name: None,
+            preconvert: true,
+            checkpoint: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupPhase {
+    Extract,
+    Organize,
+    Preconvert,
+    Checkpoint,
+}
+
+impl SetupPhase {
+    pub const ALL: [SetupPhase; 4] = [
+        SetupPhase::Extract,
+        SetupPhase::Organize,
+        SetupPhase::Preconvert,
+        SetupPhase::Checkpoint,
+    ];
+
+    /// What a completed phase leaves in the project, for failure reports
+    fn leaves_behind(self) -> &'static str {
+        match self {
+            SetupPhase::Extract => "extracted assets in content/base",
+            SetupPhase::Organize => "concatenated and repathed BINs",
+            SetupPhase::Preconvert => ".ritobin caches next to the BINs",
+            SetupPhase::Checkpoint => "an initial checkpoint",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Completed,
+    /// Turned off in the options
+    Skipped,
+    Failed,
+    Cancelled,
+    /// An earlier phase stopped the pipeline
+    NotRun,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetupStep {
+    pub phase: SetupPhase,
+    pub status: StepStatus,
+    /// Counts for a completed step, or the error for a failed one
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetupReport {
+    pub steps: Vec<SetupStep>,
+    /// Phase that stopped the pipeline (failed or cancelled)
+    pub failed_phase: Option<SetupPhase>,
+    /// What is on disk after a stopped run
+    pub partial_state: Option<String>,
+    pub extracted_count: usize,
+    pub checkpoint_id: Option<String>,
+}
+
+impl SetupReport {
+    pub fn succeeded(&self) -> bool {
+        self.failed_phase.is_none()
+    }
+
+    /// Write the report to the project's reports directory
+    pub fn save(&self, project_path: &Path) -> Result<PathBuf> {
+        save_project_report(project_path, SETUP_REPORT_FILE, self)
+    }
+}
+
+/// The project and game data the pipeline works on
+pub struct SetupTarget<'a> {
+    pub project: &'a Project,
+    /// The champion WAD to extract from
+    pub wad_path: &'a Path,
+    /// Patch the WAD is from, recorded in the path mappings
+    pub game_version: Option<&'a str>,
+    pub hashtable: &'a Hashtable,
+}
+
+/// Run the pipeline. `progress(phase, fraction, message)` reports the overall
+/// fraction done; `cancel` is checked between phases and inside extraction,
+/// preconversion and checkpointing.
+pub fn setup_project_content(
+    target: &SetupTarget,
+    options: &SetupOptions,
+    cancel: &CancellationToken,
+    mut progress: impl FnMut(SetupPhase, f32, &str),
+) -> SetupReport {
+    let project = target.project;
+    let mut runner = Runner { report: SetupReport::default(), cancel, progress: &mut progress };
+    let assets_path = project.assets_path();
+
+    let extraction = runner.run(SetupPhase::Extract, true, "Extracting assets...", || {
+        let mut reader = WadReader::open(target.wad_path)?;
+        let mut extraction = extract_skin_assets(
+            &mut reader,
+            &assets_path,
+            &project.champion,
+            project.skin_id,
+            target.hashtable,
+            Some(cancel),
+        )?;
+        extraction.path_mappings.game_version = target.game_version.map(str::to_string);
+        extraction.path_mappings.save(&project.project_path)?;
+        let message = format!("Extracted {} assets", extraction.extracted_count);
+        Ok((extraction, message))
+    });
+    runner.report.extracted_count = extraction.as_ref().map_or(0, |e| e.extracted_count);
+    let path_mappings = extraction.map(|e| e.path_mappings.lookup()).unwrap_or_default();
+
+    let creator = options.creator_name.as_deref().filter(|c| !c.is_empty());
+    let organize = options.concat || (options.repath && creator.is_some());
+    runner.run(SetupPhase::Organize, organize, "Organizing BINs...", || {
+        let config = OrganizerConfig {
+            enable_concat: options.concat,
+            enable_repath: options.repath && creator.is_some(),
+            creator_name: creator.unwrap_or_default().to_string(),
+            project_name: project.name.clone(),
+            champion: project.champion.clone(),
+            target_skin_id: project.skin_id,
+            cleanup_unused: true,
+            // Freshly extracted game files; nothing user-made to quarantine
+            delete_unused: true,
+            map_overrides: MapOverrideMode::Keep,
+            foreign_prefix: None,
+        };
+        let result = organize_project(&assets_path, &config, &path_mappings, Some(cancel))?;
+        let message = format!(
+            "Combined {} BINs, repathed {} paths",
+            result.concat_result.as_ref().map_or(0, |r| r.source_count),
+            result.repath_result.as_ref().map_or(0, |r| r.paths_modified),
+        );
+        Ok(((), message))
+    });
+
+    runner.run(SetupPhase::Preconvert, options.preconvert, "Pre-converting BINs...", || {
+        let stats = preconvert_bins(&project.project_path, Some(cancel), |_, _| {})?;
+        let message = format!("Converted {} BINs ({} failed)", stats.converted, stats.failed);
+        Ok(((), message))
+    });
+
+    let checkpoint = runner.run(SetupPhase::Checkpoint, options.checkpoint, "Creating checkpoint...", || {
+        let manager = CheckpointManager::new(project.project_path.clone());
+        manager.init()?;
+        let checkpoint = manager.create_checkpoint_with_progress(
+            "Initial project setup".to_string(),
+            vec!["auto".to_string()],
+            None::<fn(&CheckpointProgress)>,
+            Some(cancel.as_flag()),
+        )?;
+        let message = format!("Created checkpoint {}", checkpoint.id);
+        Ok((checkpoint, message))
+    });
+    runner.report.checkpoint_id = checkpoint.map(|c| c.id);
+
+    if runner.report.succeeded() {
+        (runner.progress)(SetupPhase::Checkpoint, 1.0, "Project content ready");
+    }
+    runner.report
+}
+
+struct Runner<'a> {
+    report: SetupReport,
+    cancel: &'a CancellationToken,
+    progress: &'a mut dyn FnMut(SetupPhase, f32, &str),
+}
+
+impl Runner<'_> {
+    /// Run one phase unless it is disabled or an earlier phase stopped the pipeline
+    fn run<T>(
+        &mut self,
+        phase: SetupPhase,
+        enabled: bool,
+        message: &str,
+        step: impl FnOnce() -> Result<(T, String)>,
+    ) -> Option<T> {
+        let skip = |status, message: &str| SetupStep { phase, status, message: message.to_string() };
+        if self.report.failed_phase.is_some() {
+            self.report.steps.push(skip(StepStatus::NotRun, "Stopped by an earlier phase"));
+            return None;
+        }
+        if !enabled {
+            self.report.steps.push(skip(StepStatus::Skipped, "Disabled"));
+            return None;
+        }
+
+        let index = SetupPhase::ALL.iter().position(|p| *p == phase).unwrap_or(0);
+        (self.progress)(phase, index as f32 / SetupPhase::ALL.len() as f32, message);
+
+        match self.cancel.check().and_then(|_| step()) {
+            Ok((value, message)) => {
+                tracing::info!("Setup {:?}: {}", phase, message);
+                self.report.steps.push(SetupStep { phase, status: StepStatus::Completed, message });
+                Some(value)
+            }
+            Err(e) => {
+                let status = if matches!(e, Error::Cancelled) || self.cancel.is_cancelled() {
+                    StepStatus::Cancelled
+                } else {
+                    StepStatus::Failed
+                };
+                tracing::warn!("Setup stopped in {:?}: {}", phase, e);
+                self.report.steps.push(SetupStep { phase, status, message: e.to_string() });
+                self.report.failed_phase = Some(phase);
+                self.report.partial_state = Some(self.partial_state(phase));
+                None
+            }
+        }
+    }
+
+    fn partial_state(&self, stopped: SetupPhase) -> String {
+        let mut kept: Vec<&str> = self
+            .report
+            .steps
+            .iter()
+            .filter(|s| s.status == StepStatus::Completed)
+            .map(|s| s.phase.leaves_behind())
+            .collect();
+        if stopped == SetupPhase::Extract {
+            kept.push("any assets extracted before the stop");
+        }
+        if kept.is_empty() {
+            "Nothing was changed".to_string()
+        } else {
+            format!("The project keeps {}", kept.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_phase_stops_later_steps() {
+        let cancel = CancellationToken::new();
+        let mut progress = |_: SetupPhase, _: f32, _: &str| {};
+        let mut runner = Runner { report: SetupReport::default(), cancel: &cancel, progress: &mut progress };
+
+        assert_eq!(runner.run(SetupPhase::Extract, true, "", || Ok((3, "ok".to_string()))), Some(3));
+        runner.run(SetupPhase::Organize, false, "", || Ok(((), String::new())));
+        runner.run::<()>(SetupPhase::Preconvert, true, "", || Err(Error::InvalidInput("bad bin".to_string())));
+        runner.run(SetupPhase::Checkpoint, true, "", || -> Result<((), String)> { panic!("must not run") });
+
+        let statuses: Vec<StepStatus> = runner.report.steps.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            [StepStatus::Completed, StepStatus::Skipped, StepStatus::Failed, StepStatus::NotRun]
+        );
+        assert_eq!(runner.report.failed_phase, Some(SetupPhase::Preconvert));
+        assert_eq!(
+            runner.report.partial_state.as_deref(),
+            Some("The project keeps extracted assets in content/base")
+        );
+    }
+
+    #[test]
+    fn test_cancel_is_reported_as_cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let mut progress = |_: SetupPhase, _: f32, _: &str| {};
+        let mut runner = Runner { report: SetupReport::default(), cancel: &cancel, progress: &mut progress };
+
+        runner.run(SetupPhase::Extract, true, "", || Ok(((), String::new())));
+        assert_eq!(runner.report.steps[0].status, StepStatus::Cancelled);
+        assert_eq!(runner.report.failed_phase, Some(SetupPhase::Extract));
+    }
+}
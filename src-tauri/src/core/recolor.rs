@@ -0,0 +1,901 @@
+//! Recoloring a project's VFX
+//!
+//! A recolor applies one `ColorTransform` to a selection of the project: a
+//! VFX system, meaning the colors in its BIN object and the textures it
+//! references, or every texture matching a path glob. `recolor` computes all
+//! new file contents before anything is written, so a dry run and a
+//! cancelled run leave the project untouched, and the files about to change
+//! can be snapshotted for undo before any are written.
+
+use crate::core::bin::ltk_bridge::{read_bin, write_bin};
+use crate::core::mapping::normalize_path;
+use crate::core::operation::CancellationToken;
+use crate::core::undo::FileSnapshot;
+use crate::error::{Error, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{ImageEncoder, Rgba, RgbaImage};
+use ltk_meta::PropertyValueEnum;
+use ltk_texture::Texture;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Largest side of the texture previews in a dry run
+const PREVIEW_SIZE: u32 = 64;
+
+/// Colors drawn in a BIN's preview strip
+const MAX_SWATCHES: usize = 16;
+const SWATCH_SIZE: u32 = 12;
+
+/// Properties whose vector values are colors, e.g. an emitter's `birthColor`
+/// and every keyframe of its curve
+pub(crate) const COLOR_FIELDS: &[&str] =
+    &["birthColor", "color", "colors", "fresnelColor", "reflectionFresnelColor", "lingerColor"];
+
+/// How colors are changed. Colors are RGB in 0..1; alpha is never touched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ColorTransform {
+    /// Rotate the hue, keeping saturation and lightness
+    HueShift { degrees: f32 },
+    /// Move toward `color`'s hue and saturation, keeping lightness;
+    /// `strength` 1 replaces the color entirely
+    Tint { color: [f32; 3], strength: f32 },
+    /// Replace each color by the gradient's color at its luminance
+    GradientMap { stops: Vec<GradientStop> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradientStop {
+    /// 0 is black, 1 is white
+    pub position: f32,
+    pub color: [f32; 3],
+}
+
+/// What to recolor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecolorSelector {
+    /// A BIN object by path, e.g. "Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis",
+    /// or by hash as "0x1a2b3c4d"
+    System { name: String },
+    /// Textures whose path under the WAD folder matches, e.g. "assets/characters/ahri/skins/base/particles/*"
+    Textures { glob: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecolorFileKind {
+    Bin,
+    Texture,
+}
+
+/// One file the recolor changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecolorChange {
+    /// Relative to the project's `content/base`
+    pub path: String,
+    pub kind: RecolorFileKind,
+    /// Color values rewritten; 0 for textures
+    pub colors: usize,
+    /// Base64 PNGs of the BIN's first colors or the texture, in a dry run only
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecolorFailure {
+    pub path: String,
+    pub message: String,
+}
+
+/// Result of `recolor_project`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecolorReport {
+    pub dry_run: bool,
+    pub changes: Vec<RecolorChange>,
+    /// Textures the system references that the project doesn't contain
+    pub missing_textures: Vec<String>,
+    pub failed: Vec<RecolorFailure>,
+    /// Whether `undo_last` can revert the recolor; false for a dry run or
+    /// when the old files are too large for the undo journal
+    pub undoable: bool,
+}
+
+/// A bulk transform of `apply_bin_recolor`
+///
+/// Colors are transformed as stored, sRGB-encoded as they are authored and
+/// shown, without converting to linear light and back, so a shift followed
+/// by its opposite gives back the stored values. `rgba` bytes are read as
+/// `byte / 255` in the same space. Colors brighter than 1 are scaled into
+/// range for the transform and back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BinColorTransform {
+    /// Rotate the hue by `hue` degrees and scale saturation and value; 1 keeps them
+    Hsv { hue: f32, saturation: f32, value: f32 },
+    /// Replace colors within `tolerance` (RGB distance) of a swap's `from`
+    /// with its `to`; the first matching swap wins
+    Palette { swaps: Vec<PaletteSwap>, tolerance: f32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaletteSwap {
+    /// RGB in 0..1
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+    /// New alpha; alpha is kept when unset
+    #[serde(default)]
+    pub alpha: Option<f32>,
+}
+
+/// One BIN `apply_bin_recolor` changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinRecolorFile {
+    /// Relative to the project, or the file name for a single BIN
+    pub path: String,
+    /// Color values changed
+    pub changed: usize,
+}
+
+/// Result of `apply_bin_recolor`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BinRecolorReport {
+    /// BINs with changed colors
+    pub files: Vec<BinRecolorFile>,
+    /// Project BINs outside `particles` and `skins` folders, left alone
+    pub skipped: usize,
+    pub failed: Vec<RecolorFailure>,
+    /// Checkpoint taken before writing, to undo the recolor with
+    pub checkpoint_id: Option<String>,
+}
+
+/// New contents of the files a recolor changes
+pub struct RecolorWrites {
+    files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl RecolorWrites {
+    /// Snapshots of every file `write` changes, including the `.ritobin`
+    /// caches it removes
+    pub fn snapshot(&self) -> Result<Vec<FileSnapshot>> {
+        let mut paths = Vec::new();
+        for (path, _) in &self.files {
+            paths.push(path.clone());
+            if is_bin(path) {
+                paths.push(ritobin_path(path));
+            }
+        }
+        FileSnapshot::capture(&paths)
+    }
+
+    /// Write the new contents. Stale `.ritobin` caches of rewritten BINs are
+    /// removed so they are converted again.
+    pub fn write(self) -> Result<()> {
+        for (path, data) in &self.files {
+            fs::write(path, data).map_err(|e| Error::io_with_path(e, path))?;
+            if is_bin(path) {
+                let cache = ritobin_path(path);
+                if let Err(e) = fs::remove_file(&cache) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        tracing::warn!("Failed to remove stale {}: {}", cache.display(), e);
+                    }
+                }
+            }
+        }
+        tracing::info!("Recolored {} files", self.files.len());
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Work out what `transform` does to the selected files. Nothing is written;
+/// a dry run fills in the previews, otherwise the returned writes apply it.
+pub fn recolor(
+    content_base: &Path,
+    selector: &RecolorSelector,
+    transform: &ColorTransform,
+    dry_run: bool,
+    cancel: &CancellationToken,
+) -> Result<(RecolorReport, RecolorWrites)> {
+    if let ColorTransform::GradientMap { stops } = transform {
+        if stops.is_empty() {
+            return Err(Error::InvalidInput("A gradient map needs at least one stop".into()));
+        }
+    }
+
+    let mut report = RecolorReport { dry_run, ..Default::default() };
+    let mut writes = RecolorWrites { files: Vec::new() };
+
+    let textures = match selector {
+        RecolorSelector::System { name } => {
+            let refs = recolor_system(content_base, name, transform, dry_run, cancel, &mut report, &mut writes)?;
+            let mut textures = Vec::new();
+            for rel_path in refs {
+                match resolve_asset(content_base, &rel_path) {
+                    // Distortion textures hold UV offsets, not colors
+                    Some(_) if file_name(&rel_path).contains("distort") => {}
+                    Some(path) => textures.push(path),
+                    None => report.missing_textures.push(rel_path),
+                }
+            }
+            textures
+        }
+        RecolorSelector::Textures { glob } => find_textures(content_base, glob)?,
+    };
+
+    for path in textures {
+        cancel.check()?;
+        match recolor_texture(&path, transform, dry_run) {
+            Ok((change, data)) => {
+                report.changes.push(RecolorChange { path: relative(content_base, &path), ..change });
+                if let Some(data) = data {
+                    writes.files.push((path, data));
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to recolor {}: {}", path.display(), e);
+                report.failed.push(RecolorFailure { path: relative(content_base, &path), message: e.to_string() });
+            }
+        }
+    }
+    Ok((report, writes))
+}
+
+/// Work out what `transform` does to the BIN at `path`, or to the BINs of the
+/// project at `path` under a `particles` or `skins` folder. Colors are the
+/// `rgba` values and the `vec4`s below color fields, as for `recolor`.
+/// Nothing is written; the returned writes apply it.
+pub fn recolor_bins(
+    path: &Path,
+    transform: &BinColorTransform,
+    cancel: &CancellationToken,
+) -> Result<(BinRecolorReport, RecolorWrites)> {
+    if let BinColorTransform::Palette { swaps, tolerance } = transform {
+        if swaps.is_empty() || *tolerance < 0.0 {
+            return Err(Error::InvalidInput(
+                "A palette swap needs at least one color and a tolerance of 0 or more".into(),
+            ));
+        }
+    }
+
+    let mut report = BinRecolorReport::default();
+    let (root, bins) = if path.is_file() {
+        (path.parent().unwrap_or(path), vec![path.to_path_buf()])
+    } else {
+        let content = path.join("content");
+        if !content.is_dir() {
+            return Err(Error::NotFound(format!("No BIN or project content at {}", path.display())));
+        }
+        let mut bins = Vec::new();
+        for entry in WalkDir::new(&content).min_depth(2).into_iter().filter_map(|e| e.ok()) {
+            if !is_bin(entry.path()) {
+                continue;
+            }
+            let rel_path = relative(path, entry.path());
+            if rel_path.split('/').any(|part| part == "particles" || part == "skins") {
+                bins.push(entry.into_path());
+            } else {
+                report.skipped += 1;
+            }
+        }
+        bins.sort();
+        (path, bins)
+    };
+
+    let color_fields: HashSet<u32> = COLOR_FIELDS.iter().map(|field| fnv1a(field)).collect();
+    let recolor_color = |color: [f32; 4]| transform.apply(color);
+    let mut writes = RecolorWrites { files: Vec::new() };
+    for bin_path in bins {
+        cancel.check()?;
+        let rel_path = relative(root, &bin_path);
+        let bin = fs::read(&bin_path)
+            .map_err(|e| Error::io_with_path(e, &bin_path))
+            .and_then(|data| read_bin(&data).map_err(|e| Error::InvalidInput(format!("Failed to parse BIN: {}", e))));
+        let mut bin = match bin {
+            Ok(bin) => bin,
+            Err(e) => {
+                tracing::warn!("Failed to recolor {}: {}", bin_path.display(), e);
+                report.failed.push(RecolorFailure { path: rel_path, message: e.to_string() });
+                continue;
+            }
+        };
+
+        let mut recolor = ValueRecolor { recolor: &recolor_color, color_fields: &color_fields, swatches: Vec::new() };
+        for object in bin.objects.values_mut() {
+            for (name_hash, prop) in object.properties.iter_mut() {
+                recolor.value(&mut prop.value, color_fields.contains(name_hash));
+            }
+        }
+        let changed = recolor
+            .swatches
+            .iter()
+            .filter(|(before, after)| before.iter().zip(after).any(|(b, a)| (b - a).abs() > 1e-6))
+            .count();
+        if changed == 0 {
+            continue;
+        }
+        let data = write_bin(&bin).map_err(|e| Error::InvalidInput(format!("Failed to write BIN: {}", e)))?;
+        writes.files.push((bin_path, data));
+        report.files.push(BinRecolorFile { path: rel_path, changed });
+    }
+    Ok((report, writes))
+}
+
+/// Recolor the system's object in every BIN that has it, returning the
+/// textures it references
+fn recolor_system(
+    content_base: &Path,
+    name: &str,
+    transform: &ColorTransform,
+    dry_run: bool,
+    cancel: &CancellationToken,
+    report: &mut RecolorReport,
+    writes: &mut RecolorWrites,
+) -> Result<BTreeSet<String>> {
+    let object_hash = parse_object_name(name);
+    let color_fields: HashSet<u32> = COLOR_FIELDS.iter().map(|field| fnv1a(field)).collect();
+    let mut texture_refs = BTreeSet::new();
+    let mut found = false;
+
+    for entry in WalkDir::new(content_base).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !is_bin(path) {
+            continue;
+        }
+        cancel.check()?;
+
+        let bin = fs::read(path)
+            .map_err(|e| Error::io_with_path(e, path))
+            .and_then(|data| read_bin(&data).map_err(|e| Error::InvalidInput(format!("Failed to parse BIN: {}", e))));
+        let mut bin = match bin {
+            Ok(bin) => bin,
+            Err(e) => {
+                tracing::debug!("Skipping {} for recolor: {}", path.display(), e);
+                continue;
+            }
+        };
+        let Some(object) = bin.objects.get_mut(&object_hash) else {
+            continue;
+        };
+        found = true;
+
+        let recolor_color = |color: [f32; 4]| {
+            let [r, g, b] = apply_hdr(transform, [color[0], color[1], color[2]]);
+            [r, g, b, color[3]]
+        };
+        let mut recolor = ValueRecolor { recolor: &recolor_color, color_fields: &color_fields, swatches: Vec::new() };
+        for (name_hash, prop) in object.properties.iter_mut() {
+            collect_texture_refs(&prop.value, &mut texture_refs);
+            recolor.value(&mut prop.value, color_fields.contains(name_hash));
+        }
+        if recolor.swatches.is_empty() {
+            continue;
+        }
+
+        let mut change = RecolorChange {
+            path: relative(content_base, path),
+            kind: RecolorFileKind::Bin,
+            colors: recolor.swatches.len(),
+            before: None,
+            after: None,
+        };
+        if dry_run {
+            let (before, after): (Vec<_>, Vec<_>) = recolor.swatches.iter().take(MAX_SWATCHES).copied().unzip();
+            change.before = Some(png_base64(&swatch_strip(&before))?);
+            change.after = Some(png_base64(&swatch_strip(&after))?);
+        } else {
+            let data = write_bin(&bin).map_err(|e| Error::InvalidInput(format!("Failed to write BIN: {}", e)))?;
+            writes.files.push((path.to_path_buf(), data));
+        }
+        report.changes.push(change);
+    }
+
+    if !found {
+        return Err(Error::NotFound(format!("VFX system '{}' is not in the project's BINs", name)));
+    }
+    Ok(texture_refs)
+}
+
+/// Walks a BIN value, transforming its colors
+struct ValueRecolor<'a> {
+    /// New RGBA of a color
+    recolor: &'a dyn Fn([f32; 4]) -> [f32; 4],
+    color_fields: &'a HashSet<u32>,
+    /// Each rewritten color before and after, in 0..1 (vectors may exceed 1)
+    swatches: Vec<([f32; 4], [f32; 4])>,
+}
+
+impl ValueRecolor<'_> {
+    /// `in_color` is set below a color property, where vectors are colors
+    fn value(&mut self, value: &mut PropertyValueEnum, in_color: bool) {
+        match value {
+            PropertyValueEnum::Color(c) => {
+                let before = [c.0.r, c.0.g, c.0.b, c.0.a].map(|v| v as f32 / 255.0);
+                let after = self.color(before);
+                c.0.r = to_u8(after[0]);
+                c.0.g = to_u8(after[1]);
+                c.0.b = to_u8(after[2]);
+                c.0.a = to_u8(after[3]);
+            }
+            PropertyValueEnum::Vector4(v) if in_color => {
+                let after = self.color([v.0.x, v.0.y, v.0.z, v.0.w]);
+                v.0 = glam::Vec4::from_array(after);
+            }
+            PropertyValueEnum::Container(c) => {
+                for item in &mut c.items {
+                    self.value(item, in_color);
+                }
+            }
+            PropertyValueEnum::UnorderedContainer(c) => {
+                for item in &mut c.0.items {
+                    self.value(item, in_color);
+                }
+            }
+            PropertyValueEnum::Struct(s) => {
+                for (name_hash, prop) in s.properties.iter_mut() {
+                    self.value(&mut prop.value, in_color || self.color_fields.contains(name_hash));
+                }
+            }
+            PropertyValueEnum::Embedded(e) => {
+                for (name_hash, prop) in e.0.properties.iter_mut() {
+                    self.value(&mut prop.value, in_color || self.color_fields.contains(name_hash));
+                }
+            }
+            PropertyValueEnum::Optional(o) => {
+                if let Some(inner) = &mut o.value {
+                    self.value(inner.as_mut(), in_color);
+                }
+            }
+            PropertyValueEnum::Map(m) => {
+                for val in m.entries.values_mut() {
+                    self.value(val, in_color);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn color(&mut self, before: [f32; 4]) -> [f32; 4] {
+        let after = (self.recolor)(before);
+        self.swatches.push((before, after));
+        after
+    }
+}
+
+/// Recolor one texture, returning its re-encoded bytes unless this is a dry run
+fn recolor_texture(path: &Path, transform: &ColorTransform, dry_run: bool) -> Result<(RecolorChange, Option<Vec<u8>>)> {
+    let data = fs::read(path).map_err(|e| Error::io_with_path(e, path))?;
+    let texture = Texture::from_reader(&mut Cursor::new(&data))
+        .map_err(|e| Error::InvalidInput(format!("Failed to parse texture: {:?}", e)))?;
+    let mut image = texture
+        .decode_mipmap(0)
+        .map_err(|e| Error::InvalidInput(format!("Failed to decode texture: {:?}", e)))?
+        .into_rgba_image()
+        .map_err(|e| Error::InvalidInput(format!("Failed to convert texture to RGBA: {:?}", e)))?;
+
+    let before = dry_run.then(|| preview(&image)).transpose()?;
+    for pixel in image.pixels_mut() {
+        let Rgba([r, g, b, a]) = *pixel;
+        if a == 0 {
+            continue;
+        }
+        let [r, g, b] = transform.apply([r, g, b].map(|v| v as f32 / 255.0)).map(to_u8);
+        *pixel = Rgba([r, g, b, a]);
+    }
+
+    let mut change = RecolorChange { path: String::new(), kind: RecolorFileKind::Texture, colors: 0, before, after: None };
+    if dry_run {
+        change.after = Some(preview(&image)?);
+        return Ok((change, None));
+    }
+    Ok((change, Some(encode_texture_like(&texture, &data, &image)?)))
+}
+
+/// Encode `image` in the container and, as far as supported, the format of
+/// the original texture
+fn encode_texture_like(texture: &Texture, original: &[u8], image: &RgbaImage) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    match texture {
+        Texture::Tex(tex) => {
+            use ltk_texture::tex::EncodeOptions;
+            let options = EncodeOptions::new(tex.format).with_mipmaps();
+            let new_tex = ltk_texture::Tex::encode_rgba_image(image, options)
+                .map_err(|e| Error::InvalidInput(format!("Failed to encode TEX: {:?}", e)))?;
+            new_tex
+                .write(&mut output)
+                .map_err(|e| Error::InvalidInput(format!("Failed to write TEX: {}", e)))?;
+        }
+        Texture::Dds(_) => {
+            let dds = ddsfile::Dds::read(&mut Cursor::new(original))
+                .map_err(|e| Error::InvalidInput(format!("Failed to parse DDS: {}", e)))?;
+            // Same format choice as recolor_folder
+            let format = match dds.header.spf.fourcc {
+                Some(fourcc) if fourcc.0 == u32::from_le_bytes(*b"DXT1") => image_dds::ImageFormat::BC1RgbaUnorm,
+                Some(_) => image_dds::ImageFormat::BC3RgbaUnorm,
+                None => image_dds::ImageFormat::Bgra8Unorm,
+            };
+            let new_dds = image_dds::dds_from_image(
+                image,
+                format,
+                image_dds::Quality::Normal,
+                image_dds::Mipmaps::GeneratedAutomatic,
+            )
+            .map_err(|e| Error::InvalidInput(format!("Failed to encode DDS: {:?}", e)))?;
+            new_dds
+                .write(&mut output)
+                .map_err(|e| Error::InvalidInput(format!("Failed to write DDS: {}", e)))?;
+        }
+    }
+    Ok(output)
+}
+
+impl ColorTransform {
+    /// Transform an RGB color in 0..1
+    pub fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let [r, g, b] = rgb.map(|v| v.clamp(0.0, 1.0));
+        match self {
+            ColorTransform::HueShift { degrees } => {
+                let (h, s, l) = rgb_to_hsl(r, g, b);
+                let (r, g, b) = hsl_to_rgb((h + degrees).rem_euclid(360.0), s, l);
+                [r, g, b]
+            }
+            ColorTransform::Tint { color, strength } => {
+                let (h, s, _) = rgb_to_hsl(color[0], color[1], color[2]);
+                let (_, _, l) = rgb_to_hsl(r, g, b);
+                let (tr, tg, tb) = hsl_to_rgb(h, s, l);
+                let t = strength.clamp(0.0, 1.0);
+                [r + (tr - r) * t, g + (tg - g) * t, b + (tb - b) * t]
+            }
+            ColorTransform::GradientMap { stops } => sample_gradient(stops, luminance([r, g, b])).unwrap_or([r, g, b]),
+        }
+    }
+}
+
+impl BinColorTransform {
+    /// Transform an RGBA color as stored
+    pub fn apply(&self, [r, g, b, a]: [f32; 4]) -> [f32; 4] {
+        match self {
+            BinColorTransform::Hsv { hue, saturation, value } => {
+                let peak = r.max(g).max(b).max(1.0);
+                let (h, s, v) = rgb_to_hsv(r / peak, g / peak, b / peak);
+                let (r, g, b) =
+                    hsv_to_rgb((h + hue).rem_euclid(360.0), (s * saturation).clamp(0.0, 1.0), (v * value).max(0.0));
+                [r * peak, g * peak, b * peak, a]
+            }
+            BinColorTransform::Palette { swaps, tolerance } => swaps
+                .iter()
+                .find(|swap| distance([r, g, b], swap.from) <= *tolerance)
+                .map_or([r, g, b, a], |swap| [swap.to[0], swap.to[1], swap.to[2], swap.alpha.unwrap_or(a)]),
+        }
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt()
+}
+
+/// Apply a transform to a color that may be brighter than 1, as VFX colors
+/// often are, by scaling it into range and back
+fn apply_hdr(transform: &ColorTransform, rgb: [f32; 3]) -> [f32; 3] {
+    let peak = rgb[0].max(rgb[1]).max(rgb[2]);
+    if peak <= 1.0 {
+        return transform.apply(rgb);
+    }
+    transform.apply(rgb.map(|v| v / peak)).map(|v| v * peak)
+}
+
+fn sample_gradient(stops: &[GradientStop], t: f32) -> Option<[f32; 3]> {
+    let mut sorted: Vec<&GradientStop> = stops.iter().collect();
+    sorted.sort_by(|a, b| a.position.total_cmp(&b.position));
+    let (first, last) = (sorted.first()?, sorted.last()?);
+    if t <= first.position {
+        return Some(first.color);
+    }
+    let Some(i) = sorted.iter().position(|stop| stop.position >= t) else {
+        return Some(last.color);
+    };
+    let (a, b) = (sorted[i - 1], sorted[i]);
+    let f = (t - a.position) / (b.position - a.position);
+    Some([0, 1, 2].map(|c| a.color[c] + (b.color[c] - a.color[c]) * f))
+}
+
+fn luminance([r, g, b]: [f32; 3]) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+pub(crate) fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+
+    if max == min {
+        return (0.0, 0.0, l);
+    }
+
+    let d = max - min;
+    let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+
+    let mut h = if max == r {
+        (g - b) / d + (if g < b { 6.0 } else { 0.0 })
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    h /= 6.0;
+
+    (h * 360.0, s, l)
+}
+
+/// Hue in degrees, saturation and value of an RGB color in 0..1
+fn rgb_to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let d = max - r.min(g).min(b);
+    let h = if d == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    let s = if max == 0.0 { 0.0 } else { d / max };
+    (h, s, max)
+}
+
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match (h / 60.0) as u32 % 6 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    (r + m, g + m, b + m)
+}
+
+fn hue_to_rgb(p: f32, q: f32, mut t: f32) -> f32 {
+    if t < 0.0 { t += 1.0; }
+    if t > 1.0 { t -= 1.0; }
+    if t < 1.0/6.0 { return p + (q - p) * 6.0 * t; }
+    if t < 1.0/2.0 { return q; }
+    if t < 2.0/3.0 { return p + (q - p) * (2.0/3.0 - t) * 6.0; }
+    p
+}
+
+pub(crate) fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
+    if s == 0.0 {
+        return (l, l, l);
+    }
+
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+
+    let h = h / 360.0;
+    let r = hue_to_rgb(p, q, h + 1.0/3.0);
+    let g = hue_to_rgb(p, q, h);
+    let b = hue_to_rgb(p, q, h - 1.0/3.0);
+
+    (r, g, b)
+}
+
+/// Texture paths (.dds, .tex) a BIN value references, normalized
+fn collect_texture_refs(value: &PropertyValueEnum, refs: &mut BTreeSet<String>) {
+    match value {
+        PropertyValueEnum::String(s) => {
+            let path = normalize_path(&s.0);
+            if path.ends_with(".dds") || path.ends_with(".tex") {
+                refs.insert(path);
+            }
+        }
+        PropertyValueEnum::Container(c) => c.items.iter().for_each(|item| collect_texture_refs(item, refs)),
+        PropertyValueEnum::UnorderedContainer(c) => c.0.items.iter().for_each(|item| collect_texture_refs(item, refs)),
+        PropertyValueEnum::Struct(s) => s.properties.values().for_each(|prop| collect_texture_refs(&prop.value, refs)),
+        PropertyValueEnum::Embedded(e) => e.0.properties.values().for_each(|prop| collect_texture_refs(&prop.value, refs)),
+        PropertyValueEnum::Optional(o) => {
+            if let Some(inner) = &o.value {
+                collect_texture_refs(inner, refs);
+            }
+        }
+        PropertyValueEnum::Map(m) => m.entries.values().for_each(|val| collect_texture_refs(val, refs)),
+        _ => {}
+    }
+}
+
+/// Project textures whose path matches `glob`, ignoring case. Paths are
+/// matched both under `content/base` and under the WAD folder in it.
+fn find_textures(content_base: &Path, glob: &str) -> Result<Vec<PathBuf>> {
+    let pattern = glob::Pattern::new(&normalize_path(glob))
+        .map_err(|e| Error::InvalidInput(format!("Invalid glob '{}': {}", glob, e)))?;
+    let options = glob::MatchOptions { case_sensitive: false, ..Default::default() };
+
+    let textures = WalkDir::new(content_base)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            let rel_path = relative(content_base, e.path());
+            (rel_path.ends_with(".dds") || rel_path.ends_with(".tex"))
+                && (pattern.matches_with(&rel_path, options)
+                    || rel_path.split_once('/').is_some_and(|(_, in_wad)| pattern.matches_with(in_wad, options)))
+        })
+        .map(|e| e.into_path())
+        .collect();
+    Ok(textures)
+}
+
+/// The project file for an asset path, either directly under `content/base`
+/// or under one of its WAD folders
+fn resolve_asset(content_base: &Path, rel_path: &str) -> Option<PathBuf> {
+    let direct = content_base.join(rel_path);
+    if direct.is_file() {
+        return Some(direct);
+    }
+    fs::read_dir(content_base)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().join(rel_path))
+        .find(|path| path.is_file())
+}
+
+/// Hash of a BIN object path, or the hash itself given as "0x…"
+pub(crate) fn parse_object_name(name: &str) -> u32 {
+    name.strip_prefix("0x")
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .unwrap_or_else(|| fnv1a(name))
+}
+
+/// FNV-1a of the lowercased name, as BIN object paths and field names are hashed
+pub(crate) fn fnv1a(name: &str) -> u32 {
+    name.to_lowercase()
+        .bytes()
+        .fold(0x811c_9dc5, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+/// A row of color cells
+fn swatch_strip(colors: &[[f32; 4]]) -> RgbaImage {
+    let width = SWATCH_SIZE * colors.len().max(1) as u32;
+    RgbaImage::from_fn(width, SWATCH_SIZE, |x, _| {
+        colors
+            .get((x / SWATCH_SIZE) as usize)
+            .map(|c| Rgba([to_u8(c[0]), to_u8(c[1]), to_u8(c[2]), 255]))
+            .unwrap_or(Rgba([0, 0, 0, 0]))
+    })
+}
+
+fn preview(image: &RgbaImage) -> Result<String> {
+    let scale = PREVIEW_SIZE as f32 / image.width().max(image.height()).max(1) as f32;
+    let thumbnail = if scale < 1.0 {
+        let width = ((image.width() as f32 * scale).round() as u32).max(1);
+        let height = ((image.height() as f32 * scale).round() as u32).max(1);
+        image::imageops::thumbnail(image, width, height)
+    } else {
+        image.clone()
+    };
+    png_base64(&thumbnail)
+}
+
+fn png_base64(image: &RgbaImage) -> Result<String> {
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(image.as_raw(), image.width(), image.height(), image::ExtendedColorType::Rgba8)
+        .map_err(|e| Error::InvalidInput(format!("Failed to encode PNG: {}", e)))?;
+    Ok(STANDARD.encode(&png_data))
+}
+
+fn to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn is_bin(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin"))
+}
+
+fn ritobin_path(bin_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.ritobin", bin_path.display()))
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn relative(content_base: &Path, path: &Path) -> String {
+    normalize_path(&path.strip_prefix(content_base).unwrap_or(path).to_string_lossy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() < 0.01)
+    }
+
+    #[test]
+    fn test_transforms() {
+        let blue = [0.1, 0.2, 0.9];
+        let red = ColorTransform::HueShift { degrees: 135.0 }.apply(blue);
+        assert!(red[0] > 0.8 && red[2] < 0.3, "{:?}", red);
+        assert!(close(ColorTransform::HueShift { degrees: 360.0 }.apply(blue), blue));
+
+        let tint = ColorTransform::Tint { color: [1.0, 0.0, 0.0], strength: 1.0 };
+        let (_, _, l) = rgb_to_hsl(blue[0], blue[1], blue[2]);
+        let [r, g, b] = tint.apply(blue);
+        assert!(r > g && r > b);
+        assert!((rgb_to_hsl(r, g, b).2 - l).abs() < 0.01);
+        assert!(close(ColorTransform::Tint { color: [1.0, 0.0, 0.0], strength: 0.0 }.apply(blue), blue));
+
+        let stops = vec![
+            GradientStop { position: 1.0, color: [1.0, 1.0, 0.0] },
+            GradientStop { position: 0.0, color: [0.0, 0.0, 0.0] },
+        ];
+        let map = ColorTransform::GradientMap { stops };
+        assert!(close(map.apply([0.0, 0.0, 0.0]), [0.0, 0.0, 0.0]));
+        assert!(close(map.apply([0.5, 0.5, 0.5]), [0.5, 0.5, 0.0]));
+
+        // HDR colors keep their intensity
+        let hdr = apply_hdr(&ColorTransform::HueShift { degrees: 120.0 }, [2.0, 0.0, 0.0]);
+        assert!(close(hdr, [0.0, 2.0, 0.0]), "{:?}", hdr);
+    }
+
+    #[test]
+    fn test_object_names() {
+        assert_eq!(fnv1a(""), 0x811c_9dc5);
+        assert_eq!(fnv1a("a"), 0xe40c_292c);
+        assert_eq!(fnv1a("BirthColor"), fnv1a("birthcolor"));
+        assert_eq!(parse_object_name("0x1a2b3c4d"), 0x1a2b_3c4d);
+        assert_eq!(parse_object_name("Characters/Ahri"), fnv1a("characters/ahri"));
+    }
+
+    #[test]
+    fn test_recolor_bins() {
+        // A hue shift and its opposite give back the stored color, HDR included
+        let shift = |hue| BinColorTransform::Hsv { hue, saturation: 1.0, value: 1.0 };
+        let color = [2.0, 0.4, 0.1, 0.5];
+        let back = shift(-75.0).apply(shift(75.0).apply(color));
+        assert!(color.iter().zip(back).all(|(a, b)| (a - b).abs() < 1e-4), "{:?}", back);
+
+        let temp = tempfile::tempdir().unwrap();
+        let text = "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {}\nentries: map[hash,embed] = {\n    \"Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis\" = VfxSystemDefinitionData {\n        complexEmitterDefinitionData: list[pointer] = {\n            VfxEmitterDefinitionData {\n                birthColor: embed = ValueColor {\n                    constantValue: vec4 = { 1, 0, 0, 0.5 }\n                }\n                tint: rgba = { 250, 0, 0, 255 }\n                offset: vec4 = { 1, 0, 0, 1 }\n            }\n        }\n    }\n}\n";
+        let data = crate::core::bin::ltk_bridge::write_bin(&crate::core::bin::text_to_tree(text).unwrap()).unwrap();
+        for dir in ["content/base/data/characters/ahri/skins", "content/base/data/items"] {
+            fs::create_dir_all(temp.path().join(dir)).unwrap();
+            fs::write(temp.path().join(dir).join("skin0.bin"), &data).unwrap();
+        }
+
+        let swap = BinColorTransform::Palette {
+            swaps: vec![PaletteSwap { from: [1.0, 0.0, 0.0], to: [0.0, 0.0, 1.0], alpha: None }],
+            tolerance: 0.05,
+        };
+        let (report, writes) = recolor_bins(temp.path(), &swap, &CancellationToken::new()).unwrap();
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].path, "content/base/data/characters/ahri/skins/skin0.bin");
+        // birthColor and tint; `offset` isn't a color field
+        assert_eq!(report.files[0].changed, 2);
+        writes.write().unwrap();
+
+        let bin_path = temp.path().join("content/base/data/characters/ahri/skins/skin0.bin");
+        let colors = crate::core::bin::colors::extract_bin_colors(&bin_path, &crate::core::bin::HashMapProvider::new())
+            .unwrap()
+            .colors;
+        let rgba: Vec<[f32; 4]> = colors.iter().map(|color| color.rgba).collect();
+        assert!(rgba.contains(&[0.0, 0.0, 1.0, 0.5]));
+        assert!(rgba.contains(&[0.0, 0.0, 1.0, 1.0]));
+        assert!(rgba.contains(&[1.0, 0.0, 0.0, 1.0]));
+        let (report, writes) = recolor_bins(&bin_path, &swap, &CancellationToken::new()).unwrap();
+        assert!(report.files.is_empty() && writes.is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+//! Map-scoped VFX overrides
+//!
+//! Some ability effects resolve through map data: BINs under `data/maps/`
+//! define or link to a champion's particle systems, and on that map theirs
+//! are the ones used. A mod that only ships the champion WAD loses those
+//! effects on Summoner's Rift. This module finds the map-scoped BINs in a
+//! project that reference the champion and either folds the objects involved
+//! into the concat BIN or moves the BINs into the map's own WAD folder, which
+//! the export packs as a separate WAD.
+
+use crate::core::bin::ltk_bridge::{read_bin, write_bin};
+use crate::core::mapping::normalize_path;
+use crate::core::paths::safe_join;
+use crate::core::repath::refather::collect_paths_from_value;
+use crate::core::wad::WadName;
+use crate::error::{Error, Result};
+use ltk_meta::BinTree;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// WAD folder of Summoner's Rift, where map-scoped BINs are moved to
+pub const MAP_WAD_FOLDER: &str = "map11.wad.client";
+
+/// What `organize_project` does with map-scoped BINs that reference the champion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapOverrideMode {
+    /// Leave them where they are; they are still repathed
+    #[default]
+    Keep,
+    /// Copy the objects that reference the champion into the concat BIN and
+    /// remove the map BINs, so the mod doesn't replace whole map files
+    Concat,
+    /// Move them into `map11.wad.client`
+    MapWad,
+}
+
+/// A BIN under `data/maps/` that references the champion's files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapScopedBin {
+    /// Where it is packed from, relative to `content/base` with `/`
+    /// separators; the concat BIN once its objects were pulled in there
+    pub file: String,
+    /// Path inside its WAD, e.g. "data/maps/shipping/map11/map11.bin"
+    pub path: String,
+    /// Path hashes of the objects that reference the champion
+    pub objects: Vec<u32>,
+}
+
+impl MapScopedBin {
+    /// Whether it is packed into a map WAD, where the game loads map data from
+    pub fn in_map_wad(&self) -> bool {
+        self.file.split('/').next().is_some_and(is_map_wad)
+    }
+}
+
+/// `data/maps/...`, with either slash and any case
+pub fn is_map_scoped(path: &str) -> bool {
+    normalize_path(path).starts_with("data/maps/")
+}
+
+/// Map WAD folders: "Map11.wad.client", "map12.wad.client", ...
+pub fn is_map_wad(folder: &str) -> bool {
+    WadName::parse(folder).is_some_and(|wad| wad.base.to_ascii_lowercase().starts_with("map"))
+}
+
+/// Split a path relative to `content/base` into its WAD folder (if any) and
+/// the path inside it
+pub fn split_wad_folder(file: &str) -> (Option<&str>, &str) {
+    match file.split_once('/') {
+        Some((folder, rest)) if WadName::parse(folder).is_some() => (Some(folder), rest),
+        _ => (None, file),
+    }
+}
+
+/// Objects in `tree` holding a path under the champion's `characters/` folders
+pub fn champion_objects(tree: &BinTree, champion: &str) -> Vec<u32> {
+    let needle = format!("characters/{}/", champion.to_lowercase());
+    tree.objects
+        .iter()
+        .filter(|(_, object)| {
+            let mut paths = Vec::new();
+            for prop in object.properties.values() {
+                collect_paths_from_value(&prop.value, &mut paths);
+            }
+            paths.iter().any(|path| path.contains(&needle))
+        })
+        .map(|(path_hash, _)| *path_hash)
+        .collect()
+}
+
+/// Map-scoped BINs anywhere under `content_base` that reference `champion`,
+/// sorted by file. BINs that can't be read are logged and skipped.
+pub fn find_map_scoped_bins(content_base: &Path, champion: &str) -> Vec<MapScopedBin> {
+    if champion.is_empty() {
+        return Vec::new();
+    }
+
+    let mut bins: Vec<MapScopedBin> = WalkDir::new(content_base)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+        .filter_map(|e| {
+            let file = e.path().strip_prefix(content_base).ok()?.to_string_lossy().replace('\\', "/");
+            let path = normalize_path(split_wad_folder(&file).1);
+            if !is_map_scoped(&path) {
+                return None;
+            }
+            let tree = match fs::read(e.path()).map_err(|e| e.to_string()).and_then(|data| {
+                read_bin(&data).map_err(|e| e.to_string())
+            }) {
+                Ok(tree) => tree,
+                Err(err) => {
+                    tracing::warn!("Skipping map BIN {}: {}", e.path().display(), err);
+                    return None;
+                }
+            };
+            let objects = champion_objects(&tree, champion);
+            (!objects.is_empty()).then_some(MapScopedBin { file, path, objects })
+        })
+        .collect();
+    bins.sort_by(|a, b| a.file.cmp(&b.file));
+    bins
+}
+
+/// Apply `mode` to the champion's map-scoped BINs and return them as they
+/// end up. `concat_path` is the concat BIN inside `file_base`; without one,
+/// `Concat` keeps the BINs where they are.
+pub fn apply_map_overrides(
+    content_base: &Path,
+    file_base: &Path,
+    champion: &str,
+    mode: MapOverrideMode,
+    concat_path: Option<&str>,
+) -> Result<Vec<MapScopedBin>> {
+    let bins = find_map_scoped_bins(content_base, champion);
+    if bins.is_empty() {
+        return Ok(bins);
+    }
+    tracing::info!("Found {} map-scoped BINs referencing {}", bins.len(), champion);
+
+    match (mode, concat_path) {
+        (MapOverrideMode::Keep, _) => Ok(bins),
+        (MapOverrideMode::MapWad, _) => move_to_map_wad(content_base, bins),
+        (MapOverrideMode::Concat, Some(concat_path)) => merge_into_concat(content_base, file_base, concat_path, bins),
+        (MapOverrideMode::Concat, None) => {
+            tracing::warn!("No concat BIN to pull map objects into; leaving map-scoped BINs in place");
+            Ok(bins)
+        }
+    }
+}
+
+/// Move BINs that aren't in a map WAD into `map11.wad.client`. A BIN whose
+/// destination already exists is left where it is.
+fn move_to_map_wad(content_base: &Path, bins: Vec<MapScopedBin>) -> Result<Vec<MapScopedBin>> {
+    bins.into_iter()
+        .map(|mut bin| {
+            if bin.in_map_wad() {
+                return Ok(bin);
+            }
+            let source = safe_join(content_base, &bin.file)?;
+            let file = format!("{}/{}", MAP_WAD_FOLDER, bin.path);
+            let dest = safe_join(content_base, &file)?;
+            if dest.exists() {
+                tracing::warn!("{} already exists; leaving {} in place", file, bin.file);
+                return Ok(bin);
+            }
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+            }
+            if fs::rename(&source, &dest).is_err() {
+                fs::copy(&source, &dest).map_err(|e| Error::io_with_path(e, &source))?;
+                fs::remove_file(&source).map_err(|e| Error::io_with_path(e, &source))?;
+            }
+            tracing::info!("Moved map-scoped BIN {} -> {}", bin.file, file);
+            bin.file = file;
+            Ok(bin)
+        })
+        .collect()
+}
+
+/// Copy each BIN's champion objects into the concat BIN and remove the BIN.
+/// BINs already in a map WAD are left alone.
+fn merge_into_concat(
+    content_base: &Path,
+    file_base: &Path,
+    concat_path: &str,
+    bins: Vec<MapScopedBin>,
+) -> Result<Vec<MapScopedBin>> {
+    let concat_full = safe_join(file_base, concat_path)?;
+    let data = fs::read(&concat_full).map_err(|e| Error::io_with_path(e, &concat_full))?;
+    let mut concat = read_bin(&data).map_err(|e| Error::InvalidInput(format!("Failed to parse concat BIN: {}", e)))?;
+    let concat_file = concat_full.strip_prefix(content_base).unwrap_or(&concat_full).to_string_lossy().replace('\\', "/");
+
+    let mut merged: Vec<(PathBuf, MapScopedBin)> = Vec::new();
+    let mut kept = Vec::new();
+    for bin in bins {
+        if bin.in_map_wad() {
+            kept.push(bin);
+            continue;
+        }
+        let source = safe_join(content_base, &bin.file)?;
+        let data = fs::read(&source).map_err(|e| Error::io_with_path(e, &source))?;
+        let tree = read_bin(&data).map_err(|e| Error::InvalidInput(format!("Failed to parse {}: {}", bin.file, e)))?;
+        // The champion's own objects win over the map's copies of them
+        for (path_hash, object) in tree.objects {
+            if bin.objects.contains(&path_hash) && !concat.objects.contains_key(&path_hash) {
+                concat.objects.insert(path_hash, object);
+            }
+        }
+        tracing::info!("Pulled {} objects from {} into {}", bin.objects.len(), bin.file, concat_file);
+        merged.push((source, MapScopedBin { file: concat_file.clone(), ..bin }));
+    }
+    if merged.is_empty() {
+        return Ok(kept);
+    }
+
+    let data = write_bin(&concat).map_err(|e| Error::InvalidInput(format!("Failed to write concat BIN: {}", e)))?;
+    fs::write(&concat_full, data).map_err(|e| Error::io_with_path(e, &concat_full))?;
+    for (source, bin) in merged {
+        if let Err(e) = fs::remove_file(&source) {
+            tracing::warn!("Failed to remove merged map BIN {}: {}", source.display(), e);
+        }
+        kept.push(bin);
+    }
+    kept.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_scoped_paths() {
+        assert!(is_map_scoped("DATA\\Maps\\Shipping\\Map11\\Map11.bin"));
+        assert!(!is_map_scoped("data/characters/ahri/skins/skin0.bin"));
+        assert!(is_map_wad("Map11.wad.client"));
+        assert!(!is_map_wad("ahri.wad.client"));
+
+        assert_eq!(
+            split_wad_folder("ahri.wad.client/data/maps/shipping/map11/map11.bin"),
+            (Some("ahri.wad.client"), "data/maps/shipping/map11/map11.bin")
+        );
+        assert_eq!(split_wad_folder("data/maps/a.bin"), (None, "data/maps/a.bin"));
+
+        let bin = |file: &str| MapScopedBin { file: file.to_string(), path: String::new(), objects: Vec::new() };
+        assert!(bin("map11.wad.client/data/maps/a.bin").in_map_wad());
+        assert!(!bin("ahri.wad.client/data/maps/a.bin").in_map_wad());
+        assert!(!bin("data/maps/a.bin").in_map_wad());
+    }
+}
@@ -6,11 +6,36 @@
 //! The module is organized as follows:
 //! - `refather`: Core path modification logic
 //! - `organizer`: High-level orchestrator that coordinates concat and repath operations
+//! - `skins`: Detects which skins the extracted content is for
+//! - `quarantine`: Holds files removed by cleanup until they are restored or purged
+//! - `rebase`: Remaps references that a game patch left stale
+//! - `maps`: Finds and packs map-scoped BINs that reference the champion
+//! - `prefix`: Detects and strips repath prefixes left by other mods
 
 pub mod refather;
 pub mod organizer;
+pub mod skins;
+pub mod quarantine;
+pub mod rebase;
+pub mod maps;
+pub mod prefix;
 
 #[allow(unused_imports)]
-pub use refather::{repath_project, RepathConfig, RepathResult};
+pub use refather::{
+    replace_asset_paths, repath_bin_references, repath_project, PathReplaceResult, RepathConfig, RepathFileError, RepathResult, RepathWarning,
+    RepathWarningKind,
+};
 #[allow(unused_imports)]
 pub use organizer::{organize_project, OrganizerConfig, OrganizerResult};
+#[allow(unused_imports)]
+pub use skins::{detect_skin_ids, reconcile_skin_id, SkinCheck, SkinDetection};
+#[allow(unused_imports)]
+pub use quarantine::{
+    list_quarantine, purge_quarantine, restore_quarantine, Quarantine, QuarantineBatch, QuarantineRestore,
+};
+#[allow(unused_imports)]
+pub use rebase::{apply_rebase, plan_rebase, PathRemap, RebaseReport, RebaseTarget};
+#[allow(unused_imports)]
+pub use maps::{find_map_scoped_bins, is_map_scoped, is_map_wad, MapOverrideMode, MapScopedBin, MAP_WAD_FOLDER};
+#[allow(unused_imports)]
+pub use prefix::{detect_repath_prefix, strip_repath_prefix, PrefixStripResult};
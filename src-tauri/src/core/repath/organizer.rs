@@ -6,8 +6,11 @@
 use crate::core::bin::concat::{
     concatenate_linked_bins, ConcatResult,
 };
+use crate::core::operation::{CancellationToken, PhaseTimer, PhaseTiming};
+use crate::core::project::Project;
+use crate::core::repath::maps::{apply_map_overrides, MapOverrideMode, MapScopedBin};
 use crate::core::repath::refather::{repath_project, RepathConfig, RepathResult};
-use crate::error::Result;
+use crate::error::{Error, Result};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
@@ -29,6 +32,12 @@ pub struct OrganizerConfig {
     pub target_skin_id: u32,
     /// Clean up unused/orphaned files after processing
     pub cleanup_unused: bool,
+    /// Delete unused files outright instead of moving them to the project's quarantine
+    pub delete_unused: bool,
+    /// What to do with map-scoped BINs that reference the champion
+    pub map_overrides: MapOverrideMode,
+    /// Another mod's repath prefix recorded for the project
+    pub foreign_prefix: Option<String>,
 }
 
 impl OrganizerConfig {
@@ -48,6 +57,9 @@ impl OrganizerConfig {
             champion,
             target_skin_id,
             cleanup_unused: true,
+            delete_unused: false,
+            map_overrides: MapOverrideMode::Keep,
+            foreign_prefix: None,
         }
     }
 
@@ -67,6 +79,9 @@ impl OrganizerConfig {
             champion,
             target_skin_id,
             cleanup_unused: false,
+            delete_unused: false,
+            map_overrides: MapOverrideMode::Keep,
+            foreign_prefix: None,
         }
     }
 
@@ -86,6 +101,91 @@ impl OrganizerConfig {
             champion,
             target_skin_id,
             cleanup_unused: true,
+            delete_unused: false,
+            map_overrides: MapOverrideMode::Keep,
+            foreign_prefix: None,
+        }
+    }
+
+    /// Build a full concat + repath config from a project's own identity.
+    ///
+    /// Explicit names win; otherwise the creator is the project's first author
+    /// and the prefix name is its slug. `fallback_creator` (the settings
+    /// creator) is only used when the project has no author. Anything that
+    /// didn't come from the project is reported in the returned warnings.
+    pub fn for_project(
+        project: &Project,
+        creator_name: Option<String>,
+        project_name: Option<String>,
+        fallback_creator: Option<String>,
+    ) -> Result<(Self, Vec<String>)> {
+        let mut warnings = Vec::new();
+        let non_empty = |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+
+        let creator_name = match non_empty(creator_name)
+            .or_else(|| non_empty(project.authors.first().cloned()))
+        {
+            Some(creator) => creator,
+            None => {
+                let creator = non_empty(fallback_creator).ok_or_else(|| Error::InvalidInput(
+                    "The project has no author and no creator name is set in settings".to_string(),
+                ))?;
+                warnings.push(format!(
+                    "The project has no author; using the creator name from settings ('{}')",
+                    creator
+                ));
+                creator
+            }
+        };
+
+        let project_name = match non_empty(project_name).or_else(|| non_empty(Some(project.name.clone()))) {
+            Some(name) => name,
+            None => {
+                let name = project
+                    .project_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .and_then(|n| non_empty(Some(n)))
+                    .ok_or_else(|| Error::InvalidInput("The project has no name".to_string()))?;
+                warnings.push(format!("The project has no name; using its folder name ('{}')", name));
+                name
+            }
+        };
+
+        // Without a champion the main skin BIN can't be found, so there is nothing
+        // to concat into and no way to tell which BINs are irrelevant
+        let known_champion = !project.champion.is_empty();
+        if !known_champion {
+            warnings.push(
+                "The project has no champion; BIN concatenation and cleanup were skipped".to_string(),
+            );
+        }
+
+        let config = Self {
+            enable_concat: known_champion,
+            enable_repath: true,
+            creator_name,
+            project_name,
+            champion: project.champion.clone(),
+            target_skin_id: project.skin_id,
+            cleanup_unused: known_champion,
+            delete_unused: false,
+            map_overrides: MapOverrideMode::Keep,
+            foreign_prefix: project.foreign_prefix.clone(),
+        };
+        Ok((config, warnings))
+    }
+
+    /// The settings the repath step runs with
+    pub fn repath_config(&self) -> RepathConfig {
+        RepathConfig {
+            creator_name: self.creator_name.clone(),
+            project_name: self.project_name.clone(),
+            champion: self.champion.clone(),
+            target_skin_id: self.target_skin_id,
+            cleanup_unused: self.cleanup_unused,
+            delete_unused: self.delete_unused,
+            foreign_prefix: self.foreign_prefix.clone(),
         }
     }
 }
@@ -97,6 +197,10 @@ pub struct OrganizerResult {
     pub concat_result: Option<ConcatResult>,
     /// Result of repathing operation (if enabled)
     pub repath_result: Option<RepathResult>,
+    /// Map-scoped BINs referencing the champion, where they were packed
+    pub map_bins: Vec<MapScopedBin>,
+    /// Time spent in each step that ran
+    pub timings: Vec<PhaseTiming>,
 }
 
 impl OrganizerResult {
@@ -114,17 +218,22 @@ impl OrganizerResult {
 /// Orchestrates concat and repath operations based on the provided config.
 /// Operations are run in the following order:
 /// 1. Concat (if enabled) - Merge linked Type 3 BINs
-/// 2. Repath (if enabled) - Prefix asset paths
+/// 2. Map overrides - Apply `map_overrides` to map-scoped BINs referencing the champion
+/// 3. Repath (if enabled) - Prefix asset paths
 ///
 /// # Arguments
 /// * `content_base` - Path to the content/base directory of the project
 /// * `config` - Configuration controlling which operations to run
 /// * `path_mappings` - Mappings from original paths to actual paths (for hash-named files)
+/// * `cancel` - Checked between steps; a step that has started runs to completion
 pub fn organize_project(
     content_base: &Path,
     config: &OrganizerConfig,
     path_mappings: &HashMap<String, String>,
+    cancel: Option<&CancellationToken>,
 ) -> Result<OrganizerResult> {
+    let check_cancelled = || cancel.map_or(Ok(()), |token| token.check());
+
     tracing::info!(
         "Starting project organization (concat: {}, repath: {})",
         config.enable_concat,
@@ -134,7 +243,10 @@ pub fn organize_project(
     let mut result = OrganizerResult {
         concat_result: None,
         repath_result: None,
+        map_bins: Vec::new(),
+        timings: Vec::new(),
     };
+    let mut timer = PhaseTimer::new();
 
     // Compute the WAD folder path: content_base/{champion}.wad.client/
     // This is required for league-mod compatible project structure
@@ -153,6 +265,7 @@ pub fn organize_project(
     };
 
     // Step 1: Find the main skin BIN (needed for both concat and repath)
+    timer.phase("find_skin_bin");
     let main_bin_path = if !config.champion.is_empty() {
         find_main_skin_bin(&file_base, &config.champion, config.target_skin_id)
     } else {
@@ -160,7 +273,9 @@ pub fn organize_project(
     };
 
     // Step 2: Run concat if enabled
+    check_cancelled()?;
     if config.enable_concat {
+        timer.phase("concat");
         if let Some(ref main_path) = main_bin_path {
             tracing::info!("Running BIN concatenation...");
             match concatenate_linked_bins(
@@ -189,20 +304,24 @@ pub fn organize_project(
         }
     }
 
-    // Step 3: Run repath if enabled
+    // Step 3: Map-scoped BINs, before repathing so it sees where they end up
+    check_cancelled()?;
+    if !config.champion.is_empty() {
+        timer.phase("map_overrides");
+        let concat_path = result.concat_result.as_ref().map(|r| r.concat_path.as_str());
+        match apply_map_overrides(content_base, &file_base, &config.champion, config.map_overrides, concat_path) {
+            Ok(map_bins) => result.map_bins = map_bins,
+            Err(e) => tracing::warn!("Handling map-scoped BINs failed: {}", e),
+        }
+    }
+
+    // Step 4: Run repath if enabled
+    check_cancelled()?;
     if config.enable_repath {
+        timer.phase("repath");
         tracing::info!("Running asset repathing...");
         
-        // Build RepathConfig from OrganizerConfig
-        let repath_config = RepathConfig {
-            creator_name: config.creator_name.clone(),
-            project_name: config.project_name.clone(),
-            champion: config.champion.clone(),
-            target_skin_id: config.target_skin_id,
-            cleanup_unused: config.cleanup_unused,
-        };
-
-        match repath_project(content_base, &repath_config, path_mappings) {
+        match repath_project(content_base, &config.repath_config(), path_mappings) {
             Ok(repath_result) => {
                 tracing::info!(
                     "Repathing complete: {} paths modified, {} files relocated",
@@ -217,6 +336,7 @@ pub fn organize_project(
         }
     }
 
+    result.timings = timer.finish();
     tracing::info!("Project organization complete");
     Ok(result)
 }
@@ -346,4 +466,28 @@ mod tests {
         assert!(!config.enable_concat);
         assert!(config.enable_repath);
     }
+
+
+    #[test]
+    fn test_organizer_config_for_project() {
+        let mut project = Project::new("My Mod", "Kayn", 8, "C:/League", "/mods/my-mod", Some("Dexal".to_string()));
+
+        let (config, warnings) = OrganizerConfig::for_project(&project, None, None, Some("Other".to_string())).unwrap();
+        assert_eq!(config.creator_name, "Dexal");
+        assert_eq!(config.project_name, "my-mod");
+        assert_eq!((config.champion.as_str(), config.target_skin_id), ("Kayn", 8));
+        assert!(config.cleanup_unused);
+        assert!(warnings.is_empty());
+
+        project.authors.clear();
+        project.champion.clear();
+        let (config, warnings) = OrganizerConfig::for_project(&project, None, None, Some("Other".to_string())).unwrap();
+        assert_eq!(config.creator_name, "Other");
+        assert!(!config.enable_concat && !config.cleanup_unused);
+        assert_eq!(warnings.len(), 2);
+
+        assert!(OrganizerConfig::for_project(&project, None, None, None).is_err());
+        let (config, _) = OrganizerConfig::for_project(&project, Some("Me".to_string()), None, None).unwrap();
+        assert_eq!(config.creator_name, "Me");
+    }
 }
@@ -0,0 +1,225 @@
+//! Repath prefixes left by other mods
+//!
+//! A mod built with a repath tool ships its files under
+//! `ASSETS/{creator}/{project}/...`. Repathing such content under another
+//! name nests the prefixes, or orphans the files when only some references
+//! are rewritten. `detect_repath_prefix` finds the prefix most of a project's
+//! references use; `strip_repath_prefix` rewrites those references back to
+//! canonical `assets/` and `data/` paths and moves the files to match.
+
+use crate::core::paths::safe_join;
+use crate::core::repath::refather::{cleanup_empty_dirs, replace_asset_paths, scan_bin_for_paths, RepathFileError};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Folders a repathed path continues with after `ASSETS/{creator}/{project}/`
+const CANONICAL_ROOTS: &[&str] = &["characters", "shared", "maps", "particles", "spells", "items", "ux"];
+
+/// References a prefix needs before it counts as the project's
+const MIN_PREFIX_REFERENCES: usize = 3;
+
+/// Result of `strip_repath_prefix`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrefixStripResult {
+    /// The prefix that was stripped, as "creator/project"
+    pub prefix: String,
+    pub bins_modified: usize,
+    pub paths_rewritten: usize,
+    pub files_relocated: usize,
+    /// Prefixed references whose file is in neither place; they were left as they are
+    pub missing_paths: Vec<String>,
+    /// BINs that couldn't be rewritten
+    pub failed_bins: Vec<RepathFileError>,
+}
+
+/// The "creator/project" part of a repathed path such as
+/// `assets/creator/project/characters/...`, lowercased
+pub fn path_prefix(path: &str) -> Option<String> {
+    let path = path.to_lowercase().replace('\\', "/");
+    let segments: Vec<&str> = path.split('/').collect();
+    let repathed = segments.len() >= 5
+        && segments[0] == "assets"
+        && !CANONICAL_ROOTS.contains(&segments[1])
+        && CANONICAL_ROOTS.contains(&segments[3]);
+    repathed.then(|| format!("{}/{}", segments[1], segments[2]))
+}
+
+/// The prefix most of `paths` use, if enough of them do. Ties go to the
+/// alphabetically first prefix.
+pub fn dominant_prefix<'a>(paths: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for prefix in paths.into_iter().filter_map(path_prefix) {
+        *counts.entry(prefix).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_PREFIX_REFERENCES)
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(prefix, _)| prefix)
+}
+
+/// The repath prefix the BINs under `content_base` mostly use. BINs that
+/// can't be read are skipped.
+pub fn detect_repath_prefix(content_base: &Path) -> Option<String> {
+    let paths = referenced_paths(content_base);
+    let prefix = dominant_prefix(paths.iter().map(String::as_str));
+    if let Some(prefix) = &prefix {
+        tracing::info!("Detected repath prefix ASSETS/{} in {}", prefix, content_base.display());
+    }
+    prefix
+}
+
+/// Undo `apply_prefix_to_path` for one path: drop `ASSETS/{prefix}/`, put
+/// BINs back under `data/` and everything else under `assets/`, and turn
+/// the project's character folder back into `champion`'s. Skin IDs can't be
+/// recovered and stay as they are. `None` if `path` isn't under `prefix`.
+pub fn canonical_path(path: &str, prefix: &str, champion: &str) -> Option<String> {
+    let normalized = path.to_lowercase().replace('\\', "/");
+    let prefix = prefix.to_lowercase();
+    let rest = normalized.strip_prefix("assets/")?.strip_prefix(prefix.as_str())?.strip_prefix('/')?;
+
+    let mut segments: Vec<&str> = rest.split('/').collect();
+    let project = prefix.rsplit('/').next().unwrap_or_default();
+    let champion = champion.to_lowercase();
+    if segments.len() > 2
+        && segments[0] == "characters"
+        && !champion.is_empty()
+        && segments[1].replace(' ', "-") == project
+    {
+        segments[1] = &champion;
+    }
+    let rest = segments.join("/");
+
+    let root = if rest.ends_with(".bin") { "data" } else { "assets" };
+    Some(format!("{}/{}", root, rest))
+}
+
+/// Rewrite every reference under `ASSETS/{prefix}/` to its canonical path
+/// and move the files there. A reference is only rewritten when its file is
+/// in the project at either path; files already at their canonical path are
+/// not overwritten.
+pub fn strip_repath_prefix(content_base: &Path, prefix: &str, champion: &str) -> Result<PrefixStripResult> {
+    if !content_base.exists() {
+        return Err(Error::InvalidInput(format!(
+            "Content base directory not found: {}",
+            content_base.display()
+        )));
+    }
+    let prefix = prefix.to_lowercase();
+    let prefix = prefix.trim_matches('/').trim_start_matches("assets/").to_string();
+    tracing::info!("Stripping repath prefix ASSETS/{} from {}", prefix, content_base.display());
+
+    // Same layout rule as `repath_project`
+    let wad_base = content_base.join(format!("{}.wad.client", champion.to_lowercase()));
+    let file_base = if wad_base.exists() { wad_base.as_path() } else { content_base };
+
+    let mut result = PrefixStripResult { prefix: prefix.clone(), ..Default::default() };
+    let mut replacements = HashMap::new();
+    for path in referenced_paths(content_base) {
+        let Some(canonical) = canonical_path(&path, &prefix, champion) else {
+            continue;
+        };
+        let (source, dest) = match (safe_join(file_base, &path), safe_join(file_base, &canonical)) {
+            (Ok(source), Ok(dest)) => (source, dest),
+            (Err(e), _) | (_, Err(e)) => {
+                tracing::warn!("Skipping {}: {}", path, e);
+                continue;
+            }
+        };
+
+        if dest.exists() {
+            tracing::debug!("{} is already in place", canonical);
+        } else if source.exists() {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+            }
+            if fs::rename(&source, &dest).is_err() {
+                fs::copy(&source, &dest).map_err(|e| Error::io_with_path(e, &source))?;
+                fs::remove_file(&source).map_err(|e| Error::io_with_path(e, &source))?;
+            }
+            result.files_relocated += 1;
+        } else {
+            result.missing_paths.push(path);
+            continue;
+        }
+        replacements.insert(path, canonical);
+    }
+
+    let replaced = replace_asset_paths(content_base, &replacements);
+    result.bins_modified = replaced.bins_modified;
+    result.paths_rewritten = replaced.paths_replaced;
+    result.failed_bins = replaced.failed_bins;
+    cleanup_empty_dirs(file_base)?;
+
+    tracing::info!(
+        "Stripped ASSETS/{}: {} references in {} BINs, {} files moved, {} missing",
+        prefix,
+        result.paths_rewritten,
+        result.bins_modified,
+        result.files_relocated,
+        result.missing_paths.len()
+    );
+    Ok(result)
+}
+
+/// Every asset path the BINs under `content_base` reference, normalized
+fn referenced_paths(content_base: &Path) -> BTreeSet<String> {
+    let mut paths = BTreeSet::new();
+    for entry in WalkDir::new(content_base)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+    {
+        match scan_bin_for_paths(entry.path()) {
+            Ok(found) => paths.extend(found),
+            Err(e) => tracing::debug!("Skipping {} in prefix scan: {}", entry.path().display(), e),
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_detection_and_inverse() {
+        assert_eq!(
+            path_prefix("ASSETS/SirDexal/Renny/characters/Renny/skins/skin42/base.skn").as_deref(),
+            Some("sirdexal/renny")
+        );
+        assert_eq!(path_prefix("assets/characters/renekton/skins/skin0/base.skn"), None);
+        assert_eq!(path_prefix("assets/shared/particles/glow.tex"), None);
+
+        let paths = [
+            "assets/sirdexal/renny/characters/renny/skins/skin42/a.tex",
+            "assets/sirdexal/renny/characters/renny/skins/skin42/b.tex",
+            "assets/sirdexal/renny/shared/c.tex",
+            "assets/other/mod/characters/x/d.tex",
+            "assets/characters/renekton/e.tex",
+        ];
+        assert_eq!(dominant_prefix(paths).as_deref(), Some("sirdexal/renny"));
+        assert_eq!(dominant_prefix(paths[3..].iter().copied()), None);
+
+        assert_eq!(
+            canonical_path(
+                "ASSETS/SirDexal/Renny/characters/Renny/skins/skin42/renekton_skin42_base.skn",
+                "sirdexal/renny",
+                "Renekton"
+            )
+            .as_deref(),
+            Some("assets/characters/renekton/skins/skin42/renekton_skin42_base.skn")
+        );
+        assert_eq!(
+            canonical_path("assets/sirdexal/renny/characters/renny/skins/skin42.bin", "sirdexal/renny", "Renekton")
+                .as_deref(),
+            Some("data/characters/renekton/skins/skin42.bin")
+        );
+        assert_eq!(canonical_path("assets/characters/renekton/a.tex", "sirdexal/renny", "Renekton"), None);
+    }
+}
@@ -0,0 +1,234 @@
+//! Quarantine for files removed by repath cleanup
+//!
+//! Cleanup decides what to remove by comparing normalized paths, so a
+//! normalization bug (e.g. a case mismatch) would destroy textures the user
+//! added but hasn't referenced yet. Instead of deleting them, cleanup moves
+//! them to `.flint/quarantine/{id}/`, keeping their path relative to the
+//! project root, where they can be restored or purged.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Quarantine root inside a project
+pub const QUARANTINE_DIR: &str = ".flint/quarantine";
+
+/// Files moved out of the content by one cleanup run
+pub struct Quarantine {
+    project_path: PathBuf,
+    id: String,
+    files: Vec<String>,
+}
+
+impl Quarantine {
+    /// Start a batch named after the current time, e.g. "20261016-143005123"
+    pub fn new(project_path: &Path) -> Self {
+        Self {
+            project_path: project_path.to_path_buf(),
+            id: chrono::Utc::now().format("%Y%m%d-%H%M%S%3f").to_string(),
+            files: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Move `file` (inside the project) into the batch
+    pub fn add(&mut self, file: &Path) -> Result<()> {
+        let rel_path = file.strip_prefix(&self.project_path).map_err(|_| {
+            Error::InvalidInput(format!("{} is outside the project", file.display()))
+        })?;
+        let dest = batch_dir(&self.project_path, &self.id).join(rel_path);
+        move_file(file, &dest)?;
+        self.files.push(rel_path.to_string_lossy().replace('\\', "/"));
+        Ok(())
+    }
+
+    /// Project-relative paths of the quarantined files
+    pub fn into_files(self) -> Vec<String> {
+        self.files
+    }
+}
+
+/// Summary of one quarantine batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineBatch {
+    pub id: String,
+    pub file_count: usize,
+    pub total_size: u64,
+}
+
+/// Result of restoring a batch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuarantineRestore {
+    pub restored: usize,
+    /// Files left in quarantine because the project has a file at their path again
+    pub conflicts: Vec<String>,
+}
+
+/// The project's quarantine batches, oldest first
+pub fn list_quarantine(project_path: &Path) -> Result<Vec<QuarantineBatch>> {
+    let root = project_path.join(QUARANTINE_DIR);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut batches = Vec::new();
+    for entry in fs::read_dir(&root).map_err(|e| Error::io_with_path(e, &root))? {
+        let entry = entry.map_err(|e| Error::io_with_path(e, &root))?;
+        if !entry.path().is_dir() {
+            continue;
+        }
+        let (file_count, total_size) = batch_files(&entry.path())
+            .filter_map(|p| p.metadata().ok())
+            .fold((0, 0), |(count, size), m| (count + 1, size + m.len()));
+        batches.push(QuarantineBatch {
+            id: entry.file_name().to_string_lossy().to_string(),
+            file_count,
+            total_size,
+        });
+    }
+    batches.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(batches)
+}
+
+/// Move a batch's files back to where they were. Files whose original path
+/// is taken are kept in quarantine and reported as conflicts.
+pub fn restore_quarantine(project_path: &Path, id: &str) -> Result<QuarantineRestore> {
+    let dir = existing_batch_dir(project_path, id)?;
+    let mut result = QuarantineRestore::default();
+
+    for file in batch_files(&dir).collect::<Vec<_>>() {
+        let Ok(rel_path) = file.strip_prefix(&dir) else {
+            continue;
+        };
+        let dest = project_path.join(rel_path);
+        if dest.exists() {
+            result.conflicts.push(rel_path.to_string_lossy().replace('\\', "/"));
+            continue;
+        }
+        move_file(&file, &dest)?;
+        result.restored += 1;
+    }
+
+    if result.conflicts.is_empty() {
+        fs::remove_dir_all(&dir).map_err(|e| Error::io_with_path(e, &dir))?;
+    }
+    tracing::info!(
+        "Restored {} files from quarantine {} ({} conflicts)",
+        result.restored,
+        id,
+        result.conflicts.len()
+    );
+    Ok(result)
+}
+
+/// Delete one batch, or all of them when `id` is `None`. Returns the number
+/// of files deleted.
+pub fn purge_quarantine(project_path: &Path, id: Option<&str>) -> Result<usize> {
+    let dir = match id {
+        Some(id) => existing_batch_dir(project_path, id)?,
+        None => project_path.join(QUARANTINE_DIR),
+    };
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let count = batch_files(&dir).count();
+    fs::remove_dir_all(&dir).map_err(|e| Error::io_with_path(e, &dir))?;
+    tracing::info!("Purged {} quarantined files from {}", count, dir.display());
+    Ok(count)
+}
+
+fn batch_dir(project_path: &Path, id: &str) -> PathBuf {
+    project_path.join(QUARANTINE_DIR).join(id)
+}
+
+/// Resolve a batch ID from the frontend, refusing anything but a plain name
+fn existing_batch_dir(project_path: &Path, id: &str) -> Result<PathBuf> {
+    if id.is_empty() || id == "." || id == ".." || id.contains(['/', '\\']) {
+        return Err(Error::InvalidInput(format!("Invalid quarantine ID '{}'", id)));
+    }
+    let dir = batch_dir(project_path, id);
+    if !dir.is_dir() {
+        return Err(Error::NotFound(format!("Quarantine '{}' not found", id)));
+    }
+    Ok(dir)
+}
+
+fn batch_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+}
+
+/// Rename, falling back to copy + remove across devices
+fn move_file(source: &Path, dest: &Path) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+    }
+    if fs::rename(source, dest).is_err() {
+        fs::copy(source, dest).map_err(|e| Error::io_with_path(e, source))?;
+        fs::remove_file(source).map_err(|e| Error::io_with_path(e, source))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_quarantine_restore_and_purge() {
+        let dir = tempdir().unwrap();
+        let texture = dir.path().join("content/base/ahri.wad.client/assets/my_texture.dds");
+        fs::create_dir_all(texture.parent().unwrap()).unwrap();
+        fs::write(&texture, b"DDS").unwrap();
+
+        let mut quarantine = Quarantine::new(dir.path());
+        quarantine.add(&texture).unwrap();
+        let id = quarantine.id().to_string();
+        assert_eq!(quarantine.into_files(), ["content/base/ahri.wad.client/assets/my_texture.dds"]);
+        assert!(!texture.exists());
+
+        let batches = list_quarantine(dir.path()).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!((batches[0].file_count, batches[0].total_size), (1, 3));
+
+        let restored = restore_quarantine(dir.path(), &id).unwrap();
+        assert_eq!(restored.restored, 1);
+        assert!(texture.exists());
+        assert!(list_quarantine(dir.path()).unwrap().is_empty());
+
+        let mut quarantine = Quarantine::new(dir.path());
+        quarantine.add(&texture).unwrap();
+        assert!(restore_quarantine(dir.path(), "../..").is_err());
+        assert_eq!(purge_quarantine(dir.path(), None).unwrap(), 1);
+        assert!(!dir.path().join(QUARANTINE_DIR).exists());
+    }
+
+    #[test]
+    fn test_restore_keeps_conflicting_files() {
+        let dir = tempdir().unwrap();
+        let texture = dir.path().join("content/base/a.dds");
+        fs::create_dir_all(texture.parent().unwrap()).unwrap();
+        fs::write(&texture, b"old").unwrap();
+
+        let mut quarantine = Quarantine::new(dir.path());
+        quarantine.add(&texture).unwrap();
+        let id = quarantine.id().to_string();
+        fs::write(&texture, b"new").unwrap();
+
+        let restored = restore_quarantine(dir.path(), &id).unwrap();
+        assert_eq!(restored.restored, 0);
+        assert_eq!(restored.conflicts, ["content/base/a.dds"]);
+        assert_eq!(fs::read(&texture).unwrap(), b"new");
+        assert_eq!(list_quarantine(dir.path()).unwrap()[0].file_count, 1);
+    }
+}
@@ -0,0 +1,263 @@
+//! Migrating a project to a new game patch
+//!
+//! Patches sometimes move or rename assets, leaving a project's BINs pointing
+//! at paths the game no longer has. `plan_rebase` lists the references into
+//! the champion's own folders that neither the project nor the current
+//! champion WAD provides, and proposes a new path for each one the WAD has a
+//! file of the same name for. `apply_rebase` rewrites the approved ones with
+//! `replace_asset_paths`.
+
+use crate::core::hash::Hashtable;
+use crate::core::mapping::normalize_path;
+use crate::core::operation::CancellationToken;
+use crate::core::repath::refather::{replace_asset_paths, scan_bin_for_paths, RepathFileError};
+use crate::core::wad::extractor::extract_chunk;
+use crate::core::wad::reader::WadReader;
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// What to rebase and against which game files
+pub struct RebaseTarget<'a> {
+    /// The project's `content/base`
+    pub content_base: &'a Path,
+    /// The current game's champion WAD
+    pub wad_path: &'a Path,
+    pub champion: &'a str,
+    pub skin_id: u32,
+    pub hashtable: &'a Hashtable,
+    /// Scratch directory for the game's skin BIN; removed afterwards
+    pub temp_dir: &'a Path,
+}
+
+/// A proposed replacement for a stale path reference
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathRemap {
+    pub old_path: String,
+    pub new_path: String,
+}
+
+/// Result of `rebase_project`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RebaseReport {
+    /// Proposed remappings, sorted by old path
+    pub remappings: Vec<PathRemap>,
+    /// Stale references without a single best match; these need manual attention
+    pub unresolved: Vec<String>,
+    /// Remappings written to the project's BINs
+    pub applied: usize,
+    pub bins_modified: usize,
+    /// BINs that couldn't be scanned or rewritten
+    pub failed_bins: Vec<RepathFileError>,
+}
+
+/// Find the project's stale references and propose remappings. Nothing in
+/// the project is changed.
+pub fn plan_rebase(target: &RebaseTarget, cancel: &CancellationToken) -> Result<RebaseReport> {
+    let mut report = RebaseReport::default();
+
+    let mut project_refs = HashSet::new();
+    for bin_path in find_bins(target.content_base) {
+        cancel.check()?;
+        match scan_bin_for_paths(&bin_path) {
+            Ok(paths) => project_refs.extend(paths),
+            Err(e) => report.failed_bins.push(RepathFileError::new(&bin_path, e)),
+        }
+    }
+
+    let mut reader = WadReader::open(target.wad_path)?;
+    let game_paths: HashSet<String> = reader
+        .chunks()
+        .keys()
+        .map(|hash| normalize_path(&target.hashtable.resolve(*hash)))
+        .collect();
+    cancel.check()?;
+    let skin_refs = game_skin_refs(&mut reader, target);
+
+    let champion = target.champion.to_lowercase();
+    let namespaces = [format!("assets/characters/{}/", champion), format!("data/characters/{}/", champion)];
+    let file_base = target.content_base.join(format!("{}.wad.client", champion));
+    let mut stale: Vec<String> = project_refs
+        .into_iter()
+        .filter(|path| namespaces.iter().any(|ns| path.starts_with(ns.as_str())))
+        .filter(|path| !game_paths.contains(path) && !skin_refs.contains(path))
+        .filter(|path| !file_base.join(path).exists() && !target.content_base.join(path).exists())
+        .collect();
+    stale.sort();
+
+    let (remappings, unresolved) = propose_remappings(&stale, &game_paths, &skin_refs);
+    tracing::info!(
+        "Rebase found {} stale references: {} remappable, {} unresolved",
+        stale.len(),
+        remappings.len(),
+        unresolved.len()
+    );
+    report.remappings = remappings;
+    report.unresolved = unresolved;
+    Ok(report)
+}
+
+/// Write the remappings whose old path is in `approved` to the project's BINs
+pub fn apply_rebase(content_base: &Path, mut report: RebaseReport, approved: &[String]) -> RebaseReport {
+    let approved: HashSet<String> = approved.iter().map(|p| normalize_path(p)).collect();
+    let replacements: HashMap<String, String> = report
+        .remappings
+        .iter()
+        .filter(|remap| approved.contains(&remap.old_path))
+        .map(|remap| (remap.old_path.clone(), remap.new_path.clone()))
+        .collect();
+    if replacements.len() < approved.len() {
+        tracing::warn!(
+            "{} approved paths are not in the rebase proposal and were skipped",
+            approved.len() - replacements.len()
+        );
+    }
+    if replacements.is_empty() {
+        return report;
+    }
+
+    let result = replace_asset_paths(content_base, &replacements);
+    report.applied = replacements.len();
+    report.bins_modified = result.bins_modified;
+    report.failed_bins.extend(result.failed_bins);
+    report
+}
+
+/// Paths referenced by the current game's BIN for the project's skin
+fn game_skin_refs(reader: &mut WadReader, target: &RebaseTarget) -> HashSet<String> {
+    let skin_bin = format!(
+        "data/characters/{}/skins/skin{}.bin",
+        target.champion.to_lowercase(),
+        target.skin_id
+    );
+    let Some(chunk) = reader
+        .chunks()
+        .iter()
+        .find(|(hash, _)| target.hashtable.resolve(**hash).eq_ignore_ascii_case(&skin_bin))
+        .map(|(_, chunk)| *chunk)
+    else {
+        tracing::warn!("{} not found in {}", skin_bin, target.wad_path.display());
+        return HashSet::new();
+    };
+
+    let output = target.temp_dir.join(&skin_bin);
+    let refs = extract_chunk(reader, &chunk, &output, Some(target.hashtable))
+        .and_then(|_| scan_bin_for_paths(&output))
+        .map(|paths| paths.into_iter().collect())
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to read the game's {}: {}", skin_bin, e);
+            HashSet::new()
+        });
+    if let Err(e) = fs::remove_dir_all(target.temp_dir) {
+        tracing::debug!("Failed to remove {}: {}", target.temp_dir.display(), e);
+    }
+    refs
+}
+
+fn find_bins(content_base: &Path) -> Vec<PathBuf> {
+    WalkDir::new(content_base)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// Match each stale path to a game path with the same file name. Paths the
+/// new skin BIN references win, then the one sharing the most leading
+/// directories; a tie leaves the path unresolved.
+fn propose_remappings(
+    stale: &[String],
+    game_paths: &HashSet<String>,
+    skin_refs: &HashSet<String>,
+) -> (Vec<PathRemap>, Vec<String>) {
+    let mut by_name: HashMap<&str, Vec<&str>> = HashMap::new();
+    for path in game_paths.union(skin_refs) {
+        by_name.entry(file_name(path)).or_default().push(path);
+    }
+
+    let mut remappings = Vec::new();
+    let mut unresolved = Vec::new();
+    for old_path in stale {
+        let mut ranked: Vec<((bool, usize), &str)> = by_name
+            .get(file_name(old_path))
+            .into_iter()
+            .flatten()
+            .map(|candidate| ((skin_refs.contains(*candidate), shared_dirs(old_path, candidate)), *candidate))
+            .collect();
+        ranked.sort_by_key(|(rank, _)| std::cmp::Reverse(*rank));
+
+        match ranked.as_slice() {
+            [(best, new_path), rest @ ..] if rest.first().is_none_or(|(next, _)| next < best) => {
+                remappings.push(PathRemap { old_path: old_path.clone(), new_path: new_path.to_string() });
+            }
+            _ => unresolved.push(old_path.clone()),
+        }
+    }
+    (remappings, unresolved)
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Leading directories two paths have in common
+fn shared_dirs(a: &str, b: &str) -> usize {
+    let (a_dir, b_dir) = (a.rsplit_once('/').map_or("", |d| d.0), b.rsplit_once('/').map_or("", |d| d.0));
+    a_dir.split('/').zip(b_dir.split('/')).take_while(|(x, y)| x == y).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(paths: &[&str]) -> HashSet<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_propose_remappings() {
+        let game = set(&[
+            "assets/characters/ahri/skins/base/particles/ahri_base_orb.tex",
+            "assets/characters/ahri/skins/skin01/ahri_skin01_tx_cm.tex",
+            "assets/characters/ahri/hud/icons2d/ahri_circle.tex",
+            "assets/shared/icons2d/ahri_circle.tex",
+            "assets/characters/ahri/skins/base/a/glow.tex",
+            "assets/characters/ahri/skins/base/b/glow.tex",
+        ]);
+        let skin_refs = set(&["assets/characters/ahri/skins/skin01/ahri_skin01_tx_cm.tex"]);
+        let stale = [
+            "assets/characters/ahri/skins/base/ahri_base_orb.tex",
+            "assets/characters/ahri/icons2d/ahri_circle.tex",
+            "assets/characters/ahri/skins/skin1/ahri_skin01_tx_cm.tex",
+            "assets/characters/ahri/skins/base/glow.tex",
+            "assets/characters/ahri/skins/base/removed.tex",
+        ]
+        .map(String::from);
+
+        let (remappings, unresolved) = propose_remappings(&stale, &game, &skin_refs);
+        let new_path = |old: &str| remappings.iter().find(|r| r.old_path == old).map(|r| r.new_path.as_str());
+        assert_eq!(
+            new_path("assets/characters/ahri/skins/base/ahri_base_orb.tex"),
+            Some("assets/characters/ahri/skins/base/particles/ahri_base_orb.tex")
+        );
+        // More shared leading directories wins over the shared folder
+        assert_eq!(
+            new_path("assets/characters/ahri/icons2d/ahri_circle.tex"),
+            Some("assets/characters/ahri/hud/icons2d/ahri_circle.tex")
+        );
+        assert_eq!(
+            new_path("assets/characters/ahri/skins/skin1/ahri_skin01_tx_cm.tex"),
+            Some("assets/characters/ahri/skins/skin01/ahri_skin01_tx_cm.tex")
+        );
+        // Two equally good candidates, or none at all
+        assert_eq!(
+            unresolved,
+            ["assets/characters/ahri/skins/base/glow.tex", "assets/characters/ahri/skins/base/removed.tex"]
+        );
+        assert_eq!(shared_dirs("a/b/c/x.tex", "a/b/d/x.tex"), 2);
+    }
+}
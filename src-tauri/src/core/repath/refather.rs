@@ -7,16 +7,21 @@
 //! 4. Optionally combines linked BINs into a single concat BIN
 
 use crate::core::bin::ltk_bridge::{read_bin, write_bin};
+use crate::core::paths::safe_join;
+use crate::core::repath::maps::find_map_scoped_bins;
+use crate::core::repath::prefix::path_prefix;
+use crate::core::repath::quarantine::Quarantine;
 use crate::error::{Error, Result};
 use ltk_meta::PropertyValueEnum;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
 use walkdir::WalkDir;
+use crate::core::parallel;
 use rayon::prelude::*;
-use dashmap::DashSet;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
 
 /// Configuration for repathing operations
 /// 
@@ -29,6 +34,11 @@ pub struct RepathConfig {
     pub champion: String,
     pub target_skin_id: u32,
     pub cleanup_unused: bool,
+    /// Delete unused files instead of quarantining them
+    pub delete_unused: bool,
+    /// Another mod's prefix recorded for the project, as "creator/project";
+    /// references under it are left alone and reported
+    pub foreign_prefix: Option<String>,
 }
 
 impl RepathConfig {
@@ -45,8 +55,82 @@ pub struct RepathResult {
     pub bins_processed: usize,
     pub paths_modified: usize,
     pub files_relocated: usize,
+    /// Unused files taken out of the content, deleted or quarantined
     pub files_removed: usize,
+    /// Project-relative paths of the files moved to quarantine
+    pub quarantined: Vec<String>,
+    /// Batch to pass to `restore_quarantine`, if anything was quarantined
+    pub quarantine_id: Option<String>,
     pub missing_paths: Vec<String>,
+    /// BINs that couldn't be scanned or rewritten; the rest are still repathed
+    pub failed_bins: Vec<RepathFileError>,
+    /// Problems in the main BIN's dependency walk that may leave the mod
+    /// incomplete, and referenced paths that were ignored as unsafe
+    pub warnings: Vec<RepathWarning>,
+    /// Map-scoped BINs referencing the champion that were repathed too,
+    /// relative to `content/base`
+    pub map_bins: Vec<String>,
+}
+
+/// Kinds of `RepathWarning`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepathWarningKind {
+    /// The main skin BIN couldn't be read or parsed, so none of its
+    /// dependencies were followed
+    UnreadableMainBin,
+    /// A linked BIN exists but couldn't be parsed
+    UnparsableDependency,
+    /// A linked BIN isn't in the project
+    DependencyNotFound,
+    /// A linked BIN was found through the project's path mappings
+    MappingFallback,
+    /// A BIN string or path mapping names a file outside the project; it
+    /// was ignored
+    UnsafePath,
+    /// References already under this project's prefix; they weren't prefixed again
+    AlreadyPrefixed,
+    /// References under the other mod's prefix recorded for the project;
+    /// they weren't prefixed, so that prefix should be stripped first
+    ForeignPrefix,
+}
+
+impl RepathWarningKind {
+    /// Whether an export should stop on this unless forced: the packed mod
+    /// would be missing whatever the BIN defines
+    pub fn is_blocking(self) -> bool {
+        matches!(self, Self::UnreadableMainBin | Self::UnparsableDependency)
+    }
+}
+
+/// Something the repath worked around that the user should know about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepathWarning {
+    pub kind: RepathWarningKind,
+    /// The BIN concerned, as found on disk or as linked
+    pub path: String,
+    pub message: String,
+}
+
+impl RepathWarning {
+    fn new(kind: RepathWarningKind, path: impl Into<String>, message: impl Into<String>) -> Self {
+        let warning = Self { kind, path: path.into(), message: message.into() };
+        tracing::warn!("Repath: {} ({})", warning.message, warning.path);
+        warning
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepathFileError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl RepathFileError {
+    pub(crate) fn new(path: &Path, error: Error) -> Self {
+        tracing::warn!("Failed to repath {}: {}", path.display(), error);
+        Self { path: path.to_path_buf(), message: error.to_string() }
+    }
 }
 
 /// Repath all assets in a project directory
@@ -88,7 +172,12 @@ pub fn repath_project(
         paths_modified: 0,
         files_relocated: 0,
         files_removed: 0,
+        quarantined: Vec::new(),
+        quarantine_id: None,
         missing_paths: Vec::new(),
+        failed_bins: Vec::new(),
+        warnings: Vec::new(),
+        map_bins: Vec::new(),
     };
 
     // Step 0: Find the main skin BIN (now using file_base)
@@ -105,25 +194,55 @@ pub fn repath_project(
         bin_files.push(main_path.clone());
 
         // Read the main BIN to get its linked BINs
-        if let Ok(data) = fs::read(main_path) {
-            if let Ok(bin) = read_bin(&data) {
+        let main_bin = fs::read(main_path)
+            .map_err(|e| Error::io_with_path(e, main_path))
+            .and_then(|data| read_bin(&data).map_err(|e| Error::InvalidInput(format!("Failed to parse BIN: {}", e))));
+        match main_bin {
+            Ok(bin) => {
                 tracing::info!("Main skin BIN has {} dependencies", bin.dependencies.len());
-                
+
                 for dep_path in &bin.dependencies {
                     let normalized_path = dep_path.to_lowercase().replace('\\', "/");
 
-                    let actual_path = path_mappings.get(&normalized_path)
-                        .cloned()
-                        .unwrap_or_else(|| normalized_path.clone());
-                    
-                    let full_path = file_base.join(&actual_path);
+                    let actual_path = match path_mappings.get(&normalized_path) {
+                        Some(mapped) => {
+                            result.warnings.push(RepathWarning::new(
+                                RepathWarningKind::MappingFallback,
+                                &normalized_path,
+                                format!("Linked BIN is stored as {}", mapped),
+                            ));
+                            mapped.clone()
+                        }
+                        None => normalized_path.clone(),
+                    };
+
+                    let full_path = match safe_join(file_base, &actual_path) {
+                        Ok(full_path) => full_path,
+                        Err(e) => {
+                            result.warnings.push(RepathWarning::new(
+                                RepathWarningKind::UnsafePath,
+                                &normalized_path,
+                                e.to_string(),
+                            ));
+                            continue;
+                        }
+                    };
                     if full_path.exists() {
                         bin_files.push(full_path);
                     } else {
-                        tracing::warn!("Linked BIN not found: {}", normalized_path);
+                        result.warnings.push(RepathWarning::new(
+                            RepathWarningKind::DependencyNotFound,
+                            &normalized_path,
+                            "Linked BIN not found",
+                        ));
                     }
                 }
             }
+            Err(e) => result.warnings.push(RepathWarning::new(
+                RepathWarningKind::UnreadableMainBin,
+                main_path.to_string_lossy(),
+                format!("Main skin BIN couldn't be read, so its linked BINs were skipped: {}", e),
+            )),
         }
     } else {
         tracing::warn!("No main skin BIN found, falling back to scanning all BINs");
@@ -140,56 +259,142 @@ pub fn repath_project(
             .collect();
     }
 
+    // Map data that uses the champion's files, wherever it is packed
+    for map_bin in find_map_scoped_bins(content_base, &config.champion) {
+        let full_path = content_base.join(&map_bin.file);
+        if !bin_files.contains(&full_path) {
+            bin_files.push(full_path);
+        }
+        tracing::info!("Including map-scoped BIN {}", map_bin.file);
+        result.map_bins.push(map_bin.file);
+    }
+
     tracing::info!("Processing {} BIN files", bin_files.len());
 
     // Note: BIN concatenation is now handled by the organizer module.
     // This function focuses purely on path modification.
 
-    // Step 2: Scan BINs to collect referenced asset paths (PARALLEL)
-    let all_asset_paths_set: DashSet<String> = DashSet::new();
-    bin_files.par_iter().for_each(|bin_path| {
-        if let Ok(paths) = scan_bin_for_paths(bin_path) {
-            for path in paths {
-                all_asset_paths_set.insert(path);
+    // Step 2: Scan BINs to collect referenced asset paths (PARALLEL).
+    // Each file yields its own result so failures are reported per BIN; the
+    // path sets are merged afterwards.
+    let started = Instant::now();
+    let scans: Vec<(&PathBuf, Result<Vec<String>>)> = parallel::install(|| {
+        bin_files
+            .par_iter()
+            .map(|bin_path| {
+                let _io = parallel::io_permit();
+                (bin_path, scan_bin_for_paths(bin_path))
+            })
+            .collect()
+    });
+
+    let mut all_asset_paths: HashSet<String> = HashSet::new();
+    let mut scanned_bins: Vec<&PathBuf> = Vec::with_capacity(scans.len());
+    for (bin_path, scan) in scans {
+        match scan {
+            Ok(paths) => {
+                all_asset_paths.extend(paths);
+                scanned_bins.push(bin_path);
+            }
+            Err(e) => {
+                // The main BIN's own failure was already reported by the dependency walk
+                if main_bin_path.as_ref().is_some_and(|main| main != bin_path) {
+                    result.warnings.push(RepathWarning::new(
+                        RepathWarningKind::UnparsableDependency,
+                        bin_path.to_string_lossy(),
+                        format!("Linked BIN couldn't be parsed: {}", e),
+                    ));
+                }
+                result.failed_bins.push(RepathFileError::new(bin_path, e));
             }
         }
+    }
+    all_asset_paths.retain(|path| match safe_join(file_base, path) {
+        Ok(_) => true,
+        Err(e) => {
+            result.warnings.push(RepathWarning::new(RepathWarningKind::UnsafePath, path, e.to_string()));
+            false
+        }
+    });
+
+    // Paths that already carry a repath prefix would end up nested under a
+    // second one; they are kept as they are, including through cleanup
+    let own_prefix = config.prefix().to_lowercase();
+    let foreign_prefix = config.foreign_prefix.as_deref().map(str::to_lowercase);
+    let mut prefixed_paths: HashSet<String> = HashSet::new();
+    let (mut own_count, mut foreign_count) = (0, 0);
+    all_asset_paths.retain(|path| match path_prefix(path) {
+        Some(prefix) if prefix == own_prefix => {
+            own_count += 1;
+            prefixed_paths.insert(path.clone());
+            false
+        }
+        Some(prefix) if foreign_prefix.as_ref() == Some(&prefix) => {
+            foreign_count += 1;
+            prefixed_paths.insert(path.clone());
+            false
+        }
+        _ => true,
     });
-    tracing::info!("Found {} unique asset paths in BINs", all_asset_paths_set.len());
+    if own_count > 0 {
+        result.warnings.push(RepathWarning::new(
+            RepathWarningKind::AlreadyPrefixed,
+            format!("ASSETS/{}", own_prefix),
+            format!("{} references already use this project's prefix and were left as they are", own_count),
+        ));
+    }
+    if let (Some(prefix), true) = (&foreign_prefix, foreign_count > 0) {
+        result.warnings.push(RepathWarning::new(
+            RepathWarningKind::ForeignPrefix,
+            format!("ASSETS/{}", prefix),
+            format!(
+                "{} references use the prefix of the mod this project was imported from and were left as they are; \
+                 strip that prefix and repath again",
+                foreign_count
+            ),
+        ));
+    }
 
-    // Convert DashSet to HashSet for existing_paths filtering
-    let all_asset_paths: HashSet<String> = all_asset_paths_set.into_iter().collect();
+    tracing::info!(
+        "Found {} unique asset paths in {} BINs ({:?})",
+        all_asset_paths.len(),
+        scanned_bins.len(),
+        started.elapsed()
+    );
 
     // Step 3: Determine which paths actually exist
     // Use case-insensitive matching since Windows filesystem is case-insensitive
-    let existing_paths: HashSet<String> = all_asset_paths
-        .iter()
-        .filter(|path| {
-            let full_path = file_base.join(path);
-            if full_path.exists() {
-                return true;
-            }
+    let existing_paths: HashSet<String> = parallel::install(|| {
+        all_asset_paths
+            .par_iter()
+            .filter(|path| {
+                let full_path = file_base.join(path);
+                if full_path.exists() {
+                    return true;
+                }
             
-            // Try case-insensitive lookup by checking parent directory
-            if let Some(parent) = full_path.parent() {
-                if parent.exists() {
-                    if let Some(filename) = full_path.file_name() {
-                        let filename_lower = filename.to_string_lossy().to_lowercase();
-                        if let Ok(entries) = std::fs::read_dir(parent) {
-                            for entry in entries.filter_map(|e| e.ok()) {
-                                let entry_name = entry.file_name().to_string_lossy().to_lowercase();
-                                if entry_name == filename_lower {
-                                    return true;
+                // Try case-insensitive lookup by checking parent directory
+                if let Some(parent) = full_path.parent() {
+                    if parent.exists() {
+                        if let Some(filename) = full_path.file_name() {
+                            let filename_lower = filename.to_string_lossy().to_lowercase();
+                            if let Ok(entries) = std::fs::read_dir(parent) {
+                                for entry in entries.filter_map(|e| e.ok()) {
+                                    let entry_name = entry.file_name().to_string_lossy().to_lowercase();
+                                    if entry_name == filename_lower {
+                                        return true;
+                                    }
                                 }
                             }
                         }
                     }
                 }
-            }
             
-            false
-        })
-        .cloned()
-        .collect();
+                false
+            })
+            .cloned()
+            .collect()
+    });
 
     // Log missing paths for debugging
     let missing_count = all_asset_paths.len() - existing_paths.len();
@@ -207,36 +412,57 @@ pub fn repath_project(
         result.missing_paths.push(path.clone());
     }
 
-    // Step 4: Repath BIN files (PARALLEL)
+    // Step 4: Repath BIN files (PARALLEL). BINs that failed to scan are
+    // skipped; they were already reported.
     let prefix = config.prefix();
-    let bins_processed = AtomicUsize::new(0);
-    let paths_modified = AtomicUsize::new(0);
+    let started = Instant::now();
+    let rewrites: Vec<(&PathBuf, Result<usize>)> = parallel::install(|| {
+        scanned_bins
+            .par_iter()
+            .map(|bin_path| {
+                let _io = parallel::io_permit();
+                (*bin_path, repath_bin_file(bin_path, &existing_paths, &prefix, config))
+            })
+            .collect()
+    });
 
-    bin_files.par_iter().for_each(|bin_path| {
-        match repath_bin_file(bin_path, &existing_paths, &prefix, config) {
+    for (bin_path, rewrite) in rewrites {
+        match rewrite {
             Ok(modified_count) => {
-                bins_processed.fetch_add(1, Ordering::Relaxed);
-                paths_modified.fetch_add(modified_count, Ordering::Relaxed);
-            }
-            Err(e) => {
-                tracing::warn!("Failed to repath {}: {}", bin_path.display(), e);
+                result.bins_processed += 1;
+                result.paths_modified += modified_count;
             }
+            Err(e) => result.failed_bins.push(RepathFileError::new(bin_path, e)),
         }
-    });
-
-    result.bins_processed = bins_processed.load(Ordering::Relaxed);
-    result.paths_modified = paths_modified.load(Ordering::Relaxed);
+    }
+    tracing::info!(
+        "Rewrote {} BINs ({} paths) in {:?}",
+        result.bins_processed,
+        result.paths_modified,
+        started.elapsed()
+    );
 
     // Step 5: Relocate asset files
     result.files_relocated = relocate_assets(file_base, &existing_paths, &prefix, config)?;
 
     // Step 6: Clean up unused files
     if config.cleanup_unused {
-        result.files_removed = cleanup_unused_files(file_base, &existing_paths, &prefix, config)?;
+        let mut quarantine = (!config.delete_unused).then(|| Quarantine::new(project_root(content_base)));
+        result.files_removed =
+            cleanup_unused_files(file_base, &existing_paths, &prefixed_paths, &prefix, config, quarantine.as_mut())?;
+        if let Some(quarantine) = quarantine {
+            let id = quarantine.id().to_string();
+            result.quarantined = quarantine.into_files();
+            if !result.quarantined.is_empty() {
+                tracing::info!("Quarantined {} unused files in {}", result.quarantined.len(), id);
+                result.quarantine_id = Some(id);
+            }
+        }
     }
 
     // Step 7: Clean up irrelevant extracted BINs
-    cleanup_irrelevant_bins(file_base, &config.champion, config.target_skin_id)?;
+    let map_bins: Vec<PathBuf> = result.map_bins.iter().map(|file| content_base.join(file)).collect();
+    cleanup_irrelevant_bins(file_base, &config.champion, config.target_skin_id, &map_bins)?;
 
     // Step 8: Clean up empty directories
     cleanup_empty_dirs(file_base)?;
@@ -251,8 +477,121 @@ pub fn repath_project(
     Ok(result)
 }
 
+/// Result of `replace_asset_paths`
+#[derive(Debug, Clone, Default)]
+pub struct PathReplaceResult {
+    pub bins_modified: usize,
+    pub paths_replaced: usize,
+    /// BINs that couldn't be read or rewritten; the rest are still updated
+    pub failed_bins: Vec<RepathFileError>,
+}
+
+/// Rewrite asset path references in every BIN under `content_base`.
+/// Keys of `replacements` are matched case-insensitively and with either
+/// slash; values are written as given.
+pub fn replace_asset_paths(content_base: &Path, replacements: &HashMap<String, String>) -> PathReplaceResult {
+    let replacements: HashMap<String, &str> = replacements
+        .iter()
+        .map(|(old, new)| (normalize_path(old), new.as_str()))
+        .collect();
+    let mut result = PathReplaceResult::default();
+
+    let bin_files = WalkDir::new(content_base)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+        .map(|e| e.into_path());
+    for bin_path in bin_files {
+        match replace_paths_in_bin(&bin_path, &replacements) {
+            Ok(0) => {}
+            Ok(count) => {
+                result.bins_modified += 1;
+                result.paths_replaced += count;
+            }
+            Err(e) => result.failed_bins.push(RepathFileError::new(&bin_path, e)),
+        }
+    }
+
+    tracing::info!(
+        "Replaced {} path references in {} BIN files",
+        result.paths_replaced,
+        result.bins_modified
+    );
+    result
+}
+
+fn replace_paths_in_bin(bin_path: &Path, replacements: &HashMap<String, &str>) -> Result<usize> {
+    let data = fs::read(bin_path).map_err(|e| Error::io_with_path(e, bin_path))?;
+    let mut bin = read_bin(&data)
+        .map_err(|e| Error::InvalidInput(format!("Failed to parse BIN: {}", e)))?;
+
+    let mut count = 0;
+    for object in bin.objects.values_mut() {
+        for prop in object.properties.values_mut() {
+            count += replace_paths_in_value(&mut prop.value, replacements);
+        }
+    }
+
+    if count > 0 {
+        let new_data = write_bin(&bin)
+            .map_err(|e| Error::InvalidInput(format!("Failed to write BIN: {}", e)))?;
+        fs::write(bin_path, new_data).map_err(|e| Error::io_with_path(e, bin_path))?;
+        tracing::debug!("Replaced {} paths in {}", count, bin_path.display());
+    }
+
+    Ok(count)
+}
+
+/// Recursively replace string values found in `replacements`
+fn replace_paths_in_value(value: &mut PropertyValueEnum, replacements: &HashMap<String, &str>) -> usize {
+    let mut count = 0;
+
+    match value {
+        PropertyValueEnum::String(s) => {
+            if let Some(new_path) = replacements.get(&normalize_path(&s.0)) {
+                s.0 = new_path.to_string();
+                count += 1;
+            }
+        }
+        PropertyValueEnum::Container(c) => {
+            for item in &mut c.items {
+                count += replace_paths_in_value(item, replacements);
+            }
+        }
+        PropertyValueEnum::UnorderedContainer(c) => {
+            for item in &mut c.0.items {
+                count += replace_paths_in_value(item, replacements);
+            }
+        }
+        PropertyValueEnum::Struct(s) => {
+            for prop in s.properties.values_mut() {
+                count += replace_paths_in_value(&mut prop.value, replacements);
+            }
+        }
+        PropertyValueEnum::Embedded(e) => {
+            for prop in e.0.properties.values_mut() {
+                count += replace_paths_in_value(&mut prop.value, replacements);
+            }
+        }
+        PropertyValueEnum::Optional(o) => {
+            if let Some(inner) = &mut o.value {
+                count += replace_paths_in_value(inner.as_mut(), replacements);
+            }
+        }
+        PropertyValueEnum::Map(m) => {
+            // Map keys are immutable, as in `repath_value`
+            for val in m.entries.values_mut() {
+                count += replace_paths_in_value(val, replacements);
+            }
+        }
+        _ => {}
+    }
+
+    count
+}
+
 /// Scan a BIN file for asset path references
-fn scan_bin_for_paths(bin_path: &Path) -> Result<Vec<String>> {
+pub(crate) fn scan_bin_for_paths(bin_path: &Path) -> Result<Vec<String>> {
     let data = fs::read(bin_path).map_err(|e| Error::io_with_path(e, bin_path))?;
     let bin = read_bin(&data)
         .map_err(|e| Error::InvalidInput(format!("Failed to parse BIN: {}", e)))?;
@@ -269,12 +608,10 @@ fn scan_bin_for_paths(bin_path: &Path) -> Result<Vec<String>> {
 }
 
 /// Recursively collect asset paths from a PropertyValueEnum
-fn collect_paths_from_value(value: &PropertyValueEnum, paths: &mut Vec<String>) {
+pub(crate) fn collect_paths_from_value(value: &PropertyValueEnum, paths: &mut Vec<String>) {
     match value {
-        PropertyValueEnum::String(s) => {
-            if is_asset_path(&s.0) {
-                paths.push(normalize_path(&s.0));
-            }
+        PropertyValueEnum::String(s) if is_asset_path(&s.0) => {
+            paths.push(normalize_path(&s.0));
         }
         PropertyValueEnum::Container(c) => {
             for item in &c.items {
@@ -428,18 +765,36 @@ fn repath_bin_file(bin_path: &Path, existing_paths: &HashSet<String>, prefix: &s
     Ok(modified_count)
 }
 
+/// Prefix the references in one BIN whose files an earlier repath already
+/// moved under the prefix, such as references added to a repathed project
+/// afterwards. `file_base` is the folder the references are relative to.
+/// Returns the number of references rewritten.
+pub fn repath_bin_references(bin_path: &Path, file_base: &Path, config: &RepathConfig) -> Result<usize> {
+    let prefix = config.prefix();
+    let prefixed_root = format!("assets/{}/", prefix.to_lowercase());
+    let relocated: HashSet<String> = scan_bin_for_paths(bin_path)?
+        .into_iter()
+        .filter(|path| !path.starts_with(&prefixed_root))
+        .filter(|path| {
+            safe_join(file_base, &apply_prefix_to_path(path, &prefix, config)).is_ok_and(|dest| dest.is_file())
+        })
+        .collect();
+    if relocated.is_empty() {
+        return Ok(0);
+    }
+    repath_bin_file(bin_path, &relocated, &prefix, config)
+}
+
 /// Recursively repath string values in a PropertyValueEnum
 fn repath_value(value: &mut PropertyValueEnum, existing_paths: &HashSet<String>, prefix: &str, config: &RepathConfig) -> usize {
     let mut count = 0;
 
     match value {
-        PropertyValueEnum::String(s) => {
-            if is_asset_path(&s.0) {
-                let normalized = normalize_path(&s.0);
-                if existing_paths.contains(&normalized) {
-                    s.0 = apply_prefix_to_path(&s.0, prefix, config);
-                    count += 1;
-                }
+        PropertyValueEnum::String(s) if is_asset_path(&s.0) => {
+            let normalized = normalize_path(&s.0);
+            if existing_paths.contains(&normalized) {
+                s.0 = apply_prefix_to_path(&s.0, prefix, config);
+                count += 1;
             }
         }
         PropertyValueEnum::Container(c) => {
@@ -492,9 +847,9 @@ fn relocate_assets(content_base: &Path, existing_paths: &HashSet<String>, prefix
             }
         }
 
-        let source = content_base.join(path);
+        let source = safe_join(content_base, path)?;
         let new_path = apply_prefix_to_path(path, prefix, config);
-        let dest = content_base.join(&new_path);
+        let dest = safe_join(content_base, &new_path)?;
 
         // Skip if source doesn't exist
         if !source.exists() {
@@ -525,7 +880,26 @@ fn relocate_assets(content_base: &Path, existing_paths: &HashSet<String>, prefix
     Ok(relocated)
 }
 
-fn cleanup_unused_files(content_base: &Path, referenced_paths: &HashSet<String>, prefix: &str, config: &RepathConfig) -> Result<usize> {
+/// The project directory for `content/base`; other layouts use the content directory itself
+fn project_root(content_base: &Path) -> &Path {
+    content_base
+        .parent()
+        .filter(|_| content_base.ends_with("content/base"))
+        .and_then(Path::parent)
+        .unwrap_or(content_base)
+}
+
+/// Remove files the repathed BINs don't reference, moving them to
+/// `quarantine` when one is given. `kept_paths` were left unprefixed on
+/// purpose and stay too.
+fn cleanup_unused_files(
+    content_base: &Path,
+    referenced_paths: &HashSet<String>,
+    kept_paths: &HashSet<String>,
+    prefix: &str,
+    config: &RepathConfig,
+    mut quarantine: Option<&mut Quarantine>,
+) -> Result<usize> {
     let mut removed = 0;
 
     let expected_paths: HashSet<String> = referenced_paths
@@ -533,8 +907,10 @@ fn cleanup_unused_files(content_base: &Path, referenced_paths: &HashSet<String>,
         .map(|p| normalize_path(&apply_prefix_to_path(p, prefix, config)))
         .collect();
 
+    // Outside `content/base` layouts the quarantine is inside the content directory
     for entry in WalkDir::new(content_base)
         .into_iter()
+        .filter_entry(|e| e.file_name() != ".flint")
         .filter_map(|e| e.ok())
     {
         let path = entry.path();
@@ -558,8 +934,15 @@ fn cleanup_unused_files(content_base: &Path, referenced_paths: &HashSet<String>,
                 prefix.to_lowercase()
             ));
 
+            if kept_paths.contains(&normalized) {
+                continue;
+            }
             if !expected_paths.contains(&normalized) || !in_new_tree {
-                if let Err(e) = fs::remove_file(path) {
+                let outcome = match quarantine.as_deref_mut() {
+                    Some(quarantine) => quarantine.add(path),
+                    None => fs::remove_file(path).map_err(|e| Error::io_with_path(e, path)),
+                };
+                if let Err(e) = outcome {
                     tracing::warn!("Failed to remove {}: {}", path.display(), e);
                 } else {
                     tracing::debug!("Removed unused file: {}", normalized);
@@ -576,9 +959,10 @@ fn cleanup_unused_files(content_base: &Path, referenced_paths: &HashSet<String>,
 /// 1. Main skin BIN (skins/skin{ID}.bin)
 /// 2. Animation BIN (animations/skin{ID}.bin) 
 /// 3. Concat BIN (__Concat.bin)
+/// 4. Map-scoped BINs in `map_bins`
 /// 
 /// This uses a whitelist approach - everything else is deleted.
-fn cleanup_irrelevant_bins(content_base: &Path, champion: &str, target_skin_id: u32) -> Result<usize> {
+fn cleanup_irrelevant_bins(content_base: &Path, champion: &str, target_skin_id: u32, map_bins: &[PathBuf]) -> Result<usize> {
     let mut removed = 0;
     let champion_lower = champion.to_lowercase();
     
@@ -629,6 +1013,12 @@ fn cleanup_irrelevant_bins(content_base: &Path, champion: &str, target_skin_id:
                 continue;
             }
 
+            // 4. Keep map data that references the champion
+            if map_bins.iter().any(|map_bin| map_bin == path) {
+                tracing::debug!("Keeping map-scoped BIN: {}", rel_str);
+                continue;
+            }
+
             // === EVERYTHING ELSE IS DELETED ===
             let reason = if rel_str.contains("/animations/") {
                 "wrong animation"
@@ -658,7 +1048,7 @@ fn cleanup_irrelevant_bins(content_base: &Path, champion: &str, target_skin_id:
     Ok(removed)
 }
 
-fn cleanup_empty_dirs(dir: &Path) -> Result<()> {
+pub(crate) fn cleanup_empty_dirs(dir: &Path) -> Result<()> {
     for entry in WalkDir::new(dir)
         .contents_first(true)
         .into_iter()
@@ -762,6 +1152,8 @@ mod tests {
             champion: "Renekton".to_string(),
             target_skin_id: 42,
             cleanup_unused: true,
+            delete_unused: false,
+            foreign_prefix: None,
         };
 
         // Test champion replacement
@@ -791,6 +1183,8 @@ mod tests {
             champion: "Renekton".to_string(),
             target_skin_id: 42,
             cleanup_unused: true,
+            delete_unused: false,
+            foreign_prefix: None,
         };
 
         // Test new structure: ASSETS/{creator}/characters/{project}/...
@@ -815,4 +1209,32 @@ mod tests {
             "ASSETS/SirDexal/Renny/characters/Renny/skins/skin42.bin"
         );
     }
+
+    #[test]
+    fn test_corrupt_main_bin_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let content_base = dir.path();
+        let main_bin = content_base.join("renekton.wad.client/data/characters/renekton/skins/skin0.bin");
+        fs::create_dir_all(main_bin.parent().unwrap()).unwrap();
+        fs::write(&main_bin, b"not a bin file").unwrap();
+
+        let config = RepathConfig {
+            creator_name: "SirDexal".to_string(),
+            project_name: "Renny".to_string(),
+            champion: "Renekton".to_string(),
+            target_skin_id: 0,
+            cleanup_unused: false,
+            delete_unused: false,
+            foreign_prefix: None,
+        };
+        let result = repath_project(content_base, &config, &HashMap::new()).unwrap();
+
+        assert_eq!(result.warnings.len(), 1);
+        let warning = &result.warnings[0];
+        assert_eq!(warning.kind, RepathWarningKind::UnreadableMainBin);
+        assert!(warning.kind.is_blocking());
+        assert!(warning.path.ends_with("skin0.bin"));
+        assert_eq!(result.failed_bins.len(), 1);
+        assert!(main_bin.exists());
+    }
 }
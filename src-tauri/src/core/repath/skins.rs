@@ -0,0 +1,195 @@
+//! Skin detection from extracted content
+//!
+//! Repath and cleanup keep only the BINs of the configured skin, so a project
+//! whose `skin_id` doesn't match its content loses the BINs it needs. The
+//! skin IDs are inferred from `data/characters/{champ}/skins/skin*.bin` and
+//! `.../animations/skin*.bin` paths under the content directory.
+
+use crate::core::repath::OrganizerConfig;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Skin IDs found in a content directory, ascending
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SkinDetection {
+    /// Skins with a skin BIN (`skins/skinN.bin`)
+    pub skin_ids: Vec<u32>,
+    /// Skins with an animation BIN (`animations/skinN.bin`)
+    pub animation_skin_ids: Vec<u32>,
+}
+
+/// How a project's configured skin compares to its content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkinCheck {
+    /// The configured skin is present, or no skin BINs were found at all
+    Matches,
+    /// The configured skin isn't present; `suggested` is set when exactly one skin is
+    Mismatch { detected: Vec<u32>, suggested: Option<u32> },
+}
+
+impl SkinDetection {
+    /// The skins the content was made for. Skin BINs decide; animation BINs
+    /// only count when there are none, since skins often share animations.
+    pub fn target_skin_ids(&self) -> &[u32] {
+        if self.skin_ids.is_empty() {
+            &self.animation_skin_ids
+        } else {
+            &self.skin_ids
+        }
+    }
+
+    pub fn check(&self, skin_id: u32) -> SkinCheck {
+        let detected = self.target_skin_ids();
+        if detected.is_empty() || detected.contains(&skin_id) {
+            return SkinCheck::Matches;
+        }
+        SkinCheck::Mismatch {
+            detected: detected.to_vec(),
+            suggested: (detected.len() == 1).then(|| detected[0]),
+        }
+    }
+}
+
+/// Scan `content_base` for skin and animation BINs
+pub fn detect_skin_ids(content_base: &Path) -> SkinDetection {
+    let mut skins = BTreeSet::new();
+    let mut animations = BTreeSet::new();
+
+    for entry in WalkDir::new(content_base).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(rel_path) = entry.path().strip_prefix(content_base) else {
+            continue;
+        };
+        let rel_str = rel_path.to_string_lossy().to_lowercase().replace('\\', "/");
+        match parse_skin_bin(&rel_str) {
+            Some((SkinBinKind::Skin, id)) => {
+                skins.insert(id);
+            }
+            Some((SkinBinKind::Animation, id)) => {
+                animations.insert(id);
+            }
+            None => {}
+        }
+    }
+
+    let detection = SkinDetection {
+        skin_ids: skins.into_iter().collect(),
+        animation_skin_ids: animations.into_iter().collect(),
+    };
+    tracing::debug!(
+        "Detected skins {:?} (animations {:?}) in {}",
+        detection.skin_ids,
+        detection.animation_skin_ids,
+        content_base.display()
+    );
+    detection
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SkinBinKind {
+    Skin,
+    Animation,
+}
+
+/// Match `.../data/characters/{champ}/{skins|animations}/skinN.bin` (lowercase, `/`)
+fn parse_skin_bin(rel_path: &str) -> Option<(SkinBinKind, u32)> {
+    let parts: Vec<&str> = rel_path.split('/').collect();
+    let [.., data, characters, _champion, folder, file] = parts.as_slice() else {
+        return None;
+    };
+    if *data != "data" || *characters != "characters" {
+        return None;
+    }
+    let kind = match *folder {
+        "skins" => SkinBinKind::Skin,
+        "animations" => SkinBinKind::Animation,
+        _ => return None,
+    };
+    let digits = file.strip_prefix("skin")?.strip_suffix(".bin")?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().map(|id| (kind, id))
+}
+
+/// Point `config` at the skin the content actually contains.
+///
+/// With a single detected skin, the config is retargeted to it and that ID is
+/// returned so the caller can correct the project. With several candidates
+/// and none matching, BIN cleanup is turned off rather than guessing which
+/// skin's BINs to delete. Either way a warning is added.
+pub fn reconcile_skin_id(
+    config: &mut OrganizerConfig,
+    detection: &SkinDetection,
+    warnings: &mut Vec<String>,
+) -> Option<u32> {
+    match detection.check(config.target_skin_id) {
+        SkinCheck::Matches => None,
+        SkinCheck::Mismatch { suggested: Some(skin_id), .. } => {
+            warnings.push(format!(
+                "The project is set to skin {} but its content is for skin {}; using skin {}",
+                config.target_skin_id, skin_id, skin_id
+            ));
+            config.target_skin_id = skin_id;
+            Some(skin_id)
+        }
+        SkinCheck::Mismatch { detected, suggested: None } => {
+            warnings.push(format!(
+                "The project is set to skin {} but its content has skins {:?}; BIN cleanup was skipped",
+                config.target_skin_id, detected
+            ));
+            config.cleanup_unused = false;
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_skin_bin() {
+        assert_eq!(
+            parse_skin_bin("ahri.wad.client/data/characters/ahri/skins/skin11.bin"),
+            Some((SkinBinKind::Skin, 11))
+        );
+        assert_eq!(parse_skin_bin("data/characters/ahri/animations/skin03.bin"), Some((SkinBinKind::Animation, 3)));
+        assert_eq!(parse_skin_bin("data/characters/ahri/skins/skin11.bin.ritobin"), None);
+        assert_eq!(parse_skin_bin("data/characters/ahri/skins/root.bin"), None);
+        assert_eq!(parse_skin_bin("data/characters/ahri/ahri.bin"), None);
+    }
+
+    #[test]
+    fn test_detect_and_reconcile() {
+        let dir = tempdir().unwrap();
+        let characters = dir.path().join("ahri.wad.client/data/characters/ahri");
+        for file in ["skins/skin11.bin", "animations/skin11.bin", "animations/skin0.bin"] {
+            let path = characters.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"PROP").unwrap();
+        }
+
+        let detection = detect_skin_ids(dir.path());
+        assert_eq!(detection.skin_ids, [11]);
+        assert_eq!(detection.animation_skin_ids, [0, 11]);
+        assert_eq!(detection.check(11), SkinCheck::Matches);
+
+        let mut config = OrganizerConfig::new("Me".to_string(), "mod".to_string(), "Ahri".to_string(), 0);
+        let mut warnings = Vec::new();
+        assert_eq!(reconcile_skin_id(&mut config, &detection, &mut warnings), Some(11));
+        assert_eq!(config.target_skin_id, 11);
+        assert_eq!(warnings.len(), 1);
+
+        let several = SkinDetection { skin_ids: vec![3, 11], animation_skin_ids: vec![] };
+        let mut config = OrganizerConfig::new("Me".to_string(), "mod".to_string(), "Ahri".to_string(), 0);
+        assert_eq!(reconcile_skin_id(&mut config, &several, &mut warnings), None);
+        assert_eq!(config.target_skin_id, 0);
+        assert!(!config.cleanup_unused);
+    }
+}
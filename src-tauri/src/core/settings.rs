@@ -0,0 +1,243 @@
+//! Application settings persisted as JSON in the app data directory.
+//!
+//! Fields this build doesn't know about are kept in `extra` and written back
+//! unchanged, so running an older Flint doesn't wipe settings added by a
+//! newer one.
+
+use crate::core::hash::{HashCategory, HashSource};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Current settings schema version
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// File name inside the app data directory
+pub const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub version: u32,
+    /// League installation chosen by the user; detection is the fallback
+    pub league_path: Option<PathBuf>,
+    pub creator_name: Option<String>,
+    /// Overrides the shared RitoShark hash directory
+    pub hash_dir: Option<PathBuf>,
+    /// Mirrors `download_hashes` uses instead of CommunityDragon when set
+    pub hash_sources: Vec<HashSource>,
+    /// CommunityDragon lists `download_hashes` fetches
+    pub hash_categories: Vec<HashCategory>,
+    pub auto_update_enabled: bool,
+    pub skipped_update_version: Option<String>,
+    /// Load the hashtable at startup instead of on first use
+    pub eager_hash_loading: bool,
+    /// Never touch the network: no hash update at startup, and network
+    /// commands fail with `OfflineMode`
+    pub offline_mode: bool,
+    /// Repath cleanup deletes unused files instead of quarantining them
+    pub delete_unused_files: bool,
+    pub export: ExportSettings,
+    pub performance: PerformanceSettings,
+    /// Most recently opened first
+    pub recent_projects: Vec<RecentProject>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            league_path: None,
+            creator_name: None,
+            hash_dir: None,
+            hash_sources: Vec::new(),
+            hash_categories: HashCategory::ALL.to_vec(),
+            auto_update_enabled: true,
+            skipped_update_version: None,
+            eager_hash_loading: false,
+            offline_mode: false,
+            delete_unused_files: false,
+            export: ExportSettings::default(),
+            performance: PerformanceSettings::default(),
+            recent_projects: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentProject {
+    pub name: String,
+    pub champion: String,
+    pub skin: u32,
+    pub path: PathBuf,
+    /// ISO 8601
+    pub last_opened: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Limits for parallel work (see `core::parallel`); 0 means "use all cores"
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerformanceSettings {
+    /// Worker threads shared by extraction, conversion and export
+    pub max_threads: usize,
+    /// Concurrent tasks reading or writing whole files
+    pub max_io_tasks: usize,
+    /// Use at most half the cores so the machine stays responsive
+    pub low_priority: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Defaults applied when an export command leaves an option unset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportSettings {
+    pub auto_repath: bool,
+    pub output_dir: Option<PathBuf>,
+    /// Mod manager folder of installed mods (e.g. cslol-manager's `installed`)
+    /// that fantome exports are checked against for shared files
+    pub mod_manager_dir: Option<PathBuf>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            auto_repath: true,
+            output_dir: None,
+            mod_manager_dir: None,
+            extra: Map::new(),
+        }
+    }
+}
+
+/// Load settings from `path`. A missing file yields the defaults.
+pub fn load(path: &Path) -> Result<AppSettings> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(AppSettings::default()),
+        Err(e) => return Err(Error::io_with_path(e, path)),
+    };
+
+    let mut settings: AppSettings = serde_json::from_str(&text)
+        .map_err(|e| Error::parse_with_path(e.line(), e.to_string(), path))?;
+
+    // A newer file keeps its version so the newer build doesn't re-migrate it
+    if settings.version < SETTINGS_VERSION {
+        settings.version = SETTINGS_VERSION;
+    }
+    Ok(settings)
+}
+
+/// Write settings to `path` via a temp file so a crash can't leave it half-written
+pub fn save(path: &Path, settings: &AppSettings) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+    }
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| Error::InvalidInput(format!("Failed to serialize settings: {}", e)))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| Error::io_with_path(e, &tmp))?;
+    std::fs::rename(&tmp, path).map_err(|e| Error::io_with_path(e, path))
+}
+
+/// Apply a partial update (JSON merge patch: `null` clears a field, objects
+/// merge recursively) and return the resulting settings.
+pub fn apply_patch(settings: &AppSettings, patch: &Value) -> Result<AppSettings> {
+    if !patch.is_object() {
+        return Err(Error::InvalidInput("Settings update must be a JSON object".to_string()));
+    }
+
+    let mut merged = serde_json::to_value(settings)
+        .map_err(|e| Error::InvalidInput(format!("Failed to serialize settings: {}", e)))?;
+    merge(&mut merged, patch);
+
+    let mut updated: AppSettings = serde_json::from_value(merged)
+        .map_err(|e| Error::InvalidInput(format!("Invalid settings: {}", e)))?;
+    // The schema version is owned by the backend
+    updated.version = settings.version;
+    Ok(updated)
+}
+
+fn merge(target: &mut Value, patch: &Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(key);
+                } else {
+                    merge(target.entry(key.clone()).or_insert(Value::Null), value);
+                }
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_missing_file_yields_defaults() {
+        let dir = tempdir().unwrap();
+        let settings = load(&dir.path().join(SETTINGS_FILE)).unwrap();
+        assert_eq!(settings, AppSettings::default());
+        assert!(settings.export.auto_repath);
+    }
+
+    #[test]
+    fn test_unknown_fields_survive_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(SETTINGS_FILE);
+        std::fs::write(
+            &path,
+            r#"{"version": 7, "creator_name": "Dexal", "future_flag": [1, 2],
+                "export": {"auto_repath": false, "future_format": "zip"}}"#,
+        )
+        .unwrap();
+
+        let settings = load(&path).unwrap();
+        assert_eq!(settings.version, 7);
+        assert!(!settings.export.auto_repath);
+        save(&path, &settings).unwrap();
+
+        let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["future_flag"], json!([1, 2]));
+        assert_eq!(saved["export"]["future_format"], json!("zip"));
+        assert_eq!(saved["creator_name"], json!("Dexal"));
+    }
+
+    #[test]
+    fn test_patch_merges_and_clears() {
+        let settings = AppSettings {
+            creator_name: Some("Dexal".to_string()),
+            league_path: Some(PathBuf::from("C:/Riot Games/League of Legends")),
+            ..Default::default()
+        };
+
+        let updated = apply_patch(
+            &settings,
+            &json!({"league_path": null, "export": {"auto_repath": false}, "version": 99}),
+        )
+        .unwrap();
+
+        assert_eq!(updated.league_path, None);
+        assert_eq!(updated.creator_name.as_deref(), Some("Dexal"));
+        assert!(!updated.export.auto_repath);
+        assert_eq!(updated.version, SETTINGS_VERSION);
+
+        assert!(apply_patch(&settings, &json!({"auto_update_enabled": "yes"})).is_err());
+        assert!(apply_patch(&settings, &json!([1])).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! Startup environment summary
+//!
+//! Setup emits `app-ready` as soon as settings are loaded. Slow pieces
+//! (League detection, the hash update check) start out `Pending` and are
+//! filled in by the background tasks, each of which emits `startup-updated`.
+
+use crate::core::hash::DownloadStats;
+use crate::core::league::{detect_game_version, LeagueInstallation};
+use crate::core::settings::RecentProject;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// A startup item that may still be in progress
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "status", content = "value", rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+pub enum Pending<T> {
+    #[default]
+    Pending,
+    Ready(T),
+    Failed(String),
+}
+
+impl<T, E: std::fmt::Display> From<std::result::Result<T, E>> for Pending<T> {
+    fn from(result: std::result::Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Pending::Ready(value),
+            Err(e) => Pending::Failed(e.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LeagueSummary {
+    pub installation: LeagueInstallation,
+    pub game_version: Option<String>,
+}
+
+impl LeagueSummary {
+    pub fn new(installation: LeagueInstallation) -> Self {
+        let game_version = detect_game_version(&installation.path);
+        Self { installation, game_version }
+    }
+}
+
+/// A recent project plus whether it is still on disk
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentProjectStatus {
+    #[serde(flatten)]
+    pub project: RecentProject,
+    pub exists: bool,
+}
+
+pub fn recent_project_statuses(projects: &[RecentProject]) -> Vec<RecentProjectStatus> {
+    projects
+        .iter()
+        .map(|project| RecentProjectStatus {
+            exists: project.path.join("mod.config.json").is_file(),
+            project: project.clone(),
+        })
+        .collect()
+}
+
+/// Results of the background startup tasks
+#[derive(Clone, Default)]
+pub struct StartupState {
+    league: Arc<RwLock<Pending<LeagueSummary>>>,
+    hash_update: Arc<RwLock<Pending<DownloadStats>>>,
+}
+
+impl StartupState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn league(&self) -> Pending<LeagueSummary> {
+        self.league.read().clone()
+    }
+
+    pub fn set_league(&self, league: Pending<LeagueSummary>) {
+        *self.league.write() = league;
+    }
+
+    pub fn hash_update(&self) -> Pending<DownloadStats> {
+        self.hash_update.read().clone()
+    }
+
+    pub fn set_hash_update(&self, update: Pending<DownloadStats>) {
+        *self.hash_update.write() = update;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pending_serialization() {
+        let pending: Pending<u32> = Pending::Pending;
+        assert_eq!(serde_json::to_value(&pending).unwrap(), json!({"status": "pending"}));
+
+        let ready: Pending<u32> = Ok::<_, String>(3).into();
+        assert_eq!(serde_json::to_value(&ready).unwrap(), json!({"status": "ready", "value": 3}));
+
+        let failed: Pending<u32> = Err::<u32, _>("offline").into();
+        assert_eq!(
+            serde_json::to_value(&failed).unwrap(),
+            json!({"status": "failed", "value": "offline"})
+        );
+    }
+
+    #[test]
+    fn test_recent_projects_report_existence() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("mod.config.json"), "{}").unwrap();
+        let project = |path: std::path::PathBuf| RecentProject {
+            name: "MyMod".to_string(),
+            champion: "Ahri".to_string(),
+            skin: 0,
+            path,
+            last_opened: String::new(),
+            extra: Default::default(),
+        };
+
+        let statuses = recent_project_statuses(&[
+            project(dir.path().to_path_buf()),
+            project(dir.path().join("gone")),
+        ]);
+        assert!(statuses[0].exists);
+        assert!(!statuses[1].exists);
+    }
+}
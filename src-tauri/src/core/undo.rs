@@ -0,0 +1,246 @@
+//! Session-scoped undo for small file edits
+//!
+//! Checkpoints snapshot the whole project, which is too heavy for undoing a
+//! single BIN edit or metadata change. Commands that make such edits record
+//! the bytes of the files they are about to overwrite; `undo_last` writes
+//! them back. The journal lives in memory only and is cleared for a project
+//! whenever a heavier operation (repath, restore, export) rewrites it, since
+//! the old bytes may no longer apply.
+
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Total bytes of file contents the journal keeps across all projects
+pub const UNDO_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Entries kept per project; older ones are dropped
+pub const UNDO_MAX_ENTRIES: usize = 50;
+
+/// A file's contents before an edit
+#[derive(Debug, Clone)]
+pub struct FileSnapshot {
+    path: PathBuf,
+    /// `None` if the file didn't exist, in which case undo deletes it
+    previous: Option<Vec<u8>>,
+}
+
+impl FileSnapshot {
+    /// Read the current contents of each file before it is overwritten
+    pub fn capture<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<FileSnapshot>> {
+        paths
+            .iter()
+            .map(|path| {
+                let path = path.as_ref();
+                let previous = match fs::read(path) {
+                    Ok(bytes) => Some(bytes),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                    Err(e) => return Err(Error::io_with_path(e, path)),
+                };
+                Ok(FileSnapshot { path: path.to_path_buf(), previous })
+            })
+            .collect()
+    }
+
+    fn size(&self) -> usize {
+        self.previous.as_ref().map_or(0, Vec::len)
+    }
+
+    fn restore(&self) -> Result<()> {
+        match &self.previous {
+            Some(bytes) => fs::write(&self.path, bytes).map_err(|e| Error::io_with_path(e, &self.path)),
+            None => match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::io_with_path(e, &self.path)),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+struct UndoEntry {
+    id: u64,
+    label: String,
+    created_at: DateTime<Utc>,
+    files: Vec<FileSnapshot>,
+}
+
+impl UndoEntry {
+    fn size(&self) -> usize {
+        self.files.iter().map(FileSnapshot::size).sum()
+    }
+
+    fn info(&self) -> UndoEntryInfo {
+        UndoEntryInfo {
+            id: self.id,
+            label: self.label.clone(),
+            created_at: self.created_at,
+            files: self.files.iter().map(|f| f.path.to_string_lossy().to_string()).collect(),
+            size: self.size() as u64,
+        }
+    }
+}
+
+/// An undoable edit, as reported to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoEntryInfo {
+    pub id: u64,
+    /// What the edit was, e.g. "Edit skin0.bin"
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    /// Files undo will write back
+    pub files: Vec<String>,
+    /// Bytes held for this entry
+    pub size: u64,
+}
+
+/// Managed state holding each project's undo entries, oldest first
+#[derive(Clone, Default)]
+pub struct UndoJournal {
+    projects: Arc<Mutex<HashMap<PathBuf, Vec<UndoEntry>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl UndoJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an edit that has been applied, given the snapshots taken before
+    /// it. Returns false if the snapshots are too large to keep.
+    pub fn push(&self, project_path: &Path, label: impl Into<String>, files: Vec<FileSnapshot>) -> bool {
+        let entry = UndoEntry {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            label: label.into(),
+            created_at: Utc::now(),
+            files,
+        };
+        if entry.size() > UNDO_MEMORY_LIMIT {
+            tracing::debug!("Not journaling '{}': {} bytes is over the undo limit", entry.label, entry.size());
+            return false;
+        }
+
+        let mut projects = self.projects.lock();
+        let entries = projects.entry(project_path.to_path_buf()).or_default();
+        entries.push(entry);
+        if entries.len() > UNDO_MAX_ENTRIES {
+            entries.remove(0);
+        }
+        Self::enforce_memory_limit(&mut projects);
+        true
+    }
+
+    /// Write back the files of the project's most recent entry. Returns the
+    /// entry, or `None` if there is nothing to undo.
+    pub fn undo_last(&self, project_path: &Path) -> Result<Option<UndoEntryInfo>> {
+        let Some(entry) = self.projects.lock().get_mut(project_path).and_then(Vec::pop) else {
+            return Ok(None);
+        };
+        if let Err(e) = entry.files.iter().try_for_each(FileSnapshot::restore) {
+            // Keep the entry so the user can retry
+            self.projects.lock().entry(project_path.to_path_buf()).or_default().push(entry);
+            return Err(e);
+        }
+        tracing::info!("Undid '{}' in {}", entry.label, project_path.display());
+        Ok(Some(entry.info()))
+    }
+
+    /// The project's entries, most recent first
+    pub fn history(&self, project_path: &Path) -> Vec<UndoEntryInfo> {
+        self.projects
+            .lock()
+            .get(project_path)
+            .map(|entries| entries.iter().rev().map(UndoEntry::info).collect())
+            .unwrap_or_default()
+    }
+
+    /// Drop all of the project's entries, e.g. because a repath or restore
+    /// rewrote the files they would write back
+    pub fn invalidate(&self, project_path: &Path) {
+        if let Some(entries) = self.projects.lock().remove(project_path) {
+            if !entries.is_empty() {
+                tracing::debug!("Cleared {} undo entries for {}", entries.len(), project_path.display());
+            }
+        }
+    }
+
+    /// Drop the oldest entries across all projects until under the limit
+    fn enforce_memory_limit(projects: &mut HashMap<PathBuf, Vec<UndoEntry>>) {
+        let mut total: usize = projects.values().flatten().map(UndoEntry::size).sum();
+        while total > UNDO_MEMORY_LIMIT {
+            let Some(oldest) = projects
+                .iter()
+                .filter_map(|(path, entries)| entries.first().map(|e| (e.id, path.clone())))
+                .min()
+                .map(|(_, path)| path)
+            else {
+                break;
+            };
+            if let Some(entries) = projects.get_mut(&oldest) {
+                total -= entries.remove(0).size();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_undo_restores_previous_bytes() {
+        let dir = tempdir().unwrap();
+        let bin = dir.path().join("skin0.bin");
+        let cache = dir.path().join("skin0.bin.ritobin");
+        fs::write(&bin, b"old").unwrap();
+
+        let journal = UndoJournal::new();
+        let snapshot = FileSnapshot::capture(&[&bin, &cache]).unwrap();
+        fs::write(&bin, b"new").unwrap();
+        fs::write(&cache, b"text").unwrap();
+        journal.push(dir.path(), "Edit skin0.bin", snapshot);
+
+        let history = journal.history(dir.path());
+        assert_eq!(history.len(), 1);
+        assert_eq!((history[0].label.as_str(), history[0].size), ("Edit skin0.bin", 3));
+
+        let undone = journal.undo_last(dir.path()).unwrap().unwrap();
+        assert_eq!(undone.label, "Edit skin0.bin");
+        assert_eq!(fs::read(&bin).unwrap(), b"old");
+        assert!(!cache.exists());
+        assert!(journal.undo_last(dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_invalidate_and_limits() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("a.json");
+        fs::write(&file, b"{}").unwrap();
+        let journal = UndoJournal::new();
+
+        for i in 0..UNDO_MAX_ENTRIES + 5 {
+            journal.push(dir.path(), format!("Edit {}", i), FileSnapshot::capture(&[&file]).unwrap());
+        }
+        let history = journal.history(dir.path());
+        assert_eq!(history.len(), UNDO_MAX_ENTRIES);
+        assert_eq!(history[0].label, format!("Edit {}", UNDO_MAX_ENTRIES + 4));
+
+        journal.invalidate(dir.path());
+        assert!(journal.history(dir.path()).is_empty());
+
+        let big = FileSnapshot { path: file.clone(), previous: Some(vec![0; UNDO_MEMORY_LIMIT / 2 + 1]) };
+        journal.push(Path::new("/a"), "first", vec![big.clone()]);
+        journal.push(Path::new("/b"), "second", vec![big]);
+        assert!(journal.history(Path::new("/a")).is_empty());
+        assert_eq!(journal.history(Path::new("/b")).len(), 1);
+
+        let too_big = FileSnapshot { path: file.clone(), previous: Some(vec![0; UNDO_MEMORY_LIMIT + 1]) };
+        assert!(!journal.push(Path::new("/c"), "too big", vec![too_big]));
+    }
+}
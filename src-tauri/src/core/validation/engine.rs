@@ -3,6 +3,8 @@
 //! This module provides functionality to validate that assets referenced in BIN files
 //! actually exist in WAD archives.
 
+use crate::core::operation::PhaseTiming;
+use crate::core::validation::references::BinReferences;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
@@ -17,6 +19,9 @@ pub struct ValidationReport {
     pub missing_assets: Vec<MissingAsset>,
     /// Summary statistics by asset type
     pub stats_by_type: HashMap<String, AssetTypeStats>,
+    /// Time spent in each phase, in order; filled in by the command
+    #[serde(default)]
+    pub timings: Vec<PhaseTiming>,
 }
 
 impl ValidationReport {
@@ -27,6 +32,7 @@ impl ValidationReport {
             valid_references: 0,
             missing_assets: Vec::new(),
             stats_by_type: HashMap::new(),
+            timings: Vec::new(),
         }
     }
 
@@ -80,6 +86,12 @@ pub struct MissingAsset {
     pub source_file: String,
     /// Asset type based on file extension
     pub asset_type: String,
+    /// Object holding the reference, by name or hash; only for grouped references
+    #[serde(default)]
+    pub object: Option<String>,
+    /// Field path inside that object
+    #[serde(default)]
+    pub field: Option<String>,
 }
 
 impl MissingAsset {
@@ -93,6 +105,8 @@ impl MissingAsset {
             path_hash: None,
             source_file: source_file.into(),
             asset_type,
+            object: None,
+            field: None,
         }
     }
 }
@@ -162,6 +176,8 @@ pub fn validate_assets(
                 path_hash: Some(reference.path_hash),
                 source_file: source_file.to_string(),
                 asset_type: reference.asset_type.clone(),
+                object: None,
+                field: None,
             });
         }
     }
@@ -176,6 +192,59 @@ pub fn validate_assets(
     report
 }
 
+/// Validates grouped references, so each missing asset names the file,
+/// object and field that reference it
+///
+/// # Arguments
+/// * `groups` - References by BIN file and object, from `project_references`
+/// * `available_hashes` - Set of path hashes that exist in WAD files or the project
+///
+/// # Returns
+/// * `ValidationReport` - One missing entry per referencing field
+pub fn validate_reference_groups(groups: &[BinReferences], available_hashes: &HashSet<u64>) -> ValidationReport {
+    let mut report = ValidationReport::new();
+
+    for group in groups {
+        let links = group.links.iter().map(|path| (path, None, None));
+        let fields = group.objects.iter().flat_map(|object| {
+            let owner = object.name.as_ref().unwrap_or(&object.path_hash);
+            object.references.iter().map(move |r| (&r.path, Some(owner), Some(&r.field)))
+        });
+
+        for (path, object, field) in links.chain(fields) {
+            let path_hash = compute_path_hash(path);
+            let asset_type = infer_asset_type(path);
+            let stats = report.stats_by_type.entry(asset_type.clone()).or_default();
+            stats.total += 1;
+            report.total_references += 1;
+
+            if available_hashes.contains(&path_hash) {
+                stats.valid += 1;
+                report.valid_references += 1;
+            } else {
+                stats.missing += 1;
+                report.missing_assets.push(MissingAsset {
+                    path: path.clone(),
+                    path_hash: Some(path_hash),
+                    source_file: group.file.clone(),
+                    asset_type,
+                    object: object.cloned(),
+                    field: field.cloned(),
+                });
+            }
+        }
+    }
+
+    tracing::info!(
+        "Validated {} BINs: {}/{} references valid ({:.1}%)",
+        groups.len(),
+        report.valid_references,
+        report.total_references,
+        report.success_rate()
+    );
+    report
+}
+
 /// Extracts asset references from BIN file content (text format)
 ///
 /// This looks for path-like strings in the BIN text format that reference
@@ -295,7 +364,7 @@ fn is_asset_path(s: &str) -> bool {
 }
 
 /// Computes the xxhash64 of a path (lowercase, forward slashes)
-fn compute_path_hash(path: &str) -> u64 {
+pub(crate) fn compute_path_hash(path: &str) -> u64 {
     use xxhash_rust::xxh64::xxh64;
     
     let normalized = path.to_lowercase().replace('\\', "/");
@@ -416,4 +485,41 @@ mod tests {
         assert_eq!(report.missing_count(), 1);
         assert!(!report.is_valid());
     }
+
+    #[test]
+    fn test_validate_reference_groups() {
+        use crate::core::validation::references::{FieldReference, ObjectReferences, ReferenceKind};
+
+        let texture = |path: &str, field: &str| FieldReference {
+            path: path.to_string(),
+            field: field.to_string(),
+            kind: ReferenceKind::Texture,
+        };
+        let groups = vec![BinReferences {
+            file: "ahri.wad.client/data/characters/ahri/skins/skin0.bin".to_string(),
+            links: vec!["data/characters/ahri/ahri.bin".to_string()],
+            objects: vec![ObjectReferences {
+                path_hash: "0x1a2b3c4d".to_string(),
+                name: Some("Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Orb".to_string()),
+                class_name: "VfxSystemDefinitionData".to_string(),
+                references: vec![
+                    texture("assets/orb.dds", "complexEmitterDefinitionData[0].texture"),
+                    texture("assets/missing.dds", "complexEmitterDefinitionData[1].texture"),
+                ],
+            }],
+            error: None,
+        }];
+        let available: HashSet<u64> = ["assets/orb.dds", "data/characters/ahri/ahri.bin"]
+            .iter()
+            .map(|p| compute_path_hash(p))
+            .collect();
+
+        let report = validate_reference_groups(&groups, &available);
+        assert_eq!(report.total_references, 3);
+        assert_eq!(report.valid_references, 2);
+        let missing = &report.missing_assets[0];
+        assert_eq!(missing.path, "assets/missing.dds");
+        assert_eq!(missing.object.as_deref(), Some("Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Orb"));
+        assert_eq!(missing.field.as_deref(), Some("complexEmitterDefinitionData[1].texture"));
+    }
 }
@@ -0,0 +1,236 @@
+//! Map-scoped VFX coverage
+//!
+//! Summoner's Rift data can define its own copy of a champion's particle
+//! system or link to it. When a project changes such a system but the export
+//! doesn't carry the map object that uses it, the change doesn't show on that
+//! map. This check reads the project's map-scoped BINs and, when given, the
+//! installed Summoner's Rift WAD, and reports every such system.
+
+use crate::core::bin::ltk_bridge::read_bin;
+use crate::core::hash::Hashtable;
+use crate::core::recolor::fnv1a;
+use crate::core::repath::maps::{is_map_scoped, is_map_wad, split_wad_folder, MAP_WAD_FOLDER};
+use crate::core::wad::reader::WadReader;
+use crate::error::Result;
+use ltk_meta::{BinTree, BinTreeObject, PropertyValueEnum};
+use ltk_ritobin::HashProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// A champion VFX system that map data uses but the export doesn't carry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapVfxWarning {
+    /// System object path, or its hash as "0x1a2b3c4d" when unknown
+    pub system: String,
+    /// Map BIN that defines or links to it, e.g. "data/maps/shipping/map11/map11.bin"
+    pub map_bin: String,
+    /// Project file it was read from, relative to `content/base`; `None`
+    /// when it comes from the installed map WAD
+    pub project_file: Option<String>,
+    pub message: String,
+}
+
+/// Check that every map object using one of the project's VFX systems is
+/// exported where the game loads it: a map BIN in a map WAD folder, or the
+/// concat BIN it was pulled into. The Summoner's Rift WAD among
+/// `installed_wads` is read for the game's own map data; its chunks are only
+/// found by name, so it is skipped without `wad_hashes`.
+pub fn check_map_vfx<H: HashProvider>(
+    content_base: &Path,
+    installed_wads: &[PathBuf],
+    wad_hashes: Option<&Hashtable>,
+    bin_hashes: &H,
+) -> Result<Vec<MapVfxWarning>> {
+    let system_class = fnv1a("VfxSystemDefinitionData");
+    let mut systems = HashSet::new();
+    let mut shipped_objects = HashSet::new();
+    let mut project_maps: Vec<(String, String, BinTree)> = Vec::new();
+
+    for entry in WalkDir::new(content_base)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+    {
+        let Ok(rel_path) = entry.path().strip_prefix(content_base) else {
+            continue;
+        };
+        let file = rel_path.to_string_lossy().replace('\\', "/");
+        let tree = match fs::read(entry.path()).map_err(|e| e.to_string()).and_then(|data| {
+            read_bin(&data).map_err(|e| e.to_string())
+        }) {
+            Ok(tree) => tree,
+            Err(e) => {
+                tracing::debug!("Skipping {} in map VFX check: {}", file, e);
+                continue;
+            }
+        };
+
+        let path = split_wad_folder(&file).1.to_lowercase();
+        if is_map_scoped(&path) {
+            project_maps.push((file, path, tree));
+        } else {
+            for (path_hash, object) in &tree.objects {
+                if object.class_hash == system_class {
+                    systems.insert(*path_hash);
+                }
+                shipped_objects.insert(*path_hash);
+            }
+        }
+    }
+    if systems.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut warnings = Vec::new();
+    let mut shipped_map_bins = HashSet::new();
+    for (file, path, tree) in &project_maps {
+        let (folder, _) = split_wad_folder(file);
+        if folder.is_some_and(is_map_wad) {
+            shipped_map_bins.insert(path.clone());
+            continue;
+        }
+        let message = format!(
+            "{} is in {}, where the game doesn't load map data; move it to {}",
+            path,
+            folder.unwrap_or("content/base"),
+            MAP_WAD_FOLDER
+        );
+        for system in uncovered_systems(tree, &systems, &shipped_objects) {
+            warnings.push(MapVfxWarning {
+                system: system_name(bin_hashes, system),
+                map_bin: path.clone(),
+                project_file: Some(file.clone()),
+                message: message.clone(),
+            });
+        }
+    }
+
+    if let Some(wad_hashes) = wad_hashes {
+        for wad_path in installed_wads {
+            if !wad_path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.eq_ignore_ascii_case(MAP_WAD_FOLDER)) {
+                continue;
+            }
+            warnings.extend(check_installed_map(
+                wad_path,
+                wad_hashes,
+                &systems,
+                &shipped_objects,
+                &shipped_map_bins,
+                bin_hashes,
+            )?);
+        }
+    } else if !installed_wads.is_empty() {
+        tracing::debug!("No WAD hashes loaded; skipping the installed map data");
+    }
+
+    warnings.sort_by(|a, b| (&a.map_bin, &a.system).cmp(&(&b.map_bin, &b.system)));
+    warnings.dedup_by(|a, b| a.map_bin == b.map_bin && a.system == b.system);
+    Ok(warnings)
+}
+
+/// Map BINs in an installed WAD that use the project's systems without the
+/// export carrying them. BINs the project replaces are skipped; they were
+/// checked as project files.
+fn check_installed_map<H: HashProvider>(
+    wad_path: &Path,
+    wad_hashes: &Hashtable,
+    systems: &HashSet<u32>,
+    shipped_objects: &HashSet<u32>,
+    shipped_map_bins: &HashSet<String>,
+    bin_hashes: &H,
+) -> Result<Vec<MapVfxWarning>> {
+    let mut reader = WadReader::open(wad_path)?;
+    let wad_name = wad_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let chunks: Vec<_> = reader
+        .chunks()
+        .iter()
+        .filter_map(|(hash, chunk)| {
+            let path = wad_hashes.resolve(*hash).to_lowercase();
+            (is_map_scoped(&path) && path.ends_with(".bin") && !shipped_map_bins.contains(&path))
+                .then_some((path, *chunk))
+        })
+        .collect();
+
+    let mut warnings = Vec::new();
+    for (path, chunk) in chunks {
+        let tree = match reader
+            .load_chunk(&chunk)
+            .map_err(|e| e.to_string())
+            .and_then(|data| read_bin(&data).map_err(|e| e.to_string()))
+        {
+            Ok(tree) => tree,
+            Err(e) => {
+                tracing::debug!("Skipping {} from {}: {}", path, wad_name, e);
+                continue;
+            }
+        };
+        for system in uncovered_systems(&tree, systems, shipped_objects) {
+            warnings.push(MapVfxWarning {
+                system: system_name(bin_hashes, system),
+                map_bin: path.clone(),
+                project_file: None,
+                message: format!(
+                    "{} in {} uses this system, so its changes won't show on that map; \
+                     add the map BIN to the project and export it in {}",
+                    path, wad_name, MAP_WAD_FOLDER
+                ),
+            });
+        }
+    }
+    Ok(warnings)
+}
+
+/// The project systems `tree`'s objects define or link to, leaving out
+/// links from objects the export already carries
+fn uncovered_systems(tree: &BinTree, systems: &HashSet<u32>, shipped_objects: &HashSet<u32>) -> BTreeSet<u32> {
+    let mut used = BTreeSet::new();
+    for (path_hash, object) in &tree.objects {
+        // The map's own copy of a system; only shipping the map BIN replaces it
+        if systems.contains(path_hash) {
+            used.insert(*path_hash);
+            continue;
+        }
+        if shipped_objects.contains(path_hash) {
+            continue;
+        }
+        used.extend(object_links(object).into_iter().filter(|link| systems.contains(link)));
+    }
+    used
+}
+
+/// Every object link in `object`'s properties
+fn object_links(object: &BinTreeObject) -> Vec<u32> {
+    fn collect(value: &PropertyValueEnum, out: &mut Vec<u32>) {
+        match value {
+            PropertyValueEnum::ObjectLink(link) => out.push(link.0),
+            PropertyValueEnum::Container(c) => c.items.iter().for_each(|item| collect(item, out)),
+            PropertyValueEnum::UnorderedContainer(c) => c.0.items.iter().for_each(|item| collect(item, out)),
+            PropertyValueEnum::Struct(s) => s.properties.values().for_each(|prop| collect(&prop.value, out)),
+            PropertyValueEnum::Embedded(e) => e.0.properties.values().for_each(|prop| collect(&prop.value, out)),
+            PropertyValueEnum::Optional(o) => {
+                if let Some(inner) = &o.value {
+                    collect(inner, out);
+                }
+            }
+            PropertyValueEnum::Map(m) => m.entries.values().for_each(|val| collect(val, out)),
+            _ => {}
+        }
+    }
+
+    let mut links = Vec::new();
+    for prop in object.properties.values() {
+        collect(&prop.value, &mut links);
+    }
+    links
+}
+
+fn system_name<H: HashProvider>(hashes: &H, path_hash: u32) -> String {
+    hashes
+        .lookup_entry(path_hash)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{:#010x}", path_hash))
+}
@@ -1,5 +1,17 @@
 // Validation module exports
 pub mod engine;
+pub mod maps;
+pub mod references;
+pub mod texture;
 
 #[allow(unused_imports)]
-pub use engine::{validate_assets, extract_asset_references, ValidationReport, MissingAsset, AssetReference};
+pub use engine::{
+    validate_assets, validate_reference_groups, extract_asset_references, ValidationReport, MissingAsset, AssetReference,
+};
+#[allow(unused_imports)]
+pub use references::{
+    available_path_hashes, bin_references, project_references, BinReferences, FieldReference, ObjectReferences,
+    ReferenceKind,
+};
+pub use maps::{check_map_vfx, MapVfxWarning};
+pub use texture::{check_texture_overrides, TextureOverrideWarning};
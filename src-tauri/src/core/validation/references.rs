@@ -0,0 +1,234 @@
+//! Asset references grouped by where they come from
+//!
+//! `extract_asset_references` scans BIN text for path-like strings, which is
+//! enough to know *that* a file is referenced. The functions here walk the
+//! parsed BIN instead, so each reference carries the object that owns it and
+//! the field path it sits at, e.g. `complexEmitterDefinitionData[2].texture`.
+
+use crate::core::bin::ltk_bridge::read_bin;
+use crate::core::mapping::normalize_path;
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::reader::WadReader;
+use crate::core::wad::WadName;
+use crate::error::Result;
+use ltk_meta::{BinTree, PropertyValueEnum};
+use ltk_ritobin::HashProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// What a referenced file is, from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    Texture,
+    Mesh,
+    Animation,
+    BinLink,
+    Audio,
+    Other,
+}
+
+impl ReferenceKind {
+    pub fn from_path(path: &str) -> Self {
+        let ext = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+        match ext.as_str() {
+            "dds" | "tex" | "png" | "jpg" => ReferenceKind::Texture,
+            "skn" | "skl" | "scb" | "sco" | "mapgeo" => ReferenceKind::Mesh,
+            "anm" => ReferenceKind::Animation,
+            "bin" => ReferenceKind::BinLink,
+            "bnk" | "wpk" | "wem" => ReferenceKind::Audio,
+            _ => ReferenceKind::Other,
+        }
+    }
+}
+
+/// A file path at one field of an object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldReference {
+    /// Normalized referenced path
+    pub path: String,
+    /// Field path inside the object; unknown names are shown as hashes
+    pub field: String,
+    pub kind: ReferenceKind,
+}
+
+/// The references one BIN object holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectReferences {
+    /// Object path hash as "0x1a2b3c4d"
+    pub path_hash: String,
+    /// Object path, if it is in hashes.binentries.txt
+    pub name: Option<String>,
+    /// Class name, or its hash when unknown
+    pub class_name: String,
+    pub references: Vec<FieldReference>,
+}
+
+/// The references in one BIN file, by object. Linked BINs (the file's
+/// dependency list) are listed under `links`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinReferences {
+    /// Relative to the project's `content/base`
+    pub file: String,
+    pub links: Vec<String>,
+    pub objects: Vec<ObjectReferences>,
+    /// Why the file couldn't be read; it has no references then
+    pub error: Option<String>,
+}
+
+/// Group a BIN's asset references by owning object, in file order. Objects
+/// without references are left out.
+pub fn bin_references<H: HashProvider>(tree: &BinTree, hashes: &H) -> Vec<ObjectReferences> {
+    tree.objects
+        .iter()
+        .filter_map(|(path_hash, object)| {
+            let mut references = Vec::new();
+            for (name_hash, prop) in &object.properties {
+                collect(&prop.value, field_name(hashes, *name_hash), hashes, &mut references);
+            }
+            (!references.is_empty()).then(|| ObjectReferences {
+                path_hash: format!("{:#010x}", path_hash),
+                name: hashes.lookup_entry(*path_hash).map(str::to_string),
+                class_name: hashes
+                    .lookup_type(object.class_hash)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("{:#010x}", object.class_hash)),
+                references,
+            })
+        })
+        .collect()
+}
+
+/// References of every BIN under `content_base`, sorted by file
+pub fn project_references<H: HashProvider>(content_base: &Path, hashes: &H) -> Vec<BinReferences> {
+    let mut files: Vec<PathBuf> = WalkDir::new(content_base)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bin")))
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+
+    files
+        .iter()
+        .map(|path| {
+            let file = normalize_path(&path.strip_prefix(content_base).unwrap_or(path).to_string_lossy());
+            let tree = fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| read_bin(&data).map_err(|e| e.to_string()));
+            match tree {
+                Ok(tree) => BinReferences {
+                    file,
+                    links: tree.dependencies.iter().map(|p| normalize_path(p)).collect(),
+                    objects: bin_references(&tree, hashes),
+                    error: None,
+                },
+                Err(e) => {
+                    tracing::warn!("Failed to read references from {}: {}", path.display(), e);
+                    BinReferences { file, links: Vec::new(), objects: Vec::new(), error: Some(e) }
+                }
+            }
+        })
+        .collect()
+}
+
+/// Path hashes a reference can resolve to: the chunks of the given WADs and
+/// the files in the project's WAD folders
+pub fn available_path_hashes(content_base: &Path, wads: &[PathBuf]) -> Result<HashSet<u64>> {
+    let mut hashes = HashSet::new();
+    for wad_path in wads {
+        hashes.extend(WadReader::open(wad_path)?.chunks().keys().copied());
+    }
+
+    let Ok(entries) = fs::read_dir(content_base) else {
+        return Ok(hashes);
+    };
+    for wad_dir in entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()) {
+        if wad_dir.file_name().and_then(|n| n.to_str()).and_then(WadName::parse).is_none() {
+            continue;
+        }
+        for file in WalkDir::new(&wad_dir).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+            if let Ok(rel_path) = file.path().strip_prefix(&wad_dir) {
+                hashes.insert(compute_path_hash(&normalize_path(&rel_path.to_string_lossy())));
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+fn collect<H: HashProvider>(value: &PropertyValueEnum, field: String, hashes: &H, out: &mut Vec<FieldReference>) {
+    match value {
+        PropertyValueEnum::String(s) if is_file_reference(&s.0) => {
+            let path = normalize_path(&s.0);
+            out.push(FieldReference { kind: ReferenceKind::from_path(&path), path, field });
+        }
+        PropertyValueEnum::Container(c) => {
+            for (i, item) in c.items.iter().enumerate() {
+                collect(item, format!("{}[{}]", field, i), hashes, out);
+            }
+        }
+        PropertyValueEnum::UnorderedContainer(c) => {
+            for (i, item) in c.0.items.iter().enumerate() {
+                collect(item, format!("{}[{}]", field, i), hashes, out);
+            }
+        }
+        PropertyValueEnum::Struct(s) => {
+            for (name_hash, prop) in &s.properties {
+                collect(&prop.value, format!("{}.{}", field, field_name(hashes, *name_hash)), hashes, out);
+            }
+        }
+        PropertyValueEnum::Embedded(e) => {
+            for (name_hash, prop) in &e.0.properties {
+                collect(&prop.value, format!("{}.{}", field, field_name(hashes, *name_hash)), hashes, out);
+            }
+        }
+        PropertyValueEnum::Optional(o) => {
+            if let Some(inner) = &o.value {
+                collect(inner, field, hashes, out);
+            }
+        }
+        PropertyValueEnum::Map(m) => {
+            for (i, val) in m.entries.values().enumerate() {
+                collect(val, format!("{}[{}]", field, i), hashes, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn field_name<H: HashProvider>(hashes: &H, name_hash: u32) -> String {
+    hashes
+        .lookup_field(name_hash)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{:#010x}", name_hash))
+}
+
+/// Strings that name a game file: a directory and an extension
+fn is_file_reference(s: &str) -> bool {
+    let lower = s.to_lowercase();
+    (lower.starts_with("assets/") || lower.starts_with("data/"))
+        && lower.rsplit('/').next().is_some_and(|name| name.contains('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_kinds() {
+        assert_eq!(ReferenceKind::from_path("assets/a/b.TEX"), ReferenceKind::Texture);
+        assert_eq!(ReferenceKind::from_path("assets/a/b.skn"), ReferenceKind::Mesh);
+        assert_eq!(ReferenceKind::from_path("assets/a/b.anm"), ReferenceKind::Animation);
+        assert_eq!(ReferenceKind::from_path("data/a/skin0.bin"), ReferenceKind::BinLink);
+        assert_eq!(ReferenceKind::from_path("assets/sounds/a.bnk"), ReferenceKind::Audio);
+        assert_eq!(ReferenceKind::from_path("assets/a/b"), ReferenceKind::Other);
+
+        assert!(is_file_reference("ASSETS/Characters/Ahri/ahri.skn"));
+        assert!(!is_file_reference("Characters/Ahri/Skins/Skin0"));
+        assert!(!is_file_reference("assets/characters.ahri/folder"));
+    }
+}
@@ -0,0 +1,92 @@
+//! Texture override checks
+//!
+//! A texture in a `{name}.wad.client` folder replaces the game file at the
+//! same path. When its format, colorspace or alpha usage differs from the
+//! original, the game may render it wrong (cutouts turning opaque, washed
+//! out colors), so validation compares each override with the file it
+//! replaces.
+
+use crate::core::file::texture::{analyze_texture, analyze_texture_bytes, compare_textures};
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::reader::WadReader;
+use crate::core::wad::WadName;
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// A project texture that differs from the game texture it replaces
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureOverrideWarning {
+    /// Path inside the WAD folder, e.g. "assets/characters/ahri/skins/base/ahri_base_tx_cm.dds"
+    pub path: String,
+    /// Installed WAD the original was read from
+    pub original_wad: PathBuf,
+    pub warnings: Vec<String>,
+}
+
+/// Compare the textures in each WAD folder under `content_base` with the
+/// originals in the matching installed WAD. Folders without an installed
+/// WAD and textures at new paths (e.g. after repathing) are skipped.
+pub fn check_texture_overrides(content_base: &Path, installed_wads: &[PathBuf]) -> Result<Vec<TextureOverrideWarning>> {
+    let mut results = Vec::new();
+
+    let Ok(entries) = fs::read_dir(content_base) else {
+        return Ok(results);
+    };
+    for wad_dir in entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()) {
+        let Some(dir_name) = wad_dir.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if WadName::parse(dir_name).is_none() {
+            continue;
+        }
+        let Some(wad_path) = installed_wads
+            .iter()
+            .find(|w| w.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.eq_ignore_ascii_case(dir_name)))
+        else {
+            tracing::debug!("No installed WAD for {}; skipping texture checks", dir_name);
+            continue;
+        };
+
+        let mut reader = WadReader::open(wad_path)?;
+        for file in WalkDir::new(&wad_dir).into_iter().filter_map(|e| e.ok()).filter(|e| is_texture(e.path())) {
+            let Ok(rel_path) = file.path().strip_prefix(&wad_dir) else {
+                continue;
+            };
+            let rel_path = rel_path.to_string_lossy().to_lowercase().replace('\\', "/");
+            let Some(chunk) = reader.get_chunk(compute_path_hash(&rel_path)).copied() else {
+                continue;
+            };
+
+            let compared = reader
+                .load_chunk(&chunk)
+                .map_err(|e| format!("Failed to decompress original: {}", e))
+                .and_then(|data| analyze_texture_bytes(&data).map_err(|e| e.to_string()))
+                .and_then(|original| {
+                    analyze_texture(file.path())
+                        .map(|replacement| compare_textures(&original, &replacement))
+                        .map_err(|e| e.to_string())
+                });
+            match compared {
+                Ok(warnings) if !warnings.is_empty() => results.push(TextureOverrideWarning {
+                    path: rel_path,
+                    original_wad: wad_path.clone(),
+                    warnings,
+                }),
+                Ok(_) => {}
+                Err(e) => tracing::debug!("Skipping texture check for {}: {}", rel_path, e),
+            }
+        }
+    }
+
+    tracing::info!("{} texture overrides differ from the originals", results.len());
+    Ok(results)
+}
+
+fn is_texture(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("dds") || e.eq_ignore_ascii_case("tex"))
+}
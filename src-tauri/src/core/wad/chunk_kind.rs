@@ -0,0 +1,143 @@
+//! Broad file types of WAD chunks
+//!
+//! The chunk browser groups and filters chunks by kind. A chunk's kind comes
+//! from the extension of the path its hash resolves to; chunks with an
+//! unknown hash, or a path without an extension, are identified from the
+//! magic of their first decompressed bytes (`DDS `, `PROP`, `r3d2...`).
+
+use crate::core::wad::extractor::{read_chunk_head, MAX_CHUNK_SIZE};
+use crate::core::wad::reader::{WadReader, WadSource};
+use league_toolkit::file::LeagueFileKind;
+use league_toolkit::wad::WadChunk;
+use serde::{Deserialize, Serialize};
+
+/// Broad file type of a chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkKind {
+    /// DDS, TEX and other images
+    Texture,
+    /// Property BINs
+    Bin,
+    /// Skinned and static meshes, skeletons, map geometry
+    Mesh,
+    Animation,
+    /// Wwise banks and packages
+    Audio,
+    Other,
+}
+
+impl ChunkKind {
+    /// Name as serialized, e.g. "texture"
+    pub fn name(self) -> &'static str {
+        match self {
+            ChunkKind::Texture => "texture",
+            ChunkKind::Bin => "bin",
+            ChunkKind::Mesh => "mesh",
+            ChunkKind::Animation => "animation",
+            ChunkKind::Audio => "audio",
+            ChunkKind::Other => "other",
+        }
+    }
+
+    /// Kind of a file by its path's extension; `None` when the path has no
+    /// extension to go by
+    pub fn from_path(path: &str) -> Option<Self> {
+        let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        let (_, extension) = name.rsplit_once('.')?;
+        Some(match extension.to_ascii_lowercase().as_str() {
+            "dds" | "tex" | "png" | "jpg" | "jpeg" | "tga" | "svg" => ChunkKind::Texture,
+            "bin" => ChunkKind::Bin,
+            "skn" | "skl" | "scb" | "sco" | "mapgeo" | "wgeo" => ChunkKind::Mesh,
+            "anm" => ChunkKind::Animation,
+            "bnk" | "wpk" => ChunkKind::Audio,
+            _ => ChunkKind::Other,
+        })
+    }
+
+    /// Kind of a file from its first bytes
+    pub fn from_bytes(head: &[u8]) -> Self {
+        Self::from(LeagueFileKind::identify_from_bytes(head))
+    }
+}
+
+impl From<LeagueFileKind> for ChunkKind {
+    fn from(kind: LeagueFileKind) -> Self {
+        match kind {
+            LeagueFileKind::Texture
+            | LeagueFileKind::TextureDds
+            | LeagueFileKind::Png
+            | LeagueFileKind::Jpeg
+            | LeagueFileKind::Tga
+            | LeagueFileKind::Svg => ChunkKind::Texture,
+            LeagueFileKind::PropertyBin | LeagueFileKind::PropertyBinOverride => ChunkKind::Bin,
+            LeagueFileKind::SimpleSkin
+            | LeagueFileKind::Skeleton
+            | LeagueFileKind::StaticMeshAscii
+            | LeagueFileKind::StaticMeshBinary
+            | LeagueFileKind::MapGeometry
+            | LeagueFileKind::WorldGeometry => ChunkKind::Mesh,
+            LeagueFileKind::Animation => ChunkKind::Animation,
+            LeagueFileKind::WwiseBank | LeagueFileKind::WwisePackage => ChunkKind::Audio,
+            _ => ChunkKind::Other,
+        }
+    }
+}
+
+/// Kind of a chunk from its magic. Only the first bytes are decompressed,
+/// except for subchunked data which only decodes whole; chunks that can't be
+/// read count as `Other`.
+pub fn sniff_chunk_kind(source: &mut WadSource, reader: &mut WadReader, chunk: &WadChunk) -> ChunkKind {
+    let kind = match read_chunk_head(source, chunk) {
+        Ok(Some(head)) => Ok(ChunkKind::from_bytes(&head)),
+        Ok(None) if chunk.uncompressed_size() <= MAX_CHUNK_SIZE => {
+            reader.load_chunk(chunk).map(|data| ChunkKind::from_bytes(&data)).map_err(|e| e.to_string())
+        }
+        Ok(None) => Err("too large to decode".to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    kind.unwrap_or_else(|e| {
+        tracing::debug!("Couldn't identify chunk {:016x}: {}", chunk.path_hash(), e);
+        ChunkKind::Other
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wad::writer::build_wad;
+    use std::fs;
+
+    #[test]
+    fn test_kind_from_extension_or_magic() {
+        assert_eq!(ChunkKind::from_path("assets/characters/ahri/skins/base/ahri.TEX"), Some(ChunkKind::Texture));
+        assert_eq!(ChunkKind::from_path("data/characters/ahri/skins/skin0.bin"), Some(ChunkKind::Bin));
+        assert_eq!(ChunkKind::from_path("assets/characters/ahri/skins/base/ahri.skn"), Some(ChunkKind::Mesh));
+        assert_eq!(ChunkKind::from_path("assets/sounds/wwise2016/vo/en_us/ahri.wpk"), Some(ChunkKind::Audio));
+        assert_eq!(ChunkKind::from_path("data/characters/ahri/ahri.luaobj"), Some(ChunkKind::Other));
+        assert_eq!(ChunkKind::from_path("data.dir/characters/ahri/ahri"), None);
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("files");
+        fs::create_dir_all(&dir).unwrap();
+        let mut dds = b"DDS ".to_vec();
+        dds.extend_from_slice(&[0; 124]);
+        fs::write(dir.join("0000000000000001.bin"), dds).unwrap();
+        fs::write(dir.join("0000000000000002.bin"), b"PROP\x03\x00\x00\x00").unwrap();
+        fs::write(dir.join("0000000000000003.bin"), b"r3d2anmd\x05\x00\x00\x00").unwrap();
+        fs::write(dir.join("0000000000000004.bin"), b"plain text").unwrap();
+        let wad_path = temp.path().join("Ahri.wad.client");
+        build_wad(&dir, &wad_path).unwrap();
+
+        let mut reader = WadReader::open(&wad_path).unwrap();
+        let mut source = WadSource::open(&wad_path).unwrap();
+        let kinds: Vec<ChunkKind> = (1..=4)
+            .map(|hash| {
+                let chunk = *reader.get_chunk(hash).unwrap();
+                sniff_chunk_kind(&mut source, &mut reader, &chunk)
+            })
+            .collect();
+        assert_eq!(kinds, [ChunkKind::Texture, ChunkKind::Bin, ChunkKind::Animation, ChunkKind::Other]);
+        assert_eq!(serde_json::to_string(&ChunkKind::Animation).unwrap(), "\"animation\"");
+    }
+}
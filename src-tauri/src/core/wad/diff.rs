@@ -0,0 +1,179 @@
+//! Comparing two versions of a WAD
+//!
+//! Chunks are matched by path hash. A chunk present on both sides is
+//! modified when its size differs, or when the table of contents checksums
+//! differ. WADs older than v3 carry no checksums; for those the chunks are
+//! decompressed and their contents hashed instead.
+
+use crate::core::hash::hashtable::Hashtable;
+use crate::core::wad::reader::WadReader;
+use crate::error::{Error, Result};
+use league_toolkit::wad::WadChunk;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// A chunk added, removed or modified between two WADs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WadDiffEntry {
+    /// Path hash as a 16-char lowercase hex string
+    pub hash: String,
+    /// Resolved path, `None` when the hash is unknown
+    pub path: Option<String>,
+    /// Decompressed size in the old WAD, `None` when added
+    pub old_size: Option<usize>,
+    /// Decompressed size in the new WAD, `None` when removed
+    pub new_size: Option<usize>,
+}
+
+/// Result of `diff_wads`, each list sorted by hash
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WadDiff {
+    pub old_path: String,
+    pub new_path: String,
+    pub added: Vec<WadDiffEntry>,
+    pub removed: Vec<WadDiffEntry>,
+    pub modified: Vec<WadDiffEntry>,
+    pub unchanged: usize,
+    /// Chunks compared by decompressed content for lack of checksums
+    pub content_compared: usize,
+}
+
+/// What the table of contents says about a chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkSummary {
+    pub size: usize,
+    /// 0 when the WAD version has no chunk checksums
+    pub checksum: u64,
+}
+
+impl From<&WadChunk> for ChunkSummary {
+    fn from(chunk: &WadChunk) -> Self {
+        Self { size: chunk.uncompressed_size(), checksum: chunk.checksum() }
+    }
+}
+
+/// Compare the WAD at `old_path` with the one at `new_path`
+pub fn diff_wads(old_path: &Path, new_path: &Path, hashtable: Option<&Hashtable>) -> Result<WadDiff> {
+    let mut old = WadReader::open(old_path)?;
+    let mut new = WadReader::open(new_path)?;
+    let summaries = |reader: &WadReader| -> BTreeMap<u64, ChunkSummary> {
+        reader.chunks().iter().map(|(hash, chunk)| (*hash, chunk.into())).collect()
+    };
+    let (old_chunks, new_chunks) = (summaries(&old), summaries(&new));
+
+    let mut diff = compare_chunks(&old_chunks, &new_chunks, hashtable, |hash| {
+        Ok(content_hash(&mut old, old_path, hash)? != content_hash(&mut new, new_path, hash)?)
+    })?;
+    diff.old_path = old_path.display().to_string();
+    diff.new_path = new_path.display().to_string();
+
+    tracing::info!(
+        "Diffed {} against {}: {} added, {} removed, {} modified, {} unchanged",
+        old_path.display(),
+        new_path.display(),
+        diff.added.len(),
+        diff.removed.len(),
+        diff.modified.len(),
+        diff.unchanged
+    );
+    Ok(diff)
+}
+
+/// Classify the chunks of two WADs. `content_differs` is asked about chunks
+/// of the same size without checksums on both sides.
+pub fn compare_chunks(
+    old: &BTreeMap<u64, ChunkSummary>,
+    new: &BTreeMap<u64, ChunkSummary>,
+    hashtable: Option<&Hashtable>,
+    mut content_differs: impl FnMut(u64) -> Result<bool>,
+) -> Result<WadDiff> {
+    let entry = |hash: u64, old: Option<&ChunkSummary>, new: Option<&ChunkSummary>| WadDiffEntry {
+        hash: format!("{:016x}", hash),
+        path: hashtable.and_then(|ht| ht.get(hash)).map(str::to_string),
+        old_size: old.map(|c| c.size),
+        new_size: new.map(|c| c.size),
+    };
+
+    let mut diff = WadDiff::default();
+    for (hash, old_chunk) in old {
+        let Some(new_chunk) = new.get(hash) else {
+            diff.removed.push(entry(*hash, Some(old_chunk), None));
+            continue;
+        };
+        let modified = if old_chunk.size != new_chunk.size {
+            true
+        } else if old_chunk.checksum != 0 && new_chunk.checksum != 0 {
+            old_chunk.checksum != new_chunk.checksum
+        } else {
+            diff.content_compared += 1;
+            content_differs(*hash)?
+        };
+        if modified {
+            diff.modified.push(entry(*hash, Some(old_chunk), Some(new_chunk)));
+        } else {
+            diff.unchanged += 1;
+        }
+    }
+    for (hash, new_chunk) in new.iter().filter(|(hash, _)| !old.contains_key(hash)) {
+        diff.added.push(entry(*hash, None, Some(new_chunk)));
+    }
+    Ok(diff)
+}
+
+/// xxh3 of a chunk's decompressed content
+fn content_hash(reader: &mut WadReader, wad_path: &Path, path_hash: u64) -> Result<u64> {
+    let chunk = *reader
+        .get_chunk(path_hash)
+        .ok_or_else(|| Error::wad_with_path(format!("Chunk {:016x} not found", path_hash), wad_path))?;
+    let data = reader.load_chunk(&chunk)?;
+    Ok(xxh3_64(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_uses_checksums_then_content() {
+        let summary = |size, checksum| ChunkSummary { size, checksum };
+        let old = BTreeMap::from([
+            (1, summary(10, 0xaa)),
+            (2, summary(10, 0xbb)),
+            (3, summary(10, 0)),
+            (4, summary(10, 0)),
+            (5, summary(10, 0xcc)),
+            (6, summary(10, 0xdd)),
+        ]);
+        let new = BTreeMap::from([
+            (1, summary(10, 0xaa)),
+            (2, summary(10, 0xbc)),
+            (3, summary(10, 0)),
+            (4, summary(10, 0)),
+            (5, summary(12, 0xcc)),
+            (7, summary(4, 0xee)),
+        ]);
+
+        let mut asked = Vec::new();
+        let diff = compare_chunks(&old, &new, None, |hash| {
+            asked.push(hash);
+            Ok(hash == 4)
+        })
+        .unwrap();
+
+        assert_eq!(asked, vec![3, 4]);
+        assert_eq!(diff.content_compared, 2);
+        assert_eq!(diff.unchanged, 2);
+        let hashes = |entries: &[WadDiffEntry]| entries.iter().map(|e| e.hash.clone()).collect::<Vec<_>>();
+        assert_eq!(hashes(&diff.modified), vec!["0000000000000002", "0000000000000004", "0000000000000005"]);
+        assert_eq!(hashes(&diff.removed), vec!["0000000000000006"]);
+        assert_eq!(diff.added[0], WadDiffEntry {
+            hash: "0000000000000007".to_string(),
+            path: None,
+            old_size: None,
+            new_size: Some(4),
+        });
+        assert_eq!((diff.modified[2].old_size, diff.modified[2].new_size), (Some(10), Some(12)));
+    }
+}
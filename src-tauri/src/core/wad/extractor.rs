@@ -1,11 +1,37 @@
+use crate::core::disk::check_disk_space;
 use crate::core::hash::hashtable::Hashtable;
+use crate::core::mapping::PathMappings;
+use crate::core::operation::CancellationToken;
+use crate::core::parallel;
+use crate::core::paths::safe_join;
+use crate::core::wad::reader::{WadReader, WadSource};
+use crate::core::wad::reader::{COMPRESSION_GZIP, COMPRESSION_NONE, COMPRESSION_ZSTD};
+use crate::core::wad::{WadKind, WadName};
 use crate::error::{Error, Result};
 use league_toolkit::file::LeagueFileKind;
-use league_toolkit::wad::{Wad, WadChunk};
-use std::collections::HashMap;
+use league_toolkit::wad::WadChunk;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ffi::OsStr;
+use flate2::read::GzDecoder;
 use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use xxhash_rust::xxh3::{xxh3_64, Xxh3};
+
+/// Chunks declaring a bigger decompressed size are treated as corrupt
+/// rather than extracted
+pub const MAX_CHUNK_SIZE: usize = 2 << 30;
+
+/// Decompressed bytes read before writing a streamed chunk, enough for
+/// `LeagueFileKind` to tell its type
+const MAGIC_PEEK: usize = 64;
+
+/// Write buffer of a streamed chunk
+const STREAM_BUFFER_SIZE: usize = 256 * 1024;
 
 /// Result of an extraction operation
 #[derive(Debug, Clone)]
@@ -13,45 +39,91 @@ pub struct ExtractionResult {
     /// Number of chunks successfully extracted
     pub extracted_count: usize,
     /// Mapping of original paths to actual paths (for long filenames saved with hashes)
-    pub path_mappings: HashMap<String, String>,
+    pub path_mappings: PathMappings,
+}
+
+/// A chunk whose path hash isn't in the hashtable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnknownHash {
+    /// Path hash as 16 hex digits
+    pub hash: String,
+    /// Decompressed size in bytes
+    pub size: usize,
+    /// Extension of the file type detected from the magic bytes, "unknown"
+    /// if none matched
+    pub kind: String,
+    /// File name of the WAD it came from
+    pub wad: String,
+}
+
+impl UnknownHash {
+    pub fn new(path_hash: u64, size: usize, kind: LeagueFileKind, wad: &str) -> Self {
+        Self {
+            hash: format!("{:016x}", path_hash),
+            size,
+            kind: kind.extension().unwrap_or("unknown").to_string(),
+            wad: wad.to_string(),
+        }
+    }
+}
+
+/// Whether `path_hash` is missing from `hashtable`; every hash is without one
+pub fn is_unknown_hash(hashtable: Option<&Hashtable>, path_hash: u64) -> bool {
+    hashtable.is_none_or(|ht| ht.get(path_hash).is_none())
+}
+
+/// Write unknown hashes to `path` the way CommunityDragon takes hash
+/// submissions: one 16-digit hash per line, sorted, each once. With
+/// `details` the type, size and WAD follow each hash, tab-separated.
+/// Returns the number of hashes written.
+pub fn write_unknown_hashes(path: &Path, hashes: &[UnknownHash], details: bool) -> Result<usize> {
+    let mut unique: BTreeMap<&str, &UnknownHash> = BTreeMap::new();
+    for hash in hashes {
+        unique.entry(hash.hash.as_str()).or_insert(hash);
+    }
+    let mut text = String::new();
+    for (hash, entry) in &unique {
+        if details {
+            text.push_str(&format!("{}\t{}\t{}\t{}\n", hash, entry.kind, entry.size, entry.wad));
+        } else {
+            text.push_str(&format!("{}\n", hash));
+        }
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+    }
+    fs::write(path, text).map_err(|e| Error::io_with_path(e, path))?;
+    tracing::info!("Wrote {} unknown hashes to {}", unique.len(), path.display());
+    Ok(unique.len())
 }
 
 /// Extracts a single chunk from a WAD archive to the specified output path
 /// 
 /// # Arguments
-/// * `wad` - Mutable reference to the Wad for decoding
+/// * `reader` - Reader of the WAD the chunk belongs to
 /// * `chunk` - The chunk to extract
 /// * `output_path` - Path where the chunk should be written
 /// * `hashtable` - Optional hashtable for path resolution (not used for single chunk extraction)
 /// 
 /// # Returns
-/// * `Result<()>` - Ok if extraction succeeded, Err otherwise
+/// * `Result<LeagueFileKind>` - The file type detected from the chunk's magic bytes
 /// 
 /// # Requirements
 /// Validates: Requirements 4.1, 4.2, 4.3
 pub fn extract_chunk(
-    wad: &mut Wad<File>,
+    reader: &mut WadReader,
     chunk: &WadChunk,
     output_path: impl AsRef<Path>,
     _hashtable: Option<&Hashtable>,
-) -> Result<()> {
+) -> Result<LeagueFileKind> {
     let output_path = output_path.as_ref();
     
     tracing::debug!("Extracting chunk to: {}", output_path.display());
     
-    // Create the decoder
-    let (mut decoder, _) = wad.decode();
-    
     // Decompress the chunk data
-    let chunk_data = decoder
-        .load_chunk_decompressed(chunk)
-        .map_err(|e| {
-            tracing::error!("Failed to decompress chunk for '{}': {}", output_path.display(), e);
-            Error::Wad {
-                message: format!("Failed to decompress chunk: {}", e),
-                path: Some(output_path.to_path_buf()),
-            }
-        })?;
+    let chunk_data = reader
+        .load_chunk(chunk)
+        .inspect_err(|e| tracing::error!("Failed to decompress chunk for '{}': {}", output_path.display(), e))?;
     
     // Verify decompressed size matches metadata
     if chunk_data.len() != chunk.uncompressed_size() {
@@ -89,132 +161,595 @@ pub fn extract_chunk(
     
     tracing::debug!("Successfully extracted chunk to: {}", output_path.display());
     
-    Ok(())
+    Ok(LeagueFileKind::identify_from_bytes(&chunk_data))
 }
 
-/// Extracts all chunks from a WAD archive to the specified output directory
-/// 
-/// This function resolves chunk paths using the provided hashtable, creates
-/// the necessary directory structure, handles filename collisions, detects
-/// file types, and falls back to hex hashes for unresolved paths.
-/// 
+/// Result of `extract_chunks`
+#[derive(Debug, Clone, Default)]
+pub struct ChunkExtraction {
+    /// Chunks written to disk
+    pub extracted: usize,
+    /// Chunks that failed to decompress or write; the rest are still extracted
+    pub failed: usize,
+    /// Extracted chunks the hashtable doesn't know, sorted by hash
+    pub unknown: Vec<UnknownHash>,
+    /// Decompressed bytes of the extracted chunks
+    pub bytes_written: u64,
+    /// Files written, with the path hash of their chunk
+    pub files: Vec<(u64, PathBuf)>,
+    /// Content fingerprint of each extracted chunk by path hash, as
+    /// `chunk_fingerprint` defines it, computed while the data was written
+    pub fingerprints: HashMap<u64, u64>,
+    /// Stopped by the cancellation token before every chunk was done; the
+    /// counts cover the chunks finished until then
+    pub cancelled: bool,
+}
+
+/// Where an `extract_chunks` run is at
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractProgress {
+    /// Chunks extracted or failed so far
+    pub done: usize,
+    pub total: usize,
+    pub bytes_written: u64,
+    /// Resolved path of the last chunk finished
+    pub current_path: String,
+}
+
+/// What happened to one chunk of an extraction
+#[derive(Debug, Clone)]
+enum ChunkOutcome {
+    /// Written to `path`, with its unknown hash entry if it has one
+    Extracted { path_hash: u64, path: PathBuf, unknown: Option<UnknownHash>, fingerprint: u64 },
+    Failed,
+    /// Not written, or removed again, because the extraction was cancelled
+    Cancelled,
+}
+
+/// Extracts the given chunks of the WAD at `wad_path` in parallel
+///
+/// Paths are resolved and their directories created up front, then the
+/// chunks are decompressed and written on the shared worker pool, so the
+/// `performance` thread limit caps the extraction. Each worker mounts its
+/// own handle to the WAD; writes hold an IO permit. Chunks are decompressed
+/// straight into their files, so large ones never sit in memory whole;
+/// only subchunked zstd chunks are decoded in memory first. A chunk that
+/// fails, or declares more than `MAX_CHUNK_SIZE` bytes, is logged and
+/// counted in `failed` without stopping the others.
+///
+/// Entries pointing at the same data (a texture shared by several skins)
+/// are decompressed once and written to each of their paths; each still
+/// counts as an extracted chunk.
+///
 /// # Arguments
-/// * `wad` - Mutable reference to the Wad for decoding
+/// * `wad_path` - WAD the chunks belong to
+/// * `chunks` - Chunks to extract with their path hashes
 /// * `output_dir` - Base directory where chunks should be extracted
 /// * `hashtable` - Optional hashtable for path resolution
-/// 
-/// # Returns
-/// * `Result<usize>` - Number of chunks successfully extracted, or an error
-/// 
+/// * `cancel` - Checked before each chunk and while streaming one. Once it
+///   trips, chunks not yet written are left out, a file being streamed is
+///   removed, and the partial counts come back with `cancelled` set.
+/// * `progress` - Called at most about a hundred times, never with a lower
+///   `done` count than before
+///
 /// # Requirements
 /// Validates: Requirements 4.1, 4.2, 4.3, 4.4, 4.5, 4.6
-pub fn extract_all(
-    wad: &mut Wad<File>,
+pub fn extract_chunks(
+    wad_path: impl AsRef<Path>,
+    chunks: &[(u64, WadChunk)],
     output_dir: impl AsRef<Path>,
     hashtable: Option<&Hashtable>,
-) -> Result<usize> {
+    cancel: Option<&CancellationToken>,
+    progress: impl Fn(&ExtractProgress) + Sync,
+) -> Result<ChunkExtraction> {
+    let wad_path = wad_path.as_ref();
     let output_dir = output_dir.as_ref();
-    
-    tracing::info!("Extracting all chunks to: {}", output_dir.display());
-    
-    // Create the decoder and get chunks
-    let (mut decoder, chunks) = wad.decode();
-    
-    let total_chunks = chunks.len();
-    tracing::info!("Total chunks to extract: {}", total_chunks);
-    
-    let mut extracted_count = 0;
-    
-    // Extract each chunk
-    for (path_hash, chunk) in chunks.iter() {
-        // Resolve the chunk path
-        let resolved_path = if let Some(ht) = hashtable {
-            ht.resolve(*path_hash).to_string()
-        } else {
-            // Fall back to hex hash if no hashtable provided
-            format!("{:016x}", path_hash)
+    let wad_name = wad_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let total = chunks.len();
+
+    tracing::info!("Extracting {} chunks of {} to: {}", total, wad_path.display(), output_dir.display());
+    // Chunks over the size limit fail without being written
+    let writable = chunks.iter().map(|(_, chunk)| chunk).filter(|chunk| chunk.uncompressed_size() <= MAX_CHUNK_SIZE);
+    check_disk_space(output_dir, extracted_size(writable))?;
+
+    // Resolve every path first so each directory is created once, not once per chunk
+    let mut failed = 0;
+    let mut groups: BTreeMap<(usize, usize, usize), ChunkGroup> = BTreeMap::new();
+    let mut directories = BTreeSet::new();
+    for (path_hash, chunk) in chunks {
+        let resolved_path = match hashtable {
+            Some(ht) => ht.resolve(*path_hash).to_string(),
+            None => format!("{:016x}", path_hash),
         };
-        
-        tracing::debug!("Extracting chunk: {} (hash: {:016x})", resolved_path, path_hash);
-        
-        // Decompress the chunk data
-        let chunk_data = decoder
-            .load_chunk_decompressed(chunk)
-            .map_err(|e| {
-                tracing::error!("Failed to decompress chunk '{}': {}", resolved_path, e);
-                Error::Wad {
-                    message: format!("Failed to decompress chunk {}: {}", resolved_path, e),
-                    path: Some(output_dir.to_path_buf()),
+        match safe_join(output_dir, &resolved_path) {
+            Ok(path) => {
+                if let Some(parent) = path.parent() {
+                    directories.insert(parent.to_path_buf());
                 }
-            })?;
-        
-        // Verify decompressed size matches metadata
-        if chunk_data.len() != chunk.uncompressed_size() {
-            tracing::error!(
-                "Decompressed size mismatch for '{}': expected {}, got {}",
-                resolved_path,
-                chunk.uncompressed_size(),
-                chunk_data.len()
-            );
-            return Err(Error::Wad {
-                message: format!(
-                    "Decompressed size mismatch for {}: expected {}, got {}",
-                    resolved_path,
-                    chunk.uncompressed_size(),
-                    chunk_data.len()
-                ),
-                path: Some(output_dir.to_path_buf()),
-            });
+                let data = (chunk.data_offset(), chunk.compressed_size(), chunk.uncompressed_size());
+                groups
+                    .entry(data)
+                    .or_insert_with(|| ChunkGroup { chunk: *chunk, targets: Vec::new() })
+                    .targets
+                    .push((*path_hash, resolved_path));
+            }
+            Err(e) => {
+                tracing::warn!("Skipping chunk {:016x}: {}", path_hash, e);
+                failed += 1;
+            }
         }
-        
-        // Resolve the final chunk path with extension handling
-        let final_path = resolve_chunk_path(&resolved_path, &chunk_data);
-        let full_output_path = output_dir.join(&final_path);
-        
-        // Create parent directories
-        if let Some(parent) = full_output_path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| {
-                    tracing::error!("Failed to create directory '{}': {}", parent.display(), e);
-                    Error::io_with_path(e, parent)
-                })?;
+    }
+    for dir in &directories {
+        if let Err(e) = fs::create_dir_all(dir) {
+            // Chunks inside fail on their own and fall back to their hash if the name is the problem
+            tracing::warn!("Failed to create directory '{}': {}", dir.display(), e);
         }
-        
-        // Write the chunk data
-        match fs::write(&full_output_path, &chunk_data) {
-            Ok(_) => {
-                extracted_count += 1;
-                if extracted_count % 100 == 0 {
-                    tracing::info!("Extracted {}/{} chunks", extracted_count, total_chunks);
-                }
+    }
+
+    let groups: Vec<ChunkGroup> = groups.into_values().collect();
+    let shared: usize = groups.iter().map(|group| group.targets.len()).filter(|&n| n > 1).sum();
+    if shared > 0 {
+        tracing::info!("{} chunks share their data with another, decompressing it once", shared);
+    }
+
+    let done = AtomicUsize::new(failed);
+    let bytes_written = AtomicU64::new(0);
+    let reported = Mutex::new(0);
+    let step = (total / 100).max(1);
+    let report = |finished: usize, count: usize, current_path: &str| {
+        let before = finished - count;
+        if before / step != finished / step || finished == total {
+            let mut reported = reported.lock();
+            if finished > *reported {
+                *reported = finished;
+                progress(&ExtractProgress {
+                    done: finished,
+                    total,
+                    bytes_written: bytes_written.load(Ordering::Relaxed),
+                    current_path: current_path.to_string(),
+                });
+            }
+        }
+    };
+
+    let outcomes = parallel::install(|| {
+        groups
+            .par_iter()
+            .map_init(
+                || ChunkWorker::open(wad_path).map_err(|e| e.to_string()),
+                |worker, group| {
+                    if is_cancelled(cancel) {
+                        return Ok(vec![ChunkOutcome::Cancelled; group.targets.len()]);
+                    }
+                    let worker = worker.as_mut().map_err(|e| Error::wad_with_path(e.clone(), wad_path))?;
+                    let outcomes = extract_chunk_group(worker, group, output_dir, hashtable, &wad_name, cancel);
+                    let written = outcomes.iter().filter(|o| matches!(o, ChunkOutcome::Extracted { .. })).count();
+                    bytes_written.fetch_add((written * group.chunk.uncompressed_size()) as u64, Ordering::Relaxed);
+                    let count = outcomes.iter().filter(|o| !matches!(o, ChunkOutcome::Cancelled)).count();
+                    if count > 0 {
+                        let current_path = &group.targets[count - 1].1;
+                        report(done.fetch_add(count, Ordering::Relaxed) + count, count, current_path);
+                    }
+                    Ok(outcomes)
+                },
+            )
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    let mut extraction = ChunkExtraction {
+        failed,
+        bytes_written: bytes_written.into_inner(),
+        ..Default::default()
+    };
+    for outcome in outcomes.into_iter().flatten() {
+        match outcome {
+            ChunkOutcome::Extracted { path_hash, path, unknown, fingerprint } => {
+                extraction.extracted += 1;
+                extraction.fingerprints.insert(path_hash, fingerprint);
+                extraction.files.push((path_hash, path));
+                extraction.unknown.extend(unknown);
+            }
+            ChunkOutcome::Failed => extraction.failed += 1,
+            ChunkOutcome::Cancelled => extraction.cancelled = true,
+        }
+    }
+    extraction.unknown.sort_by(|a, b| a.hash.cmp(&b.hash));
+    extraction.files.sort();
+
+    tracing::info!(
+        "Extracted {}/{} chunks ({} failed{}), {} with unknown hashes",
+        extraction.extracted,
+        total,
+        extraction.failed,
+        if extraction.cancelled { ", cancelled" } else { "" },
+        extraction.unknown.len()
+    );
+    Ok(extraction)
+}
+
+/// A file `extract_chunks` would write
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedFile {
+    /// Path hash as 16 hex digits
+    pub hash: String,
+    /// Destination, extension added the way extraction adds it
+    pub path: String,
+    /// Decompressed size in bytes
+    pub size: usize,
+    /// Not in the hashtable, so saved under its hash
+    pub unknown: bool,
+}
+
+/// What `extract_chunks` would write, without writing anything
+#[derive(Debug, Clone, Default)]
+pub struct ExtractionPlan {
+    /// Sorted by path
+    pub files: Vec<PlannedFile>,
+    /// Decompressed bytes of `files`
+    pub total_size: u64,
+    /// Chunks that would fail: invalid paths, over `MAX_CHUNK_SIZE`, or
+    /// unreadable when their type has to be sniffed
+    pub failed: usize,
+}
+
+/// Work out where `extract_chunks` would write each chunk and how big it is
+///
+/// Paths resolve the same way, so only chunks whose path has no extension
+/// are read, and only their first bytes unless the data is subchunked. The
+/// hex fallback for names the file system rejects isn't predicted.
+pub fn plan_extraction(
+    wad_path: impl AsRef<Path>,
+    chunks: &[(u64, WadChunk)],
+    output_dir: impl AsRef<Path>,
+    hashtable: Option<&Hashtable>,
+) -> Result<ExtractionPlan> {
+    let (wad_path, output_dir) = (wad_path.as_ref(), output_dir.as_ref());
+    let mut worker = ChunkWorker::open(wad_path)?;
+    let mut plan = ExtractionPlan::default();
+    for (path_hash, chunk) in chunks {
+        let size = chunk.uncompressed_size();
+        if size > MAX_CHUNK_SIZE {
+            plan.failed += 1;
+            continue;
+        }
+        let resolved_path = match hashtable {
+            Some(ht) => ht.resolve(*path_hash).to_string(),
+            None => format!("{:016x}", path_hash),
+        };
+        let head = if Path::new(&resolved_path).extension().is_some() {
+            Ok(Vec::new())
+        } else {
+            match read_chunk_head(&mut worker.file, chunk) {
+                Ok(Some(head)) => Ok(head),
+                Ok(None) => worker.reader.load_chunk(chunk).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
             }
-            Err(e) if e.kind() == std::io::ErrorKind::InvalidFilename => {
-                tracing::warn!("Invalid filename '{}', using hex hash fallback", full_output_path.display());
-                // Handle long filename by using hex hash
+        };
+        let path = head.and_then(|head| {
+            safe_join(output_dir, &resolve_chunk_path(&resolved_path, &head).to_string_lossy()).map_err(|e| e.to_string())
+        });
+        match path {
+            Ok(path) => {
+                plan.total_size += size as u64;
+                plan.files.push(PlannedFile {
+                    hash: format!("{:016x}", path_hash),
+                    path: path.to_string_lossy().to_string(),
+                    size,
+                    unknown: is_unknown_hash(hashtable, *path_hash),
+                });
+            }
+            Err(e) => {
+                tracing::warn!("Chunk {:016x} would fail to extract: {}", path_hash, e);
+                plan.failed += 1;
+            }
+        }
+    }
+    plan.files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(plan)
+}
+
+/// Per-worker handles to the WAD being extracted
+struct ChunkWorker {
+    reader: WadReader,
+    /// Raw handle chunks are streamed from
+    file: WadSource,
+}
+
+impl ChunkWorker {
+    fn open(wad_path: &Path) -> Result<Self> {
+        let reader = WadReader::open(wad_path)?;
+        let file = WadSource::open(wad_path)?;
+        Ok(Self { reader, file })
+    }
+}
+
+/// Table of contents entries sharing the same data
+struct ChunkGroup {
+    chunk: WadChunk,
+    /// Path hash and resolved path of each entry
+    targets: Vec<(u64, String)>,
+}
+
+/// A chunk's decompressed data as far as it was read before writing
+enum ChunkData {
+    /// The first `MAGIC_PEEK` bytes; the rest is streamed to the file
+    Streamed(Vec<u8>),
+    /// Everything, for compression that can't be streamed
+    Whole(Vec<u8>),
+}
+
+impl ChunkData {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            ChunkData::Streamed(head) | ChunkData::Whole(head) => head,
+        }
+    }
+}
+
+fn is_cancelled(cancel: Option<&CancellationToken>) -> bool {
+    cancel.is_some_and(CancellationToken::is_cancelled)
+}
+
+/// Decompress a group's data once and write it to each of its paths for
+/// `extract_chunks`, returning one outcome per path in order
+fn extract_chunk_group(
+    worker: &mut ChunkWorker,
+    group: &ChunkGroup,
+    output_dir: &Path,
+    hashtable: Option<&Hashtable>,
+    wad_name: &str,
+    cancel: Option<&CancellationToken>,
+) -> Vec<ChunkOutcome> {
+    let chunk = &group.chunk;
+    let size = chunk.uncompressed_size();
+    let Some(data) = decode_chunk(worker, chunk, &group.targets[0].1) else {
+        return vec![ChunkOutcome::Failed; group.targets.len()];
+    };
+
+    // Streamed data is decompressed into the first file; the others copy it.
+    // Without a checksum, streamed data is fingerprinted on that first write.
+    let mut first_written = None;
+    let mut fingerprint = match &data {
+        _ if chunk.checksum() != 0 => Some(chunk.checksum()),
+        ChunkData::Whole(data) => Some(xxh3_64(data)),
+        ChunkData::Streamed(_) => None,
+    };
+    let mut outcomes = Vec::with_capacity(group.targets.len());
+    for (path_hash, resolved_path) in &group.targets {
+        if is_cancelled(cancel) {
+            outcomes.push(ChunkOutcome::Cancelled);
+            continue;
+        }
+        tracing::debug!("Extracting chunk: {} (hash: {:016x})", resolved_path, path_hash);
+        let unknown = is_unknown_hash(hashtable, *path_hash)
+            .then(|| UnknownHash::new(*path_hash, size, LeagueFileKind::identify_from_bytes(data.bytes()), wad_name));
+
+        // Resolve the final chunk path with extension handling
+        let final_path = resolve_chunk_path(resolved_path, data.bytes());
+        let full_output_path = match safe_join(output_dir, &final_path.to_string_lossy()) {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::warn!("Skipping chunk {:016x}: {}", path_hash, e);
+                outcomes.push(ChunkOutcome::Failed);
+                continue;
+            }
+        };
+
+        let _permit = parallel::io_permit();
+        let mut write = |path: &Path| {
+            write_chunk_data(&mut worker.file, chunk, &data, &mut first_written, &mut fingerprint, path, cancel)
+        };
+        let path_hash = *path_hash;
+        let outcome = match write(&full_output_path) {
+            Ok(fingerprint) => ChunkOutcome::Extracted { path_hash, path: full_output_path, unknown, fingerprint },
+            Err(_) if is_cancelled(cancel) => ChunkOutcome::Cancelled,
+            // An invalid file name, or a directory whose name couldn't be created
+            Err(e) if e.kind() == io::ErrorKind::InvalidFilename || e.kind() == io::ErrorKind::NotFound => {
+                tracing::warn!("Invalid path '{}', using hex hash fallback", full_output_path.display());
                 let hex_path = format!("{:016x}", path_hash);
-                let hex_output_path = resolve_chunk_path(&hex_path, &chunk_data);
-                let full_hex_path = output_dir.join(&hex_output_path);
-                
-                fs::write(&full_hex_path, &chunk_data)
-                    .map_err(|e| {
+                let full_hex_path = output_dir.join(resolve_chunk_path(&hex_path, data.bytes()));
+                match write(&full_hex_path) {
+                    Ok(fingerprint) => ChunkOutcome::Extracted { path_hash, path: full_hex_path, unknown, fingerprint },
+                    Err(_) if is_cancelled(cancel) => ChunkOutcome::Cancelled,
+                    Err(e) => {
                         tracing::error!("Failed to write chunk to '{}': {}", full_hex_path.display(), e);
-                        Error::io_with_path(e, &full_hex_path)
-                    })?;
-                extracted_count += 1;
+                        ChunkOutcome::Failed
+                    }
+                }
             }
             Err(e) => {
                 tracing::error!("Failed to write chunk to '{}': {}", full_output_path.display(), e);
-                return Err(Error::io_with_path(e, &full_output_path));
+                ChunkOutcome::Failed
+            }
+        };
+        outcomes.push(outcome);
+    }
+    outcomes
+}
+
+/// Decompress a chunk, or as much of it as detecting its type takes when it
+/// can be streamed. `None` if it failed, logged under `resolved_path`.
+fn decode_chunk(worker: &mut ChunkWorker, chunk: &WadChunk, resolved_path: &str) -> Option<ChunkData> {
+    let size = chunk.uncompressed_size();
+    if size > MAX_CHUNK_SIZE {
+        tracing::error!(
+            "Chunk '{}' declares {} bytes decompressed, over the {} byte limit; the WAD is likely corrupt",
+            resolved_path,
+            size,
+            MAX_CHUNK_SIZE
+        );
+        return None;
+    }
+
+    // Streamed chunks are only decoded far enough here to detect their type
+    match read_chunk_head(&mut worker.file, chunk) {
+        Ok(Some(head)) => Some(ChunkData::Streamed(head)),
+        Ok(None) => {
+            match worker.reader.load_chunk(chunk) {
+                Ok(data) if data.len() == size => Some(ChunkData::Whole(data)),
+                Ok(data) => {
+                    tracing::error!(
+                        "Decompressed size mismatch for '{}': expected {}, got {}",
+                        resolved_path,
+                        size,
+                        data.len()
+                    );
+                    None
+                }
+                Err(e) => {
+                    tracing::error!("Failed to decompress chunk '{}': {}", resolved_path, e);
+                    None
+                }
             }
         }
+        Err(e) => {
+            tracing::error!("Failed to decompress chunk '{}': {}", resolved_path, e);
+            None
+        }
     }
-    
-    tracing::info!("Successfully extracted {}/{} chunks", extracted_count, total_chunks);
-    
-    Ok(extracted_count)
+}
+
+/// Write a chunk's data to `path` and return its fingerprint. Streamed data
+/// is only decompressed into the first file written, recorded in
+/// `first_written`; later paths copy it. A missing `fingerprint` is hashed
+/// from the data as it streams.
+fn write_chunk_data(
+    file: &mut (impl Read + Seek),
+    chunk: &WadChunk,
+    data: &ChunkData,
+    first_written: &mut Option<PathBuf>,
+    fingerprint: &mut Option<u64>,
+    path: &Path,
+    cancel: Option<&CancellationToken>,
+) -> io::Result<u64> {
+    match (data, first_written.as_deref()) {
+        (ChunkData::Whole(data), _) => fs::write(path, data)?,
+        (ChunkData::Streamed(_), Some(first)) if first == path => {}
+        (ChunkData::Streamed(_), Some(first)) => {
+            fs::copy(first, path)?;
+        }
+        (ChunkData::Streamed(_), None) => {
+            let mut hasher = fingerprint.is_none().then(Xxh3::new);
+            stream_chunk_to_file(file, chunk, path, cancel, hasher.as_mut())?;
+            *first_written = Some(path.to_path_buf());
+            if let Some(hasher) = hasher {
+                *fingerprint = Some(hasher.digest());
+            }
+        }
+    }
+    fingerprint.ok_or_else(|| io::Error::other("Chunk data was never fingerprinted"))
+}
+
+/// Decoder streaming a chunk's data out of its WAD, `None` for compression
+/// that can only be decoded whole (subchunked zstd, see `WadReader::load_chunk`)
+fn chunk_reader<'a, R: Read + Seek>(file: &'a mut R, chunk: &WadChunk) -> io::Result<Option<Box<dyn Read + 'a>>> {
+    file.seek(SeekFrom::Start(chunk.data_offset() as u64))?;
+    let raw = Read::by_ref(file).take(chunk.compressed_size() as u64);
+    Ok(match chunk.compression_type() as u8 {
+        COMPRESSION_NONE => Some(Box::new(raw)),
+        COMPRESSION_GZIP => Some(Box::new(GzDecoder::new(raw))),
+        COMPRESSION_ZSTD => Some(Box::new(zstd::stream::read::Decoder::new(raw)?)),
+        _ => None,
+    })
+}
+
+/// The first `MAGIC_PEEK` decompressed bytes of a chunk that can be streamed
+pub(crate) fn read_chunk_head(file: &mut (impl Read + Seek), chunk: &WadChunk) -> io::Result<Option<Vec<u8>>> {
+    let Some(reader) = chunk_reader(file, chunk)? else {
+        return Ok(None);
+    };
+    let mut head = Vec::with_capacity(MAGIC_PEEK);
+    reader.take(MAGIC_PEEK as u64).read_to_end(&mut head)?;
+    Ok(Some(head))
+}
+
+/// Decompress a chunk from the WAD opened as `file` straight into `path`
+/// through a fixed-size buffer, so memory use doesn't grow with the chunk.
+/// Returns the bytes written; a partial file is removed on failure, or when
+/// `cancel` trips midway. The decompressed data is also fed to `hasher`.
+pub fn stream_chunk_to_file(
+    file: &mut (impl Read + Seek),
+    chunk: &WadChunk,
+    path: &Path,
+    cancel: Option<&CancellationToken>,
+    mut hasher: Option<&mut Xxh3>,
+) -> io::Result<u64> {
+    let expected = chunk.uncompressed_size() as u64;
+    let mut stream = || -> io::Result<u64> {
+        let inner = chunk_reader(file, chunk)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "Chunk compression can't be streamed"))?;
+        let reader = CancellableRead { inner, cancel, hasher: hasher.as_deref_mut() };
+        let mut writer = BufWriter::with_capacity(STREAM_BUFFER_SIZE, File::create(path)?);
+        // One byte past the declared size is enough to tell it was exceeded
+        let written = io::copy(&mut reader.take(expected + 1), &mut writer)?;
+        writer.flush()?;
+        if written != expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Decompressed size mismatch: expected {}, got {}", expected, written),
+            ));
+        }
+        Ok(written)
+    };
+    let result = stream();
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+/// Reader that fails once `cancel` trips, so a long copy stops promptly,
+/// and feeds what it reads to `hasher`
+struct CancellableRead<'a, 'h, R> {
+    inner: R,
+    cancel: Option<&'a CancellationToken>,
+    hasher: Option<&'h mut Xxh3>,
+}
+
+impl<R: Read> Read for CancellableRead<'_, '_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if is_cancelled(self.cancel) {
+            return Err(io::Error::other("Extraction cancelled"));
+        }
+        let read = self.inner.read(buf)?;
+        if let Some(hasher) = self.hasher.as_deref_mut() {
+            hasher.update(&buf[..read]);
+        }
+        Ok(read)
+    }
+}
+
+/// What identifies a chunk's content: its table of contents checksum, or for
+/// WADs without checksums the xxh3 of its decompressed data
+pub fn chunk_fingerprint(reader: &mut WadReader, chunk: &WadChunk) -> Result<u64> {
+    if chunk.checksum() != 0 {
+        return Ok(chunk.checksum());
+    }
+    if chunk.uncompressed_size() > MAX_CHUNK_SIZE {
+        return Err(Error::Wad {
+            message: format!(
+                "Chunk declares {} bytes decompressed, over the {} byte limit",
+                chunk.uncompressed_size(),
+                MAX_CHUNK_SIZE
+            ),
+            path: None,
+        });
+    }
+    let data = reader.load_chunk(chunk)?;
+    Ok(xxh3_64(&data))
+}
+
+/// Bytes the given chunks take once decompressed, used as the disk space
+/// estimate before extracting them
+pub fn extracted_size<'a>(chunks: impl IntoIterator<Item = &'a WadChunk>) -> u64 {
+    chunks.into_iter().map(|chunk| chunk.uncompressed_size() as u64).sum()
 }
 
 /// Find the champion WAD file in a League installation
+///
+/// Only client WADs qualify; mobile and locale (voice-over) WADs are never
+/// returned. The expected lowercase name is tried first, then any client WAD
+/// whose name matches case-insensitively.
 /// 
 /// # Arguments
 /// * `league_path` - Path to League installation
@@ -233,20 +768,34 @@ pub fn find_champion_wad(league_path: impl AsRef<Path>, champion: &str) -> Optio
         .replace(".", "");
     
     // Standard WAD path
-    let wad_path = league_path
+    let champions_dir = league_path
         .join("Game")
         .join("DATA")
         .join("FINAL")
-        .join("Champions")
-        .join(format!("{}.wad.client", champion_normalized));
+        .join("Champions");
+    let wad_path = champions_dir.join(format!("{}.wad.client", champion_normalized));
     
     if wad_path.exists() {
         tracing::info!("Found champion WAD: {}", wad_path.display());
-        Some(wad_path)
-    } else {
-        tracing::warn!("Champion WAD not found: {}", wad_path.display());
-        None
+        return Some(wad_path);
     }
+
+    let found = fs::read_dir(&champions_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(WadName::parse)
+                .is_some_and(|wad| wad.kind == WadKind::Client && wad.base.eq_ignore_ascii_case(&champion_normalized))
+        });
+    match &found {
+        Some(path) => tracing::info!("Found champion WAD: {}", path.display()),
+        None => tracing::warn!("Champion WAD not found: {}", wad_path.display()),
+    }
+    found
 }
 
 /// Extract skin-specific assets from a WAD archive
@@ -255,20 +804,22 @@ pub fn find_champion_wad(league_path: impl AsRef<Path>, champion: &str) -> Optio
 /// happens later during the repathing phase based on what the skin BIN references.
 /// 
 /// # Arguments
-/// * `wad` - Mutable reference to the Wad for decoding
+/// * `reader` - Reader of the champion's WAD
 /// * `output_dir` - Base directory where chunks should be extracted
 /// * `champion` - Champion internal name (e.g., "kayn")
 /// * `skin_id` - Skin ID to extract (e.g., 1 for first skin)
 /// * `hashtable` - Hashtable for path resolution
+/// * `cancel` - Checked before each chunk; files already written are kept
 /// 
 /// # Returns
 /// * `Result<ExtractionResult>` - Extraction result with count and path mappings, or an error
 pub fn extract_skin_assets(
-    wad: &mut Wad<File>,
+    reader: &mut WadReader,
     output_dir: impl AsRef<Path>,
     champion: &str,
     _skin_id: u32,
     hashtable: &Hashtable,
+    cancel: Option<&CancellationToken>,
 ) -> Result<ExtractionResult> {
     let output_dir = output_dir.as_ref();
     
@@ -284,18 +835,21 @@ pub fn extract_skin_assets(
         wad_folder_name
     );
     
-    // Create the decoder and get chunks
-    let (mut decoder, chunks) = wad.decode();
-    
+    let chunks = reader.chunks().clone();
     let total_chunks = chunks.len();
     tracing::info!("Total chunks in WAD: {}", total_chunks);
+    check_disk_space(output_dir, extracted_size(chunks.values()))?;
     
     let mut extracted_count = 0;
-    let mut path_mappings: HashMap<String, String> = HashMap::new();
+    let mut path_mappings = PathMappings::default();
     
     // Extract all chunks - we'll clean up unused files later based on skin BIN references
     let mut skipped_unknown = 0;
     for (path_hash, chunk) in chunks.iter() {
+        if let Some(token) = cancel {
+            token.check()?;
+        }
+
         // Resolve the chunk path
         let resolved_path = hashtable.resolve(*path_hash).to_string();
         let path_lower = resolved_path.to_lowercase();
@@ -317,7 +871,7 @@ pub fn extract_skin_assets(
         }
         
         // Decompress the chunk data
-        let chunk_data = match decoder.load_chunk_decompressed(chunk) {
+        let chunk_data = match reader.load_chunk(chunk) {
             Ok(data) => data,
             Err(e) => {
                 tracing::warn!("Failed to decompress chunk '{}': {}", resolved_path, e);
@@ -338,13 +892,22 @@ pub fn extract_skin_assets(
             tracing::info!("Using hash for long filename: {} -> {}", final_path.display(), hash_path.display());
             
             // Record the mapping so refather can find the file
-            let original_normalized = final_path.to_string_lossy().to_lowercase().replace('\\', "/");
-            let actual_normalized = hash_path.to_string_lossy().to_lowercase().replace('\\', "/");
-            path_mappings.insert(original_normalized, actual_normalized);
+            path_mappings.insert(
+                &final_path.to_string_lossy(),
+                &hash_path.to_string_lossy(),
+                &wad_folder_name,
+            );
             
-            wad_output_dir.join(&hash_path)
+            safe_join(&wad_output_dir, &hash_path.to_string_lossy())
         } else {
-            wad_output_dir.join(&final_path)
+            safe_join(&wad_output_dir, &final_path.to_string_lossy())
+        };
+        let output_path_to_use = match output_path_to_use {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::warn!("Skipping chunk {:016x}: {}", path_hash, e);
+                continue;
+            }
         };
         
         // Create parent directories
@@ -450,7 +1013,52 @@ fn resolve_chunk_path(path: &str, chunk_data: &[u8]) -> PathBuf {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    /// Tracks the bytes each thread has allocated, so a test can measure its
+    /// own peak while other tests run
+    struct ThreadPeakAllocator;
+
+    thread_local! {
+        static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+        static PEAK: Cell<isize> = const { Cell::new(0) };
+    }
+
+    fn track(delta: isize) {
+        let _ = ALLOCATED.try_with(|allocated| {
+            allocated.set(allocated.get() + delta);
+            let _ = PEAK.try_with(|peak| peak.set(peak.get().max(allocated.get())));
+        });
+    }
+
+    unsafe impl GlobalAlloc for ThreadPeakAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                track(layout.size() as isize);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            track(-(layout.size() as isize));
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: ThreadPeakAllocator = ThreadPeakAllocator;
+
+    /// Peak bytes allocated by this thread while running `f`, above what it
+    /// held before
+    fn peak_allocation<R>(f: impl FnOnce() -> R) -> (R, usize) {
+        let base = ALLOCATED.with(Cell::get);
+        PEAK.with(|peak| peak.set(base));
+        let result = f();
+        (result, (PEAK.with(Cell::get) - base).max(0) as usize)
+    }
+
     #[test]
     fn test_resolve_chunk_path_with_extension() {
         let path = "characters/aatrox/aatrox.bin";
@@ -471,6 +1079,290 @@ mod tests {
         assert!(resolved.to_string_lossy().contains(".ltk"));
     }
     
+    #[test]
+    fn test_write_unknown_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let hash = |hex: &str, kind: &str| UnknownHash {
+            hash: hex.to_string(),
+            size: 10,
+            kind: kind.to_string(),
+            wad: "Ahri.wad.client".to_string(),
+        };
+        let hashes = [
+            hash("00000000000000ff", "tex"),
+            hash("0000000000000001", "unknown"),
+            hash("00000000000000ff", "tex"),
+        ];
+        let path = dir.path().join("unknown.txt");
+
+        assert_eq!(write_unknown_hashes(&path, &hashes, false).unwrap(), 2);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "0000000000000001\n00000000000000ff\n");
+        write_unknown_hashes(&path, &hashes, true).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().ends_with("00000000000000ff\ttex\t10\tAhri.wad.client\n"));
+    }
+
+    /// Write a WAD holding `chunks`, each packed from a file named by its hash
+    fn write_synthetic_wad(path: &Path, chunks: &[(u64, Vec<u8>)]) {
+        let dir = path.with_extension("files");
+        fs::create_dir_all(&dir).unwrap();
+        for (hash, bytes) in chunks {
+            fs::write(dir.join(format!("{:016x}.bin", hash)), bytes).unwrap();
+        }
+        crate::core::wad::writer::build_wad(&dir, path).unwrap();
+    }
+
+    const SYNTHETIC_CHUNKS: u64 = 512;
+
+    /// WAD path, file data by hash, the hashtable, and the WAD's chunks by hash
+    type SyntheticWad = (PathBuf, Vec<(u64, Vec<u8>)>, Hashtable, Vec<(u64, WadChunk)>);
+
+    /// A WAD of 64 KiB chunks in `dir` whose even hashes resolve into 16
+    /// directories and odd ones stay unknown, with its chunks sorted by hash
+    fn synthetic_wad(dir: &Path) -> SyntheticWad {
+        let wad_path = dir.join("Synthetic.wad.client");
+        let hash_dir = dir.join("hashes");
+        fs::create_dir_all(&hash_dir).unwrap();
+
+        let mut hash_list = String::new();
+        let chunks: Vec<(u64, Vec<u8>)> = (1..=SYNTHETIC_CHUNKS)
+            .map(|hash| {
+                if hash % 2 == 0 {
+                    hash_list.push_str(&format!("{:016x} assets/dir{}/file{}.bin\n", hash, hash % 16, hash));
+                }
+                let bytes = (0..64 * 1024u64).map(|i| ((i * 31 + hash * 7) % 251) as u8 ^ (i >> 9) as u8).collect();
+                (hash, bytes)
+            })
+            .collect();
+        fs::write(hash_dir.join("hashes.game.txt"), hash_list).unwrap();
+        write_synthetic_wad(&wad_path, &chunks);
+        let hashtable = Hashtable::from_directory(&hash_dir).unwrap();
+        let mounted: Vec<(u64, WadChunk)> = {
+            let reader = WadReader::open(&wad_path).unwrap();
+            let mut chunks: Vec<_> = reader.chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+            chunks.sort_by_key(|(hash, _)| *hash);
+            chunks
+        };
+        assert_eq!(mounted.len(), SYNTHETIC_CHUNKS as usize);
+        (wad_path, chunks, hashtable, mounted)
+    }
+
+    #[test]
+    fn test_extract_chunks_synthetic_wad() {
+        let dir = tempfile::tempdir().unwrap();
+        let (wad_path, chunks, hashtable, mounted) = synthetic_wad(dir.path());
+        let output = dir.path().join("out");
+        let reports = Mutex::new(Vec::new());
+        let extraction = extract_chunks(&wad_path, &mounted, &output, Some(&hashtable), None, |progress| {
+            reports.lock().push((progress.done, progress.total));
+        })
+        .unwrap();
+        let reports = reports.into_inner();
+
+        let total = SYNTHETIC_CHUNKS as usize;
+        assert_eq!((extraction.extracted, extraction.failed), (total, 0));
+        assert_eq!(extraction.unknown.len(), total / 2);
+        assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(reports.last(), Some(&(total, total)));
+        assert_eq!(fs::read(output.join("assets/dir2/file2.bin")).unwrap(), chunks[1].1);
+        assert_eq!(fs::read(output.join("0000000000000001.ltk")).unwrap(), chunks[0].1);
+    }
+
+    #[test]
+    fn test_extraction_fingerprints_chunks_without_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        let wad_path = dir.path().join("Fingerprint.wad.client");
+        let chunks = vec![(1u64, b"small".to_vec()), (2, (0..200_000u32).map(|i| (i % 7) as u8).collect())];
+        write_synthetic_wad(&wad_path, &chunks);
+
+        // Clear the table of contents checksums (the last 8 bytes of each
+        // 32 byte entry after the 272 byte header), as version 1 WADs have none
+        let mut bytes = fs::read(&wad_path).unwrap();
+        for entry in 0..chunks.len() {
+            let end = 272 + (entry + 1) * 32;
+            bytes[end - 8..end].fill(0);
+        }
+        fs::write(&wad_path, bytes).unwrap();
+
+        let mut reader = WadReader::open(&wad_path).unwrap();
+        let mut mounted: Vec<(u64, WadChunk)> = reader.chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+        mounted.sort_by_key(|(hash, _)| *hash);
+        assert!(mounted.iter().all(|(_, chunk)| chunk.checksum() == 0));
+
+        let extraction = extract_chunks(&wad_path, &mounted, dir.path().join("out"), None, None, |_| {}).unwrap();
+        assert_eq!(extraction.extracted, 2);
+        for ((hash, data), (_, chunk)) in chunks.iter().zip(&mounted) {
+            assert_eq!(extraction.fingerprints[hash], xxh3_64(data));
+            assert_eq!(extraction.fingerprints[hash], chunk_fingerprint(&mut reader, chunk).unwrap());
+        }
+    }
+
+    /// Compares one worker with all of them; run with `--ignored`. Ignored by
+    /// default because it reconfigures the shared worker pool other tests use.
+    #[test]
+    #[ignore = "benchmark; reconfigures the shared worker pool"]
+    fn bench_extract_chunks_synthetic_wad() {
+        let dir = tempfile::tempdir().unwrap();
+        let (wad_path, _, hashtable, mounted) = synthetic_wad(dir.path());
+        let run = |threads: usize, output: &Path| {
+            parallel::configure(&crate::core::settings::PerformanceSettings { max_threads: threads, ..Default::default() });
+            let started = std::time::Instant::now();
+            let extraction = extract_chunks(&wad_path, &mounted, output, Some(&hashtable), None, |_| {}).unwrap();
+            (extraction, started.elapsed())
+        };
+        let (sequential, sequential_time) = run(1, &dir.path().join("sequential"));
+        let (extraction, parallel_time) = run(0, &dir.path().join("parallel"));
+        let threads = parallel::limits().threads;
+        parallel::configure(&Default::default());
+        tracing::debug!(
+            "Extracted {} chunks: {:?} on 1 thread, {:?} on {} threads",
+            SYNTHETIC_CHUNKS,
+            sequential_time,
+            parallel_time,
+            threads
+        );
+
+        assert_eq!(extraction.extracted, sequential.extracted);
+        assert_eq!(extraction.unknown, sequential.unknown);
+        if threads > 1 {
+            assert!(parallel_time < sequential_time, "{:?} on {} threads vs {:?}", parallel_time, threads, sequential_time);
+        }
+    }
+
+    #[test]
+    fn test_large_chunk_streams_with_bounded_memory() {
+        const SIZE: usize = 64 << 20;
+        let dir = tempfile::tempdir().unwrap();
+        let wad_path = dir.path().join("Map11.wad.client");
+        let bytes: Vec<u8> = (0..SIZE).map(|i| (i % 251) as u8 ^ (i >> 12) as u8).collect();
+        write_synthetic_wad(&wad_path, &[(1, bytes.clone())]);
+        let chunk = *WadReader::open(&wad_path).unwrap().get_chunk(1).unwrap();
+
+        let output = dir.path().join("map11.mapgeo");
+        let mut file = File::open(&wad_path).unwrap();
+        let (written, peak) = peak_allocation(|| stream_chunk_to_file(&mut file, &chunk, &output, None, None).unwrap());
+        assert_eq!(written, SIZE as u64);
+        assert!(peak < 4 << 20, "streaming a {} byte chunk allocated {} bytes", SIZE, peak);
+        assert!(fs::read(&output).unwrap() == bytes);
+
+        // A table declaring more than the limit fails the chunk instead of allocating it
+        let mut wad = fs::read(&wad_path).unwrap();
+        wad[272 + 16..272 + 20].copy_from_slice(&u32::MAX.to_le_bytes());
+        fs::write(&wad_path, wad).unwrap();
+        let chunks: Vec<(u64, WadChunk)> =
+            WadReader::open(&wad_path).unwrap().chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+        let extraction = extract_chunks(&wad_path, &chunks, dir.path().join("out"), None, None, |_| {});
+        assert!(matches!(extraction, Ok(ChunkExtraction { extracted: 0, failed: 1, .. })));
+    }
+
+    #[test]
+    fn test_entries_sharing_data_are_written_to_each_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let wad_path = dir.path().join("Ahri.wad.client");
+        let shared = b"texture shared by two skins".repeat(100);
+        write_synthetic_wad(&wad_path, &[(1, shared.clone()), (2, vec![0; shared.len()]), (3, b"own data".to_vec())]);
+        // Point the second entry (hash 2) at the first one's data
+        let mut wad = fs::read(&wad_path).unwrap();
+        let first_entry = wad[272 + 8..272 + 32].to_vec();
+        wad[272 + 32 + 8..272 + 64].copy_from_slice(&first_entry);
+        fs::write(&wad_path, wad).unwrap();
+
+        let hash_dir = dir.path().join("hashes");
+        fs::create_dir_all(&hash_dir).unwrap();
+        let hash_list = "0000000000000001 assets/skins/base/ahri.bin\n0000000000000002 assets/skins/skin01/ahri.bin\n";
+        fs::write(hash_dir.join("hashes.game.txt"), hash_list).unwrap();
+        let hashtable = Hashtable::from_directory(&hash_dir).unwrap();
+
+        let reader = WadReader::open(&wad_path).unwrap();
+        let duplicates: Vec<bool> = reader.toc().iter().map(|entry| entry.is_duplicate).collect();
+        assert_eq!(duplicates, vec![true, true, false]);
+        let mut chunks: Vec<(u64, WadChunk)> = reader.chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+        chunks.sort_by_key(|(hash, _)| *hash);
+
+        let output = dir.path().join("out");
+        let reports = Mutex::new(Vec::new());
+        let extraction = extract_chunks(&wad_path, &chunks, &output, Some(&hashtable), None, |progress| {
+            reports.lock().push((progress.done, progress.total));
+        })
+        .unwrap();
+        assert_eq!((extraction.extracted, extraction.failed), (3, 0));
+        assert_eq!(extraction.unknown.len(), 1);
+        assert_eq!(reports.into_inner().last(), Some(&(3, 3)));
+        assert_eq!(fs::read(output.join("assets/skins/base/ahri.bin")).unwrap(), shared);
+        assert_eq!(fs::read(output.join("assets/skins/skin01/ahri.bin")).unwrap(), shared);
+
+        // A filtered selection only writes the entries picked
+        let filtered = dir.path().join("filtered");
+        let extraction = extract_chunks(&wad_path, &chunks[1..2], &filtered, Some(&hashtable), None, |_| {}).unwrap();
+        assert_eq!(extraction.extracted, 1);
+        assert_eq!(fs::read(filtered.join("assets/skins/skin01/ahri.bin")).unwrap(), shared);
+        assert!(!filtered.join("assets/skins/base").exists());
+    }
+
+    #[test]
+    fn test_cancelled_extraction_reports_partial_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let wad_path = dir.path().join("Synthetic.wad.client");
+        let chunks: Vec<(u64, Vec<u8>)> = (1..=256u64).map(|hash| (hash, vec![hash as u8; 4096])).collect();
+        write_synthetic_wad(&wad_path, &chunks);
+        let mut mounted: Vec<(u64, WadChunk)> =
+            WadReader::open(&wad_path).unwrap().chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+        mounted.sort_by_key(|(hash, _)| *hash);
+
+        // Cancel as soon as the first chunk is reported done
+        let token = CancellationToken::new();
+        let output = dir.path().join("out");
+        let reports = Mutex::new(Vec::new());
+        let extraction = extract_chunks(&wad_path, &mounted, &output, None, Some(&token), |progress| {
+            reports.lock().push(progress.clone());
+            token.cancel();
+        })
+        .unwrap();
+        assert!(extraction.cancelled);
+        assert!(extraction.extracted > 0 && extraction.extracted < mounted.len());
+        assert_eq!(extraction.failed, 0);
+        assert_eq!(extraction.bytes_written, extraction.extracted as u64 * 4096);
+        let files = walkdir::WalkDir::new(&output).into_iter().flatten().filter(|e| e.file_type().is_file()).count();
+        assert_eq!(files, extraction.extracted);
+        let first = &reports.lock()[0];
+        assert_eq!(first.total, mounted.len());
+        assert!(first.bytes_written >= 4096);
+        assert_eq!(first.current_path.len(), 16);
+
+        // A chunk streamed after the token tripped leaves no file behind
+        let mut file = File::open(&wad_path).unwrap();
+        let path = dir.path().join("partial.bin");
+        assert!(stream_chunk_to_file(&mut file, &mounted[0].1, &path, Some(&token), None).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_plan_matches_extraction() {
+        let dir = tempfile::tempdir().unwrap();
+        let wad_path = dir.path().join("Ahri.wad.client");
+        write_synthetic_wad(&wad_path, &[(1, b"PROP\x03\x00\x00\x00".to_vec()), (2, vec![7; 300]), (3, b"plain".to_vec())]);
+        let hash_dir = dir.path().join("hashes");
+        fs::create_dir_all(&hash_dir).unwrap();
+        fs::write(hash_dir.join("hashes.game.txt"), "0000000000000002 assets/characters/ahri/ahri.skn\n").unwrap();
+        let hashtable = Hashtable::from_directory(&hash_dir).unwrap();
+        let mut chunks: Vec<(u64, WadChunk)> =
+            WadReader::open(&wad_path).unwrap().chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+        chunks.sort_by_key(|(hash, _)| *hash);
+
+        let output = dir.path().join("out");
+        let plan = plan_extraction(&wad_path, &chunks, &output, Some(&hashtable)).unwrap();
+        assert!(!output.exists());
+        assert_eq!(plan.failed, 0);
+        assert_eq!(plan.total_size, 8 + 300 + 5);
+        assert_eq!(plan.files.iter().filter(|file| file.unknown).count(), 2);
+
+        let extraction = extract_chunks(&wad_path, &chunks, &output, Some(&hashtable), None, |_| {}).unwrap();
+        let planned: Vec<PathBuf> = plan.files.iter().map(|file| PathBuf::from(&file.path)).collect();
+        let mut written: Vec<PathBuf> = extraction.files.into_iter().map(|(_, path)| path).collect();
+        written.sort();
+        assert_eq!(planned, written);
+        assert!(planned.contains(&output.join("0000000000000001.ltk.bin")));
+    }
+
     #[test]
     fn test_resolve_chunk_path_hex_fallback() {
         let path = "1a2b3c4d5e6f7a8b";
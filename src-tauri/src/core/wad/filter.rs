@@ -0,0 +1,69 @@
+//! Selecting WAD chunks by resolved path
+//!
+//! Filters are glob patterns matched against the path a chunk's hash
+//! resolves to, ignoring case: `assets/characters/ahri/skins/skin11/**`,
+//! `*.dds`. Chunks whose hash doesn't resolve only match the special
+//! `unknown` filter.
+
+use crate::core::mapping::normalize_path;
+use crate::error::{Error, Result};
+
+/// Filter selecting the chunks whose path hash doesn't resolve
+pub const UNKNOWN_FILTER: &str = "unknown";
+
+/// Compiled `extract_wad` filters
+#[derive(Debug, Clone)]
+pub struct ChunkFilter {
+    patterns: Vec<glob::Pattern>,
+    unknown: bool,
+}
+
+impl ChunkFilter {
+    pub fn new(filters: &[String]) -> Result<Self> {
+        let mut patterns = Vec::new();
+        let mut unknown = false;
+        for filter in filters.iter().map(|f| f.trim()).filter(|f| !f.is_empty()) {
+            if filter.eq_ignore_ascii_case(UNKNOWN_FILTER) {
+                unknown = true;
+                continue;
+            }
+            let pattern = glob::Pattern::new(&normalize_path(filter))
+                .map_err(|e| Error::InvalidInput(format!("Invalid filter '{}': {}", filter, e)))?;
+            patterns.push(pattern);
+        }
+        if patterns.is_empty() && !unknown {
+            return Err(Error::InvalidInput("No filters given".to_string()));
+        }
+        Ok(Self { patterns, unknown })
+    }
+
+    /// Whether a chunk resolving to `path` (`None` when its hash is unknown)
+    /// is selected
+    pub fn matches(&self, path: Option<&str>) -> bool {
+        let options = glob::MatchOptions { case_sensitive: false, ..Default::default() };
+        match path {
+            Some(path) => self.patterns.iter().any(|p| p.matches_with(path, options)),
+            None => self.unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters_match_resolved_paths_and_unknown() {
+        let filter = ChunkFilter::new(&["ASSETS/Characters/Ahri/Skins/Skin11/**".to_string(), "*.dds".to_string()]).unwrap();
+        assert!(filter.matches(Some("assets/characters/ahri/skins/skin11/particles/ahri_skin11_glow.tex")));
+        assert!(filter.matches(Some("assets/characters/ahri/skins/base/ahri.dds")));
+        assert!(!filter.matches(Some("assets/characters/ahri/skins/skin12/ahri.tex")));
+        assert!(!filter.matches(None));
+
+        let with_unknown = ChunkFilter::new(&["unknown".to_string()]).unwrap();
+        assert!(with_unknown.matches(None));
+        assert!(!with_unknown.matches(Some("data/a.bin")));
+        assert!(ChunkFilter::new(&["[".to_string()]).is_err());
+        assert!(ChunkFilter::new(&[]).is_err());
+    }
+}
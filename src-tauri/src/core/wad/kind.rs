@@ -0,0 +1,114 @@
+//! WAD file name classification
+//!
+//! Installs contain more than `{Name}.wad.client`: mobile builds ship
+//! `.wad.mobile`, and voice-over lives in locale WADs such as
+//! `Ahri.en_US.wad.client`. Only plain client WADs hold the game data a mod
+//! replaces, so discovery, extraction and export tell them apart by name.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WadKind {
+    /// `{Name}.wad.client`: the game data mods target
+    Client,
+    /// `{Name}.wad.mobile`
+    Mobile,
+    /// `{Name}.{locale}.wad.client`, e.g. `Ahri.en_US.wad.client`
+    LocaleAudio { locale: String },
+    /// Legacy `.wad` and anything else
+    Other,
+}
+
+/// A WAD file name split into its base name and kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WadName {
+    /// Name without the locale and extensions, e.g. "Ahri"
+    pub base: String,
+    pub kind: WadKind,
+}
+
+impl WadName {
+    /// Classify a file name; `None` if it isn't a WAD at all
+    pub fn parse(file_name: &str) -> Option<Self> {
+        let lower = file_name.to_ascii_lowercase();
+        let (stem_len, mobile) = if lower.ends_with(".wad.client") {
+            (file_name.len() - ".wad.client".len(), false)
+        } else if lower.ends_with(".wad.mobile") {
+            (file_name.len() - ".wad.mobile".len(), true)
+        } else if lower.ends_with(".wad") {
+            (file_name.len() - ".wad".len(), false)
+        } else {
+            return None;
+        };
+        let stem = &file_name[..stem_len];
+
+        if let Some((base, locale)) = stem.rsplit_once('.').filter(|(_, l)| is_locale(l)) {
+            return Some(Self {
+                base: base.to_string(),
+                kind: WadKind::LocaleAudio { locale: locale.to_string() },
+            });
+        }
+
+        let kind = if mobile {
+            WadKind::Mobile
+        } else if stem_len + ".wad".len() == file_name.len() {
+            WadKind::Other
+        } else {
+            WadKind::Client
+        };
+        Some(Self { base: stem.to_string(), kind })
+    }
+}
+
+/// `xx_YY` locale codes as used in Riot's WAD names
+fn is_locale(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 5
+        && b[2] == b'_'
+        && b[..2].iter().all(u8::is_ascii_lowercase)
+        && b[3..].iter().all(u8::is_ascii_uppercase)
+}
+
+/// Classify the WAD at `path` by its file name
+pub fn wad_kind(path: &Path) -> Option<WadKind> {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(WadName::parse)
+        .map(|name| name.kind)
+}
+
+/// All client WADs under `{league_path}/Game/DATA/FINAL`
+pub fn list_client_wads(league_path: &Path) -> Vec<PathBuf> {
+    let root = league_path.join("Game").join("DATA").join("FINAL");
+    WalkDir::new(&root)
+        .max_depth(5)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| wad_kind(p) == Some(WadKind::Client))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wad_names() {
+        let parse = |name: &str| WadName::parse(name).map(|n| (n.base, n.kind));
+
+        assert_eq!(parse("Ahri.wad.client"), Some(("Ahri".to_string(), WadKind::Client)));
+        assert_eq!(parse("Ahri.wad.mobile"), Some(("Ahri".to_string(), WadKind::Mobile)));
+        assert_eq!(
+            parse("Ahri.en_US.wad.client"),
+            Some(("Ahri".to_string(), WadKind::LocaleAudio { locale: "en_US".to_string() }))
+        );
+        assert_eq!(parse("Map11.wad"), Some(("Map11".to_string(), WadKind::Other)));
+        assert_eq!(parse("ahri.WAD.CLIENT"), Some(("ahri".to_string(), WadKind::Client)));
+        assert_eq!(parse("skin0.bin"), None);
+    }
+}
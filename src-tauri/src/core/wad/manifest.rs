@@ -0,0 +1,232 @@
+//! What earlier extractions wrote into a project
+//!
+//! Every extraction into a project records each file it wrote in
+//! `.flint/extract-manifest.json`: the chunk's path hash, its fingerprint
+//! (the table of contents checksum, or the xxh3 of its content for WADs
+//! without checksums) and the file's size and modification time. An
+//! incremental extraction compares the chunks of a patched WAD with it and
+//! only writes the ones that are new or changed, so local edits to files the
+//! patch didn't touch survive.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// File name of the manifest in a project's `.flint` directory
+pub const MANIFEST_FILE: &str = "extract-manifest.json";
+
+/// A file written by an extraction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path hash of the chunk as 16 hex digits
+    pub hash: String,
+    /// Fingerprint of the chunk's content as 16 hex digits
+    pub checksum: String,
+    /// Size of the file when written
+    pub size: u64,
+    /// Modification time of the file when written, in milliseconds since the epoch
+    pub modified: u64,
+}
+
+/// Files extracted into a project, by path relative to the project root
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractManifest {
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+/// How a chunk compares with the previous extraction into the same folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkChange {
+    /// Not extracted before, or its file is gone
+    Added,
+    /// Extracted before with different content
+    Updated,
+    /// Same content as before; `edited` if the file changed on disk since
+    Unchanged { edited: bool },
+    /// Extracted before, but the chunk's fingerprint isn't known until its
+    /// data is read; `resolve` settles it once it is
+    Pending { previous: u64, edited: bool },
+}
+
+impl ChunkChange {
+    /// Settle a `Pending` change with the fingerprint the extraction
+    /// computed; `None` (the chunk wasn't extracted) leaves it pending
+    pub fn resolve(self, fingerprint: Option<u64>) -> Self {
+        match (self, fingerprint) {
+            (ChunkChange::Pending { previous, edited }, Some(fingerprint)) if previous == fingerprint => {
+                ChunkChange::Unchanged { edited }
+            }
+            (ChunkChange::Pending { .. }, Some(_)) => ChunkChange::Updated,
+            (change, _) => change,
+        }
+    }
+}
+
+impl ExtractManifest {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".flint").join(MANIFEST_FILE)
+    }
+
+    /// The project's manifest; empty if there is none yet or it can't be parsed
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = Self::path(project_root);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::io_with_path(e, &path)),
+        };
+        Ok(serde_json::from_str(&text).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable extraction manifest {}: {}", path.display(), e);
+            Self::default()
+        }))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+        }
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| Error::InvalidInput(format!("Failed to serialize extraction manifest: {}", e)))?;
+        fs::write(&path, json).map_err(|e| Error::io_with_path(e, &path))
+    }
+
+    /// Compare `chunks` (path hash and fingerprint, if known yet) about to be
+    /// extracted to `output_dir` with what was extracted there before
+    pub fn compare(&self, project_root: &Path, output_dir: &Path, chunks: &[(u64, Option<u64>)]) -> Vec<ChunkChange> {
+        let prefix = relative_key(project_root, output_dir).map(|dir| format!("{}/", dir)).unwrap_or_default();
+        let previous: HashMap<&str, (&str, &ManifestEntry)> = self
+            .files
+            .iter()
+            .filter(|(path, _)| path.starts_with(&prefix))
+            .map(|(path, entry)| (entry.hash.as_str(), (path.as_str(), entry)))
+            .collect();
+
+        chunks
+            .iter()
+            .map(|(hash, fingerprint)| {
+                let Some((path, entry)) = previous.get(format!("{:016x}", hash).as_str()) else {
+                    return ChunkChange::Added;
+                };
+                let Ok(previous) = u64::from_str_radix(&entry.checksum, 16) else {
+                    return ChunkChange::Updated;
+                };
+                if fingerprint.is_some_and(|fingerprint| fingerprint != previous) {
+                    return ChunkChange::Updated;
+                }
+                let Some(stamp) = file_stamp(&project_root.join(path)) else {
+                    return ChunkChange::Added;
+                };
+                let edited = stamp != (entry.size, entry.modified);
+                match fingerprint {
+                    Some(_) => ChunkChange::Unchanged { edited },
+                    None => ChunkChange::Pending { previous, edited },
+                }
+            })
+            .collect()
+    }
+
+    /// Record `file`, just written to `output_dir` for the chunk `hash`,
+    /// replacing any other file recorded for that chunk in the folder
+    pub fn record(&mut self, project_root: &Path, output_dir: &Path, file: &Path, hash: u64, fingerprint: u64) {
+        let (Some(key), Some((size, modified))) = (relative_key(project_root, file), file_stamp(file)) else {
+            return;
+        };
+        let prefix = relative_key(project_root, output_dir).map(|dir| format!("{}/", dir)).unwrap_or_default();
+        let hash = format!("{:016x}", hash);
+        self.files.retain(|path, entry| entry.hash != hash || !path.starts_with(&prefix) || path == &key);
+        self.files.insert(key, ManifestEntry { hash, checksum: format!("{:016x}", fingerprint), size, modified });
+    }
+}
+
+/// `path` relative to `root` with forward slashes
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    path.strip_prefix(root).ok().map(|relative| relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Size and modification time (ms since the epoch) of a file, `None` if it's missing
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok().filter(|m| m.is_file())?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_millis() as u64);
+    Some((metadata.len(), modified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_with_previous_extraction() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let wad_dir = root.join("content/base/Ahri.wad.client");
+        let write = |name: &str, data: &[u8]| {
+            let path = wad_dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, data).unwrap();
+            path
+        };
+
+        let mut manifest = ExtractManifest::default();
+        manifest.record(root, &wad_dir, &write("a.bin", b"a"), 1, 0xa);
+        manifest.record(root, &wad_dir, &write("b.bin", b"b"), 2, 0xb);
+        manifest.record(root, &wad_dir, &write("c.bin", b"c"), 3, 0xc);
+        manifest.record(root, &wad_dir, &write("d.bin", b"d"), 4, 0xd);
+        // Chunk 4 now resolves to a name; the old entry is replaced
+        manifest.record(root, &wad_dir, &write("data/d.bin", b"d"), 4, 0xd);
+        manifest.save(root).unwrap();
+        let manifest = ExtractManifest::load(root).unwrap();
+        assert_eq!(manifest.files.len(), 4);
+        assert!(manifest.files.contains_key("content/base/Ahri.wad.client/data/d.bin"));
+
+        write("c.bin", b"edited locally");
+        fs::remove_file(wad_dir.join("data/d.bin")).unwrap();
+        let chunks = [(1, Some(0xa)), (2, Some(0xbb)), (3, Some(0xc)), (4, Some(0xd)), (5, Some(0xe))];
+        let changes = manifest.compare(root, &wad_dir, &chunks);
+        assert_eq!(changes, vec![
+            ChunkChange::Unchanged { edited: false },
+            ChunkChange::Updated,
+            ChunkChange::Unchanged { edited: true },
+            ChunkChange::Added,
+            ChunkChange::Added,
+        ]);
+
+        // Another WAD folder has its own entries
+        let other = root.join("content/base/Annie.wad.client");
+        assert_eq!(manifest.compare(root, &other, &[(1, Some(0xa))]), vec![ChunkChange::Added]);
+    }
+
+    #[test]
+    fn test_pending_changes_resolve_after_extraction() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let wad_dir = root.join("content/base/Ahri.wad.client");
+        fs::create_dir_all(&wad_dir).unwrap();
+        for name in ["a.bin", "b.bin"] {
+            fs::write(wad_dir.join(name), name).unwrap();
+        }
+
+        let mut manifest = ExtractManifest::default();
+        manifest.record(root, &wad_dir, &wad_dir.join("a.bin"), 1, 0xa);
+        manifest.record(root, &wad_dir, &wad_dir.join("b.bin"), 2, 0xb);
+
+        // Without fingerprints only new chunks are settled up front
+        let changes = manifest.compare(root, &wad_dir, &[(1, None), (2, None), (3, None)]);
+        assert_eq!(changes, vec![
+            ChunkChange::Pending { previous: 0xa, edited: false },
+            ChunkChange::Pending { previous: 0xb, edited: false },
+            ChunkChange::Added,
+        ]);
+        assert_eq!(changes[0].resolve(Some(0xa)), ChunkChange::Unchanged { edited: false });
+        assert_eq!(changes[1].resolve(Some(0xbc)), ChunkChange::Updated);
+        assert_eq!(changes[1].resolve(None), changes[1]);
+        assert_eq!(changes[2].resolve(Some(0xc)), ChunkChange::Added);
+    }
+}
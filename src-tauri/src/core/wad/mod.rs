@@ -1,3 +1,20 @@
 // WAD module exports
 pub mod reader;
+pub mod chunk_kind;
 pub mod extractor;
+pub mod diff;
+pub mod filter;
+pub mod kind;
+pub mod manifest;
+pub mod mount;
+pub mod search;
+pub mod skin;
+pub mod subchunk;
+pub mod verify;
+pub mod writer;
+
+pub use chunk_kind::ChunkKind;
+pub use filter::ChunkFilter;
+pub use kind::{list_client_wads, WadKind, WadName};
+pub use mount::{WadMountRegistry, WadMountSet};
+pub use search::{locate_game_path, GamePathLocation, WadTocCache};
@@ -0,0 +1,185 @@
+//! Several WADs mounted as one filesystem
+//!
+//! A champion's assets are split between `Ahri.wad.client` and its locale
+//! WADs. A `WadMountSet` loads the tables of contents of a list of WADs in
+//! priority order and answers lookups from the first WAD holding an entry,
+//! the way the game resolves a path. Chunk data is only read on request.
+
+use crate::core::wad::extractor::find_champion_wad;
+use crate::core::wad::reader::WadReader;
+use crate::core::wad::{WadKind, WadName};
+use crate::error::{Error, Result};
+use league_toolkit::wad::WadChunk;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+struct MountedWad {
+    path: PathBuf,
+    chunks: HashMap<u64, WadChunk>,
+}
+
+/// WADs mounted in priority order, highest first
+pub struct WadMountSet {
+    wads: Vec<MountedWad>,
+}
+
+impl WadMountSet {
+    /// Load the tables of contents of `paths`; earlier WADs win over later ones
+    pub fn mount(paths: &[PathBuf]) -> Result<Self> {
+        let wads = paths
+            .iter()
+            .map(|path| {
+                let reader = WadReader::open(path)?;
+                Ok(MountedWad { path: path.clone(), chunks: reader.chunks().clone() })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { wads })
+    }
+
+    /// Mounted WADs in priority order
+    pub fn wads(&self) -> impl Iterator<Item = &Path> {
+        self.wads.iter().map(|wad| wad.path.as_path())
+    }
+
+    /// Distinct path hashes across all WADs
+    pub fn chunk_count(&self) -> usize {
+        let mut hashes: Vec<u64> = self.wads.iter().flat_map(|wad| wad.chunks.keys().copied()).collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        hashes.len()
+    }
+
+    /// The WAD that provides `path_hash` and its chunk there
+    pub fn find_hash(&self, path_hash: u64) -> Option<(&Path, &WadChunk)> {
+        self.wads
+            .iter()
+            .find_map(|wad| wad.chunks.get(&path_hash).map(|chunk| (wad.path.as_path(), chunk)))
+    }
+
+    /// The WAD that provides the game path `path` and its chunk there
+    #[cfg(test)]
+    pub fn find_path(&self, path: &str) -> Option<(&Path, &WadChunk)> {
+        self.find_hash(crate::core::validation::engine::compute_path_hash(path))
+    }
+
+    #[cfg(test)]
+    pub fn contains_path(&self, path: &str) -> bool {
+        self.find_path(path).is_some()
+    }
+
+    /// Decompressed data of `path_hash` from the WAD that provides it
+    pub fn read_chunk(&self, path_hash: u64) -> Result<Vec<u8>> {
+        let (wad_path, chunk) = self
+            .find_hash(path_hash)
+            .ok_or_else(|| Error::NotFound(format!("Chunk {:016x} is in none of the mounted WADs", path_hash)))?;
+        let chunk = *chunk;
+        WadReader::open(wad_path)?.load_chunk(&chunk)
+    }
+}
+
+/// A champion's client WAD followed by its locale WADs, `locale` only if
+/// given, otherwise all of them sorted by name
+pub fn champion_wad_paths(league_path: &Path, champion: &str, locale: Option<&str>) -> Result<Vec<PathBuf>> {
+    let client = find_champion_wad(league_path, champion)
+        .ok_or_else(|| Error::NotFound(format!("No WAD found for champion '{}'", champion)))?;
+    let base = WadName::parse(&client.file_name().unwrap_or_default().to_string_lossy())
+        .map(|name| name.base)
+        .unwrap_or_else(|| champion.to_string());
+
+    let mut locales: Vec<PathBuf> = client
+        .parent()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).and_then(WadName::parse);
+            name.is_some_and(|wad| {
+                wad.base.eq_ignore_ascii_case(&base)
+                    && matches!(&wad.kind, WadKind::LocaleAudio { locale: l } if locale.is_none_or(|want| l == want))
+            })
+        })
+        .collect();
+    locales.sort();
+
+    let mut paths = vec![client];
+    paths.extend(locales);
+    Ok(paths)
+}
+
+/// Mount sets the frontend holds handles to
+#[derive(Clone, Default)]
+pub struct WadMountRegistry {
+    mounts: Arc<Mutex<HashMap<String, Arc<WadMountSet>>>>,
+}
+
+impl WadMountRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `mounts` and return its handle
+    pub fn insert(&self, mounts: WadMountSet) -> String {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        self.mounts.lock().insert(id.clone(), Arc::new(mounts));
+        id
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<WadMountSet>> {
+        self.mounts.lock().get(id).cloned()
+    }
+
+    /// Drop the mount set behind `id`; false if there was none
+    pub fn remove(&self, id: &str) -> bool {
+        self.mounts.lock().remove(id).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::validation::engine::compute_path_hash;
+    use crate::core::wad::writer::build_wad;
+
+    #[test]
+    fn test_lookups_prefer_the_first_wad() {
+        let temp = tempfile::tempdir().unwrap();
+        let champions = temp.path().join("Game/DATA/FINAL/Champions");
+        let write = |wad: &str, files: &[(&str, &[u8])]| {
+            let dir = temp.path().join("content").join(wad);
+            for (file, data) in files {
+                fs::create_dir_all(dir.join(file).parent().unwrap()).unwrap();
+                fs::write(dir.join(file), data).unwrap();
+            }
+            build_wad(&dir, &champions.join(wad)).unwrap();
+        };
+        write("Ahri.wad.client", &[("data/ahri.bin", b"client"), ("assets/shared.dds", b"client texture")]);
+        write("Ahri.en_US.wad.client", &[("assets/shared.dds", b"locale texture"), ("assets/vo.bnk", b"voice")]);
+        write("Ahri.ko_KR.wad.client", &[("assets/vo.bnk", b"korean voice")]);
+        write("Annie.wad.client", &[("data/annie.bin", b"annie")]);
+
+        let paths = champion_wad_paths(temp.path(), "Ahri", None).unwrap();
+        let names: Vec<_> = paths.iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert_eq!(names, ["Ahri.wad.client", "Ahri.en_US.wad.client", "Ahri.ko_KR.wad.client"]);
+        assert_eq!(champion_wad_paths(temp.path(), "ahri", Some("ko_KR")).unwrap().len(), 2);
+
+        let mounts = WadMountSet::mount(&paths).unwrap();
+        assert_eq!(mounts.chunk_count(), 3);
+        assert!(mounts.contains_path("DATA/Ahri.bin"));
+        assert!(!mounts.contains_path("data/annie.bin"));
+        let (wad, _) = mounts.find_path("assets/vo.bnk").unwrap();
+        assert!(wad.ends_with("Ahri.en_US.wad.client"));
+        assert_eq!(mounts.read_chunk(compute_path_hash("assets/shared.dds")).unwrap(), b"client texture");
+        assert!(mounts.read_chunk(1).is_err());
+
+        let registry = WadMountRegistry::new();
+        let id = registry.insert(mounts);
+        assert!(registry.get(&id).is_some());
+        assert!(registry.remove(&id));
+        assert!(registry.get(&id).is_none());
+    }
+}
@@ -1,12 +1,305 @@
+use crate::core::league::process::{check_game_running, is_lock_error};
+use crate::core::wad::subchunk::{subchunk_ranges, subchunk_toc_hashes, SubchunkToc};
 use crate::error::{Error, Result};
 use league_toolkit::wad::{Wad, WadChunk};
 use std::collections::HashMap;
 use std::fs::File;
-use std::path::Path;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Chunk compression types as stored in the table of contents
+pub const COMPRESSION_NONE: u8 = 0;
+pub const COMPRESSION_GZIP: u8 = 1;
+pub const COMPRESSION_SATELLITE: u8 = 2;
+pub const COMPRESSION_ZSTD: u8 = 3;
+/// zstd split into subchunks, some of them stored uncompressed
+pub const COMPRESSION_ZSTD_MULTI: u8 = 4;
+
+/// Display name of a compression type
+pub fn compression_name(compression: u8) -> &'static str {
+    match compression {
+        COMPRESSION_NONE => "none",
+        COMPRESSION_GZIP => "gzip",
+        COMPRESSION_SATELLITE => "satellite",
+        COMPRESSION_ZSTD => "zstd",
+        COMPRESSION_ZSTD_MULTI => "zstd_multi",
+        _ => "unknown",
+    }
+}
+
+/// The table of contents fields of a chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TocEntry {
+    pub path_hash: u64,
+    pub data_offset: usize,
+    pub compressed_size: usize,
+    pub uncompressed_size: usize,
+    pub compression: u8,
+    /// 0 in WADs older than v3
+    pub checksum: u64,
+    /// Another chunk points at the same data
+    pub is_duplicate: bool,
+}
+
+impl TocEntry {
+    pub fn new(chunk: &WadChunk) -> Self {
+        Self {
+            path_hash: chunk.path_hash(),
+            data_offset: chunk.data_offset(),
+            compressed_size: chunk.compressed_size(),
+            uncompressed_size: chunk.uncompressed_size(),
+            compression: chunk.compression_type() as u8,
+            checksum: chunk.checksum(),
+            is_duplicate: false,
+        }
+    }
+
+    /// Split into subchunks, which only decode whole
+    pub fn is_subchunked(&self) -> bool {
+        self.compression == COMPRESSION_ZSTD_MULTI
+    }
+}
+
+/// Sort `entries` by hash and flag the ones sharing a data offset
+fn mark_duplicates(entries: &mut [TocEntry]) {
+    let mut by_offset: HashMap<usize, usize> = HashMap::new();
+    for entry in entries.iter() {
+        *by_offset.entry(entry.data_offset).or_default() += 1;
+    }
+    for entry in entries.iter_mut() {
+        entry.is_duplicate = by_offset[&entry.data_offset] > 1;
+    }
+    entries.sort_unstable_by_key(|entry| entry.path_hash);
+}
+
+/// Bytes of a v3 header: magic, version, ECDSA signature, checksum, chunk count
+const V3_HEADER_SIZE: usize = 2 + 2 + 256 + 8 + 4;
+
+/// Bytes of a v3 table of contents entry
+const V3_TOC_ENTRY_SIZE: usize = 32;
+
+/// Bytes of a v1 table of contents entry; v2 entries may add a checksum
+const LEGACY_TOC_ENTRY_SIZE: usize = 24;
+
+/// Further attempts at opening a WAD another process holds, waiting
+/// `LOCK_RETRY_DELAY` longer each time
+const LOCK_RETRIES: u32 = 3;
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// A WAD file presented in the v3 layout
+///
+/// v3 archives pass through unchanged. v1 and v2 archives keep their table of
+/// contents at an offset given in the header (after an 83 byte signature
+/// block in v2) and v1 entries have no checksum; for those a v3.1 header and
+/// table of contents is synthesized in front of the original file, with the
+/// data offsets shifted past it, so league-toolkit and the raw chunk readers
+/// only ever see one layout.
+///
+/// The header and table of contents are read with one positioned read when
+/// the source is opened and served from memory; chunk data is read from the
+/// file on demand, so opening a multi-gigabyte map WAD only touches its first
+/// few hundred kilobytes.
+pub struct WadSource {
+    file: File,
+    /// Header and table of contents: cached from the file for v3, synthesized
+    /// for v1 and v2
+    prefix: Vec<u8>,
+    /// Position of the file's first byte in the presented WAD: the length of
+    /// a synthesized prefix, 0 for v3
+    shift: u64,
+    pos: u64,
+    version: (u8, u8),
+}
+
+impl WadSource {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = open_shared(path)?;
+        let mut magic = [0u8; 4];
+        file.read_exact(&mut magic)
+            .map_err(|e| Error::wad_with_path(format!("Not a WAD file: {}", e), path))?;
+        if &magic[..2] != b"RW" {
+            return Err(Error::wad_with_path("Not a WAD file: the header doesn't start with RW", path));
+        }
+
+        let (major, minor) = (magic[2], magic[3]);
+        let toc_err =
+            |e| Error::wad_with_path(format!("Failed to read the v{}.{} table of contents: {}", major, minor, e), path);
+        let (prefix, shift) = match major {
+            3 => (v3_toc(&mut file).map_err(toc_err)?, 0),
+            1 | 2 => {
+                let prefix = legacy_toc(&mut file, major).map_err(toc_err)?;
+                let shift = prefix.len() as u64;
+                (prefix, shift)
+            }
+            _ => return Err(Error::UnsupportedWadVersion { major, minor, path: Some(path.to_path_buf()) }),
+        };
+        file.seek(SeekFrom::Start(0)).map_err(|e| Error::io_with_path(e, path))?;
+        Ok(Self { file, prefix, shift, pos: 0, version: (major, minor) })
+    }
+
+    /// `(major, minor)` from the file's own header
+    pub fn version(&self) -> (u8, u8) {
+        self.version
+    }
+
+    /// Size of the WAD as presented, synthesized table of contents included
+    pub fn size(&self) -> io::Result<u64> {
+        Ok(self.shift + self.file.metadata()?.len())
+    }
+}
+
+impl Read for WadSource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let prefix_len = self.prefix.len() as u64;
+        if self.pos < prefix_len {
+            let start = self.pos as usize;
+            let read = buf.len().min(self.prefix.len() - start);
+            buf[..read].copy_from_slice(&self.prefix[start..start + read]);
+            self.pos += read as u64;
+            if self.pos == prefix_len {
+                self.file.seek(SeekFrom::Start(prefix_len - self.shift))?;
+            }
+            return Ok(read);
+        }
+        let read = self.file.read(buf)?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for WadSource {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+            SeekFrom::End(delta) => self.size()?.checked_add_signed(delta),
+        }
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Seek before the start of the WAD"))?;
+        let prefix_len = self.prefix.len() as u64;
+        if target >= prefix_len {
+            self.file.seek(SeekFrom::Start(target - self.shift))?;
+        }
+        self.pos = target;
+        Ok(target)
+    }
+}
+
+/// Open `path` for reading without locking it: the game keeps its WADs open
+/// while it runs, and patchers replace them. A file held without sharing is
+/// retried a few times in case a patcher is just swapping it; if it stays
+/// held, or access is denied while League or the Riot Client runs, that is
+/// `Error::GameFilesLocked`.
+fn open_shared(path: &Path) -> Result<File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE
+        options.share_mode(0x1 | 0x2 | 0x4);
+    }
+
+    let mut attempt = 0;
+    loop {
+        let error = match options.open(path) {
+            Ok(file) => return Ok(file),
+            Err(e) if is_lock_error(&e) => e,
+            Err(e) => return Err(Error::io_with_path(e, path)),
+        };
+        if attempt < LOCK_RETRIES {
+            attempt += 1;
+            std::thread::sleep(LOCK_RETRY_DELAY * attempt);
+            continue;
+        }
+        let processes = check_game_running();
+        // Access denied with nothing of Riot's running is a plain permission problem
+        if error.kind() == io::ErrorKind::PermissionDenied && processes.is_empty() {
+            return Err(Error::io_with_path(error, path));
+        }
+        tracing::warn!("{} is locked ({}), running: {:?}", path.display(), error, processes);
+        return Err(Error::GameFilesLocked { path: path.to_path_buf(), processes });
+    }
+}
+
+/// The header and table of contents of the v3 WAD in `file`, as far as the
+/// file holds them; a short file is left for the parser to reject
+fn v3_toc(file: &mut File) -> io::Result<Vec<u8>> {
+    let mut prefix = Vec::with_capacity(V3_HEADER_SIZE);
+    file.seek(SeekFrom::Start(0))?;
+    Read::by_ref(file).take(V3_HEADER_SIZE as u64).read_to_end(&mut prefix)?;
+    if prefix.len() < V3_HEADER_SIZE {
+        return Ok(prefix);
+    }
+    let count = u32::from_le_bytes([prefix[268], prefix[269], prefix[270], prefix[271]]) as u64;
+    let toc_size = (V3_TOC_ENTRY_SIZE as u64 * count).min(file.metadata()?.len().saturating_sub(V3_HEADER_SIZE as u64));
+    prefix.reserve(toc_size as usize);
+    Read::by_ref(file).take(toc_size).read_to_end(&mut prefix)?;
+    Ok(prefix)
+}
+
+/// A v3.1 header and table of contents for the v1/v2 WAD in `file`, read
+/// from just after its version bytes
+fn legacy_toc(file: &mut File, major: u8) -> io::Result<Vec<u8>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    if major == 2 {
+        // ECDSA signature length, signature block, data checksum
+        file.seek(SeekFrom::Current(1 + 83 + 8))?;
+    }
+    let mut fields = [0u8; 8];
+    file.read_exact(&mut fields)?;
+    let toc_offset = u16::from_le_bytes([fields[0], fields[1]]) as u64;
+    let entry_size = u16::from_le_bytes([fields[2], fields[3]]) as usize;
+    let count = u32::from_le_bytes([fields[4], fields[5], fields[6], fields[7]]) as usize;
+    if entry_size < LEGACY_TOC_ENTRY_SIZE {
+        return Err(invalid(format!("{} byte entries are too small", entry_size)));
+    }
+    let toc_end = toc_offset + (entry_size * count) as u64;
+    if toc_end > file.metadata()?.len() {
+        return Err(invalid(format!("{} entries run past the end of the file", count)));
+    }
+
+    let mut toc = vec![0u8; entry_size * count];
+    file.seek(SeekFrom::Start(toc_offset))?;
+    file.read_exact(&mut toc)?;
+
+    let shift = (V3_HEADER_SIZE + V3_TOC_ENTRY_SIZE * count) as u64;
+    let mut prefix = Vec::with_capacity(shift as usize);
+    prefix.extend_from_slice(b"RW");
+    prefix.extend_from_slice(&[3, 1]);
+    prefix.extend_from_slice(&[0; 256]);
+    prefix.extend_from_slice(&0u64.to_le_bytes());
+    prefix.extend_from_slice(&(count as u32).to_le_bytes());
+    for entry in toc.chunks_exact(entry_size) {
+        let offset = u32::from_le_bytes([entry[8], entry[9], entry[10], entry[11]]) as u64 + shift;
+        let offset = u32::try_from(offset).map_err(|_| invalid("Too large to read".to_string()))?;
+        prefix.extend_from_slice(&entry[..8]);
+        prefix.extend_from_slice(&offset.to_le_bytes());
+        // Sizes, compression, duplicate flag and padding line up with v3
+        prefix.extend_from_slice(&entry[12..LEGACY_TOC_ENTRY_SIZE]);
+        match entry.get(LEGACY_TOC_ENTRY_SIZE..LEGACY_TOC_ENTRY_SIZE + 8) {
+            Some(checksum) => prefix.extend_from_slice(checksum),
+            None => prefix.extend_from_slice(&0u64.to_le_bytes()),
+        }
+    }
+    Ok(prefix)
+}
 
 /// A reader for WAD archive files that provides access to chunk metadata
+///
+/// Reads v1, v2 and v3 archives; other versions fail with
+/// `Error::UnsupportedWadVersion`.
 pub struct WadReader {
-    wad: Wad<File>,
+    wad: Wad<WadSource>,
+    version: (u8, u8),
+    path: PathBuf,
+    /// Subchunks of each subchunked chunk, by path hash
+    subchunks: HashMap<u64, Range<usize>>,
+    /// The subchunk table and a handle for reading subchunked chunks' stored
+    /// bytes, loaded when the first one is decoded
+    subchunked: Option<(SubchunkToc, WadSource)>,
 }
 
 impl WadReader {
@@ -24,21 +317,31 @@ impl WadReader {
         let path = path.as_ref();
         tracing::debug!("Opening WAD file: {}", path.display());
         
-        let file = File::open(path)
-            .map_err(|e| {
-                tracing::error!("Failed to open WAD file '{}': {}", path.display(), e);
-                Error::io_with_path(e, path)
-            })?;
+        let source = WadSource::open(path)
+            .inspect_err(|e| tracing::error!("Failed to open WAD file '{}': {}", path.display(), e))?;
+        let version = source.version();
+        let subchunks = subchunk_ranges(&source.prefix, V3_HEADER_SIZE, V3_TOC_ENTRY_SIZE);
         
-        let wad = Wad::mount(file)
+        let wad = Wad::mount(source)
             .map_err(|e| {
                 tracing::error!("Failed to mount WAD file '{}': {}", path.display(), e);
                 Error::wad_with_path(format!("Failed to mount WAD file: {}", e), path)
             })?;
         
-        tracing::info!("Successfully opened WAD file '{}' with {} chunks", path.display(), wad.chunks().len());
+        tracing::info!(
+            "Successfully opened v{}.{} WAD file '{}' with {} chunks",
+            version.0,
+            version.1,
+            path.display(),
+            wad.chunks().len()
+        );
         
-        Ok(Self { wad })
+        Ok(Self { wad, version, path: path.to_path_buf(), subchunks, subchunked: None })
+    }
+
+    /// `(major, minor)` version from the WAD's header
+    pub fn version(&self) -> (u8, u8) {
+        self.version
     }
 
     /// Returns a reference to all chunks in the WAD archive as a HashMap
@@ -66,6 +369,58 @@ impl WadReader {
         self.wad.chunks().get(&path_hash)
     }
 
+    /// The table of contents, sorted by path hash
+    pub fn toc(&self) -> Vec<TocEntry> {
+        let mut entries: Vec<TocEntry> = self.wad.chunks().values().map(TocEntry::new).collect();
+        mark_duplicates(&mut entries);
+        entries
+    }
+
+    /// Decompressed data of a chunk of this WAD
+    ///
+    /// Subchunked chunks are decoded piece by piece through the WAD's
+    /// subchunk table; everything else goes through the toolkit's decoder.
+    pub fn load_chunk(&mut self, chunk: &WadChunk) -> Result<Vec<u8>> {
+        let path_hash = chunk.path_hash();
+        let Some(range) = self.subchunks.get(&path_hash).cloned() else {
+            let decoded = {
+                let (mut decoder, _) = self.wad.decode();
+                decoder.load_chunk_decompressed(chunk).map(Vec::from).map_err(|e| e.to_string())
+            };
+            return decoded.map_err(|e| self.decompress_error(path_hash, e));
+        };
+
+        let loaded = match self.subchunked.take() {
+            Some(loaded) => loaded,
+            None => (self.read_subchunk_toc()?, WadSource::open(&self.path)?),
+        };
+        let (toc, raw) = self.subchunked.insert(loaded);
+        let mut stored = Vec::with_capacity(chunk.compressed_size());
+        let decoded = raw
+            .seek(SeekFrom::Start(chunk.data_offset() as u64))
+            .and_then(|_| Read::by_ref(raw).take(chunk.compressed_size() as u64).read_to_end(&mut stored))
+            .and_then(|_| toc.decode(&stored, range, chunk.uncompressed_size()));
+        decoded.map_err(|e| self.decompress_error(path_hash, e))
+    }
+
+    fn decompress_error(&self, path_hash: u64, e: impl std::fmt::Display) -> Error {
+        Error::wad_with_path(format!("Failed to decompress chunk {:016x}: {}", path_hash, e), &self.path)
+    }
+
+    /// The WAD's subchunk table, looked up by the path it has for this WAD
+    fn read_subchunk_toc(&mut self) -> Result<SubchunkToc> {
+        let chunk = subchunk_toc_hashes(&self.path)
+            .into_iter()
+            .find_map(|hash| self.get_chunk(hash).copied())
+            .ok_or_else(|| Error::wad_with_path("The WAD has subchunked chunks but no subchunk table", &self.path))?;
+        let (mut decoder, _) = self.wad.decode();
+        let data = decoder.load_chunk_decompressed(&chunk).map_err(|e| {
+            Error::wad_with_path(format!("Failed to decompress the subchunk table: {}", e), &self.path)
+        })?;
+        SubchunkToc::parse(&data)
+            .map_err(|e| Error::wad_with_path(format!("Invalid subchunk table: {}", e), &self.path))
+    }
+
     /// Returns the total number of chunks in the WAD
     pub fn chunk_count(&self) -> usize {
         self.wad.chunks().len()
@@ -76,18 +431,122 @@ impl WadReader {
     /// This is useful when you need to extract chunks, as the decoder requires
     /// mutable access to the Wad.
     #[allow(dead_code)] // Kept for API completeness
-    pub fn into_wad(self) -> Wad<File> {
+    pub fn into_wad(self) -> Wad<WadSource> {
         self.wad
     }
 
     /// Gets a reference to the underlying Wad
     #[allow(dead_code)] // Kept for API completeness
-    pub fn wad(&self) -> &Wad<File> {
+    pub fn wad(&self) -> &Wad<WadSource> {
         &self.wad
     }
 
     /// Gets a mutable reference to the underlying Wad
-    pub fn wad_mut(&mut self) -> &mut Wad<File> {
+    #[cfg(test)]
+    pub fn wad_mut(&mut self) -> &mut Wad<WadSource> {
         &mut self.wad
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wad::writer::build_wad;
+    use std::fs;
+
+    /// `v3`, as `build_wad` writes it, laid out as a v1 or v2 archive
+    fn legacy_wad(v3: &[u8], major: u8) -> Vec<u8> {
+        let count = u32::from_le_bytes(v3[268..272].try_into().unwrap()) as usize;
+        let entry_size = if major == 1 { LEGACY_TOC_ENTRY_SIZE } else { V3_TOC_ENTRY_SIZE };
+        let mut wad = vec![b'R', b'W', major, 1];
+        if major == 2 {
+            wad.push(83);
+            wad.extend_from_slice(&[0; 83 + 8]);
+        }
+        let toc_offset = wad.len() + 8;
+        wad.extend_from_slice(&(toc_offset as u16).to_le_bytes());
+        wad.extend_from_slice(&(entry_size as u16).to_le_bytes());
+        wad.extend_from_slice(&(count as u32).to_le_bytes());
+
+        let v3_data_start = V3_HEADER_SIZE + V3_TOC_ENTRY_SIZE * count;
+        let data_start = toc_offset + entry_size * count;
+        for entry in v3[V3_HEADER_SIZE..v3_data_start].chunks_exact(V3_TOC_ENTRY_SIZE) {
+            let offset = u32::from_le_bytes(entry[8..12].try_into().unwrap()) as usize - v3_data_start + data_start;
+            wad.extend_from_slice(&entry[..8]);
+            wad.extend_from_slice(&(offset as u32).to_le_bytes());
+            wad.extend_from_slice(&entry[12..entry_size]);
+        }
+        wad.extend_from_slice(&v3[v3_data_start..]);
+        wad
+    }
+
+    #[test]
+    fn test_legacy_versions_read_like_v3() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("files");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("0000000000000001.bin"), b"PROP bin data, repeated, repeated").unwrap();
+        fs::write(dir.join("0000000000000002.dds"), b"").unwrap();
+        let v3_path = temp.path().join("v3.wad.client");
+        build_wad(&dir, &v3_path).unwrap();
+        let v3 = fs::read(&v3_path).unwrap();
+        let v3_offset = WadReader::open(&v3_path).unwrap().get_chunk(1).unwrap().data_offset();
+
+        for (major, minor) in [(1, 1), (2, 1), (3, 1)] {
+            let path = temp.path().join(format!("v{}.wad.client", major));
+            if major < 3 {
+                fs::write(&path, legacy_wad(&v3, major)).unwrap();
+            }
+            let mut reader = WadReader::open(&path).unwrap();
+            assert_eq!(reader.version(), (major, minor));
+            let toc = reader.toc();
+            assert_eq!(toc.iter().map(|e| e.path_hash).collect::<Vec<_>>(), vec![1, 2]);
+            assert_eq!(toc[0].checksum == 0, major == 1);
+
+            for (hash, data) in [(1, &b"PROP bin data, repeated, repeated"[..]), (2, b"")] {
+                let chunk = *reader.get_chunk(hash).unwrap();
+                let (mut decoder, _) = reader.wad_mut().decode();
+                assert_eq!(decoder.load_chunk_decompressed(&chunk).unwrap().to_vec(), data);
+            }
+
+            // Raw reads at the table's offsets find the stored bytes
+            let chunk = *reader.get_chunk(1).unwrap();
+            let mut source = WadSource::open(&path).unwrap();
+            // The header and table of contents are all that's read up front
+            assert_eq!(source.prefix.len(), V3_HEADER_SIZE + 2 * V3_TOC_ENTRY_SIZE);
+            let mut stored = vec![0; chunk.compressed_size()];
+            source.seek(SeekFrom::Start(chunk.data_offset() as u64)).unwrap();
+            source.read_exact(&mut stored).unwrap();
+            assert_eq!(stored, v3[v3_offset..v3_offset + stored.len()]);
+        }
+
+        let future = temp.path().join("v4.wad.client");
+        fs::write(&future, [b'R', b'W', 4, 0]).unwrap();
+        match WadReader::open(&future) {
+            Err(Error::UnsupportedWadVersion { major: 4, minor: 0, .. }) => {}
+            other => panic!("expected an unsupported version error, got {:?}", other.err()),
+        }
+        fs::write(&future, b"PK\x03\x04").unwrap();
+        assert!(matches!(WadReader::open(&future), Err(Error::Wad { .. })));
+    }
+
+    #[test]
+    fn test_chunks_sharing_data_are_duplicates() {
+        let entry = |path_hash, data_offset| TocEntry {
+            path_hash,
+            data_offset,
+            compressed_size: 10,
+            uncompressed_size: 20,
+            compression: COMPRESSION_ZSTD,
+            checksum: 0,
+            is_duplicate: false,
+        };
+        let mut entries = vec![entry(3, 100), entry(1, 272), entry(2, 100)];
+        mark_duplicates(&mut entries);
+
+        let flags: Vec<(u64, bool)> = entries.iter().map(|e| (e.path_hash, e.is_duplicate)).collect();
+        assert_eq!(flags, vec![(1, false), (2, true), (3, true)]);
+        assert_eq!(compression_name(COMPRESSION_ZSTD_MULTI), "zstd_multi");
+        assert!(TocEntry { compression: COMPRESSION_ZSTD_MULTI, ..entries[0] }.is_subchunked());
+    }
+}
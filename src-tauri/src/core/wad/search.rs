@@ -0,0 +1,396 @@
+//! Finding which WADs of an installation hold a chunk
+//!
+//! Only tables of contents are read, never chunk data. Each WAD's table is
+//! cached with the file's size and modification time, so repeated searches
+//! only re-read WADs a patch replaced. `locate_game_path` answers the narrower
+//! question of which single WAD a path comes from.
+
+use crate::core::hash::hashtable::Hashtable;
+use crate::core::mapping::normalize_path;
+use crate::core::parallel;
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::mount::champion_wad_paths;
+use crate::core::wad::reader::{compression_name, WadReader};
+use crate::core::wad::{WadKind, WadName};
+use crate::error::{Error, Result};
+use league_toolkit::wad::WadChunk;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// What `find_in_wads` looks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WadQuery {
+    /// Path hash to match exactly
+    hash: u64,
+    /// Text resolved paths must contain; `None` for hash queries
+    substring: Option<String>,
+}
+
+impl WadQuery {
+    /// A 16-digit hex hash (optionally `0x`-prefixed) matches that hash only.
+    /// Anything else matches the hash of it as a full path, and resolved
+    /// paths containing it.
+    pub fn parse(query: &str) -> Result<Self> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(Error::InvalidInput("Search query is empty".to_string()));
+        }
+        let hex = query.strip_prefix("0x").unwrap_or(query);
+        if hex.len() == 16 {
+            if let Ok(hash) = u64::from_str_radix(hex, 16) {
+                return Ok(Self { hash, substring: None });
+            }
+        }
+        Ok(Self { hash: compute_path_hash(query), substring: Some(normalize_path(query)) })
+    }
+
+    pub fn matches(&self, hash: u64, path: Option<&str>) -> bool {
+        hash == self.hash
+            || self
+                .substring
+                .as_deref()
+                .is_some_and(|substring| path.is_some_and(|p| p.to_lowercase().contains(substring)))
+    }
+}
+
+/// A chunk matching the query in one WAD
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WadSearchHit {
+    pub wad_path: String,
+    pub wad_name: String,
+    /// Path hash as a 16-char lowercase hex string
+    pub hash: String,
+    pub path: Option<String>,
+    /// Decompressed size in bytes
+    pub size: usize,
+    pub compressed_size: usize,
+    pub compression: String,
+}
+
+/// What a search went through
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WadScanStats {
+    pub wads_scanned: usize,
+    /// WADs whose table came from the cache
+    pub cached: usize,
+    /// WADs that couldn't be read, with the reason
+    pub failed: Vec<String>,
+}
+
+/// The WAD a game path was found in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GamePathLocation {
+    pub wad_path: String,
+    pub wad_name: String,
+    /// Path hash as a 16-char lowercase hex string
+    pub hash: String,
+    /// Decompressed size in bytes
+    pub size: usize,
+    pub compressed_size: usize,
+    pub compression: String,
+}
+
+struct CachedToc {
+    modified: Option<SystemTime>,
+    len: u64,
+    /// Sorted by hash
+    chunks: Vec<(u64, WadChunk)>,
+}
+
+impl CachedToc {
+    fn get(&self, hash: u64) -> Option<&WadChunk> {
+        let index = self.chunks.binary_search_by_key(&hash, |(hash, _)| *hash).ok()?;
+        Some(&self.chunks[index].1)
+    }
+}
+
+/// Tables of contents of the WADs searched so far
+#[derive(Clone, Default)]
+pub struct WadTocCache {
+    tocs: Arc<Mutex<HashMap<PathBuf, Arc<CachedToc>>>>,
+}
+
+impl WadTocCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The table of `path`, read again if the file changed since it was cached.
+    /// The flag tells whether it came from the cache.
+    fn toc(&self, path: &Path) -> Result<(Arc<CachedToc>, bool)> {
+        let metadata = std::fs::metadata(path).map_err(|e| Error::io_with_path(e, path))?;
+        let (modified, len) = (metadata.modified().ok(), metadata.len());
+        if let Some(toc) = self.tocs.lock().get(path) {
+            if toc.modified == modified && toc.len == len {
+                return Ok((Arc::clone(toc), true));
+            }
+        }
+
+        let reader = WadReader::open(path)?;
+        let mut chunks: Vec<(u64, WadChunk)> = reader.chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+        chunks.sort_unstable_by_key(|(hash, _)| *hash);
+        let toc = Arc::new(CachedToc { modified, len, chunks });
+        self.tocs.lock().insert(path.to_path_buf(), Arc::clone(&toc));
+        Ok((toc, false))
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.tocs.lock().len()
+    }
+}
+
+/// Every `.wad.client` under `{league_path}/Game/DATA/FINAL`, locale WADs included
+pub fn list_wad_clients(league_path: &Path) -> Vec<PathBuf> {
+    let root = league_path.join("Game").join("DATA").join("FINAL");
+    let mut wads: Vec<PathBuf> = WalkDir::new(&root)
+        .max_depth(5)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(WadName::parse)
+                .is_some_and(|wad| matches!(wad.kind, WadKind::Client | WadKind::LocaleAudio { .. }))
+        })
+        .collect();
+    wads.sort();
+    wads
+}
+
+/// Search the tables of every WAD in the installation for `query`. Hits
+/// are sorted by WAD path, then hash. `progress` gets the scanned and total
+/// WAD counts.
+pub fn find_in_wads(
+    league_path: &Path,
+    query: &WadQuery,
+    hashtable: Option<&Hashtable>,
+    cache: &WadTocCache,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Result<(Vec<WadSearchHit>, WadScanStats)> {
+    let wads = list_wad_clients(league_path);
+    if wads.is_empty() {
+        return Err(Error::NotFound(format!(
+            "No WADs found under {}",
+            league_path.join("Game").join("DATA").join("FINAL").display()
+        )));
+    }
+
+    let scanned = AtomicUsize::new(0);
+    let results: Vec<_> = parallel::install(|| {
+        wads.par_iter()
+            .map(|wad_path| {
+                let result = cache
+                    .toc(wad_path)
+                    .map(|(toc, cached)| (search_toc(wad_path, &toc, query, hashtable), cached));
+                progress(scanned.fetch_add(1, Ordering::Relaxed) + 1, wads.len());
+                (wad_path, result)
+            })
+            .collect()
+    });
+
+    let mut hits = Vec::new();
+    let mut stats = WadScanStats { wads_scanned: wads.len(), ..Default::default() };
+    for (wad_path, result) in results {
+        match result {
+            Ok((wad_hits, cached)) => {
+                hits.extend(wad_hits);
+                stats.cached += usize::from(cached);
+            }
+            Err(e) => {
+                tracing::warn!("Skipping {} in search: {}", wad_path.display(), e);
+                stats.failed.push(format!("{}: {}", wad_path.display(), e));
+            }
+        }
+    }
+    tracing::info!(
+        "Found {} chunks in {} WADs ({} cached, {} failed)",
+        hits.len(),
+        stats.wads_scanned,
+        stats.cached,
+        stats.failed.len()
+    );
+    Ok((hits, stats))
+}
+
+/// The WAD of the installation that carries the game path `path`
+///
+/// The champion's client and locale WADs are checked first; `champion`
+/// defaults to the one the path is under (`assets/characters/ahri/...`).
+/// Then every WAD of the installation is, in path order, through the cached
+/// tables. `None` when no WAD has the path.
+pub fn locate_game_path(
+    league_path: &Path,
+    path: &str,
+    champion: Option<&str>,
+    cache: &WadTocCache,
+) -> Result<Option<GamePathLocation>> {
+    let path = normalize_path(path.trim());
+    if path.is_empty() {
+        return Err(Error::InvalidInput("Game path is empty".to_string()));
+    }
+    let hash = compute_path_hash(&path);
+    let locate = |wad_path: &Path| match cache.toc(wad_path) {
+        Ok((toc, _)) => toc.get(hash).map(|chunk| GamePathLocation {
+            wad_path: wad_path.display().to_string(),
+            wad_name: wad_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            hash: format!("{:016x}", hash),
+            size: chunk.uncompressed_size(),
+            compressed_size: chunk.compressed_size(),
+            compression: compression_name(chunk.compression_type() as u8).to_string(),
+        }),
+        Err(e) => {
+            tracing::warn!("Skipping {} while locating {}: {}", wad_path.display(), path, e);
+            None
+        }
+    };
+
+    let champion = champion.map(str::to_lowercase).or_else(|| path_champion(&path));
+    let champion_wads = champion
+        .and_then(|champion| champion_wad_paths(league_path, &champion, None).ok())
+        .unwrap_or_default();
+    if let Some(location) = champion_wads.iter().find_map(|wad_path| locate(wad_path)) {
+        return Ok(Some(location));
+    }
+
+    let wads: Vec<PathBuf> =
+        list_wad_clients(league_path).into_iter().filter(|wad| !champion_wads.contains(wad)).collect();
+    let location = parallel::install(|| wads.par_iter().find_map_first(|wad_path| locate(wad_path)));
+    match &location {
+        Some(location) => tracing::debug!("{} is in {}", path, location.wad_path),
+        None => tracing::debug!("{} is in none of the {} WADs", path, wads.len() + champion_wads.len()),
+    }
+    Ok(location)
+}
+
+/// Champion a game path belongs to, from its `characters/{name}/` folder
+fn path_champion(path: &str) -> Option<String> {
+    let rest = path.strip_prefix("assets/characters/").or_else(|| path.strip_prefix("data/characters/"))?;
+    rest.split_once('/').map(|(champion, _)| champion.to_string())
+}
+
+fn search_toc(wad_path: &Path, toc: &CachedToc, query: &WadQuery, hashtable: Option<&Hashtable>) -> Vec<WadSearchHit> {
+    let wad_name = wad_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    toc.chunks
+        .iter()
+        .filter_map(|(hash, chunk)| {
+            let path = hashtable.and_then(|ht| ht.get(*hash));
+            query.matches(*hash, path).then(|| WadSearchHit {
+                wad_path: wad_path.display().to_string(),
+                wad_name: wad_name.clone(),
+                hash: format!("{:016x}", hash),
+                path: path.map(str::to_string),
+                size: chunk.uncompressed_size(),
+                compressed_size: chunk.compressed_size(),
+                compression: compression_name(chunk.compression_type() as u8).to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wad::writer::build_wad;
+    use std::fs;
+
+    #[test]
+    fn test_find_in_wads_uses_cache_until_a_wad_changes() {
+        let temp = tempfile::tempdir().unwrap();
+        let champions = temp.path().join("Game/DATA/FINAL/Champions");
+        let content = temp.path().join("content");
+        let write = |wad: &str, files: &[&str]| {
+            let dir = content.join(wad);
+            for file in files {
+                fs::create_dir_all(dir.join(file).parent().unwrap()).unwrap();
+                fs::write(dir.join(file), b"data").unwrap();
+            }
+            build_wad(&dir, &champions.join(wad)).unwrap();
+        };
+        write("Ahri.wad.client", &["assets/characters/ahri/skins/base/ahri.dds", "data/shared.bin"]);
+        write("Ahri.en_US.wad.client", &["assets/sounds/ahri_vo.bnk"]);
+        write("Annie.wad.client", &["data/shared.bin"]);
+
+        let cache = WadTocCache::new();
+        let search = |query: &str| {
+            let query = WadQuery::parse(query).unwrap();
+            let (hits, stats) = find_in_wads(temp.path(), &query, None, &cache, |_, _| {}).unwrap();
+            (hits.into_iter().map(|hit| hit.wad_name).collect::<Vec<_>>(), stats)
+        };
+
+        let (wads, stats) = search("DATA/Shared.bin");
+        assert_eq!(wads, vec!["Ahri.wad.client", "Annie.wad.client"]);
+        assert_eq!((stats.wads_scanned, stats.cached), (3, 0));
+
+        let hash = format!("{:016x}", compute_path_hash("assets/sounds/ahri_vo.bnk"));
+        let (wads, stats) = search(&hash);
+        assert_eq!(wads, vec!["Ahri.en_US.wad.client"]);
+        assert_eq!(stats.cached, 3);
+
+        write("Annie.wad.client", &["data/shared.bin", "data/annie.bin"]);
+        let (wads, stats) = search(&format!("0x{:016x}", compute_path_hash("data/annie.bin")));
+        assert_eq!(wads, vec!["Annie.wad.client"]);
+        assert_eq!(stats.cached, 2);
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn test_locate_prefers_champion_wads() {
+        let temp = tempfile::tempdir().unwrap();
+        let content = temp.path().join("content");
+        let write = |folder: &str, wad: &str, files: &[&str]| {
+            let dir = content.join(wad);
+            for file in files {
+                fs::create_dir_all(dir.join(file).parent().unwrap()).unwrap();
+                fs::write(dir.join(file), vec![b'x'; 100]).unwrap();
+            }
+            build_wad(&dir, &temp.path().join("Game/DATA/FINAL").join(folder).join(wad)).unwrap();
+        };
+        let shared = "assets/characters/ahri/skins/skin11/ahri_skin11_tx_cm.dds";
+        write("Champions", "Ahri.wad.client", &[shared]);
+        write("Champions", "Ahri.en_US.wad.client", &["assets/sounds/wwise2016/vo/en_us/characters/ahri/ahri_vo.wpk"]);
+        write("Champions", "Annie.wad.client", &[shared]);
+        write("Maps/Shipping", "Map22.wad.client", &["data/maps/shipping/map22/map22.bin", shared]);
+
+        let cache = WadTocCache::new();
+        let locate = |path: &str, champion: Option<&str>| {
+            locate_game_path(temp.path(), path, champion, &cache).unwrap().map(|location| location.wad_name)
+        };
+        assert_eq!(locate("ASSETS/Characters/Ahri/Skins/Skin11/Ahri_Skin11_TX_CM.dds", None).as_deref(), Some("Ahri.wad.client"));
+        assert_eq!(locate(shared, Some("Annie")).as_deref(), Some("Annie.wad.client"));
+        assert_eq!(
+            locate("assets/sounds/wwise2016/vo/en_us/characters/ahri/ahri_vo.wpk", Some("Ahri")).as_deref(),
+            Some("Ahri.en_US.wad.client")
+        );
+        assert_eq!(locate("data/maps/shipping/map22/map22.bin", None).as_deref(), Some("Map22.wad.client"));
+        assert_eq!(locate("data/nowhere.bin", None), None);
+        assert_eq!(cache.len(), 4);
+
+        let location = locate_game_path(temp.path(), shared, Some("Ahri"), &cache).unwrap().unwrap();
+        assert_eq!((location.size, location.compression.as_str()), (100, "zstd"));
+        assert!(locate_game_path(temp.path(), " ", None, &cache).is_err());
+    }
+
+    #[test]
+    fn test_query_matches_hash_or_substring() {
+        let query = WadQuery::parse("Skins/Base").unwrap();
+        assert!(query.matches(1, Some("assets/characters/ahri/skins/base/ahri.dds")));
+        assert!(!query.matches(1, None));
+        assert!(query.matches(compute_path_hash("skins/base"), None));
+
+        let hash = WadQuery::parse("0x00000000000000FF").unwrap();
+        assert!(hash.matches(0xff, None));
+        assert!(!hash.matches(1, Some("00000000000000ff")));
+        assert!(WadQuery::parse("  ").is_err());
+    }
+}
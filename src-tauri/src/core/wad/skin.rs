@@ -0,0 +1,184 @@
+//! The chunks one skin uses
+//!
+//! A champion WAD holds every skin's assets. `skin_chunks` reads the skin's
+//! BIN straight out of the WAD, follows its linked BINs and the BINs it
+//! names (animation graphs), and collects every asset path they reference,
+//! base skin assets included. Extracting just those chunks, plus the BINs,
+//! gives a project the files its skin needs and nothing else.
+
+use crate::core::bin::ltk_bridge::{get_dependencies, read_bin};
+use crate::core::mapping::normalize_path;
+use crate::core::repath::refather::collect_paths_from_value;
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::reader::WadReader;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+/// Chunks of a WAD a skin references
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkinChunks {
+    /// Path hashes to extract: the BINs read and the assets they reference
+    #[serde(skip)]
+    pub hashes: BTreeSet<u64>,
+    /// BINs read, the skin BIN first
+    pub bins: Vec<String>,
+    /// Distinct asset paths the BINs reference
+    pub referenced_count: usize,
+    /// Referenced paths the WAD doesn't hold, sorted
+    pub missing: Vec<String>,
+}
+
+/// Game path of a champion's skin BIN
+pub fn skin_bin_path(champion: &str, skin_id: u32) -> String {
+    format!("data/characters/{}/skins/skin{}.bin", champion.to_lowercase(), skin_id)
+}
+
+/// Walk the BINs of `champion`'s skin `skin_id` in the WAD and collect the
+/// chunks they reference
+///
+/// A `.dds` reference is satisfied by a `.tex` chunk of the same name, the
+/// way the game loads converted textures. A BIN that fails to parse is still
+/// extracted, but its references are lost; that is logged.
+pub fn skin_chunks(reader: &mut WadReader, champion: &str, skin_id: u32) -> Result<SkinChunks> {
+    let skin_bin = skin_bin_path(champion, skin_id);
+    if reader.get_chunk(compute_path_hash(&skin_bin)).is_none() {
+        return Err(Error::NotFound(format!("{} is not in the WAD", skin_bin)));
+    }
+
+    let mut skin = SkinChunks::default();
+    let mut referenced = BTreeSet::new();
+    let mut missing = BTreeSet::new();
+    let mut queued = HashSet::from([skin_bin.clone()]);
+    let mut queue = VecDeque::from([skin_bin]);
+    while let Some(bin_path) = queue.pop_front() {
+        let hash = compute_path_hash(&bin_path);
+        let Some(chunk) = reader.get_chunk(hash).copied() else {
+            missing.insert(bin_path);
+            continue;
+        };
+        skin.hashes.insert(hash);
+
+        let tree = reader
+            .load_chunk(&chunk)
+            .map_err(|e| e.to_string())
+            .and_then(|data| read_bin(&data).map_err(|e| e.to_string()));
+        if let Err(e) = &tree {
+            tracing::warn!("Failed to read {}, extracting it without its references: {}", bin_path, e);
+        }
+        skin.bins.push(bin_path);
+        let Ok(tree) = tree else {
+            continue;
+        };
+
+        let mut paths: Vec<String> = get_dependencies(&tree).iter().map(|path| normalize_path(path)).collect();
+        for object in tree.objects.values() {
+            for prop in object.properties.values() {
+                collect_paths_from_value(&prop.value, &mut paths);
+            }
+        }
+        for path in paths {
+            if path.ends_with(".bin") {
+                if queued.insert(path.clone()) {
+                    queue.push_back(path);
+                }
+            } else {
+                referenced.insert(path);
+            }
+        }
+    }
+
+    for path in &referenced {
+        let tex = path.strip_suffix(".dds").map(|stem| format!("{}.tex", stem));
+        let found = [Some(path.as_str()), tex.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(compute_path_hash)
+            .find(|hash| reader.get_chunk(*hash).is_some());
+        match found {
+            Some(hash) => {
+                skin.hashes.insert(hash);
+            }
+            None => {
+                missing.insert(path.clone());
+            }
+        }
+    }
+
+    skin.referenced_count = referenced.len();
+    skin.missing = missing.into_iter().collect();
+    tracing::info!(
+        "Skin {} of {}: {} BINs, {} referenced assets, {} not in the WAD",
+        skin_id,
+        champion,
+        skin.bins.len(),
+        skin.referenced_count,
+        skin.missing.len()
+    );
+    Ok(skin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bin::ltk_bridge::{text_to_tree, write_bin};
+    use crate::core::wad::writer::build_wad;
+    use std::fs;
+
+    fn bin(linked: &[&str], strings: &[&str]) -> Vec<u8> {
+        let linked: Vec<String> = linked.iter().map(|path| format!("\n    \"{}\"", path)).collect();
+        let fields: Vec<String> =
+            strings.iter().enumerate().map(|(i, path)| format!("field{}: string = \"{}\"", i, path)).collect();
+        let text = format!(
+            "#PROP_text\ntype: string = \"PROP\"\nversion: u32 = 3\nlinked: list[string] = {{{}\n}}\nentries: map[hash,embed] = {{\n    \"Characters/Ahri/Skins/Skin3\" = SkinCharacterDataProperties {{\n        {}\n    }}\n}}\n",
+            linked.concat(),
+            fields.join("\n        ")
+        );
+        write_bin(&text_to_tree(&text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_skin_chunks_follow_references() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("Ahri.wad.client");
+        let files: Vec<(&str, Vec<u8>)> = vec![
+            (
+                "data/characters/ahri/skins/skin3.bin",
+                bin(&["DATA/Characters/Ahri/Ahri.bin"], &[
+                    "ASSETS/Characters/Ahri/Skins/Skin03/Ahri_Skin03_TX_CM.dds",
+                    "ASSETS/Characters/Ahri/Skins/Base/Ahri.skl",
+                    "ASSETS/Characters/Ahri/Skins/Skin03/Missing.tex",
+                    "DATA/Characters/Ahri/Animations/Skin3.bin",
+                ]),
+            ),
+            ("data/characters/ahri/ahri.bin", bin(&[], &["ASSETS/Characters/Ahri/HUD/Ahri_Square.dds"])),
+            ("data/characters/ahri/animations/skin3.bin", bin(&[], &["ASSETS/Characters/Ahri/Skins/Skin03/Idle.anm"])),
+            ("data/characters/ahri/skins/skin1.bin", bin(&[], &["ASSETS/Characters/Ahri/Skins/Skin01/Other.tex"])),
+            ("assets/characters/ahri/skins/skin03/ahri_skin03_tx_cm.tex", b"tex".to_vec()),
+            ("assets/characters/ahri/skins/base/ahri.skl", b"skl".to_vec()),
+            ("assets/characters/ahri/hud/ahri_square.dds", b"dds".to_vec()),
+            ("assets/characters/ahri/skins/skin03/idle.anm", b"anm".to_vec()),
+            ("assets/characters/ahri/skins/skin01/other.tex", b"tex".to_vec()),
+        ];
+        for (path, data) in &files {
+            fs::create_dir_all(dir.join(path).parent().unwrap()).unwrap();
+            fs::write(dir.join(path), data).unwrap();
+        }
+        let wad_path = temp.path().join("out/Ahri.wad.client");
+        build_wad(&dir, &wad_path).unwrap();
+
+        let mut reader = WadReader::open(&wad_path).unwrap();
+        let skin = skin_chunks(&mut reader, "Ahri", 3).unwrap();
+        assert_eq!(skin.bins, [
+            "data/characters/ahri/skins/skin3.bin",
+            "data/characters/ahri/ahri.bin",
+            "data/characters/ahri/animations/skin3.bin",
+        ]);
+        let expected: BTreeSet<u64> = files[..8].iter().filter(|(path, _)| !path.contains("skin1")).map(|(path, _)| compute_path_hash(path)).collect();
+        assert_eq!(skin.hashes, expected);
+        assert_eq!(skin.referenced_count, 5);
+        assert_eq!(skin.missing, ["assets/characters/ahri/skins/skin03/missing.tex"]);
+
+        assert!(matches!(skin_chunks(&mut reader, "Ahri", 9), Err(Error::NotFound(_))));
+    }
+}
@@ -0,0 +1,217 @@
+//! Chunks split into subchunks
+//!
+//! Newer WADs store large chunks as a run of pieces of at most 16 KiB
+//! decompressed (compression type 4). The table of contents entry holds the
+//! subchunk count in the high nibble of its compression byte and the index
+//! of the first subchunk; sizes and checksums of every subchunk live in a
+//! separate chunk, `<wad path without .client>.subchunktoc`
+//! (`data/final/champions/ahri.wad.subchunktoc`). Each piece is zstd
+//! compressed, or stored as is when its stored and decompressed sizes match.
+
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::reader::COMPRESSION_ZSTD_MULTI;
+use std::collections::HashMap;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// Bytes of one subchunk table entry: stored size, decompressed size, xxh3
+const SUBCHUNK_ENTRY_SIZE: usize = 16;
+
+/// One subchunk of the subchunk table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subchunk {
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+    /// xxh3 of the stored bytes
+    pub checksum: u64,
+}
+
+/// A WAD's subchunk table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubchunkToc {
+    pub subchunks: Vec<Subchunk>,
+}
+
+impl SubchunkToc {
+    pub fn parse(data: &[u8]) -> io::Result<Self> {
+        if !data.len().is_multiple_of(SUBCHUNK_ENTRY_SIZE) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("A {} byte subchunk table isn't a whole number of entries", data.len()),
+            ));
+        }
+        let subchunks = data
+            .chunks_exact(SUBCHUNK_ENTRY_SIZE)
+            .map(|entry| Subchunk {
+                compressed_size: u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]),
+                uncompressed_size: u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]),
+                checksum: u64::from_le_bytes(entry[8..16].try_into().unwrap_or_default()),
+            })
+            .collect();
+        Ok(Self { subchunks })
+    }
+
+    /// Decompress the stored bytes of a chunk made of the subchunks in
+    /// `range`, checking the result is `expected` bytes long
+    pub fn decode(&self, stored: &[u8], range: Range<usize>, expected: usize) -> io::Result<Vec<u8>> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let subchunks = self.subchunks.get(range.clone()).ok_or_else(|| {
+            invalid(format!("Subchunks {:?} are past the end of the {} entry table", range, self.subchunks.len()))
+        })?;
+
+        let mut data = Vec::with_capacity(expected);
+        let mut offset = 0;
+        for (index, subchunk) in range.zip(subchunks) {
+            let (compressed, uncompressed) = (subchunk.compressed_size as usize, subchunk.uncompressed_size as usize);
+            let piece = stored
+                .get(offset..offset + compressed)
+                .ok_or_else(|| invalid(format!("Subchunk {} runs past the chunk's stored data", index)))?;
+            if compressed == uncompressed {
+                data.extend_from_slice(piece);
+            } else {
+                let decoded = zstd::bulk::decompress(piece, uncompressed)?;
+                if decoded.len() != uncompressed {
+                    return Err(invalid(format!(
+                        "Subchunk {} decompressed to {} bytes, the table says {}",
+                        index,
+                        decoded.len(),
+                        uncompressed
+                    )));
+                }
+                data.extend_from_slice(&decoded);
+            }
+            offset += compressed;
+        }
+        if data.len() != expected {
+            return Err(invalid(format!("Subchunks decompressed to {} bytes, expected {}", data.len(), expected)));
+        }
+        Ok(data)
+    }
+}
+
+/// Subchunks of the subchunked entries in a v3 table of contents, by path
+/// hash. `toc` is the header and table as the file stores them.
+pub fn subchunk_ranges(toc: &[u8], header_size: usize, entry_size: usize) -> HashMap<u64, Range<usize>> {
+    toc.get(header_size..)
+        .unwrap_or_default()
+        .chunks_exact(entry_size)
+        .filter(|entry| entry[20] & 0x0f == COMPRESSION_ZSTD_MULTI)
+        .map(|entry| {
+            let path_hash = u64::from_le_bytes(entry[..8].try_into().unwrap_or_default());
+            let start = u16::from_le_bytes([entry[22], entry[23]]) as usize;
+            (path_hash, start..start + (entry[20] >> 4) as usize)
+        })
+        .collect()
+}
+
+/// Path hashes the subchunk table of the WAD at `wad_path` may have: from
+/// its game path when the WAD sits under a `DATA` folder, then as a
+/// champion WAD
+pub fn subchunk_toc_hashes(wad_path: &Path) -> Vec<u64> {
+    let name = wad_path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let stem = name.strip_suffix(".client").unwrap_or(&name);
+    let mut paths = Vec::new();
+
+    let components: Vec<String> =
+        wad_path.components().map(|component| component.as_os_str().to_string_lossy().to_lowercase()).collect();
+    if let Some(data) = components.iter().rposition(|component| component == "data") {
+        let dir = components[data..components.len() - 1].join("/");
+        paths.push(format!("{}/{}.subchunktoc", dir, stem));
+    }
+    paths.push(format!("data/final/champions/{}.subchunktoc", stem));
+    paths.dedup();
+    paths.iter().map(|path| compute_path_hash(path)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wad::extractor::extract_chunks;
+    use crate::core::wad::reader::{WadReader, COMPRESSION_NONE};
+    use std::fs;
+    use xxhash_rust::xxh3::xxh3_64;
+
+    /// Decompressed size of every subchunk but a chunk's last
+    const SUBCHUNK_SIZE: usize = 16 * 1024;
+
+    /// Write a v3.4 WAD to `path` holding `data` at `chunk_path`, split
+    /// every `SUBCHUNK_SIZE` bytes, and the subchunk table at `toc_path`.
+    /// Pieces that don't compress are stored as they are, like the game's.
+    fn write_subchunked_wad(path: &Path, chunk_path: &str, data: &[u8], toc_path: &str) {
+        let (mut stored, mut table) = (Vec::new(), Vec::new());
+        for piece in data.chunks(SUBCHUNK_SIZE) {
+            let compressed = zstd::encode_all(piece, 3).unwrap();
+            let kept = if compressed.len() < piece.len() { compressed } else { piece.to_vec() };
+            table.extend_from_slice(&(kept.len() as u32).to_le_bytes());
+            table.extend_from_slice(&(piece.len() as u32).to_le_bytes());
+            table.extend_from_slice(&xxh3_64(&kept).to_le_bytes());
+            stored.extend_from_slice(&kept);
+        }
+        let count = data.len().div_ceil(SUBCHUNK_SIZE) as u8;
+        let table_len = table.len();
+
+        let mut entries = [
+            (compute_path_hash(chunk_path), stored, data.len(), COMPRESSION_ZSTD_MULTI | count << 4),
+            (compute_path_hash(toc_path), table, table_len, COMPRESSION_NONE),
+        ];
+        entries.sort_by_key(|(hash, ..)| *hash);
+        let mut wad = vec![b'R', b'W', 3, 4];
+        wad.extend_from_slice(&[0; 256 + 8]);
+        wad.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        let mut offset = wad.len() + entries.len() * 32;
+        for (hash, stored, size, compression) in &entries {
+            wad.extend_from_slice(&hash.to_le_bytes());
+            wad.extend_from_slice(&(offset as u32).to_le_bytes());
+            wad.extend_from_slice(&(stored.len() as u32).to_le_bytes());
+            wad.extend_from_slice(&(*size as u32).to_le_bytes());
+            // First subchunk 0
+            wad.extend_from_slice(&[*compression, 0, 0, 0]);
+            wad.extend_from_slice(&xxh3_64(stored).to_le_bytes());
+            offset += stored.len();
+        }
+        for (_, stored, ..) in &entries {
+            wad.extend_from_slice(stored);
+        }
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, wad).unwrap();
+    }
+
+    #[test]
+    fn test_subchunked_chunks_decode_whole() {
+        // Compressible, then incompressible, then a short compressible tail
+        let mut data = vec![b'a'; SUBCHUNK_SIZE];
+        let mut state = 0x9e3779b97f4a7c15u64;
+        data.extend((0..SUBCHUNK_SIZE).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        }));
+        data.extend_from_slice(&[b'z'; 1000]);
+
+        let temp = tempfile::tempdir().unwrap();
+        let wad_path = temp.path().join("Game/DATA/FINAL/Champions/Ahri.wad.client");
+        let chunk_path = "assets/characters/ahri/skins/base/ahri_base_tx_cm.tex";
+        write_subchunked_wad(&wad_path, chunk_path, &data, "data/final/champions/ahri.wad.subchunktoc");
+
+        let mut reader = WadReader::open(&wad_path).unwrap();
+        let chunk = *reader.get_chunk(compute_path_hash(chunk_path)).unwrap();
+        assert!(reader.toc().iter().any(|entry| entry.is_subchunked()));
+        assert_eq!(reader.load_chunk(&chunk).unwrap(), data);
+
+        let output = temp.path().join("out");
+        let extraction = extract_chunks(&wad_path, &[(chunk.path_hash(), chunk)], &output, None, None, |_| {}).unwrap();
+        assert_eq!((extraction.extracted, extraction.failed), (1, 0));
+        assert_eq!(fs::read(&extraction.files[0].1).unwrap(), data);
+
+        // Without the table the chunk can't be decoded
+        let moved = temp.path().join("Ahri.wad.client");
+        write_subchunked_wad(&moved, chunk_path, &data, "data/final/maps/ahri.wad.subchunktoc");
+        let mut reader = WadReader::open(&moved).unwrap();
+        assert!(reader.load_chunk(&chunk).is_err());
+
+        assert_eq!(subchunk_toc_hashes(&wad_path)[0], compute_path_hash("data/final/champions/ahri.wad.subchunktoc"));
+        assert!(SubchunkToc::parse(&[0; 17]).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+//! Checking a WAD's chunks without extracting them
+//!
+//! Each chunk's data is read once: the raw bytes are hashed against the
+//! table of contents checksum (xxh3 since v3.1, the first 8 bytes of SHA-256
+//! in v2 and v3.0, none in v1) while they are decompressed into a sink, and the decompressed
+//! size is compared with the table. Data running past the end of the file
+//! is reported as truncated. Nothing is written to disk.
+
+use crate::core::hash::hashtable::Hashtable;
+use crate::core::operation::CancellationToken;
+use crate::core::parallel;
+use crate::core::wad::extractor::MAX_CHUNK_SIZE;
+use crate::core::wad::reader::{WadReader, WadSource, COMPRESSION_GZIP, COMPRESSION_NONE, COMPRESSION_ZSTD};
+use crate::error::{Error, Result};
+use flate2::read::GzDecoder;
+use league_toolkit::wad::WadChunk;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Outcome of checking one chunk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkCheck {
+    /// Path hash as 16 hex digits
+    pub hash: String,
+    pub path: Option<String>,
+    pub passed: bool,
+    /// What is wrong with the chunk, when it failed
+    pub error: Option<String>,
+}
+
+/// Result of `verify_wad`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WadVerification {
+    pub wad_path: String,
+    /// e.g. "3.3"
+    pub version: String,
+    /// Every chunk checked, sorted by hash
+    pub chunks: Vec<ChunkCheck>,
+    pub passed: usize,
+    pub failed: usize,
+    /// Chunks whose data was checked against a checksum; the others only had
+    /// their size checked
+    pub checksums_verified: usize,
+    /// Stopped by the cancellation token; `chunks` holds the ones checked until then
+    pub cancelled: bool,
+}
+
+/// How a WAD version checksums its chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChecksumKind {
+    None,
+    /// v2 and v3.0: first 8 bytes of the SHA-256 of the stored data
+    Sha256,
+    /// v3.1 and later: xxh3 of the stored data
+    Xxh3,
+}
+
+impl ChecksumKind {
+    fn for_version((major, minor): (u8, u8)) -> Self {
+        match (major, minor) {
+            (2, _) | (3, 0) => ChecksumKind::Sha256,
+            (major, _) if major >= 3 => ChecksumKind::Xxh3,
+            _ => ChecksumKind::None,
+        }
+    }
+}
+
+/// Reader hashing the bytes that pass through it
+struct HashingRead<R> {
+    inner: R,
+    xxh3: Xxh3,
+    sha256: Option<Sha256>,
+}
+
+impl<R: Read> HashingRead<R> {
+    fn new(inner: R, kind: ChecksumKind) -> Self {
+        Self { inner, xxh3: Xxh3::new(), sha256: (kind == ChecksumKind::Sha256).then(Sha256::new) }
+    }
+
+    fn checksum(self) -> u64 {
+        match self.sha256 {
+            Some(sha256) => {
+                let digest = sha256.finalize();
+                u64::from_le_bytes(digest[..8].try_into().unwrap_or_default())
+            }
+            None => self.xxh3.digest(),
+        }
+    }
+}
+
+impl<R: Read> Read for HashingRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        match &mut self.sha256 {
+            Some(sha256) => sha256.update(&buf[..read]),
+            None => self.xxh3.update(&buf[..read]),
+        }
+        Ok(read)
+    }
+}
+
+/// Decompress and checksum every chunk of the WAD at `wad_path` in parallel
+///
+/// `cancel` is checked before each chunk; once it trips the chunks checked
+/// so far come back with `cancelled` set. `progress` gets the checked and
+/// total chunk counts, at most about a hundred times.
+pub fn verify_wad(
+    wad_path: &Path,
+    hashtable: Option<&Hashtable>,
+    cancel: Option<&CancellationToken>,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Result<WadVerification> {
+    let reader = WadReader::open(wad_path)?;
+    let version = reader.version();
+    let file_len = WadSource::open(wad_path)?.size().map_err(|e| Error::io_with_path(e, wad_path))?;
+    let kind = ChecksumKind::for_version(version);
+
+    let mut chunks: Vec<(u64, WadChunk)> = reader.chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+    chunks.sort_unstable_by_key(|(hash, _)| *hash);
+    drop(reader);
+    let total = chunks.len();
+
+    let done = AtomicUsize::new(0);
+    let step = (total / 100).max(1);
+    let reported = Mutex::new(0);
+    let checks: Vec<Option<(ChunkCheck, bool)>> = parallel::install(|| {
+        chunks
+            .par_iter()
+            .map_init(
+                || {
+                    let file = WadSource::open(wad_path).map_err(|e| e.to_string())?;
+                    let reader = WadReader::open(wad_path).map_err(|e| e.to_string())?;
+                    Ok::<_, String>((file, reader))
+                },
+                |worker, (path_hash, chunk)| {
+                    if cancel.is_some_and(CancellationToken::is_cancelled) {
+                        return None;
+                    }
+                    let result = match worker {
+                        Ok((file, reader)) => {
+                            let _permit = parallel::io_permit();
+                            verify_chunk(file, reader, chunk, file_len, kind)
+                        }
+                        Err(e) => Err(format!("Failed to open the WAD: {}", e)),
+                    };
+                    let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if finished.is_multiple_of(step) || finished == total {
+                        let mut reported = reported.lock();
+                        if finished > *reported {
+                            *reported = finished;
+                            progress(finished, total);
+                        }
+                    }
+                    let check = ChunkCheck {
+                        hash: format!("{:016x}", path_hash),
+                        path: hashtable.and_then(|ht| ht.get(*path_hash)).map(str::to_string),
+                        passed: result.is_ok(),
+                        error: result.as_ref().err().cloned(),
+                    };
+                    Some((check, result.unwrap_or(false)))
+                },
+            )
+            .collect()
+    });
+
+    let mut verification = WadVerification {
+        wad_path: wad_path.display().to_string(),
+        version: format!("{}.{}", version.0, version.1),
+        ..Default::default()
+    };
+    for check in checks {
+        let Some((check, checksum_verified)) = check else {
+            verification.cancelled = true;
+            continue;
+        };
+        if check.passed {
+            verification.passed += 1;
+        } else {
+            tracing::warn!("Chunk {} of {} failed verification: {}", check.hash, wad_path.display(), check.error.as_deref().unwrap_or_default());
+            verification.failed += 1;
+        }
+        verification.checksums_verified += usize::from(checksum_verified);
+        verification.chunks.push(check);
+    }
+
+    tracing::info!(
+        "Verified {} chunks of {}: {} passed, {} failed, {} checksums checked{}",
+        verification.chunks.len(),
+        wad_path.display(),
+        verification.passed,
+        verification.failed,
+        verification.checksums_verified,
+        if verification.cancelled { " (cancelled)" } else { "" }
+    );
+    Ok(verification)
+}
+
+/// Check one chunk. Returns whether a checksum was compared, or what is
+/// wrong with the chunk.
+fn verify_chunk(
+    file: &mut WadSource,
+    reader: &mut WadReader,
+    chunk: &WadChunk,
+    file_len: u64,
+    kind: ChecksumKind,
+) -> std::result::Result<bool, String> {
+    let (offset, stored) = (chunk.data_offset() as u64, chunk.compressed_size() as u64);
+    let expected = chunk.uncompressed_size();
+    if offset + stored > file_len {
+        return Err(format!("Data truncated: the chunk ends at byte {} of a {} byte file", offset + stored, file_len));
+    }
+    if expected > MAX_CHUNK_SIZE {
+        return Err(format!("Declares {} bytes decompressed, over the {} byte limit", expected, MAX_CHUNK_SIZE));
+    }
+
+    let mut errors = Vec::new();
+    file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+    let mut raw = HashingRead::new(Read::by_ref(file).take(stored), kind);
+    let decoded = match chunk.compression_type() as u8 {
+        COMPRESSION_NONE => Some(io::copy(&mut raw, &mut io::sink())),
+        COMPRESSION_GZIP => Some(io::copy(&mut GzDecoder::new(&mut raw), &mut io::sink())),
+        COMPRESSION_ZSTD => Some(
+            zstd::stream::read::Decoder::new(&mut raw).and_then(|mut decoder| io::copy(&mut decoder, &mut io::sink())),
+        ),
+        // Subchunked zstd only decodes whole
+        _ => None,
+    };
+    // Hash what the decoder left unread too
+    if let Err(e) = io::copy(&mut raw, &mut io::sink()) {
+        return Err(format!("Failed to read the chunk's data: {}", e));
+    }
+
+    let checksum = chunk.checksum();
+    let checked = kind != ChecksumKind::None && checksum != 0;
+    if checked {
+        let actual = raw.checksum();
+        if actual != checksum {
+            errors.push(format!("Checksum mismatch: the table says {:016x}, the data hashes to {:016x}", checksum, actual));
+        }
+    }
+
+    let decoded = match decoded {
+        Some(result) => result.map(|len| len as usize).map_err(|e| e.to_string()),
+        None => reader.load_chunk(chunk).map(|data| data.len()).map_err(|e| e.to_string()),
+    };
+    match decoded {
+        Ok(len) if len != expected => {
+            errors.push(format!("Decompressed to {} bytes, the table says {}", len, expected));
+        }
+        Ok(_) => {}
+        Err(e) => errors.push(format!("Decompression failed: {}", e)),
+    }
+
+    if errors.is_empty() {
+        Ok(checked)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wad::writer::build_wad;
+    use std::fs;
+
+    #[test]
+    fn test_verify_reports_corrupt_and_truncated_chunks() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("files");
+        fs::create_dir_all(&dir).unwrap();
+        for hash in 1..=3u64 {
+            fs::write(dir.join(format!("{:016x}.bin", hash)), vec![hash as u8; 10_000]).unwrap();
+        }
+        let wad_path = temp.path().join("Ahri.wad.client");
+        build_wad(&dir, &wad_path).unwrap();
+
+        let verification = verify_wad(&wad_path, None, None, |_, _| {}).unwrap();
+        assert_eq!(verification.version, "3.1");
+        assert_eq!((verification.passed, verification.failed, verification.checksums_verified), (3, 0, 3));
+
+        // Flip a byte of the first chunk's data (entries are sorted by hash)
+        let mut wad = fs::read(&wad_path).unwrap();
+        let offset = u32::from_le_bytes(wad[272 + 8..272 + 12].try_into().unwrap()) as usize;
+        wad[offset + 8] ^= 0xff;
+        let last_offset = u32::from_le_bytes(wad[272 + 64 + 8..272 + 64 + 12].try_into().unwrap()) as usize;
+        wad.truncate(last_offset + 2);
+        fs::write(&wad_path, wad).unwrap();
+
+        let verification = verify_wad(&wad_path, None, None, |_, _| {}).unwrap();
+        assert_eq!((verification.passed, verification.failed), (1, 2));
+        let errors: Vec<_> = verification.chunks.iter().map(|c| c.error.clone().unwrap_or_default()).collect();
+        assert!(errors[0].starts_with("Checksum mismatch"), "{}", errors[0]);
+        assert!(errors[1].is_empty());
+        assert!(errors[2].starts_with("Data truncated"), "{}", errors[2]);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let verification = verify_wad(&wad_path, None, Some(&token), |_, _| {}).unwrap();
+        assert!(verification.cancelled && verification.chunks.is_empty());
+    }
+}
@@ -0,0 +1,472 @@
+//! Building WAD archives from loose files
+//!
+//! A WAD folder in a project (`content/base/Ahri.wad.client/`) holds the
+//! archive's files under their game paths. `write_wad` packs such a folder
+//! into a v3 archive: every file becomes a zstd-compressed chunk keyed by the
+//! xxhash64 of its lowercased path, with the table of contents sorted by hash
+//! like the game's own WADs. Files named by their hash
+//! (`0123456789abcdef.tex`, the way extraction writes unresolved chunks) keep
+//! that hash, and the `.ltk` marker extraction adds to paths without an
+//! extension is dropped again.
+//!
+//! `repack_wad` packs such a folder into a copy of an existing WAD instead:
+//! the folder's files replace or add chunks, every other chunk is carried
+//! over with its stored data untouched.
+
+use crate::core::parallel;
+use crate::core::validation::engine::compute_path_hash;
+use crate::core::wad::reader::{WadSource, COMPRESSION_NONE, COMPRESSION_ZSTD};
+use crate::error::{Error, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Version written: 3.1, read by the game and every league-toolkit release
+/// (before ltk_wad 0.2.11 only 3.1 and 3.4 are accepted)
+const VERSION: (u8, u8) = (3, 1);
+
+/// Header bytes: magic, version, ECDSA signature, checksum, chunk count
+const HEADER_SIZE: usize = 2 + 2 + 256 + 8 + 4;
+
+/// Bytes of one table of contents entry
+const TOC_ENTRY_SIZE: usize = 32;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// Result of `write_wad`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WadBuildStats {
+    /// Chunks written
+    pub chunk_count: usize,
+    /// Files left out because an earlier file (by path) has the same path hash
+    pub duplicates: Vec<String>,
+    /// Total size of the files packed
+    pub uncompressed_size: u64,
+    /// Size of the archive
+    pub size: u64,
+}
+
+/// Result of `repack_wad`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WadRepackStats {
+    /// Chunks of the original replaced by a file of the folder
+    pub replaced_count: usize,
+    /// Chunks of the original copied as they were
+    pub carried_count: usize,
+    /// Files of the folder the original doesn't have, added as new chunks
+    pub added_count: usize,
+    /// Files left out because an earlier file (by path) has the same path hash
+    pub duplicates: Vec<String>,
+    /// Size of the archive
+    pub size: u64,
+}
+
+struct PackedChunk {
+    path_hash: u64,
+    data: Vec<u8>,
+    uncompressed_size: usize,
+    compression: u8,
+}
+
+/// Path hash of a file at `relative_path` in a WAD folder
+pub fn chunk_path_hash(relative_path: &str) -> u64 {
+    let path = relative_path.replace('\\', "/");
+    let stem = path.split_once('.').map_or(path.as_str(), |(stem, _)| stem);
+    if !path.contains('/') && stem.len() == 16 {
+        if let Ok(hash) = u64::from_str_radix(stem, 16) {
+            return hash;
+        }
+    }
+    compute_path_hash(strip_ltk_marker(&path))
+}
+
+/// `name.ltk` and `name.ltk.bin` back to the `name` they were extracted from
+fn strip_ltk_marker(path: &str) -> &str {
+    let name_start = path.rfind('/').map_or(0, |i| i + 1);
+    match path[name_start..].find(".ltk") {
+        Some(index) if matches!(path[name_start + index + 4..].chars().next(), None | Some('.')) => {
+            &path[..name_start + index]
+        }
+        _ => path,
+    }
+}
+
+/// Pack the files under `dir` into a WAD at `output`
+#[cfg(test)]
+pub fn build_wad(dir: &Path, output: &Path) -> Result<WadBuildStats> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+    }
+    let file = File::create(output).map_err(|e| Error::io_with_path(e, output))?;
+    let mut writer = BufWriter::new(file);
+    let stats = write_wad(dir, &mut writer)?;
+    writer.flush().map_err(|e| Error::io_with_path(e, output))?;
+    tracing::info!(
+        "Packed {} into {} ({} chunks, {} bytes)",
+        dir.display(),
+        output.display(),
+        stats.chunk_count,
+        stats.size
+    );
+    Ok(stats)
+}
+
+/// Pack the files under `dir` into a WAD written to `writer`
+pub fn write_wad(dir: &Path, mut writer: impl Write) -> Result<WadBuildStats> {
+    let mut stats = WadBuildStats::default();
+    let chunks = pack_folder(dir, &mut stats.duplicates)?;
+
+    let data_start = HEADER_SIZE + chunks.len() * TOC_ENTRY_SIZE;
+    let size = data_start + chunks.iter().map(|chunk| chunk.data.len()).sum::<usize>();
+    if u32::try_from(size).is_err() {
+        return Err(Error::InvalidInput(format!(
+            "{} is too large for a WAD ({} bytes compressed)",
+            dir.display(),
+            size
+        )));
+    }
+
+    let mut header = wad_header(chunks.len());
+    let mut offset = data_start;
+    for chunk in &chunks {
+        header.extend_from_slice(&chunk.path_hash.to_le_bytes());
+        header.extend_from_slice(&(offset as u32).to_le_bytes());
+        header.extend_from_slice(&(chunk.data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(chunk.uncompressed_size as u32).to_le_bytes());
+        // Compression in the low nibble, no subchunks in the high one
+        header.push(chunk.compression);
+        header.push(0); // not duplicated
+        header.extend_from_slice(&0u16.to_le_bytes()); // first subchunk
+        header.extend_from_slice(&xxh3_64(&chunk.data).to_le_bytes());
+        offset += chunk.data.len();
+        stats.uncompressed_size += chunk.uncompressed_size as u64;
+    }
+
+    let write_err = |e| Error::io_with_path(e, dir);
+    writer.write_all(&header).map_err(write_err)?;
+    for chunk in &chunks {
+        writer.write_all(&chunk.data).map_err(write_err)?;
+    }
+
+    stats.chunk_count = chunks.len();
+    stats.size = size as u64;
+    Ok(stats)
+}
+
+/// Header of a v3.3 WAD with `chunk_count` chunks, unsigned
+fn wad_header(chunk_count: usize) -> Vec<u8> {
+    let mut header = Vec::with_capacity(HEADER_SIZE + chunk_count * TOC_ENTRY_SIZE);
+    header.extend_from_slice(b"RW");
+    header.extend_from_slice(&[VERSION.0, VERSION.1]);
+    header.extend_from_slice(&[0; 256]);
+    header.extend_from_slice(&0u64.to_le_bytes());
+    header.extend_from_slice(&(chunk_count as u32).to_le_bytes());
+    header
+}
+
+/// Compress the files under `dir` in parallel, sorted by path hash. Files
+/// whose hash an earlier file (by path) has are added to `duplicates`.
+fn pack_folder(dir: &Path, duplicates: &mut Vec<String>) -> Result<Vec<PackedChunk>> {
+    let mut files: Vec<(String, PathBuf)> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(dir).ok()?.to_string_lossy().replace('\\', "/");
+            Some((relative, e.path().to_path_buf()))
+        })
+        .collect();
+    files.sort();
+
+    let mut by_hash: BTreeMap<u64, PathBuf> = BTreeMap::new();
+    for (relative, path) in files {
+        let hash = chunk_path_hash(&relative);
+        if by_hash.contains_key(&hash) {
+            tracing::warn!("Skipping {}: another file has the path hash {:016x}", relative, hash);
+            duplicates.push(relative);
+            continue;
+        }
+        by_hash.insert(hash, path);
+    }
+
+    parallel::install(|| {
+        by_hash
+            .par_iter()
+            .map(|(hash, path)| {
+                let data = {
+                    let _permit = parallel::io_permit();
+                    fs::read(path).map_err(|e| Error::io_with_path(e, path))?
+                };
+                pack_chunk(*hash, data).map_err(|e| Error::io_with_path(e, path))
+            })
+            .collect::<Result<Vec<_>>>()
+    })
+}
+
+/// Write a copy of the WAD at `original` to `output` with the files under
+/// `dir` packed in
+///
+/// A file replaces the chunk with its path hash, or is added when the WAD
+/// has none; both are compressed like `write_wad` does. Every other chunk
+/// keeps its stored data, compression and subchunks, and chunks sharing
+/// data still share it. The copy is a v3.3 WAD whatever the original's
+/// version, written to a `.part` file first so a failure leaves no broken
+/// WAD behind. Refuses to write over `original` itself.
+pub fn repack_wad(original: &Path, dir: &Path, output: &Path) -> Result<WadRepackStats> {
+    if is_same_file(original, output) {
+        return Err(Error::InvalidInput(format!("Refusing to overwrite the original WAD {}", original.display())));
+    }
+
+    // v1 and v2 tables of contents come back in the v3 layout
+    let mut source = WadSource::open(original)?;
+    let read_err = |e| Error::io_with_path(e, original);
+    let mut header = [0u8; HEADER_SIZE];
+    source.read_exact(&mut header).map_err(read_err)?;
+    let count = u32::from_le_bytes(header[HEADER_SIZE - 4..].try_into().unwrap_or_default()) as usize;
+    let mut toc = vec![0u8; count * TOC_ENTRY_SIZE];
+    source.read_exact(&mut toc).map_err(read_err)?;
+    let mut originals: BTreeMap<u64, [u8; TOC_ENTRY_SIZE]> = BTreeMap::new();
+    for entry in toc.chunks_exact(TOC_ENTRY_SIZE) {
+        let entry: [u8; TOC_ENTRY_SIZE] = entry.try_into().unwrap_or([0; TOC_ENTRY_SIZE]);
+        originals.insert(u64::from_le_bytes(entry[..8].try_into().unwrap_or_default()), entry);
+    }
+
+    let mut stats = WadRepackStats::default();
+    let packed: BTreeMap<u64, PackedChunk> =
+        pack_folder(dir, &mut stats.duplicates)?.into_iter().map(|chunk| (chunk.path_hash, chunk)).collect();
+    let mut hashes: Vec<u64> = originals.keys().chain(packed.keys()).copied().collect();
+    hashes.sort_unstable();
+    hashes.dedup();
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::io_with_path(e, parent))?;
+    }
+    let part = output.with_file_name(format!(
+        "{}.part",
+        output.file_name().map(|name| name.to_string_lossy()).unwrap_or_default()
+    ));
+    let result = write_repacked(original, &mut source, &originals, &packed, &hashes, &part);
+    let size = match result {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = fs::remove_file(&part);
+            return Err(e);
+        }
+    };
+    fs::rename(&part, output).map_err(|e| Error::io_with_path(e, output))?;
+
+    stats.replaced_count = packed.keys().filter(|hash| originals.contains_key(hash)).count();
+    stats.added_count = packed.len() - stats.replaced_count;
+    stats.carried_count = originals.len() - stats.replaced_count;
+    stats.size = size;
+    tracing::info!(
+        "Repacked {} into {}: {} chunks replaced, {} added, {} carried over",
+        dir.display(),
+        output.display(),
+        stats.replaced_count,
+        stats.added_count,
+        stats.carried_count
+    );
+    Ok(stats)
+}
+
+/// Write the repacked WAD to `path`: a placeholder table of contents, the
+/// data in hash order, then the real table once every offset and checksum
+/// is known. Returns the size written.
+fn write_repacked(
+    original: &Path,
+    source: &mut WadSource,
+    originals: &BTreeMap<u64, [u8; TOC_ENTRY_SIZE]>,
+    packed: &BTreeMap<u64, PackedChunk>,
+    hashes: &[u64],
+    path: &Path,
+) -> Result<u64> {
+    let write_err = |e| Error::io_with_path(e, path);
+    let read_err = |e| Error::io_with_path(e, original);
+    let file = File::create(path).map_err(write_err)?;
+    let mut writer = BufWriter::new(file);
+    let mut header = wad_header(hashes.len());
+    let data_start = header.len() + hashes.len() * TOC_ENTRY_SIZE;
+    writer.write_all(&vec![0; data_start]).map_err(write_err)?;
+
+    // Entries with the offset and checksum of their data in the copy
+    let mut entries: Vec<([u8; TOC_ENTRY_SIZE], u64, u64)> = Vec::with_capacity(hashes.len());
+    // Original data offset to where it was copied, so shared data stays shared
+    let mut copied: HashMap<u32, (u64, u64)> = HashMap::new();
+    let mut offset = data_start as u64;
+    let mut data = Vec::new();
+    for hash in hashes {
+        if let Some(chunk) = packed.get(hash) {
+            let mut entry = [0u8; TOC_ENTRY_SIZE];
+            entry[..8].copy_from_slice(&hash.to_le_bytes());
+            entry[12..16].copy_from_slice(&(chunk.data.len() as u32).to_le_bytes());
+            entry[16..20].copy_from_slice(&(chunk.uncompressed_size as u32).to_le_bytes());
+            entry[20] = chunk.compression;
+            writer.write_all(&chunk.data).map_err(write_err)?;
+            entries.push((entry, offset, xxh3_64(&chunk.data)));
+            offset += chunk.data.len() as u64;
+            continue;
+        }
+
+        // Carried over: compression, subchunks and sizes stay as they were
+        let Some(entry) = originals.get(hash) else { continue };
+        let original_offset = u32::from_le_bytes(entry[8..12].try_into().unwrap_or_default());
+        if let Some((data_offset, checksum)) = copied.get(&original_offset) {
+            entries.push((*entry, *data_offset, *checksum));
+            continue;
+        }
+        let stored = u32::from_le_bytes(entry[12..16].try_into().unwrap_or_default());
+        data.clear();
+        source.seek(SeekFrom::Start(u64::from(original_offset))).map_err(read_err)?;
+        Read::by_ref(source).take(u64::from(stored)).read_to_end(&mut data).map_err(read_err)?;
+        if data.len() != stored as usize {
+            return Err(Error::wad_with_path(format!("Chunk {:016x} is truncated", hash), original));
+        }
+        writer.write_all(&data).map_err(write_err)?;
+        let checksum = xxh3_64(&data);
+        copied.insert(original_offset, (offset, checksum));
+        entries.push((*entry, offset, checksum));
+        offset += data.len() as u64;
+    }
+    if u32::try_from(offset).is_err() {
+        return Err(Error::InvalidInput(format!("{} is too large for a WAD ({} bytes)", path.display(), offset)));
+    }
+
+    let mut sharing: HashMap<u64, usize> = HashMap::new();
+    for (_, data_offset, _) in &entries {
+        *sharing.entry(*data_offset).or_default() += 1;
+    }
+    for (mut entry, data_offset, checksum) in entries {
+        entry[8..12].copy_from_slice(&(data_offset as u32).to_le_bytes());
+        entry[21] = u8::from(sharing[&data_offset] > 1);
+        entry[24..32].copy_from_slice(&checksum.to_le_bytes());
+        header.extend_from_slice(&entry);
+    }
+    let mut file = writer.into_inner().map_err(|e| write_err(e.into_error()))?;
+    file.seek(SeekFrom::Start(0)).map_err(write_err)?;
+    file.write_all(&header).map_err(write_err)?;
+    file.sync_all().map_err(write_err)?;
+    Ok(offset)
+}
+
+/// Whether `a` and `b` name the same file; `b` may not exist yet
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    let canonical = |path: &Path| {
+        path.canonicalize().ok().or_else(|| {
+            let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            Some(parent.canonicalize().ok()?.join(path.file_name()?))
+        })
+    };
+    match (canonical(a), canonical(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Compress one file; empty files are stored as they are
+fn pack_chunk(path_hash: u64, data: Vec<u8>) -> std::io::Result<PackedChunk> {
+    let uncompressed_size = data.len();
+    if data.is_empty() {
+        return Ok(PackedChunk { path_hash, data, uncompressed_size, compression: COMPRESSION_NONE });
+    }
+    let data = zstd::encode_all(data.as_slice(), ZSTD_LEVEL)?;
+    Ok(PackedChunk { path_hash, data, uncompressed_size, compression: COMPRESSION_ZSTD })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wad::reader::WadReader;
+
+    #[test]
+    fn test_written_wad_round_trips() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("Ahri.wad.client");
+        let files: &[(&str, &[u8])] = &[
+            ("assets/characters/ahri/skin.bin", b"sorts after its uppercase twin, so it is left out"),
+            ("ASSETS/Characters/Ahri/Skin.bin", b"PROP bin data, repeated, repeated, repeated"),
+            ("assets/empty.dds", b""),
+            ("data/characters/ahri/ahri.ltk.bin", b"extracted without an extension"),
+            ("0123456789abcdef.tex", b"unresolved chunk"),
+        ];
+        for (path, data) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, data).unwrap();
+        }
+
+        let output = temp.path().join("out").join("Ahri.wad.client");
+        let stats = build_wad(&dir, &output).unwrap();
+        assert_eq!(stats.chunk_count, 4);
+        assert_eq!(stats.duplicates, vec!["assets/characters/ahri/skin.bin"]);
+        assert_eq!(stats.size, fs::metadata(&output).unwrap().len());
+
+        let mut reader = WadReader::open(&output).unwrap();
+        let mut hashes: Vec<u64> = reader.chunks().keys().copied().collect();
+        hashes.sort_unstable();
+        let expected = |path: &str, data: &[u8]| (compute_path_hash(path), data.to_vec());
+        let mut expected = vec![
+            expected("assets/characters/ahri/skin.bin", files[1].1),
+            expected("assets/empty.dds", b""),
+            expected("data/characters/ahri/ahri", files[3].1),
+            (0x0123456789abcdef, files[4].1.to_vec()),
+        ];
+        expected.sort();
+        assert_eq!(hashes, expected.iter().map(|(hash, _)| *hash).collect::<Vec<_>>());
+
+        for (hash, data) in expected {
+            let chunk = *reader.get_chunk(hash).unwrap();
+            let (mut decoder, _) = reader.wad_mut().decode();
+            assert_eq!(decoder.load_chunk_decompressed(&chunk).unwrap().to_vec(), data);
+        }
+    }
+
+    #[test]
+    fn test_repack_replaces_adds_and_carries_chunks() {
+        let temp = tempfile::tempdir().unwrap();
+        let write = |dir: &Path, files: &[(&str, &[u8])]| {
+            for (path, data) in files {
+                let path = dir.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, data).unwrap();
+            }
+        };
+        let game = temp.path().join("game");
+        write(&game, &[
+            ("assets/a.dds", b"original a"),
+            ("assets/b.dds", b"original b"),
+            ("data/c.bin", b"original c"),
+        ]);
+        let original = temp.path().join("Ahri.wad.client");
+        build_wad(&game, &original).unwrap();
+
+        let project = temp.path().join("content/base/Ahri.wad.client");
+        write(&project, &[("ASSETS/A.dds", b"modified a"), ("assets/new.dds", b"added")]);
+        let output = temp.path().join("patched/Ahri.wad.client");
+        let stats = repack_wad(&original, &project, &output).unwrap();
+        assert_eq!((stats.replaced_count, stats.added_count, stats.carried_count), (1, 1, 2));
+        assert_eq!(stats.size, fs::metadata(&output).unwrap().len());
+        assert!(!output.with_file_name("Ahri.wad.client.part").exists());
+
+        let mut reader = WadReader::open(&output).unwrap();
+        assert_eq!(reader.chunk_count(), 4);
+        for (path, data) in [
+            ("assets/a.dds", &b"modified a"[..]),
+            ("assets/b.dds", b"original b"),
+            ("data/c.bin", b"original c"),
+            ("assets/new.dds", b"added"),
+        ] {
+            let chunk = *reader.get_chunk(compute_path_hash(path)).unwrap();
+            let (mut decoder, _) = reader.wad_mut().decode();
+            assert_eq!(decoder.load_chunk_decompressed(&chunk).unwrap().to_vec(), data, "{}", path);
+        }
+
+        let relative = original.parent().unwrap().join("game/../Ahri.wad.client");
+        assert!(matches!(repack_wad(&original, &project, &relative), Err(Error::InvalidInput(_))));
+    }
+}
@@ -22,6 +22,13 @@ pub enum Error {
         path: Option<std::path::PathBuf>,
     },
 
+    #[error("Unsupported WAD version {major}.{minor}{}", .path.as_ref().map(|p| format!(" in file '{}'", p.display())).unwrap_or_default())]
+    UnsupportedWadVersion {
+        major: u8,
+        minor: u8,
+        path: Option<std::path::PathBuf>,
+    },
+
     #[error("Hash error: {0}")]
     Hash(String),
 
@@ -33,6 +40,54 @@ pub enum Error {
 
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Hashtable is not loaded yet")]
+    HashtableNotReady,
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[error("Offline mode is on; turn it off to use the network")]
+    OfflineMode,
+
+    #[error("Checkpoint operation already in progress: {0}")]
+    CheckpointBusy(String),
+
+    #[error("Project is busy: {0}")]
+    Busy(String),
+
+    #[error("Project is read-only: '{}'", .0.display())]
+    ProjectReadOnly(std::path::PathBuf),
+
+    #[error("Unsafe path '{path}': {reason}")]
+    UnsafePath {
+        path: String,
+        reason: String,
+    },
+
+    #[error("'{}' is locked by another program{}; close League and the Riot Client, or wait for patching to finish", .path.display(), if .processes.is_empty() { String::new() } else { format!(" ({} running)", .processes.join(", ")) })]
+    GameFilesLocked {
+        path: std::path::PathBuf,
+        /// League and Riot Client processes running when it failed
+        processes: Vec<String>,
+    },
+
+    #[error("'{}' doesn't read back as written: {} divergent {}: {}{}", .path.display(), .divergent.len(), if .divergent.len() == 1 { "path" } else { "paths" }, .divergent.iter().take(5).cloned().collect::<Vec<_>>().join(", "), if .divergent.len() > 5 { ", …" } else { "" })]
+    RoundTripMismatch {
+        path: std::path::PathBuf,
+        /// Paths whose values were lost or changed in writing
+        divergent: Vec<String>,
+    },
+
+    #[error("Not enough disk space at '{}': {} needed, {} available", .path.display(), crate::core::disk::format_bytes(*.required), crate::core::disk::format_bytes(*.available))]
+    InsufficientDiskSpace {
+        required: u64,
+        available: u64,
+        path: std::path::PathBuf,
+    },
 }
 
 impl Error {
@@ -93,6 +148,149 @@ impl From<Error> for String {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Stable, machine-readable error category sent to the frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    Io,
+    NotFound,
+    Network,
+    Parse,
+    Wad,
+    /// A WAD's header names a version the reader doesn't handle
+    UnsupportedWadVersion,
+    Hash,
+    BinConversion,
+    InvalidInput,
+    HashtableNotReady,
+    Cancelled,
+    /// A network command was refused because offline mode is on
+    OfflineMode,
+    Busy,
+    InsufficientDiskSpace,
+    /// An archive entry, manifest or BIN named a path outside its directory
+    UnsafePath,
+    /// The project is on read-only media; nothing was changed
+    ProjectReadOnly,
+    /// A game file is held by the running game or a patch in progress
+    GameFilesLocked,
+    /// Errors that don't come from `Error` (task panics, ad-hoc messages)
+    Internal,
+}
+
+/// Error returned by every Tauri command.
+///
+/// `message` is the same human-readable text commands used to return as a
+/// plain string; `code` lets the frontend branch without matching on it.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Extra context, e.g. the underlying OS error
+    pub details: Option<String>,
+    /// File the error relates to, if any
+    pub path: Option<String>,
+    /// Line number for parse errors
+    pub line: Option<u32>,
+    /// Bytes needed and free, for disk space errors
+    pub required_bytes: Option<u64>,
+    pub available_bytes: Option<u64>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+            path: None,
+            line: None,
+            required_bytes: None,
+            available_bytes: None,
+        }
+    }
+
+    fn with_path(mut self, path: &Option<std::path::PathBuf>) -> Self {
+        self.path = path.as_ref().map(|p| p.display().to_string());
+        self
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<Error> for CommandError {
+    fn from(error: Error) -> Self {
+        let message = error.to_string();
+        match &error {
+            Error::Io { source, path } => {
+                let code = if source.kind() == std::io::ErrorKind::NotFound {
+                    ErrorCode::NotFound
+                } else {
+                    ErrorCode::Io
+                };
+                CommandError { details: Some(source.to_string()), ..Self::new(code, message) }.with_path(path)
+            }
+            Error::Network(_) => Self::new(ErrorCode::Network, message),
+            Error::Parse { line, path, .. } => {
+                CommandError { line: u32::try_from(*line).ok(), ..Self::new(ErrorCode::Parse, message) }.with_path(path)
+            }
+            Error::Wad { path, .. } => Self::new(ErrorCode::Wad, message).with_path(path),
+            Error::UnsupportedWadVersion { path, .. } => {
+                Self::new(ErrorCode::UnsupportedWadVersion, message).with_path(path)
+            }
+            Error::Hash(_) => Self::new(ErrorCode::Hash, message),
+            Error::BinConversion { path, .. } => Self::new(ErrorCode::BinConversion, message).with_path(path),
+            Error::InvalidInput(_) => Self::new(ErrorCode::InvalidInput, message),
+            Error::NotFound(_) => Self::new(ErrorCode::NotFound, message),
+            Error::HashtableNotReady => Self::new(ErrorCode::HashtableNotReady, message),
+            Error::Cancelled => Self::new(ErrorCode::Cancelled, message),
+            Error::OfflineMode => Self::new(ErrorCode::OfflineMode, message),
+            Error::CheckpointBusy(_) | Error::Busy(_) => Self::new(ErrorCode::Busy, message),
+            Error::ProjectReadOnly(path) => {
+                CommandError { path: Some(path.display().to_string()), ..Self::new(ErrorCode::ProjectReadOnly, message) }
+            }
+            Error::UnsafePath { path, .. } => {
+                CommandError { path: Some(path.clone()), ..Self::new(ErrorCode::UnsafePath, message) }
+            }
+            Error::GameFilesLocked { path, processes } => CommandError {
+                path: Some(path.display().to_string()),
+                details: (!processes.is_empty()).then(|| processes.join(", ")),
+                ..Self::new(ErrorCode::GameFilesLocked, message)
+            },
+            Error::RoundTripMismatch { path, divergent } => CommandError {
+                path: Some(path.display().to_string()),
+                details: Some(divergent.join("\n")),
+                ..Self::new(ErrorCode::BinConversion, message)
+            },
+            Error::InsufficientDiskSpace { required, available, path } => CommandError {
+                path: Some(path.display().to_string()),
+                required_bytes: Some(*required),
+                available_bytes: Some(*available),
+                ..Self::new(ErrorCode::InsufficientDiskSpace, message)
+            },
+        }
+    }
+}
+
+// Ad-hoc messages built in command bodies
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,6 +388,66 @@ mod tests {
         assert!(err.to_string().contains("empty path"));
     }
 
+    #[test]
+    fn test_command_error_codes_are_stable() {
+        let io = |kind| std::io::Error::new(kind, "boom");
+        let cases: Vec<(Error, &str)> = vec![
+            (Error::io_with_path(io(std::io::ErrorKind::PermissionDenied), "/a"), "IO"),
+            (Error::io_with_path(io(std::io::ErrorKind::NotFound), "/a"), "NOT_FOUND"),
+            (Error::parse_with_path(3, "bad", "/a"), "PARSE"),
+            (Error::wad_with_path("bad", "/a"), "WAD"),
+            (Error::UnsupportedWadVersion { major: 4, minor: 0, path: Some("/a".into()) }, "UNSUPPORTED_WAD_VERSION"),
+            (Error::Hash("bad".into()), "HASH"),
+            (Error::bin_conversion_with_path("bad", "/a"), "BIN_CONVERSION"),
+            (Error::InvalidInput("bad".into()), "INVALID_INPUT"),
+            (Error::NotFound("thing".into()), "NOT_FOUND"),
+            (Error::HashtableNotReady, "HASHTABLE_NOT_READY"),
+            (Error::Cancelled, "CANCELLED"),
+            (Error::OfflineMode, "OFFLINE_MODE"),
+            (Error::CheckpointBusy("restore".into()), "BUSY"),
+            (Error::Busy("repath".into()), "BUSY"),
+            (
+                Error::InsufficientDiskSpace { required: 2048, available: 1024, path: "/a".into() },
+                "INSUFFICIENT_DISK_SPACE",
+            ),
+            (Error::UnsafePath { path: "../a".into(), reason: "leaves its directory".into() }, "UNSAFE_PATH"),
+            (Error::ProjectReadOnly("/a".into()), "PROJECT_READ_ONLY"),
+            (
+                Error::GameFilesLocked { path: "/a".into(), processes: vec!["League of Legends.exe".into()] },
+                "GAME_FILES_LOCKED",
+            ),
+            (
+                Error::RoundTripMismatch { path: "/a.bin".into(), divergent: vec!["Skin0.skinScale".into()] },
+                "BIN_CONVERSION",
+            ),
+        ];
+
+        for (error, code) in cases {
+            let message = error.to_string();
+            let json = serde_json::to_value(CommandError::from(error)).unwrap();
+            assert_eq!(json["code"], code);
+            assert_eq!(json["message"], message);
+        }
+
+        let json = serde_json::to_value(CommandError::from("Task failed".to_string())).unwrap();
+        assert_eq!(json["code"], "INTERNAL");
+    }
+
+    #[test]
+    fn test_command_error_carries_location() {
+        let err = CommandError::from(Error::parse_with_path(42, "unexpected token", "/path/to/file.py"));
+        assert_eq!(err.line, Some(42));
+        assert_eq!(err.path.as_deref(), Some("/path/to/file.py"));
+
+        let err = CommandError::from(Error::io_with_path(
+            std::io::Error::new(std::io::ErrorKind::NotFound, "file not found"),
+            "/missing.bin",
+        ));
+        assert_eq!(err.code, ErrorCode::NotFound);
+        assert_eq!(err.path.as_deref(), Some("/missing.bin"));
+        assert_eq!(err.details.as_deref(), Some("file not found"));
+    }
+
     #[test]
     fn test_error_to_string_conversion() {
         let err = Error::Hash("test error".to_string());
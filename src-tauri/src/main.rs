@@ -6,9 +6,19 @@ mod core;
 mod error;
 mod state;
 
-use core::hash::get_ritoshark_hash_dir;
+use commands::startup::emit_startup_summary;
+use core::hash::{fallback_hash_dir, get_ritoshark_hash_dir};
+use core::blob::{BlobStore, BLOB_SCHEME};
+use core::export::LiveSyncRegistry;
 use core::frontend_log::{FrontendLogLayer, set_app_handle};
-use state::HashtableState;
+use core::log_capture::{init_log_file, LogCaptureLayer};
+use core::operation::{OperationKind, OperationRegistry};
+use core::settings::SETTINGS_FILE;
+use core::startup::{LeagueSummary, Pending, StartupState};
+use core::bin::search::BinTreeCache;
+use core::undo::UndoJournal;
+use core::wad::{WadMountRegistry, WadTocCache};
+use state::{HashtableState, SettingsState};
 use tauri::Manager;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
@@ -21,6 +31,7 @@ fn main() {
     tracing_subscriber::registry()
         .with(fmt::layer())
         .with(FrontendLogLayer)
+        .with(LogCaptureLayer)
         .with(filter)
         .init();
 
@@ -31,57 +42,182 @@ fn main() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .manage(HashtableState::new())
+        .manage(OperationRegistry::new())
+        .manage(SettingsState::new())
+        .manage(StartupState::new())
+        .manage(UndoJournal::new())
+        .manage(BlobStore::new())
+        .manage(LiveSyncRegistry::new())
+        .manage(WadTocCache::new())
+        .manage(BinTreeCache::new())
+        .manage(WadMountRegistry::new())
+        .register_uri_scheme_protocol(BLOB_SCHEME, |ctx, request| {
+            ctx.app_handle().state::<BlobStore>().respond(&request)
+        })
         .setup(|app| {
             // Set app handle for frontend logging
             set_app_handle(app.handle().clone());
+
+            // Mirror captured log lines to rotated files for bug reports
+            match app.path().app_log_dir() {
+                Ok(dir) => match init_log_file(&dir) {
+                    Ok(path) => tracing::info!("Logging to {}", path.display()),
+                    Err(e) => tracing::warn!("Failed to open log file: {}", e),
+                },
+                Err(e) => tracing::warn!("No app log directory: {}", e),
+            }
             
-            // Use RitoShark directory for hash files (shared with other RitoShark tools)
-            let hash_dir = get_ritoshark_hash_dir().unwrap_or_else(|e| {
-                tracing::warn!("Failed to get RitoShark hash directory: {}", e);
-                // Fallback to Tauri app data directory if RitoShark path not available
-                app.path().app_data_dir()
-                    .unwrap_or_else(|_| std::path::PathBuf::from("./hashes"))
-                    .join("hashes")
-            });
+            let settings_state = app.state::<SettingsState>().inner().clone();
+            match app.path().app_data_dir() {
+                Ok(dir) => settings_state.load_from(dir.join(SETTINGS_FILE)),
+                Err(e) => tracing::warn!("No app data directory, settings won't persist: {}", e),
+            }
+            let settings = settings_state.get();
+            core::parallel::configure(&settings.performance);
+            let startup = app.state::<StartupState>().inner().clone();
+
+            // A configured hash directory wins; otherwise use the RitoShark directory
+            // (shared with other RitoShark tools)
+            let fallback_dir = fallback_hash_dir(
+                &app.path().app_data_dir().unwrap_or_else(|_| std::path::PathBuf::from("./hashes")),
+            );
+            let hash_dir = match settings.hash_dir {
+                Some(dir) => dir,
+                None => match get_ritoshark_hash_dir() {
+                    Ok(dir) => {
+                        // Lists kept in the fallback while there was no RitoShark
+                        // directory move there instead of being left to diverge
+                        commands::hash::migrate_fallback_hashes(&fallback_dir, &dir);
+                        dir
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to get RitoShark hash directory: {}", e);
+                        // Fallback to Tauri app data directory if RitoShark path not available
+                        fallback_dir
+                    }
+                },
+            };
             
             tracing::info!("Hash directory: {}", hash_dir.display());
             
             // Set the hash directory for lazy loading (hashtable will load on first use)
             let hashtable_state = app.state::<HashtableState>().inner().clone();
             hashtable_state.set_hash_dir(hash_dir.clone());
+            commands::hash::emit_hashtable_ready(app.handle().clone(), &hashtable_state);
+            commands::hash::emit_hashtable_load_progress(app.handle().clone(), &hashtable_state);
+            core::bin::set_bin_hash_dir(hash_dir.clone());
+
+            // Pick up lists other RitoShark tools update while Flint is open
+            if let Some(watcher) = commands::hash::start_hash_watcher(app.handle().clone(), hashtable_state.clone()) {
+                app.manage(watcher);
+            }
             
-            // Spawn background task to download hashes (but NOT load them - lazy loading handles that)
+            // Spawn background task to download hashes. Loading waits for the first
+            // command that resolves hashes unless the user opted into eager loading.
+            let eager_hash_loading = settings.eager_hash_loading;
+            let handle = app.handle().clone();
+            let hash_startup = startup.clone();
+            let hash_sources = settings.hash_sources.clone();
+            let hash_categories = settings.hash_categories.clone();
+            let league_path = settings.league_path.clone();
+            // Registered so `cancel_hash_download` can stop it on a stalled connection;
+            // offline mode skips the update entirely
+            let download = (!settings.offline_mode)
+                .then(|| app.state::<OperationRegistry>().start(OperationKind::Download, None));
             tauri::async_runtime::spawn(async move {
-                tracing::info!("Checking for hash updates...");
-                match core::hash::download_hashes(&hash_dir, false).await {
-                    Ok(stats) => {
+                if let Some(download) = download {
+                    tracing::info!("Checking for hash updates...");
+                    let progress = |progress: &core::hash::HashDownloadProgress| {
+                        commands::hash::emit_download_progress(&handle, None, progress)
+                    };
+                    let cancel = download.token();
+                    let result = core::hash::download_hashes(&hash_dir, false, &hash_sources, &hash_categories, Some(&cancel), progress).await;
+                    match &result {
+                        Ok(stats) if stats.cancelled => download.finish::<(), _>(&Err(error::Error::Cancelled)),
+                        _ => download.finish(&result),
+                    }
+                    if let Ok(stats) = &result {
+                        core::hash::record_after_download(&hash_dir, league_path.as_deref(), stats);
                         if stats.downloaded > 0 {
-                            tracing::info!(
-                                "Hash update: {} downloaded, {} up-to-date",
-                                stats.downloaded, stats.skipped
-                            );
-                        } else {
-                            tracing::debug!("Hashes up-to-date ({} files)", stats.skipped);
+                            let _ = tauri::async_runtime::spawn_blocking(core::bin::reload_bin_hashes).await;
                         }
                     }
-                    Err(e) => {
-                        tracing::warn!("Failed to update hashes (will use existing): {}", e);
+                    match &result {
+                        Ok(stats) if stats.cancelled => {
+                            tracing::info!("Hash update cancelled after {} downloaded", stats.downloaded);
+                        }
+                        Ok(stats) => {
+                            if stats.downloaded > 0 {
+                                tracing::info!(
+                                    "Hash update: {} downloaded, {} not modified, {} up-to-date by age",
+                                    stats.downloaded, stats.not_modified, stats.age_skipped
+                                );
+                            } else {
+                                tracing::debug!("Hashes up-to-date ({} files)", stats.skipped);
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to update hashes (will use existing): {}", e);
+                        }
                     }
+                    hash_startup.set_hash_update(result.into());
+                    emit_startup_summary(&handle, "startup-updated");
+                } else {
+                    // Nothing on the network is tried, so startup doesn't wait on timeouts
+                    tracing::info!("Offline mode: using the hash lists on disk");
+                    hash_startup.set_hash_update(Err::<core::hash::DownloadStats, _>(error::Error::OfflineMode).into());
+                    emit_startup_summary(&handle, "startup-updated");
+                }
+
+                if eager_hash_loading {
+                    hashtable_state.ensure_loaded().await;
+                    emit_startup_summary(&handle, "startup-updated");
                 }
-                // NOTE: Hashtable is NOT loaded here anymore - lazy loading on first use
             });
-            
+
+            // League detection can take a while (process scan, registry), so it
+            // reports through startup-updated instead of holding up app-ready
+            let handle = app.handle().clone();
+            let configured_league = settings.league_path.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                let league = core::league::resolve_league_installation(configured_league.as_deref())
+                    .map(LeagueSummary::new);
+                startup.set_league(Pending::from(league));
+                emit_startup_summary(&handle, "startup-updated");
+            });
+
+            emit_startup_summary(app.handle(), "app-ready");
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::hash::download_hashes,
+            commands::hash::cancel_hash_download,
+            commands::hash::import_hash_file,
+            commands::hash::add_hash_mapping,
+            commands::hash::compute_path_hash,
             commands::hash::get_hash_status,
             commands::hash::reload_hashes,
+            commands::hash::migrate_hash_directory,
             commands::wad::read_wad,
             commands::wad::get_wad_chunks,
             commands::wad::load_all_wad_chunks,
             commands::wad::extract_wad,
+            commands::wad::preview_extraction,
+            commands::wad::export_unknown_hashes,
+            commands::wad::export_wad_manifest,
+            commands::wad::repack_wad,
+            commands::wad::guess_unknown_hashes,
+            commands::wad::diff_wads,
+            commands::wad::find_in_wads,
+            commands::wad::locate_game_path,
             commands::wad::read_wad_chunk_data,
+            commands::wad::cancel_extraction,
+            commands::wad::verify_wad,
+            commands::wad::mount_champion_wads,
+            commands::wad::lookup_mounted_chunk,
+            commands::wad::read_mounted_chunk,
+            commands::wad::unmount_wads,
             commands::wad::scan_game_wads,
             commands::bin::convert_bin_to_text,
             commands::bin::convert_bin_to_json,
@@ -91,23 +227,45 @@ fn main() {
             commands::bin::parse_bin_file_to_text,
             commands::bin::read_or_convert_bin,
             commands::bin::save_ritobin_to_bin,
+            commands::bin::get_bin_property,
+            commands::bin::set_bin_property,
+            commands::bin::search_bins,
+            commands::bin::merge_bins,
+            commands::bin::extract_bin_colors,
             // League detection commands
 
             commands::league::detect_league,
             commands::league::validate_league,
+            commands::league::check_game_running,
             // Project management commands
             commands::project::create_project,
             commands::project::open_project,
+            commands::project::export_project_hashes,
             commands::project::save_project,
+            commands::project::refresh_project_display_name,
             commands::project::list_project_files,
+            commands::project::get_disk_usage,
             commands::project::preconvert_project_bins,
+            commands::project::convert_project_bins,
+            commands::project::setup_project_content,
+            commands::project::rebase_project,
+            commands::project::recolor_project,
+            commands::project::apply_bin_recolor,
+            commands::project::rename_vfx_system,
+            // Path mapping commands
+            commands::mapping::export_path_mappings,
+            commands::mapping::import_path_mappings,
             // Champion discovery commands
             commands::champion::discover_champions,
             commands::champion::get_champion_skins,
             commands::champion::search_champions,
             // Validation commands
             commands::validation::extract_asset_references,
+            commands::validation::find_project_references,
+            commands::validation::validate_project_assets,
             commands::validation::validate_assets,
+            commands::validation::validate_texture_overrides,
+            commands::validation::validate_map_vfx,
             // File commands (preview system)
             commands::file::read_file_bytes,
             commands::file::read_file_info,
@@ -118,12 +276,23 @@ fn main() {
             commands::file::recolor_folder,
             commands::file::colorize_image,
             commands::file::colorize_folder,
+            commands::file::read_stringtable,
+            commands::file::set_stringtable_entry,
             // Export commands
             commands::export::repath_project_cmd,
+            commands::export::list_quarantine,
+            commands::export::restore_quarantine,
+            commands::export::purge_quarantine,
+            commands::export::detect_repath_prefix,
+            commands::export::strip_repath_prefix,
             commands::export::export_fantome,
+            commands::export::export_bundle,
             commands::export::export_modpkg,
             commands::export::get_fantome_filename,
             commands::export::get_export_preview,
+            commands::export::check_mod_conflicts,
+            commands::export::live_sync,
+            commands::export::stop_live_sync,
             // Mesh commands (3D preview)
             commands::mesh::read_skn_mesh,
             commands::mesh::read_scb_mesh,
@@ -138,11 +307,38 @@ fn main() {
             commands::updater::download_and_install_update,
             // Checkpoint commands
             commands::checkpoint::create_checkpoint,
+            commands::checkpoint::cancel_checkpoint_creation,
             commands::checkpoint::list_checkpoints,
+            commands::checkpoint::update_checkpoint,
             commands::checkpoint::restore_checkpoint,
+            commands::checkpoint::get_checkpoint_graph,
+            commands::checkpoint::verify_checkpoints,
             commands::checkpoint::compare_checkpoints,
             commands::checkpoint::delete_checkpoint,
+            commands::checkpoint::prune_checkpoints,
+            commands::checkpoint::get_checkpoint_lock_status,
+            commands::checkpoint::break_checkpoint_lock,
+            commands::checkpoint::get_checkpoint_storage_stats,
+            commands::checkpoint::export_checkpoint,
+            commands::checkpoint::import_checkpoint,
             commands::checkpoint::read_checkpoint_file,
+            // Long-running operation tracking
+            commands::operation::list_operations,
+            commands::operation::cancel_operation,
+            commands::operation::get_project_busy_status,
+            commands::operation::get_last_operation_metrics,
+            // Session undo for small edits
+            commands::undo::undo_last,
+            commands::undo::get_undo_history,
+            // Settings commands
+            commands::settings::get_settings,
+            commands::settings::update_settings,
+            commands::settings::set_offline_mode,
+            // Log commands
+            commands::log::get_recent_logs,
+            commands::log::get_log_file_path,
+            commands::diagnostics::create_diagnostic_bundle,
+            commands::startup::get_startup_summary,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
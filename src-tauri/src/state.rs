@@ -1,14 +1,70 @@
-use parking_lot::Mutex;
-use std::path::PathBuf;
-use std::sync::{Arc, OnceLock};
-use crate::core::hash::Hashtable;
+use parking_lot::{Mutex, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::core::hash::{HashLoadProgress, Hashtable};
+use crate::core::settings::{self, AppSettings};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
 
-/// Global lazy-loaded hashtable — only initialized on the first call to `get_hashtable`.
-static LAZY_HASHTABLE: OnceLock<Arc<Hashtable>> = OnceLock::new();
+/// Where the hashtable is in its lifecycle
+#[derive(Clone)]
+pub enum HashtableStatus {
+    NotLoaded,
+    Loading,
+    Ready(Arc<Hashtable>),
+}
+
+/// `HashtableStatus` without the table, as reported to the frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashtableLoadState {
+    NotLoaded,
+    Loading,
+    Ready,
+}
+
+/// How long commands that need resolved names wait for a loading hashtable
+pub const HASHTABLE_WAIT: Duration = Duration::from_secs(30);
 
-/// Holds the hash directory path so the hashtable can be loaded on demand.
+/// Called with the entry count whenever a full load finishes
+type ReadyListener = Box<dyn Fn(usize) + Send + Sync>;
+
+/// Called as a full load parses the lists
+type ProgressListener = Box<dyn Fn(HashLoadProgress) + Send + Sync>;
+
+/// What `reload_files` or `reload_hashes` changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashReload {
+    pub previous_count: usize,
+    pub loaded_count: usize,
+    /// Hashes that were added or removed or now resolve to another path
+    pub changed: usize,
+}
+
+/// Holds the hash directory path and the lazily loaded hashtable.
+///
+/// Nothing is loaded until a command that resolves hashes calls
+/// `ensure_loaded` (or `get_hashtable` from blocking code); commands that
+/// don't resolve hashes never pay for the load.
+///
+/// The table is shared as `Arc<Hashtable>`: callers get a cheap pointer copy
+/// and keep a consistent snapshot even if a reload swaps in a new table
+/// while they are still resolving paths.
 #[derive(Clone)]
-pub struct HashtableState(pub Arc<Mutex<Option<PathBuf>>>);
+pub struct HashtableState {
+    hash_dir: Arc<Mutex<Option<PathBuf>>>,
+    status: Arc<RwLock<HashtableStatus>>,
+    /// Serializes loads so concurrent first callers don't parse the files twice
+    load_lock: Arc<Mutex<()>>,
+    /// When the last full load started; it saw every change made before then
+    loaded_at: Arc<Mutex<Option<Instant>>>,
+    /// Lists outside the hash directory merged over every load, such as the
+    /// hashes of opened projects
+    overlays: Arc<Mutex<Vec<PathBuf>>>,
+    on_ready: Arc<Mutex<Option<ReadyListener>>>,
+    on_progress: Arc<Mutex<Option<ProgressListener>>>,
+}
 
 impl Default for HashtableState {
     fn default() -> Self {
@@ -18,35 +74,278 @@ impl Default for HashtableState {
 
 impl HashtableState {
     pub fn new() -> Self {
-        Self(Arc::new(Mutex::new(None)))
+        Self {
+            hash_dir: Arc::new(Mutex::new(None)),
+            status: Arc::new(RwLock::new(HashtableStatus::NotLoaded)),
+            load_lock: Arc::new(Mutex::new(())),
+            loaded_at: Arc::new(Mutex::new(None)),
+            overlays: Arc::new(Mutex::new(Vec::new())),
+            on_ready: Arc::new(Mutex::new(None)),
+            on_progress: Arc::new(Mutex::new(None)),
+        }
     }
 
     pub fn set_hash_dir(&self, path: PathBuf) {
-        *self.0.lock() = Some(path);
+        *self.hash_dir.lock() = Some(path);
+    }
+
+    pub fn hash_dir(&self) -> Option<PathBuf> {
+        self.hash_dir.lock().clone()
+    }
+
+    /// The table if it has already been loaded; never triggers a load
+    pub fn peek(&self) -> Option<Arc<Hashtable>> {
+        match &*self.status.read() {
+            HashtableStatus::Ready(ht) => Some(Arc::clone(ht)),
+            _ => None,
+        }
+    }
+
+    /// Call `listener` with the entry count whenever a full load finishes
+    pub fn on_ready(&self, listener: impl Fn(usize) + Send + Sync + 'static) {
+        *self.on_ready.lock() = Some(Box::new(listener));
+    }
+
+    /// Call `listener` every `PROGRESS_INTERVAL` entries of a full load
+    pub fn on_progress(&self, listener: impl Fn(HashLoadProgress) + Send + Sync + 'static) {
+        *self.on_progress.lock() = Some(Box::new(listener));
     }
 
-    /// Returns the loaded hashtable, lazily initializing it on the first call.
+    fn notify_ready(&self, count: usize) {
+        if let Some(listener) = &*self.on_ready.lock() {
+            listener(count);
+        }
+    }
+
+    pub fn load_state(&self) -> HashtableLoadState {
+        match &*self.status.read() {
+            HashtableStatus::NotLoaded => HashtableLoadState::NotLoaded,
+            HashtableStatus::Loading => HashtableLoadState::Loading,
+            HashtableStatus::Ready(_) => HashtableLoadState::Ready,
+        }
+    }
+
+    /// Returns the loaded hashtable, loading it on the first call.
+    ///
+    /// Blocks while the files are parsed; async commands should use
+    /// `ensure_loaded` instead. Returns `None` until a hash directory is set.
     pub fn get_hashtable(&self) -> Option<Arc<Hashtable>> {
         // Fast path — already loaded.
-        if let Some(ht) = LAZY_HASHTABLE.get() {
-            return Some(Arc::clone(ht));
+        if let Some(ht) = self.peek() {
+            return Some(ht);
         }
 
-        let hash_dir = self.0.lock().clone()?;
+        let hash_dir = self.hash_dir.lock().clone()?;
+
+        let _loading = self.load_lock.lock();
+        // Another caller may have finished loading while we waited
+        if let Some(ht) = self.peek() {
+            return Some(ht);
+        }
+
+        *self.status.write() = HashtableStatus::Loading;
+        *self.loaded_at.lock() = Some(Instant::now());
+        let ht = Arc::new(self.load_with_overlays(&hash_dir));
+        *self.status.write() = HashtableStatus::Ready(Arc::clone(&ht));
+        self.notify_ready(ht.len());
+        Some(ht)
+    }
 
-        let ht = LAZY_HASHTABLE.get_or_init(|| {
-            tracing::info!("Lazy-loading hashtable from {}…", hash_dir.display());
-            match Hashtable::from_directory(&hash_dir) {
-                Ok(ht)  => { tracing::info!("Hashtable ready: {} entries", ht.len()); Arc::new(ht) }
-                Err(e)  => { tracing::warn!("Hashtable load failed: {}", e); Arc::new(Hashtable::empty()) }
+    /// Async form of `get_hashtable`: the load runs on the blocking pool and
+    /// concurrent callers wait for the same load.
+    pub async fn ensure_loaded(&self) -> Option<Arc<Hashtable>> {
+        if let Some(ht) = self.peek() {
+            return Some(ht);
+        }
+
+        let state = self.clone();
+        tokio::task::spawn_blocking(move || state.get_hashtable())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Hashtable load task failed: {}", e);
+                None
+            })
+    }
+
+    /// `ensure_loaded` for commands whose output is wrong without hashes:
+    /// waits up to `timeout` and fails with `HashtableNotReady` rather than
+    /// leaving names unresolved. A load still running keeps going.
+    pub async fn wait_ready(&self, timeout: Duration) -> Result<Arc<Hashtable>> {
+        match tokio::time::timeout(timeout, self.ensure_loaded()).await {
+            Ok(Some(ht)) => Ok(ht),
+            Ok(None) => Err(Error::HashtableNotReady),
+            Err(_) => {
+                tracing::warn!("Hashtable still loading after {:?}", timeout);
+                Err(Error::HashtableNotReady)
             }
-        });
+        }
+    }
+
+    /// Re-read the hash files and swap in the new table.
+    ///
+    /// The new table is built before the swap, so lookups keep using the old
+    /// snapshot until it is ready. Returns the new entry count.
+    pub fn reload(&self) -> Option<usize> {
+        let hash_dir = self.hash_dir.lock().clone()?;
+
+        let _loading = self.load_lock.lock();
+        *self.loaded_at.lock() = Some(Instant::now());
+        let ht = Arc::new(self.load_with_overlays(&hash_dir));
+        let count = ht.len();
+        *self.status.write() = HashtableStatus::Ready(ht);
+        self.notify_ready(count);
+        Some(count)
+    }
+
+    /// Re-read hash files that changed on disk into the loaded table,
+    /// leaving the other lists' entries as they are.
+    ///
+    /// `changed_at` is when the last of the changes happened. Returns `None`
+    /// when there was nothing to apply: the table isn't loaded yet (the first
+    /// load reads the new files anyway) or a full load that started after the
+    /// changes already has them.
+    pub fn reload_files(&self, files: &[PathBuf], changed_at: Instant) -> Option<HashReload> {
+        // Shares the lock with full loads, so a watcher reload and
+        // `reload_hashes` never run at the same time
+        let _loading = self.load_lock.lock();
+        let current = self.peek()?;
+        if self.loaded_at.lock().is_some_and(|loaded_at| loaded_at > changed_at) {
+            return None;
+        }
+
+        let started = Instant::now();
+        let (ht, changed) = current.with_files(files);
+        let reload = HashReload { previous_count: current.len(), loaded_count: ht.len(), changed };
+        tracing::info!(
+            "Merged {} changed hash file(s): {} → {} entries, {} changed, in {:?}",
+            files.len(), reload.previous_count, reload.loaded_count, changed, started.elapsed()
+        );
+        *self.status.write() = HashtableStatus::Ready(Arc::new(ht));
+        Some(reload)
+    }
+
+    /// Merge `file` over the hash lists, now if the table is loaded and after
+    /// every later load. The user list still wins over it.
+    pub fn add_overlay(&self, file: PathBuf) {
+        {
+            let mut overlays = self.overlays.lock();
+            if !overlays.contains(&file) {
+                overlays.push(file.clone());
+            }
+        }
+
+        let _loading = self.load_lock.lock();
+        let Some(current) = self.peek() else {
+            return;
+        };
+        let (ht, changed) = current.with_files(&[file]);
+        tracing::info!("Merged hash overlay: {} → {} entries, {} changed", current.len(), ht.len(), changed);
+        *self.status.write() = HashtableStatus::Ready(Arc::new(ht));
+    }
 
-        Some(Arc::clone(ht))
+    fn load_with_overlays(&self, hash_dir: &Path) -> Hashtable {
+        let ht = self.load(hash_dir);
+        let overlays = self.overlays.lock().clone();
+        if overlays.is_empty() {
+            return ht;
+        }
+        ht.with_files(&overlays).0
+    }
+
+    fn load(&self, hash_dir: &Path) -> Hashtable {
+        tracing::info!("Loading hashtable from {}…", hash_dir.display());
+        let started = Instant::now();
+        let progress = |progress: HashLoadProgress| {
+            tracing::debug!("Hashtable load: {} entries", progress.entries);
+            if let Some(listener) = &*self.on_progress.lock() {
+                listener(progress);
+            }
+        };
+        match Hashtable::from_directory_with_progress(hash_dir, progress) {
+            Ok(ht) => {
+                tracing::info!(
+                    "Hashtable ready: {} entries, {} MB, in {:?}",
+                    ht.len(),
+                    ht.heap_size() / (1024 * 1024),
+                    started.elapsed()
+                );
+                ht
+            }
+            Err(e) => {
+                tracing::warn!("Hashtable load failed: {}", e);
+                Hashtable::empty()
+            }
+        }
     }
 
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
-        LAZY_HASHTABLE.get().map_or(0, |h| h.len())
+        self.peek().map_or(0, |h| h.len())
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Holds the loaded application settings and the file they persist to.
+#[derive(Clone, Default)]
+pub struct SettingsState {
+    path: Arc<Mutex<Option<PathBuf>>>,
+    settings: Arc<RwLock<AppSettings>>,
+}
+
+impl SettingsState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load settings from `path` and persist future updates there.
+    ///
+    /// An unreadable file is moved aside rather than overwritten, so a bad
+    /// edit never costs the user their other settings.
+    pub fn load_from(&self, path: PathBuf) {
+        let loaded = settings::load(&path).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load settings, using defaults: {}", e);
+            let backup = path.with_extension("json.bak");
+            if let Err(e) = std::fs::rename(&path, &backup) {
+                tracing::warn!("Failed to back up {}: {}", path.display(), e);
+            }
+            AppSettings::default()
+        });
+        *self.settings.write() = loaded;
+        *self.path.lock() = Some(path);
+    }
+
+    pub fn get(&self) -> AppSettings {
+        self.settings.read().clone()
+    }
+
+    /// Fails with `OfflineMode` when offline mode is on; network commands
+    /// call this first so they fail at once instead of timing out
+    pub fn ensure_online(&self) -> Result<()> {
+        if self.settings.read().offline_mode {
+            return Err(Error::OfflineMode);
+        }
+        Ok(())
+    }
+
+    /// Apply a partial update and persist it.
+    ///
+    /// Returns the new settings, or `None` when the patch changed nothing.
+    pub fn update(&self, patch: &serde_json::Value) -> Result<Option<AppSettings>> {
+        // Held across the save so concurrent updates are written in order
+        let mut current = self.settings.write();
+        let updated = settings::apply_patch(&current, patch)?;
+        if updated == *current {
+            return Ok(None);
+        }
+
+        if let Some(path) = self.path.lock().as_ref() {
+            settings::save(path, &updated)?;
+        }
+        *current = updated.clone();
+        Ok(Some(updated))
     }
 }
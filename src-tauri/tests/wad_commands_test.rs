@@ -1,4 +1,6 @@
 use flint::commands::wad::{read_wad, WadInfo, ChunkInfo, ExtractionResult};
+use flint::core::wad::ChunkKind;
+use flint::error::{CommandError, ErrorCode};
 
 #[tokio::test]
 async fn test_read_wad_nonexistent_file() {
@@ -11,7 +13,8 @@ async fn test_read_wad_nonexistent_file() {
     
     // Verify error message is meaningful
     if let Err(e) = result {
-        assert!(e.contains("IO error") || e.contains("WAD error") || e.contains("No such file"));
+        assert_eq!(e.code, ErrorCode::NotFound);
+        assert!(e.message.contains("IO error") || e.message.contains("No such file"));
     }
 }
 
@@ -20,6 +23,7 @@ async fn test_wad_info_serialization() {
     // Test that WadInfo can be serialized/deserialized
     let info = WadInfo {
         path: "test.wad".to_string(),
+        version: "3.1".to_string(),
         chunk_count: 42,
     };
     
@@ -27,6 +31,7 @@ async fn test_wad_info_serialization() {
     let deserialized: WadInfo = serde_json::from_str(&json).unwrap();
     
     assert_eq!(deserialized.path, "test.wad");
+    assert_eq!(deserialized.version, "3.1");
     assert_eq!(deserialized.chunk_count, 42);
 }
 
@@ -34,19 +39,25 @@ async fn test_wad_info_serialization() {
 async fn test_chunk_info_serialization() {
     // Test that ChunkInfo can be serialized/deserialized
     let info = ChunkInfo {
-        path_hash: "1a2b3c4d5e6f7a8b".to_string(),
-        resolved_path: Some("characters/aatrox/aatrox.bin".to_string()),
+        hash: "1a2b3c4d5e6f7a8b".to_string(),
+        path: Some("characters/aatrox/aatrox.bin".to_string()),
+        size: 2048,
         compressed_size: 1024,
-        uncompressed_size: 2048,
+        compression: "zstd".to_string(),
+        checksum: "0000000000000000".to_string(),
+        subchunked: false,
+        is_duplicate: false,
+        kind: ChunkKind::Bin,
     };
     
     let json = serde_json::to_string(&info).unwrap();
     let deserialized: ChunkInfo = serde_json::from_str(&json).unwrap();
     
-    assert_eq!(deserialized.path_hash, "1a2b3c4d5e6f7a8b");
-    assert_eq!(deserialized.resolved_path, Some("characters/aatrox/aatrox.bin".to_string()));
+    assert_eq!(deserialized.hash, "1a2b3c4d5e6f7a8b");
+    assert_eq!(deserialized.path, Some("characters/aatrox/aatrox.bin".to_string()));
     assert_eq!(deserialized.compressed_size, 1024);
-    assert_eq!(deserialized.uncompressed_size, 2048);
+    assert_eq!(deserialized.size, 2048);
+    assert_eq!(deserialized.kind, ChunkKind::Bin);
 }
 
 #[tokio::test]
@@ -55,6 +66,17 @@ async fn test_extraction_result_serialization() {
     let result = ExtractionResult {
         extracted_count: 10,
         failed_count: 2,
+        matched_count: 12,
+        skipped_count: 0,
+        unknown_hashes: Vec::new(),
+        bytes_written: 4096,
+        cancelled: false,
+        added_count: 10,
+        updated_count: 0,
+        unchanged_count: 0,
+        edited_count: 0,
+        timings: Vec::new(),
+        skin: None,
     };
     
     let json = serde_json::to_string(&result).unwrap();
@@ -72,7 +94,7 @@ fn test_wad_commands_api_exists() {
     // The fact that this compiles means our API is correct
     fn _check_api() {
         // These functions should exist and be async
-        let _: std::pin::Pin<Box<dyn std::future::Future<Output = Result<WadInfo, String>>>> = 
+        let _: std::pin::Pin<Box<dyn std::future::Future<Output = Result<WadInfo, CommandError>>>> = 
             Box::pin(read_wad("test.wad".to_string()));
     }
 }
@@ -1,4 +1,4 @@
-use flint::core::wad::extractor::{extract_chunk, extract_all};
+use flint::core::wad::extractor::{extract_chunk, extract_chunks};
 use std::path::PathBuf;
 
 #[test]
@@ -13,24 +13,23 @@ fn test_extractor_api_exists() {
         
         // This won't run, just checking the API compiles
         if false {
-            let reader = WadReader::open("test.wad").unwrap();
-            let mut wad = reader.into_wad();
-            // Get a chunk reference before borrowing wad mutably
-            let chunk_copy = wad.chunks().values().next().cloned();
+            let mut reader = WadReader::open("test.wad").unwrap();
+            // Get a chunk reference before borrowing the reader mutably
+            let chunk_copy = reader.chunks().values().next().cloned();
             if let Some(chunk) = chunk_copy {
-                let _ = extract_chunk(&mut wad, &chunk, "output.bin", None);
+                let _ = extract_chunk(&mut reader, &chunk, "output.bin", None);
             }
         }
     }
     
-    fn _check_extract_all_api() {
+    fn _check_extract_chunks_api() {
         use flint::core::wad::reader::WadReader;
         
         // This won't run, just checking the API compiles
         if false {
             let reader = WadReader::open("test.wad").unwrap();
-            let mut wad = reader.into_wad();
-            let _ = extract_all(&mut wad, "output_dir", None);
+            let chunks: Vec<_> = reader.chunks().iter().map(|(hash, chunk)| (*hash, *chunk)).collect();
+            let _ = extract_chunks("test.wad", &chunks, "output_dir", None, None, |_| {});
         }
     }
 }
@@ -49,8 +48,8 @@ fn test_extract_chunk_nonexistent_file() {
 }
 
 #[test]
-fn test_extract_all_nonexistent_file() {
-    // Test that extract_all properly handles errors
+fn test_extract_chunks_nonexistent_file() {
+    // Test that extract_chunks properly handles errors
     // We can't test the success case without a real WAD file
     
     // This test just verifies error handling works
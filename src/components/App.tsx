@@ -7,6 +7,7 @@ import { useAppState } from '../lib/state';
 import { initShortcuts, registerShortcut } from '../lib/utils';
 import * as api from '../lib/api';
 import * as updater from '../lib/updater';
+import type { HashesUpdated, HashtableLoadProgress, HashtableReady, Project, StartupSummary } from '../lib/types';
 
 import { TopBar } from './TopBar';
 import { LeftPanel } from './FileTree';
@@ -81,37 +82,55 @@ export const App: React.FC = () => {
             }
         });
 
-        // Load initial data
+        // Load initial data; the summary is also pushed through app-ready and
+        // startup-updated as background startup tasks finish
+        let unlisten: Array<() => void> = [];
+        let disposed = false;
+        (async () => {
+            const { listen } = await import('@tauri-apps/api/event');
+            const stops = await Promise.all(
+                ['app-ready', 'startup-updated'].map(event =>
+                    listen<StartupSummary>(event, e => applyStartupSummary(e.payload))
+                )
+            );
+            stops.push(await listen<HashtableLoadProgress>('hashtable-load-progress', e => {
+                const { entries, bytes_read, total_bytes } = e.payload;
+                const percent = total_bytes > 0 ? Math.round((bytes_read / total_bytes) * 100) : 0;
+                setWorking(`Loading hashes… ${entries.toLocaleString()} entries (${percent}%)`);
+            }));
+            stops.push(await listen<HashtableReady>('hashtable-ready', e => {
+                dispatch({ type: 'SET_STATE', payload: { hashesLoaded: true, hashCount: e.payload.loaded_count } });
+                if (stateRef.current.statusMessage.startsWith('Loading hashes')) setReady();
+            }));
+            stops.push(await listen<HashesUpdated>('hashes-updated', e => {
+                dispatch({ type: 'SET_STATE', payload: { hashesLoaded: true, hashCount: e.payload.loaded_count } });
+                console.log(`[Flint] Hash lists updated on disk (${e.payload.files.join(', ')}): ${e.payload.delta >= 0 ? '+' : ''}${e.payload.delta.toLocaleString()} entries`);
+            }));
+            // A new project's skin name is looked up after it's created
+            stops.push(await listen<Project>('project-display-name', e => {
+                const tab = stateRef.current.openTabs.find(t => t.projectPath === e.payload.project_path);
+                if (tab) dispatch({ type: 'UPDATE_TAB', payload: { tabId: tab.id, updates: { project: e.payload } } });
+            }));
+            if (disposed) stops.forEach(stop => stop());
+            else unlisten = stops;
+        })();
         loadInitialData();
-        // Clean stale projects
-        cleanStaleProjects();
+
+        return () => {
+            disposed = true;
+            unlisten.forEach(stop => stop());
+        };
     }, []); // eslint-disable-line react-hooks/exhaustive-deps
 
     const loadInitialData = async () => {
         try {
-            const hashStatus = await api.getHashStatus();
-            dispatch({
-                type: 'SET_STATE',
-                payload: {
-                    hashesLoaded: hashStatus.loaded_count > 0,
-                    hashCount: hashStatus.loaded_count,
-                },
-            });
-
-            if (hashStatus.loaded_count === 0) {
-                pollHashStatus();
-            }
+            const summary = await api.getStartupSummary();
+            applyStartupSummary(summary);
+            cleanStaleProjects(summary);
 
-            if (!state.leaguePath) {
-                try {
-                    const leagueResult = await api.detectLeague();
-                    if (leagueResult.path) {
-                        dispatch({ type: 'SET_STATE', payload: { leaguePath: leagueResult.path } });
-                        console.log('[Flint] Auto-detected League path:', leagueResult.path);
-                    }
-                } catch {
-                    console.log('[Flint] League auto-detection failed');
-                }
+            // Hashes load on first use, so only a load already in flight is worth waiting for
+            if (summary.hashes.state === 'loading') {
+                pollHashStatus();
             }
 
             // Check for updates after a short delay (don't block startup)
@@ -121,6 +140,24 @@ export const App: React.FC = () => {
         }
     };
 
+    const applyStartupSummary = (summary: StartupSummary) => {
+        dispatch({
+            type: 'SET_STATE',
+            payload: {
+                hashesLoaded: summary.hashes.loaded_count > 0,
+                hashCount: summary.hashes.loaded_count,
+            },
+        });
+
+        const league = summary.league;
+        if (league.status === 'ready' && !stateRef.current.leaguePath) {
+            dispatch({ type: 'SET_STATE', payload: { leaguePath: league.value.installation.path } });
+            console.log('[Flint] Auto-detected League path:', league.value.installation.path);
+        } else if (league.status === 'failed') {
+            console.log('[Flint] League auto-detection failed:', league.value);
+        }
+    };
+
     const pollHashStatus = async () => {
         const maxAttempts = 30;
         let attempts = 0;
@@ -128,7 +165,7 @@ export const App: React.FC = () => {
         const poll = async () => {
             try {
                 const status = await api.getHashStatus();
-                if (status.loaded_count > 0) {
+                if (status.state === 'ready') {
                     dispatch({
                         type: 'SET_STATE',
                         payload: { hashesLoaded: true, hashCount: status.loaded_count },
@@ -138,7 +175,7 @@ export const App: React.FC = () => {
                 }
 
                 attempts++;
-                if (attempts < maxAttempts) {
+                if (status.state === 'loading' && attempts < maxAttempts) {
                     setTimeout(poll, 1000);
                 }
             } catch (error) {
@@ -155,6 +192,10 @@ export const App: React.FC = () => {
             console.log('[Flint] Auto-updates disabled, skipping update check');
             return;
         }
+        if (stateRef.current.offlineMode) {
+            console.log('[Flint] Offline mode, skipping update check');
+            return;
+        }
 
         try {
             console.log('[Flint] Checking for updates...');
@@ -189,26 +230,14 @@ export const App: React.FC = () => {
         }
     };
 
-    const cleanStaleProjects = async () => {
-        try {
-            const recent = stateRef.current.recentProjects;
-            if (recent.length === 0) return;
+    const cleanStaleProjects = (summary: StartupSummary) => {
+        const missing = new Set(
+            summary.recent_projects.filter(project => !project.exists).map(project => project.path)
+        );
+        if (missing.size === 0) return;
 
-            // Validate all projects in parallel instead of sequentially
-            const results = await Promise.allSettled(
-                recent.map(project => api.listProjectFiles(project.path).then(() => project))
-            );
-
-            const validProjects = results
-                .filter((r): r is PromiseFulfilledResult<typeof recent[number]> => r.status === 'fulfilled')
-                .map(r => r.value);
-
-            if (validProjects.length !== recent.length) {
-                dispatch({ type: 'SET_RECENT_PROJECTS', payload: validProjects });
-            }
-        } catch (error) {
-            console.error('[Flint] Failed to clean stale projects:', error);
-        }
+        const recent = stateRef.current.recentProjects;
+        dispatch({ type: 'SET_RECENT_PROJECTS', payload: recent.filter(project => !missing.has(project.path)) });
     };
 
     // Resizer handling
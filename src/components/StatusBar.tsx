@@ -173,6 +173,11 @@ export const LogPanel: React.FC = () => {
                 </span>
             </div>
             <div className="log-panel__right">
+                {state.offlineMode && (
+                    <span className="log-panel__badge" title="Offline mode: no hash updates or network lookups">
+                        Offline
+                    </span>
+                )}
                 <span className="log-panel__hint">
                     <TerminalIcon /> {logs.length}
                 </span>
@@ -101,7 +101,7 @@ export const TopBar: React.FC = () => {
             showToast('success', `Exported to ${result.path}`);
 
             // Auto-checkpoint after export
-            api.createCheckpoint(currentProjectPath, `Auto-checkpoint: Exported to ${format}`).catch(e => {
+            api.createCheckpoint(currentProjectPath, `Auto-checkpoint: Exported to ${format}`, ['auto', 'export']).catch(e => {
                 console.warn('Auto-checkpoint failed:', e);
             });
 
@@ -4,12 +4,13 @@
  * Replaces the FileTree panel when a WAD extract session is active.
  */
 
-import React, { useMemo, useCallback, useState } from 'react';
+import React, { useMemo, useCallback, useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { useAppState } from '../lib/state';
 import * as api from '../lib/api';
 import { open } from '@tauri-apps/plugin-dialog';
 import { getIcon, getFileIcon } from '../lib/fileIcons';
-import type { WadChunk, ExtractSession } from '../lib/types';
+import type { WadChunk, ExtractSession, WadExtractProgress } from '../lib/types';
 
 // =============================================================================
 // Virtual Tree Types
@@ -235,6 +236,16 @@ TreeNode.displayName = 'WadTreeNode';
 export const WadBrowserPanel: React.FC<{ style?: React.CSSProperties }> = ({ style }) => {
     const { state, dispatch, showToast } = useAppState();
     const [isExtracting, setIsExtracting] = useState(false);
+    const [extractProgress, setExtractProgress] = useState<WadExtractProgress | null>(null);
+
+    useEffect(() => {
+        if (!isExtracting) return;
+        let unlisten: (() => void) | null = null;
+        listen<WadExtractProgress>('wad-extract-progress', (event) => {
+            setExtractProgress(event.payload);
+        }).then(fn => { unlisten = fn; });
+        return () => { if (unlisten) unlisten(); };
+    }, [isExtracting]);
 
     const session = state.extractSessions.find(s => s.id === state.activeExtractId);
 
@@ -283,15 +294,27 @@ export const WadBrowserPanel: React.FC<{ style?: React.CSSProperties }> = ({ sty
             setIsExtracting(true);
             const hashes = [...session.selectedHashes];
             const result = await api.extractWad(session.wadPath, destDir as string, hashes);
-            showToast('success', `Extracted ${result.extracted} file${result.extracted !== 1 ? 's' : ''}`);
+            const extracted = `${result.extracted_count} file${result.extracted_count !== 1 ? 's' : ''}`;
+            if (result.cancelled) {
+                showToast('info', `Extraction cancelled after ${extracted}`);
+            } else {
+                showToast('success', `Extracted ${extracted}`);
+            }
         } catch (err) {
             console.error('[WadBrowser] Extract failed:', err);
             showToast('error', 'Extraction failed');
         } finally {
             setIsExtracting(false);
+            setExtractProgress(null);
         }
     }, [session, showToast]);
 
+    const handleCancelExtract = useCallback(() => {
+        api.cancelExtraction(extractProgress?.operation_id).catch(err => {
+            console.error('[WadBrowser] Cancel failed:', err);
+        });
+    }, [extractProgress]);
+
     const onSearchChange = useCallback((e: React.ChangeEvent<HTMLInputElement>) => {
         if (!session) return;
         dispatch({ type: 'SET_EXTRACT_SEARCH', payload: { sessionId: session.id, query: e.target.value } });
@@ -389,8 +412,21 @@ export const WadBrowserPanel: React.FC<{ style?: React.CSSProperties }> = ({ sty
                     title={selectedCount === 0 ? 'Select files to extract' : `Extract ${selectedCount} selected file${selectedCount !== 1 ? 's' : ''}`}
                 >
                     <span dangerouslySetInnerHTML={{ __html: getIcon('export') }} />
-                    <span>{isExtracting ? 'Extracting...' : `Extract${selectedCount > 0 ? ` (${selectedCount})` : ''}`}</span>
+                    <span>
+                        {isExtracting
+                            ? extractProgress?.total ? `Extracting ${extractProgress.done}/${extractProgress.total}` : 'Extracting...'
+                            : `Extract${selectedCount > 0 ? ` (${selectedCount})` : ''}`}
+                    </span>
                 </button>
+                {isExtracting && (
+                    <button
+                        className="btn btn--ghost btn--sm"
+                        onClick={handleCancelExtract}
+                        title={extractProgress?.current_path ?? 'Stop the extraction'}
+                    >
+                        Cancel
+                    </button>
+                )}
             </div>
         </div>
     );
@@ -235,7 +235,7 @@ const ChunkPreview: React.FC<{
             if (!dest) return;
             setExtracting(true);
             const res = await api.extractWad(wadPath, dest as string, [chunk.hash]);
-            showToast('success', `Extracted ${res.extracted} file`);
+            showToast('success', `Extracted ${res.extracted_count} file`);
         } catch { showToast('error', 'Extraction failed'); }
         finally { setExtracting(false); }
     };
@@ -709,7 +709,7 @@ export const WadExplorer: React.FC = () => {
                     const dest = await open({ title: 'Choose Extraction Folder', directory: true });
                     if (!dest) return;
                     const res = await api.extractWad(wadPath, dest as string, [chunk.hash]);
-                    showToast('success', `Extracted ${res.extracted} file`);
+                    showToast('success', `Extracted ${res.extracted_count} file`);
                 } catch { showToast('error', 'Extraction failed'); }
             },
         });
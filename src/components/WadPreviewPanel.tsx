@@ -316,7 +316,7 @@ export const WadPreviewPanel: React.FC = () => {
             if (!destDir) return;
             setIsExtracting(true);
             const result = await api.extractWad(session.wadPath, destDir as string, [chunk.hash]);
-            showToast('success', `Extracted ${result.extracted} file${result.extracted !== 1 ? 's' : ''}`);
+            showToast('success', `Extracted ${result.extracted_count} file${result.extracted_count !== 1 ? 's' : ''}`);
         } catch (err) {
             console.error('[WadPreviewPanel] Extract failed:', err);
             showToast('error', 'Extraction failed');
@@ -12,6 +12,7 @@ export const ExportModal: React.FC = () => {
     const { state, closeModal, showToast } = useAppState();
 
     const [format, setFormat] = useState<'fantome' | 'modpkg'>('fantome');
+    const [packAsWad, setPackAsWad] = useState(false);
     const [isExporting, setIsExporting] = useState(false);
     const [progress, setProgress] = useState('');
 
@@ -61,6 +62,7 @@ export const ExportModal: React.FC = () => {
                     version: currentProject.version || '1.0.0',
                     description: currentProject.description || '',
                 },
+                packAsWad: format === 'fantome' ? packAsWad : undefined,
             });
 
             showToast('success', `Exported to ${result.path}`);
@@ -125,6 +127,19 @@ export const ExportModal: React.FC = () => {
                         </div>
                     </div>
 
+                    {format === 'fantome' && (
+                        <div className="form-group">
+                            <label style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+                                <input
+                                    type="checkbox"
+                                    checked={packAsWad}
+                                    onChange={(e) => setPackAsWad(e.target.checked)}
+                                />
+                                <span>Pack WAD folders as .wad.client archives</span>
+                            </label>
+                        </div>
+                    )}
+
                     <div className="form-group">
                         <label className="form-label">Project</label>
                         <div style={{ color: 'var(--text-secondary)' }}>
@@ -170,7 +170,7 @@ export const NewProjectModal: React.FC = () => {
             showToast('success', 'Project created successfully!');
 
             // Initial auto-checkpoint
-            api.createCheckpoint(projectDir, 'Initial Project State').catch(e => {
+            api.createCheckpoint(projectDir, 'Initial Project State', ['auto', 'create-project']).catch(e => {
                 console.warn('Initial checkpoint failed:', e);
             });
 
@@ -16,6 +16,7 @@ export const SettingsModal: React.FC = () => {
     const [leaguePath, setLeaguePath] = useState(state.leaguePath || '');
     const [creatorName, setCreatorName] = useState(state.creatorName || '');
     const [autoUpdateEnabled, setAutoUpdateEnabled] = useState(state.autoUpdateEnabled);
+    const [offlineMode, setOfflineMode] = useState(state.offlineMode);
     const [isValidating, setIsValidating] = useState(false);
 
     // Update checker state
@@ -31,11 +32,12 @@ export const SettingsModal: React.FC = () => {
             setLeaguePath(state.leaguePath || '');
             setCreatorName(state.creatorName || '');
             setAutoUpdateEnabled(state.autoUpdateEnabled);
+            setOfflineMode(state.offlineMode);
 
             // Load current version
             getVersion().then(setCurrentVersion).catch(() => setCurrentVersion('0.0.0'));
         }
-    }, [isVisible, state.leaguePath, state.creatorName, state.autoUpdateEnabled]);
+    }, [isVisible, state.leaguePath, state.creatorName, state.autoUpdateEnabled, state.offlineMode]);
 
     const handleBrowseLeague = async () => {
         const selected = await open({
@@ -132,6 +134,13 @@ export const SettingsModal: React.FC = () => {
                 autoUpdateEnabled,
             },
         });
+        if (offlineMode !== state.offlineMode) {
+            try {
+                await api.setOfflineMode(offlineMode);
+            } catch (error) {
+                console.error('[Flint] Failed to set offline mode:', error);
+            }
+        }
 
         showToast('success', 'Settings saved');
         closeModal();
@@ -268,6 +277,15 @@ export const SettingsModal: React.FC = () => {
                                 </>
                             )}
                         </div>
+                        <label style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer', marginTop: '8px' }}>
+                            <input
+                                type="checkbox"
+                                checked={offlineMode}
+                                onChange={(e) => setOfflineMode(e.target.checked)}
+                                style={{ width: 'auto', margin: 0 }}
+                            />
+                            <span>Offline mode (skip hash updates and other network access)</span>
+                        </label>
                     </div>
                 </div>
 
@@ -3,8 +3,8 @@
  * Async wrappers for all Tauri commands with error handling
  */
 
-import { invoke } from '@tauri-apps/api/core';
-import type { HashStatus, Project, FileTreeNode, Champion, GameWadInfo } from './types';
+import { invoke, convertFileSrc } from '@tauri-apps/api/core';
+import type { HashStatus, HashReload, HashDirMigration, GuessReport, Project, FileTreeNode, Champion, GameWadInfo, CommandError, CommandErrorCode, LeagueInstallation, SetupOptions, SetupReport, PathMappingImport, QuarantineBatch, QuarantineRestore, PrefixStripResult, ChunkSort, DiskUsage, RebaseReport, TextureAnalysis, TextureOverrideWarning, MapVfxWarning, MapOverrideMode, ExportManifestEntry, ColorTransform, RecolorSelector, RecolorReport, RenameReport, AssetReference, BinReferences, ObjectReferences, ValidationReport, BinaryPayload, HashSource, DownloadStats, HashImportResult, HashMapping, HashCategory, ProjectHashExport, ModConflictReport, PathHash, LiveSyncStarted, UnknownHash, ExtractionResult, WadDiff, WadSearchPage, WadChunk, WadMountInfo, MountedChunk, WadVerification, WadInfo, ChunkKind, ManifestFormat, WadManifestExport, SkinSelection, WadRepackStats, GamePathLocation, ExtractionPreview, BinConvertOptions, BinConversionReport, BinPropertyValue, BinSearchPage, BinMergeReport, MergeSide, BinColorReport, BinColorTransform, BinRecolorReport } from './types';
 
 // =============================================================================
 // Error Handling
@@ -16,6 +16,11 @@ import type { HashStatus, Project, FileTreeNode, Champion, GameWadInfo } from '.
 export class FlintError extends Error {
     command: string;
     originalError: unknown;
+    code: CommandErrorCode | null;
+    details: string | null;
+    path: string | null;
+    requiredBytes: number | null;
+    availableBytes: number | null;
 
     constructor(command: string, originalError: unknown) {
         const message = typeof originalError === 'string'
@@ -25,6 +30,15 @@ export class FlintError extends Error {
         this.name = 'FlintError';
         this.command = command;
         this.originalError = originalError;
+
+        const structured = typeof originalError === 'object' && originalError !== null && 'code' in originalError
+            ? originalError as CommandError
+            : null;
+        this.code = structured?.code ?? null;
+        this.details = structured?.details ?? null;
+        this.path = structured?.path ?? null;
+        this.requiredBytes = structured?.required_bytes ?? null;
+        this.availableBytes = structured?.available_bytes ?? null;
     }
 
     /**
@@ -37,17 +51,33 @@ export class FlintError extends Error {
             'download_hashes': 'Failed to download hash files. Please check your internet connection.',
             'get_hash_status': 'Failed to check hash status.',
             'reload_hashes': 'Failed to reload hash files.',
+            'import_hash_file': 'Failed to import hash file.',
+            'compute_path_hash': 'Failed to compute path hash.',
             'discover_champions': 'Failed to discover champions.',
             'get_champion_skins': 'Failed to get skins for this champion.',
             'search_champions': 'Champion search failed.',
             'create_project': 'Failed to create project.',
             'open_project': 'Failed to open project. The project file may be corrupted.',
             'save_project': 'Failed to save project.',
+            'refresh_project_display_name': "Couldn't look up the skin's name.",
             'list_project_files': 'Failed to list project files.',
+            'get_disk_usage': 'Failed to measure project disk usage.',
+            'undo_last': 'Failed to undo the last change.',
+            'read_stringtable': 'Failed to read string table.',
+            'set_stringtable_entry': 'Failed to update string table.',
             'preconvert_project_bins': 'Failed to pre-convert BIN files.',
+            'export_path_mappings': 'Failed to export path mappings.',
+            'import_path_mappings': 'Failed to import path mappings.',
+            'restore_quarantine': 'Failed to restore quarantined files.',
+            'rebase_project': 'Failed to migrate the project to the current patch.',
+            'recolor_project': 'Failed to recolor the project.',
+            'purge_quarantine': 'Failed to delete quarantined files.',
+            'detect_repath_prefix': 'Failed to scan the project for a repath prefix.',
+            'strip_repath_prefix': 'Failed to strip the repath prefix.',
             'read_wad': 'Failed to read WAD file. The file may be corrupted.',
             'get_wad_chunks': 'Failed to read WAD contents.',
             'extract_wad': 'Failed to extract files from WAD.',
+            'export_unknown_hashes': 'Failed to write the unknown hashes.',
             'read_wad_chunk_data': 'Failed to read chunk from WAD.',
             'scan_game_wads': 'Failed to scan game WAD directory.',
             'decode_bytes_to_png': 'Failed to decode texture.',
@@ -69,16 +99,27 @@ export class FlintError extends Error {
             'recolor_image': 'Failed to recolor image.',
             'recolor_folder': 'Failed to recolor folder assets.',
             'extract_asset_references': 'Failed to extract asset references.',
+            'find_project_references': 'Failed to list the project\'s asset references.',
+            'validate_project_assets': 'Failed to validate the project\'s asset references.',
             'validate_assets': 'Asset validation failed.',
+            'validate_texture_overrides': 'Failed to compare textures with the game files.',
+            'validate_map_vfx': 'Failed to check map-scoped VFX.',
             'export_fantome': 'Failed to export Fantome package.',
+            'export_bundle': 'Failed to export bundle.',
+            'check_mod_conflicts': 'Failed to check for conflicts with other mods.',
             'export_modpkg': 'Failed to export modpkg package.',
             'read_skn_mesh': 'Failed to read SKN mesh file.',
             'read_scb_mesh': 'Failed to read SCB mesh file.',
             'create_checkpoint': 'Failed to create checkpoint.',
             'list_checkpoints': 'Failed to load checkpoints.',
+            'update_checkpoint': 'Failed to update checkpoint.',
             'restore_checkpoint': 'Failed to restore checkpoint.',
             'compare_checkpoints': 'Failed to compare checkpoints.',
             'delete_checkpoint': 'Failed to delete checkpoint.',
+            'export_checkpoint': 'Failed to export checkpoint.',
+            'verify_checkpoints': 'Failed to verify checkpoints.',
+            'import_checkpoint': 'Failed to import checkpoint.',
+            'cancel_operation': 'Failed to cancel operation.',
         };
         return messages[this.command] || this.message;
     }
@@ -87,6 +128,18 @@ export class FlintError extends Error {
      * Get a recovery suggestion for this error
      */
     getRecoverySuggestion(): string | null {
+        if (this.code === 'INSUFFICIENT_DISK_SPACE') {
+            return 'Free up space on the drive, or delete old checkpoints and exports, then try again.';
+        }
+        if (this.code === 'UNSAFE_PATH') {
+            return 'The file names a path outside the project. Only use archives and mappings from sources you trust.';
+        }
+        if (this.code === 'PROJECT_READ_ONLY') {
+            return 'The project is open for inspection only. Copy it to a writable folder to make changes.';
+        }
+        if (this.code === 'GAME_FILES_LOCKED') {
+            return 'Close League of Legends and the Riot Client, or wait for patching to finish, then try again.';
+        }
         const suggestions: Record<string, string> = {
             'detect_league': 'Go to Settings (Ctrl+,) and set the League path manually.',
             'validate_league': 'Make sure the path points to the League of Legends "Game" folder.',
@@ -95,12 +148,15 @@ export class FlintError extends Error {
             'create_project': 'Check that you have write permissions to the selected folder.',
             'open_project': 'Try opening a different project or create a new one.',
             'save_project': 'Check that the project folder still exists and is writable.',
+            'refresh_project_display_name': 'Check your internet connection and try again.',
+            'import_path_mappings': 'Make sure the file is a path mapping file exported by Flint or another RitoShark tool.',
             'save_ritobin_to_bin': 'Check for syntax errors in the BIN editor.',
             'decode_dds_to_png': 'The texture format may not be supported.',
             'recolor_image': 'Make sure the texture format is supported and the file is not read-only.',
             'recolor_folder': 'Check if the folder contains valid texture files.',
             'read_file_bytes': 'Check that the file exists and is accessible.',
             'export_fantome': 'Ensure all project files are saved.',
+            'export_bundle': 'Remove the listed files from all but one project, or export the failing project on its own to find the problem.',
             'create_checkpoint': 'Make sure you have enough disk space and the project is not in use by another program.',
             'restore_checkpoint': 'Ensure all project files are closed before restoring.',
         };
@@ -120,20 +176,101 @@ async function invokeCommand<T>(command: string, args: Record<string, unknown> =
     }
 }
 
+/**
+ * Fetch a blob a command parked on the backend. Tokens are single-use.
+ */
+async function fetchBlob(command: string, token: string): Promise<Blob> {
+    const response = await fetch(convertFileSrc(token, 'flint-blob'));
+    if (!response.ok) {
+        throw new FlintError(command, `Blob ${token} is no longer available (HTTP ${response.status})`);
+    }
+    return response.blob();
+}
+
+/**
+ * Invoke a command returning a BinaryPayload and resolve it to its bytes
+ */
+async function invokeBinary(command: string, args: Record<string, unknown> = {}): Promise<Uint8Array> {
+    const payload = await invokeCommand<BinaryPayload>(command, args);
+    if (payload.kind === 'inline') {
+        return new Uint8Array(payload.data);
+    }
+    const blob = await fetchBlob(command, payload.token);
+    return new Uint8Array(await blob.arrayBuffer());
+}
+
+/**
+ * Fill in `data` for a decoded texture whose PNG was served as a blob
+ */
+async function resolveDecodedTexture(command: string, result: DecodedTexture): Promise<DecodedTexture> {
+    if (!result.blob) {
+        return result;
+    }
+    const blob = await fetchBlob(command, result.blob);
+    const dataUrl = await new Promise<string>((resolve, reject) => {
+        const reader = new FileReader();
+        reader.onload = () => resolve(reader.result as string);
+        reader.onerror = () => reject(new FlintError(command, reader.error));
+        reader.readAsDataURL(blob);
+    });
+    return { ...result, data: dataUrl.slice(dataUrl.indexOf(',') + 1), blob: null };
+}
+
 // =============================================================================
 // Hash Management Commands
 // =============================================================================
 
-export async function downloadHashes(): Promise<{ downloaded: number; total: number }> {
-    return invokeCommand('download_hashes');
+/**
+ * Download hash lists. `sources` replaces the saved mirrors (empty for
+ * CommunityDragon) and `categories` the CommunityDragon lists to fetch;
+ * leave them out to keep the saved ones.
+ */
+export async function downloadHashes(
+    force = false,
+    sources?: HashSource[],
+    categories?: HashCategory[]
+): Promise<DownloadStats> {
+    return invokeCommand('download_hashes', { force, sources, categories });
+}
+
+/** Stop running hash downloads, the startup update included; false if none was running */
+export async function cancelHashDownload(): Promise<boolean> {
+    return invokeCommand('cancel_hash_download');
 }
 
 export async function getHashStatus(): Promise<HashStatus> {
     return invokeCommand('get_hash_status');
 }
 
-export async function reloadHashes(): Promise<{ count: number }> {
-    return invokeCommand('reload_hashes');
+/**
+ * Move hashes from the fallback directory to the RitoShark directory and
+ * switch to it. `null` when there was nothing to move.
+ */
+export async function migrateHashDirectory(): Promise<HashDirMigration | null> {
+    return invokeCommand('migrate_hash_directory');
+}
+
+/** Re-read every hash list, or only `files` (names in the hash directory) */
+export async function reloadHashes(files?: string[]): Promise<HashReload> {
+    return invokeCommand('reload_hashes', { files: files ?? null });
+}
+
+/**
+ * WAD path hashes (xxhash64 of the lowercase, forward-slash path). With
+ * `checkKnown` each hash is also looked up in the hashtable.
+ */
+export async function computePathHash(paths: string | string[], checkKnown = false): Promise<PathHash[]> {
+    return invokeCommand('compute_path_hash', { paths: Array.isArray(paths) ? paths : [paths], checkKnown });
+}
+
+/** Merge a local hash list into hashes.user.txt, which wins over downloaded lists */
+export async function importHashFile(path: string): Promise<HashImportResult> {
+    return invokeCommand('import_hash_file', { path });
+}
+
+/** Record one hash -> path mapping in hashes.user.txt; the loaded table picks it up without a reload */
+export async function addHashMapping(hash: string, path: string): Promise<HashMapping> {
+    return invokeCommand('add_hash_mapping', { hash, path });
 }
 
 // =============================================================================
@@ -144,10 +281,12 @@ export async function detectLeague(): Promise<{ path: string; source: string }>
     return invokeCommand('detect_league');
 }
 
-interface LeagueInstallation {
-    path: string;
-    game_path: string;
-    auto_detected: boolean;
+/**
+ * Names of the League and Riot Client processes running; empty when none
+ * is. Worth checking before long operations on game files.
+ */
+export async function checkGameRunning(): Promise<string[]> {
+    return invokeCommand('check_game_running');
 }
 
 export async function validateLeague(path: string): Promise<{ valid: boolean; path: string | null }> {
@@ -213,6 +352,16 @@ export async function saveProject(project: Project): Promise<void> {
     return invokeCommand('save_project', { project });
 }
 
+/** Save the hash names the project uses to project.hashes.txt, merged back in on open */
+export async function exportProjectHashes(projectPath: string): Promise<ProjectHashExport> {
+    return invokeCommand('export_project_hashes', { projectPath });
+}
+
+/** Rename an older project after its skin (e.g. "Star Guardian Ahri") */
+export async function refreshProjectDisplayName(projectPath: string): Promise<Project> {
+    return invokeCommand('refresh_project_display_name', { projectPath });
+}
+
 // Backend file tree entry format
 interface BackendFileEntry {
     path: string;
@@ -275,40 +424,265 @@ export async function listProjectFiles(projectPath: string): Promise<FileTreeNod
     return transformFileTree(rawTree, 'Project');
 }
 
+export async function getDiskUsage(projectPath: string): Promise<DiskUsage> {
+    return invokeCommand('get_disk_usage', { projectPath });
+}
+
 export async function preconvertProjectBins(projectPath: string): Promise<number> {
     return invokeCommand('preconvert_project_bins', { projectPath });
 }
 
+/**
+ * Convert every BIN in the project's content layers to ritobin text (`.py`)
+ * or JSON next to the original. Up-to-date conversions are skipped unless
+ * `force`, e.g. after a hashtable update. Emits `bin-convert-progress`.
+ */
+export async function convertProjectBins(projectPath: string, options: BinConvertOptions = {}): Promise<BinConversionReport> {
+    return invokeCommand('convert_project_bins', { projectPath, options });
+}
+
+/**
+ * Extract, organize, pre-convert and checkpoint a new project in one cancellable
+ * operation. Check `failed_phase` on the report: a stopped run still resolves.
+ */
+export async function setupProjectContent(
+    projectPath: string,
+    options?: SetupOptions,
+    queue?: boolean
+): Promise<SetupReport> {
+    return invokeCommand('setup_project_content', { projectPath, options, queue });
+}
+
+/**
+ * Compare a project with the current game patch. Without `approved` only the
+ * proposed remappings are returned; pass their `old_path`s to apply them.
+ */
+export async function rebaseProject(
+    projectPath: string,
+    leaguePath?: string,
+    approved?: string[],
+    queue?: boolean
+): Promise<RebaseReport> {
+    return invokeCommand('rebase_project', { projectPath, leaguePath, approved, queue });
+}
+
+/**
+ * Recolor a VFX system or the textures matching a glob. A dry run returns
+ * before/after previews and writes nothing; an applied recolor can be undone.
+ */
+export async function recolorProject(
+    projectPath: string,
+    selector: RecolorSelector,
+    transform: ColorTransform,
+    dryRun: boolean,
+    queue?: boolean
+): Promise<RecolorReport> {
+    return invokeCommand('recolor_project', { projectPath, selector, transform, dryRun, queue });
+}
+
+/**
+ * Shift or palette-swap the colors of a BIN, or of a project's particle and
+ * skin BINs. Inside a project a checkpoint is taken first to undo with.
+ */
+export async function applyBinRecolor(
+    path: string,
+    transform: BinColorTransform,
+    queue?: boolean
+): Promise<BinRecolorReport> {
+    return invokeCommand('apply_bin_recolor', { path, transform, queue });
+}
+
+/**
+ * Rename a VFX system and every reference to it across the project's BINs.
+ * A dry run only lists the locations; an applied rename can be undone.
+ */
+export async function renameVfxSystem(
+    projectPath: string,
+    oldName: string,
+    newName: string,
+    dryRun: boolean,
+    queue?: boolean
+): Promise<RenameReport> {
+    return invokeCommand('rename_vfx_system', { projectPath, oldName, newName, dryRun, queue });
+}
+
+/** Write a project's path mappings to a file; returns how many were written */
+export async function exportPathMappings(projectPath: string, outputPath: string): Promise<number> {
+    return invokeCommand('export_path_mappings', { projectPath, outputPath });
+}
+
+/** Merge a path mapping file (from Flint or another RitoShark tool) into a project */
+export async function importPathMappings(projectPath: string, inputPath: string): Promise<PathMappingImport> {
+    return invokeCommand('import_path_mappings', { projectPath, inputPath });
+}
+
+/** Batches of unused files that repath cleanup moved to `.flint/quarantine` */
+export async function listQuarantine(projectPath: string): Promise<QuarantineBatch[]> {
+    return invokeCommand('list_quarantine', { projectPath });
+}
+
+/** Move a quarantine batch back into the project; files whose path is taken again stay quarantined */
+export async function restoreQuarantine(projectPath: string, timestamp: string): Promise<QuarantineRestore> {
+    return invokeCommand('restore_quarantine', { projectPath, timestamp });
+}
+
+/** Permanently delete one quarantine batch, or all of them; returns the number of files deleted */
+export async function purgeQuarantine(projectPath: string, timestamp?: string): Promise<number> {
+    return invokeCommand('purge_quarantine', { projectPath, timestamp });
+}
+
+/** Find and record the repath prefix another mod left on the content; null if there is none */
+export async function detectRepathPrefix(projectPath: string): Promise<string | null> {
+    return invokeCommand('detect_repath_prefix', { projectPath });
+}
+
+/** Rewrite references under another mod's prefix back to assets/ and data/ paths */
+export async function stripRepathPrefix(projectPath: string, prefix?: string): Promise<PrefixStripResult> {
+    return invokeCommand('strip_repath_prefix', { projectPath, prefix });
+}
+
 // =============================================================================
 // WAD Commands
 // =============================================================================
 
-export async function readWad(wadPath: string): Promise<{ version: string; chunkCount: number }> {
-    return invokeCommand('read_wad', { wadPath });
+export async function readWad(wadPath: string): Promise<WadInfo> {
+    return invokeCommand('read_wad', { path: wadPath });
 }
 
+/**
+ * Chunks sorted by path unless `sort` says otherwise, only those of `kind`
+ * if given; `hash` identifies a chunk
+ */
 export async function getWadChunks(
-    wadPath: string
-): Promise<Array<{ hash: string; path: string | null; size: number }>> {
-    return invokeCommand('get_wad_chunks', { path: wadPath });
+    wadPath: string,
+    sort?: ChunkSort,
+    kind?: ChunkKind
+): Promise<WadChunk[]> {
+    return invokeCommand('get_wad_chunks', { path: wadPath, sort, kind });
 }
 
 export interface WadChunkBatch {
     path: string;
-    chunks: Array<{ hash: string; path: string | null; size: number }>;
+    chunks: WadChunk[];
     error: string | null;
 }
 
-export async function loadAllWadChunks(paths: string[]): Promise<WadChunkBatch[]> {
-    return invokeCommand('load_all_wad_chunks', { paths });
+/** Batches in the order of `paths`; chunks in each sorted like `getWadChunks` */
+export async function loadAllWadChunks(paths: string[], sort?: ChunkSort): Promise<WadChunkBatch[]> {
+    return invokeCommand('load_all_wad_chunks', { paths, sort });
 }
 
+/**
+ * Extract chunks of a WAD: the given hashes or all of them, narrowed to
+ * resolved paths matching `filters` (globs such as `*.dds`; `unknown`
+ * selects chunks whose hash doesn't resolve). With `incremental` only chunks
+ * that are new or changed since the last extraction into the project folder
+ * are written. `skin` extracts only the chunks that skin references, in place
+ * of `chunkHashes`; the result lists referenced paths missing from the WAD.
+ */
 export async function extractWad(
     wadPath: string,
-    outputPath: string,
-    hashes: string[] | null = null
-): Promise<{ extracted: number }> {
-    return invokeCommand('extract_wad', { wadPath, outputPath, hashes });
+    outputDir: string,
+    chunkHashes: string[] | null = null,
+    filters: string[] | null = null,
+    incremental = false,
+    skin: SkinSelection | null = null
+): Promise<ExtractionResult> {
+    return invokeCommand('extract_wad', { wadPath, outputDir, chunkHashes, skin, filters, incremental });
+}
+
+/**
+ * List what `extractWad` with the same selection would write, with sizes,
+ * without writing anything
+ */
+export async function previewExtraction(
+    wadPath: string,
+    outputDir: string,
+    chunkHashes: string[] | null = null,
+    filters: string[] | null = null,
+    skin: SkinSelection | null = null
+): Promise<ExtractionPreview> {
+    return invokeCommand('preview_extraction', { wadPath, outputDir, chunkHashes, skin, filters });
+}
+
+/**
+ * Stop a running `extractWad` (every running one without an id). The
+ * extraction returns what it finished with `cancelled` set; returns how many
+ * extractions were asked to stop.
+ */
+export async function cancelExtraction(operationId?: string): Promise<number> {
+    return invokeCommand('cancel_extraction', { operationId: operationId ?? null });
+}
+
+/**
+ * Write a WAD's complete chunk listing, unresolved hashes included, to a
+ * CSV or JSON file
+ */
+export async function exportWadManifest(wadPath: string, outputPath: string, format: ManifestFormat): Promise<WadManifestExport> {
+    return invokeCommand('export_wad_manifest', { wadPath, outputPath, format });
+}
+
+/**
+ * Write a copy of a game WAD with the project's files from its WAD folder
+ * packed in; chunks the project doesn't touch are copied untouched
+ */
+export async function repackWad(projectPath: string, wadPath: string, outputPath: string): Promise<WadRepackStats> {
+    return invokeCommand('repack_wad', { projectPath, wadPath, outputPath });
+}
+
+/**
+ * Decompress and checksum every chunk of a WAD without writing anything;
+ * progress arrives as wad-verify-progress events
+ */
+export async function verifyWad(wadPath: string): Promise<WadVerification> {
+    return invokeCommand('verify_wad', { wadPath });
+}
+
+/**
+ * Write the unknown hashes of extractions to a file for submitting to
+ * CommunityDragon, one hash per line; returns how many were written
+ */
+export async function exportUnknownHashes(hashes: UnknownHash[], outputPath: string, details = false): Promise<number> {
+    return invokeCommand('export_unknown_hashes', { hashes, outputPath, details });
+}
+
+/**
+ * Guess paths for unresolved hashes of a WAD (default: all of them) from its
+ * resolved paths. Nothing is saved; add accepted guesses with `addHashMapping`.
+ */
+export async function guessUnknownHashes(wadPath: string, hashes?: string[]): Promise<GuessReport> {
+    return invokeCommand('guess_unknown_hashes', { wadPath, hashes: hashes ?? null });
+}
+
+/**
+ * Find the WADs of an installation holding a path, 16-digit hash or path
+ * substring. Tables of contents are cached, so paging through the hits is
+ * cheap; `wad-search-progress` events report the scan.
+ */
+export async function findInWads(
+    leaguePath: string,
+    query: string,
+    offset?: number,
+    limit?: number,
+): Promise<WadSearchPage> {
+    return invokeCommand('find_in_wads', { leaguePath, query, offset: offset ?? null, limit: limit ?? null });
+}
+
+/**
+ * The WAD of an installation a game path comes from: the champion's WADs
+ * first, then every other one. `null` when no WAD has the path.
+ */
+export async function locateGamePath(
+    leaguePath: string,
+    path: string,
+    champion?: string,
+): Promise<GamePathLocation | null> {
+    return invokeCommand('locate_game_path', { leaguePath, path, champion: champion ?? null });
+}
+
+/** Added, removed and modified chunks between two versions of a WAD */
+export async function diffWads(oldPath: string, newPath: string): Promise<WadDiff> {
+    return invokeCommand('diff_wads', { oldPath, newPath });
 }
 
 /**
@@ -316,8 +690,30 @@ export async function extractWad(
  * Returns the decompressed raw bytes of the chunk.
  */
 export async function readWadChunkData(wadPath: string, hash: string): Promise<Uint8Array> {
-    const result = await invokeCommand<number[]>('read_wad_chunk_data', { wadPath, hash });
-    return new Uint8Array(result);
+    return invokeBinary('read_wad_chunk_data', { wadPath, hash });
+}
+
+/**
+ * Mount a champion's client WAD and its locale WADs (only `locale`'s if
+ * given). Lookups and reads through the returned handle pick the first WAD
+ * holding an entry; drop it with `unmountWads`.
+ */
+export async function mountChampionWads(leaguePath: string, champion: string, locale?: string): Promise<WadMountInfo> {
+    return invokeCommand('mount_champion_wads', { leaguePath, champion, locale: locale ?? null });
+}
+
+/** Which mounted WAD provides a game path or 16-digit hex hash, null if none does */
+export async function lookupMountedChunk(id: string, entry: string): Promise<MountedChunk | null> {
+    return invokeCommand('lookup_mounted_chunk', { id, entry });
+}
+
+/** Decompressed data of a game path or hash from a mount set */
+export async function readMountedChunk(id: string, entry: string): Promise<Uint8Array> {
+    return invokeBinary('read_mounted_chunk', { id, entry });
+}
+
+export async function unmountWads(id: string): Promise<boolean> {
+    return invokeCommand('unmount_wads', { id });
 }
 
 /**
@@ -361,8 +757,72 @@ export async function readOrConvertBin(binPath: string): Promise<string> {
     return invokeCommand('read_or_convert_bin', { binPath });
 }
 
-export async function saveRitobinToBin(binPath: string, content: string): Promise<void> {
-    return invokeCommand('save_ritobin_to_bin', { binPath, content });
+/**
+ * Save edited ritobin text to a BIN. Unless `verify` is false, the written
+ * BIN is read back first and the save fails, listing divergent paths in the
+ * error's `details`, if it differs from the text.
+ */
+export async function saveRitobinToBin(binPath: string, content: string, verify?: boolean): Promise<void> {
+    return invokeCommand('save_ritobin_to_bin', { binPath, content, verify });
+}
+
+/**
+ * Read one property of a BIN. `object` is the object path or "0x…" hash;
+ * `propertyPath` joins fields with dots, `[n]` for list items and `[key]`
+ * for map entries, e.g. "complexEmitterDefinitionData[0].rate".
+ */
+export async function getBinProperty(binPath: string, object: string, propertyPath: string): Promise<BinPropertyValue> {
+    return invokeCommand('get_bin_property', { binPath, object, propertyPath });
+}
+
+/**
+ * Set one property of a BIN to `value`, ritobin text of type `valueType`
+ * (e.g. "f32" and "1.5"), and return the old value. The kind must match.
+ */
+export async function setBinProperty(
+    binPath: string,
+    object: string,
+    propertyPath: string,
+    valueType: string,
+    value: string
+): Promise<BinPropertyValue> {
+    return invokeCommand('set_bin_property', { binPath, object, propertyPath, valueType, value });
+}
+
+/**
+ * Search every BIN of a project for text (names and strings, any case), a
+ * number, or a "0x…" hash. Progress comes as `bin-search-progress` events.
+ */
+export async function searchBins(
+    projectPath: string,
+    query: string,
+    offset?: number,
+    limit?: number,
+): Promise<BinSearchPage> {
+    return invokeCommand('search_bins', { projectPath, query, offset: offset ?? null, limit: limit ?? null });
+}
+
+/**
+ * List the colors of a BIN, or of every BIN of a project folder, with
+ * animated colors grouped as gradients.
+ */
+export async function extractBinColors(path: string): Promise<BinColorReport> {
+    return invokeCommand('extract_bin_colors', { path });
+}
+
+/**
+ * Three-way merge two edited versions of a BIN into `outputPath`. Conflicts
+ * keep our version unless `resolutions` picks theirs by conflict id; call
+ * again with resolutions to settle them.
+ */
+export async function mergeBins(
+    basePath: string,
+    oursPath: string,
+    theirsPath: string,
+    outputPath: string,
+    resolutions?: Record<string, MergeSide>,
+): Promise<BinMergeReport> {
+    return invokeCommand('merge_bins', { basePath, oursPath, theirsPath, outputPath, resolutions: resolutions ?? null });
 }
 
 export async function parseBinToTree(binPath: string): Promise<unknown[]> {
@@ -378,8 +838,7 @@ export async function getBinPaths(binPath: string): Promise<unknown[]> {
 // =============================================================================
 
 export async function readFileBytes(path: string): Promise<Uint8Array> {
-    const result = await invokeCommand<number[]>('read_file_bytes', { path });
-    return new Uint8Array(result);
+    return invokeBinary('read_file_bytes', { path });
 }
 
 interface FileInfo {
@@ -388,6 +847,8 @@ interface FileInfo {
     fileType: string;
     extension: string;
     dimensions: [number, number] | null;
+    /** For DDS and TEX textures */
+    texture: TextureAnalysis | null;
 }
 
 export async function readFileInfo(path: string): Promise<FileInfo> {
@@ -395,7 +856,10 @@ export async function readFileInfo(path: string): Promise<FileInfo> {
 }
 
 interface DecodedTexture {
+    /** Base64 PNG; filled in from the blob for large textures */
     data: string;
+    /** Blob token set by the backend for PNGs over 2MB */
+    blob: string | null;
     width: number;
     height: number;
     format: string;
@@ -406,7 +870,8 @@ interface DecodedTexture {
  * Despite the name, this handles both DDS and TEX formats
  */
 export async function decodeDdsToPng(path: string): Promise<DecodedTexture> {
-    return invokeCommand('decode_dds_to_png', { path });
+    const result = await invokeCommand<DecodedTexture>('decode_dds_to_png', { path });
+    return resolveDecodedTexture('decode_dds_to_png', result);
 }
 
 /**
@@ -414,7 +879,8 @@ export async function decodeDdsToPng(path: string): Promise<DecodedTexture> {
  * Used by the WAD browser for in-memory preview — no disk file needed.
  */
 export async function decodeBytesToPng(data: Uint8Array): Promise<DecodedTexture> {
-    return invokeCommand('decode_bytes_to_png', { data: Array.from(data) });
+    const result = await invokeCommand<DecodedTexture>('decode_bytes_to_png', { data: Array.from(data) });
+    return resolveDecodedTexture('decode_bytes_to_png', result);
 }
 
 export async function readTextFile(path: string): Promise<string> {
@@ -457,12 +923,43 @@ export async function colorizeFolder(
     return invokeCommand('colorize_folder', { path, targetHue, preserveSaturation, skipDistortion });
 }
 
+// =============================================================================
+// String Table Commands
+// =============================================================================
+
+import type { StringTableEntry } from './types';
+
+export async function readStringtable(path: string): Promise<StringTableEntry[]> {
+    return invokeCommand('read_stringtable', { path });
+}
+
+/** `key` is a key name or an unresolved entry's hash as `{hash}` */
+export async function setStringtableEntry(path: string, key: string, value: string): Promise<StringTableEntry> {
+    return invokeCommand('set_stringtable_entry', { path, key, value });
+}
+
 // =============================================================================
 // Validation Commands
 // =============================================================================
 
-export async function extractAssetReferences(binData: Uint8Array): Promise<string[]> {
-    return invokeCommand('extract_asset_references', { binData: Array.from(binData) });
+/** References in ritobin text, grouped by the object that holds them */
+export async function extractAssetReferences(content: string): Promise<ObjectReferences[]> {
+    return invokeCommand('extract_asset_references', { content });
+}
+
+/** Fast mode: the deduplicated paths in ritobin text, without their objects */
+export async function extractAssetPaths(content: string): Promise<AssetReference[]> {
+    return invokeCommand('extract_asset_references', { content, pathsOnly: true });
+}
+
+/** Asset references of every BIN in a project, by file and object */
+export async function findProjectReferences(projectPath: string): Promise<BinReferences[]> {
+    return invokeCommand('find_project_references', { projectPath });
+}
+
+/** Referenced files that are neither in the project nor the game, with the object and field referencing each */
+export async function validateProjectAssets(projectPath: string, leaguePath?: string): Promise<ValidationReport> {
+    return invokeCommand('validate_project_assets', { projectPath, leaguePath });
 }
 
 export async function validateAssets(
@@ -472,6 +969,19 @@ export async function validateAssets(
     return invokeCommand('validate_assets', { assetPaths, wadPath });
 }
 
+/** Project textures whose format, colorspace or alpha usage differs from the game texture they replace */
+export async function validateTextureOverrides(
+    projectPath: string,
+    leaguePath?: string
+): Promise<TextureOverrideWarning[]> {
+    return invokeCommand('validate_texture_overrides', { projectPath, leaguePath });
+}
+
+/** Champion VFX systems Summoner's Rift data uses that the export won't carry there */
+export async function validateMapVfx(projectPath: string, leaguePath?: string): Promise<MapVfxWarning[]> {
+    return invokeCommand('validate_map_vfx', { projectPath, leaguePath });
+}
+
 // =============================================================================
 // Export Commands
 // =============================================================================
@@ -489,6 +999,14 @@ interface ExportParams {
     format: 'fantome' | 'modpkg';
     champion: string;
     metadata: ExportMetadata;
+    /** Export even if repathing couldn't read some BINs */
+    force?: boolean;
+    /** Fantome only: what repathing does with map-scoped BINs referencing the champion */
+    mapOverrides?: MapOverrideMode;
+    /** Fantome only: installed WAD to pack the champion's content as, e.g. "Gnar.wad.client" */
+    wadName?: string;
+    /** Fantome only: build each WAD folder into a .wad.client archive instead of loose files */
+    packAsWad?: boolean;
 }
 
 export async function exportProject(params: ExportParams): Promise<{ path: string }> {
@@ -498,7 +1016,10 @@ export async function exportProject(params: ExportParams): Promise<{ path: strin
             outputPath: params.outputPath,
             champion: params.champion,
             metadata: params.metadata,
-            autoRepath: true,
+            force: params.force,
+            mapOverrides: params.mapOverrides,
+            wadName: params.wadName,
+            packAsWad: params.packAsWad,
         });
     }
     // modpkg format
@@ -508,6 +1029,47 @@ export async function exportProject(params: ExportParams): Promise<{ path: strin
     });
 }
 
+/**
+ * Export several projects as one .fantome. Each project is repathed under its
+ * own prefix; `export-progress` events name the project being processed.
+ */
+export async function exportBundle(
+    projectPaths: string[],
+    metadata: ExportMetadata,
+    outputPath: string,
+    force?: boolean,
+): Promise<{ output_path: string }> {
+    return invokeCommand('export_bundle', { projectPaths, metadata, outputPath, force });
+}
+
+/**
+ * Files a package (.fantome, mod folder or project) shares with other mods.
+ * `others` are packages or directories of them; empty uses the mod manager
+ * directory from settings.
+ */
+export async function checkModConflicts(packagePath: string, others: string[] = []): Promise<ModConflictReport> {
+    return invokeCommand('check_mod_conflicts', { packagePath, others });
+}
+
+/**
+ * Experimental: repath and copy a project into a mod folder a running game
+ * reads, then keep copying changed files over. Each synced batch is
+ * reported in a `live-sync` event.
+ */
+export async function liveSync(projectPath: string, overlayDir: string, queue?: boolean): Promise<LiveSyncStarted> {
+    return invokeCommand('live_sync', { projectPath, overlayDir, queue });
+}
+
+/** Stop a project's live sync; returns whether one was running */
+export async function stopLiveSync(projectPath: string): Promise<boolean> {
+    return invokeCommand('stop_live_sync', { projectPath });
+}
+
+/** The files an export would pack and the WAD each goes into */
+export async function getExportPreview(projectPath: string): Promise<ExportManifestEntry[]> {
+    return invokeCommand('get_export_preview', { projectPath });
+}
+
 // =============================================================================
 // Mesh Commands (3D Preview)
 // =============================================================================
@@ -683,22 +1245,64 @@ export async function downloadAndInstallUpdate(downloadUrl: string): Promise<voi
 // Checkpoint Commands
 // =============================================================================
 
-import type { Checkpoint, CheckpointDiff, CheckpointFileContent } from './types';
+import type {
+    Checkpoint,
+    CheckpointDiff,
+    CheckpointFileContent,
+    CheckpointFilter,
+    CheckpointGraph,
+    CheckpointLockInfo,
+    CheckpointOrder,
+    CheckpointStorageStats,
+    CheckpointVerifyReport,
+} from './types';
 
 export async function createCheckpoint(
     projectPath: string,
     message: string,
-    tags: string[] = []
+    tags: string[] = [],
+    storeBinsAsText?: boolean
+): Promise<Checkpoint> {
+    return invokeCommand('create_checkpoint', { projectPath, message, tags, storeBinsAsText });
+}
+
+export async function cancelCheckpointCreation(projectPath: string): Promise<boolean> {
+    return invokeCommand('cancel_checkpoint_creation', { projectPath });
+}
+
+/** Newest first unless `order` is 'oldest_first' */
+export async function listCheckpoints(
+    projectPath: string,
+    filter?: CheckpointFilter,
+    order?: CheckpointOrder
+): Promise<Checkpoint[]> {
+    return invokeCommand('list_checkpoints', { projectPath, filter, order });
+}
+
+export async function updateCheckpoint(
+    projectPath: string,
+    checkpointId: string,
+    message?: string,
+    tags?: string[]
 ): Promise<Checkpoint> {
-    return invokeCommand('create_checkpoint', { projectPath, message, tags });
+    return invokeCommand('update_checkpoint', { projectPath, checkpointId, message, tags });
 }
 
-export async function listCheckpoints(projectPath: string): Promise<Checkpoint[]> {
-    return invokeCommand('list_checkpoints', { projectPath });
+/** With `queue`, waits for a running repath/export on the project instead of failing with BUSY */
+export async function restoreCheckpoint(
+    projectPath: string,
+    checkpointId: string,
+    queue?: boolean
+): Promise<void> {
+    return invokeCommand('restore_checkpoint', { projectPath, checkpointId, queue });
 }
 
-export async function restoreCheckpoint(projectPath: string, checkpointId: string): Promise<void> {
-    return invokeCommand('restore_checkpoint', { projectPath, checkpointId });
+export async function getCheckpointGraph(projectPath: string): Promise<CheckpointGraph> {
+    return invokeCommand('get_checkpoint_graph', { projectPath });
+}
+
+export async function verifyCheckpoints(projectPath: string): Promise<CheckpointVerifyReport> {
+    return invokeCommand('verify_checkpoints', { projectPath });
 }
 
 export async function compareCheckpoints(
@@ -709,10 +1313,42 @@ export async function compareCheckpoints(
     return invokeCommand('compare_checkpoints', { projectPath, fromId, toId });
 }
 
-export async function deleteCheckpoint(projectPath: string, checkpointId: string): Promise<void> {
+export async function deleteCheckpoint(projectPath: string, checkpointId: string): Promise<number> {
     return invokeCommand('delete_checkpoint', { projectPath, checkpointId });
 }
 
+export async function pruneCheckpoints(projectPath: string, count: number): Promise<number> {
+    return invokeCommand('prune_checkpoints', { projectPath, count });
+}
+
+export async function getCheckpointLockStatus(projectPath: string): Promise<CheckpointLockInfo | null> {
+    return invokeCommand('get_checkpoint_lock_status', { projectPath });
+}
+
+export async function breakCheckpointLock(projectPath: string): Promise<boolean> {
+    return invokeCommand('break_checkpoint_lock', { projectPath });
+}
+
+export async function getCheckpointStorageStats(projectPath: string): Promise<CheckpointStorageStats> {
+    return invokeCommand('get_checkpoint_storage_stats', { projectPath });
+}
+
+export async function exportCheckpoint(
+    projectPath: string,
+    checkpointId: string,
+    outputZip: string
+): Promise<number> {
+    return invokeCommand('export_checkpoint', { projectPath, checkpointId, outputZip });
+}
+
+export async function importCheckpoint(
+    projectPath: string,
+    zipPath: string,
+    message: string
+): Promise<Checkpoint> {
+    return invokeCommand('import_checkpoint', { projectPath, zipPath, message });
+}
+
 export async function readCheckpointFile(
     projectPath: string,
     hash: string,
@@ -721,4 +1357,113 @@ export async function readCheckpointFile(
     return invokeCommand('read_checkpoint_file', { projectPath, hash, filePath });
 }
 
+// =============================================================================
+// Operation Commands
+// =============================================================================
+
+import type { OperationInfo, ProjectBusyStatus } from './types';
+
+/** Running operations plus ones that finished in the last minute */
+export async function listOperations(): Promise<OperationInfo[]> {
+    return invokeCommand('list_operations');
+}
+
+/** Returns false if the operation had already finished */
+export async function cancelOperation(id: string): Promise<boolean> {
+    return invokeCommand('cancel_operation', { id });
+}
+
+export async function getProjectBusyStatus(projectPath: string): Promise<ProjectBusyStatus> {
+    return invokeCommand('get_project_busy_status', { projectPath });
+}
+
+/** Most recent finished operation of each kind with its phase timings, most recent first */
+export async function getLastOperationMetrics(): Promise<OperationInfo[]> {
+    return invokeCommand('get_last_operation_metrics');
+}
+
+// =============================================================================
+// Undo Commands
+// =============================================================================
+
+import type { UndoEntryInfo } from './types';
+
+/** Reverts the most recent BIN save or project settings edit; null if there was nothing to undo */
+export async function undoLast(projectPath: string): Promise<UndoEntryInfo | null> {
+    return invokeCommand('undo_last', { projectPath });
+}
+
+/** This session's undoable edits, most recent first */
+export async function getUndoHistory(projectPath: string): Promise<UndoEntryInfo[]> {
+    return invokeCommand('get_undo_history', { projectPath });
+}
+
+// =============================================================================
+// Settings Commands
+// =============================================================================
+
+import type { AppSettings, SettingsPatch } from './types';
+
+export async function getSettings(): Promise<AppSettings> {
+    return invokeCommand('get_settings');
+}
+
+/** Persists the patch and broadcasts `settings-changed` to open views */
+export async function updateSettings(patch: SettingsPatch): Promise<AppSettings> {
+    return invokeCommand('update_settings', { patch });
+}
+
+/** Skip the startup hash update and refuse network commands while on */
+export async function setOfflineMode(enabled: boolean): Promise<AppSettings> {
+    return invokeCommand('set_offline_mode', { enabled });
+}
+
+// =============================================================================
+// Log Commands
+// =============================================================================
+
+import type { LogLevel, LogLine } from './types';
+
+/**
+ * Recent backend log lines, oldest first
+ * @param level - Least severe level to include
+ * @param operationId - Only lines logged while this operation ran
+ */
+export async function getRecentLogs(
+    limit?: number,
+    level?: LogLevel,
+    operationId?: string
+): Promise<LogLine[]> {
+    return invokeCommand('get_recent_logs', { limit, level, operationId });
+}
+
+/** Path of the active log file, or null if file logging is unavailable */
+export async function getLogFilePath(): Promise<string | null> {
+    return invokeCommand('get_log_file_path');
+}
+
+import type { DiagnosticBundle } from './types';
+
+/**
+ * Zip logs, settings and environment details for a bug report
+ * @param includeProject - Project whose metadata and reports to add (never its assets)
+ * @param includeLeaguePath - false replaces League paths with "set"/"unset"
+ */
+export async function createDiagnosticBundle(
+    outputPath: string,
+    includeProject?: string,
+    includeLeaguePath?: boolean
+): Promise<DiagnosticBundle> {
+    return invokeCommand('create_diagnostic_bundle', { outputPath, includeProject, includeLeaguePath });
+}
+
+// =============================================================================
+// Startup Commands
+// =============================================================================
 
+import type { StartupSummary } from './types';
+
+/** The summary sent with `app-ready`, for views that mount after it fired */
+export async function getStartupSummary(): Promise<StartupSummary> {
+    return invokeCommand('get_startup_summary');
+}
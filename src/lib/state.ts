@@ -4,7 +4,8 @@
  */
 
 import React, { createContext, useContext, useReducer, useCallback, useEffect, useMemo, ReactNode } from 'react';
-import type { AppState, ModalType, Toast, RecentProject, Project, FileTreeNode, Champion, LogEntry, ContextMenuState, ContextMenuOption, ProjectTab, WadChunk, ExtractSession, WadExplorerState, WadExplorerWad, GameWadInfo } from './types';
+import * as api from './api';
+import type { AppSettings, StoredRecentProject, AppState, ModalType, Toast, RecentProject, Project, FileTreeNode, Champion, LogEntry, ContextMenuState, ContextMenuOption, ProjectTab, WadChunk, ExtractSession, WadExplorerState, WadExplorerWad, GameWadInfo } from './types';
 
 // =============================================================================
 // Initial State
@@ -68,6 +69,9 @@ const initialState: AppState = {
     // Auto-update settings
     autoUpdateEnabled: true,
     skippedUpdateVersion: null,
+
+    // Offline mode (backend setting)
+    offlineMode: false,
 };
 
 // =============================================================================
@@ -687,6 +691,22 @@ const AppContext = createContext<AppContextValue | null>(null);
 
 const SETTINGS_KEY = 'flint_settings';
 
+const toStoredRecent = (project: RecentProject): StoredRecentProject => ({
+    name: project.name,
+    champion: project.champion,
+    skin: project.skin,
+    path: project.path,
+    last_opened: project.lastOpened,
+});
+
+const fromStoredRecent = (project: StoredRecentProject): RecentProject => ({
+    name: project.name,
+    champion: project.champion,
+    skin: project.skin,
+    path: project.path,
+    lastOpened: project.last_opened,
+});
+
 interface AppProviderProps {
     children: ReactNode;
 }
@@ -713,6 +733,63 @@ export function AppProvider({ children }: AppProviderProps) {
         return initial;
     });
 
+    // Backend settings are the source of truth; localStorage only seeds the
+    // first render and migrates values saved before the backend owned them
+    const backendSettingsLoaded = React.useRef(false);
+    const stateRef = React.useRef(state);
+    stateRef.current = state;
+
+    useEffect(() => {
+        const fromBackend = (settings: AppSettings): Partial<AppState> => ({
+            leaguePath: settings.league_path,
+            creatorName: settings.creator_name,
+            autoUpdateEnabled: settings.auto_update_enabled,
+            skippedUpdateVersion: settings.skipped_update_version,
+            recentProjects: (settings.recent_projects ?? []).map(fromStoredRecent),
+            offlineMode: settings.offline_mode ?? false,
+        });
+
+        let unlisten: (() => void) | undefined;
+        let disposed = false;
+
+        (async () => {
+            try {
+                let settings = await api.getSettings();
+                const local = stateRef.current;
+                if (!settings.league_path && !settings.creator_name && (local.leaguePath || local.creatorName)) {
+                    settings = await api.updateSettings({
+                        league_path: local.leaguePath,
+                        creator_name: local.creatorName,
+                        auto_update_enabled: local.autoUpdateEnabled,
+                        skipped_update_version: local.skippedUpdateVersion,
+                    });
+                }
+                if (!settings.recent_projects?.length && local.recentProjects.length) {
+                    settings = await api.updateSettings({
+                        recent_projects: local.recentProjects.map(toStoredRecent),
+                    });
+                }
+                if (disposed) return;
+                dispatch({ type: 'SET_STATE', payload: fromBackend(settings) });
+                backendSettingsLoaded.current = true;
+
+                const { listen } = await import('@tauri-apps/api/event');
+                const stop = await listen<AppSettings>('settings-changed', (event) => {
+                    dispatch({ type: 'SET_STATE', payload: fromBackend(event.payload) });
+                });
+                if (disposed) stop();
+                else unlisten = stop;
+            } catch (error) {
+                console.error('[Flint] Failed to load settings:', error);
+            }
+        })();
+
+        return () => {
+            disposed = true;
+            unlisten?.();
+        };
+    }, []);
+
     // Persist settings on change
     useEffect(() => {
         try {
@@ -727,6 +804,18 @@ export function AppProvider({ children }: AppProviderProps) {
         } catch (error) {
             console.error('[Flint] Failed to save settings:', error);
         }
+
+        // Unchanged values are a no-op on the backend, so echoes of
+        // settings-changed don't loop
+        if (backendSettingsLoaded.current) {
+            api.updateSettings({
+                league_path: state.leaguePath,
+                creator_name: state.creatorName,
+                auto_update_enabled: state.autoUpdateEnabled,
+                skipped_update_version: state.skippedUpdateVersion,
+                recent_projects: state.recentProjects.map(toStoredRecent),
+            }).catch((error) => console.error('[Flint] Failed to save settings:', error));
+        }
     }, [state.leaguePath, state.recentProjects, state.creatorName, state.autoUpdateEnabled, state.skippedUpdateVersion]);
 
     // Toast ID counter
@@ -10,6 +10,39 @@ export type AppStatus = 'ready' | 'working' | 'error';
 export type ModalType = 'newProject' | 'settings' | 'export' | 'firstTimeSetup' | 'updateAvailable' | 'recolor' | 'checkpoint' | null;
 export type ViewType = 'welcome' | 'preview' | 'editor' | 'project' | 'checkpoints' | 'extract' | 'wad-explorer';
 
+/** Stable error category returned by backend commands */
+export type CommandErrorCode =
+    | 'IO'
+    | 'NOT_FOUND'
+    | 'NETWORK'
+    | 'PARSE'
+    | 'WAD'
+    | 'UNSUPPORTED_WAD_VERSION'
+    | 'HASH'
+    | 'BIN_CONVERSION'
+    | 'INVALID_INPUT'
+    | 'HASHTABLE_NOT_READY'
+    | 'CANCELLED'
+    | 'OFFLINE_MODE'
+    | 'BUSY'
+    | 'INSUFFICIENT_DISK_SPACE'
+    | 'UNSAFE_PATH'
+    | 'PROJECT_READ_ONLY'
+    | 'GAME_FILES_LOCKED'
+    | 'INTERNAL';
+
+/** Structured error payload rejected by every backend command */
+export interface CommandError {
+    code: CommandErrorCode;
+    message: string;
+    details: string | null;
+    path: string | null;
+    line: number | null;
+    /** Bytes needed and free, for INSUFFICIENT_DISK_SPACE */
+    required_bytes: number | null;
+    available_bytes: number | null;
+}
+
 export interface Toast {
     id: number;
     type: 'info' | 'success' | 'warning' | 'error';
@@ -49,6 +82,10 @@ export interface Project {
     version?: string;
     description?: string;
     project_path?: string;
+    /** Opened from media that can't be written; editing commands are refused */
+    read_only?: boolean;
+    /** Repath prefix ("creator/project") of the mod the content came from */
+    foreign_prefix?: string;
 }
 
 export interface Champion {
@@ -94,8 +131,43 @@ export interface WadChunk {
     hash: string;        // hex string e.g. "0x1a2b3c4d5e6f7a8b"
     path: string | null; // resolved path, null if hash is unknown
     size: number;
+    compressed_size: number;
+    compression: 'none' | 'gzip' | 'satellite' | 'zstd' | 'zstd_multi' | 'unknown';
+    checksum: string;    // xxh3 from the TOC, all zeros before WAD v3
+    subchunked: boolean;
+    is_duplicate: boolean; // shares its data with another chunk
+    kind: ChunkKind;       // from the path's extension, or the data's magic
+}
+
+/** File format of exportWadManifest */
+export type ManifestFormat = 'csv' | 'json';
+
+/** Result of exportWadManifest */
+export interface WadManifestExport {
+    output_path: string;
+    /** Chunks written, unresolved ones included */
+    row_count: number;
+    /** Size of the written file in bytes */
+    size: number;
+}
+
+/** Result of `repackWad` */
+export interface WadRepackStats {
+    /** Chunks of the original replaced by a project file */
+    replaced_count: number;
+    /** Chunks of the original copied as they were */
+    carried_count: number;
+    /** Project files the original doesn't have, added as new chunks */
+    added_count: number;
+    /** Project files left out because another file has the same path hash */
+    duplicates: string[];
+    /** Size of the written WAD in bytes */
+    size: number;
 }
 
+/** Broad file type of a WAD chunk */
+export type ChunkKind = 'texture' | 'bin' | 'mesh' | 'animation' | 'audio' | 'other';
+
 export interface ExtractSession {
     id: string;
     wadPath: string;
@@ -110,14 +182,25 @@ export interface ExtractSession {
 
 /** A WAD file discovered while scanning a game installation */
 export interface GameWadInfo {
-    /** Absolute path to the .wad.client file */
+    /** Absolute path to the WAD file */
     path: string;
     /** Filename e.g. "Aatrox.wad.client" */
     name: string;
     /** Parent directory used as display group e.g. "Champions" */
     category: string;
+    kind: WadKind;
 }
 
+/** Only `client` WADs hold the game data mods replace */
+export type WadKind =
+    | { type: 'client' }
+    | { type: 'mobile' }
+    | { type: 'locale_audio'; locale: string }
+    | { type: 'other' };
+
+/** Chunk list order: by path (unresolved chunks last, by hash), by hash, or largest first */
+export type ChunkSort = 'path' | 'hash' | 'size';
+
 // =============================================================================
 // WAD Explorer (VFS) Types
 // =============================================================================
@@ -196,14 +279,132 @@ export interface AppState {
     // Auto-update settings (persisted)
     autoUpdateEnabled: boolean;
     skippedUpdateVersion: string | null;
+
+    /** Backend `offline_mode`; network features are skipped while on */
+    offlineMode: boolean;
 }
 
 // =============================================================================
 // API Response Types
 // =============================================================================
 
+export type HashtableLoadState = 'not_loaded' | 'loading' | 'ready';
+
 export interface HashStatus {
+    /** Hashes load on first use unless eager loading is enabled in settings */
+    state: HashtableLoadState;
     loaded_count: number;
+    last_updated: string | null;
+    /** Entries in hashes.user.txt, which downloads never overwrite */
+    user_count: number;
+    /** The directory hashes load from */
+    hash_dir: string;
+    /** "fallback" is the app data directory, used when there is no RitoShark directory */
+    hash_dir_source: 'settings' | 'ritoshark' | 'fallback';
+    /** RitoShark directory hashes can move to with `migrateHashDirectory` */
+    preferred_hash_dir: string | null;
+    /** Hash lists in hash_dir, by name */
+    files: HashFileStatus[];
+    /** The CommunityDragon lists among `files` by category */
+    categories: HashCategoryStatus[];
+    /** Version of the configured League install */
+    game_version: string | null;
+    /** Game version the lists were last refreshed against */
+    hashes_game_version: string | null;
+    /** The install is newer than the lists; suggest a forced download */
+    possibly_stale: boolean;
+    /** Entries per list in the BIN name cache; null until a BIN is converted */
+    bin_counts: BinHashCounts | null;
+}
+
+/**
+ * Groups of CommunityDragon hash lists. Map and TFT arena paths are in the
+ * game list with champion assets.
+ */
+export type HashCategory = 'game' | 'lcu' | 'bin' | 'rst';
+
+/** The lists of one category in the hash directory */
+export interface HashCategoryStatus {
+    category: HashCategory;
+    /** Fetched by download_hashes */
+    enabled: boolean;
+    files: string[];
+    /** Entries the loaded table parsed from them; null until it is loaded */
+    entries: number | null;
+}
+
+/** Entries in each BIN hash list (FNV-1a hashed names) */
+export interface BinHashCounts {
+    /** hashes.binentries.txt: object paths */
+    entries: number;
+    /** hashes.binfields.txt: property names */
+    fields: number;
+    /** hashes.binhashes.txt: hash values */
+    hashes: number;
+    /** hashes.bintypes.txt: class names */
+    types: number;
+}
+
+/** One hash list in the hash directory */
+export interface HashFileStatus {
+    name: string;
+    size: number;
+    modified: string | null;
+    /** Entries parsed from it; null until the table loaded */
+    entries: number | null;
+    /** Malformed lines skipped; null until the table loaded */
+    rejected: number | null;
+    /** 1-based numbers of the first malformed lines */
+    rejected_lines: number[];
+    /** Time the table took to parse it; null until the table loaded */
+    duration_ms: number | null;
+    /** Why the loaded table couldn't read it */
+    error: string | null;
+}
+
+/** Payload of `hashtable-load-progress`, every 250k entries of a full load */
+export interface HashtableLoadProgress {
+    entries: number;
+    bytes_read: number;
+    /** Size of all lists being loaded */
+    total_bytes: number;
+}
+
+/** Payload of `hashtable-ready`: a full load of the hashtable finished */
+export interface HashtableReady {
+    loaded_count: number;
+}
+
+/** Payload of `hashes-updated`: lists changed on disk were merged in */
+export interface HashesUpdated {
+    files: string[];
+    previous_count: number;
+    loaded_count: number;
+    delta: number;
+    /** Hashes added, removed or resolving to another path */
+    changed: number;
+}
+
+/** Result of `migrate_hash_directory` */
+export interface HashDirMigration {
+    from: string;
+    to: string;
+    /** Lists copied over, newer than the target's or missing there */
+    copied: string[];
+    /** Lists left alone, the target's copy being as new or newer */
+    kept: string[];
+    /** Fallback user entries merged into the target's user list */
+    user_entries: number;
+    /** Where the fallback directory was moved to */
+    migrated_to: string | null;
+}
+
+/** Result of reload_hashes */
+export interface HashReload {
+    previous_count: number;
+    loaded_count: number;
+    /** Hashes added, removed or resolving to another path */
+    changed: number;
 }
 
 export interface LeagueDetectResult {
@@ -236,6 +437,7 @@ export interface FileEntry {
     hash: string;
     size: number;
     asset_type: AssetType;
+    storage?: 'raw' | 'bin_text';
 }
 
 export interface Checkpoint {
@@ -245,18 +447,88 @@ export interface Checkpoint {
     author?: string;
     tags: string[];
     file_manifest: Record<string, FileEntry>;
+    flint_version?: string;
+    game_version?: string;
+    ignore_patterns?: string[];
+    parent_id?: string | null;
+}
+
+export interface CheckpointNode {
+    id: string;
+    parent_id: string | null;
+    children: string[];
+    message: string;
+    timestamp: string; // ISO 8601
+    tags: string[];
+}
+
+export interface CheckpointGraph {
+    nodes: CheckpointNode[];
+    head: string | null;
 }
 
+export interface CheckpointFilter {
+    tags_any?: string[];
+    tags_all?: string[];
+    message_contains?: string;
+    from?: string; // ISO 8601
+    to?: string; // ISO 8601
+}
+
+export type CheckpointOrder = 'newest_first' | 'oldest_first';
+
 export interface CheckpointDiff {
     added: FileEntry[];
     modified: [FileEntry, FileEntry][];
     deleted: FileEntry[];
 }
 
+export interface CheckpointUsage {
+    id: string;
+    message: string;
+    timestamp: string; // ISO 8601
+    logical_size: number;
+    unique_size: number;
+}
+
+export interface CheckpointStorageStats {
+    total_bytes: number;
+    blob_count: number;
+    orphaned_bytes: number;
+    checkpoints: CheckpointUsage[];
+    /** Index n = bytes freed by pruning the oldest n + 1 checkpoints */
+    reclaimable_by_pruning_oldest: number[];
+}
+
+export interface CheckpointObjectIssue {
+    hash: string;
+    kind: 'corrupt' | 'missing';
+    affected: { checkpoint_id: string; path: string }[];
+}
+
+export interface CheckpointVerifyReport {
+    objects_checked: number;
+    checkpoints_checked: number;
+    issues: CheckpointObjectIssue[];
+    affected_checkpoints: string[];
+}
+
+export type CheckpointOperation = 'create' | 'restore' | 'delete' | 'prune' | 'import';
+
+export interface CheckpointLockInfo {
+    operation: CheckpointOperation;
+    pid: number;
+    started_at: string; // ISO 8601
+    stale: boolean;
+}
+
 export interface CheckpointProgress {
     phase: string;
     current: number;
     total: number;
+    bytes_done: number;
+    bytes_total: number;
+    operation_id?: string;
 }
 
 export type CheckpointFileContent =
@@ -267,4 +539,1008 @@ export type CheckpointFileContent =
 export interface DownloadProgress {
     downloaded: number;
     total: number;
+    operation_id: string;
+}
+
+// =============================================================================
+// Operation Types
+// =============================================================================
+
+export type OperationKind = 'extraction' | 'export' | 'repath' | 'checkpoint' | 'download' | 'setup' | 'validation' | 'recolor';
+export type OperationStatus = 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface OperationInfo {
+    id: string;
+    kind: OperationKind;
+    project: string | null;
+    started_at: string;
+    finished_at: string | null;
+    status: OperationStatus;
+    progress: number | null;
+    message: string | null;
+    cancellable: boolean;
+    /** Holds the project's exclusive slot (repath, export with repath, restore, setup) */
+    exclusive: boolean;
+    /** Time spent in each phase, in order; empty for operations that don't report phases */
+    timings: PhaseTiming[];
+}
+
+/** Result of `extract_wad` */
+export interface ExtractionResult {
+    extracted_count: number;
+    failed_count: number;
+    /** Chunks selected: the given hashes or the whole WAD, narrowed by filters */
+    matched_count: number;
+    /** Chunks the filters left out */
+    skipped_count: number;
+    /** Extracted chunks the hashtable doesn't know, saved under their hash */
+    unknown_hashes: UnknownHash[];
+    /** Decompressed bytes written */
+    bytes_written: number;
+    /** Stopped by `cancel_extraction`; the counts cover what was finished */
+    cancelled: boolean;
+    /** New chunks, or ones whose file is gone; this and the next three are 0 outside a project */
+    added_count: number;
+    /** Chunks whose content changed since the last extraction */
+    updated_count: number;
+    /** Chunks with the same content as last time, skipped when incremental */
+    unchanged_count: number;
+    /** Unchanged chunks whose file was edited since it was extracted */
+    edited_count: number;
+    timings: PhaseTiming[];
+    /** Set when a skin was extracted */
+    skin: SkinChunks | null;
+}
+
+/** Skin of a champion WAD for `extractWad` */
+export interface SkinSelection {
+    champion: string;
+    skin_id: number;
+}
+
+/** What extracting a skin read and couldn't find */
+export interface SkinChunks {
+    /** BINs read, the skin BIN first */
+    bins: string[];
+    /** Distinct asset paths the BINs reference */
+    referenced_count: number;
+    /** Referenced paths the WAD doesn't hold */
+    missing: string[];
+}
+
+/** A file `extract_wad` would write */
+export interface PlannedFile {
+    hash: string;
+    /** Destination, with the extension extraction would add */
+    path: string;
+    /** Decompressed bytes */
+    size: number;
+    /** Not in the hashtable, so saved under its hash */
+    unknown: boolean;
+}
+
+/** Result of `preview_extraction` */
+export interface ExtractionPreview {
+    /** Sorted by path */
+    files: PlannedFile[];
+    total_size: number;
+    /** Files that would be saved under their hash */
+    unknown_count: number;
+    matched_count: number;
+    skipped_count: number;
+    /** Chunks missing from the WAD or that would fail to extract */
+    failed_count: number;
+    skin: SkinChunks | null;
+}
+
+/** Payload of `wad-extract-progress` events */
+export interface WadExtractProgress {
+    operation_id: string;
+    status: 'starting' | 'extracting';
+    wad_path: string;
+    done?: number;
+    total?: number;
+    bytes_written?: number;
+    /** Resolved path of the last chunk finished */
+    current_path?: string;
+}
+
+/** Result of readWad */
+export interface WadInfo {
+    path: string;
+    /** Version from the header, e.g. "3.3"; v1 and v2 WADs are read too */
+    version: string;
+    chunk_count: number;
+}
+
+/** Outcome of checking one chunk in verifyWad */
+export interface ChunkCheck {
+    hash: string;
+    path: string | null;
+    passed: boolean;
+    error: string | null;
+}
+
+/** Result of verifyWad */
+export interface WadVerification {
+    wad_path: string;
+    version: string;
+    /** Every chunk checked, sorted by hash */
+    chunks: ChunkCheck[];
+    passed: number;
+    failed: number;
+    /** Chunks checked against a checksum; the rest only had their size checked */
+    checksums_verified: number;
+    cancelled: boolean;
+}
+
+/** Payload of wad-verify-progress events */
+export interface WadVerifyProgress {
+    operation_id: string;
+    status: 'starting' | 'verifying';
+    wad_path: string;
+    done?: number;
+    total?: number;
+}
+
+/** Duration of one phase of an operation */
+export interface PhaseTiming {
+    phase: string;
+    millis: number;
+}
+
+export interface ProjectBusyStatus {
+    busy: boolean;
+    operation: OperationInfo | null;
+}
+
+/** One entry of a .stringtable (RST) file */
+export interface StringTableEntry {
+    /** Key hash as 10 hex digits */
+    hash: string;
+    /** Key name, null if it isn't in hashes.rst.txt */
+    key: string | null;
+    value: string;
+}
+
+/** A small edit that undo_last can revert; kept for the session only */
+export interface UndoEntryInfo {
+    id: number;
+    /** e.g. "Edit skin0.bin" */
+    label: string;
+    created_at: string;
+    /** Files undo writes back */
+    files: string[];
+    size: number;
+}
+
+// =============================================================================
+// Settings Types
+// =============================================================================
+
+export interface ExportSettings {
+    auto_repath: boolean;
+    output_dir: string | null;
+    /** Installed mods (e.g. cslol-manager's `installed`) fantome exports are checked against */
+    mod_manager_dir: string | null;
+}
+
+/** Limits for parallel work; 0 means "use all cores" */
+export interface PerformanceSettings {
+    max_threads: number;
+    max_io_tasks: number;
+    /** Use at most half the cores so the machine stays responsive */
+    low_priority: boolean;
+}
+
+/** A hash list mirror used instead of CommunityDragon */
+export interface HashSource {
+    name: string;
+    url: string;
+    /** File name in the hash directory, e.g. "hashes.game.txt" */
+    file: string;
+}
+
+/** Result of `download_hashes`; `skipped` is `not_modified` plus `age_skipped` */
+export interface DownloadStats {
+    downloaded: number;
+    skipped: number;
+    /** The server answered 304 Not Modified */
+    not_modified: number;
+    /** No stored ETag or Last-Modified, and recent enough to keep */
+    age_skipped: number;
+    errors: number;
+    /** Downloads that failed validation, as "name: reason"; the previous files were kept */
+    rejected: string[];
+    /** Stopped by `cancelHashDownload`; files finished before are kept */
+    cancelled: boolean;
+}
+
+/** Result of `compute_path_hash` for one path */
+export interface PathHash {
+    /** Lowercase with forward slashes, as hashed */
+    path: string;
+    /** Decimal, as a string since it doesn't fit a number */
+    hash: string;
+    /** 16 hex digits */
+    hex: string;
+    /** Null when the hashtable wasn't checked */
+    known: boolean | null;
+    known_path: string | null;
+}
+
+/** A chunk extract_wad saved under its hash because the path is unknown */
+export interface UnknownHash {
+    /** 16 hex digits */
+    hash: string;
+    /** Decompressed size in bytes */
+    size: number;
+    /** Extension detected from the magic bytes, or "unknown" */
+    kind: string;
+    /** WAD file name */
+    wad: string;
+}
+
+/** How `guess_unknown_hashes` built a guessed path */
+export type GuessRule = 'suffix' | 'prefix' | 'skin_number' | 'extension' | 'directory';
+
+/** A guessed path for an unresolved hash */
+export interface HashGuess {
+    /** 16 hex digits */
+    hash: string;
+    path: string;
+    rule: GuessRule;
+    /** Resolved path the guess was built from */
+    from: string;
+}
+
+/** Result of `guess_unknown_hashes` */
+export interface GuessReport {
+    /** Matches, sorted by hash */
+    guesses: HashGuess[];
+    /** Number of candidate paths hashed */
+    candidates: number;
+    /** Hashes still unresolved after guessing */
+    unresolved: number;
+}
+
+/** A chunk added, removed or modified between two WADs */
+export interface WadDiffEntry {
+    /** 16 hex digits */
+    hash: string;
+    /** Resolved path, null when the hash is unknown */
+    path: string | null;
+    /** Decompressed size in the old WAD, null when added */
+    old_size: number | null;
+    /** Decompressed size in the new WAD, null when removed */
+    new_size: number | null;
+}
+
+/** A chunk `find_in_wads` found in one WAD */
+export interface WadSearchHit {
+    wad_path: string;
+    wad_name: string;
+    /** 16 hex digits */
+    hash: string;
+    path: string | null;
+    /** Decompressed size in bytes */
+    size: number;
+    compressed_size: number;
+    compression: string;
+}
+
+/** The WAD a game path was found in, from `locateGamePath` */
+export interface GamePathLocation {
+    wad_path: string;
+    wad_name: string;
+    /** 16 hex digits */
+    hash: string;
+    /** Decompressed size in bytes */
+    size: number;
+    compressed_size: number;
+    compression: string;
+}
+
+/** One page of `find_in_wads` hits */
+export interface WadSearchPage {
+    hits: WadSearchHit[];
+    /** Hits across all pages */
+    total: number;
+    offset: number;
+    stats: {
+        wads_scanned: number;
+        /** WADs whose table came from the cache */
+        cached: number;
+        /** WADs that couldn't be read, with the reason */
+        failed: string[];
+    };
+}
+
+/** A champion's WADs mounted as one filesystem */
+export interface WadMountInfo {
+    /** Handle for lookups and reads */
+    id: string;
+    /** Highest priority first */
+    wads: string[];
+    chunk_count: number;
 }
+
+export interface MountedChunk {
+    wad_path: string;
+    /** 16 hex digits */
+    hash: string;
+    /** Decompressed size in bytes */
+    size: number;
+}
+
+/** Result of `diff_wads`, each list sorted by hash */
+export interface WadDiff {
+    old_path: string;
+    new_path: string;
+    added: WadDiffEntry[];
+    removed: WadDiffEntry[];
+    modified: WadDiffEntry[];
+    unchanged: number;
+    /** Chunks compared by decompressed content for lack of checksums */
+    content_compared: number;
+}
+
+/** Result of `import_hash_file` */
+export interface HashImportResult {
+    /** Hashes that weren't in hashes.user.txt yet */
+    added: number;
+    /** Hashes already there; the imported path replaced the old one */
+    merged: number;
+    /** Lines that aren't "hex<TAB>path" or "hex path" */
+    rejected: number;
+    /** Line numbers of the first rejected lines */
+    rejected_lines: number[];
+    /** Entries in hashes.user.txt after the import */
+    total: number;
+}
+
+/** Result of `add_hash_mapping` */
+export interface HashMapping {
+    hash: string;
+    path: string;
+    /** Path hashes.user.txt had for the hash before */
+    replaced: string | null;
+    /** Whether the path hashes to the hash (WAD path or BIN name hash) */
+    verified: boolean;
+    /** Entries in hashes.user.txt after adding it */
+    total: number;
+}
+
+/** Payload of `hash-download-progress`; only `status` and `operation_id` are set for "starting" */
+export interface HashDownloadProgress {
+    status: 'starting' | 'downloading';
+    /** Null for the update check at startup */
+    operation_id: string | null;
+    file?: string;
+    file_index?: number;
+    file_count?: number;
+    downloaded?: number;
+    /** Null when the server doesn't send Content-Length */
+    total?: number | null;
+}
+
+/** Persisted by the backend; unknown fields from newer versions are passed through */
+export interface AppSettings {
+    version: number;
+    league_path: string | null;
+    creator_name: string | null;
+    hash_dir: string | null;
+    /** Mirrors download_hashes uses; empty for CommunityDragon */
+    hash_sources: HashSource[];
+    /** CommunityDragon lists download_hashes fetches */
+    hash_categories: HashCategory[];
+    auto_update_enabled: boolean;
+    skipped_update_version: string | null;
+    /** Load hashes at startup instead of on first use */
+    eager_hash_loading: boolean;
+    /** Skip the startup hash update; network commands fail with OFFLINE_MODE */
+    offline_mode: boolean;
+    /** Repath cleanup deletes unused files instead of quarantining them */
+    delete_unused_files: boolean;
+    export: ExportSettings;
+    performance: PerformanceSettings;
+    /** Most recently opened first */
+    recent_projects: StoredRecentProject[];
+    [key: string]: unknown;
+}
+
+/** `RecentProject` as persisted in backend settings */
+export interface StoredRecentProject {
+    name: string;
+    champion: string;
+    skin: number;
+    path: string;
+    last_opened: string;
+}
+
+/** Partial update; `null` resets a field to its default */
+export type SettingsPatch = {
+    [K in keyof Omit<AppSettings, 'version' | 'export' | 'performance'>]?: AppSettings[K] | null;
+} & {
+    export?: Partial<{ [K in keyof ExportSettings]: ExportSettings[K] | null }>;
+    performance?: Partial<{ [K in keyof PerformanceSettings]: PerformanceSettings[K] | null }>;
+};
+
+// =============================================================================
+// Log Capture Types
+// =============================================================================
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+/** A log line captured by the backend */
+export interface LogLine {
+    /** Unix timestamp in milliseconds */
+    timestamp: number;
+    level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+    target: string;
+    message: string;
+    operation_id: string | null;
+}
+
+// =============================================================================
+// Diagnostics Types
+// =============================================================================
+
+export interface DiagnosticBundleEntry {
+    name: string;
+    size: number;
+}
+
+/** What went into a diagnostic bundle, for review before sharing */
+export interface DiagnosticBundle {
+    path: string;
+    entries: DiagnosticBundleEntry[];
+    /** Items that were left out, with the reason */
+    notes: string[];
+}
+
+// =============================================================================
+// Startup Types
+// =============================================================================
+
+/** A startup item that may still be in progress; `startup-updated` fills it in */
+export type Pending<T> =
+    | { status: 'pending' }
+    | { status: 'ready'; value: T }
+    | { status: 'failed'; value: string };
+
+export interface LeagueInstallation {
+    path: string;
+    game_path: string;
+    auto_detected: boolean;
+}
+
+export interface LeagueSummary {
+    installation: LeagueInstallation;
+    game_version: string | null;
+}
+
+export interface HashSummary {
+    dir: string | null;
+    state: HashtableLoadState;
+    loaded_count: number;
+    /** Result of the startup check for newer hash files */
+    update: Pending<{ downloaded: number; skipped: number; errors: number }>;
+}
+
+export interface RecentProjectStatus extends StoredRecentProject {
+    exists: boolean;
+}
+
+/** Payload of `app-ready` and `startup-updated` */
+export interface StartupSummary {
+    version: string;
+    settings: AppSettings;
+    hashes: HashSummary;
+    league: Pending<LeagueSummary>;
+    recent_projects: RecentProjectStatus[];
+}
+
+// =============================================================================
+// Project Setup Types
+// =============================================================================
+
+export type SetupPhase = 'extract' | 'organize' | 'preconvert' | 'checkpoint';
+export type SetupStepStatus = 'completed' | 'skipped' | 'failed' | 'cancelled' | 'not_run';
+
+/** Optional steps of `setupProjectContent`; all default to on */
+export interface SetupOptions {
+    concat?: boolean;
+    /** Needs a creator name (defaults to the one in settings) */
+    repath?: boolean;
+    creator_name?: string | null;
+    preconvert?: boolean;
+    checkpoint?: boolean;
+}
+
+export interface SetupStep {
+    phase: SetupPhase;
+    status: SetupStepStatus;
+    message: string;
+}
+
+export interface SetupReport {
+    steps: SetupStep[];
+    /** Phase that stopped the pipeline, if any */
+    failed_phase: SetupPhase | null;
+    /** What the earlier phases left in the project after a stop */
+    partial_state: string | null;
+    extracted_count: number;
+    checkpoint_id: string | null;
+}
+
+/** Payload of `setup-progress` */
+export interface SetupProgress {
+    phase: SetupPhase;
+    progress: number;
+    message: string;
+    operation_id: string;
+}
+
+/** Result of import_path_mappings */
+export interface PathMappingImport {
+    /** Mappings that were new or changed */
+    imported: number;
+    /** Mappings in the project afterwards */
+    total: number;
+    /** Imported game paths whose file isn't in the project's content */
+    missing_files: string[];
+}
+
+/** Files one repath cleanup moved to `.flint/quarantine/{id}` */
+export interface QuarantineBatch {
+    /** Timestamp-based ID, e.g. "20261016-143005123" */
+    id: string;
+    file_count: number;
+    total_size: number;
+}
+
+/** A stale path reference and its proposed replacement */
+export interface PathRemap {
+    old_path: string;
+    new_path: string;
+}
+
+/** Blocking kinds (unreadable_main_bin, unparsable_dependency) stop an export unless forced */
+export type RepathWarningKind = 'unreadable_main_bin' | 'unparsable_dependency' | 'dependency_not_found' | 'mapping_fallback' | 'unsafe_path' | 'already_prefixed' | 'foreign_prefix';
+
+/** A problem repathing worked around while following the main BIN's dependencies */
+export interface RepathWarning {
+    kind: RepathWarningKind;
+    path: string;
+    message: string;
+}
+
+export interface ChannelStats {
+    min: number;
+    max: number;
+    mean: number;
+}
+
+/** Texture format and channel usage, from a mip of at most 256px */
+export interface TextureAnalysis {
+    container: 'DDS' | 'TEX';
+    /** e.g. "BC3" or "BC7_SRGB" */
+    format: string;
+    /** 'unknown' for TEX and DDS files without a DX10 header */
+    colorspace: 'srgb' | 'linear' | 'unknown';
+    width: number;
+    height: number;
+    mip_count: number;
+    analyzed_size: [number, number];
+    red: ChannelStats;
+    green: ChannelStats;
+    blue: ChannelStats;
+    alpha: ChannelStats;
+    alpha_usage: 'none' | 'binary' | 'gradient';
+    /** Channels whose values vary, e.g. "RGA" */
+    used_channels: string;
+    grayscale: boolean;
+    /** Varying color channels are mostly near-black or near-white, as in packed masks */
+    mask_like: boolean;
+}
+
+/** A project texture that differs from the game texture it replaces */
+export interface TextureOverrideWarning {
+    path: string;
+    original_wad: string;
+    warnings: string[];
+}
+
+/** Champion VFX systems that Summoner's Rift data defines or links to but the export won't carry there */
+export interface MapVfxWarning {
+    /** System object path, or its hash as "0x1a2b3c4d" */
+    system: string;
+    /** Map BIN using it, e.g. "data/maps/shipping/map11/map11.bin" */
+    map_bin: string;
+    /** Project file it was read from; null for the installed map WAD */
+    project_file: string | null;
+    message: string;
+}
+
+/** What repathing does with map-scoped BINs (data/maps/...) that reference the champion */
+export type MapOverrideMode = 'keep' | 'concat' | 'map_wad';
+
+/** A file an export packs, and the WAD it goes into */
+export interface ExportManifestEntry {
+    /** Relative to content/base */
+    file: string;
+    wad: string | null;
+    /** Why it is packed there, e.g. map data bound for Map11.wad.client */
+    note: string | null;
+}
+
+/** Files a package shares with one other mod */
+export interface ModConflictGroup {
+    mod_name: string;
+    package: string;
+    /** "Ahri.wad.client: assets/...", hex where the path is unknown; cut off past a limit */
+    files: string[];
+    count: number;
+}
+
+/** Result of check_mod_conflicts, also attached to exports when a mod manager directory is set */
+export interface ModConflictReport {
+    checked: number;
+    /** Most shared files first */
+    conflicts: ModConflictGroup[];
+    unreadable: string[];
+}
+
+/** Result of live_sync */
+export interface LiveSyncStarted {
+    overlay_dir: string;
+    /** Files copied by the initial sync */
+    file_count: number;
+    warnings: string[];
+}
+
+/** One file a live sync copied or removed */
+export interface LiveSyncFile {
+    /** Relative to content/base */
+    path: string;
+    kind: 'texture' | 'bin' | 'other';
+    /** Only shows after the game restarts */
+    restart_required: boolean;
+    removed: boolean;
+    paths_repathed: number;
+}
+
+/** Payload of `live-sync`, emitted after each batch of synced changes */
+export interface LiveSyncEvent {
+    project_path: string;
+    overlay_dir: string;
+    files: LiveSyncFile[];
+    failed: string[];
+    restart_required: boolean;
+}
+
+/** Result of rebase_project */
+export interface RebaseReport {
+    /** Proposed remappings, sorted by old path */
+    remappings: PathRemap[];
+    /** Stale references without a single best match; these need manual attention */
+    unresolved: string[];
+    /** Remappings written to the project's BINs (0 for a preview) */
+    applied: number;
+    bins_modified: number;
+    failed_bins: { path: string; message: string }[];
+}
+
+/** Colors are RGB in 0..1; alpha is never changed */
+export type ColorTransform =
+    | { type: 'hue_shift'; degrees: number }
+    /** Moves toward the color's hue and saturation, keeping lightness */
+    | { type: 'tint'; color: [number, number, number]; strength: number }
+    /** Replaces each color by the gradient's color at its luminance (0 black, 1 white) */
+    | { type: 'gradient_map'; stops: { position: number; color: [number, number, number] }[] };
+
+export type RecolorSelector =
+    /** BIN object path, e.g. "Characters/Ahri/Skins/Skin0/Particles/Ahri_Base_Q_Mis", or "0x…" hash */
+    | { type: 'system'; name: string }
+    /** Texture path glob, matched under content/base or its WAD folder */
+    | { type: 'textures'; glob: string };
+
+/** One file a recolor changes */
+export interface RecolorChange {
+    path: string;
+    kind: 'bin' | 'texture';
+    /** Color values rewritten; 0 for textures */
+    colors: number;
+    /** Base64 PNGs, in a dry run only */
+    before: string | null;
+    after: string | null;
+}
+
+/** Result of recolor_project */
+export interface RecolorReport {
+    dry_run: boolean;
+    changes: RecolorChange[];
+    /** Textures the system references that the project doesn't contain */
+    missing_textures: string[];
+    failed: { path: string; message: string }[];
+    /** False for a dry run or when the old files are too large to journal */
+    undoable: boolean;
+}
+
+/**
+ * Bulk transform of apply_bin_recolor. Colors are transformed as stored
+ * (sRGB-encoded), so opposite shifts round-trip; alpha is kept unless a swap sets it.
+ */
+export type BinColorTransform =
+    /** Hue in degrees; saturation and value are multipliers, 1 keeps them */
+    | { type: 'hsv'; hue: number; saturation: number; value: number }
+    /** Colors within `tolerance` (RGB distance) of a swap's `from` become its `to` */
+    | {
+          type: 'palette';
+          swaps: { from: [number, number, number]; to: [number, number, number]; alpha?: number | null }[];
+          tolerance: number;
+      };
+
+/** Result of apply_bin_recolor */
+export interface BinRecolorReport {
+    /** BINs with changed colors and how many values changed in each */
+    files: { path: string; changed: number }[];
+    /** Project BINs outside particles and skins folders, left alone */
+    skipped: number;
+    failed: { path: string; message: string }[];
+    /** Checkpoint taken before writing, to undo with */
+    checkpoint_id: string | null;
+}
+
+/** One place rename_vfx_system changes */
+export interface RenameLocation {
+    /** BIN relative to content/base */
+    file: string;
+    /** Object holding the reference, or its hash as "0x1a2b3c4d" */
+    object: string;
+    /** Field path in the object; empty for the definition */
+    field: string;
+    kind: 'definition' | 'link' | 'hash' | 'string' | 'map_key';
+    old: string;
+    new: string;
+}
+
+/** Result of rename_vfx_system */
+export interface RenameReport {
+    dry_run: boolean;
+    old_name: string;
+    new_name: string;
+    locations: RenameLocation[];
+    files: string[];
+    /** BINs that couldn't be read; references in them weren't renamed */
+    unreadable: string[];
+    undoable: boolean;
+}
+
+/** Result of export_project_hashes */
+export interface ProjectHashExport {
+    /** The written project.hashes.txt */
+    path: string;
+    paths: number;
+    /** BIN object, field, hash and class names */
+    bin_names: number;
+    /** Hashes the local lists don't resolve either */
+    unresolved: number;
+    /** BINs that couldn't be read */
+    unreadable: string[];
+}
+
+/** A path found in ritobin text by extract_asset_references' fast mode */
+export interface AssetReference {
+    path: string;
+    path_hash: number;
+    asset_type: string;
+    /** Line number */
+    location: number | null;
+}
+
+export type ReferenceKind = 'texture' | 'mesh' | 'animation' | 'bin_link' | 'audio' | 'other';
+
+/** The references one BIN object holds */
+export interface ObjectReferences {
+    /** e.g. "0x1a2b3c4d" */
+    path_hash: string;
+    /** Object path, if it is in hashes.binentries.txt */
+    name: string | null;
+    /** Class name, or its hash when unknown */
+    class_name: string;
+    /** `field` is the path inside the object, e.g. "complexEmitterDefinitionData[2].texture" */
+    references: { path: string; field: string; kind: ReferenceKind }[];
+}
+
+/** The references in one BIN file */
+export interface BinReferences {
+    /** Relative to content/base */
+    file: string;
+    /** Linked BINs */
+    links: string[];
+    objects: ObjectReferences[];
+    /** Why the file couldn't be read */
+    error: string | null;
+}
+
+export interface MissingAsset {
+    path: string;
+    path_hash: number | null;
+    source_file: string;
+    asset_type: string;
+    /** Object holding the reference (name or hash); null for linked BINs */
+    object: string | null;
+    field: string | null;
+}
+
+export interface ValidationReport {
+    total_references: number;
+    valid_references: number;
+    missing_assets: MissingAsset[];
+    stats_by_type: Record<string, { total: number; valid: number; missing: number }>;
+    timings: PhaseTiming[];
+}
+
+/** Result of strip_repath_prefix */
+export interface PrefixStripResult {
+    /** Stripped prefix, as "creator/project" */
+    prefix: string;
+    bins_modified: number;
+    paths_rewritten: number;
+    files_relocated: number;
+    /** Prefixed references whose file is in neither place; left as they are */
+    missing_paths: string[];
+    failed_bins: { path: string; message: string }[];
+}
+
+/** Result of restore_quarantine */
+export interface QuarantineRestore {
+    restored: number;
+    /** Files kept in quarantine because their original path is taken again */
+    conflicts: string[];
+}
+
+/** Result of get_disk_usage, in bytes */
+export interface DiskUsage {
+    content: number;
+    output: number;
+    /** Checkpoint metadata and object store under .flint/ */
+    checkpoints: number;
+    other: number;
+    total: number;
+    /** Free space on the project's volume, null if unknown */
+    available: number | null;
+}
+
+/** Options of `convert_project_bins` */
+export interface BinConvertOptions {
+    /** Convert BINs whose converted file is already newer */
+    force?: boolean;
+    /** "text" writes ritobin `.py`; default */
+    format?: 'text' | 'json';
+}
+
+/** One BIN of a `convert_project_bins` run */
+export interface BinConversion {
+    /** Relative to the project */
+    path: string;
+    output: string;
+    status: 'converted' | 'skipped' | 'failed';
+    error: string | null;
+}
+
+/** Result of `convert_project_bins` */
+export interface BinConversionReport {
+    /** Every BIN found, sorted by path */
+    files: BinConversion[];
+    converted: number;
+    skipped: number;
+    failed: number;
+}
+
+/** A BIN property from `get_bin_property` or `set_bin_property` */
+export interface BinPropertyValue {
+    /** Kind of value, e.g. "F32", "Container" or "Embedded" */
+    kind: string;
+    /** The value as in the BIN's JSON form */
+    value: unknown;
+}
+
+/** A property of a project BIN matching a `searchBins` query */
+export interface BinSearchHit {
+    /** BIN path relative to the project */
+    file: string;
+    /** Object path, or its hash as "0x…" */
+    object: string;
+    /** Property path as `getBinProperty` takes it */
+    path: string;
+    /** The name or value that matched */
+    value: string;
+    matched: 'name' | 'value';
+}
+
+/** One page of `searchBins` hits */
+export interface BinSearchPage {
+    hits: BinSearchHit[];
+    /** Hits across all pages */
+    total: number;
+    offset: number;
+    stats: {
+        files_searched: number;
+        /** BINs that came from the cache */
+        cached: number;
+        /** BINs that couldn't be read, with the reason */
+        failed: string[];
+        /** The search stopped at 5000 hits */
+        truncated: boolean;
+    };
+}
+
+/** A color value of a BIN, from `extractBinColors` */
+export interface BinColor {
+    /** BIN path relative to the project, or its file name for a single BIN */
+    file: string;
+    /** Object path, or its hash as "0x…" */
+    object: string;
+    /** Property path as `getBinProperty` takes it */
+    path: string;
+    /** RGBA in 0..1; vec4 colors may exceed 1 */
+    rgba: [number, number, number, number];
+    /** Stored as rgba bytes rather than a vec4 */
+    bytes: boolean;
+    /** Below a known color field such as birthColor */
+    known_field: boolean;
+}
+
+/** An animated color: a struct of `times` and `values` */
+export interface BinGradient {
+    file: string;
+    object: string;
+    path: string;
+    keys: { time: number; rgba: [number, number, number, number] }[];
+    known_field: boolean;
+}
+
+/** Result of `extractBinColors` */
+export interface BinColorReport {
+    colors: BinColor[];
+    gradients: BinGradient[];
+    files_scanned: number;
+    /** BINs that couldn't be read, with the reason */
+    failed: string[];
+}
+
+/** Version a BIN merge conflict is resolved to */
+export type MergeSide = 'ours' | 'theirs';
+
+/** An object or field both sides of a BIN merge changed differently */
+export interface MergeConflict {
+    /** Key of the conflict in the resolution map: the object, or `object.field` */
+    id: string;
+    /** Object path, or its hash as "0x…" */
+    object: string;
+    /** Field name; null when the whole object conflicts */
+    field: string | null;
+    /** Each version in the BIN's JSON form, null where it doesn't exist */
+    base: unknown | null;
+    ours: unknown | null;
+    theirs: unknown | null;
+    /** Version the merge took; null when unresolved, which keeps ours */
+    resolution: MergeSide | null;
+}
+
+/** Result of `mergeBins` */
+export interface BinMergeReport {
+    /** Objects and fields taken from theirs without conflict */
+    applied: number;
+    conflicts: MergeConflict[];
+    /** Conflicts the resolution map didn't cover */
+    unresolved: number;
+}
+
+/** Binary command result; payloads over 2MB are fetched from the flint-blob protocol by token */
+export type BinaryPayload =
+    | { kind: 'inline'; data: number[] }
+    | { kind: 'blob'; token: string; size: number };